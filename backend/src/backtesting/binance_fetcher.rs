@@ -2,11 +2,14 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc, Duration as ChronoDuration};
 use rust_decimal::Decimal;
 use serde::{Deserialize};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use crate::exchange_connectors::{Kline, KlineInterval};
+use crate::exchange_connectors::binance::binance_rate_limiter;
+use crate::exchange_connectors::{Exchange, Kline, KlineInterval, SymbolRef};
 use crate::utils::errors::AppError;
+use crate::utils::http::{request_with_rate_limit, RetryPolicy, TokenBucket};
 use super::data_cache::{get_cache, DataCache};
+use super::data_validation::{validate_klines, DataValidationConfig};
 
 /// Binance public API base URL
 const BINANCE_API_BASE: &str = "https://api.binance.com";
@@ -14,9 +17,6 @@ const BINANCE_API_BASE: &str = "https://api.binance.com";
 /// Maximum klines per request (Binance limit)
 const MAX_KLINES_PER_REQUEST: usize = 1000;
 
-/// Request weight for klines endpoint
-const KLINES_REQUEST_WEIGHT: u32 = 1;
-
 /// Binance kline response structure
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -35,15 +35,58 @@ struct BinanceKline(
     String, // Ignore
 );
 
+/// Pagination and retry behaviour for the fetcher
+#[derive(Debug, Clone)]
+pub struct FetcherConfig {
+    /// Klines requested per page (capped at the Binance limit)
+    pub page_limit: usize,
+    /// Attempts, backoff and deadline for each page
+    pub retry: RetryPolicy,
+    /// Return the pages fetched so far (with a warning) when a page keeps failing,
+    /// instead of failing the whole fetch
+    pub allow_partial_data: bool,
+    /// Price sanity checks applied to freshly fetched data
+    pub validation: DataValidationConfig,
+}
+
+impl Default for FetcherConfig {
+    fn default() -> Self {
+        Self {
+            page_limit: MAX_KLINES_PER_REQUEST,
+            retry: RetryPolicy {
+                max_attempts: 4,
+                deadline: Some(std::time::Duration::from_secs(120)),
+                ..RetryPolicy::default()
+            },
+            allow_partial_data: false,
+            validation: DataValidationConfig::default(),
+        }
+    }
+}
+
 /// Binance data fetcher with caching
 pub struct BinanceFetcher {
     client: reqwest::Client,
     cache: Arc<DataCache>,
+    /// Shared with every other Binance client in the process
+    rate_limiter: Arc<TokenBucket>,
+    base_url: String,
+    config: FetcherConfig,
 }
 
 impl BinanceFetcher {
     /// Create a new Binance fetcher
     pub fn new() -> Self {
+        Self::with_config(FetcherConfig::default())
+    }
+
+    /// Create a fetcher with custom pagination/retry settings
+    pub fn with_config(config: FetcherConfig) -> Self {
+        Self::with_base_url(BINANCE_API_BASE, config)
+    }
+
+    /// Create a fetcher against a different API host (used by tests)
+    pub fn with_base_url(base_url: &str, config: FetcherConfig) -> Self {
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .build()
@@ -52,12 +95,15 @@ impl BinanceFetcher {
         Self {
             client,
             cache: get_cache(),
+            rate_limiter: binance_rate_limiter(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            config,
         }
     }
 
     /// Convert base asset symbol to trading pair
     /// For example: "BTC" -> "BTCUSDT", "ETH" -> "ETHUSDT"
-    fn convert_to_trading_pair(symbol: &str) -> String {
+    pub(crate) fn convert_to_trading_pair(symbol: &str) -> String {
         // If already a trading pair (contains USDT, USDC, etc.), return as is
         if symbol.contains("USDT") || symbol.contains("USDC") || symbol.contains("BUSD") {
             return symbol.to_uppercase();
@@ -93,7 +139,11 @@ impl BinanceFetcher {
         }
 
         // Convert symbol to trading pair for Binance API
-        let trading_pair = Self::convert_to_trading_pair(symbol);
+        let trading_pair = if symbol.contains(['/', '-']) {
+            Self::native_symbol(symbol)?
+        } else {
+            Self::convert_to_trading_pair(symbol)
+        };
         debug!("Converting symbol '{}' to trading pair '{}'", symbol, trading_pair);
 
         // Check cache first (use original symbol for cache key)
@@ -108,55 +158,72 @@ impl BinanceFetcher {
             symbol, start_time, end_time, trading_pair
         );
 
-        let all_klines = self
+        let (all_klines, complete) = self
             .fetch_klines_chunked(&trading_pair, interval, start_time, end_time)
             .await?;
 
-        // Store in cache for future requests (use original symbol for cache key)
-        self.cache
-            .store(symbol, interval, start_time, end_time, all_klines.clone())
-            .await;
+        let (all_klines, report) = validate_klines(symbol, all_klines, &self.config.validation)?;
+        if !report.is_clean() {
+            warn!(
+                "Dropped {} of {} klines for {} that failed price validation: {:?}",
+                report.bars_removed, report.bars_checked, symbol, report.anomalies
+            );
+        }
+
+        // Store in cache for future requests (use original symbol for cache key).
+        // Partial results are never cached so a later request can fill the gap.
+        if complete {
+            self.cache
+                .store(symbol, interval, start_time, end_time, all_klines.clone())
+                .await;
+        }
 
         Ok(all_klines)
     }
 
-    /// Fetch klines in chunks (due to Binance 1000 limit)
+    /// Fetch klines in chunks (due to Binance 1000 limit).
+    ///
+    /// Each page is retried independently; pages already fetched are kept. Returns the
+    /// klines and whether the full range was fetched.
     async fn fetch_klines_chunked(
         &self,
         symbol: &str,
         interval: &KlineInterval,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
-    ) -> Result<Vec<Kline>, AppError> {
+    ) -> Result<(Vec<Kline>, bool), AppError> {
         let mut all_klines = Vec::new();
         let mut current_start = start_time;
+        let page_limit = self.config.page_limit.clamp(1, MAX_KLINES_PER_REQUEST);
 
         // Calculate interval duration
-        let interval_duration = self.get_interval_duration(interval);
+        let interval_duration = interval.duration();
 
         while current_start < end_time {
             // Calculate chunk end time
             let chunk_end = std::cmp::min(
-                current_start + interval_duration * (MAX_KLINES_PER_REQUEST as i32),
+                current_start + interval_duration * (page_limit as i32),
                 end_time,
             );
 
-            // Wait if rate limited
-            self.cache.wait_if_needed().await;
-
-            // Check if we can make request
-            if !self.cache.can_make_request(KLINES_REQUEST_WEIGHT).await {
-                // Wait for rate limit reset
-                self.cache.wait_if_needed().await;
-            }
-
-            // Fetch chunk
-            let chunk = self
-                .fetch_single_chunk(symbol, interval, current_start, chunk_end)
-                .await?;
-
-            // Record the request
-            self.cache.record_request(KLINES_REQUEST_WEIGHT).await;
+            // Fetch chunk, retrying on failure
+            let chunk = match self
+                .fetch_chunk_with_retry(symbol, interval, current_start, chunk_end, page_limit)
+                .await
+            {
+                Ok(chunk) => chunk,
+                Err(e) if self.config.allow_partial_data && !all_klines.is_empty() => {
+                    warn!(
+                        "Returning partial data for {} ({} klines up to {}): {}",
+                        symbol,
+                        all_klines.len(),
+                        current_start,
+                        e
+                    );
+                    return Ok((Self::dedupe_klines(all_klines), false));
+                }
+                Err(e) => return Err(e),
+            };
 
             if chunk.is_empty() {
                 break;
@@ -174,25 +241,27 @@ impl BinanceFetcher {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
 
-        Ok(all_klines)
+        Ok((Self::dedupe_klines(all_klines), true))
     }
 
-    /// Fetch a single chunk of klines
-    async fn fetch_single_chunk(
+    /// Fetch one page, retrying transport failures and the policy's retryable statuses.
+    /// Waiting on the rate limiter doesn't count against the policy's deadline.
+    async fn fetch_chunk_with_retry(
         &self,
         symbol: &str,
         interval: &KlineInterval,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
+        limit: usize,
     ) -> Result<Vec<Kline>, AppError> {
-        let url = format!("{}/api/v3/klines", BINANCE_API_BASE);
+        let url = format!("{}/api/v3/klines", self.base_url);
 
         let params = [
             ("symbol", symbol.to_uppercase()),
             ("interval", interval.to_string()),
             ("startTime", start_time.timestamp_millis().to_string()),
             ("endTime", end_time.timestamp_millis().to_string()),
-            ("limit", MAX_KLINES_PER_REQUEST.to_string()),
+            ("limit", limit.to_string()),
         ];
 
         debug!(
@@ -200,16 +269,16 @@ impl BinanceFetcher {
             symbol, interval, start_time, end_time
         );
 
-        let response = self
-            .client
-            .get(&url)
-            .query(&params)
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Failed to send request to Binance: {}", e);
-                AppError::ExternalServiceError(format!("Failed to fetch data: {}", e))
-            })?;
+        let response = request_with_rate_limit(
+            &self.config.retry,
+            &self.rate_limiter,
+            self.client.get(&url).query(&params),
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch klines for {} from {}: {}", symbol, start_time, e);
+            AppError::ExternalServiceError(format!("Failed to fetch data: {}", e))
+        })?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -247,6 +316,13 @@ impl BinanceFetcher {
         Ok(klines)
     }
 
+    /// Sort by open time and drop duplicate boundary klines between pages
+    fn dedupe_klines(mut klines: Vec<Kline>) -> Vec<Kline> {
+        klines.sort_by_key(|k| k.open_time);
+        klines.dedup_by_key(|k| k.open_time);
+        klines
+    }
+
     /// Convert Binance kline to our format
     fn convert_kline(&self, binance_kline: BinanceKline) -> Result<Kline, AppError> {
         Ok(Kline {
@@ -276,26 +352,15 @@ impl BinanceFetcher {
         })
     }
 
-    /// Get interval duration
-    fn get_interval_duration(&self, interval: &KlineInterval) -> ChronoDuration {
-        match interval.as_str() {
-            "1m" => ChronoDuration::minutes(1),
-            "3m" => ChronoDuration::minutes(3),
-            "5m" => ChronoDuration::minutes(5),
-            "15m" => ChronoDuration::minutes(15),
-            "30m" => ChronoDuration::minutes(30),
-            "1h" => ChronoDuration::hours(1),
-            "2h" => ChronoDuration::hours(2),
-            "4h" => ChronoDuration::hours(4),
-            "6h" => ChronoDuration::hours(6),
-            "8h" => ChronoDuration::hours(8),
-            "12h" => ChronoDuration::hours(12),
-            "1d" => ChronoDuration::days(1),
-            "3d" => ChronoDuration::days(3),
-            "1w" => ChronoDuration::weeks(1),
-            "1M" => ChronoDuration::days(30),
-            _ => ChronoDuration::hours(1), // Default
+    /// Binance spelling of a pair named canonically (`BTC/USDT`) or the way dashed exchanges
+    /// write it (`BTC-USDT`). Symbols without a separator are returned as given.
+    pub fn native_symbol(symbol: &str) -> Result<String, AppError> {
+        if !symbol.contains(['/', '-']) {
+            return Ok(symbol.to_string());
         }
+        SymbolRef::from_any(symbol)
+            .and_then(|pair| pair.to_native(&Exchange::Binance))
+            .map_err(|e| AppError::BadRequest(e.to_string()))
     }
 
     /// Validate symbol format (e.g., BTCUSDT)
@@ -331,4 +396,152 @@ impl BinanceFetcher {
             "LINKUSDT".to_string(),
         ])
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mock_http::{self, MockRequest, MockResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const HOUR_MS: i64 = 3_600_000;
+    const BASE_MS: i64 = 1_700_000_000_000 - (1_700_000_000_000 % HOUR_MS);
+
+    fn query_param(request: &MockRequest, key: &str) -> Option<i64> {
+        request.param(key).and_then(|v| v.parse().ok())
+    }
+
+    /// Serves `total` hourly klines starting at `BASE_MS`. Each page also repeats the
+    /// kline just before `startTime` to simulate overlapping boundaries. Request numbers
+    /// for which `fail` returns a status get that status instead.
+    async fn spawn_mock_server(
+        total: i64,
+        fail: impl Fn(usize) -> Option<&'static str> + Send + Sync + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        mock_http::spawn(move |request| {
+            if let Some(status) = fail(request.number) {
+                return Some(MockResponse::json(status, "{\"msg\":\"boom\"}"));
+            }
+
+            let start = query_param(request, "startTime").unwrap_or(BASE_MS);
+            let end = query_param(request, "endTime").unwrap_or(i64::MAX);
+            let limit = query_param(request, "limit").unwrap_or(1000);
+
+            let first = ((start - BASE_MS) / HOUR_MS - 1).max(0);
+            let rows: Vec<String> = (first..total)
+                .map(|i| BASE_MS + i * HOUR_MS)
+                .take_while(|open| *open <= end)
+                .take(limit as usize)
+                .map(|open| {
+                    format!(
+                        "[{},\"100\",\"110\",\"90\",\"105\",\"1\",{},\"105\",1,\"0.5\",\"52\",\"0\"]",
+                        open,
+                        open + HOUR_MS - 1
+                    )
+                })
+                .collect();
+            Some(MockResponse::json("200 OK", format!("[{}]", rows.join(","))))
+        })
+        .await
+    }
+
+    fn test_config(allow_partial_data: bool) -> FetcherConfig {
+        FetcherConfig {
+            page_limit: 10,
+            retry: RetryPolicy {
+                max_attempts: 3,
+                base_delay: std::time::Duration::from_millis(1),
+                ..RetryPolicy::default()
+            },
+            allow_partial_data,
+            validation: DataValidationConfig::default(),
+        }
+    }
+
+    fn range(hours: i64) -> (DateTime<Utc>, DateTime<Utc>) {
+        (
+            DateTime::from_timestamp_millis(BASE_MS).unwrap(),
+            DateTime::from_timestamp_millis(BASE_MS + hours * HOUR_MS).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_retries_failed_page_and_accumulates() {
+        // Second request (first attempt at page two) fails once
+        let (url, requests) = spawn_mock_server(25, |n| (n == 1).then_some("500 Internal Server Error")).await;
+        let fetcher = BinanceFetcher::with_base_url(&url, test_config(false));
+        let (start, end) = range(25);
+
+        let klines = fetcher
+            .fetch_klines("RETRYPAGEUSDT", &KlineInterval::OneHour, start, end)
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 25);
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+        assert!(klines.windows(2).all(|w| w[0].open_time < w[1].open_time));
+    }
+
+    #[tokio::test]
+    async fn test_dedupes_overlapping_boundary_klines() {
+        let (url, _) = spawn_mock_server(25, |_| None).await;
+        let fetcher = BinanceFetcher::with_base_url(&url, test_config(false));
+        let (start, end) = range(25);
+
+        let klines = fetcher
+            .fetch_klines("DEDUPEPAGEUSDT", &KlineInterval::OneHour, start, end)
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 25);
+        assert_eq!(klines.first().unwrap().open_time, start);
+    }
+
+    #[tokio::test]
+    async fn test_returns_partial_data_when_allowed() {
+        // Every request after the first page fails
+        let (url, requests) = spawn_mock_server(25, |n| (n >= 1).then_some("500 Internal Server Error")).await;
+        let fetcher = BinanceFetcher::with_base_url(&url, test_config(true));
+        let (start, end) = range(25);
+
+        let klines = fetcher
+            .fetch_klines("PARTIALPAGEUSDT", &KlineInterval::OneHour, start, end)
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 10);
+        // One initial attempt plus two retries for the failing page
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+        // Partial results must not be cached
+        assert!(get_cache()
+            .get("PARTIALPAGEUSDT", &KlineInterval::OneHour, start, end)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_errors_on_page_failure_when_partial_disallowed() {
+        let (url, _) = spawn_mock_server(25, |n| (n >= 1).then_some("500 Internal Server Error")).await;
+        let fetcher = BinanceFetcher::with_base_url(&url, test_config(false));
+        let (start, end) = range(25);
+
+        let result = fetcher
+            .fetch_klines("STRICTPAGEUSDT", &KlineInterval::OneHour, start, end)
+            .await;
+
+        assert!(matches!(result, Err(AppError::ExternalServiceError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (url, requests) = spawn_mock_server(25, |n| (n == 1).then_some("400 Bad Request")).await;
+        let fetcher = BinanceFetcher::with_base_url(&url, test_config(false));
+        let (start, end) = range(25);
+
+        let result = fetcher
+            .fetch_klines("BADPAGEUSDT", &KlineInterval::OneHour, start, end)
+            .await;
+
+        assert!(matches!(result, Err(AppError::ExternalServiceError(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
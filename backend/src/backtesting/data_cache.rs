@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc};
@@ -7,6 +8,7 @@ use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::exchange_connectors::{Kline, KlineInterval};
+use crate::utils::metrics::metrics;
 
 /// Cache key for kline data
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -26,19 +28,36 @@ struct CacheEntry {
     last_accessed: Instant,
 }
 
+impl CacheEntry {
+    /// Approximate memory held by the entry's klines
+    fn size_bytes(&self) -> usize {
+        estimated_size_bytes(&self.data)
+    }
+}
+
+fn estimated_size_bytes(data: &[Kline]) -> usize {
+    data.len() * std::mem::size_of::<Kline>()
+}
+
 /// Smart caching system for historical kline data
 pub struct DataCache {
     /// Main cache storage
     cache: Arc<DashMap<CacheKey, CacheEntry>>,
-    /// Rate limit tracker for Binance API
+    /// Rate limit tracker for the stock data API; Binance requests go through the
+    /// process-wide Binance limiter instead
     rate_limiter: Arc<RwLock<RateLimiter>>,
     /// Cache configuration
     config: CacheConfig,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
 }
 
 /// Cache configuration
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
+    /// Maximum number of cached ranges
+    pub max_entries: usize,
     /// Maximum cache size in MB
     pub max_size_mb: usize,
     /// TTL for cache entries
@@ -52,6 +71,7 @@ pub struct CacheConfig {
 impl Default for CacheConfig {
     fn default() -> Self {
         Self {
+            max_entries: 1000,
             max_size_mb: 500, // 500MB cache
             ttl_seconds: 300, // 5 minutes for cold data
             hot_ttl_seconds: 900, // 15 minutes for hot data
@@ -60,6 +80,30 @@ impl Default for CacheConfig {
     }
 }
 
+impl CacheConfig {
+    /// Read `BACKTEST_CACHE_MAX_ENTRIES` / `BACKTEST_CACHE_MAX_SIZE_MB`, falling back to the defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |key: &str, default: usize| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+
+        Self {
+            max_entries: read("BACKTEST_CACHE_MAX_ENTRIES", defaults.max_entries),
+            max_size_mb: read("BACKTEST_CACHE_MAX_SIZE_MB", defaults.max_size_mb),
+            ..defaults
+        }
+    }
+
+    fn max_size_bytes(&self) -> usize {
+        self.max_size_mb.saturating_mul(1024 * 1024)
+    }
+}
+
 /// Rate limiter for Binance API
 #[derive(Debug)]
 struct RateLimiter {
@@ -127,9 +171,18 @@ impl DataCache {
             cache: Arc::new(DashMap::new()),
             rate_limiter: Arc::new(RwLock::new(RateLimiter::new())),
             config,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
         }
     }
 
+    fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        metrics().record_cache_lookup(hit);
+    }
+
     /// Get cached data or None if not found/expired
     pub async fn get(
         &self,
@@ -160,6 +213,7 @@ impl DataCache {
                 // Entry expired
                 drop(entry);
                 self.cache.remove(&key);
+                self.record_lookup(false);
                 debug!("Cache miss: expired entry for {}:{}", symbol, interval);
                 return None;
             }
@@ -173,9 +227,12 @@ impl DataCache {
                 "Cache hit: {}:{} (accesses: {})",
                 symbol, interval, entry.access_count
             );
+            drop(entry);
+            self.record_lookup(true);
 
             Some(data)
         } else {
+            self.record_lookup(false);
             debug!("Cache miss: no entry for {}:{}", symbol, interval);
             None
         }
@@ -204,10 +261,9 @@ impl DataCache {
             last_accessed: Instant::now(),
         };
 
-        // Check cache size before inserting
-        if self.should_evict().await {
-            self.evict_lru().await;
-        }
+        // Replacing a range frees its old entry first
+        self.cache.remove(&key);
+        self.evict_to_fit(entry.size_bytes());
 
         self.cache.insert(key, entry);
         info!(
@@ -216,36 +272,40 @@ impl DataCache {
         );
     }
 
-    /// Check if we need to evict entries
-    async fn should_evict(&self) -> bool {
-        // Estimate cache size (simplified)
-        let entry_count = self.cache.len();
-        let estimated_size_mb = entry_count * 2; // Rough estimate: 2MB per entry
-
-        estimated_size_mb > self.config.max_size_mb
+    /// Approximate memory held by all cached klines
+    fn size_bytes(&self) -> usize {
+        self.cache.iter().map(|entry| entry.size_bytes()).sum()
     }
 
-    /// Evict least recently used entries
-    async fn evict_lru(&self) {
-        let mut entries: Vec<_> = self.cache
-            .iter()
-            .map(|entry| {
-                let key = entry.key().clone();
-                let last_accessed = entry.last_accessed;
-                (key, last_accessed)
-            })
-            .collect();
-
-        // Sort by last accessed time
-        entries.sort_by_key(|&(_, last_accessed)| last_accessed);
-
-        // Remove 20% of oldest entries
-        let to_remove = entries.len() / 5;
-        for (key, _) in entries.iter().take(to_remove) {
-            self.cache.remove(key);
+    /// Evict least recently used entries until one more entry of `incoming_bytes`
+    /// fits within the entry and memory limits
+    fn evict_to_fit(&self, incoming_bytes: usize) {
+        let max_bytes = self.config.max_size_bytes();
+        let mut size_bytes = self.size_bytes();
+        let mut evicted = 0;
+
+        while !self.cache.is_empty()
+            && (self.cache.len() >= self.config.max_entries || size_bytes + incoming_bytes > max_bytes)
+        {
+            let oldest = self
+                .cache
+                .iter()
+                .min_by_key(|entry| entry.last_accessed)
+                .map(|entry| entry.key().clone());
+            let Some(key) = oldest else { break };
+
+            if let Some((_, entry)) = self.cache.remove(&key) {
+                size_bytes = size_bytes.saturating_sub(entry.size_bytes());
+                evicted += 1;
+                debug!("Evicted cache entry {}:{}", key.symbol, key.interval);
+            }
         }
 
-        info!("Evicted {} cache entries", to_remove);
+        if evicted > 0 {
+            self.evictions.fetch_add(evicted, Ordering::Relaxed);
+            metrics().data_cache_evictions_total.inc_by(evicted);
+            info!("Evicted {} cache entries", evicted);
+        }
     }
 
     /// Clear all cache entries
@@ -267,11 +327,23 @@ impl DataCache {
             }
         }
 
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        let size_bytes = self.size_bytes();
+
         CacheStats {
             total_entries,
             hot_entries,
             total_accesses,
-            estimated_size_mb: total_entries * 2,
+            estimated_size_mb: size_bytes / (1024 * 1024),
+            estimated_size_bytes: size_bytes,
+            max_entries: self.config.max_entries,
+            max_size_mb: self.config.max_size_mb,
+            hits,
+            misses,
+            hit_rate: if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 },
+            evictions: self.evictions.load(Ordering::Relaxed),
         }
     }
 
@@ -310,6 +382,14 @@ pub struct CacheStats {
     pub hot_entries: usize,
     pub total_accesses: usize,
     pub estimated_size_mb: usize,
+    pub estimated_size_bytes: usize,
+    pub max_entries: usize,
+    pub max_size_mb: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Fraction of lookups served from the cache (0.0 to 1.0)
+    pub hit_rate: f64,
+    pub evictions: u64,
 }
 
 /// Global cache instance (singleton)
@@ -318,7 +398,100 @@ static CACHE_INSTANCE: once_cell::sync::OnceCell<Arc<DataCache>> = once_cell::sy
 /// Get or create the global cache instance
 pub fn get_cache() -> Arc<DataCache> {
     CACHE_INSTANCE
-        .get_or_init(|| Arc::new(DataCache::new(CacheConfig::default())))
+        .get_or_init(|| Arc::new(DataCache::new(CacheConfig::from_env())))
         .clone()
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+
+    fn klines(count: usize) -> Vec<Kline> {
+        let time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..count)
+            .map(|_| Kline {
+                open_time: time,
+                close_time: time,
+                open: Decimal::ONE,
+                high: Decimal::ONE,
+                low: Decimal::ONE,
+                close: Decimal::ONE,
+                volume: Decimal::ONE,
+                quote_asset_volume: Decimal::ONE,
+                number_of_trades: 1,
+                taker_buy_base_asset_volume: Decimal::ONE,
+                taker_buy_quote_asset_volume: Decimal::ONE,
+            })
+            .collect()
+    }
+
+    fn day(n: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + chrono::Duration::days(n)
+    }
+
+    fn small_cache(max_entries: usize) -> DataCache {
+        DataCache::new(CacheConfig {
+            max_entries,
+            ..CacheConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_exceeding_entry_cap_evicts_least_recently_used() {
+        let cache = small_cache(2);
+        let interval = KlineInterval::OneHour;
+        cache.store("BTCUSDT", &interval, day(0), day(1), klines(3)).await;
+        cache.store("ETHUSDT", &interval, day(0), day(1), klines(3)).await;
+
+        // Touch BTC so ETH becomes the least recently used entry
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        assert!(cache.get("BTCUSDT", &interval, day(0), day(1)).await.is_some());
+
+        cache.store("SOLUSDT", &interval, day(0), day(1), klines(3)).await;
+
+        assert!(cache.get("ETHUSDT", &interval, day(0), day(1)).await.is_none());
+        assert!(cache.get("BTCUSDT", &interval, day(0), day(1)).await.is_some());
+        assert!(cache.get("SOLUSDT", &interval, day(0), day(1)).await.is_some());
+        let stats = cache.stats().await;
+        assert_eq!(stats.total_entries, 2);
+        assert_eq!(stats.evictions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_cap_evicts_to_fit() {
+        let cache = DataCache::new(CacheConfig {
+            max_size_mb: 1,
+            ..CacheConfig::default()
+        });
+        let interval = KlineInterval::OneHour;
+        // Each range takes a bit over half the 1MB budget
+        let per_entry = 1024 * 1024 / std::mem::size_of::<Kline>() / 2 + 1;
+        cache.store("BTCUSDT", &interval, day(0), day(1), klines(per_entry)).await;
+        cache.store("ETHUSDT", &interval, day(0), day(1), klines(per_entry)).await;
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.total_entries, 1);
+        assert!(stats.estimated_size_bytes <= 1024 * 1024);
+        assert!(cache.get("ETHUSDT", &interval, day(0), day(1)).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_hits_and_misses_are_counted() {
+        let cache = small_cache(10);
+        let interval = KlineInterval::OneHour;
+        assert!(cache.get("BTCUSDT", &interval, day(0), day(1)).await.is_none());
+
+        cache.store("BTCUSDT", &interval, day(0), day(1), klines(2)).await;
+        assert!(cache.get("BTCUSDT", &interval, day(0), day(1)).await.is_some());
+        assert!(cache.get("BTCUSDT", &interval, day(0), day(1)).await.is_some());
+        assert!(cache.get("BTCUSDT", &interval, day(1), day(2)).await.is_none());
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.hit_rate, 0.5);
+    }
+}
@@ -0,0 +1,291 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::exchange_connectors::Kline;
+use crate::utils::errors::AppError;
+
+/// Smallest per-bar return deviation used for spike detection, so that a mostly flat
+/// series does not flag every small move as a spike
+const MIN_RETURN_SIGMA: f64 = 0.005;
+
+/// Scale factor turning a median absolute deviation into a standard deviation estimate
+const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// What to do with a series that contains bad bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyAction {
+    /// Fail the fetch
+    Reject,
+    /// Drop the bad bars and continue with the rest
+    #[default]
+    Clean,
+}
+
+/// Price sanity checks run on fetched backtest data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DataValidationConfig {
+    pub action: AnomalyAction,
+    /// A close that moves more than this many standard deviations of the series'
+    /// bar-to-bar log returns, and moves back on the next bar, is treated as a spike
+    pub max_jump_sigma: f64,
+}
+
+impl Default for DataValidationConfig {
+    fn default() -> Self {
+        Self {
+            action: AnomalyAction::Clean,
+            max_jump_sigma: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Open, high, low or close is zero or negative
+    NonPositivePrice,
+    /// High is below low
+    HighBelowLow,
+    /// Close jumps away from its neighbours by more than the configured sigma
+    PriceSpike,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataAnomaly {
+    pub open_time: DateTime<Utc>,
+    pub kind: AnomalyKind,
+    pub detail: String,
+}
+
+/// Anomalies found in one fetched series
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub bars_checked: usize,
+    pub anomalies: Vec<DataAnomaly>,
+    /// Bars dropped from the returned series (only when cleaning)
+    pub bars_removed: usize,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    fn summary(&self) -> String {
+        self.anomalies
+            .iter()
+            .map(|a| format!("{} {:?}: {}", a.open_time, a.kind, a.detail))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Check a kline series for impossible or implausible prices.
+///
+/// Bars with a non-positive price or `high < low` are always anomalies. A close is a
+/// spike when its log return from the last good close exceeds `max_jump_sigma` times a
+/// robust (median absolute deviation) estimate of the series' return deviation and the
+/// next bar gives back more than half of the jump; a sustained move is left alone.
+///
+/// Depending on `config.action` the bad bars are either dropped from the returned
+/// series or the whole series is rejected.
+pub fn validate_klines(
+    symbol: &str,
+    klines: Vec<Kline>,
+    config: &DataValidationConfig,
+) -> Result<(Vec<Kline>, ValidationReport), AppError> {
+    let mut report = ValidationReport {
+        bars_checked: klines.len(),
+        ..Default::default()
+    };
+    let mut bad = vec![false; klines.len()];
+
+    for (i, kline) in klines.iter().enumerate() {
+        let prices = [kline.open, kline.high, kline.low, kline.close];
+        if prices.iter().any(|p| *p <= Decimal::ZERO) {
+            report.anomalies.push(DataAnomaly {
+                open_time: kline.open_time,
+                kind: AnomalyKind::NonPositivePrice,
+                detail: format!(
+                    "open {} high {} low {} close {}",
+                    kline.open, kline.high, kline.low, kline.close
+                ),
+            });
+            bad[i] = true;
+        } else if kline.high < kline.low {
+            report.anomalies.push(DataAnomaly {
+                open_time: kline.open_time,
+                kind: AnomalyKind::HighBelowLow,
+                detail: format!("high {} < low {}", kline.high, kline.low),
+            });
+            bad[i] = true;
+        }
+    }
+
+    let closes: Vec<(usize, f64)> = klines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !bad[*i])
+        .filter_map(|(i, k)| k.close.to_f64().map(|c| (i, c)))
+        .collect();
+    let sigma = robust_return_sigma(&closes);
+    let threshold = config.max_jump_sigma * sigma;
+
+    let mut last_good: Option<f64> = None;
+    for (n, &(i, close)) in closes.iter().enumerate() {
+        let Some(previous) = last_good else {
+            last_good = Some(close);
+            continue;
+        };
+
+        let jump = (close / previous).ln();
+        let reverts = closes
+            .get(n + 1)
+            .map_or(true, |&(_, next)| (next / previous).ln().abs() < jump.abs() / 2.0);
+
+        if jump.abs() > threshold && reverts {
+            report.anomalies.push(DataAnomaly {
+                open_time: klines[i].open_time,
+                kind: AnomalyKind::PriceSpike,
+                detail: format!(
+                    "close {} is a {:.1}% move from {} ({:.1} sigma)",
+                    klines[i].close,
+                    jump.exp_m1() * 100.0,
+                    previous,
+                    jump.abs() / sigma
+                ),
+            });
+            bad[i] = true;
+        } else {
+            last_good = Some(close);
+        }
+    }
+
+    if report.is_clean() {
+        return Ok((klines, report));
+    }
+
+    match config.action {
+        AnomalyAction::Reject => Err(AppError::ExternalServiceError(format!(
+            "{} of {} bars for {} failed price validation: {}",
+            report.anomalies.len(),
+            report.bars_checked,
+            symbol,
+            report.summary()
+        ))),
+        AnomalyAction::Clean => {
+            let cleaned: Vec<Kline> = klines
+                .into_iter()
+                .zip(bad)
+                .filter_map(|(kline, bad)| (!bad).then_some(kline))
+                .collect();
+            report.bars_removed = report.bars_checked - cleaned.len();
+            Ok((cleaned, report))
+        }
+    }
+}
+
+/// Standard deviation estimate of bar-to-bar log returns that a few outliers cannot inflate
+fn robust_return_sigma(closes: &[(usize, f64)]) -> f64 {
+    let mut returns: Vec<f64> = closes.windows(2).map(|w| (w[1].1 / w[0].1).ln()).collect();
+    if returns.is_empty() {
+        return MIN_RETURN_SIGMA;
+    }
+
+    let median_return = median(&mut returns);
+    let mut deviations: Vec<f64> = returns.iter().map(|r| (r - median_return).abs()).collect();
+    (median(&mut deviations) * MAD_TO_SIGMA).max(MIN_RETURN_SIGMA)
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn kline(hour: i64, close: Decimal) -> Kline {
+        let open_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::hours(hour);
+        Kline {
+            open_time,
+            close_time: open_time + Duration::minutes(59),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: Decimal::from(10),
+            quote_asset_volume: Decimal::from(1000),
+            number_of_trades: 5,
+            taker_buy_base_asset_volume: Decimal::from(5),
+            taker_buy_quote_asset_volume: Decimal::from(500),
+        }
+    }
+
+    /// Gently oscillating series around 100
+    fn series(len: i64) -> Vec<Kline> {
+        (0..len)
+            .map(|i| kline(i, Decimal::from(100) + Decimal::new((i % 5) * 7, 1)))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_series_passes_untouched() {
+        let data = series(30);
+        let (cleaned, report) = validate_klines("BTC", data.clone(), &DataValidationConfig::default()).unwrap();
+
+        assert!(report.is_clean());
+        assert_eq!(cleaned.len(), data.len());
+    }
+
+    #[test]
+    fn test_detects_negative_price_and_spike() {
+        let mut data = series(30);
+        data[8] = kline(8, Decimal::from(-5));
+        data[20] = kline(20, data[19].close * Decimal::from(10));
+
+        let (cleaned, report) = validate_klines("BTC", data.clone(), &DataValidationConfig::default()).unwrap();
+
+        let kinds: Vec<(DateTime<Utc>, AnomalyKind)> =
+            report.anomalies.iter().map(|a| (a.open_time, a.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (data[8].open_time, AnomalyKind::NonPositivePrice),
+                (data[20].open_time, AnomalyKind::PriceSpike),
+            ]
+        );
+        assert_eq!(report.bars_removed, 2);
+        assert_eq!(cleaned.len(), 28);
+        assert!(cleaned.iter().all(|k| k.open_time != data[8].open_time && k.open_time != data[20].open_time));
+
+        let reject = DataValidationConfig { action: AnomalyAction::Reject, ..Default::default() };
+        assert!(validate_klines("BTC", data, &reject).is_err());
+    }
+
+    #[test]
+    fn test_high_below_low_and_sustained_move() {
+        let mut data = series(30);
+        data[3].high = Decimal::from(90);
+        // A lasting 10x re-pricing (e.g. a redenomination) is not a spike
+        for k in data.iter_mut().skip(15) {
+            let close = k.close * Decimal::from(10);
+            (k.open, k.high, k.low, k.close) = (close, close, close, close);
+        }
+
+        let (_, report) = validate_klines("BTC", data, &DataValidationConfig::default()).unwrap();
+
+        assert_eq!(report.anomalies.len(), 1);
+        assert_eq!(report.anomalies[0].kind, AnomalyKind::HighBelowLow);
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::backtesting::types::{BacktestTrade, PerformancePoint, TradeType};
+use crate::models::backtest_result::Model as BacktestResultModel;
+use crate::utils::errors::AppError;
+
+/// Equity values closer than this are treated as equal (older stored curves were rounded to cents)
+const EQUITY_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Change in one summary metric between two runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub base: Option<Decimal>,
+    pub compare: Option<Decimal>,
+    /// `compare - base`, when both sides are present
+    pub delta: Option<Decimal>,
+}
+
+/// A trade present in both runs at the same bar but with different fills
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedTrade {
+    pub base: BacktestTrade,
+    pub compare: BacktestTrade,
+}
+
+/// A bar where the two equity curves disagree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityDivergence {
+    pub timestamp: DateTime<Utc>,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub base_value: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub compare_value: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub difference: Decimal,
+}
+
+/// What changed between a stored backtest run and a later run of the same config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestDiff {
+    pub base_id: Uuid,
+    pub compare_id: Uuid,
+    /// Config fields that differ between the runs; empty when they share a config
+    pub config_differences: Vec<String>,
+    /// Metrics whose value changed
+    pub metrics: Vec<MetricDelta>,
+    /// Trades only in the compare run, matched by timestamp and side
+    pub added_trades: Vec<BacktestTrade>,
+    /// Trades only in the base run
+    pub removed_trades: Vec<BacktestTrade>,
+    pub changed_trades: Vec<ChangedTrade>,
+    pub first_divergence: Option<DateTime<Utc>>,
+    pub equity_divergence: Vec<EquityDivergence>,
+}
+
+impl BacktestDiff {
+    pub fn is_identical(&self) -> bool {
+        self.metrics.is_empty()
+            && self.added_trades.is_empty()
+            && self.removed_trades.is_empty()
+            && self.changed_trades.is_empty()
+            && self.equity_divergence.is_empty()
+    }
+}
+
+/// Diff two stored backtest results
+pub fn diff_results(base: &BacktestResultModel, compare: &BacktestResultModel) -> Result<BacktestDiff, AppError> {
+    let base_trades = stored_trades(base)?;
+    let compare_trades = stored_trades(compare)?;
+    let (added_trades, removed_trades, changed_trades) = diff_trades(base_trades, compare_trades);
+
+    let equity_divergence = diff_equity(&stored_equity(base)?, &stored_equity(compare)?);
+
+    Ok(BacktestDiff {
+        base_id: base.id,
+        compare_id: compare.id,
+        config_differences: config_differences(base, compare),
+        metrics: diff_metrics(base, compare),
+        added_trades,
+        removed_trades,
+        changed_trades,
+        first_divergence: equity_divergence.first().map(|point| point.timestamp),
+        equity_divergence,
+    })
+}
+
+fn stored_trades(result: &BacktestResultModel) -> Result<Vec<BacktestTrade>, AppError> {
+    serde_json::from_value(result.trades_data.clone()).map_err(|e| {
+        AppError::ParseError(format!("Backtest {} has unreadable trades: {}", result.id, e))
+    })
+}
+
+fn stored_equity(result: &BacktestResultModel) -> Result<Vec<PerformancePoint>, AppError> {
+    serde_json::from_value(result.equity_curve.clone()).map_err(|e| {
+        AppError::ParseError(format!("Backtest {} has an unreadable equity curve: {}", result.id, e))
+    })
+}
+
+fn config_differences(base: &BacktestResultModel, compare: &BacktestResultModel) -> Vec<String> {
+    let mut differences = Vec::new();
+    if base.strategy_name != compare.strategy_name {
+        differences.push("strategy_name".to_string());
+    }
+    if base.strategy_type != compare.strategy_type {
+        differences.push("strategy_type".to_string());
+    }
+    if base.symbol != compare.symbol {
+        differences.push("symbol".to_string());
+    }
+    if base.interval != compare.interval {
+        differences.push("interval".to_string());
+    }
+    if base.start_date != compare.start_date || base.end_date != compare.end_date {
+        differences.push("date_range".to_string());
+    }
+    if base.initial_balance != compare.initial_balance {
+        differences.push("initial_balance".to_string());
+    }
+    if base.strategy_parameters != compare.strategy_parameters {
+        differences.push("strategy_parameters".to_string());
+    }
+    differences
+}
+
+fn diff_metrics(base: &BacktestResultModel, compare: &BacktestResultModel) -> Vec<MetricDelta> {
+    let count = |n: i32| Some(Decimal::from(n));
+    let pairs = [
+        ("final_balance", Some(base.final_balance), Some(compare.final_balance)),
+        ("total_return", Some(base.total_return), Some(compare.total_return)),
+        ("total_return_percentage", Some(base.total_return_percentage), Some(compare.total_return_percentage)),
+        ("max_drawdown", Some(base.max_drawdown), Some(compare.max_drawdown)),
+        ("max_drawdown_percentage", Some(base.max_drawdown_percentage), Some(compare.max_drawdown_percentage)),
+        ("sharpe_ratio", base.sharpe_ratio, compare.sharpe_ratio),
+        ("total_trades", count(base.total_trades), count(compare.total_trades)),
+        ("winning_trades", count(base.winning_trades), count(compare.winning_trades)),
+        ("losing_trades", count(base.losing_trades), count(compare.losing_trades)),
+        ("win_rate", Some(base.win_rate), Some(compare.win_rate)),
+        ("profit_factor", base.profit_factor, compare.profit_factor),
+        ("largest_win", Some(base.largest_win), Some(compare.largest_win)),
+        ("largest_loss", Some(base.largest_loss), Some(compare.largest_loss)),
+        ("total_invested", Some(base.total_invested), Some(compare.total_invested)),
+    ];
+
+    pairs
+        .into_iter()
+        .filter(|(_, base, compare)| base != compare)
+        .map(|(metric, base, compare)| MetricDelta {
+            metric: metric.to_string(),
+            base,
+            compare,
+            delta: base.zip(compare).map(|(base, compare)| compare - base),
+        })
+        .collect()
+}
+
+fn trade_key(trade: &BacktestTrade) -> (DateTime<Utc>, bool) {
+    (trade.timestamp, matches!(trade.trade_type, TradeType::Buy))
+}
+
+/// Split trades into (added, removed, changed), matching them by timestamp and side
+fn diff_trades(
+    base: Vec<BacktestTrade>,
+    compare: Vec<BacktestTrade>,
+) -> (Vec<BacktestTrade>, Vec<BacktestTrade>, Vec<ChangedTrade>) {
+    let mut remaining: BTreeMap<(DateTime<Utc>, bool), Vec<BacktestTrade>> = BTreeMap::new();
+    for trade in base {
+        remaining.entry(trade_key(&trade)).or_default().push(trade);
+    }
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for trade in compare {
+        let matched = remaining.get_mut(&trade_key(&trade)).and_then(|trades| {
+            if trades.is_empty() {
+                None
+            } else {
+                Some(trades.remove(0))
+            }
+        });
+        match matched {
+            Some(base) if base.price != trade.price || base.quantity != trade.quantity => {
+                changed.push(ChangedTrade { base, compare: trade });
+            }
+            Some(_) => {}
+            None => added.push(trade),
+        }
+    }
+
+    let removed = remaining.into_values().flatten().collect();
+    (added, removed, changed)
+}
+
+/// Bars (matched by timestamp) where portfolio values differ
+fn diff_equity(base: &[PerformancePoint], compare: &[PerformancePoint]) -> Vec<EquityDivergence> {
+    let base_values: BTreeMap<DateTime<Utc>, Decimal> =
+        base.iter().map(|point| (point.timestamp, point.portfolio_value)).collect();
+
+    compare
+        .iter()
+        .filter_map(|point| {
+            let base_value = *base_values.get(&point.timestamp)?;
+            let difference = point.portfolio_value - base_value;
+            (difference.abs() >= EQUITY_TOLERANCE).then(|| EquityDivergence {
+                timestamp: point.timestamp,
+                base_value,
+                compare_value: point.portfolio_value,
+                difference,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+
+    fn at(hour: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::hours(hour)
+    }
+
+    fn trade(hour: i64, trade_type: TradeType, price: i64) -> BacktestTrade {
+        BacktestTrade {
+            timestamp: at(hour),
+            trade_type,
+            price: Decimal::from(price),
+            quantity: Decimal::ONE,
+            total_value: Decimal::from(price),
+            portfolio_value: Decimal::from(1000),
+            balance_remaining: Decimal::from(1000 - price),
+            reason: "Strategy signal".to_string(),
+            pnl: None,
+            pnl_percentage: None,
+        }
+    }
+
+    fn equity(values: &[i64]) -> Vec<PerformancePoint> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(hour, value)| PerformancePoint {
+                timestamp: at(hour as i64),
+                portfolio_value: Decimal::from(*value),
+                asset_price: Decimal::from(100),
+                trade_marker: None,
+            })
+            .collect()
+    }
+
+    fn stored(trades: Vec<BacktestTrade>, curve: Vec<PerformancePoint>, final_balance: i64) -> BacktestResultModel {
+        BacktestResultModel {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "MACD run".to_string(),
+            description: None,
+            strategy_name: "macd".to_string(),
+            strategy_type: None,
+            symbol: "BTCUSDT".to_string(),
+            interval: "1h".to_string(),
+            start_date: at(0),
+            end_date: at(5),
+            initial_balance: Decimal::from(1000),
+            final_balance: Decimal::from(final_balance),
+            total_return: Decimal::from(final_balance - 1000),
+            total_return_percentage: Decimal::from(final_balance - 1000) / Decimal::from(10),
+            max_drawdown: Decimal::ZERO,
+            max_drawdown_percentage: Decimal::ZERO,
+            sharpe_ratio: None,
+            total_trades: trades.len() as i32,
+            winning_trades: 0,
+            losing_trades: 0,
+            win_rate: Decimal::ZERO,
+            profit_factor: None,
+            largest_win: Decimal::ZERO,
+            largest_loss: Decimal::ZERO,
+            average_win: Decimal::ZERO,
+            average_loss: Decimal::ZERO,
+            total_invested: Decimal::ZERO,
+            strategy_parameters: json!({ "fast": 12, "slow": 26 }),
+            trades_data: serde_json::to_value(&trades).unwrap(),
+            equity_curve: serde_json::to_value(&curve).unwrap(),
+            drawdown_curve: json!([]),
+            status: "completed".to_string(),
+            error_message: None,
+            checkpoint_json: None,
+            execution_time_ms: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_diff_pinpoints_the_one_differing_trade() {
+        let base = stored(
+            vec![trade(1, TradeType::Buy, 100), trade(3, TradeType::Sell, 110)],
+            equity(&[1000, 1000, 1005, 1010, 1010]),
+            1010,
+        );
+        // The fixed signal line sells one bar later, at a better price
+        let compare = stored(
+            vec![trade(1, TradeType::Buy, 100), trade(4, TradeType::Sell, 115)],
+            equity(&[1000, 1000, 1005, 1012, 1015]),
+            1015,
+        );
+
+        let diff = diff_results(&base, &compare).unwrap();
+
+        assert!(diff.config_differences.is_empty());
+        assert!(diff.changed_trades.is_empty());
+        assert_eq!(diff.removed_trades.len(), 1);
+        assert_eq!(diff.removed_trades[0].timestamp, at(3));
+        assert_eq!(diff.added_trades.len(), 1);
+        assert_eq!(diff.added_trades[0].timestamp, at(4));
+        assert_eq!(diff.added_trades[0].price, Decimal::from(115));
+
+        assert_eq!(diff.first_divergence, Some(at(3)));
+        assert_eq!(diff.equity_divergence.len(), 2);
+        assert_eq!(diff.equity_divergence[1].difference, Decimal::from(5));
+
+        let final_balance = diff.metrics.iter().find(|m| m.metric == "final_balance").unwrap();
+        assert_eq!(final_balance.delta, Some(Decimal::from(5)));
+        assert!(diff.metrics.iter().all(|m| m.metric != "total_trades"));
+    }
+
+    #[test]
+    fn test_identical_runs_produce_empty_diff() {
+        let trades = vec![trade(1, TradeType::Buy, 100)];
+        let base = stored(trades.clone(), equity(&[1000, 1000]), 1000);
+        let mut compare = stored(trades, equity(&[1000, 1000]), 1000);
+        compare.strategy_parameters = json!({ "fast": 10, "slow": 26 });
+
+        let diff = diff_results(&base, &compare).unwrap();
+
+        assert!(diff.is_identical());
+        assert_eq!(diff.config_differences, vec!["strategy_parameters".to_string()]);
+    }
+}
@@ -3,129 +3,1293 @@ use std::time::Instant;
 use rust_decimal::{Decimal, prelude::*};
 use tracing::{info, debug, warn};
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
 
 use crate::backtesting::types::*;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use crate::backtesting::binance_fetcher::BinanceFetcher;
 use crate::backtesting::stock_fetcher::StockFetcher;
-use crate::strategies::{Strategy, create_strategy, StrategySignal, StrategySignalType, QuantityType, StrategyMode, StrategyContext, MarketData};
+use crate::backtesting::robustness::RobustnessScore;
+use crate::strategies::{Strategy, create_strategy, StrategySignal, StrategySignalType, QuantityType, StrategyMode, StrategyContext, MarketData, ExecutionOrderType, apply_min_confidence, min_confidence_from_parameters, TradeStats};
 use crate::strategies::core::traits::{OrderUpdate, OrderStatus, OrderType as TraitsOrderType};
-use crate::exchange_connectors::{Kline};
+use crate::strategies::indicators::{atr, realized_volatility, to_heikin_ashi};
+use crate::exchange_connectors::{Kline, KlineInterval};
+use crate::exchange_connectors::common_types::OrderSide;
+use crate::models::backtest_result;
 use crate::utils::errors::AppError;
+use crate::utils::metrics::metrics;
+use crate::utils::precision::decimal_sqrt;
+
+/// Per-bar and per-trade `debug!`, skipped (arguments included) unless the backtest
+/// has `log_trades` set
+macro_rules! trade_debug {
+    ($config:expr, $($arg:tt)+) => {
+        if $config.log_trades {
+            debug!($($arg)+);
+        }
+    };
+}
+
+/// `warn!` counterpart of `trade_debug!`
+macro_rules! trade_warn {
+    ($config:expr, $($arg:tt)+) => {
+        if $config.log_trades {
+            warn!($($arg)+);
+        }
+    };
+}
+
+/// Limit order resting on the book until price trades through it or it times out
+struct PendingLimitOrder {
+    signal: StrategySignal,
+    side: OrderSide,
+    limit_price: Decimal,
+    expires_at: DateTime<Utc>,
+}
+
+/// What happens to a resting limit order on a bar
+#[derive(Debug, PartialEq)]
+enum LimitFill {
+    /// The bar traded through the limit price
+    Maker(Decimal),
+    /// Timed out unfilled; the signal is executed at the bar's close
+    MarketFallback(Decimal),
+    Resting,
+}
+
+impl PendingLimitOrder {
+    fn check(&self, kline: &Kline) -> LimitFill {
+        let touched = match self.side {
+            OrderSide::Buy => kline.low <= self.limit_price,
+            OrderSide::Sell => kline.high >= self.limit_price,
+        };
+        if touched {
+            LimitFill::Maker(self.limit_price)
+        } else if kline.close_time >= self.expires_at {
+            LimitFill::MarketFallback(kline.close)
+        } else {
+            LimitFill::Resting
+        }
+    }
+}
+
+/// Side of the order a signal places, for signals that can rest as limit orders
+fn signal_side(signal_type: &StrategySignalType) -> Option<OrderSide> {
+    match signal_type {
+        StrategySignalType::Enter | StrategySignalType::AddToPosition => Some(OrderSide::Buy),
+        StrategySignalType::Exit | StrategySignalType::ReducePosition => Some(OrderSide::Sell),
+        _ => None,
+    }
+}
+
+/// Fewest per-bar returns for which a 95% VaR is reported; below this the 5% tail
+/// holds no observation
+const MIN_VAR_RETURNS: usize = 20;
+
+/// Historical 95% Value-at-Risk and Conditional VaR of a return series, as percentages.
+///
+/// VaR is the nearest-rank 5th percentile of the returns and CVaR (expected shortfall)
+/// is the mean of the returns at or below it; both are negative when the tail loses
+/// money. Returns `None` for fewer than `MIN_VAR_RETURNS` returns.
+fn historical_value_at_risk(returns: &[Decimal]) -> Option<(Decimal, Decimal)> {
+    if returns.len() < MIN_VAR_RETURNS {
+        return None;
+    }
+
+    let mut sorted = returns.to_vec();
+    sorted.sort();
+    let tail_len = (sorted.len() * 5).div_ceil(100);
+    let tail = &sorted[..tail_len];
+
+    let var = tail[tail_len - 1];
+    let cvar = tail.iter().sum::<Decimal>() / Decimal::from(tail_len);
+    Some((var * Decimal::from(100), cvar * Decimal::from(100)))
+}
+
+/// Annualized downside deviation of a return series, in percent: the root mean square
+/// of the returns with every gain counted as zero, so only losing bars add to it.
+/// `None` when no bar lost money.
+fn downside_deviation_annual_pct(returns: &[Decimal], bars_per_year: Decimal) -> Option<Decimal> {
+    if returns.is_empty() {
+        return None;
+    }
+
+    let downside_variance = returns
+        .iter()
+        .map(|r| r.min(Decimal::ZERO))
+        .map(|r| r * r)
+        .sum::<Decimal>()
+        / Decimal::from(returns.len());
+    let deviation = decimal_sqrt(downside_variance)? * decimal_sqrt(bars_per_year)? * Decimal::from(100);
+    (deviation > Decimal::ZERO).then_some(deviation)
+}
+
+/// Drawdown from the running peak at each point of the equity curve
+fn drawdown_curve(equity_curve: &[EquityPoint]) -> Vec<DrawdownPoint> {
+    let mut peak = Decimal::ZERO;
+    equity_curve
+        .iter()
+        .map(|point| {
+            peak = peak.max(point.portfolio_value);
+            let drawdown_pct = if peak > Decimal::ZERO {
+                (point.portfolio_value - peak) / peak * Decimal::from(100)
+            } else {
+                Decimal::ZERO
+            };
+            DrawdownPoint { timestamp: point.timestamp, drawdown_pct }
+        })
+        .collect()
+}
+
+/// Sum of several equity curves at every timestamp any of them has. Each curve is
+/// carried forward from its last point, or its starting value before its first.
+fn combined_equity_curve(curves: &[(Decimal, &[EquityPoint])]) -> Vec<EquityPoint> {
+    let timestamps: BTreeSet<DateTime<Utc>> = curves
+        .iter()
+        .flat_map(|(_, curve)| curve.iter().map(|point| point.timestamp))
+        .collect();
+    let mut next_points = vec![0; curves.len()];
+    let mut values: Vec<Decimal> = curves.iter().map(|(starting_value, _)| *starting_value).collect();
+
+    timestamps
+        .into_iter()
+        .map(|timestamp| {
+            for (i, (_, curve)) in curves.iter().enumerate() {
+                while let Some(point) = curve.get(next_points[i]).filter(|point| point.timestamp <= timestamp) {
+                    values[i] = point.portfolio_value;
+                    next_points[i] += 1;
+                }
+            }
+            EquityPoint { timestamp, portfolio_value: values.iter().sum() }
+        })
+        .collect()
+}
+
+/// Value of `initial_balance` held in the benchmark from the first bar both series share,
+/// at the close of every traded bar the benchmark also has. Bars are matched on open time.
+fn benchmark_curve(historical_data: &[Kline], benchmark_data: &[Kline], initial_balance: Decimal) -> Vec<EquityPoint> {
+    let benchmark_closes: HashMap<DateTime<Utc>, Decimal> = benchmark_data
+        .iter()
+        .map(|kline| (kline.open_time, kline.close))
+        .collect();
+
+    let aligned: Vec<(DateTime<Utc>, Decimal)> = historical_data
+        .iter()
+        .filter_map(|kline| benchmark_closes.get(&kline.open_time).map(|close| (kline.close_time, *close)))
+        .collect();
+
+    let Some(&(_, first_close)) = aligned.first().filter(|(_, close)| *close > Decimal::ZERO) else {
+        return Vec::new();
+    };
+
+    aligned
+        .into_iter()
+        .map(|(timestamp, close)| EquityPoint {
+            timestamp,
+            portfolio_value: initial_balance * close / first_close,
+        })
+        .collect()
+}
+
+/// Percent return over a benchmark curve
+fn benchmark_return(benchmark_curve: &[EquityPoint]) -> Option<Decimal> {
+    let first = benchmark_curve.first()?;
+    let last = benchmark_curve.last()?;
+    if first.portfolio_value.is_zero() {
+        return None;
+    }
+    Some((last.portfolio_value - first.portfolio_value) / first.portfolio_value * Decimal::from(100))
+}
+
+/// Share of the configured range spanned by `historical_data`, in percent
+fn data_coverage_pct(config: &BacktestConfig, historical_data: &[Kline]) -> Option<Decimal> {
+    let first = historical_data.first()?.open_time.max(config.start_time);
+    let last = historical_data.last()?.close_time.min(config.end_time);
+    let requested = (config.end_time - config.start_time).num_milliseconds();
+    if requested <= 0 {
+        return None;
+    }
+
+    let covered = (last - first).num_milliseconds().max(0);
+    let pct = Decimal::from(covered) * Decimal::from(100) / Decimal::from(requested);
+    Some(pct.round_dp(2).min(Decimal::from(100)))
+}
+
+/// Trades, final portfolio, open positions and per-bar equity of a finished simulation
+type SimulationOutcome = (Vec<BacktestTrade>, Portfolio, Vec<OpenPosition>, Vec<EquityPoint>);
+
+/// Where a simulation stopped
+enum SimulationEnd {
+    Finished(SimulationOutcome),
+    /// Stopped at the requested bar; resume from the checkpoint to carry on
+    Paused(Box<BacktestCheckpoint>),
+}
+
+/// Bars a run actually tested, against the range it asked for
+struct DataSpan {
+    actual_start_time: Option<DateTime<Utc>>,
+    actual_end_time: Option<DateTime<Utc>>,
+    data_coverage_pct: Option<Decimal>,
+}
+
+impl DataSpan {
+    /// Symbols listed after the requested start only return part of the range; warn
+    /// about or refuse data covering less than `min_data_coverage_pct`
+    fn check(config: &BacktestConfig, historical_data: &[Kline]) -> Result<Self, AppError> {
+        let span = Self {
+            actual_start_time: historical_data.first().map(|k| k.open_time),
+            actual_end_time: historical_data.last().map(|k| k.close_time),
+            data_coverage_pct: data_coverage_pct(config, historical_data),
+        };
+        if let (Some(coverage), Some(first), Some(last)) = (span.data_coverage_pct, span.actual_start_time, span.actual_end_time) {
+            if coverage < config.min_data_coverage_pct {
+                let message = format!(
+                    "Historical data for {} only covers {}% of the requested range ({} to {} instead of {} to {})",
+                    config.symbol, coverage, first, last, config.start_time, config.end_time
+                );
+                match config.on_short_data {
+                    ShortDataAction::Warn => warn!("{}", message),
+                    ShortDataAction::Reject => return Err(AppError::BadRequest(message)),
+                }
+            }
+        }
+        Ok(span)
+    }
+}
+
+/// Index of the first trade that differs between two runs of the same strategy
+fn first_trade_divergence(a: &[BacktestTrade], b: &[BacktestTrade]) -> Option<usize> {
+    let same = |x: &BacktestTrade, y: &BacktestTrade| {
+        x.timestamp == y.timestamp
+            && std::mem::discriminant(&x.trade_type) == std::mem::discriminant(&y.trade_type)
+            && x.price == y.price
+            && x.quantity == y.quantity
+    };
+    a.iter()
+        .zip(b)
+        .position(|(x, y)| !same(x, y))
+        .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))
+}
+
+/// Smallest `max_curve_points`: room for the first, last, lowest and highest points
+const MIN_CURVE_POINTS: usize = 4;
+
+/// Thin `curve` to at most `max_points` for the response. The first and last points,
+/// the lowest and highest values and the points at `trade_times` (as many as fit) are
+/// always kept; the rest of the budget keeps each bucket's low and high.
+fn downsample_curve<T>(
+    curve: Vec<T>,
+    max_points: usize,
+    trade_times: &[DateTime<Utc>],
+    point: impl Fn(&T) -> (DateTime<Utc>, Decimal),
+) -> Vec<T> {
+    let n = curve.len();
+    if n <= max_points {
+        return curve;
+    }
+
+    let points: Vec<(DateTime<Utc>, Decimal)> = curve.iter().map(&point).collect();
+    let extremes = |range: std::ops::Range<usize>| {
+        let lowest = range.clone().min_by_key(|&i| points[i].1).unwrap_or(range.start);
+        let highest = range.clone().max_by_key(|&i| points[i].1).unwrap_or(range.start);
+        (lowest, highest)
+    };
+
+    let (lowest, highest) = extremes(0..n);
+    let mut selected: BTreeSet<usize> = [0, n - 1, lowest, highest].into_iter().collect();
+
+    // The point each trade lands on (or the first one after it)
+    let mut trade_points: Vec<usize> = trade_times
+        .iter()
+        .map(|time| points.partition_point(|(timestamp, _)| timestamp < time))
+        .filter(|&i| i < n && !selected.contains(&i))
+        .collect();
+    trade_points.sort_unstable();
+    trade_points.dedup();
+
+    let room = max_points.saturating_sub(selected.len());
+    if trade_points.len() > room {
+        selected.extend((0..room).map(|k| trade_points[k * trade_points.len() / room]));
+    } else {
+        selected.extend(trade_points);
+        let buckets = (max_points - selected.len()) / 2;
+        if buckets > 0 {
+            let size = n.div_ceil(buckets);
+            for start in (0..n).step_by(size) {
+                let (low, high) = extremes(start..(start + size).min(n));
+                selected.insert(low);
+                selected.insert(high);
+            }
+        }
+    }
+
+    curve
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| selected.contains(i))
+        .map(|(_, item)| item)
+        .collect()
+}
+
+/// ATR period behind `BacktestMetrics::avg_atr_pct`
+const ASSET_ATR_PERIOD: usize = 14;
+
+/// Annualized volatility of the asset over the whole series, in percent
+fn asset_volatility_annual_pct(historical_data: &[Kline]) -> Option<Decimal> {
+    realized_volatility(historical_data, historical_data.len().checked_sub(1)?)
+}
+
+/// Mean of the rolling `period`-bar ATR as a percentage of each bar's close, over every
+/// bar with a full ATR window
+fn average_atr_pct(historical_data: &[Kline], period: usize) -> Option<Decimal> {
+    let atr_pcts: Vec<Decimal> = historical_data
+        .windows(period + 1)
+        .filter(|window| !window[period].close.is_zero())
+        .filter_map(|window| atr(window, period).map(|atr| atr / window[period].close * Decimal::from(100)))
+        .collect();
+    if atr_pcts.is_empty() {
+        return None;
+    }
+
+    Some(atr_pcts.iter().sum::<Decimal>() / Decimal::from(atr_pcts.len()))
+}
+
+/// Bars per year used to annualize per-bar volatility, unless the config sets its own.
+/// Stocks trade about 252 days a year; crypto trades around the clock.
+fn periods_per_year(config: &BacktestConfig) -> f64 {
+    if let Some(periods) = config.periods_per_year {
+        return periods as f64;
+    }
+    match (config.asset_type.as_str(), &config.interval) {
+        ("stock", KlineInterval::OneDay) => 252.0,
+        _ => config.interval.periods_per_year(),
+    }
+}
+
+/// Bars simulated between checkpoints unless `BACKTEST_CHECKPOINT_BARS` says otherwise
+const DEFAULT_CHECKPOINT_BARS: usize = 1000;
+
+/// Most assets one portfolio backtest may hold
+const MAX_PORTFOLIO_ASSETS: usize = 10;
+
+/// Most strategies one comparison may run
+const MAX_COMPARED_STRATEGIES: usize = 10;
+
+/// `backtest_results` row a run saves its progress to
+#[derive(Clone)]
+struct CheckpointTarget {
+    db: Arc<DatabaseConnection>,
+    result_id: Uuid,
+    every_bars: usize,
+}
+
+impl CheckpointTarget {
+    async fn find_result(&self) -> Result<backtest_result::Model, AppError> {
+        backtest_result::Entity::find_by_id(self.result_id)
+            .one(self.db.as_ref())
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Backtest result not found".to_string()))
+    }
+
+    async fn save(&self, checkpoint: &BacktestCheckpoint) -> Result<(), AppError> {
+        let mut active_model: backtest_result::ActiveModel = self.find_result().await?.into();
+        active_model.checkpoint_json = Set(Some(serde_json::to_value(checkpoint)?));
+        active_model.updated_at = Set(Utc::now());
+        active_model.update(self.db.as_ref()).await.map_err(AppError::DatabaseError)?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<BacktestCheckpoint, AppError> {
+        let checkpoint_json = self.find_result().await?.checkpoint_json.ok_or_else(|| {
+            AppError::BadRequest("Backtest has no checkpoint to resume from".to_string())
+        })?;
+        serde_json::from_value(checkpoint_json)
+            .map_err(|e| AppError::BadRequest(format!("Invalid backtest checkpoint: {}", e)))
+    }
+}
+
+/// Where a simulation saves its progress, and the checkpoint it picks up from, if any
+struct Checkpointing<'a> {
+    target: &'a CheckpointTarget,
+    resume_from: Option<BacktestCheckpoint>,
+}
+
+/// Backtesting engine with integrated caching and optimization
+pub struct BacktestEngine {
+    binance_fetcher: Arc<BinanceFetcher>,
+    stock_fetcher: Option<Arc<StockFetcher>>,
+    checkpoints: Option<CheckpointTarget>,
+}
+
+impl BacktestEngine {
+    /// Create a new backtest engine
+    pub fn new() -> Self {
+        Self {
+            binance_fetcher: Arc::new(BinanceFetcher::new()),
+            stock_fetcher: None,
+            checkpoints: None,
+        }
+    }
+
+    /// Create a new backtest engine with stock support
+    pub fn new_with_stock_support(alpha_vantage_api_key: String) -> Self {
+        Self {
+            binance_fetcher: Arc::new(BinanceFetcher::new()),
+            stock_fetcher: Some(Arc::new(StockFetcher::new(alpha_vantage_api_key))),
+            checkpoints: None,
+        }
+    }
+
+    /// Save the simulation's progress to the `backtest_results` row `result_id` every
+    /// `BACKTEST_CHECKPOINT_BARS` bars (default 1000), so `resume_backtest` can finish a
+    /// run cut short by a restart
+    pub fn with_checkpoints(mut self, db: Arc<DatabaseConnection>, result_id: Uuid) -> Self {
+        let every_bars = std::env::var("BACKTEST_CHECKPOINT_BARS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|bars| *bars > 0)
+            .unwrap_or(DEFAULT_CHECKPOINT_BARS);
+        self.checkpoints = Some(CheckpointTarget { db, result_id, every_bars });
+        self
+    }
+
+    /// Carry on the run saved to `result_id` from its last checkpoint, refetching the
+    /// data. Needs `with_checkpoints` for the database; the resumed run keeps saving
+    /// checkpoints to `result_id`.
+    pub async fn resume_backtest(&self, result_id: Uuid) -> Result<BacktestResult, AppError> {
+        let target = self.checkpoint_target(result_id)?;
+        let checkpoint = target.load().await?;
+        let config = checkpoint.config.clone();
+        info!("Resuming backtest {} at bar {}", result_id, checkpoint.next_index);
+
+        let historical_data = self.fetch_historical_data(&config).await?;
+        let benchmark_data = self.fetch_benchmark_data(&config).await?;
+        let checkpointing = Checkpointing { target: &target, resume_from: Some(checkpoint) };
+        self.simulate(config, &historical_data, benchmark_data.as_deref(), Instant::now(), Some(checkpointing)).await
+    }
+
+    /// `resume_backtest` on already-fetched data, benchmarked against the traded symbol
+    pub async fn resume_backtest_with_data(
+        &self,
+        result_id: Uuid,
+        historical_data: &[Kline],
+    ) -> Result<BacktestResult, AppError> {
+        let target = self.checkpoint_target(result_id)?;
+        let checkpoint = target.load().await?;
+        let config = checkpoint.config.clone();
+        let checkpointing = Checkpointing { target: &target, resume_from: Some(checkpoint) };
+        self.simulate(config, historical_data, None, Instant::now(), Some(checkpointing)).await
+    }
+
+    fn checkpoint_target(&self, result_id: Uuid) -> Result<CheckpointTarget, AppError> {
+        let target = self.checkpoints.as_ref().ok_or_else(|| {
+            AppError::BadRequest("Backtest checkpoints are not enabled".to_string())
+        })?;
+        Ok(CheckpointTarget { result_id, ..target.clone() })
+    }
+
+    /// Run a backtest with the given configuration
+    pub async fn run_backtest(
+        &self,
+        config: BacktestConfig,
+    ) -> Result<BacktestResult, AppError> {
+        let start_time = Instant::now();
+
+        info!(
+            "Starting backtest for {} on {} from {} to {}",
+            config.strategy_name, config.symbol, config.start_time, config.end_time
+        );
+
+        // Validate inputs
+        self.validate_config(&config)?;
+
+        let historical_data = self.fetch_historical_data(&config).await?;
+        let benchmark_data = self.fetch_benchmark_data(&config).await?;
+
+        debug!("Fetched {} klines for backtesting", historical_data.len());
+
+        let checkpointing = self.checkpoints.as_ref().map(|target| Checkpointing { target, resume_from: None });
+        self.simulate(config, &historical_data, benchmark_data.as_deref(), start_time, checkpointing).await
+    }
+
+    /// Run a backtest on already-fetched data, e.g. to reuse one dataset across runs.
+    /// The run is benchmarked against the traded symbol.
+    pub async fn run_backtest_with_data(
+        &self,
+        config: BacktestConfig,
+        historical_data: &[Kline],
+    ) -> Result<BacktestResult, AppError> {
+        self.run_backtest_with_benchmark_data(config, historical_data, None).await
+    }
+
+    /// Run a backtest on already-fetched data, benchmarked against `benchmark_data`
+    /// (or the traded symbol when `None`)
+    pub async fn run_backtest_with_benchmark_data(
+        &self,
+        config: BacktestConfig,
+        historical_data: &[Kline],
+        benchmark_data: Option<&[Kline]>,
+    ) -> Result<BacktestResult, AppError> {
+        let start_time = Instant::now();
+        self.validate_config(&config)?;
+
+        if historical_data.is_empty() {
+            return Err(AppError::BadRequest(
+                "No historical data available for the given period".to_string(),
+            ));
+        }
+
+        let checkpointing = self.checkpoints.as_ref().map(|target| Checkpointing { target, resume_from: None });
+        self.simulate(config, historical_data, benchmark_data, start_time, checkpointing).await
+    }
+
+    /// Simulate the configured strategy over `historical_data` and build the result
+    async fn simulate(
+        &self,
+        config: BacktestConfig,
+        historical_data: &[Kline],
+        benchmark_data: Option<&[Kline]>,
+        start_time: Instant,
+        checkpointing: Option<Checkpointing<'_>>,
+    ) -> Result<BacktestResult, AppError> {
+        let span = DataSpan::check(&config, historical_data)?;
+
+        // Create strategy instance
+        let mut strategy = create_strategy(&config.strategy_name)?;
+
+        // Run the backtest simulation
+        let simulation = self.run_simulation_from(
+            historical_data,
+            &mut *strategy,
+            config.initial_balance,
+            &config,
+            checkpointing,
+        ).await;
+        let simulation = match simulation {
+            Ok(outcome) if config.strict_lookahead => self
+                .check_lookahead(historical_data, &config, &outcome.0)
+                .await
+                .map(|_| outcome),
+            other => other,
+        };
+        let outcome = match simulation {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                metrics().record_backtest(false, start_time.elapsed().as_secs_f64());
+                return Err(e);
+            }
+        };
+
+        metrics().record_backtest(true, start_time.elapsed().as_secs_f64());
+        Ok(self.build_result(config, historical_data, benchmark_data, outcome, span, start_time))
+    }
+
+    /// Metrics, curves and robustness score of a finished simulation
+    fn build_result(
+        &self,
+        config: BacktestConfig,
+        historical_data: &[Kline],
+        benchmark_data: Option<&[Kline]>,
+        (trades, portfolio, open_positions, equity_curve): SimulationOutcome,
+        span: DataSpan,
+        start_time: Instant,
+    ) -> BacktestResult {
+        // Calculate comprehensive metrics
+        let mut backtest_metrics = self.calculate_metrics(
+            &trades,
+            &portfolio,
+            historical_data,
+            &equity_curve,
+            &config,
+        );
+
+        // Buy & hold of the benchmark over the bars both series share
+        let benchmark_curve = benchmark_curve(
+            historical_data,
+            benchmark_data.unwrap_or(historical_data),
+            config.initial_balance,
+        );
+        backtest_metrics.benchmark_return = benchmark_return(&benchmark_curve);
+
+        let execution_time = start_time.elapsed().as_millis() as u64;
+
+        info!(
+            "Backtest completed in {}ms. Final portfolio value: {} ({:+.2}%)",
+            execution_time,
+            portfolio.total_value,
+            backtest_metrics.total_return_percentage
+        );
+
+        let performance_chart = self.generate_performance_chart(&trades, historical_data);
+
+        // Metrics above use every bar; only the returned curves are thinned
+        let mut drawdown_curve = drawdown_curve(&equity_curve);
+        let mut benchmark_curve = benchmark_curve;
+        if let Some(max_points) = config.max_curve_points {
+            let trade_times: Vec<DateTime<Utc>> = trades.iter().map(|t| t.timestamp).collect();
+            drawdown_curve = downsample_curve(drawdown_curve, max_points, &trade_times, |p| (p.timestamp, p.drawdown_pct));
+            benchmark_curve = downsample_curve(benchmark_curve, max_points, &trade_times, |p| (p.timestamp, p.portfolio_value));
+        }
+
+        let robustness_score = RobustnessScore::from_metrics(&backtest_metrics, &trades);
+
+        BacktestResult {
+            config,
+            trades,
+            metrics: backtest_metrics,
+            robustness_score,
+            performance_chart,
+            drawdown_curve,
+            benchmark_curve,
+            actual_start_time: span.actual_start_time,
+            actual_end_time: span.actual_end_time,
+            data_coverage_pct: span.data_coverage_pct,
+            execution_time_ms: execution_time,
+            open_positions,
+            valuation: None,
+        }
+    }
+
+    /// Strict runs hide every bar after the first from `initialize`, the only place the
+    /// engine hands a strategy more than the bars up to the one it is analyzing. Re-run
+    /// with the whole series visible there and fail if the trades change, which means
+    /// the strategy kept that data and read ahead of the current bar.
+    async fn check_lookahead(
+        &self,
+        historical_data: &[Kline],
+        config: &BacktestConfig,
+        trades: &[BacktestTrade],
+    ) -> Result<(), AppError> {
+        let full_view = BacktestConfig { strict_lookahead: false, ..config.clone() };
+        let mut strategy = create_strategy(&config.strategy_name)?;
+        let (reference, _, _, _) = self.run_simulation(
+            historical_data,
+            &mut *strategy,
+            full_view.initial_balance,
+            &full_view,
+        ).await?;
+
+        match first_trade_divergence(trades, &reference) {
+            Some(index) => {
+                let at = trades.get(index).or_else(|| reference.get(index)).map(|t| t.timestamp);
+                Err(AppError::BadRequest(format!(
+                    "Look-ahead bias: strategy {} trades differently when it can see bars after the one it is analyzing (first difference at {})",
+                    config.strategy_name,
+                    at.map(|t| t.to_rfc3339()).unwrap_or_default()
+                )))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Run several strategies over the same range, fetching the data only once
+    pub async fn compare_strategies(
+        &self,
+        base_config: BacktestConfig,
+        strategies: Vec<StrategyComparisonEntry>,
+    ) -> Result<StrategyComparison, AppError> {
+        self.validate_config(&base_config)?;
+        Self::validate_comparison(&strategies)?;
+        let historical_data = self.fetch_historical_data(&base_config).await?;
+        self.compare_strategies_with_data(base_config, strategies, &historical_data).await
+    }
+
+    /// Run several strategies over already-fetched data so their equity curves line up bar for bar
+    pub async fn compare_strategies_with_data(
+        &self,
+        base_config: BacktestConfig,
+        strategies: Vec<StrategyComparisonEntry>,
+        historical_data: &[Kline],
+    ) -> Result<StrategyComparison, AppError> {
+        let start_time = Instant::now();
+
+        Self::validate_comparison(&strategies)?;
+
+        if historical_data.is_empty() {
+            return Err(AppError::BadRequest(
+                "No historical data available for the given period".to_string(),
+            ));
+        }
+
+        info!(
+            "Comparing {} strategies on {} ({} klines)",
+            strategies.len(), base_config.symbol, historical_data.len()
+        );
+
+        let mut results = Vec::with_capacity(strategies.len());
+        for entry in strategies {
+            let config = Self::config_for_strategy(&base_config, &entry);
+            let mut strategy = create_strategy(&config.strategy_name)?;
+
+            let (trades, portfolio, _, equity_curve) = self.run_simulation(
+                historical_data,
+                &mut *strategy,
+                config.initial_balance,
+                &config,
+            ).await?;
+
+            let metrics = self.calculate_metrics(&trades, &portfolio, historical_data, &equity_curve, &config);
+
+            results.push(StrategyComparisonResult {
+                strategy_name: config.strategy_name,
+                parameters: config.strategy_parameters,
+                equity_curve,
+                metrics,
+            });
+        }
+
+        Ok(StrategyComparison {
+            symbol: base_config.symbol,
+            interval: base_config.interval,
+            start_time: base_config.start_time,
+            end_time: base_config.end_time,
+            data_points: historical_data.len(),
+            results,
+            execution_time_ms: start_time.elapsed().as_millis() as u64,
+            valuation: None,
+        })
+    }
+
+    fn validate_comparison(strategies: &[StrategyComparisonEntry]) -> Result<(), AppError> {
+        if strategies.is_empty() {
+            return Err(AppError::BadRequest(
+                "At least one strategy is required for a comparison".to_string(),
+            ));
+        }
+        if strategies.len() > MAX_COMPARED_STRATEGIES {
+            return Err(AppError::BadRequest(format!(
+                "Comparisons are limited to {} strategies",
+                MAX_COMPARED_STRATEGIES
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rerun a backtest across a grid of fee and slippage levels, fetching the data only once
+    pub async fn run_sensitivity(
+        &self,
+        config: BacktestConfig,
+        fee_levels: Vec<Decimal>,
+        slippage_levels: Vec<Decimal>,
+    ) -> Result<SensitivityReport, AppError> {
+        self.validate_config(&config)?;
+        let historical_data = self.fetch_historical_data(&config).await?;
+        self.run_sensitivity_with_data(config, fee_levels, slippage_levels, &historical_data).await
+    }
+
+    /// Rerun a backtest on already-fetched data for every fee/slippage combination
+    pub async fn run_sensitivity_with_data(
+        &self,
+        config: BacktestConfig,
+        fee_levels: Vec<Decimal>,
+        slippage_levels: Vec<Decimal>,
+        historical_data: &[Kline],
+    ) -> Result<SensitivityReport, AppError> {
+        let start_time = Instant::now();
+
+        if fee_levels.is_empty() || slippage_levels.is_empty() {
+            return Err(AppError::BadRequest(
+                "At least one fee level and one slippage level are required".to_string(),
+            ));
+        }
+
+        let max_cells = 100;
+        if fee_levels.len() * slippage_levels.len() > max_cells {
+            return Err(AppError::BadRequest(format!(
+                "Sensitivity grid too large. Maximum {} fee/slippage combinations allowed",
+                max_cells
+            )));
+        }
+
+        if historical_data.is_empty() {
+            return Err(AppError::BadRequest(
+                "No historical data available for the given period".to_string(),
+            ));
+        }
+
+        let mut matrix = Vec::with_capacity(fee_levels.len());
+        for fee in &fee_levels {
+            let mut row = Vec::with_capacity(slippage_levels.len());
+            for slippage in &slippage_levels {
+                let cell_config = BacktestConfig {
+                    fee_percentage: *fee,
+                    // A tier or per-side rate would override the swept fee
+                    fee_tier: None,
+                    maker_fee_percentage: None,
+                    taker_fee_percentage: None,
+                    slippage_percentage: *slippage,
+                    ..config.clone()
+                };
+                self.validate_config(&cell_config)?;
+
+                let mut strategy = create_strategy(&cell_config.strategy_name)?;
+                let (trades, portfolio, _, equity_curve) = self.run_simulation(
+                    historical_data,
+                    &mut *strategy,
+                    cell_config.initial_balance,
+                    &cell_config,
+                ).await?;
+                let metrics = self.calculate_metrics(&trades, &portfolio, historical_data, &equity_curve, &cell_config);
+
+                row.push(SensitivityCell {
+                    fee_percentage: *fee,
+                    slippage_percentage: *slippage,
+                    total_return_percentage: metrics.total_return_percentage,
+                    sharpe_ratio: metrics.sharpe_ratio,
+                    profitable: metrics.total_return_percentage > Decimal::ZERO,
+                });
+            }
+            matrix.push(row);
+        }
+
+        let break_even = matrix
+            .iter()
+            .flatten()
+            .filter(|cell| !cell.profitable)
+            .min_by_key(|cell| (cell.fee_percentage + cell.slippage_percentage, cell.fee_percentage))
+            .cloned();
+
+        Ok(SensitivityReport {
+            fee_levels,
+            slippage_levels,
+            matrix,
+            break_even,
+            data_points: historical_data.len(),
+            execution_time_ms: start_time.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Backtest several assets out of one cash pool holding the sum of their
+    /// `initial_balance`s, each asset trading with its own config
+    pub async fn run_portfolio_backtest(
+        &self,
+        configs: Vec<BacktestConfig>,
+        rebalance: RebalancePolicy,
+    ) -> Result<PortfolioBacktestResult, AppError> {
+        self.validate_portfolio(&configs, rebalance)?;
+
+        let mut historical_data = Vec::with_capacity(configs.len());
+        for config in &configs {
+            historical_data.push(self.fetch_historical_data(config).await?);
+        }
+
+        self.simulate_portfolio(configs, &historical_data, rebalance).await
+    }
+
+    /// `run_portfolio_backtest` on already-fetched data, one series per config
+    pub async fn run_portfolio_backtest_with_data(
+        &self,
+        configs: Vec<BacktestConfig>,
+        historical_data: &[Vec<Kline>],
+        rebalance: RebalancePolicy,
+    ) -> Result<PortfolioBacktestResult, AppError> {
+        self.validate_portfolio(&configs, rebalance)?;
+        if historical_data.len() != configs.len() {
+            return Err(AppError::BadRequest(format!(
+                "Expected historical data for {} assets, got {}",
+                configs.len(),
+                historical_data.len()
+            )));
+        }
+
+        self.simulate_portfolio(configs, historical_data, rebalance).await
+    }
+
+    fn validate_portfolio(&self, configs: &[BacktestConfig], rebalance: RebalancePolicy) -> Result<(), AppError> {
+        if configs.is_empty() {
+            return Err(AppError::BadRequest(
+                "At least one asset is required for a portfolio backtest".to_string(),
+            ));
+        }
+        if configs.len() > MAX_PORTFOLIO_ASSETS {
+            return Err(AppError::BadRequest(format!(
+                "Portfolio backtests are limited to {} assets",
+                MAX_PORTFOLIO_ASSETS
+            )));
+        }
+
+        let mut symbols = BTreeSet::new();
+        for config in configs {
+            self.validate_config(config)?;
+            if !symbols.insert(config.symbol.to_uppercase()) {
+                return Err(AppError::BadRequest(format!(
+                    "{} appears more than once in the portfolio",
+                    config.symbol
+                )));
+            }
+            // Outside contributions would top up the shared pool without limit
+            if config.dca_mode() == DcaMode::ExternalContribution {
+                return Err(AppError::BadRequest(format!(
+                    "{} funds its buys from outside contributions; set dca_mode to fixed_capital to backtest it in a portfolio",
+                    config.symbol
+                )));
+            }
+        }
+
+        if let RebalancePolicy::Periodic { every_bars } = rebalance {
+            if every_bars == 0 {
+                return Err(AppError::BadRequest(
+                    "Rebalancing needs an interval of at least one bar".to_string(),
+                ));
+            }
+            // The check reruns each asset on its own, where rebalanced cash never arrives
+            if configs.iter().any(|config| config.strict_lookahead) {
+                return Err(AppError::BadRequest(
+                    "Look-ahead checks can't be combined with rebalancing".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn simulate_portfolio(
+        &self,
+        configs: Vec<BacktestConfig>,
+        historical_data: &[Vec<Kline>],
+        rebalance: RebalancePolicy,
+    ) -> Result<PortfolioBacktestResult, AppError> {
+        let start_time = Instant::now();
+        let result = self.run_portfolio(configs, historical_data, rebalance, start_time).await;
+        metrics().record_backtest(result.is_ok(), start_time.elapsed().as_secs_f64());
+        result
+    }
+
+    /// Simulate every asset side by side. Assets only trade on the bars they have, so one
+    /// listed later or missing bars simply sits those out; between rebalances each asset
+    /// runs exactly as it would on its own.
+    async fn run_portfolio(
+        &self,
+        configs: Vec<BacktestConfig>,
+        historical_data: &[Vec<Kline>],
+        rebalance: RebalancePolicy,
+        start_time: Instant,
+    ) -> Result<PortfolioBacktestResult, AppError> {
+        let mut spans = Vec::with_capacity(configs.len());
+        for (config, data) in configs.iter().zip(historical_data) {
+            if data.is_empty() {
+                return Err(AppError::BadRequest(format!(
+                    "No historical data available for {} in the given period",
+                    config.symbol
+                )));
+            }
+            spans.push(DataSpan::check(config, data)?);
+        }
+
+        info!(
+            "Starting portfolio backtest of {} ({:?})",
+            configs.iter().map(|config| config.symbol.as_str()).collect::<Vec<_>>().join(", "),
+            rebalance
+        );
+
+        // Rebalances fall on every `every_bars`th bar close of any asset, short of the last
+        let timeline: Vec<DateTime<Utc>> = historical_data
+            .iter()
+            .flatten()
+            .map(|kline| kline.close_time)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let rebalance_times: Vec<DateTime<Utc>> = match rebalance {
+            RebalancePolicy::None => Vec::new(),
+            RebalancePolicy::Periodic { every_bars } => timeline
+                .iter()
+                .skip(every_bars - 1)
+                .step_by(every_bars)
+                .filter(|time| Some(*time) != timeline.last())
+                .copied()
+                .collect(),
+        };
+
+        // `None` until an asset's first bar has been simulated
+        let mut progress: Vec<Option<SimulationEnd>> = configs.iter().map(|_| None).collect();
+        let mut net_transfers = vec![Decimal::ZERO; configs.len()];
+        let mut rebalances = 0;
+
+        for rebalance_time in &rebalance_times {
+            let mut participants = Vec::new();
+            for (i, (config, data)) in configs.iter().zip(historical_data).enumerate() {
+                let bars_done = data.partition_point(|kline| kline.close_time <= *rebalance_time);
+                if bars_done == 0 {
+                    // Not listed yet; it keeps its starting balance
+                    continue;
+                }
+
+                progress[i] = match progress[i].take() {
+                    Some(SimulationEnd::Paused(checkpoint)) if checkpoint.next_index < bars_done => {
+                        Some(self.run_portfolio_asset(config, data, Some(*checkpoint), Some(bars_done)).await?)
+                    }
+                    None => Some(self.run_portfolio_asset(config, data, None, Some(bars_done)).await?),
+                    already_there => already_there,
+                };
+                // An asset held past the rebalance by a resting limit order sits it out
+                if matches!(&progress[i], Some(SimulationEnd::Paused(checkpoint)) if checkpoint.next_index == bars_done) {
+                    participants.push(i);
+                }
+            }
+
+            let cash_of = |end: &Option<SimulationEnd>| match end {
+                Some(SimulationEnd::Paused(checkpoint)) => checkpoint.portfolio.cash_balance,
+                _ => Decimal::ZERO,
+            };
+            let mut unallocated: Decimal = participants.iter().map(|&i| cash_of(&progress[i])).sum();
+            let mut unweighted: Decimal = participants.iter().map(|&i| configs[i].initial_balance).sum();
+            let mut moved = false;
+            for &i in &participants {
+                // The last participant takes what is left, so no cash is lost to rounding
+                let cash = if unweighted == configs[i].initial_balance {
+                    unallocated
+                } else {
+                    unallocated * configs[i].initial_balance / unweighted
+                };
+                unallocated -= cash;
+                unweighted -= configs[i].initial_balance;
+
+                if let Some(SimulationEnd::Paused(checkpoint)) = &mut progress[i] {
+                    let transfer = cash - checkpoint.portfolio.cash_balance;
+                    checkpoint.portfolio.cash_balance = cash;
+                    net_transfers[i] += transfer;
+                    moved |= !transfer.is_zero();
+                }
+            }
+            if moved {
+                rebalances += 1;
+                trade_debug!(configs[participants[0]], "Rebalanced the cash of {} assets at {}", participants.len(), rebalance_time);
+            }
+        }
 
-/// Backtesting engine with integrated caching and optimization
-pub struct BacktestEngine {
-    binance_fetcher: Arc<BinanceFetcher>,
-    stock_fetcher: Option<Arc<StockFetcher>>,
-}
+        let starting_values: Vec<Decimal> = configs
+            .iter()
+            .map(|config| {
+                config.initial_balance
+                    + config.initial_position().map(|p| p.quantity * p.average_cost).unwrap_or(Decimal::ZERO)
+            })
+            .collect();
+        let initial_balance: Decimal = starting_values.iter().sum();
+
+        let mut assets = Vec::with_capacity(configs.len());
+        for (i, ((config, data), span)) in configs.into_iter().zip(historical_data).zip(spans).enumerate() {
+            let outcome = match progress[i].take() {
+                Some(SimulationEnd::Finished(outcome)) => outcome,
+                unfinished => {
+                    let resume_from = match unfinished {
+                        Some(SimulationEnd::Paused(checkpoint)) => Some(*checkpoint),
+                        _ => None,
+                    };
+                    match self.run_portfolio_asset(&config, data, resume_from, None).await? {
+                        SimulationEnd::Finished(outcome) => outcome,
+                        SimulationEnd::Paused(_) => unreachable!("a simulation without a pause point runs to the end"),
+                    }
+                }
+            };
+            if config.strict_lookahead {
+                self.check_lookahead(data, &config, &outcome.0).await?;
+            }
 
-impl BacktestEngine {
-    /// Create a new backtest engine
-    pub fn new() -> Self {
-        Self {
-            binance_fetcher: Arc::new(BinanceFetcher::new()),
-            stock_fetcher: None,
+            let equity_curve = outcome.3.clone();
+            let result = self.build_result(config, data, None, outcome, span, start_time);
+            let pnl = result.metrics.final_portfolio_value - starting_values[i] - net_transfers[i];
+            assets.push(PortfolioAssetResult {
+                symbol: result.config.symbol.clone(),
+                weight_pct: starting_values[i] / initial_balance * Decimal::from(100),
+                net_transfers: net_transfers[i],
+                pnl,
+                contribution_pct: pnl / initial_balance * Decimal::from(100),
+                equity_curve,
+                result,
+            });
         }
+
+        let curves: Vec<(Decimal, &[EquityPoint])> = assets
+            .iter()
+            .zip(&starting_values)
+            .map(|(asset, starting_value)| (*starting_value, asset.equity_curve.as_slice()))
+            .collect();
+        let equity_curve = combined_equity_curve(&curves);
+        let max_drawdown = drawdown_curve(&equity_curve)
+            .iter()
+            .map(|point| -point.drawdown_pct)
+            .max()
+            .unwrap_or(Decimal::ZERO);
+
+        let final_portfolio_value: Decimal = assets.iter().map(|asset| asset.result.metrics.final_portfolio_value).sum();
+        let total_return = final_portfolio_value - initial_balance;
+        let execution_time_ms = start_time.elapsed().as_millis() as u64;
+
+        info!(
+            "Portfolio backtest completed in {}ms. Final value: {} ({:+.2}%)",
+            execution_time_ms,
+            final_portfolio_value,
+            total_return / initial_balance * Decimal::from(100)
+        );
+
+        Ok(PortfolioBacktestResult {
+            rebalance,
+            rebalances,
+            initial_balance,
+            final_portfolio_value,
+            total_return,
+            total_return_percentage: total_return / initial_balance * Decimal::from(100),
+            max_drawdown,
+            equity_curve,
+            assets,
+            execution_time_ms,
+        })
     }
 
-    /// Create a new backtest engine with stock support
-    pub fn new_with_stock_support(alpha_vantage_api_key: String) -> Self {
-        Self {
-            binance_fetcher: Arc::new(BinanceFetcher::new()),
-            stock_fetcher: Some(Arc::new(StockFetcher::new(alpha_vantage_api_key))),
+    /// Simulate one portfolio asset with a fresh strategy, from its first bar or a checkpoint
+    async fn run_portfolio_asset(
+        &self,
+        config: &BacktestConfig,
+        historical_data: &[Kline],
+        resume_from: Option<BacktestCheckpoint>,
+        pause_after: Option<usize>,
+    ) -> Result<SimulationEnd, AppError> {
+        let mut strategy = create_strategy(&config.strategy_name)?;
+        self.run_simulation_until(
+            historical_data,
+            &mut *strategy,
+            config.initial_balance,
+            config,
+            None,
+            resume_from,
+            pause_after,
+        ).await
+    }
+
+    /// Derive the config for one comparison entry from the shared base config
+    fn config_for_strategy(base_config: &BacktestConfig, entry: &StrategyComparisonEntry) -> BacktestConfig {
+        let parameters = entry.parameters.clone().unwrap_or_else(|| serde_json::json!({}));
+        let is_dca = entry.strategy_name.contains("dca");
+
+        let strategy_type = if is_dca {
+            parameters.get("strategy_type")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        } else {
+            None
+        };
+
+        BacktestConfig {
+            strategy_name: entry.strategy_name.clone(),
+            strategy_type,
+            strategy_parameters: parameters,
+            unlimited_capital: is_dca,
+            ..base_config.clone()
         }
     }
 
-    /// Run a backtest with the given configuration
-    pub async fn run_backtest(
+    /// Run the strategy over the configured range without executing trades and
+    /// report its decision (and explanation) for the final bar
+    pub async fn preview_strategy(
         &self,
         config: BacktestConfig,
-    ) -> Result<BacktestResult, AppError> {
-        let start_time = Instant::now();
-
-        info!(
-            "Starting backtest for {} on {} from {} to {}",
-            config.strategy_name, config.symbol, config.start_time, config.end_time
-        );
-
-        // Validate inputs
+    ) -> Result<StrategyPreview, AppError> {
         self.validate_config(&config)?;
 
-        // Create strategy instance
         let mut strategy = create_strategy(&config.strategy_name)?;
+        let historical_data = self.fetch_historical_data(&config).await?;
+        let strategy_data = if config.use_heikin_ashi {
+            to_heikin_ashi(&historical_data)
+        } else {
+            historical_data.clone()
+        };
+
+        let strategy_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let build_context = |index: usize| StrategyContext {
+            strategy_id,
+            user_id,
+            symbol: config.symbol.clone(),
+            interval: config.interval.to_string(),
+            mode: StrategyMode::Backtest,
+            current_time: historical_data[index].close_time,
+            historical_data: strategy_data[..=index].to_vec(),
+            current_price: historical_data[index].close,
+            available_balance: config.initial_balance,
+            current_positions: Vec::new(),
+            market_data: MarketData::default(),
+        };
+
+        strategy.initialize(&config.strategy_parameters, StrategyMode::Backtest, &build_context(0)).await?;
+
+        let mut last_signal = None;
+        for index in 0..historical_data.len() {
+            last_signal = strategy.analyze(&build_context(index)).await?;
+        }
+
+        let last_kline = historical_data.last().unwrap();
+        Ok(StrategyPreview {
+            timestamp: last_kline.close_time,
+            price: last_kline.close,
+            signal: last_signal,
+            explanation: strategy.explain(),
+        })
+    }
+
+    /// Fetch historical data for the configured asset type
+    async fn fetch_historical_data(&self, config: &BacktestConfig) -> Result<Vec<Kline>, AppError> {
+        let historical_data = self.fetch_klines(&config.symbol, &config.asset_type, config).await?;
+
+        if historical_data.is_empty() {
+            return Err(AppError::BadRequest(
+                "No historical data available for the given period".to_string(),
+            ));
+        }
+
+        Ok(historical_data)
+    }
+
+    /// Klines for the configured benchmark, when it is not the traded symbol
+    async fn fetch_benchmark_data(&self, config: &BacktestConfig) -> Result<Option<Vec<Kline>>, AppError> {
+        let Some((symbol, asset_type)) = config.separate_benchmark() else {
+            return Ok(None);
+        };
+
+        let benchmark_data = self.fetch_klines(symbol, asset_type, config).await?;
+        if benchmark_data.is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "No historical data available for benchmark {} in the given period",
+                symbol
+            )));
+        }
+
+        Ok(Some(benchmark_data))
+    }
 
-        // Fetch historical data based on asset type
-        let historical_data = match config.asset_type.as_str() {
+    /// Fetch `symbol` over the configured range and interval from the source for its asset type
+    async fn fetch_klines(&self, symbol: &str, asset_type: &str, config: &BacktestConfig) -> Result<Vec<Kline>, AppError> {
+        match asset_type {
             "stock" => {
                 let fetcher = self.stock_fetcher.as_ref()
                     .ok_or_else(|| AppError::InternalServerError)?;
                 fetcher.fetch_klines(
-                    &config.symbol,
+                    symbol,
                     &config.interval,
                     config.start_time,
                     config.end_time,
-                ).await?
+                ).await
             }
             "crypto" | _ => {
                 self.binance_fetcher.fetch_klines(
-                    &config.symbol,
+                    symbol,
                     &config.interval,
                     config.start_time,
                     config.end_time,
-                ).await?
+                ).await
             }
-        };
-
-        if historical_data.is_empty() {
-            return Err(AppError::BadRequest(
-                "No historical data available for the given period".to_string(),
-            ));
         }
-
-        debug!("Fetched {} klines for backtesting", historical_data.len());
-
-        // Run the backtest simulation
-        let (trades, portfolio, open_positions) = self.run_simulation(
-            &historical_data,
-            &mut *strategy,
-            config.initial_balance,
-            &config,
-        ).await?;
-
-        // Calculate comprehensive metrics
-        let metrics = self.calculate_metrics(
-            &trades,
-            &portfolio,
-            &historical_data,
-            &config,
-        );
-
-        let execution_time = start_time.elapsed().as_millis() as u64;
-
-        info!(
-            "Backtest completed in {}ms. Final portfolio value: {} ({:+.2}%)",
-            execution_time,
-            portfolio.total_value,
-            metrics.total_return_percentage
-        );
-
-        let performance_chart = self.generate_performance_chart(&trades, &historical_data);
-
-        Ok(BacktestResult {
-            config,
-            trades,
-            metrics,
-            performance_chart,
-            execution_time_ms: execution_time,
-            open_positions,
-        })
     }
 
     /// Validate backtest configuration
     fn validate_config(&self, config: &BacktestConfig) -> Result<(), AppError> {
-        // Validate symbol
-        BinanceFetcher::validate_symbol(&config.symbol)?;
+        // Validate symbol, which may name the pair canonically (e.g. BTC/USDT)
+        BinanceFetcher::validate_symbol(&BinanceFetcher::native_symbol(&config.symbol)?)?;
+        if let Some((benchmark_symbol, _)) = config.separate_benchmark() {
+            BinanceFetcher::validate_symbol(&BinanceFetcher::native_symbol(benchmark_symbol)?)?;
+        }
 
         // Validate time range
         if config.start_time >= config.end_time {
@@ -145,7 +1309,7 @@ impl BacktestEngine {
         }
 
         // Warn if using very long timeframes with small intervals
-        if days_diff > 730 && config.interval.as_str() == "1h" {
+        if days_diff > 730 && config.interval == KlineInterval::OneHour {
             warn!("Long backtest period ({} days) with 1h interval may be slow", days_diff);
         }
 
@@ -165,33 +1329,179 @@ impl BacktestEngine {
             )));
         }
 
+        // Validate trading costs
+        let max_cost = Decimal::from(100);
+        let out_of_range = [config.fee_percentage, config.slippage_percentage]
+            .into_iter()
+            .chain(config.maker_fee_percentage)
+            .chain(config.taker_fee_percentage)
+            .any(|cost| cost < Decimal::ZERO || cost >= max_cost);
+        if out_of_range {
+            return Err(AppError::BadRequest(
+                "Fee and slippage percentages must be between 0 and 100".to_string(),
+            ));
+        }
+        config.fee_percentages()?;
+
+        if config.min_data_coverage_pct < Decimal::ZERO || config.min_data_coverage_pct > Decimal::from(100) {
+            return Err(AppError::BadRequest(
+                "Minimum data coverage must be between 0 and 100 percent".to_string(),
+            ));
+        }
+
+        if config.risk_free_rate < Decimal::ZERO || config.risk_free_rate >= Decimal::from(100) {
+            return Err(AppError::BadRequest(
+                "Risk-free rate must be between 0 and 100 percent".to_string(),
+            ));
+        }
+
+        if config.periods_per_year == Some(0) {
+            return Err(AppError::BadRequest(
+                "periods_per_year must be at least 1".to_string(),
+            ));
+        }
+
+        if config.max_curve_points.is_some_and(|max| max < MIN_CURVE_POINTS) {
+            return Err(AppError::BadRequest(format!(
+                "max_curve_points must be at least {}",
+                MIN_CURVE_POINTS
+            )));
+        }
+
+        ExecutionOrderType::from_parameters(&config.strategy_parameters)?;
+
+        // Validate imported starting positions
+        for position in &config.initial_positions {
+            if position.quantity <= Decimal::ZERO || position.average_cost < Decimal::ZERO {
+                return Err(AppError::BadRequest(format!(
+                    "Initial position for {} must have a positive quantity and non-negative average cost",
+                    position.symbol
+                )));
+            }
+        }
+
         Ok(())
     }
 
     /// Run the backtest simulation
-    async fn run_simulation(
+    pub(crate) async fn run_simulation(
+        &self,
+        historical_data: &[Kline],
+        strategy: &mut dyn Strategy,
+        initial_balance: Decimal,
+        config: &BacktestConfig,
+    ) -> Result<SimulationOutcome, AppError> {
+        self.run_simulation_from(historical_data, strategy, initial_balance, config, None).await
+    }
+
+    /// `run_simulation`, saving checkpoints and starting from one when `checkpointing`
+    /// says so
+    async fn run_simulation_from(
+        &self,
+        historical_data: &[Kline],
+        strategy: &mut dyn Strategy,
+        initial_balance: Decimal,
+        config: &BacktestConfig,
+        checkpointing: Option<Checkpointing<'_>>,
+    ) -> Result<SimulationOutcome, AppError> {
+        let (checkpoint_target, resume_from) = match checkpointing {
+            Some(Checkpointing { target, resume_from }) => (Some(target), resume_from),
+            None => (None, None),
+        };
+
+        let end = self.run_simulation_until(
+            historical_data,
+            strategy,
+            initial_balance,
+            config,
+            checkpoint_target,
+            resume_from,
+            None,
+        ).await?;
+        match end {
+            SimulationEnd::Finished(outcome) => Ok(outcome),
+            SimulationEnd::Paused(_) => unreachable!("a simulation without a pause point runs to the end"),
+        }
+    }
+
+    /// Simulate from the start or `resume_from`, stopping with a checkpoint once at least
+    /// `pause_after` bars are done and no limit order is resting
+    async fn run_simulation_until(
         &self,
         historical_data: &[Kline],
         strategy: &mut dyn Strategy,
         initial_balance: Decimal,
         config: &BacktestConfig,
-    ) -> Result<(Vec<BacktestTrade>, Portfolio, Vec<OpenPosition>), AppError> {
+        checkpoint_target: Option<&CheckpointTarget>,
+        resume_from: Option<BacktestCheckpoint>,
+        pause_after: Option<usize>,
+    ) -> Result<SimulationEnd, AppError> {
         let mut portfolio = Portfolio::new(initial_balance);
         let mut trades = Vec::new();
+        let mut equity_curve = Vec::with_capacity(historical_data.len());
         let mut position_tracker = PositionTracker::new();
         let mut open_positions: VecDeque<OpenPosition> = VecDeque::new();
 
+        // Start partially invested when the user imported an existing holding
+        if let (Some(position), Some(first_kline)) = (config.initial_position(), historical_data.first()) {
+            portfolio = Portfolio::with_initial_position(initial_balance, position.quantity, position.average_cost);
+            position_tracker.open_position(position.average_cost, position.quantity);
+            open_positions.push_back(OpenPosition {
+                timestamp: first_kline.open_time,
+                price: position.average_cost,
+                quantity: position.quantity,
+                total_value: position.quantity * position.average_cost,
+                reason: "Imported position".to_string(),
+            });
+            debug!("Imported starting position: {} @ {}", position.quantity, position.average_cost);
+        }
+        // Market fills pay the taker rate; resting limit orders pay the maker rate
+        let (maker_fee_pct, taker_fee_pct) = config.fee_percentages()?;
+        portfolio.set_trading_costs(taker_fee_pct, config.slippage_percentage);
+
+        let order_type = ExecutionOrderType::from_parameters(&config.strategy_parameters)?;
+        let min_confidence = if config.apply_min_confidence {
+            min_confidence_from_parameters(&config.strategy_parameters)?
+        } else {
+            None
+        };
+        let fallback_after = order_type
+            .fallback_after()
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+            .unwrap_or_else(chrono::Duration::zero);
+        let mut pending_order: Option<PendingLimitOrder> = None;
+        // Market signal waiting for the next bar's open
+        let mut pending_market: Option<StrategySignal> = None;
+
         debug!("BACKTEST START - Initial Balance: ${}, Strategy: {}", initial_balance, config.strategy_name);
 
-        // Create basic strategy context for initialization
+        // What the strategy sees; fills and valuations always use the real klines
+        let strategy_data = if config.use_heikin_ashi {
+            to_heikin_ashi(historical_data)
+        } else {
+            historical_data.to_vec()
+        };
+
+        // Create basic strategy context for initialization; a resumed run keeps its ids
+        let (strategy_id, user_id) = resume_from
+            .as_ref()
+            .map(|checkpoint| (checkpoint.strategy_id, checkpoint.user_id))
+            .unwrap_or_else(|| (Uuid::new_v4(), Uuid::new_v4()));
         let init_context = StrategyContext {
-            strategy_id: Uuid::new_v4(),
-            user_id: Uuid::new_v4(),
+            strategy_id,
+            user_id,
             symbol: config.symbol.clone(),
             interval: config.interval.to_string(),
             mode: StrategyMode::Backtest,
-            current_time: Utc::now(),
-            historical_data: historical_data.to_vec(),
+            current_time: match (config.strict_lookahead, historical_data.first()) {
+                (true, Some(first)) => first.close_time,
+                _ => Utc::now(),
+            },
+            historical_data: if config.strict_lookahead {
+                strategy_data.iter().take(1).cloned().collect()
+            } else {
+                strategy_data.clone()
+            },
             current_price: historical_data.first().map(|k| k.close).unwrap_or(Decimal::ZERO),
             available_balance: config.initial_balance,
             current_positions: Vec::new(),
@@ -201,11 +1511,99 @@ impl BacktestEngine {
         // Initialize strategy
         strategy.initialize(&config.strategy_parameters, StrategyMode::Backtest, &init_context).await?;
 
+        // Pick up where a checkpointed run left off
+        let mut start_index = 0;
+        if let Some(checkpoint) = resume_from {
+            let lines_up = checkpoint.next_index > 0
+                && historical_data.get(checkpoint.next_index - 1).map(|k| k.close_time)
+                    == Some(checkpoint.last_bar_close_time);
+            if !lines_up {
+                return Err(AppError::BadRequest(
+                    "Historical data no longer matches the backtest checkpoint".to_string(),
+                ));
+            }
+
+            strategy.restore_state(&checkpoint.strategy_state)?;
+            portfolio = checkpoint.portfolio;
+            position_tracker = PositionTracker::from(checkpoint.position);
+            open_positions = checkpoint.open_positions.into();
+            trades = checkpoint.trades;
+            equity_curve = checkpoint.equity_curve;
+            pending_market = checkpoint.pending_market;
+            start_index = checkpoint.next_index;
+            debug!("Resuming backtest at bar {} of {}", start_index, historical_data.len());
+        }
+
         // Process each kline
-        for (index, kline) in historical_data.iter().enumerate() {
+        for (index, kline) in historical_data.iter().enumerate().skip(start_index) {
             // Update portfolio value
             portfolio.update_total_value(kline.close);
 
+            // Fill or expire the limit order placed on an earlier bar
+            if let Some(order) = pending_order.take() {
+                let fill = order.check(kline);
+                let trade = match fill {
+                    LimitFill::Resting => {
+                        pending_order = Some(order);
+                        None
+                    }
+                    LimitFill::Maker(price) => {
+                        // Maker fills happen at the limit price, without slippage, at the maker fee
+                        let fill_kline = Kline { close: price, ..kline.clone() };
+                        let slippage_rate = std::mem::replace(&mut portfolio.slippage_rate, Decimal::ZERO);
+                        let fee_rate = std::mem::replace(&mut portfolio.fee_rate, maker_fee_pct / Decimal::from(100));
+                        let trade = self.execute_signal(
+                            order.signal,
+                            &fill_kline,
+                            &mut portfolio,
+                            &mut position_tracker,
+                            &mut open_positions,
+                            "Limit order filled".to_string(),
+                            strategy,
+                            &config.symbol,
+                            config,
+                        ).await;
+                        portfolio.slippage_rate = slippage_rate;
+                        portfolio.fee_rate = fee_rate;
+                        trade
+                    }
+                    LimitFill::MarketFallback(_) => {
+                        self.execute_signal(
+                            order.signal,
+                            kline,
+                            &mut portfolio,
+                            &mut position_tracker,
+                            &mut open_positions,
+                            "Limit order unfilled, market fallback".to_string(),
+                            strategy,
+                            &config.symbol,
+                            config,
+                        ).await
+                    }
+                };
+                if let Some(trade) = trade {
+                    trades.push(trade);
+                }
+            }
+
+            // Fill last bar's market signal at this bar's open
+            if let Some(signal) = pending_market.take() {
+                let open_kline = Kline { close: kline.open, close_time: kline.open_time, ..kline.clone() };
+                if let Some(trade) = self.execute_signal(
+                    signal,
+                    &open_kline,
+                    &mut portfolio,
+                    &mut position_tracker,
+                    &mut open_positions,
+                    "Strategy signal".to_string(),
+                    strategy,
+                    &config.symbol,
+                    config,
+                ).await {
+                    trades.push(trade);
+                }
+            }
+
             // Create context for this analysis
             let context = StrategyContext {
                 strategy_id: init_context.strategy_id,
@@ -214,7 +1612,7 @@ impl BacktestEngine {
                 interval: config.interval.to_string(),
                 mode: StrategyMode::Backtest,
                 current_time: kline.close_time,
-                historical_data: historical_data[..=index].to_vec(),
+                historical_data: strategy_data[..=index].to_vec(),
                 current_price: kline.close,
                 available_balance: portfolio.cash_balance,
                 current_positions: Vec::new(), // TODO: Convert from position tracker
@@ -224,20 +1622,55 @@ impl BacktestEngine {
             // Get strategy signal
             let signal_result = strategy.analyze(&context).await;
 
-            // Execute trades based on signal
+            let signal_result = signal_result.map(|signal| {
+                signal
+                    .and_then(|s| apply_min_confidence(s, min_confidence))
+                    .and_then(|s| s.resolve_volatility_target(&historical_data[..=index], portfolio.cash_balance))
+                    .map(|s| s.resolve_kelly(TradeStats::from_pnls(trades.iter().filter_map(|t| t.pnl)), portfolio.cash_balance))
+            });
+
+            // Execute trades based on signal; limit orders rest until a later bar
             if let Ok(Some(signal)) = signal_result {
-                if let Some(trade) = self.execute_signal(
-                    signal,
-                    kline,
-                    &mut portfolio,
-                    &mut position_tracker,
-                    &mut open_positions,
-                    "Strategy signal".to_string(),
-                    strategy,
-                    &config.symbol,
-                    &config,
-                ).await {
-                    trades.push(trade);
+                let limit = signal_side(&signal.signal_type)
+                    .and_then(|side| order_type.limit_price(&side, kline.close).map(|price| (side, price)));
+                if let Some((side, limit_price)) = limit {
+                    if pending_order.is_some() {
+                        trade_debug!(config, "Skipping signal - a limit order is already resting");
+                    } else {
+                        pending_order = Some(PendingLimitOrder {
+                            signal,
+                            side,
+                            limit_price,
+                            expires_at: kline.close_time + fallback_after,
+                        });
+                    }
+                } else {
+                    let fill_kline = match config.fill_price {
+                        FillPrice::NextOpen => None,
+                        FillPrice::Close => Some(kline.clone()),
+                        FillPrice::Typical => Some(Kline {
+                            close: (kline.high + kline.low + kline.close) / Decimal::from(3),
+                            ..kline.clone()
+                        }),
+                    };
+                    match fill_kline {
+                        None => pending_market = Some(signal),
+                        Some(fill_kline) => {
+                            if let Some(trade) = self.execute_signal(
+                                signal,
+                                &fill_kline,
+                                &mut portfolio,
+                                &mut position_tracker,
+                                &mut open_positions,
+                                "Strategy signal".to_string(),
+                                strategy,
+                                &config.symbol,
+                                config,
+                            ).await {
+                                trades.push(trade);
+                            }
+                        }
+                    }
                 }
             }
 
@@ -249,6 +1682,52 @@ impl BacktestEngine {
                 &mut trades,
                 config,
             );
+
+            portfolio.update_total_value(kline.close);
+            equity_curve.push(EquityPoint {
+                timestamp: kline.close_time,
+                portfolio_value: portfolio.total_value,
+            });
+
+            // Save progress every `every_bars` bars; a resting limit order can't be
+            // checkpointed, so that save point (or pause) is skipped
+            let bars_done = index + 1;
+            let can_checkpoint = bars_done < historical_data.len() && pending_order.is_none();
+            let save_to = checkpoint_target
+                .filter(|target| can_checkpoint && bars_done % target.every_bars == 0);
+            let pause = can_checkpoint && pause_after.is_some_and(|pause_after| bars_done >= pause_after);
+            if save_to.is_some() || pause {
+                let checkpoint = strategy.get_state().map(|strategy_state| BacktestCheckpoint {
+                    config: config.clone(),
+                    next_index: bars_done,
+                    last_bar_close_time: kline.close_time,
+                    portfolio: portfolio.clone(),
+                    position: position_tracker.checkpoint(),
+                    open_positions: open_positions.iter().cloned().collect(),
+                    trades: trades.clone(),
+                    equity_curve: equity_curve.clone(),
+                    pending_market: pending_market.clone(),
+                    strategy_id,
+                    user_id,
+                    strategy_state,
+                });
+                if pause {
+                    return checkpoint.map(|checkpoint| SimulationEnd::Paused(Box::new(checkpoint)));
+                }
+                if let Some(target) = save_to {
+                    let saved = match checkpoint {
+                        Ok(checkpoint) => target.save(&checkpoint).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = saved {
+                        warn!("Failed to checkpoint backtest {} at bar {}: {}", target.result_id, bars_done, e);
+                    }
+                }
+            }
+        }
+
+        if pending_market.is_some() {
+            trade_debug!(config, "Signal on the final bar left unfilled - no next open");
         }
 
         // Close any remaining positions at the end
@@ -270,7 +1749,7 @@ impl BacktestEngine {
         debug!("BACKTEST END - Final Portfolio Value: ${}, Cash: ${}, Asset Quantity: {}, Total Invested: ${}, Open Positions: {}",
                portfolio.total_value, portfolio.cash_balance, portfolio.asset_quantity, portfolio.total_invested, open_positions.len());
 
-        Ok((trades, portfolio, open_positions.into_iter().collect()))
+        Ok(SimulationEnd::Finished((trades, portfolio, open_positions.into_iter().collect(), equity_curve)))
     }
 
     /// Execute a trading signal
@@ -296,7 +1775,7 @@ impl BacktestEngine {
                 };
                 // Check if we already have a position
                 if position_tracker.has_position() {
-                    debug!("Skipping buy signal - already have position");
+                    trade_debug!(backtest_config, "Skipping buy signal - already have position");
                     return None;
                 }
 
@@ -308,6 +1787,7 @@ impl BacktestEngine {
                     let order_update = OrderUpdate {
                         order_id: Uuid::new_v4().to_string(),
                         symbol: symbol.to_string(),
+                        side: OrderSide::Buy,
                         order_type: TraitsOrderType::Market,
                         status: OrderStatus::Filled,
                         quantity,
@@ -330,10 +1810,10 @@ impl BacktestEngine {
                         pnl_percentage: None,
                     };
 
-                    debug!("Executed BUY: {} @ {}", quantity, kline.close);
+                    trade_debug!(backtest_config, "Executed BUY: {} @ {}", quantity, kline.close);
                     Some(trade)
                 } else {
-                    warn!("Failed to execute buy - insufficient balance");
+                    trade_warn!(backtest_config, "Failed to execute buy - insufficient balance");
                     None
                 }
             }
@@ -347,17 +1827,17 @@ impl BacktestEngine {
                 };
 
                 let quantity = amount / kline.close;
-                debug!("DCA buy attempt: amount=${}, price={}, quantity={}, cash_balance={}",
+                trade_debug!(backtest_config, "DCA buy attempt: amount=${}, price={}, quantity={}, cash_balance={}",
                        amount, kline.close, quantity, portfolio.cash_balance);
 
-                // For DCA strategies, use unlimited capital mode (continuous investment simulation)
-                // Grid trading should use actual capital constraint
-                let is_grid_trading = backtest_config.strategy_name.contains("grid");
-                let buy_success = if !is_grid_trading && (backtest_config.unlimited_capital || backtest_config.strategy_name.contains("dca")) {
-                    portfolio.execute_buy_with_injection(kline.close, quantity);
-                    true
-                } else {
-                    portfolio.execute_buy(kline.close, quantity)
+                // External contributions simulate ongoing income; fixed capital (and grid
+                // trading) is limited to the cash the portfolio actually holds
+                let buy_success = match backtest_config.dca_mode() {
+                    DcaMode::ExternalContribution => {
+                        portfolio.execute_buy_with_injection(kline.close, quantity);
+                        true
+                    }
+                    DcaMode::FixedCapital => portfolio.execute_buy(kline.close, quantity),
                 };
 
                 if buy_success {
@@ -373,13 +1853,14 @@ impl BacktestEngine {
                         reason: reason.clone(),
                     });
 
-                    debug!("BUY EXECUTED - Amount: ${}, Quantity: {}, Price: {}, Cash Remaining: ${}, Total Invested: ${}, Open Positions: {}",
+                    trade_debug!(backtest_config, "BUY EXECUTED - Amount: ${}, Quantity: {}, Price: {}, Cash Remaining: ${}, Total Invested: ${}, Open Positions: {}",
                            amount, quantity, kline.close, portfolio.cash_balance, portfolio.total_invested, open_positions.len());
 
                     // Notify strategy about order execution
                     let order_update = OrderUpdate {
                         order_id: Uuid::new_v4().to_string(),
                         symbol: symbol.to_string(),
+                        side: OrderSide::Buy,
                         order_type: TraitsOrderType::Market,
                         status: OrderStatus::Filled,
                         quantity,
@@ -402,11 +1883,11 @@ impl BacktestEngine {
                         pnl_percentage: None,
                     };
 
-                    debug!("Executed DCA BUY: {} @ {} (total position: {}, open positions: {})",
+                    trade_debug!(backtest_config, "Executed DCA BUY: {} @ {} (total position: {}, open positions: {})",
                            quantity, kline.close, position_tracker.entry_quantity, open_positions.len());
                     Some(trade)
                 } else {
-                    warn!("Failed to execute DCA buy - insufficient balance");
+                    trade_warn!(backtest_config, "Failed to execute DCA buy - insufficient balance");
                     None
                 }
             }
@@ -419,7 +1900,7 @@ impl BacktestEngine {
                 };
                 // Check if we have a position to sell
                 if !position_tracker.has_position() {
-                    debug!("Skipping sell signal - no position");
+                    trade_debug!(backtest_config, "Skipping sell signal - no position");
                     return None;
                 }
 
@@ -440,7 +1921,8 @@ impl BacktestEngine {
                         pnl_percentage: pnl_data.1,
                     };
 
-                    debug!(
+                    trade_debug!(
+                        backtest_config,
                         "Executed SELL: {} @ {} (PnL: {:+.2})",
                         actual_quantity,
                         kline.close,
@@ -448,7 +1930,7 @@ impl BacktestEngine {
                     );
                     Some(trade)
                 } else {
-                    warn!("Failed to execute sell");
+                    trade_warn!(backtest_config, "Failed to execute sell");
                     None
                 }
             }
@@ -462,7 +1944,7 @@ impl BacktestEngine {
 
                 // Check if we have enough to sell
                 if portfolio.asset_quantity < quantity {
-                    debug!("Skipping sell signal - insufficient asset quantity ({} < {})", portfolio.asset_quantity, quantity);
+                    trade_debug!(backtest_config, "Skipping sell signal - insufficient asset quantity ({} < {})", portfolio.asset_quantity, quantity);
                     return None;
                 }
 
@@ -490,6 +1972,7 @@ impl BacktestEngine {
                     let order_update = OrderUpdate {
                         order_id: Uuid::new_v4().to_string(),
                         symbol: symbol.to_string(),
+                        side: OrderSide::Sell,
                         order_type: TraitsOrderType::Market,
                         status: OrderStatus::Filled,
                         quantity,
@@ -512,16 +1995,16 @@ impl BacktestEngine {
                         pnl_percentage: pnl_data.1,
                     };
 
-                    debug!("Executed GRID SELL: {} @ {} (open positions remaining: {})", quantity, kline.close, open_positions.len());
+                    trade_debug!(backtest_config, "Executed GRID SELL: {} @ {} (open positions remaining: {})", quantity, kline.close, open_positions.len());
                     Some(trade)
                 } else {
-                    warn!("Failed to execute grid sell");
+                    trade_warn!(backtest_config, "Failed to execute grid sell");
                     None
                 }
             }
             _ => {
                 // Handle other signal types
-                debug!("Ignoring unsupported signal type: {:?}", signal.signal_type);
+                trade_debug!(backtest_config, "Ignoring unsupported signal type: {:?}", signal.signal_type);
                 None
             }
         }
@@ -612,6 +2095,7 @@ impl BacktestEngine {
         trades: &[BacktestTrade],
         portfolio: &Portfolio,
         historical_data: &[Kline],
+        equity_curve: &[EquityPoint],
         config: &BacktestConfig,
     ) -> BacktestMetrics {
         let final_value = portfolio.total_value;
@@ -619,9 +2103,9 @@ impl BacktestEngine {
         let total_invested = portfolio.total_invested;
 
         // Basic returns calculation:
-        // - For unlimited capital mode (DCA): calculate based on total invested
+        // - For external contributions (DCA): calculate based on total invested
         // - For fixed capital (grid trading, normal strategies): use initial value
-        let base_amount = if config.unlimited_capital && total_invested > Decimal::ZERO {
+        let base_amount = if config.dca_mode() == DcaMode::ExternalContribution && total_invested > Decimal::ZERO {
             total_invested
         } else {
             initial_value
@@ -682,11 +2166,30 @@ impl BacktestEngine {
             None
         };
 
-        // Calculate max drawdown
-        let max_drawdown = self.calculate_max_drawdown(trades);
+        // Max drawdown over every bar's close, not just the bars that traded
+        let max_drawdown = drawdown_curve(equity_curve)
+            .iter()
+            .map(|point| -point.drawdown_pct)
+            .max()
+            .unwrap_or(Decimal::ZERO);
 
         // Calculate volatility
-        let volatility = self.calculate_volatility(historical_data);
+        let volatility = self.calculate_volatility(historical_data, config);
+
+        // Historical VaR over per-bar portfolio returns
+        let bar_returns: Vec<Decimal> = equity_curve
+            .windows(2)
+            .filter(|window| window[0].portfolio_value > Decimal::ZERO)
+            .map(|window| (window[1].portfolio_value - window[0].portfolio_value) / window[0].portfolio_value)
+            .collect();
+        let (value_at_risk_95, conditional_value_at_risk_95) = match historical_value_at_risk(&bar_returns) {
+            Some((var, cvar)) => (Some(var), Some(cvar)),
+            None => (None, None),
+        };
+
+        // How volatile the asset itself was, to put the strategy's return in context
+        let asset_volatility_annual_pct = asset_volatility_annual_pct(historical_data);
+        let avg_atr_pct = average_atr_pct(historical_data, ASSET_ATR_PERIOD);
 
         // Annualized return calculation
         let days_elapsed = (config.end_time - config.start_time).num_days() as f64;
@@ -701,13 +2204,26 @@ impl BacktestEngine {
         };
 
         // Sharpe ratio (simplified)
-        let risk_free_rate = Decimal::from_str("2.0").unwrap(); // 2% annual risk-free rate
+        let risk_free_rate = config.risk_free_rate;
         let sharpe_ratio = if volatility > Decimal::ZERO && annualized_return.is_some() {
             Some((annualized_return.unwrap() - risk_free_rate) / volatility)
         } else {
             None
         };
 
+        // Sortino: excess return over the downside deviation of the portfolio's bars
+        let bars_per_year = Decimal::from_f64(periods_per_year(config)).unwrap_or(Decimal::ZERO);
+        let sortino_ratio = match (annualized_return, downside_deviation_annual_pct(&bar_returns, bars_per_year)) {
+            (Some(annualized), Some(downside_deviation)) => Some((annualized - risk_free_rate) / downside_deviation),
+            _ => None,
+        };
+
+        // Calmar: annualized return per point of max drawdown
+        let calmar_ratio = match annualized_return {
+            Some(annualized) if max_drawdown > Decimal::ZERO => Some(annualized / max_drawdown),
+            _ => None,
+        };
+
         // Calculate buy & hold return for benchmark
         let benchmark_return = if let (Some(first), Some(last)) =
             (historical_data.first(), historical_data.last())
@@ -732,8 +2248,14 @@ impl BacktestEngine {
             total_return_percentage,
             annualized_return,
             sharpe_ratio,
+            sortino_ratio,
+            calmar_ratio,
             max_drawdown,
             volatility,
+            value_at_risk_95,
+            conditional_value_at_risk_95,
+            asset_volatility_annual_pct,
+            avg_atr_pct,
             total_trades,
             winning_trades,
             losing_trades,
@@ -750,44 +2272,18 @@ impl BacktestEngine {
             open_trades,
             realized_pnl,
             unrealized_pnl,
+            total_fees: portfolio.total_fees,
+            total_return_before_fees: total_return + portfolio.total_fees,
         }
     }
 
-    /// Calculate maximum drawdown
-    fn calculate_max_drawdown(&self, trades: &[BacktestTrade]) -> Decimal {
-        if trades.is_empty() {
-            return Decimal::ZERO;
-        }
-
-        let mut max_value = trades[0].portfolio_value;
-        let mut max_drawdown = Decimal::ZERO;
-
-        for trade in trades {
-            if trade.portfolio_value > max_value {
-                max_value = trade.portfolio_value;
-            }
-
-            let current_drawdown = if max_value > Decimal::ZERO {
-                ((max_value - trade.portfolio_value) / max_value) * Decimal::from(100)
-            } else {
-                Decimal::ZERO
-            };
-
-            if current_drawdown > max_drawdown {
-                max_drawdown = current_drawdown;
-            }
-        }
-
-        max_drawdown
-    }
-
     /// Calculate volatility (annualized)
-    fn calculate_volatility(&self, historical_data: &[Kline]) -> Decimal {
+    fn calculate_volatility(&self, historical_data: &[Kline], config: &BacktestConfig) -> Decimal {
         if historical_data.len() < 2 {
             return Decimal::ZERO;
         }
 
-        // Calculate daily returns
+        // Calculate per-bar returns
         let returns: Vec<Decimal> = historical_data
             .windows(2)
             .map(|window| {
@@ -818,13 +2314,12 @@ impl BacktestEngine {
             .sum::<Decimal>()
             / Decimal::from(returns.len());
 
-        // Approximate annualized volatility
-        // sqrt(variance) * sqrt(252) ≈ variance^0.5 * 15.87
-        // Using approximation since Decimal doesn't have sqrt
-        let daily_vol = variance.to_f64().unwrap_or(0.0).sqrt();
-        let annual_vol = daily_vol * (252_f64).sqrt() * 100.0;
-
-        Decimal::from_f64(annual_vol).unwrap_or(Decimal::ZERO)
+        // Annualize: sqrt(variance) * sqrt(bars per year)
+        let bars_per_year = Decimal::from_f64(periods_per_year(config)).unwrap_or(Decimal::ZERO);
+        match (decimal_sqrt(variance), decimal_sqrt(bars_per_year)) {
+            (Some(bar_vol), Some(annualizer)) => bar_vol * annualizer * Decimal::from(100),
+            _ => Decimal::ZERO,
+        }
     }
 
     /// Generate performance chart data
@@ -920,10 +2415,1313 @@ impl PositionTracker {
 
         (Some(pnl), Some(pnl_percentage))
     }
+
+    fn checkpoint(&self) -> CheckpointPosition {
+        CheckpointPosition {
+            entry_price: self.entry_price,
+            entry_quantity: self.entry_quantity,
+            is_open: self.is_open,
+        }
+    }
+}
+
+impl From<CheckpointPosition> for PositionTracker {
+    fn from(position: CheckpointPosition) -> Self {
+        Self {
+            entry_price: position.entry_price,
+            entry_quantity: position.entry_quantity,
+            is_open: position.is_open,
+        }
+    }
 }
 
 impl Default for BacktestEngine {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::{Duration, TimeZone};
+    use serde_json::{json, Value};
+    use crate::strategies::{StrategyMetadata, StrategyCategory, RiskLevel, StrategyFactory, register_strategy};
+    use crate::services::fx_rates::FxRate;
+    use crate::database::test_support::{seed_backtest_result, seed_user, test_db};
+
+    /// Strategy that buys $500 on `buy_bar` and sells the whole position on `sell_bar`.
+    /// Bars can also be set through the `buy_bar`/`sell_bar` parameters.
+    #[derive(Default)]
+    struct ScriptedStrategy {
+        buy_bar: Option<usize>,
+        sell_bar: Option<usize>,
+        seen: usize,
+        /// Close of the latest bar in each analyzed context
+        seen_closes: Vec<Decimal>,
+        /// Bars kept from `initialize` by a `"peek": true` strategy, which buys ahead of
+        /// the first rise it can see coming
+        peeked: Option<Vec<Kline>>,
+    }
+
+    fn scripted_metadata() -> StrategyMetadata {
+        StrategyMetadata {
+            id: "scripted_test".to_string(),
+            name: "Scripted Test".to_string(),
+            description: "Test strategy".to_string(),
+            version: "1.0.0".to_string(),
+            author: "test".to_string(),
+            category: StrategyCategory::Custom,
+            risk_level: RiskLevel::Conservative,
+            supported_modes: vec![StrategyMode::Backtest],
+            min_balance: None,
+            max_positions: None,
+            supported_intervals: vec!["1h".to_string()],
+            tags: Vec::new(),
+        }
+    }
+
+    #[async_trait]
+    impl Strategy for ScriptedStrategy {
+        fn metadata(&self) -> StrategyMetadata {
+            scripted_metadata()
+        }
+
+        async fn initialize(&mut self, parameters: &Value, _mode: StrategyMode, context: &StrategyContext) -> Result<(), AppError> {
+            if parameters.get("peek").and_then(|v| v.as_bool()) == Some(true) {
+                self.peeked = Some(context.historical_data.clone());
+            }
+            if let Some(bar) = parameters.get("buy_bar").and_then(|v| v.as_u64()) {
+                self.buy_bar = Some(bar as usize);
+            }
+            if let Some(bar) = parameters.get("sell_bar").and_then(|v| v.as_u64()) {
+                self.sell_bar = Some(bar as usize);
+            }
+            self.seen = 0;
+            Ok(())
+        }
+
+        async fn analyze(&mut self, context: &StrategyContext) -> Result<Option<StrategySignal>, AppError> {
+            let bar = Some(self.seen);
+            self.seen += 1;
+            if let Some(kline) = context.historical_data.last() {
+                self.seen_closes.push(kline.close);
+            }
+            if let (Some(peeked), Some(current), None) = (&self.peeked, context.historical_data.last(), self.buy_bar) {
+                if peeked.get(self.seen).is_some_and(|next| next.close > current.close) {
+                    self.buy_bar = bar;
+                }
+            }
+            if bar == self.buy_bar {
+                return Ok(Some(StrategySignal::buy(
+                    context.symbol.clone(),
+                    QuantityType::DollarAmount(Decimal::from(500)),
+                    "Test buy".to_string(),
+                    None,
+                )));
+            }
+            if bar == self.sell_bar {
+                return Ok(Some(StrategySignal::sell(
+                    context.symbol.clone(),
+                    QuantityType::AllPosition,
+                    "Test sell".to_string(),
+                    None,
+                )));
+            }
+            Ok(None)
+        }
+
+        fn validate_parameters(&self, _parameters: &Value) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn parameter_schema(&self) -> Value {
+            json!({})
+        }
+
+        fn get_state(&self) -> Result<Value, AppError> {
+            Ok(json!({ "seen": self.seen }))
+        }
+
+        fn restore_state(&mut self, state: &Value) -> Result<(), AppError> {
+            self.seen = state["seen"].as_u64().unwrap_or_default() as usize;
+            Ok(())
+        }
+    }
+
+    struct ScriptedFactory {
+        metadata: StrategyMetadata,
+    }
+
+    impl StrategyFactory for ScriptedFactory {
+        fn create(&self) -> Box<dyn Strategy> {
+            Box::new(ScriptedStrategy::default())
+        }
+
+        fn metadata(&self) -> &StrategyMetadata {
+            &self.metadata
+        }
+    }
+
+    fn create_klines(closes: &[i64]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let price = Decimal::from(*close);
+                Kline {
+                    open_time: base_time + Duration::hours(i as i64),
+                    close_time: base_time + Duration::hours(i as i64) + Duration::minutes(59),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::from(1000),
+                    quote_asset_volume: Decimal::from(1000) * price,
+                    number_of_trades: 100,
+                    taker_buy_base_asset_volume: Decimal::from(500),
+                    taker_buy_quote_asset_volume: Decimal::from(500) * price,
+                }
+            })
+            .collect()
+    }
+
+    fn test_config(initial_positions: Vec<InitialPosition>) -> BacktestConfig {
+        let start_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        BacktestConfig {
+            symbol: "BTCUSDT".to_string(),
+            interval: KlineInterval::OneHour,
+            start_time,
+            end_time: start_time + Duration::days(1),
+            initial_balance: Decimal::from(1000),
+            strategy_name: "scripted_test".to_string(),
+            strategy_type: None,
+            strategy_parameters: json!({}),
+            stop_loss_percentage: None,
+            take_profit_percentage: None,
+            unlimited_capital: false,
+            dca_mode: None,
+            asset_type: "crypto".to_string(),
+            initial_positions,
+            fee_percentage: Decimal::ZERO,
+            fee_tier: None,
+            fee_token_discount: false,
+            maker_fee_percentage: None,
+            taker_fee_percentage: None,
+            slippage_percentage: Decimal::ZERO,
+            fill_price: FillPrice::Close,
+            apply_min_confidence: false,
+            use_heikin_ashi: false,
+            log_trades: false,
+            strict_lookahead: false,
+            benchmark_symbol: None,
+            benchmark_asset_type: None,
+            min_data_coverage_pct: Decimal::from(95),
+            on_short_data: ShortDataAction::Warn,
+            max_curve_points: None,
+            risk_free_rate: Decimal::from(2),
+            periods_per_year: None,
+        }
+    }
+
+    #[test]
+    fn test_every_interval_deserializes_and_annualizes_from_the_enum() {
+        for interval in KlineInterval::ALL {
+            let mut value = serde_json::to_value(test_config(Vec::new())).unwrap();
+            value["interval"] = json!(interval.as_str());
+            let config: BacktestConfig = serde_json::from_value(value).unwrap();
+            assert_eq!(config.interval, interval);
+            assert_eq!(serde_json::to_value(&config).unwrap()["interval"], interval.as_str());
+
+            // Crypto bars cover the whole calendar year
+            let covered_secs = periods_per_year(&config) * interval.duration().num_seconds() as f64;
+            assert!((covered_secs - 365.25 * 86_400.0).abs() < 1e-6, "{}", interval);
+        }
+
+        let stock_daily = BacktestConfig {
+            asset_type: "stock".to_string(),
+            interval: KlineInterval::OneDay,
+            ..test_config(Vec::new())
+        };
+        assert!((periods_per_year(&stock_daily) - 252.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_volatility_matches_reference_for_100_daily_stock_bars() {
+        let closes: Vec<i64> = (0..100).map(|i| 100 + (i * 37 % 23)).collect();
+        let klines = create_klines(&closes);
+        let config = BacktestConfig {
+            asset_type: "stock".to_string(),
+            interval: KlineInterval::OneDay,
+            ..test_config(Vec::new())
+        };
+
+        // Population std dev of the 99 bar returns * sqrt(252) * 100, worked out in
+        // Python's decimal module at 40 digits
+        let reference = Decimal::from_str("165.2533683147484116").unwrap();
+        let volatility = BacktestEngine::new().calculate_volatility(&klines, &config);
+        assert!((volatility - reference).abs() < Decimal::new(1, 2), "{}", volatility);
+
+        // Flat prices have no volatility
+        assert_eq!(BacktestEngine::new().calculate_volatility(&create_klines(&[100; 10]), &config), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_same_returns_annualize_by_interval() {
+        let closes: Vec<i64> = (0..48).map(|i| 100 + (i * 37 % 23)).collect();
+        let klines = create_klines(&closes);
+        let engine = BacktestEngine::new();
+        let hourly = test_config(Vec::new());
+        let daily = BacktestConfig { interval: KlineInterval::OneDay, ..test_config(Vec::new()) };
+
+        // 24 hourly bars to a day, so the same per-bar returns are sqrt(24) times as volatile a year
+        let hourly_volatility = engine.calculate_volatility(&klines, &hourly);
+        let daily_volatility = engine.calculate_volatility(&klines, &daily);
+        let scale = decimal_sqrt(Decimal::from(24)).unwrap();
+        assert!((hourly_volatility - daily_volatility * scale).abs() < Decimal::new(1, 8), "{} vs {}", hourly_volatility, daily_volatility);
+
+        // An explicit bar count wins over the interval
+        let stock_daily = BacktestConfig { asset_type: "stock".to_string(), ..daily };
+        let hourly_252 = BacktestConfig { periods_per_year: Some(252), ..hourly };
+        assert!((periods_per_year(&hourly_252) - 252.0).abs() < f64::EPSILON);
+        assert_eq!(engine.calculate_volatility(&klines, &hourly_252), engine.calculate_volatility(&klines, &stock_daily));
+    }
+
+    #[tokio::test]
+    async fn test_sharpe_and_sortino_measure_excess_over_the_configured_risk_free_rate() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let engine = BacktestEngine::new();
+        let closes: Vec<i64> = (0..24).map(|i| 100 + (i * 7) % 13).collect();
+        let klines = create_klines(&closes);
+        let config = |risk_free_rate| BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 2, "sell_bar": 15 }),
+            risk_free_rate,
+            ..test_config(Vec::new())
+        };
+
+        let zero = engine.run_backtest_with_data(config(Decimal::ZERO), &klines).await.unwrap().metrics;
+        let five = engine.run_backtest_with_data(config(Decimal::from(5)), &klines).await.unwrap().metrics;
+
+        // Five points more hurdle lowers Sharpe by 5 / volatility
+        assert!(zero.volatility > Decimal::ZERO);
+        let sharpe_drop = zero.sharpe_ratio.unwrap() - five.sharpe_ratio.unwrap();
+        assert!((sharpe_drop - Decimal::from(5) / zero.volatility).abs() < Decimal::new(1, 12), "{}", sharpe_drop);
+        // The held position has down bars, so both runs have a downside deviation
+        let (sortino_zero, sortino_five) = (zero.sortino_ratio.unwrap(), five.sortino_ratio.unwrap());
+        assert!(sortino_five < sortino_zero, "{} >= {}", sortino_five, sortino_zero);
+
+        let negative = engine.validate_config(&config(Decimal::from(-1)));
+        assert!(matches!(negative, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_canonical_pair_validates_and_fetches_the_binance_symbol() {
+        use crate::backtesting::binance_fetcher::FetcherConfig;
+        use crate::utils::mock_http::{self, MockResponse};
+
+        let requested = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = requested.clone();
+        let (url, _) = mock_http::spawn(move |request| {
+            seen.lock().unwrap().push(request.param("symbol").unwrap_or_default());
+            Some(MockResponse::json("200 OK", "[]"))
+        })
+        .await;
+        let engine = BacktestEngine {
+            binance_fetcher: Arc::new(BinanceFetcher::with_base_url(&url, FetcherConfig::default())),
+            ..BacktestEngine::new()
+        };
+        let config = BacktestConfig {
+            symbol: "BTC/USDT".to_string(),
+            benchmark_symbol: Some("eth-usdt".to_string()),
+            ..test_config(Vec::new())
+        };
+
+        engine.validate_config(&config).unwrap();
+        // The mock has no bars to give, only the symbol asked for matters
+        let _ = engine.fetch_klines(&config.symbol, &config.asset_type, &config).await;
+        let requested = requested.lock().unwrap().clone();
+        assert!(!requested.is_empty());
+        assert!(requested.iter().all(|symbol| symbol == "BTCUSDT"), "{:?}", requested);
+
+        let unquoted = BacktestConfig { symbol: "BTC/".to_string(), ..test_config(Vec::new()) };
+        assert!(matches!(engine.validate_config(&unquoted), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_invalid_interval_fails_to_deserialize() {
+        let mut value = serde_json::to_value(test_config(Vec::new())).unwrap();
+        for invalid in ["2m", "1H", "hourly", ""] {
+            value["interval"] = json!(invalid);
+            let err = serde_json::from_value::<BacktestConfig>(value.clone()).unwrap_err();
+            assert!(err.to_string().contains("invalid interval"), "{}", err);
+        }
+
+        // Results saved with the variant name still load
+        value["interval"] = json!("FourHours");
+        let config: BacktestConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.interval, KlineInterval::FourHours);
+    }
+
+    fn btc_position(quantity: i64, average_cost: i64) -> InitialPosition {
+        InitialPosition {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(quantity),
+            average_cost: Decimal::from(average_cost),
+        }
+    }
+
+    #[test]
+    fn test_parse_initial_positions_csv() {
+        let csv = "symbol,quantity,average_cost\nbtcusdt, 0.5, 30000\n\nETHUSDT,2,1500\n";
+        let positions = InitialPosition::parse_csv(csv).unwrap();
+
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].symbol, "BTCUSDT");
+        assert_eq!(positions[0].quantity, Decimal::new(5, 1));
+        assert_eq!(positions[1].average_cost, Decimal::from(1500));
+
+        assert!(InitialPosition::parse_csv("BTCUSDT,abc,100\n").is_err());
+        assert!(InitialPosition::parse_csv("BTCUSDT,1\n").is_err());
+    }
+
+    #[test]
+    fn test_quiet_backtest_skips_trade_log_formatting() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Counts how often the log arguments are actually formatted
+        struct CountingArg<'a>(&'a AtomicUsize);
+
+        impl std::fmt::Display for CountingArg<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                write!(f, "trade")
+            }
+        }
+
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(std::io::sink)
+            .finish();
+        let formatted = AtomicUsize::new(0);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let quiet = test_config(Vec::new());
+            for _ in 0..1000 {
+                trade_debug!(quiet, "Executed BUY: {}", CountingArg(&formatted));
+                trade_warn!(quiet, "Failed to execute buy: {}", CountingArg(&formatted));
+            }
+            assert_eq!(formatted.load(Ordering::SeqCst), 0);
+
+            let verbose = BacktestConfig { log_trades: true, ..test_config(Vec::new()) };
+            trade_debug!(verbose, "Executed BUY: {}", CountingArg(&formatted));
+            assert_eq!(formatted.load(Ordering::SeqCst), 1);
+        });
+    }
+
+    #[test]
+    fn test_historical_value_at_risk_percentiles() {
+        // Returns -50%, -49%, ..., +49%: the 5% tail is the five worst bars
+        let returns: Vec<Decimal> = (-50..50).rev().map(|r| Decimal::new(r, 2)).collect();
+        let (var, cvar) = historical_value_at_risk(&returns).unwrap();
+        assert_eq!(var, Decimal::from(-46));
+        assert_eq!(cvar, Decimal::from(-48));
+
+        // 40 returns: nearest rank puts the 5th percentile on the second worst
+        let mut returns = vec![Decimal::new(1, 2); 38];
+        returns.extend([Decimal::new(-10, 2), Decimal::new(-4, 2)]);
+        let (var, cvar) = historical_value_at_risk(&returns).unwrap();
+        assert_eq!(var, Decimal::from(-4));
+        assert_eq!(cvar, Decimal::from(-7));
+
+        assert!(historical_value_at_risk(&returns[..MIN_VAR_RETURNS - 1]).is_none());
+    }
+
+    #[test]
+    fn test_downside_deviation_counts_only_losing_bars() {
+        // Losses of 4% and 3% over four bars: sqrt((0.0016 + 0.0009) / 4) = 2.5% a bar,
+        // doubled by four bars a year
+        let deviation = |returns: [i64; 4]| {
+            let returns = returns.map(|r| Decimal::new(r, 2));
+            downside_deviation_annual_pct(&returns, Decimal::from(4)).map(|d| d.round_dp(12))
+        };
+        assert_eq!(deviation([3, -4, 1, -3]), Some(Decimal::from(5)));
+
+        // Bigger gains leave it unchanged
+        assert_eq!(deviation([30, -4, 10, -3]), Some(Decimal::from(5)));
+
+        assert!(downside_deviation_annual_pct(&[Decimal::new(1, 2); 4], Decimal::from(4)).is_none());
+        assert!(downside_deviation_annual_pct(&[], Decimal::from(4)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sortino_and_calmar_follow_the_equity_curve() {
+        let engine = BacktestEngine::new();
+        let config = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 0, "sell_bar": 3 }),
+            ..test_config(Vec::new())
+        };
+        let result = engine
+            .run_backtest_with_data(config.clone(), &create_klines(&[100, 110, 99, 105]))
+            .await
+            .unwrap();
+        let metrics = &result.metrics;
+
+        assert!(metrics.max_drawdown > Decimal::ZERO);
+        let annualized = metrics.annualized_return.unwrap();
+        assert_eq!(metrics.calmar_ratio, Some(annualized / metrics.max_drawdown));
+        assert!(metrics.sortino_ratio.unwrap() > Decimal::ZERO);
+
+        // Never below its peak and never a losing bar: neither ratio is defined
+        let rising = engine
+            .run_backtest_with_data(config, &create_klines(&[100, 101, 102, 103]))
+            .await
+            .unwrap();
+        assert_eq!(rising.metrics.calmar_ratio, None);
+        assert_eq!(rising.metrics.sortino_ratio, None);
+    }
+
+    #[test]
+    fn test_asset_volatility_metrics_rise_with_volatility() {
+        let calm = create_klines(&(0..40).map(|i| if i % 2 == 0 { 100 } else { 101 }).collect::<Vec<_>>());
+        let volatile = create_klines(&(0..40).map(|i| if i % 2 == 0 { 100 } else { 110 }).collect::<Vec<_>>());
+
+        let calm_volatility = asset_volatility_annual_pct(&calm).unwrap();
+        let volatile_volatility = asset_volatility_annual_pct(&volatile).unwrap();
+        assert!(calm_volatility > Decimal::ZERO);
+        assert!(volatile_volatility > calm_volatility);
+
+        let calm_atr = average_atr_pct(&calm, ASSET_ATR_PERIOD).unwrap();
+        let volatile_atr = average_atr_pct(&volatile, ASSET_ATR_PERIOD).unwrap();
+        assert!(calm_atr > Decimal::ZERO && calm_atr < Decimal::ONE);
+        assert!(volatile_atr > Decimal::from(9) && volatile_atr < Decimal::from(10));
+
+        // Too few bars for a full ATR window
+        assert!(average_atr_pct(&calm[..ASSET_ATR_PERIOD], ASSET_ATR_PERIOD).is_none());
+    }
+
+    #[test]
+    fn test_drawdown_curve_tracks_running_peak() {
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100, 110, 99, 88, 120, 108]);
+        let equity_curve: Vec<EquityPoint> = klines
+            .iter()
+            .map(|kline| EquityPoint { timestamp: kline.close_time, portfolio_value: kline.close })
+            .collect();
+
+        let curve = drawdown_curve(&equity_curve);
+        let drawdowns: Vec<Decimal> = curve.iter().map(|point| point.drawdown_pct).collect();
+        // New highs at 100, 110 and 120; 99 and 88 are 10% and 20% under 110, 108 is 10% under 120
+        assert_eq!(drawdowns, [0, 0, -10, -20, 0, -10].map(Decimal::from));
+        assert_eq!(curve[3].timestamp, klines[3].close_time);
+
+        let config = test_config(Vec::new());
+        let mut portfolio = Portfolio::new(config.initial_balance);
+        portfolio.update_total_value(klines.last().unwrap().close);
+        let metrics = engine.calculate_metrics(&[], &portfolio, &klines, &equity_curve, &config);
+        assert_eq!(drawdowns.iter().min().copied(), Some(-metrics.max_drawdown));
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_symbol_drives_benchmark_metrics() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let engine = BacktestEngine::new();
+
+        // Traded symbol gains 10%; the benchmark starts an hour later and doubles from 50 to 100
+        let klines = create_klines(&[100, 102, 104, 106, 108, 110]);
+        let mut benchmark = create_klines(&[40, 50, 60, 70, 80, 90, 100]);
+        benchmark.remove(0);
+        let config = BacktestConfig {
+            benchmark_symbol: Some("ETHUSDT".to_string()),
+            ..test_config(Vec::new())
+        };
+
+        let own = engine.run_backtest_with_data(config.clone(), &klines).await.unwrap();
+        assert_eq!(own.metrics.benchmark_return, Some(Decimal::from(10)));
+        assert_eq!(own.benchmark_curve.len(), klines.len());
+
+        let result = engine
+            .run_backtest_with_benchmark_data(config, &klines, Some(&benchmark))
+            .await
+            .unwrap();
+
+        // Only the five bars both series share are compared: 50 -> 90
+        assert_eq!(result.benchmark_curve.len(), 5);
+        assert_eq!(result.benchmark_curve[0].timestamp, klines[1].close_time);
+        assert_eq!(result.benchmark_curve[0].portfolio_value, Decimal::from(1000));
+        assert_eq!(result.benchmark_curve[4].portfolio_value, Decimal::from(1800));
+        assert_eq!(result.metrics.benchmark_return, Some(Decimal::from(80)));
+    }
+
+    #[test]
+    fn test_initial_position_changes_unrealized_pnl() {
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100, 110, 120]);
+        let last_price = klines.last().unwrap().close;
+
+        let cash_config = test_config(Vec::new());
+        let mut cash_portfolio = Portfolio::new(cash_config.initial_balance);
+        cash_portfolio.update_total_value(last_price);
+        let cash_metrics = engine.calculate_metrics(&[], &cash_portfolio, &klines, &[], &cash_config);
+
+        let config = test_config(vec![btc_position(2, 100)]);
+        let mut portfolio = Portfolio::with_initial_position(config.initial_balance, Decimal::from(2), Decimal::from(100));
+        portfolio.update_total_value(last_price);
+        let metrics = engine.calculate_metrics(&[], &portfolio, &klines, &[], &config);
+
+        assert_eq!(cash_metrics.unrealized_pnl, Decimal::ZERO);
+        // 2 units bought at 100, now worth 120
+        assert_eq!(metrics.unrealized_pnl, Decimal::from(40));
+        assert_eq!(metrics.total_invested, Decimal::from(200));
+        assert_eq!(metrics.final_portfolio_value, Decimal::from(1240));
+    }
+
+    #[tokio::test]
+    async fn test_sell_realizes_against_imported_cost_basis() {
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100, 150, 150]);
+        let config = test_config(vec![btc_position(2, 80)]);
+        let mut strategy = ScriptedStrategy { sell_bar: Some(1), ..Default::default() };
+
+        let (trades, portfolio, _, _) = engine
+            .run_simulation(&klines, &mut strategy, config.initial_balance, &config)
+            .await
+            .unwrap();
+
+        assert_eq!(trades.len(), 1);
+        let sell = &trades[0];
+        assert!(matches!(sell.trade_type, TradeType::Sell));
+        assert_eq!(sell.quantity, Decimal::from(2));
+        // (150 - 80) * 2
+        assert_eq!(sell.pnl, Some(Decimal::from(140)));
+        assert_eq!(portfolio.cash_balance, Decimal::from(1300));
+        assert_eq!(portfolio.asset_quantity, Decimal::ZERO);
+    }
+
+    fn limit_config(offset_bps: u32, fallback_after_secs: u64) -> BacktestConfig {
+        BacktestConfig {
+            strategy_parameters: json!({
+                "buy_bar": 0,
+                "execution_order_type": {
+                    "type": "limit",
+                    "offset_bps": offset_bps,
+                    "fallback_after_secs": fallback_after_secs
+                }
+            }),
+            slippage_percentage: Decimal::ONE,
+            ..test_config(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limit_buy_fills_at_offset_price_without_slippage() {
+        let engine = BacktestEngine::new();
+        let mut klines = create_klines(&[100, 100, 100]);
+        klines[1].low = Decimal::from(79);
+        // 20% below the signal bar's close of 100
+        let config = limit_config(2000, 7200);
+        let mut strategy = ScriptedStrategy::default();
+
+        let (trades, portfolio, _, _) = engine
+            .run_simulation(&klines, &mut strategy, config.initial_balance, &config)
+            .await
+            .unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].price, Decimal::from(80));
+        assert_eq!(trades[0].timestamp, klines[1].close_time);
+        assert_eq!(trades[0].quantity, Decimal::new(625, 2));
+        // Maker fill: the configured 1% slippage is not charged
+        assert_eq!(portfolio.cash_balance, Decimal::from(500));
+    }
+
+    /// Fees paid buying $500 at 100 and selling the 5 units at 105 (1,025 of taker volume)
+    async fn round_trip_fees(fee_tier: Option<u8>, fee_token_discount: bool) -> Decimal {
+        let klines = create_klines(&[100, 100, 105]);
+        let config = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 0, "sell_bar": 2 }),
+            fee_percentage: Decimal::from(5),
+            fee_tier,
+            fee_token_discount,
+            ..test_config(Vec::new())
+        };
+        let (trades, portfolio, _, _) = BacktestEngine::new()
+            .run_simulation(&klines, &mut ScriptedStrategy::default(), config.initial_balance, &config)
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 2);
+        portfolio.total_fees
+    }
+
+    #[tokio::test]
+    async fn test_higher_fee_tier_lowers_fees_for_the_same_trades() {
+        let engine = BacktestEngine::new();
+
+        // A tier replaces the flat fee_percentage
+        assert_eq!(round_trip_fees(Some(0), false).await, Decimal::new(1025, 3));
+        assert_eq!(round_trip_fees(Some(5), false).await, Decimal::new(492, 3));
+        assert_eq!(round_trip_fees(Some(5), true).await, Decimal::new(369, 3));
+
+        // Resting limit orders pay the VIP5 maker rate of 0.036%
+        let config = BacktestConfig {
+            fee_tier: Some(5),
+            ..limit_config(2000, 7200)
+        };
+        let mut limit_klines = create_klines(&[100, 100, 100]);
+        limit_klines[1].low = Decimal::from(79);
+        let (_, portfolio, _, _) = engine
+            .run_simulation(&limit_klines, &mut ScriptedStrategy::default(), config.initial_balance, &config)
+            .await
+            .unwrap();
+        assert_eq!(portfolio.total_fees, Decimal::new(18, 2));
+
+        let unknown_tier = BacktestConfig { fee_tier: Some(42), ..test_config(Vec::new()) };
+        assert!(engine.validate_config(&unknown_tier).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_at_a_flat_price_loses_the_taker_fees() {
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100, 100, 100]);
+        let config = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 0, "sell_bar": 2 }),
+            // The per-side rate wins over the flat fee
+            fee_percentage: Decimal::from(5),
+            taker_fee_percentage: Some(Decimal::new(1, 1)),
+            ..test_config(Vec::new())
+        };
+        let result = engine.run_backtest_with_data(config, &klines).await.unwrap();
+
+        // 0.1% of $500 bought and of $500 sold
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.metrics.total_fees, Decimal::ONE);
+        assert_eq!(result.metrics.total_return, Decimal::NEGATIVE_ONE);
+        assert_eq!(result.metrics.total_return_before_fees, Decimal::ZERO);
+        assert_eq!(result.metrics.final_portfolio_value, Decimal::from(999));
+
+        // Costs default to zero
+        let free = engine.run_backtest_with_data(
+            BacktestConfig { strategy_parameters: json!({ "buy_bar": 0, "sell_bar": 2 }), ..test_config(Vec::new()) },
+            &klines,
+        ).await.unwrap();
+        assert_eq!(free.metrics.total_fees, Decimal::ZERO);
+        assert_eq!(free.metrics.total_return, free.metrics.total_return_before_fees);
+
+        let maker_too_high = BacktestConfig { maker_fee_percentage: Some(Decimal::from(100)), ..test_config(Vec::new()) };
+        assert!(engine.validate_config(&maker_too_high).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unfilled_limit_falls_back_to_market_after_timeout() {
+        let engine = BacktestEngine::new();
+        // Price runs away from the 99.5 bid; the order times out two bars later
+        let klines = create_klines(&[100, 101, 102, 103]);
+        let config = limit_config(50, 7200);
+        let mut strategy = ScriptedStrategy::default();
+
+        let (trades, _, _, _) = engine
+            .run_simulation(&klines, &mut strategy, config.initial_balance, &config)
+            .await
+            .unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].price, Decimal::from(102));
+        assert_eq!(trades[0].timestamp, klines[2].close_time);
+        assert_eq!(trades[0].reason, "Limit order unfilled, market fallback");
+    }
+
+    #[tokio::test]
+    async fn test_compare_strategies_share_timestamps() {
+        use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency};
+        use crate::strategies::implementations::sma_crossover::SMACrossoverConfig;
+
+        crate::strategies::init_all_strategies().unwrap();
+
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[
+            100, 98, 96, 95, 97, 100, 104, 108, 110, 107,
+            103, 99, 96, 94, 97, 101, 105, 109, 112, 108,
+        ]);
+        let strategies = vec![
+            StrategyComparisonEntry {
+                strategy_name: "dca_v2".to_string(),
+                parameters: Some(serde_json::to_value(DCAConfig::simple(Decimal::from(100), DCAFrequency::Hourly(1))).unwrap()),
+            },
+            StrategyComparisonEntry {
+                strategy_name: "sma_crossover_v2".to_string(),
+                parameters: Some(serde_json::to_value(SMACrossoverConfig::aggressive(2, 3)).unwrap()),
+            },
+        ];
+
+        let comparison = engine
+            .compare_strategies_with_data(test_config(Vec::new()), strategies, &klines)
+            .await
+            .unwrap();
+
+        assert_eq!(comparison.results.len(), 2);
+        assert_eq!(comparison.data_points, klines.len());
+
+        let dca_times: Vec<_> = comparison.results[0].equity_curve.iter().map(|p| p.timestamp).collect();
+        let sma_times: Vec<_> = comparison.results[1].equity_curve.iter().map(|p| p.timestamp).collect();
+        assert_eq!(dca_times.len(), klines.len());
+        assert_eq!(dca_times, sma_times);
+    }
+
+    #[tokio::test]
+    async fn test_compare_strategies_rejects_too_many_strategies() {
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100; 5]);
+        let strategies = vec![
+            StrategyComparisonEntry {
+                strategy_name: "dca_v2".to_string(),
+                parameters: None,
+            };
+            MAX_COMPARED_STRATEGIES + 1
+        ];
+
+        let result = engine
+            .compare_strategies_with_data(test_config(Vec::new()), strategies, &klines)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_short_history_reports_actual_range() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let engine = BacktestEngine::new();
+
+        // A day was requested but the symbol only has bars from noon onwards
+        let klines = create_klines(&[100; 24]).split_off(12);
+        let result = engine.run_backtest_with_data(test_config(Vec::new()), &klines).await.unwrap();
+        assert_eq!(result.actual_start_time, Some(klines[0].open_time));
+        assert_eq!(result.actual_end_time, Some(klines[11].close_time));
+        // 11h59m of the 24h requested
+        assert_eq!(result.data_coverage_pct, Some(Decimal::new(4993, 2)));
+
+        let strict = BacktestConfig {
+            on_short_data: ShortDataAction::Reject,
+            ..test_config(Vec::new())
+        };
+        let rejected = engine.run_backtest_with_data(strict.clone(), &klines).await;
+        assert!(matches!(rejected, Err(AppError::BadRequest(_))));
+
+        let lenient = BacktestConfig {
+            min_data_coverage_pct: Decimal::from(40),
+            ..strict
+        };
+        assert!(engine.run_backtest_with_data(lenient, &klines).await.is_ok());
+
+        let full = create_klines(&[100; 24]);
+        let result = engine.run_backtest_with_data(test_config(Vec::new()), &full).await.unwrap();
+        assert_eq!(result.data_coverage_pct, Some(Decimal::new(9993, 2)));
+    }
+
+    #[tokio::test]
+    async fn test_fixed_capital_dca_stops_when_cash_runs_out() {
+        use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency};
+
+        crate::strategies::init_all_strategies().unwrap();
+
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100; 20]);
+        let dca_config = |dca_mode| BacktestConfig {
+            strategy_name: "dca_v2".to_string(),
+            strategy_parameters: serde_json::to_value(DCAConfig::simple(Decimal::from(100), DCAFrequency::Hourly(1))).unwrap(),
+            dca_mode,
+            ..test_config(Vec::new())
+        };
+        assert_eq!(dca_config(None).dca_mode(), DcaMode::ExternalContribution);
+
+        // 1,000 of starting cash covers ten 100 buys, then the strategy keeps signalling into an empty account
+        let fixed = engine.run_backtest_with_data(dca_config(Some(DcaMode::FixedCapital)), &klines).await.unwrap();
+        assert_eq!(fixed.trades.len(), 10);
+        assert_eq!(fixed.metrics.total_invested, Decimal::from(1000));
+        assert!(fixed.trades.iter().all(|trade| trade.balance_remaining >= Decimal::ZERO));
+
+        let external = engine.run_backtest_with_data(dca_config(Some(DcaMode::ExternalContribution)), &klines).await.unwrap();
+        assert!(external.trades.len() > 10);
+        assert_eq!(external.metrics.total_invested, Decimal::from(100) * Decimal::from(external.trades.len() as u64));
+        assert!(external.trades.last().unwrap().timestamp > fixed.trades.last().unwrap().timestamp);
+    }
+
+    #[tokio::test]
+    async fn test_dca_exit_resets_the_cost_basis_on_the_sell_fill() {
+        use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency};
+
+        crate::strategies::init_all_strategies().unwrap();
+
+        let engine = BacktestEngine::new();
+        // Two buys at 100 take profit at 120; the next buy at 100 is a fresh basis, so 111 is +11%
+        let klines = create_klines(&[100, 100, 120, 100, 111]);
+        let parameters = DCAConfig {
+            enable_take_profit: true,
+            take_profit_percentage: Some(Decimal::from(10)),
+            enable_stop_loss: true,
+            stop_loss_percentage: Some(Decimal::from(50)),
+            ..DCAConfig::simple(Decimal::from(100), DCAFrequency::Hourly(1))
+        };
+        let config = BacktestConfig {
+            strategy_name: "dca_v2".to_string(),
+            strategy_parameters: serde_json::to_value(parameters).unwrap(),
+            ..test_config(Vec::new())
+        };
+
+        let result = engine.run_backtest_with_data(config, &klines).await.unwrap();
+        let sells: Vec<bool> = result.trades.iter().map(|trade| matches!(trade.trade_type, TradeType::Sell)).collect();
+        assert_eq!(sells, vec![false, false, true, false, true]);
+        assert_eq!(result.trades[2].quantity, Decimal::from(2));
+        assert_eq!(result.trades[4].quantity, Decimal::ONE);
+    }
+
+    #[tokio::test]
+    async fn test_sensitivity_returns_fall_with_fees_and_find_break_even() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+
+        let engine = BacktestEngine::new();
+        // Buy $500 at 100, sell at 105: +2.5% on the $1000 balance before costs
+        let klines = create_klines(&[100, 101, 102, 103, 105]);
+        let config = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 0, "sell_bar": 4 }),
+            ..test_config(Vec::new())
+        };
+        let fee_levels: Vec<Decimal> = [0, 1, 2, 3].iter().map(|f| Decimal::from(*f)).collect();
+        let slippage_levels = vec![Decimal::ZERO, Decimal::new(5, 1)];
+
+        let report = engine
+            .run_sensitivity_with_data(config, fee_levels, slippage_levels, &klines)
+            .await
+            .unwrap();
+
+        assert_eq!(report.matrix.len(), 4);
+        assert_eq!(report.matrix[0][0].total_return_percentage, Decimal::new(25, 1));
+
+        for column in 0..2 {
+            for row in 1..report.matrix.len() {
+                assert!(
+                    report.matrix[row][column].total_return_percentage
+                        < report.matrix[row - 1][column].total_return_percentage
+                );
+            }
+        }
+
+        // Profitable up to 2% fees without slippage; 2% fees plus 0.5% slippage is the cheapest loss
+        assert!(report.matrix[2][0].profitable);
+        assert!(!report.matrix[3][0].profitable);
+        let break_even = report.break_even.unwrap();
+        assert_eq!(break_even.fee_percentage, Decimal::from(2));
+        assert_eq!(break_even.slippage_percentage, Decimal::new(5, 1));
+    }
+
+    #[tokio::test]
+    async fn test_heikin_ashi_feeds_strategy_but_fills_at_real_prices() {
+        let engine = BacktestEngine::new();
+        let mut klines = create_klines(&[100, 110, 104, 120]);
+        for i in 1..klines.len() {
+            klines[i].open = klines[i - 1].close;
+            klines[i].high = klines[i].open.max(klines[i].close) + Decimal::from(5);
+            klines[i].low = klines[i].open.min(klines[i].close) - Decimal::from(5);
+        }
+        let heikin_ashi = to_heikin_ashi(&klines);
+        let config = BacktestConfig { use_heikin_ashi: true, ..test_config(Vec::new()) };
+        let mut strategy = ScriptedStrategy { buy_bar: Some(1), sell_bar: Some(3), ..Default::default() };
+
+        let (trades, _, _, _) = engine
+            .run_simulation(&klines, &mut strategy, config.initial_balance, &config)
+            .await
+            .unwrap();
+
+        let ha_closes: Vec<Decimal> = heikin_ashi.iter().map(|k| k.close).collect();
+        assert_eq!(strategy.seen_closes, ha_closes);
+        assert_ne!(heikin_ashi[1].close, klines[1].close);
+
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].price, klines[1].close);
+        assert_eq!(trades[0].quantity, Decimal::from(500) / klines[1].close);
+        assert_eq!(trades[1].price, klines[3].close);
+    }
+
+    #[tokio::test]
+    async fn test_next_open_fills_signal_at_following_bar_open() {
+        let engine = BacktestEngine::new();
+        let mut klines = create_klines(&[100, 110, 104, 120]);
+        for i in 1..klines.len() {
+            // Gap each bar away from the previous close
+            klines[i].open = klines[i - 1].close + Decimal::from(3);
+        }
+        let run = |fill_price| {
+            let config = BacktestConfig { fill_price, ..test_config(Vec::new()) };
+            let klines = klines.clone();
+            let engine = &engine;
+            async move {
+                let mut strategy = ScriptedStrategy { buy_bar: Some(1), sell_bar: Some(2), ..Default::default() };
+                engine
+                    .run_simulation(&klines, &mut strategy, config.initial_balance, &config)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let (next_open, next_open_portfolio, _, _) = run(FillPrice::NextOpen).await;
+        assert_eq!(next_open.len(), 2);
+        assert_eq!(next_open[0].price, klines[2].open);
+        assert_eq!(next_open[0].timestamp, klines[2].open_time);
+        assert_eq!(next_open[0].quantity, Decimal::from(500) / klines[2].open);
+        assert_eq!(next_open[1].price, klines[3].open);
+        assert_eq!(next_open[1].timestamp, klines[3].open_time);
+
+        let (close, close_portfolio, _, _) = run(FillPrice::Close).await;
+        assert_eq!(close[0].price, klines[1].close);
+        assert_eq!(close[1].price, klines[2].close);
+        assert_ne!(next_open_portfolio.cash_balance, close_portfolio.cash_balance);
+    }
+
+    #[test]
+    fn test_saved_config_without_fill_price_fills_at_close() {
+        let mut value = serde_json::to_value(test_config(Vec::new())).unwrap();
+        value.as_object_mut().unwrap().remove("fill_price");
+        let config: BacktestConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.fill_price, FillPrice::Close);
+
+        let request: BacktestRequest = serde_json::from_value(json!({
+            "symbol": "BTCUSDT",
+            "interval": "1h",
+            "start_date": "2024-01-01",
+            "end_date": "2024-02-01",
+            "initial_balance": "1000",
+            "strategy_name": "dca_v2"
+        }))
+        .unwrap();
+        assert_eq!(request.fill_price, FillPrice::NextOpen);
+    }
+
+    #[tokio::test]
+    async fn test_typical_price_fills_at_bar_average() {
+        let engine = BacktestEngine::new();
+        let mut klines = create_klines(&[100, 110, 104]);
+        klines[1].high = Decimal::from(116);
+        klines[1].low = Decimal::from(98);
+        let config = BacktestConfig { fill_price: FillPrice::Typical, ..test_config(Vec::new()) };
+        let mut strategy = ScriptedStrategy { buy_bar: Some(1), ..Default::default() };
+
+        let (trades, _, _, _) = engine
+            .run_simulation(&klines, &mut strategy, config.initial_balance, &config)
+            .await
+            .unwrap();
+
+        // (116 + 98 + 110) / 3
+        assert_eq!(trades[0].price, Decimal::from(108));
+    }
+
+    #[tokio::test]
+    async fn test_strict_lookahead_flags_a_peeking_strategy() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100, 98, 105, 103]);
+        let peeking = BacktestConfig {
+            strategy_parameters: json!({ "peek": true }),
+            ..test_config(Vec::new())
+        };
+
+        // Unchecked, the peeking strategy buys right before the rise
+        let result = engine.run_backtest_with_data(peeking.clone(), &klines).await.unwrap();
+        assert_eq!(result.trades[0].price, Decimal::from(98));
+
+        let strict = BacktestConfig { strict_lookahead: true, ..peeking };
+        match engine.run_backtest_with_data(strict, &klines).await {
+            Err(AppError::BadRequest(message)) => assert!(message.contains("Look-ahead bias")),
+            other => panic!("expected a look-ahead error, got {:?}", other.map(|r| r.trades.len())),
+        }
+
+        let honest = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 1 }),
+            strict_lookahead: true,
+            ..test_config(Vec::new())
+        };
+        assert!(engine.run_backtest_with_data(honest, &klines).await.is_ok());
+    }
+
+    #[test]
+    fn test_downsample_curve_keeps_extremes_and_trades_within_cap() {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let curve: Vec<EquityPoint> = (0..1000i64)
+            .map(|i| EquityPoint {
+                timestamp: base_time + Duration::minutes(i),
+                portfolio_value: Decimal::from(1000 + (i * 37) % 101) + if i == 613 { Decimal::from(500) } else { Decimal::ZERO },
+            })
+            .collect();
+        let trade_times = [curve[17].timestamp, curve[402].timestamp, curve[998].timestamp];
+
+        let sampled = downsample_curve(curve.clone(), 50, &trade_times, |p| (p.timestamp, p.portfolio_value));
+        assert!(sampled.len() <= 50);
+        let values: Vec<Decimal> = sampled.iter().map(|p| p.portfolio_value).collect();
+        assert_eq!(values.iter().max(), curve.iter().map(|p| p.portfolio_value).max().as_ref());
+        assert_eq!(values.iter().min(), curve.iter().map(|p| p.portfolio_value).min().as_ref());
+        assert_eq!(sampled.first().unwrap().timestamp, curve[0].timestamp);
+        assert_eq!(sampled.last().unwrap().timestamp, curve[999].timestamp);
+        for time in trade_times {
+            assert!(sampled.iter().any(|p| p.timestamp == time));
+        }
+        assert!(sampled.windows(2).all(|pair| pair[0].timestamp < pair[1].timestamp));
+
+        // More trades than the cap leaves room for still respect the cap
+        let busy: Vec<DateTime<Utc>> = curve.iter().step_by(3).map(|p| p.timestamp).collect();
+        let sampled = downsample_curve(curve.clone(), 20, &busy, |p| (p.timestamp, p.portfolio_value));
+        assert_eq!(sampled.len(), 20);
+
+        // Short curves come back untouched
+        assert_eq!(downsample_curve(curve[..30].to_vec(), 50, &trade_times, |p| (p.timestamp, p.portfolio_value)).len(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_max_curve_points_caps_curves_but_not_metrics() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let engine = BacktestEngine::new();
+        let closes: Vec<i64> = (0..24).map(|i| 100 + (i * 7) % 13).collect();
+        let klines = create_klines(&closes);
+        let full_config = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 2, "sell_bar": 15 }),
+            ..test_config(Vec::new())
+        };
+
+        let full = engine.run_backtest_with_data(full_config.clone(), &klines).await.unwrap();
+        let capped = engine
+            .run_backtest_with_data(BacktestConfig { max_curve_points: Some(8), ..full_config }, &klines)
+            .await
+            .unwrap();
+
+        assert_eq!(full.drawdown_curve.len(), klines.len());
+        assert!(capped.drawdown_curve.len() <= 8);
+        assert!(capped.benchmark_curve.len() <= 8);
+        assert_eq!(capped.metrics.max_drawdown, full.metrics.max_drawdown);
+        assert_eq!(capped.metrics.total_return_percentage, full.metrics.total_return_percentage);
+        let deepest = full.drawdown_curve.iter().map(|p| p.drawdown_pct).min();
+        assert_eq!(capped.drawdown_curve.iter().map(|p| p.drawdown_pct).min(), deepest);
+        for trade in &capped.trades {
+            assert!(capped.drawdown_curve.iter().any(|p| p.timestamp == trade.timestamp));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_base_currency_scales_money_values_by_the_fx_rate() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let engine = BacktestEngine::new();
+        let klines = create_klines(&[100, 110, 104, 120, 90, 130]);
+        let config = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 1, "sell_bar": 3 }),
+            ..test_config(Vec::new())
+        };
+        let usd = engine.run_backtest_with_data(config, &klines).await.unwrap();
+        let as_of = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let rate = FxRate { base_currency: "EUR".to_string(), usd_rate: Decimal::new(92, 2), as_of };
+
+        let eur = usd.clone().valued_in(rate.clone());
+
+        let scaled = |value: Decimal| value * rate.usd_rate;
+        assert_eq!(eur.metrics.final_portfolio_value, scaled(usd.metrics.final_portfolio_value));
+        assert_eq!(eur.metrics.total_return, scaled(usd.metrics.total_return));
+        assert_eq!(eur.metrics.realized_pnl, scaled(usd.metrics.realized_pnl));
+        assert_eq!(eur.metrics.total_return_percentage, usd.metrics.total_return_percentage);
+        assert_eq!(eur.trades.len(), usd.trades.len());
+        for (eur_trade, usd_trade) in eur.trades.iter().zip(&usd.trades) {
+            assert_eq!(eur_trade.total_value, scaled(usd_trade.total_value));
+            assert_eq!(eur_trade.portfolio_value, scaled(usd_trade.portfolio_value));
+            assert_eq!(eur_trade.quantity, usd_trade.quantity);
+        }
+        let last = |result: &BacktestResult| result.performance_chart.last().unwrap().portfolio_value;
+        assert_eq!(last(&eur), scaled(last(&usd)));
+
+        let json = serde_json::to_value(&eur).unwrap();
+        assert_eq!(json["valuation"]["base_currency"], "EUR");
+        assert_eq!(json["valuation"]["as_of"], "2024-03-01T00:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn test_resumed_backtest_matches_an_uninterrupted_run() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let db = Arc::new(test_db().await);
+        let user_id = seed_user(&db).await;
+        let result_id = seed_backtest_result(&db, user_id).await;
+
+        let klines = create_klines(&[
+            100, 98, 97, 97, 99, 102, 101, 104, 103, 106, 108, 105,
+            103, 107, 110, 109, 111, 108, 112, 115, 113, 116, 118,
+        ]);
+        let config = BacktestConfig {
+            strategy_parameters: json!({ "buy_bar": 3, "sell_bar": 14 }),
+            fee_percentage: Decimal::new(1, 1),
+            ..test_config(Vec::new())
+        };
+        let uninterrupted = BacktestEngine::new().run_backtest_with_data(config.clone(), &klines).await.unwrap();
+
+        let mut engine = BacktestEngine::new();
+        engine.checkpoints = Some(CheckpointTarget { db: db.clone(), result_id, every_bars: 5 });
+
+        // The first run dies after bar 12, so its last checkpoint is after bar 9 and
+        // holds the open position but not the sell
+        engine.run_backtest_with_data(config, &klines[..13]).await.unwrap();
+        let checkpoint = engine.checkpoint_target(result_id).unwrap().load().await.unwrap();
+        assert_eq!(checkpoint.next_index, 10);
+        assert_eq!(checkpoint.trades.len(), 1);
+        assert!(checkpoint.position.is_open);
+        assert_eq!(checkpoint.strategy_state, json!({ "seen": 10 }));
+
+        // The open position comes back with every digit, not as an API response rounds it
+        assert_eq!(checkpoint.open_positions.len(), 1);
+        assert!(checkpoint.open_positions[0].quantity.scale() > 8);
+        assert_eq!(checkpoint.open_positions[0].quantity, uninterrupted.trades[0].quantity);
+        assert_eq!(checkpoint.trades[0].quantity, uninterrupted.trades[0].quantity);
+
+        let resumed = engine.resume_backtest_with_data(result_id, &klines).await.unwrap();
+        assert_eq!(resumed.trades.len(), 2);
+        assert_eq!(
+            resumed.trades.iter().map(|t| t.pnl).collect::<Vec<_>>(),
+            uninterrupted.trades.iter().map(|t| t.pnl).collect::<Vec<_>>()
+        );
+        assert_eq!(resumed.metrics.final_portfolio_value, uninterrupted.metrics.final_portfolio_value);
+        assert_eq!(resumed.metrics.total_fees, uninterrupted.metrics.total_fees);
+        assert_eq!(
+            serde_json::to_value(&resumed.metrics).unwrap(),
+            serde_json::to_value(&uninterrupted.metrics).unwrap()
+        );
+
+        // Data that no longer lines up with the checkpoint is refused
+        assert!(engine.resume_backtest_with_data(result_id, &klines[5..]).await.is_err());
+    }
+
+    fn portfolio_asset(symbol: &str, buy_bar: u64, sell_bar: u64) -> BacktestConfig {
+        BacktestConfig {
+            symbol: symbol.to_string(),
+            strategy_parameters: json!({ "buy_bar": buy_bar, "sell_bar": sell_bar }),
+            fee_percentage: Decimal::new(1, 1),
+            ..test_config(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_without_rebalancing_is_the_sum_of_isolated_runs() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let engine = BacktestEngine::new();
+        let btc = create_klines(&[100, 102, 101, 105, 108, 104, 110, 112, 109, 115]);
+        let eth = create_klines(&[50, 49, 47, 48, 52, 55, 53, 51, 56, 58]);
+        let configs = vec![portfolio_asset("BTCUSDT", 1, 6), portfolio_asset("ETHUSDT", 2, 8)];
+
+        let portfolio = engine
+            .run_portfolio_backtest_with_data(configs.clone(), &[btc.clone(), eth.clone()], RebalancePolicy::None)
+            .await
+            .unwrap();
+
+        let mut isolated_curves = Vec::new();
+        let mut isolated_final = Decimal::ZERO;
+        for (config, data) in configs.iter().zip([&btc, &eth]) {
+            let mut strategy = create_strategy(&config.strategy_name).unwrap();
+            let (_, end_state, _, curve) = engine
+                .run_simulation(data, &mut *strategy, config.initial_balance, config)
+                .await
+                .unwrap();
+            isolated_final += end_state.total_value;
+            isolated_curves.push(curve);
+        }
+
+        assert_eq!(portfolio.rebalances, 0);
+        assert_eq!(portfolio.initial_balance, Decimal::from(2000));
+        assert_eq!(portfolio.final_portfolio_value, isolated_final);
+        assert_eq!(portfolio.equity_curve.len(), btc.len());
+        for (i, point) in portfolio.equity_curve.iter().enumerate() {
+            assert_eq!(point.timestamp, btc[i].close_time);
+            assert_eq!(
+                point.portfolio_value,
+                isolated_curves[0][i].portfolio_value + isolated_curves[1][i].portfolio_value
+            );
+        }
+
+        // Attribution adds up to the combined return
+        assert_eq!(portfolio.assets.iter().map(|asset| asset.pnl).sum::<Decimal>(), portfolio.total_return);
+        assert!(portfolio.assets.iter().all(|asset| asset.net_transfers.is_zero()));
+        assert_eq!(portfolio.assets[0].weight_pct, Decimal::from(50));
+        assert_eq!(portfolio.assets[1].result.trades.len(), 2);
+
+        // One series per asset, each symbol once
+        assert!(engine
+            .run_portfolio_backtest_with_data(configs.clone(), &[btc.clone()], RebalancePolicy::None)
+            .await
+            .is_err());
+        let twice = vec![configs[0].clone(), configs[0].clone()];
+        assert!(engine
+            .run_portfolio_backtest_with_data(twice, &[btc.clone(), btc], RebalancePolicy::None)
+            .await
+            .is_err());
+        assert!(engine
+            .run_portfolio_backtest_with_data(configs, &[eth.clone(), eth], RebalancePolicy::Periodic { every_bars: 0 })
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_carries_an_asset_forward_over_bars_it_lacks() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let btc = create_klines(&[100, 101, 102, 103, 104, 105]);
+        // SOL lists on the third bar and has no fifth bar
+        let mut sol = create_klines(&[20, 20, 20, 22, 24, 26]);
+        sol.drain(..2);
+        sol.remove(2);
+        let configs = vec![portfolio_asset("BTCUSDT", 0, 4), portfolio_asset("SOLUSDT", 0, 9)];
+
+        let portfolio = BacktestEngine::new()
+            .run_portfolio_backtest_with_data(configs, &[btc.clone(), sol], RebalancePolicy::None)
+            .await
+            .unwrap();
+
+        let btc_curve = &portfolio.assets[0].equity_curve;
+        let sol_curve = &portfolio.assets[1].equity_curve;
+        assert_eq!(sol_curve.len(), 3);
+        assert_eq!(portfolio.assets[1].result.trades[0].timestamp, btc[2].close_time);
+
+        let sol_values = [
+            Decimal::from(1000),
+            Decimal::from(1000),
+            sol_curve[0].portfolio_value,
+            sol_curve[1].portfolio_value,
+            sol_curve[1].portfolio_value,
+            sol_curve[2].portfolio_value,
+        ];
+        assert_eq!(portfolio.equity_curve.len(), btc.len());
+        for (i, point) in portfolio.equity_curve.iter().enumerate() {
+            assert_eq!(point.portfolio_value, btc_curve[i].portfolio_value + sol_values[i], "bar {}", i);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_periodic_rebalance_pools_idle_cash() {
+        register_strategy(ScriptedFactory { metadata: scripted_metadata() }).unwrap();
+        let btc = create_klines(&[100, 102, 104, 106, 108, 110, 112, 114, 116, 118]);
+        let eth = create_klines(&[50; 10]);
+        // BTC buys $500 on bar 1 and holds; ETH never trades
+        let configs = vec![
+            BacktestConfig { fee_percentage: Decimal::ZERO, ..portfolio_asset("BTCUSDT", 1, 99) },
+            BacktestConfig { fee_percentage: Decimal::ZERO, ..portfolio_asset("ETHUSDT", 99, 99) },
+        ];
+
+        let portfolio = BacktestEngine::new()
+            .run_portfolio_backtest_with_data(configs, &[btc, eth], RebalancePolicy::Periodic { every_bars: 4 })
+            .await
+            .unwrap();
+
+        // After bar 3 the $1,500 of idle cash is split evenly; after bar 7 it already is
+        assert_eq!(portfolio.rebalances, 1);
+        let (btc_asset, eth_asset) = (&portfolio.assets[0], &portfolio.assets[1]);
+        let tolerance = Decimal::new(1, 20);
+        assert!((btc_asset.net_transfers - Decimal::from(250)).abs() < tolerance);
+        assert!((btc_asset.net_transfers + eth_asset.net_transfers).abs() < tolerance);
+
+        let eth_values: Vec<Decimal> = eth_asset.equity_curve.iter().map(|point| point.portfolio_value).collect();
+        assert!(eth_values[..4].iter().all(|value| *value == Decimal::from(1000)));
+        assert!(eth_values[4..].iter().all(|value| (*value - Decimal::from(750)).abs() < tolerance));
+
+        // The idle asset made nothing; the gain is all BTC's
+        assert!(eth_asset.pnl.abs() < tolerance);
+        assert!(btc_asset.pnl > Decimal::ZERO);
+        assert!((btc_asset.pnl + eth_asset.pnl - portfolio.total_return).abs() < tolerance);
+        assert_eq!(btc_asset.result.trades.len(), 2);
+    }
+}
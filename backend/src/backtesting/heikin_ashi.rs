@@ -0,0 +1,85 @@
+use rust_decimal::Decimal;
+
+use crate::exchange_connectors::Kline;
+
+/// Convert standard klines to Heikin-Ashi candles.
+///
+/// - close = (open + high + low + close) / 4
+/// - open = (previous HA open + previous HA close) / 2, seeded with (open + close) / 2
+/// - high = max(high, HA open, HA close), low = min(low, HA open, HA close)
+///
+/// Times and volumes are carried over unchanged.
+pub fn to_heikin_ashi(data: &[Kline]) -> Vec<Kline> {
+    let two = Decimal::from(2);
+    let four = Decimal::from(4);
+
+    let mut candles: Vec<Kline> = Vec::with_capacity(data.len());
+    for kline in data {
+        let close = (kline.open + kline.high + kline.low + kline.close) / four;
+        let open = match candles.last() {
+            Some(previous) => (previous.open + previous.close) / two,
+            None => (kline.open + kline.close) / two,
+        };
+
+        candles.push(Kline {
+            open,
+            high: kline.high.max(open).max(close),
+            low: kline.low.min(open).min(close),
+            close,
+            ..kline.clone()
+        });
+    }
+
+    candles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn kline(hour: i64, open: i64, high: i64, low: i64, close: i64) -> Kline {
+        let open_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::hours(hour);
+        Kline {
+            open_time,
+            close_time: open_time + Duration::minutes(59),
+            open: Decimal::from(open),
+            high: Decimal::from(high),
+            low: Decimal::from(low),
+            close: Decimal::from(close),
+            volume: Decimal::from(10),
+            quote_asset_volume: Decimal::from(1000),
+            number_of_trades: 5,
+            taker_buy_base_asset_volume: Decimal::from(5),
+            taker_buy_quote_asset_volume: Decimal::from(500),
+        }
+    }
+
+    #[test]
+    fn test_heikin_ashi_recurrence() {
+        let data = vec![
+            kline(0, 100, 110, 90, 104),
+            kline(1, 104, 112, 100, 108),
+            kline(2, 108, 109, 95, 96),
+        ];
+        let candles = to_heikin_ashi(&data);
+
+        assert_eq!(candles.len(), 3);
+        // First candle is seeded from the real open and close
+        assert_eq!(candles[0].open, Decimal::from(102));
+        assert_eq!(candles[0].close, Decimal::from(101));
+        assert_eq!(candles[0].high, Decimal::from(110));
+        assert_eq!(candles[0].low, Decimal::from(90));
+
+        for i in 1..data.len() {
+            let real = &data[i];
+            let ha = &candles[i];
+            assert_eq!(ha.close, (real.open + real.high + real.low + real.close) / Decimal::from(4));
+            assert_eq!(ha.open, (candles[i - 1].open + candles[i - 1].close) / Decimal::from(2));
+            assert_eq!(ha.high, real.high.max(ha.open).max(ha.close));
+            assert_eq!(ha.low, real.low.min(ha.open).min(ha.close));
+            assert_eq!(ha.open_time, real.open_time);
+            assert_eq!(ha.volume, real.volume);
+        }
+    }
+}
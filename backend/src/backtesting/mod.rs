@@ -3,9 +3,19 @@ pub mod types;
 pub mod data_cache;
 pub mod binance_fetcher;
 pub mod stock_fetcher;
+pub mod data_validation;
+pub mod diff;
+pub mod replay;
+pub mod heikin_ashi;
+pub mod robustness;
+
+#[cfg(test)]
+pub mod scenarios;
 
 pub use engine::BacktestEngine;
 pub use types::*;
 pub use data_cache::get_cache;
 pub use binance_fetcher::BinanceFetcher;
-pub use stock_fetcher::StockFetcher;
\ No newline at end of file
+pub use stock_fetcher::StockFetcher;
+pub use robustness::RobustnessScore;
+pub use data_validation::{AnomalyAction, DataValidationConfig, ValidationReport};
\ No newline at end of file
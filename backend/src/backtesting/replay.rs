@@ -0,0 +1,594 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::backtesting::types::TradeType;
+use crate::exchange_connectors::common_types::{
+    OcoOrder, Order, OrderSide, OrderStatus, OrderType, TimeInForce, WalletType,
+};
+use crate::exchange_connectors::traits::{ExchangeConnector, TradeExecutionAPI};
+use crate::exchange_connectors::{ExchangeError, Kline};
+use crate::strategies::core::traits::{
+    OrderStatus as StrategyOrderStatus, OrderType as StrategyOrderType, OrderUpdate,
+};
+use crate::strategies::{
+    apply_min_confidence, create_strategy, min_confidence_from_parameters, LiveExecutableStrategy, MarketData, QuantityType,
+    StrategyContext, StrategyMode, StrategySignal, StrategySignalType,
+};
+use crate::utils::errors::AppError;
+
+/// How fast historical bars are fed to the strategy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ReplayPace {
+    /// As fast as the strategy can process bars
+    #[default]
+    Instant,
+    /// `speed` bars' worth of time per second of wall-clock time (60 = a 1h bar per minute)
+    Accelerated { speed: f64 },
+    /// One bar per bar interval, exactly as live
+    RealTime,
+}
+
+impl ReplayPace {
+    /// Wall-clock wait before feeding a bar spanning `bar_duration`
+    pub fn delay(&self, bar_duration: Duration) -> Duration {
+        match self {
+            Self::Instant => Duration::ZERO,
+            Self::Accelerated { speed } if *speed > 0.0 => bar_duration.div_f64(*speed),
+            Self::Accelerated { .. } => Duration::ZERO,
+            Self::RealTime => bar_duration,
+        }
+    }
+}
+
+/// Request to replay historical data through a strategy's live code path
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayRequest {
+    pub strategy_name: String,
+    #[serde(default)]
+    pub strategy_parameters: Option<serde_json::Value>,
+    pub symbol: String,
+    pub interval: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub initial_balance: Decimal,
+    #[serde(default)]
+    pub pace: ReplayPace,
+}
+
+/// A paper fill made during a replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayTrade {
+    pub timestamp: DateTime<Utc>,
+    pub trade_type: TradeType,
+    #[serde(with = "crate::utils::precision::crypto")]
+    pub price: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
+    pub quantity: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub total_value: Decimal,
+    pub reason: String,
+}
+
+/// Account state at the end of a replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySummary {
+    pub bars: usize,
+    pub trades: Vec<ReplayTrade>,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub cash_balance: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
+    pub asset_quantity: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub portfolio_value: Decimal,
+}
+
+/// Progress streamed to the client while a replay runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ReplayEvent {
+    Bar {
+        timestamp: DateTime<Utc>,
+        #[serde(with = "crate::utils::precision::crypto")]
+        price: Decimal,
+        #[serde(with = "crate::utils::precision::usd")]
+        portfolio_value: Decimal,
+    },
+    Trade(ReplayTrade),
+    Finished(ReplaySummary),
+    Error { message: String },
+}
+
+/// Create the live implementation of a registered strategy
+pub fn live_strategy(strategy_name: &str) -> Result<Box<dyn LiveExecutableStrategy>, AppError> {
+    create_strategy(strategy_name)?
+        .into_live()
+        .ok_or_else(|| AppError::BadRequest(format!("Strategy {} has no live implementation", strategy_name)))
+}
+
+/// Paper account that fills market orders at the replayed bar's close
+struct PaperExchange {
+    state: Mutex<PaperAccount>,
+}
+
+struct PaperAccount {
+    price: Decimal,
+    time: DateTime<Utc>,
+    cash: Decimal,
+    asset: Decimal,
+    next_order_id: u64,
+}
+
+impl PaperExchange {
+    fn new(initial_balance: Decimal) -> Self {
+        Self {
+            state: Mutex::new(PaperAccount {
+                price: Decimal::ZERO,
+                time: Utc::now(),
+                cash: initial_balance,
+                asset: Decimal::ZERO,
+                next_order_id: 1,
+            }),
+        }
+    }
+
+    fn set_bar(&self, kline: &Kline) {
+        let mut account = self.state.lock().unwrap();
+        account.price = kline.close;
+        account.time = kline.close_time;
+    }
+
+    fn balances(&self) -> (Decimal, Decimal) {
+        let account = self.state.lock().unwrap();
+        (account.cash, account.asset)
+    }
+}
+
+#[async_trait]
+impl ExchangeConnector for PaperExchange {
+    async fn test_connection(&self) -> Result<bool, ExchangeError> {
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl TradeExecutionAPI for PaperExchange {
+    async fn place_market_order(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        quantity: Option<Decimal>,
+        quote_quantity: Option<Decimal>,
+        wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        let mut account = self.state.lock().unwrap();
+        let price = account.price;
+        let (quantity, cost) = match (quantity, quote_quantity) {
+            (_, Some(quote)) => (quote / price, quote),
+            (Some(quantity), None) => (quantity, quantity * price),
+            (None, None) => return Err(ExchangeError::InvalidOrder("Order quantity required".to_string())),
+        };
+
+        match side {
+            OrderSide::Buy if account.cash < cost => {
+                return Err(ExchangeError::InsufficientBalance(format!("{} needed, {} available", cost, account.cash)));
+            }
+            OrderSide::Buy => {
+                account.cash -= cost;
+                account.asset += quantity;
+            }
+            OrderSide::Sell if account.asset < quantity => {
+                return Err(ExchangeError::InsufficientBalance(format!("{} needed, {} held", quantity, account.asset)));
+            }
+            OrderSide::Sell => {
+                account.asset -= quantity;
+                account.cash += cost;
+            }
+        }
+
+        let order_id = account.next_order_id;
+        account.next_order_id += 1;
+        Ok(Order {
+            order_id: format!("paper-{}", order_id),
+            client_order_id: None,
+            symbol: symbol.to_string(),
+            side,
+            order_type: OrderType::Market,
+            status: OrderStatus::Filled,
+            time_in_force: TimeInForce::GTC,
+            price: None,
+            stop_price: None,
+            quantity,
+            executed_quantity: quantity,
+            cumulative_quote_quantity: cost,
+            average_price: Some(price),
+            fee: Some(Decimal::ZERO),
+            fee_asset: None,
+            pnl: None,
+            created_time: account.time,
+            updated_time: account.time,
+            wallet_type,
+        })
+    }
+
+    async fn place_limit_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: TimeInForce, _: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Paper replay fills market orders only".to_string()))
+    }
+
+    async fn place_stop_loss_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Paper replay fills market orders only".to_string()))
+    }
+
+    async fn place_take_profit_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Paper replay fills market orders only".to_string()))
+    }
+
+    async fn place_oco_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<OcoOrder, ExchangeError> {
+        Err(ExchangeError::NotSupported("Paper replay fills market orders only".to_string()))
+    }
+}
+
+/// Market order a signal turns into: (side, base quantity, quote amount), sized the
+/// same way the backtest engine sizes its fills
+fn signal_order(signal: &StrategySignal, price: Decimal, cash: Decimal, position: Decimal) -> Option<(OrderSide, Option<Decimal>, Option<Decimal>)> {
+    let buy_amount = |quantity: &QuantityType| match quantity {
+        QuantityType::DollarAmount(amount) => *amount,
+        QuantityType::Fixed(quantity) => *quantity * price,
+        QuantityType::BalancePercentage(pct) => cash * pct / Decimal::from(100),
+        _ => Decimal::from(100),
+    };
+
+    match signal.signal_type {
+        StrategySignalType::Enter if position > Decimal::ZERO => None,
+        StrategySignalType::Enter | StrategySignalType::AddToPosition => {
+            Some((OrderSide::Buy, None, Some(buy_amount(&signal.action.quantity))))
+        }
+        StrategySignalType::Exit => {
+            if position <= Decimal::ZERO {
+                return None;
+            }
+            let quantity = match &signal.action.quantity {
+                QuantityType::Fixed(quantity) => *quantity,
+                QuantityType::PositionPercentage(pct) => position * pct / Decimal::from(100),
+                _ => position,
+            };
+            Some((OrderSide::Sell, Some(quantity.min(position)), None))
+        }
+        StrategySignalType::ReducePosition => {
+            let quantity = match &signal.action.quantity {
+                QuantityType::Fixed(quantity) => *quantity,
+                QuantityType::PositionPercentage(pct) => position * pct / Decimal::from(100),
+                _ => position,
+            };
+            (quantity <= position).then_some((OrderSide::Sell, Some(quantity), None))
+        }
+        _ => None,
+    }
+}
+
+/// Feeds historical klines through a strategy's live code path in paper mode
+pub struct StrategyReplay {
+    symbol: String,
+    interval: String,
+    initial_balance: Decimal,
+    pace: ReplayPace,
+}
+
+impl StrategyReplay {
+    pub fn new(symbol: &str, interval: &str, initial_balance: Decimal, pace: ReplayPace) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+            initial_balance,
+            pace,
+        }
+    }
+
+    /// Replay `klines` through `strategy`. Progress is sent to `events` as it happens;
+    /// the replay stops early if the receiving side goes away.
+    pub async fn run(
+        &self,
+        strategy: &mut dyn LiveExecutableStrategy,
+        parameters: &serde_json::Value,
+        klines: &[Kline],
+        events: Option<UnboundedSender<ReplayEvent>>,
+    ) -> Result<ReplaySummary, AppError> {
+        let first = klines
+            .first()
+            .ok_or_else(|| AppError::BadRequest("No historical data to replay".to_string()))?;
+        let min_confidence = min_confidence_from_parameters(parameters)?;
+        let exchange = PaperExchange::new(self.initial_balance);
+        let strategy_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+
+        let context_at = |index: usize, cash: Decimal| StrategyContext {
+            strategy_id,
+            user_id,
+            symbol: self.symbol.clone(),
+            interval: self.interval.clone(),
+            mode: StrategyMode::Paper,
+            current_time: klines[index].close_time,
+            historical_data: klines[..=index].to_vec(),
+            current_price: klines[index].close,
+            available_balance: cash,
+            current_positions: Vec::new(),
+            market_data: MarketData::default(),
+        };
+
+        let mut init_context = context_at(0, self.initial_balance);
+        init_context.current_time = first.open_time;
+        init_context.historical_data = klines.to_vec();
+        strategy.initialize(parameters, StrategyMode::Paper, &init_context).await?;
+        strategy.start_live_execution(&init_context).await?;
+        info!("Replaying {} bars of {} through {}", klines.len(), self.symbol, strategy.metadata().id);
+
+        let emit = |event: ReplayEvent| match &events {
+            Some(sender) => sender.send(event).is_ok(),
+            None => true,
+        };
+
+        let mut trades = Vec::new();
+        let mut bars = 0;
+        for (index, kline) in klines.iter().enumerate() {
+            if index > 0 {
+                let bar_duration = (kline.open_time - klines[index - 1].open_time).to_std().unwrap_or_default();
+                let delay = self.pace.delay(bar_duration);
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            if !strategy.is_running() {
+                break;
+            }
+
+            exchange.set_bar(kline);
+            bars += 1;
+            let (cash, position) = exchange.balances();
+            let context = context_at(index, cash);
+
+            // Scheduled strategies are only woken once they are due, as in production
+            let due = strategy.next_execution_time().map_or(true, |next| kline.close_time >= next);
+            let signal = if due { strategy.analyze(&context).await? } else { None };
+            let signal = signal
+                .and_then(|s| apply_min_confidence(s, min_confidence))
+                .and_then(|s| s.resolve_volatility_target(&klines[..=index], cash))
+                // Replays keep no realized P&L, so Kelly orders use the starting bet
+                .map(|s| s.resolve_kelly(None, cash));
+
+            if let Some(signal) = signal {
+                if let Some(trade) = self.fill(&exchange, strategy, &signal, kline, cash, position).await {
+                    if !emit(ReplayEvent::Trade(trade.clone())) {
+                        break;
+                    }
+                    trades.push(trade);
+                }
+            }
+
+            let (cash, position) = exchange.balances();
+            if !emit(ReplayEvent::Bar {
+                timestamp: kline.close_time,
+                price: kline.close,
+                portfolio_value: cash + position * kline.close,
+            }) {
+                debug!("Replay client disconnected after {} bars", bars);
+                break;
+            }
+        }
+
+        strategy.stop_live_execution().await?;
+
+        let (cash_balance, asset_quantity) = exchange.balances();
+        let last_price = klines.last().map(|k| k.close).unwrap_or_default();
+        let summary = ReplaySummary {
+            bars,
+            trades,
+            cash_balance,
+            asset_quantity,
+            portfolio_value: cash_balance + asset_quantity * last_price,
+        };
+        emit(ReplayEvent::Finished(summary.clone()));
+        Ok(summary)
+    }
+
+    /// Place the paper order for a signal and report the fill back to the strategy
+    async fn fill(
+        &self,
+        exchange: &PaperExchange,
+        strategy: &mut dyn LiveExecutableStrategy,
+        signal: &StrategySignal,
+        kline: &Kline,
+        cash: Decimal,
+        position: Decimal,
+    ) -> Option<ReplayTrade> {
+        let (side, quantity, quote_quantity) = signal_order(signal, kline.close, cash, position)?;
+        let order = match exchange
+            .place_market_order(&self.symbol, side.clone(), quantity, quote_quantity, WalletType::Spot)
+            .await
+        {
+            Ok(order) => order,
+            Err(e) => {
+                warn!("Replay order for {} rejected: {}", self.symbol, e);
+                return None;
+            }
+        };
+
+        let update = OrderUpdate {
+            order_id: order.order_id.clone(),
+            symbol: self.symbol.clone(),
+            side: side.clone(),
+            order_type: StrategyOrderType::Market,
+            status: StrategyOrderStatus::Filled,
+            quantity: order.quantity,
+            price: Some(kline.close),
+            filled_quantity: order.executed_quantity,
+            timestamp: kline.close_time,
+        };
+        if let Err(e) = strategy.on_order_update(&update).await {
+            warn!("Strategy rejected replay order update: {}", e);
+        }
+
+        Some(ReplayTrade {
+            timestamp: kline.close_time,
+            trade_type: match side {
+                OrderSide::Buy => TradeType::Buy,
+                OrderSide::Sell => TradeType::Sell,
+            },
+            price: kline.close,
+            quantity: order.executed_quantity,
+            total_value: order.cumulative_quote_quantity,
+            reason: signal.reason.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration as ChronoDuration, TimeZone};
+    use crate::backtesting::{BacktestConfig, BacktestEngine, FillPrice, ShortDataAction};
+    use crate::exchange_connectors::KlineInterval;
+    use crate::strategies::implementations::sma_crossover::SMACrossoverConfig;
+
+    fn create_klines(closes: &[i64]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let price = Decimal::from(*close);
+                Kline {
+                    open_time: base_time + ChronoDuration::hours(i as i64),
+                    close_time: base_time + ChronoDuration::hours(i as i64) + ChronoDuration::minutes(59),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::from(1000),
+                    quote_asset_volume: Decimal::from(1000) * price,
+                    number_of_trades: 100,
+                    taker_buy_base_asset_volume: Decimal::from(500),
+                    taker_buy_quote_asset_volume: Decimal::from(500) * price,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pace_delay() {
+        let hour = Duration::from_secs(3600);
+        assert_eq!(ReplayPace::Instant.delay(hour), Duration::ZERO);
+        assert_eq!(ReplayPace::Accelerated { speed: 60.0 }.delay(hour), Duration::from_secs(60));
+        assert_eq!(ReplayPace::RealTime.delay(hour), hour);
+    }
+
+    #[test]
+    fn test_live_strategies_come_from_the_registry() {
+        crate::strategies::init_all_strategies().unwrap();
+
+        for strategy_id in [
+            "dca_v2",
+            "sma_crossover_v2",
+            "grid_trading_v2",
+            "rebalance_v1",
+            "ma_ribbon_v1",
+            "rsi_macd_confluence_v1",
+        ] {
+            let strategy = live_strategy(strategy_id).unwrap();
+            assert_eq!(strategy.metadata().id, strategy_id);
+        }
+        assert!(matches!(live_strategy("no_such_strategy"), Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_replay_through_live_path_matches_backtest_trades() {
+        crate::strategies::init_all_strategies().unwrap();
+
+        let klines = create_klines(&[
+            100, 98, 96, 95, 97, 100, 104, 108, 110, 107,
+            103, 99, 96, 94, 97, 101, 105, 109, 112, 108,
+        ]);
+        let parameters = serde_json::to_value(SMACrossoverConfig::aggressive(2, 3)).unwrap();
+        let start_time = klines[0].open_time;
+
+        let config = BacktestConfig {
+            symbol: "BTCUSDT".to_string(),
+            interval: KlineInterval::OneHour,
+            start_time,
+            end_time: start_time + ChronoDuration::days(1),
+            initial_balance: Decimal::from(1000),
+            strategy_name: "sma_crossover_v2".to_string(),
+            strategy_type: None,
+            strategy_parameters: parameters.clone(),
+            stop_loss_percentage: None,
+            take_profit_percentage: None,
+            unlimited_capital: false,
+            dca_mode: None,
+            asset_type: "crypto".to_string(),
+            initial_positions: Vec::new(),
+            fee_percentage: Decimal::ZERO,
+            fee_tier: None,
+            fee_token_discount: false,
+            maker_fee_percentage: None,
+            taker_fee_percentage: None,
+            slippage_percentage: Decimal::ZERO,
+            fill_price: FillPrice::Close,
+            apply_min_confidence: false,
+            use_heikin_ashi: false,
+            log_trades: false,
+            strict_lookahead: false,
+            benchmark_symbol: None,
+            benchmark_asset_type: None,
+            min_data_coverage_pct: Decimal::from(95),
+            on_short_data: ShortDataAction::Warn,
+            max_curve_points: None,
+            risk_free_rate: Decimal::from(2),
+            periods_per_year: None,
+        };
+        let backtest = BacktestEngine::new().run_backtest_with_data(config, &klines).await.unwrap();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut strategy = live_strategy("sma_crossover_v2").unwrap();
+        let replay = StrategyReplay::new("BTCUSDT", "1h", Decimal::from(1000), ReplayPace::Instant)
+            .run(strategy.as_mut(), &parameters, &klines, Some(sender))
+            .await
+            .unwrap();
+
+        // The backtest force-closes open positions at the end; live trading does not
+        let backtest_trades: Vec<_> = backtest
+            .trades
+            .iter()
+            .filter(|t| t.reason != "End of backtest period")
+            .map(|t| (t.timestamp, matches!(t.trade_type, TradeType::Buy), t.price, t.quantity))
+            .collect();
+        let replay_trades: Vec<_> = replay
+            .trades
+            .iter()
+            .map(|t| (t.timestamp, matches!(t.trade_type, TradeType::Buy), t.price, t.quantity))
+            .collect();
+
+        assert!(!replay_trades.is_empty());
+        assert_eq!(replay_trades, backtest_trades);
+        assert_eq!(replay.bars, klines.len());
+        assert!(!strategy.is_running());
+
+        let mut streamed_trades = 0;
+        let mut finished = false;
+        while let Ok(event) = receiver.try_recv() {
+            match event {
+                ReplayEvent::Trade(_) => streamed_trades += 1,
+                ReplayEvent::Finished(_) => finished = true,
+                _ => {}
+            }
+        }
+        assert_eq!(streamed_trades, replay_trades.len());
+        assert!(finished);
+    }
+}
@@ -0,0 +1,133 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::backtesting::types::{BacktestMetrics, BacktestTrade};
+
+// Component weights, in percent of the final score
+const SHARPE_WEIGHT: i64 = 35;
+const DRAWDOWN_WEIGHT: i64 = 30;
+const TRADE_COUNT_WEIGHT: i64 = 15;
+const COST_WEIGHT: i64 = 20;
+
+/// Sharpe ratio that earns the full Sharpe component
+const FULL_SHARPE: i64 = 2;
+/// Max drawdown, in percent, at which the drawdown component reaches zero
+const ZERO_SCORE_DRAWDOWN_PCT: i64 = 50;
+/// Closed trades needed for the full trade count component
+const FULL_TRADE_COUNT: u32 = 30;
+/// Below this many closed trades the result is mostly luck, so the score is capped
+const MIN_TRADES: u32 = 10;
+const FEW_TRADES_CAP: i64 = 40;
+/// Extra cost per fill, in percent, the cost component stresses the run with
+const COST_STRESS_PCT: Decimal = Decimal::from_parts(1, 0, 0, false, 1);
+
+/// 0-100 summary of how much a backtest's result can be trusted, and its parts.
+///
+/// Each component is scored 0-100 and weighted: Sharpe ratio 35% (full marks at 2),
+/// max drawdown 30% (full marks at none, zero at 50%), closed trade count 15% (full
+/// marks at 30) and cost sensitivity 20% (the share of the profit left if every fill
+/// paid another 0.1%). Fewer than 10 closed trades caps the score at 40.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RobustnessScore {
+    pub score: Decimal,
+    pub sharpe: Decimal,
+    pub drawdown: Decimal,
+    pub trade_count: Decimal,
+    pub cost_sensitivity: Decimal,
+    /// Whether too few closed trades held the score down
+    pub capped_by_trade_count: bool,
+}
+
+impl RobustnessScore {
+    pub fn from_metrics(metrics: &BacktestMetrics, trades: &[BacktestTrade]) -> Self {
+        let traded_notional = trades.iter().map(|t| t.total_value.abs()).sum();
+        Self::new(
+            metrics.sharpe_ratio,
+            metrics.max_drawdown,
+            metrics.closed_trades,
+            metrics.total_return,
+            traded_notional,
+        )
+    }
+
+    fn new(
+        sharpe_ratio: Option<Decimal>,
+        max_drawdown_pct: Decimal,
+        closed_trades: u32,
+        total_return: Decimal,
+        traded_notional: Decimal,
+    ) -> Self {
+        let hundred = Decimal::from(100);
+        let scale = |fraction: Decimal| (fraction.clamp(Decimal::ZERO, Decimal::ONE) * hundred).round_dp(2);
+
+        let sharpe = scale(sharpe_ratio.unwrap_or(Decimal::ZERO) / Decimal::from(FULL_SHARPE));
+        let drawdown = scale(Decimal::ONE - max_drawdown_pct / Decimal::from(ZERO_SCORE_DRAWDOWN_PCT));
+        let trade_count = scale(Decimal::from(closed_trades) / Decimal::from(FULL_TRADE_COUNT));
+        let cost_sensitivity = if total_return > Decimal::ZERO {
+            let stressed = total_return - traded_notional * COST_STRESS_PCT / hundred;
+            scale(stressed / total_return)
+        } else {
+            Decimal::ZERO
+        };
+
+        let weighted = (sharpe * Decimal::from(SHARPE_WEIGHT)
+            + drawdown * Decimal::from(DRAWDOWN_WEIGHT)
+            + trade_count * Decimal::from(TRADE_COUNT_WEIGHT)
+            + cost_sensitivity * Decimal::from(COST_WEIGHT))
+            / hundred;
+        let capped_by_trade_count = closed_trades < MIN_TRADES && weighted > Decimal::from(FEW_TRADES_CAP);
+        let score = if capped_by_trade_count {
+            Decimal::from(FEW_TRADES_CAP)
+        } else {
+            weighted.round_dp(2)
+        };
+
+        Self {
+            score,
+            sharpe,
+            drawdown,
+            trade_count,
+            cost_sensitivity,
+            capped_by_trade_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steady_strategy_beats_high_return_with_deep_drawdown() {
+        // Sharpe 1.8, 8% drawdown, $2,000 profit on $40,000 traded
+        let steady = RobustnessScore::new(Some(Decimal::new(18, 1)), Decimal::from(8), 40, Decimal::from(2000), Decimal::from(40_000));
+        // Triple the profit, but a 45% drawdown and a Sharpe of 0.6
+        let wild = RobustnessScore::new(Some(Decimal::new(6, 1)), Decimal::from(45), 40, Decimal::from(6000), Decimal::from(40_000));
+
+        assert!(steady.score > wild.score);
+        assert!(steady.drawdown > wild.drawdown);
+        assert!(!steady.capped_by_trade_count);
+        // 0.1% of $40,000 is $40 of the $2,000 profit
+        assert_eq!(steady.cost_sensitivity, Decimal::from(98));
+    }
+
+    #[test]
+    fn test_too_few_trades_caps_the_score() {
+        let few = RobustnessScore::new(Some(Decimal::from(3)), Decimal::ZERO, 3, Decimal::from(2000), Decimal::from(3000));
+        let many = RobustnessScore::new(Some(Decimal::from(3)), Decimal::ZERO, 30, Decimal::from(2000), Decimal::from(3000));
+
+        assert!(few.capped_by_trade_count);
+        assert_eq!(few.score, Decimal::from(FEW_TRADES_CAP));
+        assert!(!many.capped_by_trade_count);
+        assert!(many.score > few.score);
+    }
+
+    #[test]
+    fn test_losing_run_gets_no_cost_or_sharpe_credit() {
+        let losing = RobustnessScore::new(Some(Decimal::new(-5, 1)), Decimal::from(60), 20, Decimal::from(-500), Decimal::from(10_000));
+
+        assert_eq!(losing.sharpe, Decimal::ZERO);
+        assert_eq!(losing.drawdown, Decimal::ZERO);
+        assert_eq!(losing.cost_sensitivity, Decimal::ZERO);
+    }
+}
@@ -2,11 +2,14 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc, Duration as ChronoDuration};
 use rust_decimal::Decimal;
 use serde::Deserialize;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use crate::exchange_connectors::Kline;
+use crate::exchange_connectors::{Kline, KlineInterval};
+use crate::services::stock_data_service::alpha_vantage_retry_policy;
 use crate::utils::errors::AppError;
+use crate::utils::http::{request_with_policy, RetryPolicy};
 use super::data_cache::{get_cache, DataCache};
+use super::data_validation::{validate_klines, DataValidationConfig};
 
 /// Alpha Vantage API base URL
 const ALPHA_VANTAGE_BASE: &str = "https://www.alphavantage.co/query";
@@ -42,6 +45,8 @@ pub struct StockFetcher {
     client: reqwest::Client,
     cache: Arc<DataCache>,
     api_key: String,
+    validation: DataValidationConfig,
+    retry_policy: RetryPolicy,
 }
 
 impl StockFetcher {
@@ -56,15 +61,23 @@ impl StockFetcher {
             client,
             cache: get_cache(),
             api_key,
+            validation: DataValidationConfig::default(),
+            retry_policy: alpha_vantage_retry_policy(),
         }
     }
 
+    /// Use different price sanity checks for fetched data
+    pub fn with_validation(mut self, validation: DataValidationConfig) -> Self {
+        self.validation = validation;
+        self
+    }
+
     /// Fetch historical stock data
     /// Note: Alpha Vantage only supports daily intervals for historical data
     pub async fn fetch_klines(
         &self,
         symbol: &str,
-        interval: &crate::exchange_connectors::KlineInterval,
+        interval: &KlineInterval,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> Result<Vec<Kline>, AppError> {
@@ -75,7 +88,7 @@ impl StockFetcher {
 
         // Alpha Vantage only supports daily intervals for historical data
         // For backtesting purposes, we'll use daily data
-        if !matches!(interval.as_str(), "1d" | "1w" | "1M") {
+        if !matches!(interval, KlineInterval::OneDay | KlineInterval::OneWeek | KlineInterval::OneMonth) {
             return Err(AppError::BadRequest(
                 "Stock backtesting only supports daily (1d), weekly (1w), or monthly (1M) intervals".to_string()
             ));
@@ -121,6 +134,14 @@ impl StockFetcher {
             )));
         }
 
+        let (filtered_klines, report) = validate_klines(&symbol_upper, filtered_klines, &self.validation)?;
+        if !report.is_clean() {
+            warn!(
+                "Dropped {} of {} bars for stock {} that failed price validation: {:?}",
+                report.bars_removed, report.bars_checked, symbol_upper, report.anomalies
+            );
+        }
+
         // Store in cache for future requests
         self.cache
             .store(&symbol_upper, interval, start_time, end_time, filtered_klines.clone())
@@ -132,15 +153,16 @@ impl StockFetcher {
     /// Fetch daily data from Alpha Vantage
     /// Returns full dataset (outputsize=full gives 20+ years of data)
     async fn fetch_daily_data(&self, symbol: &str) -> Result<Vec<Kline>, AppError> {
-        let response = self.client
+        let request = self.client
             .get(ALPHA_VANTAGE_BASE)
             .query(&[
                 ("function", "TIME_SERIES_DAILY"),
                 ("symbol", symbol),
                 ("outputsize", "full"), // Get full historical data
                 ("apikey", &self.api_key),
-            ])
-            .send()
+            ]);
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch stock data from Alpha Vantage: {}", e);
@@ -2,6 +2,13 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use crate::exchange_connectors::{KlineInterval};
+use crate::exchange_connectors::fees::FeeSchedule;
+use crate::backtesting::robustness::RobustnessScore;
+use crate::services::fx_rates::FxRate;
+use crate::strategies::StrategySignal;
+use crate::utils::errors::AppError;
+use crate::utils::precision::{round_crypto, round_usd};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BacktestConfig {
@@ -20,23 +27,298 @@ pub struct BacktestConfig {
     /// When true, capital is "injected" for each buy, simulating ongoing income
     #[serde(default)]
     pub unlimited_capital: bool,
+    /// How accumulation buys are funded; unset falls back to `unlimited_capital`
+    /// (always on for DCA strategies)
+    #[serde(default)]
+    pub dca_mode: Option<DcaMode>,
     /// Asset type: "crypto" or "stock"
     #[serde(default = "default_asset_type")]
     pub asset_type: String,
+    /// Holdings the portfolio starts with in addition to `initial_balance`
+    #[serde(default)]
+    pub initial_positions: Vec<InitialPosition>,
+    /// Fee charged on every fill, as a percentage of the fill's notional
+    #[serde(default)]
+    pub fee_percentage: Decimal,
+    /// Binance VIP tier whose maker/taker rates replace `fee_percentage`
+    #[serde(default)]
+    pub fee_tier: Option<u8>,
+    /// Apply the BNB fee discount to the `fee_tier` rates
+    #[serde(default)]
+    pub fee_token_discount: bool,
+    /// Fee on resting limit fills, in percent, overriding the flat or tier maker rate
+    #[serde(default)]
+    pub maker_fee_percentage: Option<Decimal>,
+    /// Fee on market fills, in percent, overriding the flat or tier taker rate
+    #[serde(default)]
+    pub taker_fee_percentage: Option<Decimal>,
+    /// Adverse price movement applied to every fill, as a percentage of the bar close
+    #[serde(default)]
+    pub slippage_percentage: Decimal,
+    /// Price market orders from strategy signals fill at. Configs saved before this
+    /// setting existed filled at the close and keep doing so.
+    #[serde(default = "default_saved_fill_price")]
+    pub fill_price: FillPrice,
+    /// Skip signals below the strategy's `min_confidence`, as live execution does
+    #[serde(default)]
+    pub apply_min_confidence: bool,
+    /// Show the strategy Heikin-Ashi candles; fills still happen at real prices
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
+    /// Log every skipped signal and executed trade; off by default since it floods
+    /// the logs on long, fine-grained runs
+    #[serde(default)]
+    pub log_trades: bool,
+    /// Fail the run if the strategy's trades depend on bars after the one being analyzed
+    #[serde(default)]
+    pub strict_lookahead: bool,
+    /// Symbol whose buy-and-hold the run is compared against; defaults to `symbol`
+    #[serde(default)]
+    pub benchmark_symbol: Option<String>,
+    /// Asset type of `benchmark_symbol`; defaults to `asset_type`
+    #[serde(default)]
+    pub benchmark_asset_type: Option<String>,
+    /// Share of the requested range the fetched data must span, in percent
+    #[serde(default = "default_min_data_coverage_pct")]
+    pub min_data_coverage_pct: Decimal,
+    /// What to do when the data spans less than `min_data_coverage_pct`
+    #[serde(default)]
+    pub on_short_data: ShortDataAction,
+    /// Cap on the points in each returned curve; metrics still use every bar
+    #[serde(default)]
+    pub max_curve_points: Option<usize>,
+    /// Annual risk-free rate, in percent, that Sharpe and Sortino measure excess return over
+    #[serde(default = "default_risk_free_rate")]
+    pub risk_free_rate: Decimal,
+    /// Bars per year used to annualize volatility; derived from `interval` when unset
+    #[serde(default)]
+    pub periods_per_year: Option<u32>,
+}
+
+impl BacktestConfig {
+    /// Imported position for the backtested symbol, if any
+    pub fn initial_position(&self) -> Option<&InitialPosition> {
+        self.initial_positions
+            .iter()
+            .find(|p| p.symbol.eq_ignore_ascii_case(&self.symbol))
+    }
+
+    /// Maker and taker fee per fill, in percent: the `fee_tier` rates when a tier is set,
+    /// otherwise `fee_percentage` for both, with `maker_fee_percentage` and
+    /// `taker_fee_percentage` replacing their side when set
+    pub fn fee_percentages(&self) -> Result<(Decimal, Decimal), AppError> {
+        let (maker, taker) = match self.fee_tier {
+            Some(tier) => {
+                let rates = FeeSchedule::binance_spot().rates(tier, self.fee_token_discount)?;
+                (rates.maker_pct(), rates.taker_pct())
+            }
+            None => (self.fee_percentage, self.fee_percentage),
+        };
+        Ok((
+            self.maker_fee_percentage.unwrap_or(maker),
+            self.taker_fee_percentage.unwrap_or(taker),
+        ))
+    }
+
+    /// Funding model for accumulation buys. Grid trading always trades its own capital;
+    /// otherwise an explicit `dca_mode` wins over the `unlimited_capital` default
+    pub fn dca_mode(&self) -> DcaMode {
+        if self.strategy_name.contains("grid") {
+            return DcaMode::FixedCapital;
+        }
+        match self.dca_mode {
+            Some(mode) => mode,
+            None if self.unlimited_capital || self.strategy_name.contains("dca") => DcaMode::ExternalContribution,
+            None => DcaMode::FixedCapital,
+        }
+    }
+
+    /// Benchmark symbol and asset type, when the benchmark is not the traded symbol
+    pub fn separate_benchmark(&self) -> Option<(&str, &str)> {
+        let symbol = self.benchmark_symbol
+            .as_deref()
+            .map(str::trim)
+            .filter(|symbol| !symbol.is_empty() && !symbol.eq_ignore_ascii_case(&self.symbol))?;
+        Some((symbol, self.benchmark_asset_type.as_deref().unwrap_or(&self.asset_type)))
+    }
+
+    pub fn needs_stock_data(&self) -> bool {
+        self.asset_type == "stock" || self.benchmark_asset_type.as_deref() == Some("stock")
+    }
+}
+
+/// What to do when the data covers less of the requested range than required, e.g.
+/// because the symbol listed after the requested start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortDataAction {
+    /// Log a warning and backtest the bars that exist
+    #[default]
+    Warn,
+    /// Fail the backtest
+    Reject,
+}
+
+/// Price a strategy's market orders fill at. Strategies decide on a bar's close, so
+/// filling at that same close assumes they could trade on a price they had only just seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FillPrice {
+    /// The next bar's open; a signal on the last bar goes unfilled
+    #[default]
+    NextOpen,
+    /// The signal bar's close
+    Close,
+    /// The signal bar's typical price, (high + low + close) / 3
+    Typical,
+}
+
+/// How a DCA backtest pays for its buys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DcaMode {
+    /// Buys are always filled, topping cash up from outside when it runs short.
+    /// Returns are measured against the total contributed.
+    ExternalContribution,
+    /// Buys draw down `initial_balance` and stop once it is spent.
+    /// Returns are measured against the starting balance.
+    FixedCapital,
+}
+
+/// Pre-existing holding imported as a backtest starting position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitialPosition {
+    pub symbol: String,
+    pub quantity: Decimal,
+    /// Average cost per unit, used as the cost basis for realized/unrealized P&L
+    pub average_cost: Decimal,
+}
+
+impl InitialPosition {
+    /// Parse positions from CSV rows of `symbol,quantity,average_cost`.
+    /// Blank lines and a leading header row are skipped.
+    pub fn parse_csv(csv: &str) -> Result<Vec<Self>, AppError> {
+        let mut positions = Vec::new();
+
+        for (line_no, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            if fields.len() != 3 {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid initial positions CSV on line {}: expected symbol,quantity,average_cost",
+                    line_no + 1
+                )));
+            }
+
+            let quantity = fields[1].parse::<Decimal>();
+            let average_cost = fields[2].parse::<Decimal>();
+            let (quantity, average_cost) = match (quantity, average_cost) {
+                (Ok(quantity), Ok(average_cost)) => (quantity, average_cost),
+                // Header row
+                _ if positions.is_empty() && line_no == 0 => continue,
+                _ => {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid number in initial positions CSV on line {}",
+                        line_no + 1
+                    )))
+                }
+            };
+
+            positions.push(Self {
+                symbol: fields[0].to_uppercase(),
+                quantity,
+                average_cost,
+            });
+        }
+
+        Ok(positions)
+    }
 }
 
 fn default_asset_type() -> String {
     "crypto".to_string()
 }
 
+fn default_min_data_coverage_pct() -> Decimal {
+    Decimal::from(95)
+}
+
+fn default_risk_free_rate() -> Decimal {
+    Decimal::from(2)
+}
+
+fn default_saved_fill_price() -> FillPrice {
+    FillPrice::Close
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BacktestResult {
     pub config: BacktestConfig,
     pub trades: Vec<BacktestTrade>,
     pub metrics: BacktestMetrics,
+    /// 0-100 robustness summary of `metrics`, with its components
+    #[serde(default)]
+    pub robustness_score: RobustnessScore,
     pub performance_chart: Vec<PerformancePoint>,
+    /// Per-bar drawdown from the running equity peak, for underwater charts
+    #[serde(default)]
+    pub drawdown_curve: Vec<DrawdownPoint>,
+    /// Initial balance held in the benchmark, on the bars it shares with the traded symbol
+    #[serde(default)]
+    pub benchmark_curve: Vec<EquityPoint>,
+    /// Open of the first and close of the last bar actually tested; `config` holds the
+    /// requested range
+    #[serde(default)]
+    pub actual_start_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub actual_end_time: Option<DateTime<Utc>>,
+    /// Share of the requested range the tested bars span, in percent
+    #[serde(default)]
+    pub data_coverage_pct: Option<Decimal>,
     pub execution_time_ms: u64,
     pub open_positions: Vec<OpenPosition>,
+    /// Rate the money values were converted from USD with; `None` means they are in USD.
+    /// `config` always echoes the request in USD.
+    #[serde(default)]
+    pub valuation: Option<FxRate>,
+}
+
+impl BacktestResult {
+    /// Convert every price and money value from USD into `rate`'s base currency.
+    /// Percentages, ratios and quantities are currency-free and left as they are.
+    pub fn valued_in(mut self, rate: FxRate) -> Self {
+        self.trades = self.trades.into_iter().map(|trade| trade.valued_in(&rate)).collect();
+        self.metrics = self.metrics.valued_in(&rate);
+        self.performance_chart = self.performance_chart.into_iter().map(|point| point.valued_in(&rate)).collect();
+        for point in &mut self.benchmark_curve {
+            point.portfolio_value = rate.convert(point.portfolio_value);
+        }
+        for position in &mut self.open_positions {
+            position.price = rate.convert(position.price);
+            position.total_value = rate.convert(position.total_value);
+        }
+
+        self.valuation = Some(rate);
+        self
+    }
+
+    /// Round money to the configured USD decimals and prices and quantities to the
+    /// crypto decimals, for an API response. Stored results keep every digit.
+    pub fn rounded(mut self) -> Self {
+        self.trades = self.trades.into_iter().map(BacktestTrade::rounded).collect();
+        self.metrics = self.metrics.rounded();
+        self.performance_chart = self.performance_chart.into_iter().map(PerformancePoint::rounded).collect();
+        for position in &mut self.open_positions {
+            position.price = round_crypto(position.price);
+            position.quantity = round_crypto(position.quantity);
+            position.total_value = round_usd(position.total_value);
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +344,29 @@ pub struct BacktestTrade {
     pub pnl_percentage: Option<Decimal>,
 }
 
+impl BacktestTrade {
+    /// See `BacktestResult::valued_in`
+    pub fn valued_in(mut self, rate: &FxRate) -> Self {
+        self.price = rate.convert(self.price);
+        self.total_value = rate.convert(self.total_value);
+        self.portfolio_value = rate.convert(self.portfolio_value);
+        self.balance_remaining = rate.convert(self.balance_remaining);
+        self.pnl = self.pnl.map(|pnl| rate.convert(pnl));
+        self
+    }
+
+    /// See `BacktestResult::rounded`
+    pub fn rounded(mut self) -> Self {
+        self.price = round_crypto(self.price);
+        self.quantity = round_crypto(self.quantity);
+        self.total_value = round_usd(self.total_value);
+        self.portfolio_value = round_usd(self.portfolio_value);
+        self.balance_remaining = round_usd(self.balance_remaining);
+        self.pnl = self.pnl.map(round_usd);
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TradeType {
     Buy,
@@ -74,8 +379,27 @@ pub struct BacktestMetrics {
     pub total_return_percentage: Decimal,
     pub annualized_return: Option<Decimal>,
     pub sharpe_ratio: Option<Decimal>,
+    /// Excess annualized return over the annualized downside deviation of per-bar
+    /// portfolio returns; `None` when no bar lost money
+    #[serde(default)]
+    pub sortino_ratio: Option<Decimal>,
+    /// Annualized return over max drawdown; `None` without a drawdown
+    #[serde(default)]
+    pub calmar_ratio: Option<Decimal>,
     pub max_drawdown: Decimal,
     pub volatility: Decimal,
+    /// Historical 95% Value-at-Risk: 5th percentile of per-bar portfolio returns, in percent
+    #[serde(default)]
+    pub value_at_risk_95: Option<Decimal>,
+    /// Mean per-bar return at or below the 95% VaR (expected shortfall), in percent
+    #[serde(default)]
+    pub conditional_value_at_risk_95: Option<Decimal>,
+    /// Annualized volatility of the asset's close-to-close returns over the test period, in percent
+    #[serde(default)]
+    pub asset_volatility_annual_pct: Option<Decimal>,
+    /// Mean 14-bar ATR as a percentage of the close price over the test period
+    #[serde(default)]
+    pub avg_atr_pct: Option<Decimal>,
     pub total_trades: u32,
     pub winning_trades: u32,
     pub losing_trades: u32,
@@ -97,6 +421,50 @@ pub struct BacktestMetrics {
     pub realized_pnl: Decimal,
     /// Unrealized profit/loss from open positions
     pub unrealized_pnl: Decimal,
+    /// Trading fees paid over the backtest
+    #[serde(default)]
+    pub total_fees: Decimal,
+    /// What `total_return` would have been without trading fees
+    #[serde(default)]
+    pub total_return_before_fees: Decimal,
+}
+
+impl BacktestMetrics {
+    /// See `BacktestResult::valued_in`
+    pub fn valued_in(mut self, rate: &FxRate) -> Self {
+        for value in [
+            &mut self.total_return,
+            &mut self.average_win,
+            &mut self.average_loss,
+            &mut self.final_portfolio_value,
+            &mut self.total_invested,
+            &mut self.realized_pnl,
+            &mut self.unrealized_pnl,
+            &mut self.total_fees,
+            &mut self.total_return_before_fees,
+        ] {
+            *value = rate.convert(*value);
+        }
+        self
+    }
+
+    /// See `BacktestResult::rounded`
+    pub fn rounded(mut self) -> Self {
+        for value in [
+            &mut self.total_return,
+            &mut self.average_win,
+            &mut self.average_loss,
+            &mut self.final_portfolio_value,
+            &mut self.total_invested,
+            &mut self.realized_pnl,
+            &mut self.unrealized_pnl,
+            &mut self.total_fees,
+            &mut self.total_return_before_fees,
+        ] {
+            *value = round_usd(*value);
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,7 +476,7 @@ pub struct HistoricalDataRequest {
     pub exchange: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Portfolio {
     pub cash_balance: Decimal,
     pub asset_quantity: Decimal,
@@ -116,6 +484,12 @@ pub struct Portfolio {
     pub initial_value: Decimal,
     /// Total amount invested (for DCA tracking)
     pub total_invested: Decimal,
+    /// Fee per fill as a fraction of notional
+    pub fee_rate: Decimal,
+    /// Adverse fill price movement as a fraction of the quoted price
+    pub slippage_rate: Decimal,
+    /// Fees paid so far
+    pub total_fees: Decimal,
 }
 
 impl Portfolio {
@@ -126,19 +500,59 @@ impl Portfolio {
             total_value: initial_balance,
             initial_value: initial_balance,
             total_invested: Decimal::ZERO,
+            fee_rate: Decimal::ZERO,
+            slippage_rate: Decimal::ZERO,
+            total_fees: Decimal::ZERO,
         }
     }
 
+    /// Portfolio that already holds `quantity` units bought at `average_cost`.
+    /// The imported cost basis counts towards the initial value and amount invested.
+    pub fn with_initial_position(initial_balance: Decimal, quantity: Decimal, average_cost: Decimal) -> Self {
+        let cost_basis = quantity * average_cost;
+        Self {
+            cash_balance: initial_balance,
+            asset_quantity: quantity,
+            total_value: initial_balance + cost_basis,
+            initial_value: initial_balance + cost_basis,
+            total_invested: cost_basis,
+            fee_rate: Decimal::ZERO,
+            slippage_rate: Decimal::ZERO,
+            total_fees: Decimal::ZERO,
+        }
+    }
+
+    /// Apply fees and slippage (both given as percentages) to all subsequent fills
+    pub fn set_trading_costs(&mut self, fee_percentage: Decimal, slippage_percentage: Decimal) {
+        self.fee_rate = fee_percentage / Decimal::from(100);
+        self.slippage_rate = slippage_percentage / Decimal::from(100);
+    }
+
+    /// Cash needed to buy `quantity` at `price`, including slippage and fees: (total, fee)
+    fn buy_cost(&self, price: Decimal, quantity: Decimal) -> (Decimal, Decimal) {
+        let notional = price * (Decimal::ONE + self.slippage_rate) * quantity;
+        let fee = notional * self.fee_rate;
+        (notional + fee, fee)
+    }
+
+    /// Cash received for selling `quantity` at `price`, net of slippage and fees: (proceeds, fee)
+    fn sell_proceeds(&self, price: Decimal, quantity: Decimal) -> (Decimal, Decimal) {
+        let notional = price * (Decimal::ONE - self.slippage_rate) * quantity;
+        let fee = notional * self.fee_rate;
+        (notional - fee, fee)
+    }
+
     pub fn update_total_value(&mut self, current_price: Decimal) {
         self.total_value = self.cash_balance + (self.asset_quantity * current_price);
     }
 
     pub fn execute_buy(&mut self, price: Decimal, quantity: Decimal) -> bool {
-        let total_cost = price * quantity;
+        let (total_cost, fee) = self.buy_cost(price, quantity);
         if self.cash_balance >= total_cost {
             self.cash_balance -= total_cost;
             self.asset_quantity += quantity;
             self.total_invested += total_cost;
+            self.total_fees += fee;
             true
         } else {
             false
@@ -152,7 +566,7 @@ impl Portfolio {
 
     /// Execute buy with capital injection if needed (for DCA unlimited mode)
     pub fn execute_buy_with_injection(&mut self, price: Decimal, quantity: Decimal) -> bool {
-        let total_cost = price * quantity;
+        let (total_cost, fee) = self.buy_cost(price, quantity);
 
         // Inject exactly the amount needed if insufficient
         if self.cash_balance < total_cost {
@@ -163,13 +577,16 @@ impl Portfolio {
         self.cash_balance -= total_cost;
         self.asset_quantity += quantity;
         self.total_invested += total_cost;
+        self.total_fees += fee;
         true
     }
 
     pub fn execute_sell(&mut self, price: Decimal, quantity: Decimal) -> bool {
         if self.asset_quantity >= quantity {
-            self.cash_balance += price * quantity;
+            let (proceeds, fee) = self.sell_proceeds(price, quantity);
+            self.cash_balance += proceeds;
             self.asset_quantity -= quantity;
+            self.total_fees += fee;
             true
         } else {
             false
@@ -177,6 +594,16 @@ impl Portfolio {
     }
 }
 
+/// Strategy decision on the most recent bar of a data range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyPreview {
+    pub timestamp: DateTime<Utc>,
+    pub price: Decimal,
+    pub signal: Option<StrategySignal>,
+    /// Output of `Strategy::explain` for the last bar
+    pub explanation: serde_json::Value,
+}
+
 /// Performance point for charting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformancePoint {
@@ -186,6 +613,271 @@ pub struct PerformancePoint {
     pub trade_marker: Option<TradeType>,
 }
 
+impl PerformancePoint {
+    /// See `BacktestResult::valued_in`
+    pub fn valued_in(mut self, rate: &FxRate) -> Self {
+        self.portfolio_value = rate.convert(self.portfolio_value);
+        self.asset_price = rate.convert(self.asset_price);
+        self
+    }
+
+    /// See `BacktestResult::rounded`
+    pub fn rounded(mut self) -> Self {
+        self.portfolio_value = round_usd(self.portfolio_value);
+        self.asset_price = round_crypto(self.asset_price);
+        self
+    }
+}
+
+/// Progress of a simulation after `next_index` bars, saved periodically so a run cut
+/// short by a restart carries on from here instead of starting over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestCheckpoint {
+    pub config: BacktestConfig,
+    /// Index of the first bar still to simulate
+    pub next_index: usize,
+    /// Close time of the last simulated bar, to check refetched data still lines up
+    pub last_bar_close_time: DateTime<Utc>,
+    pub portfolio: Portfolio,
+    pub position: CheckpointPosition,
+    pub open_positions: Vec<OpenPosition>,
+    pub trades: Vec<BacktestTrade>,
+    pub equity_curve: Vec<EquityPoint>,
+    /// Market signal waiting for the next bar's open
+    pub pending_market: Option<StrategySignal>,
+    /// Ids the strategy's contexts carry
+    pub strategy_id: Uuid,
+    pub user_id: Uuid,
+    /// `Strategy::get_state` after the last simulated bar
+    pub strategy_state: serde_json::Value,
+}
+
+/// The position a simulation's trades are measured against
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CheckpointPosition {
+    pub entry_price: Decimal,
+    pub entry_quantity: Decimal,
+    pub is_open: bool,
+}
+
+/// Portfolio value at the close of a bar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub portfolio_value: Decimal,
+}
+
+/// Distance below the running equity peak at the close of a bar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawdownPoint {
+    pub timestamp: DateTime<Utc>,
+    /// Percent below the highest portfolio value so far: zero at a new high, negative below it
+    pub drawdown_pct: Decimal,
+}
+
+/// One strategy to include in a comparison run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyComparisonEntry {
+    pub strategy_name: String,
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// Outcome of a single strategy within a comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyComparisonResult {
+    pub strategy_name: String,
+    pub parameters: serde_json::Value,
+    /// One point per bar, aligned across all strategies in the comparison
+    pub equity_curve: Vec<EquityPoint>,
+    pub metrics: BacktestMetrics,
+}
+
+/// Several strategies run on the same dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyComparison {
+    pub symbol: String,
+    pub interval: KlineInterval,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub data_points: usize,
+    pub results: Vec<StrategyComparisonResult>,
+    pub execution_time_ms: u64,
+    /// Rate the money values were converted from USD with; `None` means they are in USD
+    #[serde(default)]
+    pub valuation: Option<FxRate>,
+}
+
+impl StrategyComparison {
+    /// Convert the metrics and equity curves from USD into `rate`'s base currency
+    pub fn valued_in(mut self, rate: FxRate) -> Self {
+        self.results = self
+            .results
+            .into_iter()
+            .map(|result| StrategyComparisonResult {
+                equity_curve: result
+                    .equity_curve
+                    .into_iter()
+                    .map(|point| EquityPoint { portfolio_value: rate.convert(point.portfolio_value), ..point })
+                    .collect(),
+                metrics: result.metrics.valued_in(&rate),
+                ..result
+            })
+            .collect();
+        self.valuation = Some(rate);
+        self
+    }
+
+    /// Round every strategy's metrics for an API response
+    pub fn rounded(mut self) -> Self {
+        self.results = self
+            .results
+            .into_iter()
+            .map(|result| StrategyComparisonResult { metrics: result.metrics.rounded(), ..result })
+            .collect();
+        self
+    }
+}
+
+/// Backtest outcome for one fee/slippage combination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensitivityCell {
+    pub fee_percentage: Decimal,
+    pub slippage_percentage: Decimal,
+    pub total_return_percentage: Decimal,
+    pub sharpe_ratio: Option<Decimal>,
+    pub profitable: bool,
+}
+
+/// Backtest returns across a grid of fee and slippage levels. Only percentages and
+/// ratios, so it reads the same in every base currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensitivityReport {
+    pub fee_levels: Vec<Decimal>,
+    pub slippage_levels: Vec<Decimal>,
+    /// One row per fee level, one column per slippage level
+    pub matrix: Vec<Vec<SensitivityCell>>,
+    /// Cheapest combination (by fee + slippage) at which the strategy is no longer profitable
+    pub break_even: Option<SensitivityCell>,
+    pub data_points: usize,
+    pub execution_time_ms: u64,
+}
+
+/// When a portfolio backtest moves cash between its assets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RebalancePolicy {
+    /// Each asset only ever trades the cash it started with
+    #[default]
+    None,
+    /// Every `every_bars` bars of the combined timeline, pool the idle cash of the
+    /// assets trading by then and share it out again in proportion to their starting
+    /// balances. Holdings are left as they are.
+    Periodic { every_bars: usize },
+}
+
+/// One asset of a portfolio backtest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioAssetResult {
+    pub symbol: String,
+    /// Share of the combined starting balance, in percent
+    pub weight_pct: Decimal,
+    /// Cash rebalancing moved into the asset, negative when it gave cash away.
+    /// `result`'s metrics include it.
+    pub net_transfers: Decimal,
+    /// Profit or loss the asset added to the portfolio: its final value less its
+    /// starting balance and `net_transfers`
+    pub pnl: Decimal,
+    /// `pnl` as a percentage of the combined starting balance
+    pub contribution_pct: Decimal,
+    /// Value of the asset's share of the portfolio at the close of each of its bars
+    pub equity_curve: Vec<EquityPoint>,
+    pub result: BacktestResult,
+}
+
+/// Several assets backtested together out of one cash pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioBacktestResult {
+    pub rebalance: RebalancePolicy,
+    /// Rebalances that moved cash
+    pub rebalances: u32,
+    pub initial_balance: Decimal,
+    pub final_portfolio_value: Decimal,
+    pub total_return: Decimal,
+    pub total_return_percentage: Decimal,
+    /// Largest drop of `equity_curve` from its running peak, in percent
+    pub max_drawdown: Decimal,
+    /// Combined value at every bar close of any asset. An asset without a bar at that
+    /// time counts at its last value, or its starting balance before its first bar.
+    pub equity_curve: Vec<EquityPoint>,
+    pub assets: Vec<PortfolioAssetResult>,
+    pub execution_time_ms: u64,
+}
+
+/// Fee/slippage sensitivity request from API
+#[derive(Debug, Clone, Deserialize)]
+pub struct SensitivityRequest {
+    #[serde(flatten)]
+    pub backtest: BacktestRequest,
+    /// Fee levels to test, in percent
+    #[serde(default = "default_fee_levels")]
+    pub fee_levels: Vec<Decimal>,
+    /// Slippage levels to test, in percent
+    #[serde(default = "default_slippage_levels")]
+    pub slippage_levels: Vec<Decimal>,
+}
+
+fn default_fee_levels() -> Vec<Decimal> {
+    vec![
+        Decimal::ZERO,
+        Decimal::new(5, 2),
+        Decimal::new(1, 1),
+        Decimal::new(25, 2),
+        Decimal::new(5, 1),
+        Decimal::ONE,
+    ]
+}
+
+fn default_slippage_levels() -> Vec<Decimal> {
+    vec![
+        Decimal::ZERO,
+        Decimal::new(5, 2),
+        Decimal::new(1, 1),
+        Decimal::new(25, 2),
+    ]
+}
+
+/// Strategy comparison request from API
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompareStrategiesRequest {
+    pub symbol: String,
+    pub interval: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub initial_balance: Decimal,
+    pub strategies: Vec<StrategyComparisonEntry>,
+    /// Asset type: "crypto" or "stock" (defaults to "crypto")
+    #[serde(default = "default_asset_type")]
+    pub asset_type: String,
+    /// Fee per fill, in percent
+    #[serde(default)]
+    pub fee_percentage: Decimal,
+    /// Binance VIP tier to take maker/taker fees from instead of `fee_percentage`
+    pub fee_tier: Option<u8>,
+    /// Pay fees in BNB at the discounted rate
+    #[serde(default)]
+    pub fee_token_discount: bool,
+    /// Maker fee per fill, in percent, overriding `fee_percentage` and the tier rate
+    pub maker_fee_percentage: Option<Decimal>,
+    /// Taker fee per fill, in percent, overriding `fee_percentage` and the tier rate
+    pub taker_fee_percentage: Option<Decimal>,
+    /// Slippage per fill, in percent
+    #[serde(default)]
+    pub slippage_percentage: Decimal,
+    /// Fill at the next bar's open (default), the signal bar's close or its typical price
+    #[serde(default)]
+    pub fill_price: FillPrice,
+}
+
 /// Backtesting request from API
 #[derive(Debug, Clone, Deserialize)]
 pub struct BacktestRequest {
@@ -201,4 +893,74 @@ pub struct BacktestRequest {
     /// Asset type: "crypto" or "stock" (defaults to "crypto")
     #[serde(default = "default_asset_type")]
     pub asset_type: String,
+    /// Existing holdings to start from
+    #[serde(default)]
+    pub initial_positions: Vec<InitialPosition>,
+    /// Existing holdings as CSV (`symbol,quantity,average_cost`), merged with `initial_positions`
+    pub initial_positions_csv: Option<String>,
+    /// Fee per fill, in percent
+    #[serde(default)]
+    pub fee_percentage: Decimal,
+    /// Binance VIP tier to take maker/taker fees from instead of `fee_percentage`
+    pub fee_tier: Option<u8>,
+    /// Pay fees in BNB at the discounted rate
+    #[serde(default)]
+    pub fee_token_discount: bool,
+    /// Maker fee per fill, in percent, overriding `fee_percentage` and the tier rate
+    pub maker_fee_percentage: Option<Decimal>,
+    /// Taker fee per fill, in percent, overriding `fee_percentage` and the tier rate
+    pub taker_fee_percentage: Option<Decimal>,
+    /// Slippage per fill, in percent
+    #[serde(default)]
+    pub slippage_percentage: Decimal,
+    /// Fill at the next bar's open (default), the signal bar's close or its typical price
+    #[serde(default)]
+    pub fill_price: FillPrice,
+    /// Honour the strategy's `min_confidence` gate
+    #[serde(default)]
+    pub apply_min_confidence: bool,
+    /// Feed the strategy Heikin-Ashi candles instead of raw klines
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
+    /// Verbose per-trade logging, for debugging a run
+    #[serde(default)]
+    pub log_trades: bool,
+    /// Check the strategy for look-ahead bias, for debugging a strategy
+    #[serde(default)]
+    pub strict_lookahead: bool,
+    /// Compare against this symbol's buy-and-hold instead of the traded symbol's
+    pub benchmark_symbol: Option<String>,
+    /// Asset type of `benchmark_symbol` (defaults to `asset_type`)
+    pub benchmark_asset_type: Option<String>,
+    /// Fund DCA buys from outside contributions or from `initial_balance` only
+    pub dca_mode: Option<DcaMode>,
+    /// Minimum share of the requested range the data must span, in percent (defaults to 95)
+    #[serde(default = "default_min_data_coverage_pct")]
+    pub min_data_coverage_pct: Decimal,
+    /// Warn (default) or fail when the data spans less than `min_data_coverage_pct`
+    #[serde(default)]
+    pub on_short_data: ShortDataAction,
+    /// Downsample the drawdown and benchmark curves to at most this many points
+    pub max_curve_points: Option<usize>,
+    /// Annual risk-free rate for Sharpe and Sortino, in percent (defaults to 2)
+    #[serde(default = "default_risk_free_rate")]
+    pub risk_free_rate: Decimal,
+    /// Override the bars per year volatility is annualized with (365.25 days of `interval` bars, or 252 for daily stocks)
+    pub periods_per_year: Option<u32>,
+}
+
+impl BacktestRequest {
+    /// Whether the traded symbol or the benchmark needs the stock data source
+    pub fn needs_stock_data(&self) -> bool {
+        self.asset_type == "stock" || self.benchmark_asset_type.as_deref() == Some("stock")
+    }
+
+    /// Starting positions from both the JSON list and the CSV import
+    pub fn resolve_initial_positions(&self) -> Result<Vec<InitialPosition>, AppError> {
+        let mut positions = self.initial_positions.clone();
+        if let Some(csv) = &self.initial_positions_csv {
+            positions.extend(InitialPosition::parse_csv(csv)?);
+        }
+        Ok(positions)
+    }
 }
\ No newline at end of file
@@ -2,6 +2,36 @@ use serde::Deserialize;
 use std::env;
 use anyhow::{Result, Context};
 
+/// Methods allowed in cross-origin requests unless `CORS_ALLOWED_METHODS` is set
+pub const DEFAULT_CORS_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "OPTIONS"];
+
+/// Request headers allowed in cross-origin requests unless `CORS_ALLOWED_HEADERS` is set
+pub const DEFAULT_CORS_HEADERS: &[&str] = &[
+    "Authorization",
+    "Content-Type",
+    "Accept",
+    "X-CSRF-Token",
+    "X-Requested-With",
+    "Origin",
+    "Access-Control-Request-Method",
+    "Access-Control-Request-Headers",
+];
+
+/// Seconds browsers may cache a preflight response unless `CORS_MAX_AGE` is set
+pub const DEFAULT_CORS_MAX_AGE_SECS: usize = 3600;
+
+/// Seconds of inactivity after which a login session expires unless `SESSION_IDLE_TIMEOUT` is set
+pub const DEFAULT_SESSION_IDLE_TIMEOUT_SECS: u64 = 86_400;
+
+/// Seconds after login a session expires however active it is, unless `SESSION_ABSOLUTE_TIMEOUT` is set
+pub const DEFAULT_SESSION_ABSOLUTE_TIMEOUT_SECS: u64 = 604_800;
+
+/// Methods accepted in `CORS_ALLOWED_METHODS`
+const HTTP_METHODS: &[&str] = &["GET", "HEAD", "POST", "PUT", "DELETE", "PATCH", "OPTIONS", "CONNECT", "TRACE"];
+
+/// Longest preflight cache any browser honours (Firefox caps at 24 hours)
+const MAX_CORS_MAX_AGE_SECS: usize = 86_400;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub database_url: String,
@@ -9,7 +39,19 @@ pub struct Config {
     pub server_host: String,
     pub server_port: u16,
     pub cors_origin: String,
+    /// HTTP methods allowed in cross-origin requests
+    pub cors_allowed_methods: Vec<String>,
+    /// Request headers allowed in cross-origin requests
+    pub cors_allowed_headers: Vec<String>,
+    /// Seconds browsers may cache a preflight response
+    pub cors_max_age_secs: usize,
     pub alpha_vantage_api_key: String,
+    /// Seconds of inactivity after which a login session expires; activity slides it forward
+    pub session_idle_timeout_secs: u64,
+    /// Seconds after login a session expires regardless of activity
+    pub session_absolute_timeout_secs: u64,
+    /// Serve `/metrics` on this port instead of the main server port
+    pub metrics_port: Option<u16>,
 }
 
 impl Config {
@@ -37,17 +79,58 @@ impl Config {
             .or_else(|_| env::var("cors_origin"))
             .unwrap_or_else(|_| "http://localhost:3000".to_string());
 
+        let cors_allowed_methods = env::var("CORS_ALLOWED_METHODS")
+            .or_else(|_| env::var("cors_allowed_methods"))
+            .map(|methods| parse_list(&methods))
+            .unwrap_or_else(|_| DEFAULT_CORS_METHODS.iter().map(|m| m.to_string()).collect());
+
+        let cors_allowed_headers = env::var("CORS_ALLOWED_HEADERS")
+            .or_else(|_| env::var("cors_allowed_headers"))
+            .map(|headers| parse_list(&headers))
+            .unwrap_or_else(|_| DEFAULT_CORS_HEADERS.iter().map(|h| h.to_string()).collect());
+
+        let cors_max_age_secs = env::var("CORS_MAX_AGE")
+            .or_else(|_| env::var("cors_max_age"))
+            .unwrap_or_else(|_| DEFAULT_CORS_MAX_AGE_SECS.to_string())
+            .parse()
+            .context("CORS_MAX_AGE must be a number of seconds")?;
+
         let alpha_vantage_api_key = env::var("ALPHA_VANTAGE_API_KEY")
             .or_else(|_| env::var("alpha_vantage_api_key"))
             .context("ALPHA_VANTAGE_API_KEY environment variable is required")?;
 
+        let session_idle_timeout_secs = env::var("SESSION_IDLE_TIMEOUT")
+            .or_else(|_| env::var("session_idle_timeout"))
+            .unwrap_or_else(|_| DEFAULT_SESSION_IDLE_TIMEOUT_SECS.to_string())
+            .parse()
+            .context("SESSION_IDLE_TIMEOUT must be a number of seconds")?;
+
+        let session_absolute_timeout_secs = env::var("SESSION_ABSOLUTE_TIMEOUT")
+            .or_else(|_| env::var("session_absolute_timeout"))
+            .unwrap_or_else(|_| DEFAULT_SESSION_ABSOLUTE_TIMEOUT_SECS.to_string())
+            .parse()
+            .context("SESSION_ABSOLUTE_TIMEOUT must be a number of seconds")?;
+
+        let metrics_port = env::var("METRICS_PORT")
+            .or_else(|_| env::var("metrics_port"))
+            .ok()
+            .map(|port| port.parse())
+            .transpose()
+            .context("METRICS_PORT must be a valid port number")?;
+
         Ok(Config {
             database_url,
             jwt_secret,
             server_host,
             server_port,
             cors_origin,
+            cors_allowed_methods,
+            cors_allowed_headers,
+            cors_max_age_secs,
             alpha_vantage_api_key,
+            session_idle_timeout_secs,
+            session_absolute_timeout_secs,
+            metrics_port,
         })
     }
 
@@ -61,11 +144,48 @@ impl Config {
             anyhow::bail!("SERVER_PORT must be a valid port number");
         }
 
+        if matches!(self.metrics_port, Some(port) if port == 0 || port == self.server_port) {
+            anyhow::bail!("METRICS_PORT must be a valid port number different from SERVER_PORT");
+        }
+
         // Validate CORS origin format
         if !self.cors_origin.starts_with("http://") && !self.cors_origin.starts_with("https://") {
             anyhow::bail!("CORS_ORIGIN must start with http:// or https://");
         }
 
+        if self.cors_allowed_methods.is_empty() {
+            anyhow::bail!("CORS_ALLOWED_METHODS must list at least one method");
+        }
+        if let Some(method) = self.cors_allowed_methods.iter().find(|m| !HTTP_METHODS.contains(&m.as_str())) {
+            anyhow::bail!("CORS_ALLOWED_METHODS contains an unknown method: {}", method);
+        }
+
+        for header in &self.cors_allowed_headers {
+            actix_web::http::header::HeaderName::from_bytes(header.as_bytes())
+                .with_context(|| format!("CORS_ALLOWED_HEADERS contains an invalid header name: {}", header))?;
+        }
+
+        if self.cors_max_age_secs > MAX_CORS_MAX_AGE_SECS {
+            anyhow::bail!("CORS_MAX_AGE must be at most {} seconds", MAX_CORS_MAX_AGE_SECS);
+        }
+
+        if self.session_idle_timeout_secs == 0 {
+            anyhow::bail!("SESSION_IDLE_TIMEOUT must be positive");
+        }
+        if self.session_absolute_timeout_secs < self.session_idle_timeout_secs {
+            anyhow::bail!("SESSION_ABSOLUTE_TIMEOUT must be at least SESSION_IDLE_TIMEOUT");
+        }
+
         Ok(())
     }
+}
+
+/// Split a comma-separated environment value, dropping blanks
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
 }
\ No newline at end of file
@@ -2,6 +2,9 @@ use sea_orm::{Database, DatabaseConnection, ConnectionTrait};
 use tracing::{info, warn, error};
 use anyhow::{Result, Context};
 
+#[cfg(test)]
+pub mod test_support;
+
 pub async fn create_connection(database_url: &str) -> Result<DatabaseConnection> {
     info!("Connecting to database: {}", database_url);
     let db = Database::connect(database_url)
@@ -45,6 +48,10 @@ async fn create_tables(db: &DatabaseConnection) -> Result<()> {
         ("sma_crossover_executions", include_str!("sql/create_sma_crossover_executions_table.sql")),
         ("market_data", include_str!("sql/create_market_data_table.sql")),
         ("backtest_results", include_str!("sql/create_backtest_results_table.sql")),
+        ("balance_snapshots", include_str!("sql/create_balance_snapshots_table.sql")),
+        ("connection_balance_snapshots", include_str!("sql/create_connection_balance_snapshots_table.sql")),
+        ("paper_portfolios", include_str!("sql/create_paper_portfolios_table.sql")),
+        ("paper_trades", include_str!("sql/create_paper_trades_table.sql")),
     ];
 
     for (table_name, sql) in tables {
@@ -89,6 +96,28 @@ async fn run_migrations(db: &DatabaseConnection) -> Result<()> {
     migrate_total_invested_column(db).await?;
     // Migration for DCA strategies table schema
     migrate_dca_strategies_schema(db).await?;
+    // Migration for strategy version columns (optimistic concurrency)
+    migrate_strategy_version_columns(db).await?;
+    // Migration for exchange API key permissions
+    migrate_api_permissions_column(db).await?;
+    // Migration for per-user order notional limits
+    migrate_order_notional_limit_columns(db).await?;
+    // Migration for exchange fee tiers
+    migrate_fee_tier_columns(db).await?;
+    // Migration for per-user valuation currency
+    migrate_base_currency_column(db).await?;
+    // Migration for saved strategy framework state
+    migrate_strategy_state_columns(db).await?;
+    // Migration for per-strategy kill switches
+    migrate_kill_switch_columns(db).await?;
+    // Migration for per-user tradable symbol lists
+    migrate_symbol_access_columns(db).await?;
+    // Migration for resumable backtests
+    migrate_backtest_checkpoint_column(db).await?;
+    // Migration for paper-trading DCA strategies
+    migrate_dca_mode_columns(db).await?;
+    // Migration for opt-in balance history credentials
+    migrate_history_credentials_columns(db).await?;
 
     info!("✓ Database migrations completed");
     Ok(())
@@ -330,6 +359,215 @@ async fn migrate_total_invested_column(db: &DatabaseConnection) -> Result<()> {
     Ok(())
 }
 
+async fn migrate_strategy_version_columns(db: &DatabaseConnection) -> Result<()> {
+    for table in ["dca_strategies", "sma_crossover_strategies", "grid_trading_strategies"] {
+        let test_query = format!("SELECT version FROM {} LIMIT 1", table);
+
+        if db.execute_unprepared(&test_query).await.is_ok() {
+            continue;
+        }
+
+        let alter = format!("ALTER TABLE {} ADD COLUMN version INTEGER NOT NULL DEFAULT 1", table);
+        match db.execute_unprepared(&alter).await {
+            Ok(_) => info!("✓ Added version column to {} table", table),
+            Err(e) => {
+                error!("Failed to add version column to {}: {}", table, e);
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate_strategy_state_columns(db: &DatabaseConnection) -> Result<()> {
+    for table in ["dca_strategies", "sma_crossover_strategies", "grid_trading_strategies"] {
+        let test_query = format!("SELECT state_json FROM {} LIMIT 1", table);
+
+        if db.execute_unprepared(&test_query).await.is_ok() {
+            continue;
+        }
+
+        let alter = format!("ALTER TABLE {} ADD COLUMN state_json TEXT", table);
+        match db.execute_unprepared(&alter).await {
+            Ok(_) => info!("✓ Added state_json column to {} table", table),
+            Err(e) => {
+                error!("Failed to add state_json column to {}: {}", table, e);
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate_kill_switch_columns(db: &DatabaseConnection) -> Result<()> {
+    for table in ["dca_strategies", "sma_crossover_strategies", "grid_trading_strategies"] {
+        let test_query = format!("SELECT kill_switch FROM {} LIMIT 1", table);
+
+        if db.execute_unprepared(&test_query).await.is_ok() {
+            continue;
+        }
+
+        let alter = format!("ALTER TABLE {} ADD COLUMN kill_switch BOOLEAN NOT NULL DEFAULT 0", table);
+        match db.execute_unprepared(&alter).await {
+            Ok(_) => info!("✓ Added kill_switch column to {} table", table),
+            Err(e) => {
+                error!("Failed to add kill_switch column to {}: {}", table, e);
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate_symbol_access_columns(db: &DatabaseConnection) -> Result<()> {
+    if db.execute_unprepared("SELECT symbol_allow_list FROM user_profiles LIMIT 1").await.is_ok() {
+        return Ok(());
+    }
+
+    for sql in [
+        "ALTER TABLE user_profiles ADD COLUMN symbol_allow_list TEXT",
+        "ALTER TABLE user_profiles ADD COLUMN symbol_deny_list TEXT",
+    ] {
+        if let Err(e) = db.execute_unprepared(sql).await {
+            error!("Failed to add symbol access columns: {}", e);
+            return Err(e.into());
+        }
+    }
+    info!("✓ Added symbol access columns to user_profiles table");
+
+    Ok(())
+}
+
+async fn migrate_backtest_checkpoint_column(db: &DatabaseConnection) -> Result<()> {
+    if db.execute_unprepared("SELECT checkpoint_json FROM backtest_results LIMIT 1").await.is_ok() {
+        return Ok(());
+    }
+
+    match db.execute_unprepared("ALTER TABLE backtest_results ADD COLUMN checkpoint_json TEXT").await {
+        Ok(_) => info!("✓ Added checkpoint_json column to backtest_results table"),
+        Err(e) => {
+            error!("Failed to add checkpoint_json column: {}", e);
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate_dca_mode_columns(db: &DatabaseConnection) -> Result<()> {
+    for table in ["dca_strategies", "dca_executions"] {
+        let test_query = format!("SELECT mode FROM {} LIMIT 1", table);
+
+        if db.execute_unprepared(&test_query).await.is_ok() {
+            continue;
+        }
+
+        let alter = format!("ALTER TABLE {} ADD COLUMN mode TEXT NOT NULL DEFAULT 'live'", table);
+        match db.execute_unprepared(&alter).await {
+            Ok(_) => info!("✓ Added mode column to {} table", table),
+            Err(e) => {
+                error!("Failed to add mode column to {}: {}", table, e);
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate_api_permissions_column(db: &DatabaseConnection) -> Result<()> {
+    if db.execute_unprepared("SELECT api_permissions FROM exchange_connections LIMIT 1").await.is_ok() {
+        return Ok(());
+    }
+
+    match db.execute_unprepared("ALTER TABLE exchange_connections ADD COLUMN api_permissions TEXT").await {
+        Ok(_) => info!("✓ Added api_permissions column to exchange_connections table"),
+        Err(e) => {
+            error!("Failed to add api_permissions column: {}", e);
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate_order_notional_limit_columns(db: &DatabaseConnection) -> Result<()> {
+    if db.execute_unprepared("SELECT max_order_notional FROM user_profiles LIMIT 1").await.is_ok() {
+        return Ok(());
+    }
+
+    for sql in [
+        "ALTER TABLE user_profiles ADD COLUMN max_order_notional TEXT",
+        "ALTER TABLE user_profiles ADD COLUMN clamp_oversized_orders BOOLEAN NOT NULL DEFAULT 0",
+    ] {
+        if let Err(e) = db.execute_unprepared(sql).await {
+            error!("Failed to add order notional limit columns: {}", e);
+            return Err(e.into());
+        }
+    }
+    info!("✓ Added order notional limit columns to user_profiles table");
+
+    Ok(())
+}
+
+async fn migrate_fee_tier_columns(db: &DatabaseConnection) -> Result<()> {
+    if db.execute_unprepared("SELECT fee_tier FROM exchange_connections LIMIT 1").await.is_ok() {
+        return Ok(());
+    }
+
+    for sql in [
+        "ALTER TABLE exchange_connections ADD COLUMN fee_tier INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE exchange_connections ADD COLUMN fee_token_discount BOOLEAN NOT NULL DEFAULT 0",
+    ] {
+        if let Err(e) = db.execute_unprepared(sql).await {
+            error!("Failed to add fee tier columns: {}", e);
+            return Err(e.into());
+        }
+    }
+    info!("✓ Added fee tier columns to exchange_connections table");
+
+    Ok(())
+}
+
+async fn migrate_history_credentials_columns(db: &DatabaseConnection) -> Result<()> {
+    if db.execute_unprepared("SELECT history_credentials FROM exchange_connections LIMIT 1").await.is_ok() {
+        return Ok(());
+    }
+
+    for sql in [
+        "ALTER TABLE exchange_connections ADD COLUMN history_credentials TEXT",
+        "ALTER TABLE exchange_connections ADD COLUMN history_credentials_nonce TEXT",
+        "ALTER TABLE exchange_connections ADD COLUMN history_credentials_salt TEXT",
+    ] {
+        if let Err(e) = db.execute_unprepared(sql).await {
+            error!("Failed to add history credentials columns: {}", e);
+            return Err(e.into());
+        }
+    }
+    info!("✓ Added history credentials columns to exchange_connections table");
+
+    Ok(())
+}
+
+async fn migrate_base_currency_column(db: &DatabaseConnection) -> Result<()> {
+    if db.execute_unprepared("SELECT base_currency FROM user_profiles LIMIT 1").await.is_ok() {
+        return Ok(());
+    }
+
+    match db.execute_unprepared("ALTER TABLE user_profiles ADD COLUMN base_currency TEXT NOT NULL DEFAULT 'USD'").await {
+        Ok(_) => info!("✓ Added base_currency column to user_profiles table"),
+        Err(e) => {
+            error!("Failed to add base_currency column: {}", e);
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
 async fn migrate_dca_strategies_schema(db: &DatabaseConnection) -> Result<()> {
     // Check if config_json column exists in dca_strategies (new schema)
     let test_query = "SELECT config_json FROM dca_strategies LIMIT 1";
@@ -359,6 +597,7 @@ async fn migrate_dca_strategies_schema(db: &DatabaseConnection) -> Result<()> {
                 average_buy_price REAL,
                 last_execution_at TEXT,
                 next_execution_at TEXT,
+                version INTEGER NOT NULL DEFAULT 1,
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
@@ -30,6 +30,7 @@ CREATE TABLE IF NOT EXISTS backtest_results (
   drawdown_curve TEXT NOT NULL,
   status TEXT NOT NULL DEFAULT 'running',
   error_message TEXT,
+  checkpoint_json TEXT,
   execution_time_ms INTEGER,
   created_at TEXT NOT NULL,
   updated_at TEXT NOT NULL,
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS balance_snapshots (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    snapshot_date TEXT NOT NULL,
+    total_usd_value REAL NOT NULL,
+    source TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS connection_balance_snapshots (
+    id TEXT PRIMARY KEY,
+    connection_id TEXT NOT NULL,
+    total_usd_value REAL NOT NULL,
+    recorded_at TEXT NOT NULL,
+    FOREIGN KEY (connection_id) REFERENCES exchange_connections (id) ON DELETE CASCADE
+);
@@ -13,6 +13,7 @@ CREATE TABLE IF NOT EXISTS dca_executions (
     order_status TEXT NOT NULL DEFAULT 'pending',
     execution_timestamp TEXT NOT NULL,
     error_message TEXT,
+    mode TEXT NOT NULL DEFAULT 'live',
     created_at TEXT NOT NULL,
     FOREIGN KEY (strategy_id) REFERENCES dca_strategies (id) ON DELETE CASCADE,
     FOREIGN KEY (exchange_connection_id) REFERENCES exchange_connections (id) ON DELETE CASCADE
@@ -10,6 +10,10 @@ CREATE TABLE IF NOT EXISTS dca_strategies (
     average_buy_price REAL,
     last_execution_at TEXT,
     next_execution_at TEXT,
+    version INTEGER NOT NULL DEFAULT 1,
+    state_json TEXT,
+    kill_switch BOOLEAN NOT NULL DEFAULT 0,
+    mode TEXT NOT NULL DEFAULT 'live',
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
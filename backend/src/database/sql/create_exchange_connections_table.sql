@@ -16,6 +16,12 @@ CREATE TABLE IF NOT EXISTS exchange_connections (
     last_sync TEXT,
     connection_status TEXT NOT NULL DEFAULT 'pending',
     last_error TEXT,
+    api_permissions TEXT,
+    fee_tier INTEGER NOT NULL DEFAULT 0,
+    fee_token_discount BOOLEAN NOT NULL DEFAULT 0,
+    history_credentials TEXT,
+    history_credentials_nonce TEXT,
+    history_credentials_salt TEXT,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
@@ -24,6 +24,9 @@ CREATE TABLE IF NOT EXISTS grid_trading_strategies (
     active_buy_orders INTEGER NOT NULL DEFAULT 0,
     active_sell_orders INTEGER NOT NULL DEFAULT 0,
     last_execution_at TEXT,
+    version INTEGER NOT NULL DEFAULT 1,
+    state_json TEXT,
+    kill_switch BOOLEAN NOT NULL DEFAULT 0,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
@@ -10,6 +10,7 @@ CREATE INDEX IF NOT EXISTS idx_user_sessions_expires ON user_sessions(expires_at
 -- Exchange connection indexes
 CREATE INDEX IF NOT EXISTS idx_exchange_connections_user_id ON exchange_connections(user_id);
 CREATE INDEX IF NOT EXISTS idx_exchange_connections_exchange_name ON exchange_connections(exchange_name);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_exchange_connections_user_exchange_name ON exchange_connections(user_id, exchange_name, display_name);
 
 -- Wallet balance indexes
 CREATE INDEX IF NOT EXISTS idx_wallet_balances_exchange_connection_id ON wallet_balances(exchange_connection_id);
@@ -54,3 +55,13 @@ CREATE INDEX IF NOT EXISTS idx_backtest_results_strategy ON backtest_results(str
 CREATE INDEX IF NOT EXISTS idx_backtest_results_symbol ON backtest_results(symbol);
 CREATE INDEX IF NOT EXISTS idx_backtest_results_status ON backtest_results(status);
 CREATE INDEX IF NOT EXISTS idx_backtest_results_created_at ON backtest_results(created_at);
+
+-- Balance snapshot indexes (one snapshot per user per day)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_balance_snapshots_user_date ON balance_snapshots(user_id, snapshot_date);
+
+-- Per-connection balance history indexes
+CREATE INDEX IF NOT EXISTS idx_connection_balance_snapshots_connection_time ON connection_balance_snapshots(connection_id, recorded_at);
+
+-- Paper trading indexes
+CREATE INDEX IF NOT EXISTS idx_paper_portfolios_user_id ON paper_portfolios(user_id);
+CREATE INDEX IF NOT EXISTS idx_paper_trades_portfolio_id ON paper_trades(portfolio_id);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS paper_portfolios (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    strategy_id TEXT NOT NULL UNIQUE,
+    strategy_type TEXT NOT NULL,
+    starting_balance TEXT NOT NULL,
+    cash_balance TEXT NOT NULL,
+    asset_quantity TEXT NOT NULL DEFAULT '0',
+    reset_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS paper_trades (
+    id TEXT PRIMARY KEY,
+    portfolio_id TEXT NOT NULL,
+    side TEXT NOT NULL,
+    price TEXT NOT NULL,
+    quantity TEXT NOT NULL,
+    total_value TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    executed_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (portfolio_id) REFERENCES paper_portfolios (id) ON DELETE CASCADE
+);
@@ -21,6 +21,9 @@ CREATE TABLE IF NOT EXISTS sma_crossover_strategies (
     last_signal_type TEXT,
     last_signal_time TEXT,
     last_execution_at TEXT,
+    version INTEGER NOT NULL DEFAULT 1,
+    state_json TEXT,
+    kill_switch BOOLEAN NOT NULL DEFAULT 0,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
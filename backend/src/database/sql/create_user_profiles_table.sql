@@ -9,6 +9,11 @@ CREATE TABLE IF NOT EXISTS user_profiles (
     join_date TEXT NOT NULL,
     avatar_url TEXT,
     is_verified BOOLEAN NOT NULL DEFAULT 0,
+    max_order_notional TEXT,
+    clamp_oversized_orders BOOLEAN NOT NULL DEFAULT 0,
+    symbol_allow_list TEXT,
+    symbol_deny_list TEXT,
+    base_currency TEXT NOT NULL DEFAULT 'USD',
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
@@ -0,0 +1,207 @@
+//! Helpers for tests that need a real database.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use uuid::Uuid;
+
+use crate::models::{backtest_result, dca_strategy, exchange_connection, user, user_profile};
+
+/// Fresh SQLite database in a temporary file with the full schema applied
+pub async fn test_db() -> DatabaseConnection {
+    let path = std::env::temp_dir().join(format!("e_squared_test_{}.db", Uuid::new_v4()));
+    let url = format!("sqlite://{}?mode=rwc", path.display());
+    super::create_connection(&url)
+        .await
+        .expect("create test database")
+}
+
+/// Insert a user and return its id
+pub async fn seed_user(db: &DatabaseConnection) -> Uuid {
+    let id = Uuid::new_v4();
+    user::ActiveModel {
+        id: Set(id),
+        email: Set(format!("{}@example.com", id)),
+        password_hash: Set("hash".to_string()),
+        is_active: Set(true),
+        is_verified: Set(true),
+        totp_secret: Set(None),
+        totp_enabled: Set(false),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await
+    .expect("insert user");
+    id
+}
+
+/// Insert a profile with default settings for `user_id`
+pub async fn seed_user_profile(db: &DatabaseConnection, user_id: Uuid) -> user_profile::Model {
+    user_profile::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        name: Set("Trader".to_string()),
+        email: Set(format!("{}@example.com", user_id)),
+        phone: Set(None),
+        location: Set(None),
+        bio: Set(None),
+        join_date: Set("January 2024".to_string()),
+        avatar_url: Set(None),
+        is_verified: Set(false),
+        max_order_notional: Set(None),
+        clamp_oversized_orders: Set(false),
+        symbol_allow_list: Set(None),
+        symbol_deny_list: Set(None),
+        base_currency: Set("USD".to_string()),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await
+    .expect("insert user profile")
+}
+
+/// Insert an exchange connection for `user_id` and return its id
+pub async fn seed_exchange_connection(db: &DatabaseConnection, user_id: Uuid, exchange_name: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    exchange_connection::ActiveModel {
+        id: Set(id),
+        user_id: Set(user_id),
+        exchange_name: Set(exchange_name.to_string()),
+        display_name: Set(exchange_name.to_string()),
+        encrypted_api_key: Set("key".to_string()),
+        encrypted_api_secret: Set("secret".to_string()),
+        encrypted_passphrase: Set(None),
+        api_key_nonce: Set("nonce".to_string()),
+        api_secret_nonce: Set("nonce".to_string()),
+        passphrase_nonce: Set(None),
+        api_key_salt: Set("salt".to_string()),
+        api_secret_salt: Set("salt".to_string()),
+        passphrase_salt: Set(None),
+        is_active: Set(true),
+        last_sync: Set(None),
+        connection_status: Set("connected".to_string()),
+        last_error: Set(None),
+        api_permissions: Set(None),
+        fee_tier: Set(0),
+        fee_token_discount: Set(false),
+        history_credentials: Set(None),
+        history_credentials_nonce: Set(None),
+        history_credentials_salt: Set(None),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await
+    .expect("insert exchange connection");
+    id
+}
+
+/// Insert an active DCA strategy on `asset_symbol` and return its id
+pub async fn seed_dca_strategy(db: &DatabaseConnection, user_id: Uuid, asset_symbol: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    dca_strategy::ActiveModel {
+        id: Set(id),
+        user_id: Set(user_id),
+        name: Set(format!("{} DCA", asset_symbol)),
+        asset_symbol: Set(asset_symbol.to_string()),
+        status: Set("active".to_string()),
+        config_json: Set("{}".to_string()),
+        total_invested: Set(Decimal::ZERO),
+        total_purchased: Set(Decimal::ZERO),
+        average_buy_price: Set(None),
+        last_execution_at: Set(None),
+        next_execution_at: Set(None),
+        version: Set(1),
+        state_json: Set(None),
+        kill_switch: Set(false),
+        mode: Set("live".to_string()),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await
+    .expect("insert DCA strategy");
+    id
+}
+
+/// Record a filled DCA execution of `quantity` on `connection_id`
+pub async fn seed_dca_execution(
+    db: &DatabaseConnection,
+    strategy_id: Uuid,
+    connection_id: Uuid,
+    execution_type: &str,
+    quantity: Decimal,
+    timestamp: DateTime<Utc>,
+) {
+    dca_strategy::execution::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        strategy_id: Set(strategy_id),
+        exchange_connection_id: Set(connection_id),
+        execution_type: Set(execution_type.to_string()),
+        trigger_reason: Set("scheduled".to_string()),
+        amount_usd: Set(Decimal::from(100)),
+        amount_asset: Set(Some(quantity)),
+        price_at_execution: Set(None),
+        fear_greed_index: Set(None),
+        market_volatility: Set(None),
+        order_id: Set(None),
+        order_status: Set("filled".to_string()),
+        execution_timestamp: Set(timestamp),
+        error_message: Set(None),
+        mode: Set("live".to_string()),
+        created_at: Set(timestamp),
+    }
+    .insert(db)
+    .await
+    .expect("insert DCA execution");
+}
+
+/// Insert a running backtest of BTCUSDT for `user_id` and return its id
+pub async fn seed_backtest_result(db: &DatabaseConnection, user_id: Uuid) -> Uuid {
+    let id = Uuid::new_v4();
+    backtest_result::ActiveModel {
+        id: Set(id),
+        user_id: Set(user_id),
+        name: Set("Test backtest".to_string()),
+        description: Set(None),
+        strategy_name: Set("scripted_test".to_string()),
+        strategy_type: Set(None),
+        symbol: Set("BTCUSDT".to_string()),
+        interval: Set("1h".to_string()),
+        start_date: Set(Utc::now()),
+        end_date: Set(Utc::now()),
+        initial_balance: Set(Decimal::from(1000)),
+        final_balance: Set(Decimal::ZERO),
+        total_return: Set(Decimal::ZERO),
+        total_return_percentage: Set(Decimal::ZERO),
+        max_drawdown: Set(Decimal::ZERO),
+        max_drawdown_percentage: Set(Decimal::ZERO),
+        sharpe_ratio: Set(None),
+        total_trades: Set(0),
+        winning_trades: Set(0),
+        losing_trades: Set(0),
+        win_rate: Set(Decimal::ZERO),
+        profit_factor: Set(None),
+        largest_win: Set(Decimal::ZERO),
+        largest_loss: Set(Decimal::ZERO),
+        average_win: Set(Decimal::ZERO),
+        average_loss: Set(Decimal::ZERO),
+        total_invested: Set(Decimal::ZERO),
+        strategy_parameters: Set(serde_json::json!({})),
+        trades_data: Set(serde_json::json!([])),
+        equity_curve: Set(serde_json::json!([])),
+        drawdown_curve: Set(serde_json::json!([])),
+        status: Set("running".to_string()),
+        error_message: Set(None),
+        checkpoint_json: Set(None),
+        execution_time_ms: Set(None),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await
+    .expect("insert backtest result");
+    id
+}
@@ -15,6 +15,7 @@ use super::common_types::*;
 use super::errors::DexError;
 use super::traits::DexConnector;
 use super::WalletCredentials;
+use crate::utils::http::{request_with_policy, RetryPolicy};
 
 /// Jupiter V6 API Quote Response
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -97,6 +98,7 @@ pub struct JupiterConnector {
     keypair: Keypair,
     jupiter_api_url: String,
     http_client: reqwest::Client,
+    retry_policy: RetryPolicy,
 }
 
 impl JupiterConnector {
@@ -110,8 +112,11 @@ impl JupiterConnector {
             CommitmentConfig::confirmed(),
         );
 
-        // Parse private key to create keypair
-        let keypair = Self::parse_private_key(&credentials.private_key)?;
+        // Solana transactions are signed locally, so a WalletConnect session can't be used
+        let private_key = credentials.signing.private_key().ok_or_else(|| {
+            DexError::InvalidCredentials("Jupiter requires the wallet's private key".to_string())
+        })?;
+        let keypair = Self::parse_private_key(private_key)?;
 
         // Verify wallet address matches
         let derived_address = keypair.pubkey().to_string();
@@ -134,6 +139,7 @@ impl JupiterConnector {
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .map_err(|e| DexError::InternalError(format!("Failed to create HTTP client: {}", e)))?,
+            retry_policy: RetryPolicy::default(),
         })
     }
 
@@ -204,9 +210,9 @@ impl JupiterConnector {
             self.jupiter_api_url, input_mint, output_mint, amount, slippage_bps
         );
 
-        let response = self.http_client
-            .get(&url)
-            .send()
+        let request = self.http_client.get(&url);
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| DexError::NetworkError(format!("Jupiter API request failed: {}", e)))?;
 
@@ -242,10 +248,11 @@ impl JupiterConnector {
 
         tracing::debug!("Jupiter swap request to: {}", url);
 
-        let response = self.http_client
+        let request = self.http_client
             .post(&url)
-            .json(&swap_request)
-            .send()
+            .json(&swap_request);
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| DexError::NetworkError(format!("Jupiter swap API request failed: {}", e)))?;
 
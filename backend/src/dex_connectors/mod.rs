@@ -6,11 +6,13 @@ pub mod uniswap;
 pub mod pancakeswap;
 pub mod raydium;
 pub mod jupiter;
+pub mod signer;
 
 pub use traits::*;
 pub use common_types::*;
 pub use errors::*;
 pub use factory::*;
+pub use signer::{SigningMethod, TransactionSigner, WalletConnectSession};
 
 use serde::{Deserialize, Serialize};
 
@@ -58,6 +60,7 @@ impl DEX {
 /// Wallet credentials for DEX operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletCredentials {
-    pub private_key: String,
+    /// Stored key or WalletConnect session that signs the wallet's transactions
+    pub signing: SigningMethod,
     pub wallet_address: String,
 }
@@ -10,6 +10,7 @@ use std::sync::Arc;
 use super::common_types::*;
 use super::errors::DexError;
 use super::traits::DexConnector;
+use super::signer::{evm_signer, sign_and_send, TransactionSigner};
 use super::WalletCredentials;
 
 // PancakeSwap V3 Contract Addresses on BNB Chain (BSC) Mainnet
@@ -24,6 +25,9 @@ const FEE_LOW: u32 = 500; // 0.05%
 const FEE_MEDIUM: u32 = 2500; // 0.25%
 const FEE_HIGH: u32 = 10000; // 1%
 
+/// BSC mainnet
+const CHAIN_ID: u64 = 56;
+
 /// PancakeSwap V3 connector for BNB Chain (BSC)
 ///
 /// This connector supports:
@@ -35,12 +39,18 @@ const FEE_HIGH: u32 = 10000; // 1%
 pub struct PancakeSwapConnector {
     credentials: WalletCredentials,
     provider: Arc<Provider<Http>>,
-    wallet: LocalWallet,
+    signer: Arc<dyn TransactionSigner>,
     chain_id: u64,
 }
 
 impl PancakeSwapConnector {
     pub fn new(credentials: WalletCredentials) -> Result<Self, DexError> {
+        let signer = evm_signer(&credentials, CHAIN_ID)?;
+        Self::with_signer(credentials, signer)
+    }
+
+    /// Connector whose transactions are signed by `signer`
+    pub fn with_signer(credentials: WalletCredentials, signer: Arc<dyn TransactionSigner>) -> Result<Self, DexError> {
         // Get BSC RPC URL from environment or use default
         let rpc_url = std::env::var("BSC_RPC_URL")
             .unwrap_or_else(|_| "https://bsc-dataseed1.binance.org".to_string());
@@ -49,40 +59,22 @@ impl PancakeSwapConnector {
         let provider = Provider::<Http>::try_from(rpc_url.as_str())
             .map_err(|e| DexError::NetworkError(format!("Failed to create provider: {}", e)))?;
 
-        // Parse private key
-        let wallet = Self::parse_private_key(&credentials.private_key)?;
-
         // Verify wallet address matches
-        let derived_address = format!("{:?}", wallet.address());
+        let derived_address = format!("{:?}", signer.address());
         if derived_address.to_lowercase() != credentials.wallet_address.to_lowercase() {
             return Err(DexError::InvalidCredentials(
-                "Wallet address does not match private key".to_string()
+                "Wallet address does not match the signing wallet".to_string()
             ));
         }
 
         Ok(Self {
             credentials,
             provider: Arc::new(provider),
-            wallet,
-            chain_id: 56, // BSC mainnet
+            signer,
+            chain_id: CHAIN_ID,
         })
     }
 
-    fn parse_private_key(private_key: &str) -> Result<LocalWallet, DexError> {
-        // Remove 0x prefix if present
-        let key_str = private_key.strip_prefix("0x").unwrap_or(private_key);
-
-        // Parse as hex
-        if key_str.len() != 64 {
-            return Err(DexError::InvalidCredentials(
-                "Invalid BNB Chain private key length (expected 64 hex characters)".to_string()
-            ));
-        }
-
-        LocalWallet::from_str(private_key)
-            .map_err(|e| DexError::InvalidCredentials(format!("Invalid private key: {}", e)))
-    }
-
     async fn get_bnb_balance(&self, address: Address) -> Result<U256, DexError> {
         self.provider
             .get_balance(address, None)
@@ -267,7 +259,7 @@ impl PancakeSwapConnector {
 
         // recipient (this wallet)
         calldata.extend_from_slice(&[0u8; 12]);
-        calldata.extend_from_slice(self.wallet.address().as_bytes());
+        calldata.extend_from_slice(self.signer.address().as_bytes());
 
         // amountIn
         let mut amount_in_bytes = [0u8; 32];
@@ -290,17 +282,7 @@ impl PancakeSwapConnector {
             ..Default::default()
         };
 
-        let client = SignerMiddleware::new(self.provider.clone(), self.wallet.clone().with_chain_id(self.chain_id));
-
-        let pending_tx = client
-            .send_transaction(tx, None)
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Failed to send transaction: {}", e)))?;
-
-        let receipt = pending_tx
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Transaction failed: {}", e)))?
-            .ok_or_else(|| DexError::TransactionFailed("No receipt".to_string()))?;
+        let receipt = sign_and_send(&self.provider, self.signer.as_ref(), self.chain_id, tx).await?;
 
         Ok(receipt.transaction_hash)
     }
@@ -326,16 +308,7 @@ impl PancakeSwapConnector {
             ..Default::default()
         };
 
-        let client = SignerMiddleware::new(self.provider.clone(), self.wallet.clone().with_chain_id(self.chain_id));
-
-        let pending_tx = client
-            .send_transaction(tx, None)
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Failed to approve token: {}", e)))?;
-
-        pending_tx
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Approval transaction failed: {}", e)))?;
+        sign_and_send(&self.provider, self.signer.as_ref(), self.chain_id, tx).await?;
 
         Ok(())
     }
@@ -15,6 +15,7 @@ use super::common_types::*;
 use super::errors::DexError;
 use super::traits::DexConnector;
 use super::WalletCredentials;
+use crate::utils::http::{request_with_policy, RetryPolicy};
 
 /// Raydium Production-Ready Integration
 ///
@@ -136,6 +137,7 @@ pub struct RaydiumConnector {
     rpc_client: RpcClient,
     keypair: Keypair,
     http_client: reqwest::Client,
+    retry_policy: RetryPolicy,
     raydium_api_base: String,
     raydium_transaction_api: String,
 }
@@ -151,8 +153,11 @@ impl RaydiumConnector {
             CommitmentConfig::confirmed(),
         );
 
-        // Parse private key to create keypair
-        let keypair = Self::parse_private_key(&credentials.private_key)?;
+        // Solana transactions are signed locally, so a WalletConnect session can't be used
+        let private_key = credentials.signing.private_key().ok_or_else(|| {
+            DexError::InvalidCredentials("Raydium requires the wallet's private key".to_string())
+        })?;
+        let keypair = Self::parse_private_key(private_key)?;
 
         // Verify wallet address matches
         let derived_address = keypair.pubkey().to_string();
@@ -176,6 +181,7 @@ impl RaydiumConnector {
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .map_err(|e| DexError::InternalError(format!("Failed to create HTTP client: {}", e)))?,
+            retry_policy: RetryPolicy::default(),
             raydium_api_base,
             raydium_transaction_api,
         })
@@ -244,9 +250,9 @@ impl RaydiumConnector {
 
         tracing::debug!("Fetching Raydium priority fees from: {}", url);
 
-        let response = self.http_client
-            .get(&url)
-            .send()
+        let request = self.http_client.get(&url);
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| DexError::NetworkError(format!("Priority fee request failed: {}", e)))?;
 
@@ -284,9 +290,9 @@ impl RaydiumConnector {
 
         tracing::debug!("Computing Raydium swap: {}", url);
 
-        let response = self.http_client
-            .get(&url)
-            .send()
+        let request = self.http_client.get(&url);
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| DexError::NetworkError(format!("Swap compute request failed: {}", e)))?;
 
@@ -332,10 +338,11 @@ impl RaydiumConnector {
 
         tracing::debug!("Fetching Raydium swap transaction from: {}", url);
 
-        let response = self.http_client
+        let http_request = self.http_client
             .post(&url)
-            .json(&request)
-            .send()
+            .json(&request);
+
+        let response = request_with_policy(&self.retry_policy, http_request)
             .await
             .map_err(|e| DexError::NetworkError(format!("Swap transaction request failed: {}", e)))?;
 
@@ -0,0 +1,418 @@
+//! Transaction signing for the EVM DEX connectors.
+//!
+//! Connectors build a transaction and hand it to a [`TransactionSigner`], so the calldata,
+//! nonce, gas and fees are the same however the wallet signs. Which signer is used
+//! depends on the wallet's [`SigningMethod`]:
+//!
+//! - `PrivateKey`: the stored key signs in-process.
+//! - `WalletConnect`: the user's own wallet signs and no key reaches the platform. Each
+//!   transaction is queued in [`wallet_connect_requests`] under the session's topic. The
+//!   frontend, which holds the WalletConnect session, polls
+//!   `GET /wallets/signature-requests?topic=` for the requests of the user's wallets, asks
+//!   the wallet to sign each request's `transaction` (`eth_signTransaction`, taking `r`,
+//!   `s` and `v` from the signed result), and posts the 65-byte signature as hex to
+//!   `/wallets/signature-requests/{id}/signature`, or posts to
+//!   `/wallets/signature-requests/{id}/reject` if the user declines. The connector checks
+//!   the signature recovers to the wallet's address and broadcasts the transaction itself.
+
+use async_trait::async_trait;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use super::errors::DexError;
+use super::WalletCredentials;
+
+/// How long a transaction waits for the user to sign it in their wallet
+const WALLET_CONNECT_TIMEOUT: Duration = Duration::from_secs(120);
+
+static WALLET_CONNECT_REQUESTS: Lazy<Arc<SignatureRequests>> = Lazy::new(Default::default);
+
+/// How a wallet's transactions are signed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningMethod {
+    /// Key stored on the platform, signing in-process
+    PrivateKey(String),
+    /// The user's wallet signs each transaction over a WalletConnect session
+    WalletConnect { session: WalletConnectSession },
+}
+
+impl SigningMethod {
+    /// The stored key, for connectors that can only sign locally
+    pub fn private_key(&self) -> Option<&str> {
+        match self {
+            Self::PrivateKey(private_key) => Some(private_key),
+            Self::WalletConnect { .. } => None,
+        }
+    }
+}
+
+/// WalletConnect session the frontend opened with the user's wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletConnectSession {
+    /// Session topic, which the frontend uses to find the requests for its session
+    pub topic: String,
+}
+
+/// Produces signatures for a wallet's transactions
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    /// Address the signatures are made by
+    fn address(&self) -> Address;
+
+    /// Signature over `tx`'s signing hash
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, DexError>;
+}
+
+/// Signer for a key held on the platform
+pub struct LocalKeySigner {
+    wallet: LocalWallet,
+}
+
+impl LocalKeySigner {
+    pub fn new(wallet: LocalWallet) -> Self {
+        Self { wallet }
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for LocalKeySigner {
+    fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, DexError> {
+        self.wallet
+            .sign_transaction(tx)
+            .await
+            .map_err(|e| DexError::InternalError(format!("Failed to sign transaction: {}", e)))
+    }
+}
+
+/// Transaction waiting for the user's wallet to sign it
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureRequest {
+    pub id: Uuid,
+    pub session_topic: String,
+    pub from: Address,
+    pub chain_id: u64,
+    pub transaction: TypedTransaction,
+    /// Hash the signature must be made over
+    pub sighash: H256,
+}
+
+/// Signature requests waiting on WalletConnect wallets
+#[derive(Default)]
+pub struct SignatureRequests {
+    pending: Mutex<HashMap<Uuid, (SignatureRequest, oneshot::Sender<Signature>)>>,
+}
+
+impl SignatureRequests {
+    /// Queue `request` and wait up to `timeout` for its signature
+    async fn request(&self, request: SignatureRequest, timeout: Duration) -> Result<Signature, DexError> {
+        let id = request.id;
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, (request, sender));
+
+        let outcome = tokio::time::timeout(timeout, receiver).await;
+        self.pending.lock().unwrap().remove(&id);
+        match outcome {
+            Ok(Ok(signature)) => Ok(signature),
+            Ok(Err(_)) => Err(DexError::TransactionFailed("Signature request was rejected".to_string())),
+            Err(_) => Err(DexError::TransactionFailed(format!(
+                "Wallet did not sign within {} seconds",
+                timeout.as_secs()
+            ))),
+        }
+    }
+
+    /// Requests waiting on the wallet in session `topic`
+    pub fn pending(&self, topic: &str) -> Vec<SignatureRequest> {
+        self.pending
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|(request, _)| request.session_topic == topic)
+            .map(|(request, _)| request.clone())
+            .collect()
+    }
+
+    /// Request `id`, if it is still waiting
+    pub fn get(&self, id: Uuid) -> Option<SignatureRequest> {
+        self.pending.lock().unwrap().get(&id).map(|(request, _)| request.clone())
+    }
+
+    /// Hand back the hex signature the wallet made for request `id`. Signatures not made
+    /// by the requesting wallet are refused and the request keeps waiting.
+    pub fn submit(&self, id: Uuid, signature: &str) -> Result<(), DexError> {
+        let signature = Signature::from_str(signature)
+            .map_err(|e| DexError::InvalidCredentials(format!("Invalid signature: {}", e)))?;
+
+        let mut pending = self.pending.lock().unwrap();
+        let (request, _) = pending
+            .get(&id)
+            .ok_or_else(|| DexError::InvalidCredentials(format!("No signature request {}", id)))?;
+        check_signer(request.sighash, &signature, request.from)?;
+
+        let (_, sender) = pending.remove(&id).expect("request is pending");
+        sender
+            .send(signature)
+            .map_err(|_| DexError::TransactionFailed(format!("Signature request {} is no longer waiting", id)))
+    }
+
+    /// Fail request `id`, when the user declines it in their wallet. False if it wasn't pending.
+    pub fn reject(&self, id: Uuid) -> bool {
+        self.pending.lock().unwrap().remove(&id).is_some()
+    }
+}
+
+/// Signature requests for every WalletConnect wallet, shared with the API the frontend polls
+pub fn wallet_connect_requests() -> Arc<SignatureRequests> {
+    WALLET_CONNECT_REQUESTS.clone()
+}
+
+/// Signer that asks the user's wallet for each signature over WalletConnect
+pub struct WalletConnectSigner {
+    session: WalletConnectSession,
+    address: Address,
+    requests: Arc<SignatureRequests>,
+    timeout: Duration,
+}
+
+impl WalletConnectSigner {
+    pub fn new(session: WalletConnectSession, address: Address, requests: Arc<SignatureRequests>) -> Self {
+        Self { session, address, requests, timeout: WALLET_CONNECT_TIMEOUT }
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for WalletConnectSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, DexError> {
+        let request = SignatureRequest {
+            id: Uuid::new_v4(),
+            session_topic: self.session.topic.clone(),
+            from: self.address,
+            chain_id: tx.chain_id().map(|id| id.as_u64()).unwrap_or_default(),
+            transaction: tx.clone(),
+            sighash: tx.sighash(),
+        };
+        self.requests.request(request, self.timeout).await
+    }
+}
+
+/// Signer for an EVM wallet's credentials on chain `chain_id`
+pub fn evm_signer(credentials: &WalletCredentials, chain_id: u64) -> Result<Arc<dyn TransactionSigner>, DexError> {
+    match &credentials.signing {
+        SigningMethod::PrivateKey(private_key) => {
+            let key_str = private_key.strip_prefix("0x").unwrap_or(private_key);
+            if key_str.len() != 64 {
+                return Err(DexError::InvalidCredentials(
+                    "Invalid private key length (expected 64 hex characters)".to_string()
+                ));
+            }
+
+            let wallet = LocalWallet::from_str(key_str)
+                .map_err(|e| DexError::InvalidCredentials(format!("Invalid private key: {}", e)))?;
+            Ok(Arc::new(LocalKeySigner::new(wallet.with_chain_id(chain_id))))
+        }
+        SigningMethod::WalletConnect { session } => {
+            let address = Address::from_str(&credentials.wallet_address)
+                .map_err(|_| DexError::InvalidCredentials("Invalid wallet address".to_string()))?;
+            Ok(Arc::new(WalletConnectSigner::new(session.clone(), address, wallet_connect_requests())))
+        }
+    }
+}
+
+/// Error unless `signature` over `sighash` was made by `expected`
+fn check_signer(sighash: H256, signature: &Signature, expected: Address) -> Result<(), DexError> {
+    let signer = signature
+        .recover(sighash)
+        .map_err(|e| DexError::InvalidCredentials(format!("Invalid signature: {}", e)))?;
+    if signer != expected {
+        return Err(DexError::InvalidCredentials(format!(
+            "Transaction was signed by {:?}, not the wallet {:?}",
+            signer, expected
+        )));
+    }
+    Ok(())
+}
+
+/// Fill in `tx`'s nonce, gas and fees, have `signer` sign it and broadcast it, waiting for
+/// the receipt
+pub async fn sign_and_send(
+    provider: &Provider<Http>,
+    signer: &dyn TransactionSigner,
+    chain_id: u64,
+    tx: TransactionRequest,
+) -> Result<TransactionReceipt, DexError> {
+    let mut tx: TypedTransaction = tx.from(signer.address()).chain_id(chain_id).into();
+    provider
+        .fill_transaction(&mut tx, None)
+        .await
+        .map_err(|e| DexError::GasEstimationFailed(format!("Failed to prepare transaction: {}", e)))?;
+
+    let signature = signer.sign_transaction(&tx).await?;
+    check_signer(tx.sighash(), &signature, signer.address())?;
+
+    let pending_tx = provider
+        .send_raw_transaction(tx.rlp_signed(&signature))
+        .await
+        .map_err(|e| DexError::TransactionFailed(format!("Failed to send transaction: {}", e)))?;
+
+    pending_tx
+        .await
+        .map_err(|e| DexError::TransactionFailed(format!("Transaction failed: {}", e)))?
+        .ok_or_else(|| DexError::TransactionFailed("No receipt".to_string()))
+}
+
+/// Signer for tests: signs with a fixed key and records every transaction it signs
+#[cfg(test)]
+pub struct MockSigner {
+    wallet: LocalWallet,
+    pub signed: Mutex<Vec<TypedTransaction>>,
+}
+
+#[cfg(test)]
+impl MockSigner {
+    /// Well-known development key, address 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+    pub const PRIVATE_KEY: &'static str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    pub fn new() -> Self {
+        Self { wallet: LocalWallet::from_str(Self::PRIVATE_KEY).unwrap(), signed: Mutex::new(Vec::new()) }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl TransactionSigner for MockSigner {
+    fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, DexError> {
+        self.signed.lock().unwrap().push(tx.clone());
+        Ok(self.wallet.sign_transaction(tx).await.unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction() -> TypedTransaction {
+        TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            .value(1_000u64)
+            .nonce(7u64)
+            .gas(21_000u64)
+            .gas_price(30_000_000_000u64)
+            .chain_id(1u64)
+            .into()
+    }
+
+    fn credentials(signing: SigningMethod) -> WalletCredentials {
+        WalletCredentials {
+            signing,
+            wallet_address: format!("{:?}", MockSigner::new().address()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_private_key_and_mock_signers_agree() {
+        let tx = transaction();
+        let mock = MockSigner::new();
+        let local = evm_signer(&credentials(SigningMethod::PrivateKey(format!("0x{}", MockSigner::PRIVATE_KEY))), 1).unwrap();
+
+        assert_eq!(local.address(), mock.address());
+        let signature = local.sign_transaction(&tx).await.unwrap();
+        assert_eq!(signature, mock.sign_transaction(&tx).await.unwrap());
+        assert!(check_signer(tx.sighash(), &signature, mock.address()).is_ok());
+        assert_eq!(mock.signed.lock().unwrap().len(), 1);
+
+        let short_key = credentials(SigningMethod::PrivateKey("0x1234".to_string()));
+        assert!(matches!(evm_signer(&short_key, 1), Err(DexError::InvalidCredentials(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wallet_connect_waits_for_the_wallets_signature() {
+        let requests = Arc::new(SignatureRequests::default());
+        let mock = MockSigner::new();
+        let session = WalletConnectSession { topic: "topic-1".to_string() };
+        let signer = WalletConnectSigner::new(session, mock.address(), requests.clone());
+        let tx = transaction();
+
+        // Stand in for the frontend: sign whatever the session is asked for
+        let frontend = {
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                let request = loop {
+                    if let Some(request) = requests.pending("topic-1").pop() {
+                        break request;
+                    }
+                    tokio::task::yield_now().await;
+                };
+                assert!(requests.pending("another-topic").is_empty());
+                assert_eq!(request.chain_id, 1);
+
+                // A signature from some other key is refused and the request keeps waiting
+                let stranger = LocalWallet::from_str("59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d").unwrap();
+                let forged = stranger.sign_transaction(&request.transaction).await.unwrap();
+                assert!(requests.submit(request.id, &forged.to_string()).is_err());
+
+                let signature = MockSigner::new().sign_transaction(&request.transaction).await.unwrap();
+                requests.submit(request.id, &signature.to_string()).unwrap();
+            })
+        };
+
+        let signature = signer.sign_transaction(&tx).await.unwrap();
+        frontend.await.unwrap();
+        assert!(check_signer(tx.sighash(), &signature, mock.address()).is_ok());
+        assert!(requests.pending("topic-1").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_wallet_connect_request_fails_the_transaction() {
+        let requests = Arc::new(SignatureRequests::default());
+        let session = WalletConnectSession { topic: "topic-2".to_string() };
+        let signer = WalletConnectSigner::new(session, Address::repeat_byte(0x22), requests.clone());
+
+        let rejecting = {
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                loop {
+                    if let Some(request) = requests.pending("topic-2").pop() {
+                        assert!(requests.reject(request.id));
+                        break;
+                    }
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let result = signer.sign_transaction(&transaction()).await;
+        rejecting.await.unwrap();
+        assert!(matches!(result, Err(DexError::TransactionFailed(_))));
+    }
+
+    #[test]
+    fn test_signing_method_serializes_by_name() {
+        let method = SigningMethod::WalletConnect { session: WalletConnectSession { topic: "abc".to_string() } };
+        let json = serde_json::to_value(&method).unwrap();
+        assert_eq!(json, serde_json::json!({ "wallet_connect": { "session": { "topic": "abc" } } }));
+        assert_eq!(serde_json::from_value::<SigningMethod>(json).unwrap(), method);
+        assert_eq!(SigningMethod::PrivateKey("key".to_string()).private_key(), Some("key"));
+        assert_eq!(method.private_key(), None);
+    }
+}
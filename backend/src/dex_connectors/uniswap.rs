@@ -1,7 +1,9 @@
 use async_trait::async_trait;
+use ethers::abi;
 use ethers::prelude::*;
 use ethers::providers::{Http, Provider};
 use ethers::types::{Address, H160, H256, U256};
+use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -10,6 +12,7 @@ use std::sync::Arc;
 use super::common_types::*;
 use super::errors::DexError;
 use super::traits::DexConnector;
+use super::signer::{evm_signer, sign_and_send, TransactionSigner};
 use super::WalletCredentials;
 
 // Uniswap V3 Contract Addresses on Ethereum Mainnet
@@ -18,11 +21,16 @@ const QUOTER_V2: &str = "0x61fFE014bA17989E743c5F6cB21bF9697530B21e";
 const FACTORY: &str = "0x1F98431c8aD98523631AE4a59f267346ea31F984";
 const WETH9: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
 
+const QUOTE_EXACT_INPUT_SINGLE: &str = "quoteExactInputSingle((address,address,uint256,uint24,uint160))";
+
 // Fee tiers for Uniswap V3 pools (in hundredths of a bip, i.e. 1e-6)
 const FEE_LOW: u32 = 500; // 0.05%
 const FEE_MEDIUM: u32 = 3000; // 0.3%
 const FEE_HIGH: u32 = 10000; // 1%
 
+/// Ethereum mainnet
+const CHAIN_ID: u64 = 1;
+
 /// Uniswap V3 connector for Ethereum
 ///
 /// This connector supports:
@@ -34,54 +42,38 @@ const FEE_HIGH: u32 = 10000; // 1%
 pub struct UniswapConnector {
     credentials: WalletCredentials,
     provider: Arc<Provider<Http>>,
-    wallet: LocalWallet,
+    quoter: UniswapQuoter,
+    signer: Arc<dyn TransactionSigner>,
     chain_id: u64,
 }
 
 impl UniswapConnector {
     pub fn new(credentials: WalletCredentials) -> Result<Self, DexError> {
-        // Get Ethereum RPC URL from environment or use default
-        let rpc_url = std::env::var("ETHEREUM_RPC_URL")
-            .unwrap_or_else(|_| "https://eth-mainnet.g.alchemy.com/v2/demo".to_string());
-
-        // Create provider
-        let provider = Provider::<Http>::try_from(rpc_url.as_str())
-            .map_err(|e| DexError::NetworkError(format!("Failed to create provider: {}", e)))?;
+        let signer = evm_signer(&credentials, CHAIN_ID)?;
+        Self::with_signer(credentials, signer)
+    }
 
-        // Parse private key
-        let wallet = Self::parse_private_key(&credentials.private_key)?;
+    /// Connector whose transactions are signed by `signer`
+    pub fn with_signer(credentials: WalletCredentials, signer: Arc<dyn TransactionSigner>) -> Result<Self, DexError> {
+        let provider = Arc::new(ethereum_provider()?);
 
         // Verify wallet address matches
-        let derived_address = format!("{:?}", wallet.address());
+        let derived_address = format!("{:?}", signer.address());
         if derived_address.to_lowercase() != credentials.wallet_address.to_lowercase() {
             return Err(DexError::InvalidCredentials(
-                "Wallet address does not match private key".to_string()
+                "Wallet address does not match the signing wallet".to_string()
             ));
         }
 
         Ok(Self {
             credentials,
-            provider: Arc::new(provider),
-            wallet,
-            chain_id: 1, // Ethereum mainnet
+            quoter: UniswapQuoter::new(provider.clone()),
+            provider,
+            signer,
+            chain_id: CHAIN_ID,
         })
     }
 
-    fn parse_private_key(private_key: &str) -> Result<LocalWallet, DexError> {
-        // Remove 0x prefix if present
-        let key_str = private_key.strip_prefix("0x").unwrap_or(private_key);
-
-        // Parse as hex
-        if key_str.len() != 64 {
-            return Err(DexError::InvalidCredentials(
-                "Invalid Ethereum private key length (expected 64 hex characters)".to_string()
-            ));
-        }
-
-        LocalWallet::from_str(private_key)
-            .map_err(|e| DexError::InvalidCredentials(format!("Invalid private key: {}", e)))
-    }
-
     async fn get_eth_balance(&self, address: Address) -> Result<U256, DexError> {
         self.provider
             .get_balance(address, None)
@@ -115,105 +107,6 @@ impl UniswapConnector {
         Ok(U256::from_big_endian(result.as_ref()))
     }
 
-    async fn get_token_decimals(&self, token_address: Address) -> Result<u8, DexError> {
-        // ERC20 decimals() function selector
-        let selector = &[0x31, 0x3c, 0xe5, 0x67]; // decimals()
-
-        let call = ethers::types::transaction::eip2718::TypedTransaction::Legacy(
-            ethers::types::TransactionRequest {
-                to: Some(token_address.into()),
-                data: Some(selector.to_vec().into()),
-                ..Default::default()
-            }
-        );
-
-        let result = self.provider
-            .call(&call, None)
-            .await
-            .map_err(|e| DexError::NetworkError(format!("Failed to get token decimals: {}", e)))?;
-
-        if result.len() >= 32 {
-            Ok(result[31])
-        } else {
-            Ok(18) // Default to 18 decimals
-        }
-    }
-
-    async fn get_quote_exact_input_single(
-        &self,
-        token_in: Address,
-        token_out: Address,
-        amount_in: U256,
-        fee: u32,
-    ) -> Result<(U256, U256, U256), DexError> {
-        // QuoterV2.quoteExactInputSingle parameters
-        // struct QuoteExactInputSingleParams {
-        //     address tokenIn;
-        //     address tokenOut;
-        //     uint256 amountIn;
-        //     uint24 fee;
-        //     uint160 sqrtPriceLimitX96;
-        // }
-
-        let quoter_address = Address::from_str(QUOTER_V2)
-            .map_err(|_| DexError::InternalError("Invalid quoter address".to_string()))?;
-
-        // Function selector for quoteExactInputSingle
-        let selector = &[0xc6, 0xe2, 0x01, 0xf8]; // quoteExactInputSingle((address,address,uint256,uint24,uint160))
-
-        let mut calldata = selector.to_vec();
-
-        // Encode struct as tuple
-        // offset to struct data
-        calldata.extend_from_slice(&[0u8; 31]);
-        calldata.push(0x20);
-
-        // tokenIn (address - 32 bytes padded)
-        calldata.extend_from_slice(&[0u8; 12]);
-        calldata.extend_from_slice(token_in.as_bytes());
-
-        // tokenOut (address - 32 bytes padded)
-        calldata.extend_from_slice(&[0u8; 12]);
-        calldata.extend_from_slice(token_out.as_bytes());
-
-        // amountIn (uint256)
-        let mut amount_bytes = [0u8; 32];
-        amount_in.to_big_endian(&mut amount_bytes);
-        calldata.extend_from_slice(&amount_bytes);
-
-        // fee (uint24 - 32 bytes padded)
-        calldata.extend_from_slice(&[0u8; 29]);
-        calldata.extend_from_slice(&fee.to_be_bytes()[1..4]);
-
-        // sqrtPriceLimitX96 (uint160 - set to 0 for no limit)
-        calldata.extend_from_slice(&[0u8; 32]);
-
-        let call = ethers::types::transaction::eip2718::TypedTransaction::Legacy(
-            ethers::types::TransactionRequest {
-                to: Some(quoter_address.into()),
-                data: Some(calldata.into()),
-                ..Default::default()
-            }
-        );
-
-        let result = self.provider
-            .call(&call, None)
-            .await
-            .map_err(|e| DexError::NetworkError(format!("Failed to get quote: {}", e)))?;
-
-        // Decode result: (amountOut, sqrtPriceX96After, initializedTicksCrossed, gasEstimate)
-        // Returns: uint256 amountOut, uint160 sqrtPriceX96After, uint32 initializedTicksCrossed, uint256 gasEstimate
-        if result.len() >= 128 {
-            let amount_out = U256::from_big_endian(&result[0..32]);
-            let sqrt_price = U256::from_big_endian(&result[32..64]);
-            let gas_estimate = U256::from_big_endian(&result[96..128]);
-
-            Ok((amount_out, sqrt_price, gas_estimate))
-        } else {
-            Err(DexError::InternalError("Invalid quote response".to_string()))
-        }
-    }
-
     async fn execute_swap_exact_input_single(
         &self,
         token_in: Address,
@@ -267,7 +160,7 @@ impl UniswapConnector {
 
         // recipient (this wallet)
         calldata.extend_from_slice(&[0u8; 12]);
-        calldata.extend_from_slice(self.wallet.address().as_bytes());
+        calldata.extend_from_slice(self.signer.address().as_bytes());
 
         // amountIn
         let mut amount_in_bytes = [0u8; 32];
@@ -290,17 +183,7 @@ impl UniswapConnector {
             ..Default::default()
         };
 
-        let client = SignerMiddleware::new(self.provider.clone(), self.wallet.clone().with_chain_id(self.chain_id));
-
-        let pending_tx = client
-            .send_transaction(tx, None)
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Failed to send transaction: {}", e)))?;
-
-        let receipt = pending_tx
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Transaction failed: {}", e)))?
-            .ok_or_else(|| DexError::TransactionFailed("No receipt".to_string()))?;
+        let receipt = sign_and_send(&self.provider, self.signer.as_ref(), self.chain_id, tx).await?;
 
         Ok(receipt.transaction_hash)
     }
@@ -326,16 +209,7 @@ impl UniswapConnector {
             ..Default::default()
         };
 
-        let client = SignerMiddleware::new(self.provider.clone(), self.wallet.clone().with_chain_id(self.chain_id));
-
-        let pending_tx = client
-            .send_transaction(tx, None)
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Failed to approve token: {}", e)))?;
-
-        pending_tx
-            .await
-            .map_err(|e| DexError::TransactionFailed(format!("Approval transaction failed: {}", e)))?;
+        sign_and_send(&self.provider, self.signer.as_ref(), self.chain_id, tx).await?;
 
         Ok(())
     }
@@ -356,6 +230,257 @@ impl UniswapConnector {
     }
 }
 
+/// Provider for the node at `ETHEREUM_RPC_URL`, falling back to a public endpoint
+fn ethereum_provider() -> Result<Provider<Http>, DexError> {
+    let rpc_url = std::env::var("ETHEREUM_RPC_URL")
+        .unwrap_or_else(|_| "https://eth-mainnet.g.alchemy.com/v2/demo".to_string());
+
+    Provider::<Http>::try_from(rpc_url.as_str())
+        .map_err(|e| DexError::NetworkError(format!("Failed to create provider: {}", e)))
+}
+
+/// Read-only Uniswap V3 pricing through the Factory and QuoterV2 contracts.
+///
+/// Quoting needs no wallet, so prices can be shown before the user connects one.
+pub struct UniswapQuoter<P = Http> {
+    provider: Arc<Provider<P>>,
+}
+
+impl UniswapQuoter<Http> {
+    pub fn from_env() -> Result<Self, DexError> {
+        Ok(Self::new(Arc::new(ethereum_provider()?)))
+    }
+}
+
+impl<P: JsonRpcClient> UniswapQuoter<P> {
+    pub fn new(provider: Arc<Provider<P>>) -> Self {
+        Self { provider }
+    }
+
+    /// Quote selling `amount` of `from_token` for `to_token` in the fee tier that pays out the most
+    pub async fn quote(
+        &self,
+        from_token: &str,
+        to_token: &str,
+        amount: Decimal,
+    ) -> Result<SwapQuote, DexError> {
+        let token_in = Address::from_str(from_token)
+            .map_err(|_| DexError::InvalidCredentials("Invalid from_token address".to_string()))?;
+
+        let token_out = Address::from_str(to_token)
+            .map_err(|_| DexError::InvalidCredentials("Invalid to_token address".to_string()))?;
+
+        let decimals_in = self.token_decimals(token_in).await?;
+        let amount_in_wei = UniswapConnector::decimal_to_wei(amount, decimals_in);
+
+        // (pool, amount out, gas estimate) of the best tier so far
+        let mut best_quote: Option<(Address, U256, U256)> = None;
+        let mut pool_found = false;
+
+        for fee in [FEE_LOW, FEE_MEDIUM, FEE_HIGH] {
+            let Some(pool) = self.pool_address(token_in, token_out, fee).await? else {
+                continue;
+            };
+            pool_found = true;
+
+            let Some((amount_out, _sqrt_price_after, gas_estimate)) =
+                self.quote_exact_input_single(token_in, token_out, amount_in_wei, fee).await?
+            else {
+                continue;
+            };
+
+            if best_quote.map_or(true, |(_, best_out, _)| amount_out > best_out) {
+                best_quote = Some((pool, amount_out, gas_estimate));
+            }
+        }
+
+        let (pool, amount_out_wei, gas_estimate) = best_quote.ok_or_else(|| {
+            if pool_found {
+                DexError::PoolNotFound(format!(
+                    "No Uniswap V3 pool for {} / {} has liquidity for {}",
+                    from_token, to_token, amount
+                ))
+            } else {
+                DexError::PoolNotFound(format!("No Uniswap V3 pool exists for {} / {}", from_token, to_token))
+            }
+        })?;
+
+        let sqrt_price_x96 = self.pool_sqrt_price(pool).await?;
+        let price_impact = price_impact(token_in < token_out, sqrt_price_x96, amount_in_wei, amount_out_wei);
+
+        let decimals_out = self.token_decimals(token_out).await?;
+        let amount_out = UniswapConnector::wei_to_decimal(amount_out_wei, decimals_out);
+
+        // Calculate minimum received with 0.5% slippage
+        let slippage = Decimal::from_str("0.005").unwrap();
+        let minimum_received = amount_out * (Decimal::ONE - slippage);
+
+        Ok(SwapQuote {
+            from_token: Token {
+                address: from_token.to_string(),
+                symbol: "UNKNOWN".to_string(),
+                name: "Unknown".to_string(),
+                decimals: decimals_in,
+            },
+            to_token: Token {
+                address: to_token.to_string(),
+                symbol: "UNKNOWN".to_string(),
+                name: "Unknown".to_string(),
+                decimals: decimals_out,
+            },
+            from_amount: amount,
+            to_amount: amount_out,
+            price_impact,
+            minimum_received,
+            route: vec![from_token.to_string(), to_token.to_string()],
+            estimated_gas: gas_estimate.to_string(),
+        })
+    }
+
+    async fn token_decimals(&self, token_address: Address) -> Result<u8, DexError> {
+        // ERC20 decimals() function selector
+        let selector = &[0x31, 0x3c, 0xe5, 0x67]; // decimals()
+
+        let result = self.call(token_address, selector.to_vec())
+            .await
+            .map_err(|e| DexError::NetworkError(format!("Failed to get token decimals: {}", e)))?;
+
+        if result.len() >= 32 {
+            Ok(result[31])
+        } else {
+            Ok(18) // Default to 18 decimals
+        }
+    }
+
+    /// Pool for the pair at `fee`, or `None` when the factory never deployed one
+    async fn pool_address(&self, token_a: Address, token_b: Address, fee: u32) -> Result<Option<Address>, DexError> {
+        let factory_address = Address::from_str(FACTORY)
+            .map_err(|_| DexError::InternalError("Invalid factory address".to_string()))?;
+
+        // Function selector for getPool(address,address,uint24)
+        let mut calldata = vec![0x16, 0x98, 0xee, 0x82];
+
+        calldata.extend_from_slice(&[0u8; 12]);
+        calldata.extend_from_slice(token_a.as_bytes());
+
+        calldata.extend_from_slice(&[0u8; 12]);
+        calldata.extend_from_slice(token_b.as_bytes());
+
+        calldata.extend_from_slice(&[0u8; 29]);
+        calldata.extend_from_slice(&fee.to_be_bytes()[1..4]);
+
+        let result = self.call(factory_address, calldata)
+            .await
+            .map_err(|e| DexError::NetworkError(format!("Failed to look up pool: {}", e)))?;
+
+        if result.len() < 32 {
+            return Err(DexError::InternalError("Invalid getPool response".to_string()));
+        }
+
+        // The factory answers with the zero address for pairs it has no pool for
+        let pool = Address::from_slice(&result[12..32]);
+        Ok((!pool.is_zero()).then_some(pool))
+    }
+
+    /// Current sqrtPriceX96 of `pool`, read from slot0()
+    async fn pool_sqrt_price(&self, pool: Address) -> Result<U256, DexError> {
+        let selector = &[0x38, 0x50, 0xc7, 0xbd]; // slot0()
+
+        let result = self.call(pool, selector.to_vec())
+            .await
+            .map_err(|e| DexError::NetworkError(format!("Failed to read pool price: {}", e)))?;
+
+        if result.len() < 32 {
+            return Err(DexError::InternalError("Invalid slot0 response".to_string()));
+        }
+
+        Ok(U256::from_big_endian(&result[0..32]))
+    }
+
+    /// QuoterV2 quote for one fee tier, or `None` when the pool reverts because it can't fill the amount
+    async fn quote_exact_input_single(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        fee: u32,
+    ) -> Result<Option<(U256, U256, U256)>, DexError> {
+        let quoter_address = Address::from_str(QUOTER_V2)
+            .map_err(|_| DexError::InternalError("Invalid quoter address".to_string()))?;
+
+        let calldata = quote_exact_input_single_calldata(token_in, token_out, amount_in, fee);
+
+        let result = match self.call(quoter_address, calldata).await {
+            Ok(result) => result,
+            // The node ran the call and it reverted
+            Err(ProviderError::JsonRpcClientError(e)) if e.as_error_response().is_some() => return Ok(None),
+            Err(e) => return Err(DexError::NetworkError(format!("Failed to get quote: {}", e))),
+        };
+
+        // Decode result: (amountOut, sqrtPriceX96After, initializedTicksCrossed, gasEstimate)
+        // Returns: uint256 amountOut, uint160 sqrtPriceX96After, uint32 initializedTicksCrossed, uint256 gasEstimate
+        if result.len() >= 128 {
+            let amount_out = U256::from_big_endian(&result[0..32]);
+            let sqrt_price = U256::from_big_endian(&result[32..64]);
+            let gas_estimate = U256::from_big_endian(&result[96..128]);
+
+            Ok(Some((amount_out, sqrt_price, gas_estimate)))
+        } else {
+            Err(DexError::InternalError("Invalid quote response".to_string()))
+        }
+    }
+
+    async fn call(&self, to: Address, calldata: Vec<u8>) -> Result<Bytes, ProviderError> {
+        let call = ethers::types::transaction::eip2718::TypedTransaction::Legacy(
+            ethers::types::TransactionRequest {
+                to: Some(to.into()),
+                data: Some(calldata.into()),
+                ..Default::default()
+            }
+        );
+
+        self.provider.call(&call, None).await
+    }
+}
+
+/// Calldata for QuoterV2.quoteExactInputSingle. Its params struct
+/// `(tokenIn, tokenOut, amountIn, fee, sqrtPriceLimitX96)` is all static, so it is
+/// encoded inline with no offset word; a zero price limit means no limit.
+fn quote_exact_input_single_calldata(token_in: Address, token_out: Address, amount_in: U256, fee: u32) -> Vec<u8> {
+    let params = abi::Token::Tuple(vec![
+        abi::Token::Address(token_in),
+        abi::Token::Address(token_out),
+        abi::Token::Uint(amount_in),
+        abi::Token::Uint(U256::from(fee)),
+        abi::Token::Uint(U256::zero()),
+    ]);
+
+    let mut calldata = ethers::utils::id(QUOTE_EXACT_INPUT_SINGLE).to_vec();
+    calldata.extend(abi::encode(&[params]));
+    calldata
+}
+
+/// Fraction by which the fill price falls short of the pool's mid price before the swap, fee included.
+/// `zero_for_one` is set when the input is the pool's token0, whose price in token1 is sqrtPriceX96² / 2¹⁹².
+fn price_impact(zero_for_one: bool, sqrt_price_x96: U256, amount_in: U256, amount_out: U256) -> Decimal {
+    let sqrt_price = u256_to_f64(sqrt_price_x96) / 2f64.powi(96);
+    let price = sqrt_price * sqrt_price;
+    let mid_price = if zero_for_one { price } else { 1.0 / price };
+    let fill_price = u256_to_f64(amount_out) / u256_to_f64(amount_in);
+
+    if !(mid_price.is_finite() && mid_price > 0.0 && fill_price.is_finite()) {
+        return Decimal::ZERO;
+    }
+
+    Decimal::from_f64((1.0 - fill_price / mid_price).max(0.0))
+        .unwrap_or(Decimal::ZERO)
+        .round_dp(6)
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(0.0)
+}
+
 #[async_trait]
 impl DexConnector for UniswapConnector {
     async fn test_connection(&self) -> Result<bool, DexError> {
@@ -405,7 +530,7 @@ impl DexConnector for UniswapConnector {
             .map_err(|_| DexError::InvalidCredentials("Invalid wallet address".to_string()))?;
 
         let balance_wei = self.get_erc20_balance(token, wallet).await?;
-        let decimals = self.get_token_decimals(token).await?;
+        let decimals = self.quoter.token_decimals(token).await?;
         let balance = Self::wei_to_decimal(balance_wei, decimals);
 
         Ok(TokenBalance {
@@ -426,63 +551,7 @@ impl DexConnector for UniswapConnector {
     ) -> Result<SwapQuote, DexError> {
         tracing::info!("Getting Uniswap swap quote: {} {} -> {}", amount, from_token, to_token);
 
-        let token_in = Address::from_str(from_token)
-            .map_err(|_| DexError::InvalidCredentials("Invalid from_token address".to_string()))?;
-
-        let token_out = Address::from_str(to_token)
-            .map_err(|_| DexError::InvalidCredentials("Invalid to_token address".to_string()))?;
-
-        // Get token decimals
-        let decimals_in = self.get_token_decimals(token_in).await?;
-        let amount_in_wei = Self::decimal_to_wei(amount, decimals_in);
-
-        // Try different fee tiers to find the best quote
-        let mut best_quote: Option<(U256, U256, u32)> = None;
-
-        for fee in &[FEE_LOW, FEE_MEDIUM, FEE_HIGH] {
-            match self.get_quote_exact_input_single(token_in, token_out, amount_in_wei, *fee).await {
-                Ok((amount_out, _sqrt_price, gas_estimate)) => {
-                    if best_quote.is_none() || amount_out > best_quote.unwrap().0 {
-                        best_quote = Some((amount_out, gas_estimate, *fee));
-                    }
-                }
-                Err(_) => continue, // Pool doesn't exist for this fee tier
-            }
-        }
-
-        let (amount_out_wei, gas_estimate, best_fee) = best_quote
-            .ok_or_else(|| DexError::PoolNotFound("No liquidity pool found for this pair".to_string()))?;
-
-        let decimals_out = self.get_token_decimals(token_out).await?;
-        let amount_out = Self::wei_to_decimal(amount_out_wei, decimals_out);
-
-        // Calculate price impact (simplified)
-        let price_impact = Decimal::from_str("0.01").unwrap(); // 1% placeholder
-
-        // Calculate minimum received with 0.5% slippage
-        let slippage = Decimal::from_str("0.005").unwrap();
-        let minimum_received = amount_out * (Decimal::ONE - slippage);
-
-        Ok(SwapQuote {
-            from_token: Token {
-                address: from_token.to_string(),
-                symbol: "UNKNOWN".to_string(),
-                name: "Unknown".to_string(),
-                decimals: decimals_in,
-            },
-            to_token: Token {
-                address: to_token.to_string(),
-                symbol: "UNKNOWN".to_string(),
-                name: "Unknown".to_string(),
-                decimals: decimals_out,
-            },
-            from_amount: amount,
-            to_amount: amount_out,
-            price_impact,
-            minimum_received,
-            route: vec![from_token.to_string(), to_token.to_string()],
-            estimated_gas: gas_estimate.to_string(),
-        })
+        self.quoter.quote(from_token, to_token, amount).await
     }
 
     async fn execute_swap(
@@ -506,12 +575,12 @@ impl DexConnector for UniswapConnector {
         // Get quote first to determine best fee tier
         let quote = self.get_swap_quote(from_token, to_token, amount).await?;
 
-        let decimals_in = self.get_token_decimals(token_in).await?;
+        let decimals_in = self.quoter.token_decimals(token_in).await?;
         let amount_in_wei = Self::decimal_to_wei(amount, decimals_in);
 
         // Calculate minimum output with slippage
         let minimum_output = quote.to_amount * (Decimal::ONE - slippage_tolerance / Decimal::from(100));
-        let decimals_out = self.get_token_decimals(token_out).await?;
+        let decimals_out = self.quoter.token_decimals(token_out).await?;
         let amount_out_minimum_wei = Self::decimal_to_wei(minimum_output, decimals_out);
 
         // Execute swap with medium fee tier (most common)
@@ -614,3 +683,130 @@ impl DexConnector for UniswapConnector {
         "ethereum"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{JsonRpcError, MockProvider, MockResponse};
+
+    const TOKEN_A: &str = "0x0000000000000000000000000000000000000001";
+    const TOKEN_B: &str = "0x0000000000000000000000000000000000000002";
+    const POOL: &str = "0x00000000000000000000000000000000000000aa";
+
+    fn word(value: U256) -> Vec<u8> {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        bytes.to_vec()
+    }
+
+    fn address_word(address: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; 12];
+        bytes.extend_from_slice(Address::from_str(address).unwrap().as_bytes());
+        bytes
+    }
+
+    fn quoter_output(amount_out: U256, gas_estimate: u64) -> Vec<u8> {
+        [word(amount_out), word(U256::one() << 96usize), word(U256::one()), word(gas_estimate.into())].concat()
+    }
+
+    #[test]
+    fn test_quote_calldata_matches_the_quoter_v2_abi() {
+        let amount_in = U256::from(1_000_000_000_000_000_000u64);
+        let calldata = quote_exact_input_single_calldata(
+            Address::from_str(TOKEN_A).unwrap(),
+            Address::from_str(TOKEN_B).unwrap(),
+            amount_in,
+            FEE_MEDIUM,
+        );
+
+        let expected = [
+            "c6a5026a",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+            "0000000000000000000000000000000000000000000000000000000000000bb8",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ]
+        .concat();
+        assert_eq!(ethers::utils::hex::encode(&calldata), expected);
+    }
+
+    /// Quoter whose node answers the calls with `responses`, in order
+    fn mocked_quoter(responses: Vec<MockResponse>) -> UniswapQuoter<MockProvider> {
+        let (provider, mock) = Provider::mocked();
+        // The mock answers with the most recently pushed response first
+        for response in responses.into_iter().rev() {
+            mock.push_response(response);
+        }
+        UniswapQuoter::new(Arc::new(provider))
+    }
+
+    fn ok(data: Vec<u8>) -> MockResponse {
+        MockResponse::Value(serde_json::to_value(Bytes::from(data)).unwrap())
+    }
+
+    fn reverted() -> MockResponse {
+        MockResponse::Error(JsonRpcError {
+            code: 3,
+            message: "execution reverted".to_string(),
+            data: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_quote_uses_the_best_pool_and_measures_price_impact() {
+        let eighteen = word(U256::from(18u64));
+        let one_token = U256::exp10(18);
+        let quoter = mocked_quoter(vec![
+            ok(eighteen.clone()), // decimals(tokenIn)
+            ok(word(U256::zero())), // getPool 0.05%: none
+            ok(address_word(POOL)), // getPool 0.3%
+            ok(quoter_output(one_token * U256::from(99u64) / U256::from(100u64), 120_000)), // quote 0.3%
+            ok(address_word("0x00000000000000000000000000000000000000bb")), // getPool 1%
+            reverted(), // quote 1%: too thin
+            ok(word(U256::one() << 96usize)), // slot0 of the 0.3% pool: price 1
+            ok(eighteen), // decimals(tokenOut)
+        ]);
+
+        let quote = quoter.quote(TOKEN_A, TOKEN_B, Decimal::ONE).await.unwrap();
+
+        assert_eq!(quote.to_amount, Decimal::new(99, 2));
+        assert_eq!(quote.price_impact, Decimal::new(1, 2));
+        assert_eq!(quote.estimated_gas, "120000");
+        assert_eq!(quote.route, vec![TOKEN_A.to_string(), TOKEN_B.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_quote_without_a_pool_is_a_clear_error() {
+        let quoter = mocked_quoter(vec![
+            ok(word(U256::from(18u64))),
+            ok(word(U256::zero())),
+            ok(word(U256::zero())),
+            ok(word(U256::zero())),
+        ]);
+
+        let err = quoter.quote(TOKEN_A, TOKEN_B, Decimal::ONE).await.unwrap_err();
+
+        assert!(matches!(&err, DexError::PoolNotFound(msg) if msg.contains("No Uniswap V3 pool exists")));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_failure_is_not_reported_as_a_missing_pool() {
+        // The node goes away after answering decimals()
+        let quoter = mocked_quoter(vec![ok(word(U256::from(18u64)))]);
+
+        let err = quoter.quote(TOKEN_A, TOKEN_B, Decimal::ONE).await.unwrap_err();
+
+        assert!(matches!(err, DexError::NetworkError(_)));
+    }
+
+    #[test]
+    fn test_price_impact_follows_the_swap_direction() {
+        // Pool price 4 token1 per token0
+        let sqrt_price = U256::from(2u64) << 96usize;
+        let thousand = U256::from(1000);
+
+        assert_eq!(price_impact(true, sqrt_price, thousand, U256::from(3960)), Decimal::new(1, 2));
+        assert_eq!(price_impact(false, sqrt_price, thousand, U256::from(245)), Decimal::new(2, 2));
+    }
+}
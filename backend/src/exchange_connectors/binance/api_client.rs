@@ -2,18 +2,109 @@ use reqwest::Client;
 use serde_json::Value;
 use chrono::{ Utc};
 use std::collections::HashMap;
-use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use once_cell::sync::Lazy;
+use reqwest::{Method, RequestBuilder, Response};
 use rust_decimal::Decimal;
+use tracing::warn;
 use crate::exchange_connectors::{ExchangeCredentials, ExchangeError};
+use crate::exchange_connectors::common_types::PriceSource;
+use crate::exchange_connectors::numeric::parse_decimal;
+use crate::utils::http::{request_with_rate_limit, HttpError, RetryPolicy, TokenBucket};
+use crate::utils::metrics::metrics;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Quote assets tried, in order, when pricing an asset in USD
+const USD_QUOTES: [&str; 3] = ["USDT", "USDC", "BUSD"];
+
+/// Stablecoins valued against USD
+const STABLECOINS: [&str; 5] = ["USDT", "USDC", "BUSD", "DAI", "FDUSD"];
+
+pub fn is_stablecoin(asset: &str) -> bool {
+    STABLECOINS.contains(&asset.to_uppercase().as_str())
+}
+
+/// How stablecoin balances are valued
+#[derive(Debug, Clone)]
+pub struct StablecoinPegConfig {
+    /// Use the stablecoin's USDT market price when one exists instead of assuming $1
+    pub use_market_price: bool,
+    /// Warn when a stablecoin trades more than this many percent off its peg
+    pub alert_deviation_percentage: Decimal,
+}
+
+impl StablecoinPegConfig {
+    /// Read `STABLECOIN_PEG_CHECK` and `STABLECOIN_PEG_ALERT_PERCENTAGE` from the environment
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        let use_market_price = std::env::var("STABLECOIN_PEG_CHECK")
+            .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(default.use_market_price);
+
+        let alert_deviation_percentage = std::env::var("STABLECOIN_PEG_ALERT_PERCENTAGE")
+            .ok()
+            .and_then(|v| parse_decimal(&v).ok())
+            .unwrap_or(default.alert_deviation_percentage);
+
+        Self {
+            use_market_price,
+            alert_deviation_percentage,
+        }
+    }
+}
+
+impl Default for StablecoinPegConfig {
+    fn default() -> Self {
+        Self {
+            use_market_price: false,
+            alert_deviation_percentage: Decimal::from(2),
+        }
+    }
+}
+
+/// Requests a minute sent to Binance when `BINANCE_MAX_REQUESTS_PER_MINUTE` is unset
+const DEFAULT_MAX_REQUESTS_PER_MINUTE: u32 = 1200;
+
+/// Binance limits request weight per IP, so every client in the process shares one bucket
+static RATE_LIMITER: Lazy<Arc<TokenBucket>> = Lazy::new(|| {
+    let requests_per_minute = std::env::var("BINANCE_MAX_REQUESTS_PER_MINUTE")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_MAX_REQUESTS_PER_MINUTE);
+    Arc::new(TokenBucket::per_minute(requests_per_minute))
+});
+
+/// The process-wide limiter for requests to Binance
+pub fn binance_rate_limiter() -> Arc<TokenBucket> {
+    RATE_LIMITER.clone()
+}
+
+/// Retries 429s and server errors, finishing well inside the 5 second `recvWindow`
+/// a signed request's timestamp is valid for. A 429 or 418 waits out its `Retry-After`;
+/// a 418 ban usually lasts longer than `max_delay`, which ends the retries.
+pub fn binance_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(250),
+        max_delay: Duration::from_secs(2),
+        retryable_statuses: vec![418, 429, 500, 502, 503, 504],
+        deadline: Some(Duration::from_secs(4)),
+        ..RetryPolicy::default()
+    }
+}
+
 pub struct BinanceApiClient {
     pub client: Client,
     pub spot_base_url: String,
     pub futures_base_url: String,
+    pub stablecoin_peg: StablecoinPegConfig,
+    pub retry_policy: RetryPolicy,
+    pub rate_limiter: Arc<TokenBucket>,
     credentials: ExchangeCredentials,
 }
 
@@ -25,14 +116,22 @@ impl BinanceApiClient {
             client,
             spot_base_url: "https://api.binance.com".to_string(),
             futures_base_url: "https://fapi.binance.com".to_string(),
+            stablecoin_peg: StablecoinPegConfig::from_env(),
+            retry_policy: binance_retry_policy(),
+            rate_limiter: binance_rate_limiter(),
             credentials,
         })
     }
 
+    /// Send `request` under the client's retry policy and rate limit
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, HttpError> {
+        request_with_rate_limit(&self.retry_policy, &self.rate_limiter, request).await
+    }
+
 
     pub async fn test_connectivity(&self) -> Result<bool, ExchangeError> {
         let url = format!("{}/api/v3/ping", self.spot_base_url);
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(self.client.get(&url)).await?;
         Ok(response.status().is_success())
     }
 
@@ -45,30 +144,14 @@ impl BinanceApiClient {
 
     pub async fn get_symbol_price(&self, symbol: &str) -> Result<Decimal, ExchangeError> {
         // Handle stablecoins
-        if matches!(symbol.to_uppercase().as_str(), "USDT" | "USDC" | "BUSD" | "DAI" | "FDUSD") {
-            return Ok(Decimal::from(1));
+        if is_stablecoin(symbol) {
+            return Ok(self.get_stablecoin_price(symbol).await.0);
         }
 
         // Try different trading pairs
-        let pairs = vec![
-            format!("{}USDT", symbol.to_uppercase()),
-            format!("{}USDC", symbol.to_uppercase()),
-            format!("{}BUSD", symbol.to_uppercase()),
-        ];
-
-        for pair in pairs {
-            let url = format!("{}/api/v3/ticker/price?symbol={}", self.spot_base_url, pair);
-
-            if let Ok(response) = self.client.get(&url).send().await {
-                if response.status().is_success() {
-                    if let Ok(json) = response.json::<Value>().await {
-                        if let Some(price_str) = json.get("price").and_then(|v| v.as_str()) {
-                            if let Ok(price) = Decimal::from_str(price_str) {
-                                return Ok(price);
-                            }
-                        }
-                    }
-                }
+        for quote in USD_QUOTES {
+            if let Some(price) = self.fetch_ticker_price(&format!("{}{}", symbol.to_uppercase(), quote)).await {
+                return Ok(price);
             }
         }
 
@@ -76,7 +159,128 @@ impl BinanceApiClient {
         Ok(Decimal::ZERO)
     }
 
+    /// USD price of a stablecoin. With the peg check enabled the `{asset}USDT` market price
+    /// is used when there is one; otherwise (and for USDT itself) it is valued 1:1.
+    pub async fn get_stablecoin_price(&self, asset: &str) -> (Decimal, PriceSource) {
+        let asset = asset.to_uppercase();
+        let market_price = if self.checks_peg(&asset) {
+            self.fetch_ticker_price(&format!("{}USDT", asset)).await
+        } else {
+            None
+        };
+        self.stablecoin_valuation(&asset, market_price)
+    }
+
+    /// Last price of every spot pair, keyed by symbol, from a single bulk ticker request
+    pub async fn get_all_prices(&self) -> Result<HashMap<String, Decimal>, ExchangeError> {
+        let url = format!("{}/api/v3/ticker/price", self.spot_base_url);
+        let response = match self.send(self.client.get(&url)).await {
+            Ok(response) => response,
+            Err(e) => {
+                metrics().record_exchange_error("binance", "network");
+                return Err(e.into());
+            }
+        };
+
+        if !response.status().is_success() {
+            let status = response.status();
+            metrics().record_exchange_error("binance", status.as_str());
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(self.parse_binance_error(status.as_u16(), &error_text));
+        }
+
+        let json: Value = response.json().await?;
+        let prices = json
+            .as_array()
+            .map(|tickers| {
+                tickers
+                    .iter()
+                    .filter_map(|ticker| {
+                        let symbol = ticker.get("symbol")?.as_str()?;
+                        let price = parse_decimal(ticker.get("price")?.as_str()?).ok()?;
+                        (price > Decimal::ZERO).then(|| (symbol.to_string(), price))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(prices)
+    }
+
+    /// USD price of `asset` looked up in a `get_all_prices` map, or None when no USD pair
+    /// is listed. Stablecoins are valued as `get_stablecoin_price` does.
+    pub fn price_from(&self, prices: &HashMap<String, Decimal>, asset: &str) -> Option<(Decimal, PriceSource)> {
+        let asset = asset.to_uppercase();
+        if is_stablecoin(&asset) {
+            let market_price = if self.checks_peg(&asset) {
+                prices.get(&format!("{}USDT", asset)).copied()
+            } else {
+                None
+            };
+            return Some(self.stablecoin_valuation(&asset, market_price));
+        }
+
+        USD_QUOTES
+            .iter()
+            .find_map(|quote| prices.get(&format!("{}{}", asset, quote)).copied())
+            .map(|price| (price, PriceSource::ExchangeTicker))
+    }
+
+    /// Whether a stablecoin is valued at its market price rather than 1:1
+    fn checks_peg(&self, asset: &str) -> bool {
+        self.stablecoin_peg.use_market_price && asset != "USDT"
+    }
+
+    /// Value a stablecoin at `market_price`, warning when it is off its peg, or at 1:1
+    /// without one
+    fn stablecoin_valuation(&self, asset: &str, market_price: Option<Decimal>) -> (Decimal, PriceSource) {
+        match market_price {
+            Some(price) => {
+                let deviation = ((price - Decimal::ONE) / Decimal::ONE).abs() * Decimal::from(100);
+                if deviation > self.stablecoin_peg.alert_deviation_percentage {
+                    warn!(
+                        "Stablecoin {} is trading at ${}, {:.2}% off its peg",
+                        asset, price, deviation
+                    );
+                }
+                (price, PriceSource::ExchangeTicker)
+            }
+            None => (Decimal::ONE, PriceSource::StablecoinPeg),
+        }
+    }
+
+    /// Last price for `pair` from the public ticker, if the pair exists
+    async fn fetch_ticker_price(&self, pair: &str) -> Option<Decimal> {
+        let url = format!("{}/api/v3/ticker/price?symbol={}", self.spot_base_url, pair);
+
+        let response = self.send(self.client.get(&url)).await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+
+        let json = response.json::<Value>().await.ok()?;
+        let price = json.get("price").and_then(|v| v.as_str())
+            .and_then(|price_str| parse_decimal(price_str).ok())?;
+
+        if price > Decimal::ZERO {
+            Some(price)
+        } else {
+            None
+        }
+    }
+
     pub async fn signed_request(&self, endpoint: &str, params: &HashMap<String, String>) -> Result<Value, ExchangeError> {
+        self.signed_request_with(Method::GET, endpoint, params).await
+    }
+
+    /// Call a signed endpoint with `method`: orders are placed with POST and cancelled
+    /// with DELETE
+    pub async fn signed_request_with(
+        &self,
+        method: Method,
+        endpoint: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<Value, ExchangeError> {
         let mut query_params = params.clone();
         query_params.insert("timestamp".to_string(), Utc::now().timestamp_millis().to_string());
 
@@ -109,14 +313,27 @@ impl BinanceApiClient {
             format!("{}/api/v3/{}", base_url, endpoint)
         };
 
-        let response = self.client
-            .get(&format!("{}?{}", url, final_query))
-            .header("X-MBX-APIKEY", &self.credentials.api_key)
-            .send()
-            .await?;
+        // The order endpoint may place an order; a replay could place it twice
+        let retry_policy = if endpoint == "order" {
+            self.retry_policy.without_retries()
+        } else {
+            self.retry_policy.clone()
+        };
+        let request = self.client
+            .request(method, format!("{}?{}", url, final_query))
+            .header("X-MBX-APIKEY", &self.credentials.api_key);
+
+        let response = match request_with_rate_limit(&retry_policy, &self.rate_limiter, request).await {
+            Ok(response) => response,
+            Err(e) => {
+                metrics().record_exchange_error("binance", "network");
+                return Err(e.into());
+            }
+        };
 
         if !response.status().is_success() {
             let status = response.status();
+            metrics().record_exchange_error("binance", status.as_str());
             let error_text = response.text().await.unwrap_or_default();
             return Err(self.parse_binance_error(status.as_u16(), &error_text));
         }
@@ -155,7 +372,8 @@ impl BinanceApiClient {
                     
                     // Rate limiting or balance (error code -1003 can mean both)
                     -1003 => {
-                        if msg.to_lowercase().contains("rate") || msg.to_lowercase().contains("limit") {
+                        let throttled = matches!(status_code, 418 | 429);
+                        if throttled || msg.to_lowercase().contains("rate") || msg.to_lowercase().contains("limit") {
                             ExchangeError::RateLimitExceeded(format!("Too many requests: {}", msg))
                         } else {
                             ExchangeError::InsufficientBalance(format!("Balance insufficient: {}", msg))
@@ -172,10 +390,74 @@ impl BinanceApiClient {
         match status_code {
             401 | 403 => ExchangeError::AuthenticationError(format!("Authentication failed: {}", error_text)),
             429 => ExchangeError::RateLimitExceeded(format!("Rate limit exceeded: {}", error_text)),
+            418 => ExchangeError::RateLimitExceeded(format!("IP banned for exceeding rate limits: {}", error_text)),
             503 => ExchangeError::Maintenance,
             404 => ExchangeError::Unknown(format!("Endpoint not found: {}", error_text)),
             400 => ExchangeError::InvalidParameter(format!("Bad request: {}", error_text)),
             _ => ExchangeError::Unknown(format!("HTTP {}: {}", status_code, error_text))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::utils::mock_http::{self, MockResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::Instant;
+
+    /// Answers the first `throttled` requests with `status` and a one second `Retry-After`,
+    /// then every request with a BTCUSDT price
+    async fn spawn_throttling_server(status: &'static str, throttled: usize) -> (String, Arc<AtomicUsize>) {
+        mock_http::spawn(move |request| {
+            Some(if request.number < throttled {
+                MockResponse::json(
+                    status,
+                    r#"{"code":-1003,"msg":"Way too much request weight used; IP banned until 1700000000000."}"#,
+                )
+                .with_header("Retry-After", 1)
+            } else {
+                MockResponse::json("200 OK", r#"{"symbol":"BTCUSDT","price":"50000.00"}"#)
+            })
+        })
+        .await
+    }
+
+    fn test_client(base_url: String) -> BinanceApiClient {
+        let mut client = BinanceApiClient::new(ExchangeCredentials {
+            api_key: "key".to_string(),
+            api_secret: "secret".to_string(),
+            passphrase: None,
+        })
+        .unwrap();
+        client.spot_base_url = base_url;
+        client.rate_limiter = Arc::new(TokenBucket::per_minute(DEFAULT_MAX_REQUESTS_PER_MINUTE));
+        client
+    }
+
+    #[tokio::test]
+    async fn test_price_lookup_waits_out_429_and_succeeds() {
+        let (url, requests) = spawn_throttling_server("429 Too Many Requests", 1).await;
+        let client = test_client(url);
+
+        let started = Instant::now();
+        let price = client.get_symbol_price("BTC").await.unwrap();
+
+        assert_eq!(price, Decimal::from_str("50000.00").unwrap());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() >= Duration::from_secs(1), "Retry-After was not honored");
+    }
+
+    #[tokio::test]
+    async fn test_ban_longer_than_the_policy_allows_is_not_retried() {
+        let (url, requests) = spawn_throttling_server("418 I'm a teapot", usize::MAX).await;
+        let mut client = test_client(url);
+        client.retry_policy.max_delay = Duration::from_millis(500);
+
+        let result = client.signed_request("account", &HashMap::new()).await;
+
+        assert!(matches!(result, Err(ExchangeError::RateLimitExceeded(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
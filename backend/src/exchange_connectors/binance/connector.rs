@@ -9,12 +9,15 @@ use crate::exchange_connectors::{
     traits::{ExchangeConnector, AccountAPI, OrderAPI, TradeExecutionAPI, MarketDataAPI},
     ExchangeCredentials,
     ExchangeError,
-    common_types::{SpotAccount, MarginAccount, FuturesAccount, AccountBalances, AssetBalance, WalletType, FuturesType, OrderSide, TimeInForce, Order, OcoOrder},
+    common_types::{ApiKeyPermissions, SpotAccount, MarginAccount, FuturesAccount, AccountBalances, AssetBalance, WalletType, FuturesType, OrderSide, TimeInForce, Order, OcoOrder, PriceSource},
     shared_types::{Ticker, OrderBook, Trade, Kline, KlineInterval, ExchangeInfo, SymbolInfo},
+    numeric::round_down_to_step,
 };
+use crate::utils::http::HttpError;
+use reqwest::Method;
 use super::types::*;
 
-use super::api_client::BinanceApiClient;
+use super::api_client::{is_stablecoin, BinanceApiClient};
 use super::converters::*;
 
 pub struct BinanceConnector {
@@ -26,6 +29,60 @@ impl BinanceConnector {
         let client = BinanceApiClient::new(credentials)?;
         Ok(Self { client })
     }
+
+    /// Round an order quantity down to the symbol's `LOT_SIZE` step, refusing one that
+    /// ends up below the symbol's minimum
+    fn round_quantity_for(symbol: &SymbolInfo, quantity: Decimal) -> Result<Decimal, ExchangeError> {
+        let rounded = round_down_to_step(quantity, symbol.step_size);
+        if rounded <= Decimal::ZERO || rounded < symbol.min_quantity {
+            return Err(ExchangeError::InvalidOrder(format!(
+                "Quantity {} is below the minimum {} for {} after rounding to step size {}",
+                quantity, symbol.min_quantity, symbol.symbol, symbol.step_size
+            )));
+        }
+        Ok(rounded)
+    }
+
+    /// Round a limit price down to the symbol's `PRICE_FILTER` tick
+    fn round_price_for(symbol: &SymbolInfo, price: Decimal) -> Result<Decimal, ExchangeError> {
+        let rounded = round_down_to_step(price, symbol.tick_size);
+        if rounded <= Decimal::ZERO || rounded < symbol.min_price {
+            return Err(ExchangeError::InvalidOrder(format!(
+                "Price {} is below the minimum {} for {} after rounding to tick size {}",
+                price, symbol.min_price, symbol.symbol, symbol.tick_size
+            )));
+        }
+        Ok(rounded)
+    }
+
+    /// Refuse an order worth less than the symbol's `NOTIONAL`/`MIN_NOTIONAL` minimum
+    fn check_min_notional_for(symbol: &SymbolInfo, notional: Decimal) -> Result<(), ExchangeError> {
+        if notional < symbol.min_notional {
+            return Err(ExchangeError::InvalidOrder(format!(
+                "Order value {} is below the minimum notional {} for {}",
+                notional.round_dp(8).normalize(), symbol.min_notional, symbol.symbol
+            )));
+        }
+        Ok(())
+    }
+
+    /// `quantity` rounded down to `symbol`'s `LOT_SIZE` step, refused below its minimum
+    #[allow(dead_code)]
+    pub async fn round_quantity(&self, symbol: &str, quantity: Decimal) -> Result<Decimal, ExchangeError> {
+        Self::round_quantity_for(&self.get_symbol_info(symbol).await?, quantity)
+    }
+
+    /// `price` rounded down to `symbol`'s `PRICE_FILTER` tick, refused below its minimum
+    #[allow(dead_code)]
+    pub async fn round_price(&self, symbol: &str, price: Decimal) -> Result<Decimal, ExchangeError> {
+        Self::round_price_for(&self.get_symbol_info(symbol).await?, price)
+    }
+
+    /// Refuse an order on `symbol` worth less than its minimum notional
+    #[allow(dead_code)]
+    pub async fn check_min_notional(&self, symbol: &str, notional: Decimal) -> Result<(), ExchangeError> {
+        Self::check_min_notional_for(&self.get_symbol_info(symbol).await?, notional)
+    }
 }
 
 #[async_trait]
@@ -33,7 +90,7 @@ impl ExchangeConnector for BinanceConnector {
     async fn test_connection(&self) -> Result<bool, ExchangeError> {
         // First check exchange status (maintenance mode, etc.)
         let url = format!("{}/api/v3/exchangeInfo", self.client.spot_base_url);
-        match self.client.client.get(&url).send().await {
+        match self.client.send(self.client.client.get(&url)).await {
             Ok(response) => {
                 if !response.status().is_success() {
                     let status_code = response.status().as_u16();
@@ -43,13 +100,10 @@ impl ExchangeConnector for BinanceConnector {
                     }
                 }
             }
-            Err(e) => {
-                if e.is_timeout() {
-                    return Err(ExchangeError::Timeout);
-                } else {
-                    return Err(ExchangeError::NetworkError(format!("Exchange unreachable: {}", e)));
-                }
+            Err(HttpError::Transport(e)) if !e.is_timeout() => {
+                return Err(ExchangeError::NetworkError(format!("Exchange unreachable: {}", e)));
             }
+            Err(_) => return Err(ExchangeError::Timeout),
         }
 
         // Test basic connectivity
@@ -81,6 +135,11 @@ impl ExchangeConnector for BinanceConnector {
         }
     }
 
+    async fn get_api_permissions(&self) -> Result<ApiKeyPermissions, ExchangeError> {
+        let params = HashMap::new();
+        let response = self.client.signed_request("sapi/v1/account/apiRestrictions", &params).await?;
+        parse_api_restrictions(response)
+    }
 }
 
 #[async_trait]
@@ -257,12 +316,16 @@ impl BinanceConnector {
                         if total_amount > Decimal::ZERO {
                             // Get USD value
                             let mut usd_value = None;
-                            if asset == "USDT" || asset == "USDC" || asset == "BUSD" || asset == "DAI" {
-                                usd_value = Some(total_amount);
+                            let mut price_source = None;
+                            if is_stablecoin(asset) {
+                                let (price, source) = self.client.get_stablecoin_price(asset).await;
+                                usd_value = Some(total_amount * price);
+                                price_source = Some(source);
                             } else {
                                 match self.client.get_symbol_price(asset).await {
                                     Ok(price) => {
                                         usd_value = Some(total_amount * price);
+                                        price_source = Some(PriceSource::ExchangeTicker);
                                         debug!("Savings - Got Binance price for {}: ${}, value: ${}", asset, price, total_amount * price);
                                     }
                                     Err(e) => {
@@ -279,6 +342,8 @@ impl BinanceConnector {
                                 usd_value,
                                 btc_value: None,
                                 wallet_type: WalletType::Spot, // Map Earn to Spot for compatibility
+                                priced: usd_value.is_some(),
+                                price_source,
                             });
                         }
                     }
@@ -303,12 +368,16 @@ impl BinanceConnector {
                         if amount > Decimal::ZERO {
                             // Get USD value
                             let mut usd_value = None;
-                            if asset == "USDT" || asset == "USDC" || asset == "BUSD" || asset == "DAI" {
-                                usd_value = Some(amount);
+                            let mut price_source = None;
+                            if is_stablecoin(asset) {
+                                let (price, source) = self.client.get_stablecoin_price(asset).await;
+                                usd_value = Some(amount * price);
+                                price_source = Some(source);
                             } else {
                                 match self.client.get_symbol_price(asset).await {
                                     Ok(price) => {
                                         usd_value = Some(amount * price);
+                                        price_source = Some(PriceSource::ExchangeTicker);
                                         debug!("Locked Savings - Got Binance price for {}: ${}, value: ${}", asset, price, amount * price);
                                     }
                                     Err(e) => {
@@ -325,6 +394,8 @@ impl BinanceConnector {
                                 usd_value,
                                 btc_value: None,
                                 wallet_type: WalletType::Spot, // Map Earn to Spot for compatibility
+                                priced: usd_value.is_some(),
+                                price_source,
                             });
                         }
                     }
@@ -423,7 +494,7 @@ impl OrderAPI for BinanceConnector {
                 params.insert("symbol".to_string(), symbol.to_string());
                 params.insert("orderId".to_string(), order_id.to_string());
 
-                match self.client.signed_request("order", &params).await {
+                match self.client.signed_request_with(Method::DELETE, "order", &params).await {
                     Ok(response) => parse_single_order_from_json(response, wallet_type),
                     Err(ExchangeError::ApiError(msg)) if msg.contains("-2011") => {
                         Err(ExchangeError::OrderNotFound(format!("Order {} not found for symbol {}", order_id, symbol)))
@@ -455,7 +526,7 @@ impl TradeExecutionAPI for BinanceConnector {
         }
 
         // Validate trading symbol exists (financial safety check)
-        self.get_symbol_info(symbol).await?;
+        let info = self.get_symbol_info(symbol).await?;
 
         // Validate order parameters
         if quantity.is_none() && quote_quantity.is_none() {
@@ -474,10 +545,14 @@ impl TradeExecutionAPI for BinanceConnector {
         });
         params.insert("type".to_string(), "MARKET".to_string());
 
-        if let Some(qty) = quantity {
-            if qty <= Decimal::ZERO {
+        let quantity = match quantity {
+            Some(qty) if qty <= Decimal::ZERO => {
                 return Err(ExchangeError::InvalidOrder("Quantity must be greater than zero".to_string()));
             }
+            Some(qty) => Some(Self::round_quantity_for(&info, qty)?),
+            None => None,
+        };
+        if let Some(qty) = quantity {
             params.insert("quantity".to_string(), qty.to_string());
         }
 
@@ -488,6 +563,19 @@ impl TradeExecutionAPI for BinanceConnector {
             params.insert("quoteOrderQty".to_string(), quote_qty.to_string());
         }
 
+        // A market order by quantity is valued at the last price; skip the check when
+        // there is none rather than refuse the order
+        let notional = match (quantity, quote_quantity) {
+            (Some(qty), _) => match self.get_ticker(symbol).await {
+                Ok(ticker) if ticker.last_price > Decimal::ZERO => Some(qty * ticker.last_price),
+                _ => None,
+            },
+            (None, quote_qty) => quote_qty,
+        };
+        if let Some(notional) = notional {
+            Self::check_min_notional_for(&info, notional)?;
+        }
+
         // Check balance before placing order
         if side == OrderSide::Sell {
             if let Some(qty) = quantity {
@@ -505,10 +593,8 @@ impl TradeExecutionAPI for BinanceConnector {
             }
         }
 
-        match self.client.signed_request("order", &params).await {
-            Ok(response) => parse_single_order_from_json(response, wallet_type),
-            Err(e) => Err(e)
-        }
+        let response = self.client.signed_request_with(Method::POST, "order", &params).await?;
+        parse_single_order_from_json(response, wallet_type)
     }
 
     async fn place_limit_order(
@@ -525,7 +611,7 @@ impl TradeExecutionAPI for BinanceConnector {
         }
 
         // Validate trading symbol exists (financial safety check)
-        self.get_symbol_info(symbol).await?;
+        let info = self.get_symbol_info(symbol).await?;
 
         // Validate order parameters
         if price <= Decimal::ZERO {
@@ -534,6 +620,9 @@ impl TradeExecutionAPI for BinanceConnector {
         if quantity <= Decimal::ZERO {
             return Err(ExchangeError::InvalidOrder("Quantity must be greater than zero".to_string()));
         }
+        let quantity = Self::round_quantity_for(&info, quantity)?;
+        let price = Self::round_price_for(&info, price)?;
+        Self::check_min_notional_for(&info, price * quantity)?;
 
         let mut params = HashMap::new();
         params.insert("symbol".to_string(), symbol.to_uppercase());
@@ -585,10 +674,8 @@ impl TradeExecutionAPI for BinanceConnector {
             Err(_) => {} // Continue if we can't check balance
         }
 
-        match self.client.signed_request("order", &params).await {
-            Ok(response) => parse_single_order_from_json(response, wallet_type),
-            Err(e) => Err(e)
-        }
+        let response = self.client.signed_request_with(Method::POST, "order", &params).await?;
+        parse_single_order_from_json(response, wallet_type)
     }
 
     async fn place_stop_loss_order(
@@ -633,7 +720,7 @@ impl TradeExecutionAPI for BinanceConnector {
 impl MarketDataAPI for BinanceConnector {
     async fn get_ticker(&self, symbol: &str) -> Result<Ticker, ExchangeError> {
         let url = format!("{}/api/v3/ticker/24hr?symbol={}", self.client.spot_base_url, symbol);
-        let response = self.client.client.get(&url).send().await?;
+        let response = self.client.send(self.client.client.get(&url)).await?;
         let json: Value = response.json().await?;
         parse_ticker_from_json(json, symbol)
     }
@@ -641,7 +728,7 @@ impl MarketDataAPI for BinanceConnector {
     async fn get_order_book(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBook, ExchangeError> {
         let limit_param = limit.unwrap_or(100);
         let url = format!("{}/api/v3/depth?symbol={}&limit={}", self.client.spot_base_url, symbol, limit_param);
-        let response = self.client.client.get(&url).send().await?;
+        let response = self.client.send(self.client.client.get(&url)).await?;
         let json: Value = response.json().await?;
         parse_order_book_from_json(json, symbol)
     }
@@ -649,7 +736,7 @@ impl MarketDataAPI for BinanceConnector {
     async fn get_recent_trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<Trade>, ExchangeError> {
         let limit_param = limit.unwrap_or(500);
         let url = format!("{}/api/v3/trades?symbol={}&limit={}", self.client.spot_base_url, symbol, limit_param);
-        let response = self.client.client.get(&url).send().await?;
+        let response = self.client.send(self.client.client.get(&url)).await?;
         let json: Value = response.json().await?;
         parse_trades_from_json(json, symbol)
     }
@@ -675,14 +762,14 @@ impl MarketDataAPI for BinanceConnector {
             url.push_str(&format!("&limit={}", lim));
         }
 
-        let response = self.client.client.get(&url).send().await?;
+        let response = self.client.send(self.client.client.get(&url)).await?;
         let json: Value = response.json().await?;
         parse_klines_from_json(json)
     }
 
     async fn get_exchange_info(&self) -> Result<ExchangeInfo, ExchangeError> {
         let url = format!("{}/api/v3/exchangeInfo", self.client.spot_base_url);
-        let response = self.client.client.get(&url).send().await?;
+        let response = self.client.send(self.client.client.get(&url)).await?;
         let json: Value = response.json().await?;
         parse_exchange_info_from_json(json)
     }
@@ -693,4 +780,325 @@ impl MarketDataAPI for BinanceConnector {
             .find(|s| s.symbol == symbol)
             .ok_or_else(|| ExchangeError::SymbolNotFound(format!("Symbol {} not found", symbol)))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange_connectors::common_types::{OrderRequest, OrderStatus, OrderType};
+    use crate::utils::http::TokenBucket;
+    use crate::utils::mock_http::{self, MockResponse};
+    use hmac::{Hmac, Mac};
+    use serde_json::json;
+    use sha2::Sha256;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    const SECRET: &str = "secret";
+
+    /// Method, path and query parameters of a request the mock received
+    type Received = (String, String, HashMap<String, String>);
+
+    /// Mock of the spot API: BTCUSDT's exchange info and ticker, and signed order placement
+    /// and cancellation that reject a bad signature the way Binance does
+    async fn spawn_order_server() -> (String, Arc<Mutex<Vec<Received>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+
+        let (url, _) = mock_http::spawn(move |request| {
+            let params = request.params();
+            log.lock().unwrap().push((request.method.clone(), request.path.clone(), params.clone()));
+
+            let signed = request.query.split_once("&signature=").is_some_and(|(payload, signature)| {
+                let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+                mac.update(payload.as_bytes());
+                hex::encode(mac.finalize().into_bytes()) == signature
+            });
+            let param = |key: &str| params.get(key).cloned().unwrap_or_default();
+
+            let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/api/v3/exchangeInfo") => ("200 OK", json!({
+                    "timezone": "UTC",
+                    "serverTime": 1700000000000i64,
+                    "symbols": [{
+                        "symbol": "BTCUSDT",
+                        "baseAsset": "BTC",
+                        "quoteAsset": "USDT",
+                        "status": "TRADING",
+                        "filters": [
+                            {"filterType": "PRICE_FILTER", "minPrice": "0.01000000", "maxPrice": "1000000.00000000", "tickSize": "0.01000000"},
+                            {"filterType": "LOT_SIZE", "minQty": "0.00001000", "maxQty": "9000.00000000", "stepSize": "0.00001000"},
+                            {"filterType": "NOTIONAL", "minNotional": "5.00000000", "applyMinToMarket": true, "maxNotional": "9000000.00000000", "applyMaxToMarket": false, "avgPriceMins": 5}
+                        ]
+                    }]
+                })),
+                ("GET", "/api/v3/ticker/24hr") => ("200 OK", json!({
+                    "symbol": param("symbol"),
+                    "bidPrice": "49999.99000000",
+                    "askPrice": "50000.00000000",
+                    "lastPrice": "50000.00000000"
+                })),
+                ("POST" | "DELETE", "/api/v3/order") if !signed => (
+                    "401 Unauthorized",
+                    json!({"code": -1022, "msg": "Signature for this request is not valid."}),
+                ),
+                ("POST", "/api/v3/order") if param("type") == "MARKET" => {
+                    let quantity = Decimal::from_str(&param("quantity")).unwrap();
+                    ("200 OK", json!({
+                        "symbol": param("symbol"),
+                        "orderId": 28,
+                        "transactTime": 1700000000000i64,
+                        "price": "0.00000000",
+                        "origQty": quantity.to_string(),
+                        "executedQty": quantity.to_string(),
+                        "cummulativeQuoteQty": (quantity * Decimal::from(50_000)).to_string(),
+                        "status": "FILLED",
+                        "timeInForce": "GTC",
+                        "type": "MARKET",
+                        "side": param("side")
+                    }))
+                }
+                ("POST", "/api/v3/order") => ("200 OK", json!({
+                    "symbol": param("symbol"),
+                    "orderId": 29,
+                    "transactTime": 1700000000000i64,
+                    "price": param("price"),
+                    "origQty": param("quantity"),
+                    "executedQty": "0.00000000",
+                    "cummulativeQuoteQty": "0.00000000",
+                    "status": "NEW",
+                    "timeInForce": param("timeInForce"),
+                    "type": "LIMIT",
+                    "side": param("side")
+                })),
+                ("DELETE", "/api/v3/order") => ("200 OK", json!({
+                    "symbol": param("symbol"),
+                    "orderId": param("orderId").parse::<i64>().unwrap_or_default(),
+                    "price": "50000.12000000",
+                    "origQty": "0.50000000",
+                    "executedQty": "0.00000000",
+                    "cummulativeQuoteQty": "0.00000000",
+                    "status": "CANCELED",
+                    "timeInForce": "GTC",
+                    "type": "LIMIT",
+                    "side": "SELL"
+                })),
+                _ => ("404 Not Found", json!({"code": -1, "msg": "Not found"})),
+            };
+
+            Some(MockResponse::json(status, body))
+        })
+        .await;
+
+        (url, received)
+    }
+
+    fn test_connector(base_url: String) -> BinanceConnector {
+        let mut client = BinanceApiClient::new(ExchangeCredentials {
+            api_key: "key".to_string(),
+            api_secret: SECRET.to_string(),
+            passphrase: None,
+        })
+        .unwrap();
+        client.spot_base_url = base_url;
+        client.rate_limiter = Arc::new(TokenBucket::per_minute(6000));
+        BinanceConnector { client }
+    }
+
+    fn order_requests(received: &Mutex<Vec<Received>>) -> Vec<Received> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, path, _)| path == "/api/v3/order")
+            .cloned()
+            .collect()
+    }
+
+    fn request(order_type: OrderType, side: OrderSide, quantity: &str, price: Option<&str>) -> OrderRequest {
+        OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side,
+            order_type,
+            quantity: Some(Decimal::from_str(quantity).unwrap()),
+            quote_quantity: None,
+            price: price.map(|price| Decimal::from_str(price).unwrap()),
+            time_in_force: None,
+            wallet_type: WalletType::Spot,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_market_order_is_posted_signed_with_quantity_rounded_to_step_size() {
+        let (url, received) = spawn_order_server().await;
+        let connector = test_connector(url);
+
+        let order = connector
+            .place_order(request(OrderType::Market, OrderSide::Buy, "0.0123456", None))
+            .await
+            .unwrap();
+
+        let orders = order_requests(&received);
+        assert_eq!(orders.len(), 1);
+        let (method, _, params) = &orders[0];
+        assert_eq!(method, "POST");
+        assert_eq!(params["type"], "MARKET");
+        assert_eq!(params["side"], "BUY");
+        assert_eq!(params["quantity"], "0.01234");
+
+        assert_eq!(order.order_id, "28");
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.executed_quantity, Decimal::from_str("0.01234").unwrap());
+        assert_eq!(order.average_price, Some(Decimal::from(50_000)));
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_and_its_cancellation() {
+        let (url, received) = spawn_order_server().await;
+        let connector = test_connector(url);
+
+        let order = connector
+            .place_order(request(OrderType::Limit, OrderSide::Sell, "0.5", Some("50000.129")))
+            .await
+            .unwrap();
+        assert_eq!(order.order_id, "29");
+        assert_eq!(order.status, OrderStatus::New);
+        assert_eq!(order.price, Some(Decimal::from_str("50000.12").unwrap()));
+
+        let cancelled = connector.cancel_order(&order.order_id, "BTCUSDT", WalletType::Spot).await.unwrap();
+        assert_eq!(cancelled.status, OrderStatus::Canceled);
+
+        let orders = order_requests(&received);
+        assert_eq!(orders.len(), 2);
+        let (method, _, params) = &orders[0];
+        assert_eq!(method, "POST");
+        assert_eq!(params["type"], "LIMIT");
+        assert_eq!(params["price"], "50000.12");
+        assert_eq!(params["quantity"], "0.5");
+        assert_eq!(params["timeInForce"], "GTC");
+        let (method, _, params) = &orders[1];
+        assert_eq!(method, "DELETE");
+        assert_eq!(params["orderId"], "29");
+    }
+
+    #[test]
+    fn test_btcusdt_filters_round_down_to_step_and_tick() {
+        let btcusdt = SymbolInfo {
+            symbol: "BTCUSDT".to_string(),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDT".to_string(),
+            status: "TRADING".to_string(),
+            min_price: Decimal::from_str("0.01").unwrap(),
+            max_price: Decimal::from(1_000_000),
+            tick_size: Decimal::from_str("0.01").unwrap(),
+            min_quantity: Decimal::from_str("0.00001").unwrap(),
+            max_quantity: Decimal::from(9000),
+            step_size: Decimal::from_str("0.00001").unwrap(),
+            min_notional: Decimal::from(5),
+            is_spot_trading_allowed: true,
+            is_margin_trading_allowed: true,
+            permissions: vec!["SPOT".to_string()],
+        };
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        assert_eq!(BinanceConnector::round_quantity_for(&btcusdt, dec("0.123456789")).unwrap(), dec("0.12345"));
+        assert_eq!(BinanceConnector::round_quantity_for(&btcusdt, dec("0.00001")).unwrap(), dec("0.00001"));
+        assert!(BinanceConnector::round_quantity_for(&btcusdt, dec("0.000009")).is_err());
+
+        assert_eq!(BinanceConnector::round_price_for(&btcusdt, dec("64123.456")).unwrap(), dec("64123.45"));
+        assert!(BinanceConnector::round_price_for(&btcusdt, dec("0.009")).is_err());
+
+        assert!(BinanceConnector::check_min_notional_for(&btcusdt, dec("5")).is_ok());
+        assert!(matches!(
+            BinanceConnector::check_min_notional_for(&btcusdt, dec("4.99")),
+            Err(ExchangeError::InvalidOrder(message)) if message.contains("minimum notional 5")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_filter_helpers_look_up_the_symbol_by_name() {
+        let (url, _) = spawn_order_server().await;
+        let connector = test_connector(url);
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        assert_eq!(connector.round_quantity("BTCUSDT", dec("0.123456789")).await.unwrap(), dec("0.12345"));
+        assert_eq!(connector.round_price("BTCUSDT", dec("64123.456")).await.unwrap(), dec("64123.45"));
+        assert!(connector.check_min_notional("BTCUSDT", dec("5")).await.is_ok());
+        assert!(matches!(
+            connector.check_min_notional("BTCUSDT", dec("4.99")).await,
+            Err(ExchangeError::InvalidOrder(_))
+        ));
+        assert!(matches!(
+            connector.round_quantity("NOPEUSDT", dec("1")).await,
+            Err(ExchangeError::SymbolNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_orders_below_min_notional_are_never_sent() {
+        let (url, received) = spawn_order_server().await;
+        let connector = test_connector(url);
+
+        // 0.00009 BTC at the last price of 50,000 is worth 4.50 USDT
+        let by_quantity = connector
+            .place_order(request(OrderType::Market, OrderSide::Buy, "0.00009", None))
+            .await;
+        assert!(matches!(by_quantity, Err(ExchangeError::InvalidOrder(message)) if message.contains("notional")));
+
+        let mut by_quote = request(OrderType::Market, OrderSide::Buy, "1", None);
+        by_quote.quantity = None;
+        by_quote.quote_quantity = Some(Decimal::from_str("4.99").unwrap());
+        assert!(matches!(connector.place_order(by_quote).await, Err(ExchangeError::InvalidOrder(_))));
+
+        let limit = connector
+            .place_order(request(OrderType::Limit, OrderSide::Buy, "0.0001", Some("49999.99")))
+            .await;
+        assert!(matches!(limit, Err(ExchangeError::InvalidOrder(_))));
+        assert!(order_requests(&received).is_empty());
+
+        // Exactly the minimum goes through
+        connector
+            .place_order(request(OrderType::Limit, OrderSide::Buy, "0.0001", Some("50000")))
+            .await
+            .unwrap();
+        assert_eq!(order_requests(&received).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_order_rounding_below_the_minimum_is_never_sent() {
+        let (url, received) = spawn_order_server().await;
+        let connector = test_connector(url);
+
+        let result = connector
+            .place_order(request(OrderType::Market, OrderSide::Buy, "0.000009", None))
+            .await;
+        assert!(matches!(result, Err(ExchangeError::InvalidOrder(_))));
+
+        let missing_price = connector
+            .place_order(request(OrderType::Limit, OrderSide::Buy, "1", None))
+            .await;
+        assert!(matches!(missing_price, Err(ExchangeError::InvalidOrder(_))));
+        assert!(order_requests(&received).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_badly_signed_order_is_rejected() {
+        let (url, _) = spawn_order_server().await;
+        let mut connector = test_connector(url.clone());
+        connector.client = {
+            let mut client = BinanceApiClient::new(ExchangeCredentials {
+                api_key: "key".to_string(),
+                api_secret: "wrong".to_string(),
+                passphrase: None,
+            })
+            .unwrap();
+            client.spot_base_url = url;
+            client
+        };
+
+        let result = connector
+            .place_order(request(OrderType::Market, OrderSide::Buy, "0.01", None))
+            .await;
+        assert!(matches!(result, Err(ExchangeError::InvalidApiKey)));
+    }
+}
@@ -1,20 +1,17 @@
 use chrono::{DateTime, Utc, TimeZone};
 use rust_decimal::Decimal;
-use std::str::FromStr;
 use serde_json::Value;
+use std::collections::HashMap;
 use tracing::{warn, debug};
 use crate::exchange_connectors::{
     ExchangeError,
     shared_types::{Ticker, OrderBook, OrderBookLevel, Trade, Kline, KlineInterval, ExchangeInfo, SymbolInfo},
-    common_types::{Order, OrderSide, OrderType, OrderStatus, TimeInForce, WalletType},
+    common_types::{ApiKeyPermissions, Order, OrderSide, OrderType, OrderStatus, TimeInForce, WalletType},
 };
 use super::types::*;
 use super::api_client::BinanceApiClient;
 
-pub fn parse_decimal(s: &str) -> Result<Decimal, ExchangeError> {
-    Decimal::from_str(s)
-        .map_err(|e| ExchangeError::ParseError(format!("Failed to parse decimal: {}", e)))
-}
+pub use crate::exchange_connectors::numeric::parse_decimal;
 
 pub fn parse_timestamp(ts: i64) -> DateTime<Utc> {
     Utc.timestamp_millis_opt(ts).single()
@@ -42,13 +39,23 @@ pub fn convert_kline_interval_to_string(interval: &KlineInterval) -> &'static st
     }
 }
 
+/// Every Binance spot price from one bulk request. When it fails the map is empty and
+/// only stablecoins can be priced.
+async fn fetch_price_map(client: &BinanceApiClient) -> HashMap<String, Decimal> {
+    client.get_all_prices().await.unwrap_or_else(|e| {
+        warn!("Failed to fetch Binance prices: {:?}", e);
+        HashMap::new()
+    })
+}
 
 pub async fn parse_spot_account_from_json_with_prices(
     json: Value,
     client: &BinanceApiClient
 ) -> Result<BinanceSpotAccount, ExchangeError> {
+    let prices = fetch_price_map(client).await;
     let mut balances = Vec::new();
     let mut total_usd_value = Decimal::ZERO;
+    let mut unpriced_assets = 0u32;
 
     if let Some(balance_array) = json.get("balances").and_then(|v| v.as_array()) {
         for balance in balance_array {
@@ -61,32 +68,19 @@ pub async fn parse_spot_account_from_json_with_prices(
             let total = free + locked;
 
             if total > Decimal::ZERO {
-                // Get USD value for the asset
-                let mut usd_value = None;
-                
-                // Skip USDT, USDC, BUSD (stablecoins) - they're already ~$1
-                if asset == "USDT" || asset == "USDC" || asset == "BUSD" || asset == "DAI" {
-                    usd_value = Some(total); // 1:1 USD value for stablecoins
-                } else {
-                    // Get current price from Binance directly
-                    match client.get_symbol_price(asset).await {
-                        Ok(price) if price > Decimal::ZERO => {
-                            let asset_usd_value = total * price;
-                            usd_value = Some(asset_usd_value);
-                            debug!("Got Binance price for {}: ${}, total value: ${}", asset, price, asset_usd_value);
-                        }
-                        Ok(_) => {
-                            debug!("No price data available for {} on Binance", asset);
-                        }
-                        Err(e) => {
-                            warn!("Failed to get Binance price for {}: {:?}", asset, e);
-                        }
+                // Stablecoins are valued at their peg unless the peg check is enabled
+                let (usd_value, price_source) = match client.price_from(&prices, asset) {
+                    Some((price, source)) => (Some(total * price), Some(source)),
+                    None => {
+                        debug!("No price data available for {} on Binance", asset);
+                        (None, None)
                     }
-                }
+                };
 
                 // Add to total if we got a USD value
-                if let Some(value) = usd_value {
-                    total_usd_value += value;
+                match usd_value {
+                    Some(value) => total_usd_value += value,
+                    None => unpriced_assets += 1,
                 }
 
                 balances.push(BinanceAssetBalance {
@@ -97,6 +91,8 @@ pub async fn parse_spot_account_from_json_with_prices(
                     usd_value,
                     btc_value: None, // TODO: Add BTC conversion if needed
                     wallet_type: BinanceWalletType::Spot,
+                    priced: usd_value.is_some(),
+                    price_source,
                 });
             }
         }
@@ -119,9 +115,14 @@ pub async fn parse_spot_account_from_json_with_prices(
         .map(parse_timestamp)
         .unwrap_or_else(|| Utc::now());
 
+    if unpriced_assets > 0 {
+        warn!("{} spot assets could not be priced and are excluded from the total", unpriced_assets);
+    }
+
     Ok(BinanceSpotAccount {
         balances,
         total_usd_value: Some(total_usd_value),
+        unpriced_assets,
         total_btc_value: None,
         maker_commission,
         taker_commission,
@@ -136,6 +137,7 @@ pub async fn parse_margin_account_from_json_with_prices(
     json: Value,
     client: &BinanceApiClient
 ) -> Result<BinanceMarginAccount, ExchangeError> {
+    let prices = fetch_price_map(client).await;
     let mut balances = Vec::new();
     let mut total_asset_value = Decimal::ZERO;
     let mut total_liability_value = Decimal::ZERO;
@@ -155,31 +157,18 @@ pub async fn parse_margin_account_from_json_with_prices(
             let total = free + locked;
 
             if total > Decimal::ZERO || borrowed > Decimal::ZERO {
-                // Get USD value for the asset
-                let mut usd_value = None;
-                
-                // Skip USDT, USDC, BUSD (stablecoins) - they're already ~$1
-                if asset == "USDT" || asset == "USDC" || asset == "BUSD" || asset == "DAI" {
-                    usd_value = Some(total);
-                    total_asset_value += total;
-                    total_liability_value += borrowed + interest;
-                } else {
-                    // Get current price from Binance directly
-                    match client.get_symbol_price(asset).await {
-                        Ok(price) => {
-                            let asset_usd_value = total * price;
-                            let liability_usd_value = (borrowed + interest) * price;
-                            usd_value = Some(asset_usd_value);
-                            total_asset_value += asset_usd_value;
-                            total_liability_value += liability_usd_value;
-                            debug!("Margin - Got Binance price for {}: ${}, asset value: ${}, liability: ${}",
-                                   asset, price, asset_usd_value, liability_usd_value);
-                        }
-                        Err(e) => {
-                            warn!("Failed to get Binance price for margin asset {}: {:?}", asset, e);
-                        }
+                // Stablecoins are valued at their peg unless the peg check is enabled
+                let (usd_value, price_source) = match client.price_from(&prices, asset) {
+                    Some((price, source)) => {
+                        total_asset_value += total * price;
+                        total_liability_value += (borrowed + interest) * price;
+                        (Some(total * price), Some(source))
                     }
-                }
+                    None => {
+                        warn!("No Binance price for margin asset {}", asset);
+                        (None, None)
+                    }
+                };
 
                 balances.push(BinanceAssetBalance {
                     asset: asset.to_string(),
@@ -189,6 +178,8 @@ pub async fn parse_margin_account_from_json_with_prices(
                     usd_value,
                     btc_value: None,
                     wallet_type: BinanceWalletType::Margin,
+                    priced: usd_value.is_some(),
+                    price_source,
                 });
             }
         }
@@ -224,6 +215,7 @@ pub async fn parse_futures_account_from_json_with_prices(
     client: &BinanceApiClient,
     account_type: BinanceFuturesType,
 ) -> Result<BinanceFuturesAccount, ExchangeError> {
+    let prices = fetch_price_map(client).await;
     let mut balances = Vec::new();
     let mut positions = Vec::new();
 
@@ -240,22 +232,13 @@ pub async fn parse_futures_account_from_json_with_prices(
             let margin_balance = parse_decimal(margin_balance_str)?;
 
             if wallet_balance > Decimal::ZERO || margin_balance > Decimal::ZERO {
-                // Get USD value for the asset
-                let mut usd_value = None;
-                
-                if asset == "USDT" || asset == "USDC" || asset == "BUSD" {
-                    usd_value = Some(margin_balance);
-                } else {
-                    match client.get_symbol_price(asset).await {
-                        Ok(price) => {
-                            usd_value = Some(margin_balance * price);
-                            debug!("Futures - Got Binance price for {}: ${}, value: ${}", asset, price, margin_balance * price);
-                        }
-                        Err(e) => {
-                            warn!("Failed to get Binance price for futures asset {}: {:?}", asset, e);
-                        }
+                let (usd_value, price_source) = match client.price_from(&prices, asset) {
+                    Some((price, source)) => (Some(margin_balance * price), Some(source)),
+                    None => {
+                        warn!("No Binance price for futures asset {}", asset);
+                        (None, None)
                     }
-                }
+                };
 
                 balances.push(BinanceAssetBalance {
                     asset: asset.to_string(),
@@ -265,6 +248,8 @@ pub async fn parse_futures_account_from_json_with_prices(
                     usd_value,
                     btc_value: None,
                     wallet_type: BinanceWalletType::Futures,
+                    priced: usd_value.is_some(),
+                    price_source,
                 });
             }
         }
@@ -426,7 +411,9 @@ pub fn parse_single_order_from_json(json: Value, wallet_type: WalletType) -> Res
         .transpose()?
         .unwrap_or(Decimal::ZERO);
 
+    // A newly placed order has a transaction time instead
     let created_time = json.get("time")
+        .or_else(|| json.get("transactTime"))
         .and_then(|v| v.as_i64())
         .map(parse_timestamp)
         .unwrap_or_else(|| Utc::now());
@@ -449,7 +436,8 @@ pub fn parse_single_order_from_json(json: Value, wallet_type: WalletType) -> Res
         quantity: quantity,
         executed_quantity: executed_quantity,
         cumulative_quote_quantity: cumulative_quote_quantity,
-        average_price: None,
+        average_price: (executed_quantity > Decimal::ZERO)
+            .then(|| cumulative_quote_quantity / executed_quantity),
         fee: None,
         fee_asset: None,
         pnl: None,
@@ -693,21 +681,45 @@ pub fn parse_exchange_info_from_json(json: Value) -> Result<ExchangeInfo, Exchan
                 .unwrap_or_default()
                 .to_string();
 
+            let filters = symbol_json.get("filters")
+                .and_then(|v| v.as_array())
+                .map(|filters| filters.as_slice())
+                .unwrap_or_default();
+            let filter_value = |filter_type: &[&str], key: &str| -> Result<Decimal, ExchangeError> {
+                filters.iter()
+                    .filter(|f| f.get("filterType").and_then(|t| t.as_str()).is_some_and(|t| filter_type.contains(&t)))
+                    .find_map(|f| f.get(key).and_then(|v| v.as_str()))
+                    .map(parse_decimal)
+                    .transpose()
+                    .map(|value| value.unwrap_or(Decimal::ZERO))
+            };
+
+            let permissions: Vec<String> = symbol_json.get("permissions")
+                .and_then(|v| v.as_array())
+                .map(|permissions| permissions.iter().filter_map(|p| p.as_str()).map(str::to_string).collect())
+                .filter(|permissions: &Vec<String>| !permissions.is_empty())
+                .unwrap_or_else(|| vec!["SPOT".to_string()]);
+
             symbols.push(SymbolInfo {
                 symbol,
                 base_asset,
                 quote_asset,
                 status,
-                min_price: Decimal::ZERO,
-                max_price: Decimal::ZERO,
-                tick_size: Decimal::ZERO,
-                min_quantity: Decimal::ZERO,
-                max_quantity: Decimal::ZERO,
-                step_size: Decimal::ZERO,
-                min_notional: Decimal::ZERO,
-                is_spot_trading_allowed: true,
-                is_margin_trading_allowed: false,
-                permissions: vec!["SPOT".to_string()],
+                min_price: filter_value(&["PRICE_FILTER"], "minPrice")?,
+                max_price: filter_value(&["PRICE_FILTER"], "maxPrice")?,
+                tick_size: filter_value(&["PRICE_FILTER"], "tickSize")?,
+                min_quantity: filter_value(&["LOT_SIZE"], "minQty")?,
+                max_quantity: filter_value(&["LOT_SIZE"], "maxQty")?,
+                step_size: filter_value(&["LOT_SIZE"], "stepSize")?,
+                // Newer symbols carry NOTIONAL, older ones MIN_NOTIONAL
+                min_notional: filter_value(&["NOTIONAL", "MIN_NOTIONAL"], "minNotional")?,
+                is_spot_trading_allowed: symbol_json.get("isSpotTradingAllowed")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true),
+                is_margin_trading_allowed: symbol_json.get("isMarginTradingAllowed")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                permissions,
             });
         }
     }
@@ -718,4 +730,264 @@ pub fn parse_exchange_info_from_json(json: Value) -> Result<ExchangeInfo, Exchan
         rate_limits: vec![], // TODO: Parse rate limits
         symbols,
     })
-}
\ No newline at end of file
+}
+
+pub fn parse_api_restrictions(json: Value) -> Result<ApiKeyPermissions, ExchangeError> {
+    let restrictions: BinanceApiRestrictions = serde_json::from_value(json)
+        .map_err(|e| ExchangeError::ParseError(format!("Failed to parse API restrictions: {}", e)))?;
+
+    // Binance grants spot and margin trading together; margin additionally needs `enableMargin`
+    Ok(ApiKeyPermissions {
+        can_read: restrictions.enable_reading,
+        can_trade_spot: restrictions.enable_spot_and_margin_trading,
+        can_trade_margin: restrictions.enable_spot_and_margin_trading && restrictions.enable_margin,
+        can_trade_futures: restrictions.enable_futures,
+        can_withdraw: restrictions.enable_withdrawals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use crate::exchange_connectors::ExchangeCredentials;
+    use crate::exchange_connectors::common_types::PriceSource;
+    use crate::utils::mock_http::{self, MockResponse};
+    use super::super::api_client::StablecoinPegConfig;
+
+    /// Serves ticker prices for the given pairs, and all of them at once without a symbol;
+    /// any other symbol gets a 400 like Binance does
+    async fn spawn_price_server(prices: Vec<(&'static str, &'static str)>) -> String {
+        spawn_counting_price_server(prices).await.0
+    }
+
+    /// `spawn_price_server`, also counting the requests it answers
+    async fn spawn_counting_price_server(prices: Vec<(&'static str, &'static str)>) -> (String, Arc<AtomicUsize>) {
+        mock_http::spawn(move |request| {
+            let Some(symbol) = request.param("symbol") else {
+                let tickers: Vec<Value> = prices
+                    .iter()
+                    .map(|(pair, price)| json!({ "symbol": pair, "price": price }))
+                    .collect();
+                return Some(MockResponse::json("200 OK", Value::Array(tickers)));
+            };
+
+            Some(match prices.iter().find(|(pair, _)| *pair == symbol) {
+                Some((_, price)) => MockResponse::json("200 OK", format!(r#"{{"price":"{}"}}"#, price)),
+                None => MockResponse::json("400 Bad Request", r#"{"code":-1121,"msg":"Invalid symbol."}"#),
+            })
+        })
+        .await
+    }
+
+    fn test_client(base_url: String) -> BinanceApiClient {
+        let mut client = BinanceApiClient::new(ExchangeCredentials {
+            api_key: "key".to_string(),
+            api_secret: "secret".to_string(),
+            passphrase: None,
+        })
+        .unwrap();
+        client.spot_base_url = base_url;
+        client.stablecoin_peg = StablecoinPegConfig::default();
+        client
+    }
+
+    #[tokio::test]
+    async fn test_spot_account_flags_unpriced_assets() {
+        let client = test_client(spawn_price_server(vec![("BTCUSDT", "50000")]).await);
+        let account_json = json!({
+            "balances": [
+                { "asset": "BTC", "free": "0.5", "locked": "0" },
+                { "asset": "USDT", "free": "100", "locked": "0" },
+                { "asset": "OBSCURE", "free": "10", "locked": "0" },
+                { "asset": "ETH", "free": "0", "locked": "0" }
+            ]
+        });
+
+        let account = parse_spot_account_from_json_with_prices(account_json, &client).await.unwrap();
+
+        assert_eq!(account.balances.len(), 3);
+        assert_eq!(account.unpriced_assets, 1);
+        assert_eq!(account.total_usd_value, Some(Decimal::from(25100)));
+
+        let btc = account.balances.iter().find(|b| b.asset == "BTC").unwrap();
+        assert!(btc.priced);
+        assert_eq!(btc.price_source, Some(PriceSource::ExchangeTicker));
+
+        let usdt = account.balances.iter().find(|b| b.asset == "USDT").unwrap();
+        assert!(usdt.priced);
+        assert_eq!(usdt.price_source, Some(PriceSource::StablecoinPeg));
+
+        let obscure = account.balances.iter().find(|b| b.asset == "OBSCURE").unwrap();
+        assert!(!obscure.priced);
+        assert_eq!(obscure.price_source, None);
+        assert_eq!(obscure.usd_value, None);
+    }
+
+    #[tokio::test]
+    async fn test_spot_account_all_priced() {
+        let client = test_client(spawn_price_server(vec![("BTCUSDT", "50000")]).await);
+        let account_json = json!({
+            "balances": [
+                { "asset": "BTC", "free": "1", "locked": "0" }
+            ]
+        });
+
+        let account = parse_spot_account_from_json_with_prices(account_json, &client).await.unwrap();
+
+        assert_eq!(account.unpriced_assets, 0);
+        assert!(account.balances[0].priced);
+    }
+
+    #[tokio::test]
+    async fn test_many_asset_account_is_priced_from_one_bulk_request() {
+        const PAIRS: [&str; 17] = [
+            "BTCUSDT", "ETHUSDT", "BNBUSDT", "SOLUSDT", "XRPUSDT", "ADAUSDT", "DOGEUSDT", "DOTUSDT",
+            "LINKUSDT", "LTCUSDT", "AVAXUSDT", "ATOMUSDT", "UNIUSDT", "XLMUSDT", "NEARUSDT", "APTUSDT",
+            "ARBUSDT",
+        ];
+        let prices = PAIRS.iter().map(|pair| (*pair, "2")).chain([("FDUSDUSDT", "0.5")]).collect();
+        let (url, requests) = spawn_counting_price_server(prices).await;
+        let client = test_client(url);
+
+        // 17 priced coins, 2 stablecoins valued at their peg and one coin without a market
+        let balances: Vec<Value> = PAIRS
+            .iter()
+            .map(|pair| pair.trim_end_matches("USDT"))
+            .chain(["USDT", "FDUSD", "OBSCURE"])
+            .map(|asset| json!({ "asset": asset, "free": "10", "locked": "0" }))
+            .collect();
+        assert_eq!(balances.len(), 20);
+
+        let account = parse_spot_account_from_json_with_prices(json!({ "balances": balances }), &client)
+            .await
+            .unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(account.balances.len(), 20);
+        assert_eq!(account.unpriced_assets, 1);
+        // 17 * 10 * $2 + 2 stablecoins * 10 * $1
+        assert_eq!(account.total_usd_value, Some(Decimal::from(360)));
+        let fdusd = account.balances.iter().find(|b| b.asset == "FDUSD").unwrap();
+        assert_eq!(fdusd.price_source, Some(PriceSource::StablecoinPeg));
+    }
+
+    fn depegged_usdc_account() -> Value {
+        json!({
+            "balances": [
+                { "asset": "USDC", "free": "100", "locked": "0" }
+            ]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_depegged_stablecoin_uses_market_price_when_check_enabled() {
+        let mut client = test_client(spawn_price_server(vec![("USDCUSDT", "0.90")]).await);
+        client.stablecoin_peg = StablecoinPegConfig {
+            use_market_price: true,
+            alert_deviation_percentage: Decimal::from(2),
+        };
+
+        let account = parse_spot_account_from_json_with_prices(depegged_usdc_account(), &client).await.unwrap();
+
+        let usdc = &account.balances[0];
+        assert_eq!(usdc.usd_value, Some(Decimal::from(90)));
+        assert_eq!(usdc.price_source, Some(PriceSource::ExchangeTicker));
+        assert_eq!(client.get_symbol_price("USDC").await.unwrap(), Decimal::from_str("0.90").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_depegged_stablecoin_valued_at_peg_when_check_disabled() {
+        let client = test_client(spawn_price_server(vec![("USDCUSDT", "0.90")]).await);
+
+        let account = parse_spot_account_from_json_with_prices(depegged_usdc_account(), &client).await.unwrap();
+
+        let usdc = &account.balances[0];
+        assert_eq!(usdc.usd_value, Some(Decimal::from(100)));
+        assert_eq!(usdc.price_source, Some(PriceSource::StablecoinPeg));
+        assert_eq!(client.get_symbol_price("USDC").await.unwrap(), Decimal::ONE);
+    }
+
+    #[test]
+    fn test_api_restrictions_map_to_permission_flags() {
+        let restrictions = json!({
+            "ipRestrict": false,
+            "createTime": 1698645219000i64,
+            "enableReading": true,
+            "enableSpotAndMarginTrading": true,
+            "enableMargin": false,
+            "enableFutures": true,
+            "enableWithdrawals": false,
+            "enableInternalTransfer": false,
+            "permitsUniversalTransfer": false,
+            "enableVanillaOptions": false,
+            "enablePortfolioMarginTrading": false
+        });
+
+        let permissions = parse_api_restrictions(restrictions).unwrap();
+
+        assert_eq!(
+            permissions,
+            ApiKeyPermissions {
+                can_read: true,
+                can_trade_spot: true,
+                can_trade_margin: false,
+                can_trade_futures: true,
+                can_withdraw: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_exchange_info_parses_symbol_filters() {
+        let info = parse_exchange_info_from_json(json!({
+            "timezone": "UTC",
+            "serverTime": 1700000000000i64,
+            "symbols": [{
+                "symbol": "BTCUSDT",
+                "status": "TRADING",
+                "baseAsset": "BTC",
+                "quoteAsset": "USDT",
+                "isSpotTradingAllowed": true,
+                "isMarginTradingAllowed": true,
+                "filters": [
+                    { "filterType": "PRICE_FILTER", "minPrice": "0.01000000", "maxPrice": "1000000.00000000", "tickSize": "0.01000000" },
+                    { "filterType": "LOT_SIZE", "minQty": "0.00001000", "maxQty": "9000.00000000", "stepSize": "0.00001000" },
+                    { "filterType": "NOTIONAL", "minNotional": "5.00000000", "applyMinToMarket": true, "maxNotional": "9000000.00000000" }
+                ]
+            }, {
+                "symbol": "OLDUSDT",
+                "status": "TRADING",
+                "baseAsset": "OLD",
+                "quoteAsset": "USDT",
+                "filters": [{ "filterType": "MIN_NOTIONAL", "minNotional": "10.00000000" }]
+            }]
+        }))
+        .unwrap();
+
+        let btc = &info.symbols[0];
+        assert_eq!(btc.tick_size, Decimal::new(1, 2));
+        assert_eq!(btc.step_size, Decimal::new(1, 5));
+        assert_eq!(btc.min_quantity, Decimal::new(1, 5));
+        assert_eq!(btc.max_quantity, Decimal::from(9000));
+        assert_eq!(btc.min_notional, Decimal::from(5));
+        assert!(btc.is_margin_trading_allowed);
+
+        assert_eq!(info.symbols[1].min_notional, Decimal::from(10));
+        assert_eq!(info.symbols[1].step_size, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_read_only_key_has_no_trading_permissions() {
+        let permissions = parse_api_restrictions(json!({ "enableReading": true })).unwrap();
+
+        assert!(permissions.can_read);
+        assert!(!permissions.can_trade_spot);
+        assert!(!permissions.can_trade_margin);
+        assert!(!permissions.can_trade_futures);
+        assert!(!permissions.can_withdraw);
+    }
+}
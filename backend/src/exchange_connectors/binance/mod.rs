@@ -3,4 +3,6 @@ mod api_client;
 mod converters;
 pub mod types;
 
-pub use connector::BinanceConnector;
\ No newline at end of file
+pub use connector::BinanceConnector;
+pub use api_client::{binance_rate_limiter, binance_retry_policy};
+pub use converters::parse_exchange_info_from_json;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use crate::exchange_connectors::common_types;
+use crate::exchange_connectors::common_types::{self, PriceSource};
 
 /// Binance-specific wallet types focused on the main trading functions
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -30,6 +30,10 @@ pub struct BinanceAssetBalance {
     pub usd_value: Option<Decimal>,
     pub btc_value: Option<Decimal>,
     pub wallet_type: BinanceWalletType,
+    /// Whether `usd_value` could be determined
+    #[serde(default)]
+    pub priced: bool,
+    pub price_source: Option<PriceSource>,
 }
 
 /// Binance Spot Account - Core trading account
@@ -37,6 +41,9 @@ pub struct BinanceAssetBalance {
 pub struct BinanceSpotAccount {
     pub balances: Vec<BinanceAssetBalance>,
     pub total_usd_value: Option<Decimal>,
+    /// Number of balances missing from `total_usd_value` because no price was found
+    #[serde(default)]
+    pub unpriced_assets: u32,
     pub total_btc_value: Option<Decimal>,
     pub maker_commission: Option<Decimal>,
     pub taker_commission: Option<Decimal>,
@@ -132,6 +139,24 @@ pub enum BinanceMarginType {
     Cross,
 }
 
+/// Response of `GET /sapi/v1/account/apiRestrictions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceApiRestrictions {
+    #[serde(default)]
+    pub ip_restrict: bool,
+    #[serde(default)]
+    pub enable_reading: bool,
+    #[serde(default)]
+    pub enable_spot_and_margin_trading: bool,
+    #[serde(default)]
+    pub enable_margin: bool,
+    #[serde(default)]
+    pub enable_futures: bool,
+    #[serde(default)]
+    pub enable_withdrawals: bool,
+}
+
 /// Comprehensive Binance account balances across all wallet types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BinanceAccountBalances {
@@ -262,6 +287,7 @@ impl From<BinanceSpotAccount> for common_types::SpotAccount {
         common_types::SpotAccount {
             balances: val.balances.into_iter().map(|b| b.into()).collect(),
             total_usd_value: val.total_usd_value,
+            unpriced_assets: val.unpriced_assets,
             total_btc_value: val.total_btc_value,
             maker_commission: val.maker_commission,
             taker_commission: val.taker_commission,
@@ -300,6 +326,8 @@ impl From<BinanceAssetBalance> for common_types::AssetBalance {
             usd_value: val.usd_value,
             btc_value: val.btc_value,
             wallet_type: val.wallet_type.into(),
+            priced: val.priced,
+            price_source: val.price_source,
         }
     }
 }
@@ -0,0 +1,260 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+
+use crate::exchange_connectors::{
+    traits::{ExchangeConnector, AccountAPI, OrderAPI, TradeExecutionAPI, MarketDataAPI},
+    ExchangeCredentials,
+    ExchangeError,
+    common_types::{ApiKeyPermissions, SpotAccount, MarginAccount, FuturesAccount, AccountBalances, WalletType, FuturesType, OrderSide, TimeInForce, Order, OcoOrder},
+    shared_types::{Ticker, OrderBook, Trade, Kline, KlineInterval, ExchangeInfo, SymbolInfo},
+};
+
+use super::api_client::CoinbaseApiClient;
+use super::converters::*;
+
+/// Most accounts Coinbase returns per page
+const ACCOUNTS_PAGE_SIZE: u32 = 250;
+
+/// Coinbase Advanced Trade connector. Covers balances and prices; trading is not implemented yet.
+pub struct CoinbaseConnector {
+    client: CoinbaseApiClient,
+}
+
+impl CoinbaseConnector {
+    pub fn new(credentials: ExchangeCredentials) -> Result<Self, ExchangeError> {
+        let client = CoinbaseApiClient::new(credentials)?;
+        Ok(Self { client })
+    }
+
+    /// Every account of the key's portfolio, following the pagination cursor
+    async fn get_accounts(&self) -> Result<Vec<Value>, ExchangeError> {
+        let mut accounts = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut query = vec![("limit", ACCOUNTS_PAGE_SIZE.to_string())];
+            if let Some(cursor) = &cursor {
+                query.push(("cursor", cursor.clone()));
+            }
+
+            let page = self.client.signed_request("/api/v3/brokerage/accounts", &query).await?;
+            if let Some(page_accounts) = page.get("accounts").and_then(|a| a.as_array()) {
+                accounts.extend(page_accounts.iter().cloned());
+            }
+
+            let has_next = page.get("has_next").and_then(|v| v.as_bool()).unwrap_or(false);
+            match page.get("cursor").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+                Some(next) if has_next => cursor = Some(next.to_string()),
+                _ => return Ok(accounts),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ExchangeConnector for CoinbaseConnector {
+    async fn test_connection(&self) -> Result<bool, ExchangeError> {
+        // Public endpoint first so an outage is not reported as bad credentials
+        self.client.public_request("/api/v3/brokerage/time", &[]).await?;
+
+        match self.client.signed_request("/api/v3/brokerage/accounts", &[("limit", "1".to_string())]).await {
+            Ok(_) => {
+                info!("Coinbase API credentials validated successfully");
+                Ok(true)
+            }
+            Err(e) => {
+                error!("Coinbase API credential validation failed: {}", e);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn get_api_permissions(&self) -> Result<ApiKeyPermissions, ExchangeError> {
+        let data = self.client.signed_request("/api/v3/brokerage/key_permissions", &[]).await?;
+        let granted = |scope: &str| data.get(scope).and_then(|v| v.as_bool()).unwrap_or(false);
+
+        Ok(ApiKeyPermissions {
+            can_read: granted("can_view"),
+            can_trade_spot: granted("can_trade"),
+            can_trade_margin: false,
+            can_trade_futures: false,
+            can_withdraw: granted("can_transfer"),
+        })
+    }
+}
+
+#[async_trait]
+impl AccountAPI for CoinbaseConnector {
+    async fn get_spot_account(&self) -> Result<SpotAccount, ExchangeError> {
+        let accounts = self.get_accounts().await?;
+
+        let mut prices = HashMap::new();
+        for currency in held_currencies(&accounts)? {
+            match self.client.get_symbol_price(&currency).await {
+                Ok(price) => {
+                    prices.insert(currency, price);
+                }
+                Err(e) => warn!("Failed to get Coinbase price for {}: {}", currency, e),
+            }
+        }
+
+        parse_accounts_from_json(&accounts, &prices)
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase has no margin account".to_string()))
+    }
+
+    async fn get_futures_account(&self, _account_type: FuturesType) -> Result<FuturesAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase futures account not yet implemented".to_string()))
+    }
+
+    async fn get_all_balances(&self) -> Result<AccountBalances, ExchangeError> {
+        let spot = self.get_spot_account().await?;
+        let total_usd_value = spot.total_usd_value.unwrap_or(Decimal::ZERO);
+
+        Ok(AccountBalances {
+            spot: Some(spot),
+            margin: None,
+            futures_usdm: None,
+            futures_coinm: None,
+            total_usd_value,
+            total_btc_value: Decimal::ZERO,
+        })
+    }
+}
+
+#[async_trait]
+impl OrderAPI for CoinbaseConnector {
+    async fn get_open_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase orders not yet implemented".to_string()))
+    }
+
+    async fn get_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase orders not yet implemented".to_string()))
+    }
+
+    async fn get_order_history(
+        &self,
+        _symbol: Option<&str>,
+        _wallet_type: WalletType,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+        _limit: Option<u32>
+    ) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_all_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase orders not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl TradeExecutionAPI for CoinbaseConnector {
+    async fn place_market_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Option<Decimal>,
+        _quote_quantity: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase trading not yet implemented".to_string()))
+    }
+
+    async fn place_limit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _price: Decimal,
+        _quantity: Decimal,
+        _time_in_force: TimeInForce,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase trading not yet implemented".to_string()))
+    }
+
+    async fn place_stop_loss_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase trading not yet implemented".to_string()))
+    }
+
+    async fn place_take_profit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase trading not yet implemented".to_string()))
+    }
+
+    async fn place_oco_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Decimal,
+        _price: Decimal,
+        _stop_price: Decimal,
+        _stop_limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<OcoOrder, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase trading not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl MarketDataAPI for CoinbaseConnector {
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker, ExchangeError> {
+        let product_id = to_coinbase_symbol(symbol)?;
+        let data = self.client
+            .public_request(&format!("/api/v3/brokerage/market/products/{}", product_id), &[])
+            .await?;
+        parse_ticker_from_json(&data, &product_id)
+    }
+
+    async fn get_order_book(&self, _symbol: &str, _limit: Option<u32>) -> Result<OrderBook, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase order book not yet implemented".to_string()))
+    }
+
+    async fn get_recent_trades(&self, _symbol: &str, _limit: Option<u32>) -> Result<Vec<Trade>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase recent trades not yet implemented".to_string()))
+    }
+
+    async fn get_klines(
+        &self,
+        _symbol: &str,
+        _interval: KlineInterval,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+        _limit: Option<u32>
+    ) -> Result<Vec<Kline>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase candles not yet implemented".to_string()))
+    }
+
+    async fn get_exchange_info(&self) -> Result<ExchangeInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase exchange info not yet implemented".to_string()))
+    }
+
+    async fn get_symbol_info(&self, _symbol: &str) -> Result<SymbolInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("Coinbase symbol info not yet implemented".to_string()))
+    }
+}
@@ -0,0 +1,223 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::exchange_connectors::{
+    Exchange, ExchangeError, SymbolRef,
+    common_types::{AssetBalance, PriceSource, SpotAccount, WalletType},
+    shared_types::Ticker,
+};
+
+use super::api_client::is_usd_asset;
+
+pub use crate::exchange_connectors::numeric::parse_decimal;
+
+fn decimal_field(json: &Value, key: &str) -> Result<Decimal, ExchangeError> {
+    match json.get(key).and_then(|v| v.as_str()) {
+        Some(value) if !value.is_empty() => parse_decimal(value),
+        _ => Ok(Decimal::ZERO),
+    }
+}
+
+/// Amount of a `{"value": "0.1", "currency": "BTC"}` balance field
+fn balance_field(json: &Value, key: &str) -> Result<Decimal, ExchangeError> {
+    match json.get(key) {
+        Some(balance) => decimal_field(balance, "value"),
+        None => Ok(Decimal::ZERO),
+    }
+}
+
+/// Convert `BTCUSD`, `BTC/USD` or an already dashed `BTC-USD` to the Coinbase product id `BTC-USD`
+pub fn to_coinbase_symbol(symbol: &str) -> Result<String, ExchangeError> {
+    SymbolRef::from_any(symbol)?.to_native(&Exchange::Coinbase)
+}
+
+/// Currencies with a non-zero balance in `GET /api/v3/brokerage/accounts` pages, each once
+pub fn held_currencies(accounts: &[Value]) -> Result<Vec<String>, ExchangeError> {
+    let mut currencies = Vec::new();
+    for account in accounts {
+        let total = balance_field(account, "available_balance")? + balance_field(account, "hold")?;
+        let currency = account.get("currency").and_then(|v| v.as_str()).unwrap_or_default().to_uppercase();
+        if !total.is_zero() && !currency.is_empty() && !currencies.contains(&currency) {
+            currencies.push(currency);
+        }
+    }
+    Ok(currencies)
+}
+
+/// Map the `accounts` of every `GET /api/v3/brokerage/accounts` page to a spot account.
+/// Accounts of the same currency are combined; `prices` holds USD prices (zero when
+/// unknown), and USD and USDC are valued at par.
+pub fn parse_accounts_from_json(
+    accounts: &[Value],
+    prices: &HashMap<String, Decimal>,
+) -> Result<SpotAccount, ExchangeError> {
+    // (available, hold) per currency, ordered so results are stable
+    let mut totals: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
+    for account in accounts {
+        let currency = account.get("currency").and_then(|v| v.as_str()).unwrap_or_default().to_uppercase();
+        if currency.is_empty() {
+            continue;
+        }
+
+        let entry = totals.entry(currency).or_insert((Decimal::ZERO, Decimal::ZERO));
+        entry.0 += balance_field(account, "available_balance")?;
+        entry.1 += balance_field(account, "hold")?;
+    }
+
+    let mut balances = Vec::new();
+    let mut total_usd_value = Decimal::ZERO;
+    let mut unpriced_assets = 0;
+    for (asset, (free, locked)) in totals {
+        let total = free + locked;
+        if total.is_zero() {
+            continue;
+        }
+
+        let (price, price_source) = if is_usd_asset(&asset) {
+            (Some(Decimal::ONE), PriceSource::StablecoinPeg)
+        } else {
+            (prices.get(&asset).copied().filter(|price| *price > Decimal::ZERO), PriceSource::ExchangeTicker)
+        };
+        let usd_value = price.map(|price| total * price);
+        match usd_value {
+            Some(value) => total_usd_value += value,
+            None => unpriced_assets += 1,
+        }
+
+        balances.push(AssetBalance {
+            asset,
+            free,
+            locked,
+            total,
+            usd_value,
+            btc_value: None,
+            wallet_type: WalletType::Spot,
+            priced: usd_value.is_some(),
+            price_source: usd_value.map(|_| price_source),
+        });
+    }
+
+    Ok(SpotAccount {
+        balances,
+        total_usd_value: Some(total_usd_value),
+        unpriced_assets,
+        total_btc_value: None,
+        maker_commission: None,
+        taker_commission: None,
+        can_trade: true,
+        can_withdraw: false,
+        can_deposit: true,
+        last_update_time: Utc::now(),
+    })
+}
+
+/// Map `GET /api/v3/brokerage/market/products/{product_id}` to a ticker reported under `symbol`
+pub fn parse_ticker_from_json(data: &Value, symbol: &str) -> Result<Ticker, ExchangeError> {
+    let last_price = decimal_field(data, "price")?;
+    if last_price.is_zero() {
+        return Err(ExchangeError::SymbolNotFound(symbol.to_string()));
+    }
+
+    // Only the 24h change is reported as a percentage; derive the change in price from it
+    let price_change_percent = decimal_field(data, "price_percentage_change_24h")?;
+    let open_factor = Decimal::ONE + price_change_percent / Decimal::from(100);
+    let price_change = if open_factor > Decimal::ZERO {
+        last_price - last_price / open_factor
+    } else {
+        Decimal::ZERO
+    };
+
+    let close_time = Utc::now();
+    Ok(Ticker {
+        symbol: symbol.to_uppercase(),
+        // Products carry neither the best bid and ask nor the 24h high and low
+        bid_price: Decimal::ZERO,
+        bid_quantity: Decimal::ZERO,
+        ask_price: Decimal::ZERO,
+        ask_quantity: Decimal::ZERO,
+        last_price,
+        price_change,
+        price_change_percent,
+        high_price: Decimal::ZERO,
+        low_price: Decimal::ZERO,
+        volume: decimal_field(data, "volume_24h")?,
+        quote_volume: decimal_field(data, "approximate_quote_24h_volume")?,
+        open_time: close_time - Duration::hours(24),
+        close_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_symbol_conversion() {
+        assert_eq!(to_coinbase_symbol("btcusd").unwrap(), "BTC-USD");
+        assert_eq!(to_coinbase_symbol("ETH-BTC").unwrap(), "ETH-BTC");
+        assert_eq!(to_coinbase_symbol("SOL/USDC").unwrap(), "SOL-USDC");
+    }
+
+    #[test]
+    fn test_accounts_are_combined_and_valued() {
+        // Recorded GET /api/v3/brokerage/accounts pages
+        let accounts = vec![
+            json!({ "uuid": "8bfc20d7-f7c6-4422-bf07-8243ca4169fe", "currency": "BTC", "type": "ACCOUNT_TYPE_CRYPTO",
+                    "available_balance": { "value": "0.45", "currency": "BTC" }, "hold": { "value": "0.05", "currency": "BTC" } }),
+            json!({ "uuid": "5f1c5e4a-8d1d-4f4b-a6d4-0b6c1fe0d3aa", "currency": "USD", "type": "ACCOUNT_TYPE_FIAT",
+                    "available_balance": { "value": "1200.5", "currency": "USD" }, "hold": { "value": "0", "currency": "USD" } }),
+            json!({ "uuid": "b3a4a0b2-3c1d-4a4b-9f77-1d3c1a2e4f55", "currency": "USDC", "type": "ACCOUNT_TYPE_CRYPTO",
+                    "available_balance": { "value": "300", "currency": "USDC" }, "hold": { "value": "0", "currency": "USDC" } }),
+            json!({ "uuid": "0c9e4f1a-7b2d-4a8e-9c3f-6e5d4c3b2a10", "currency": "ETH", "type": "ACCOUNT_TYPE_CRYPTO",
+                    "available_balance": { "value": "0", "currency": "ETH" }, "hold": { "value": "0", "currency": "ETH" } }),
+            json!({ "uuid": "e1f2a3b4-c5d6-4e7f-8a9b-0c1d2e3f4a5b", "currency": "XYZ", "type": "ACCOUNT_TYPE_CRYPTO",
+                    "available_balance": { "value": "10", "currency": "XYZ" }, "hold": { "value": "0", "currency": "XYZ" } }),
+        ];
+        assert_eq!(held_currencies(&accounts).unwrap(), vec!["BTC", "USD", "USDC", "XYZ"]);
+
+        // XYZ has no USD market, so get_symbol_price gave zero
+        let prices = HashMap::from([("BTC".to_string(), Decimal::from(42000)), ("XYZ".to_string(), Decimal::ZERO)]);
+
+        let account = parse_accounts_from_json(&accounts, &prices).unwrap();
+
+        let assets: Vec<&str> = account.balances.iter().map(|b| b.asset.as_str()).collect();
+        // Empty accounts are left out
+        assert_eq!(assets, vec!["BTC", "USD", "USDC", "XYZ"]);
+
+        let btc = &account.balances[0];
+        assert_eq!(btc.free, Decimal::from_str("0.45").unwrap());
+        assert_eq!(btc.locked, Decimal::from_str("0.05").unwrap());
+        assert_eq!(btc.usd_value, Some(Decimal::from(21000)));
+        assert!(matches!(account.balances[1].price_source, Some(PriceSource::StablecoinPeg)));
+
+        assert!(!account.balances[3].priced);
+        assert_eq!(account.unpriced_assets, 1);
+        assert_eq!(account.total_usd_value, Some(Decimal::from_str("22500.5").unwrap()));
+    }
+
+    #[test]
+    fn test_ticker_derives_the_price_change() {
+        // Recorded GET /api/v3/brokerage/market/products/BTC-USD data
+        let data = json!({
+            "product_id": "BTC-USD",
+            "price": "42000",
+            "price_percentage_change_24h": "5",
+            "volume_24h": "12345.67",
+            "approximate_quote_24h_volume": "518518140"
+        });
+
+        let ticker = parse_ticker_from_json(&data, "btc-usd").unwrap();
+
+        assert_eq!(ticker.symbol, "BTC-USD");
+        assert_eq!(ticker.last_price, Decimal::from(42000));
+        assert_eq!(ticker.price_change, Decimal::from(2000));
+        assert_eq!(ticker.volume, Decimal::from_str("12345.67").unwrap());
+
+        let delisted = json!({ "product_id": "XYZ-USD", "price": "" });
+        assert!(matches!(parse_ticker_from_json(&delisted, "XYZ-USD"), Err(ExchangeError::SymbolNotFound(_))));
+    }
+}
@@ -80,6 +80,24 @@ pub struct Order {
     pub wallet_type: WalletType,
 }
 
+/// Generic order request for all exchanges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    /// `Market` or `Limit`
+    pub order_type: OrderType,
+    /// Base asset quantity; rounded down to the symbol's step size
+    pub quantity: Option<Decimal>,
+    /// Quote asset amount, instead of `quantity`; market orders only
+    pub quote_quantity: Option<Decimal>,
+    /// Limit price; limit orders only
+    pub price: Option<Decimal>,
+    /// Limit orders default to good till canceled
+    pub time_in_force: Option<TimeInForce>,
+    pub wallet_type: WalletType,
+}
+
 /// Generic OCO order for all exchanges
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OcoOrder {
@@ -92,23 +110,46 @@ pub struct OcoOrder {
     pub orders: Vec<Order>,
 }
 
+/// Where an asset's USD valuation came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PriceSource {
+    /// Stablecoin valued 1:1 against USD
+    StablecoinPeg,
+    /// Last price from the exchange's ticker
+    ExchangeTicker,
+}
+
 /// Generic asset balance for all exchanges
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetBalance {
     pub asset: String,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub free: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub locked: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total: Decimal,
+    #[serde(with = "crate::utils::precision::usd_option")]
     pub usd_value: Option<Decimal>,
+    #[serde(with = "crate::utils::precision::crypto_option")]
     pub btc_value: Option<Decimal>,
     pub wallet_type: WalletType,
+    /// Whether `usd_value` could be determined; unpriced assets are left out of totals
+    #[serde(default)]
+    pub priced: bool,
+    pub price_source: Option<PriceSource>,
 }
 
 /// Generic spot account - all exchanges must implement this basic interface
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpotAccount {
     pub balances: Vec<AssetBalance>,
+    #[serde(with = "crate::utils::precision::usd_option")]
     pub total_usd_value: Option<Decimal>,
+    /// Number of balances that could not be priced and are missing from `total_usd_value`
+    #[serde(default)]
+    pub unpriced_assets: u32,
+    #[serde(with = "crate::utils::precision::crypto_option")]
     pub total_btc_value: Option<Decimal>,
     pub maker_commission: Option<Decimal>,
     pub taker_commission: Option<Decimal>,
@@ -122,8 +163,11 @@ pub struct SpotAccount {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarginAccount {
     pub balances: Vec<AssetBalance>,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_asset_value: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_liability_value: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_net_value: Decimal,
     pub margin_level: Option<Decimal>,
     pub margin_ratio: Option<Decimal>,
@@ -138,12 +182,19 @@ pub struct MarginAccount {
 pub struct FuturesAccount {
     pub account_type: FuturesType,
     pub balances: Vec<AssetBalance>,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_wallet_balance: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_unrealized_pnl: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_margin_balance: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub available_balance: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub max_withdraw_amount: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_initial_margin: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_maintenance_margin: Decimal,
     pub margin_ratio: Option<Decimal>,
     pub can_trade: bool,
@@ -152,6 +203,16 @@ pub struct FuturesAccount {
     pub last_update_time: DateTime<Utc>,
 }
 
+/// What an API key is allowed to do, as reported by the exchange
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyPermissions {
+    pub can_read: bool,
+    pub can_trade_spot: bool,
+    pub can_trade_margin: bool,
+    pub can_trade_futures: bool,
+    pub can_withdraw: bool,
+}
+
 /// Generic account balances - all exchanges must implement this basic interface
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountBalances {
@@ -159,6 +220,8 @@ pub struct AccountBalances {
     pub margin: Option<MarginAccount>,
     pub futures_usdm: Option<FuturesAccount>,
     pub futures_coinm: Option<FuturesAccount>,
+    #[serde(with = "crate::utils::precision::usd")]
     pub total_usd_value: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
     pub total_btc_value: Decimal,
 }
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::utils::http::HttpError;
+
 #[derive(Error, Debug)]
 pub enum ExchangeError {
     #[error("Authentication failed: {0}")]
@@ -63,6 +65,15 @@ impl From<reqwest::Error> for ExchangeError {
     }
 }
 
+impl From<HttpError> for ExchangeError {
+    fn from(err: HttpError) -> Self {
+        match err {
+            HttpError::Transport(err) => err.into(),
+            HttpError::DeadlineExceeded { .. } => ExchangeError::Timeout,
+        }
+    }
+}
+
 impl From<serde_json::Error> for ExchangeError {
     fn from(err: serde_json::Error) -> Self {
         ExchangeError::ParseError(err.to_string())
@@ -5,6 +5,10 @@ use crate::exchange_connectors::{
     ExchangeError,
     traits::{ExchangeConnector, AccountAPI, OrderAPI, TradeExecutionAPI, MarketDataAPI},
     binance::BinanceConnector,
+    okx::OkxConnector,
+    kucoin::KucoinConnector,
+    coinbase::CoinbaseConnector,
+    kraken::KrakenConnector,
 };
 
 pub trait FullExchangeAPI: ExchangeConnector + AccountAPI + OrderAPI + TradeExecutionAPI + MarketDataAPI {}
@@ -27,16 +31,20 @@ impl ExchangeFactory {
                 Err(ExchangeError::NotSupported("Bybit connector not yet implemented".to_string()))
             }
             Exchange::Coinbase => {
-                Err(ExchangeError::NotSupported("Coinbase connector not yet implemented".to_string()))
+                let connector = CoinbaseConnector::new(credentials)?;
+                Ok(Arc::new(connector))
             }
             Exchange::Kraken => {
-                Err(ExchangeError::NotSupported("Kraken connector not yet implemented".to_string()))
+                let connector = KrakenConnector::new(credentials)?;
+                Ok(Arc::new(connector))
             }
             Exchange::Kucoin => {
-                Err(ExchangeError::NotSupported("Kucoin connector not yet implemented".to_string()))
+                let connector = KucoinConnector::new(credentials)?;
+                Ok(Arc::new(connector))
             }
             Exchange::OKX => {
-                Err(ExchangeError::NotSupported("OKX connector not yet implemented".to_string()))
+                let connector = OkxConnector::new(credentials)?;
+                Ok(Arc::new(connector))
             }
         }
     }
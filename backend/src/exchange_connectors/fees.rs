@@ -0,0 +1,180 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::Exchange;
+use crate::utils::errors::AppError;
+
+/// Tier every account starts in
+pub const BASE_FEE_TIER: u8 = 0;
+
+/// Maker and taker commission, in basis points of a fill's notional
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeRates {
+    pub maker_bps: Decimal,
+    pub taker_bps: Decimal,
+}
+
+impl FeeRates {
+    pub fn new(maker_bps: Decimal, taker_bps: Decimal) -> Self {
+        Self { maker_bps, taker_bps }
+    }
+
+    /// Commission on a fill of `notional`
+    pub fn fee(&self, notional: Decimal, is_maker: bool) -> Decimal {
+        let bps = if is_maker { self.maker_bps } else { self.taker_bps };
+        notional * bps / Decimal::from(10_000)
+    }
+
+    /// Maker rate as a percentage of notional, the unit backtests use
+    pub fn maker_pct(&self) -> Decimal {
+        self.maker_bps / Decimal::from(100)
+    }
+
+    /// Taker rate as a percentage of notional
+    pub fn taker_pct(&self) -> Decimal {
+        self.taker_bps / Decimal::from(100)
+    }
+
+    fn discounted(self, discount_pct: Decimal) -> Self {
+        let factor = Decimal::ONE - discount_pct / Decimal::from(100);
+        Self::new(self.maker_bps * factor, self.taker_bps * factor)
+    }
+}
+
+/// One row of an exchange's fee schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeTier {
+    pub tier: u8,
+    /// 30-day trading volume, in USD, that qualifies for the tier
+    pub min_volume_30d: Decimal,
+    pub rates: FeeRates,
+}
+
+/// Commission tiers of an exchange, lowest tier first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    pub tiers: Vec<FeeTier>,
+    /// Percent taken off every fee paid in the exchange's own token (e.g. BNB)
+    pub token_discount_pct: Decimal,
+}
+
+impl FeeSchedule {
+    /// Binance spot VIP 0-9, with the 25% BNB discount
+    pub fn binance_spot() -> Self {
+        // (tier, 30-day USD volume, maker, taker) with rates in hundredths of a basis point
+        let tiers: [(u8, i64, i64, i64); 10] = [
+            (0, 0, 1000, 1000),
+            (1, 1_000_000, 900, 1000),
+            (2, 5_000_000, 800, 1000),
+            (3, 20_000_000, 420, 600),
+            (4, 100_000_000, 420, 540),
+            (5, 150_000_000, 360, 480),
+            (6, 400_000_000, 300, 420),
+            (7, 800_000_000, 240, 360),
+            (8, 2_000_000_000, 180, 300),
+            (9, 4_000_000_000, 120, 240),
+        ];
+
+        Self {
+            tiers: tiers
+                .iter()
+                .map(|&(tier, volume, maker, taker)| FeeTier {
+                    tier,
+                    min_volume_30d: Decimal::from(volume),
+                    rates: FeeRates::new(Decimal::new(maker, 2), Decimal::new(taker, 2)),
+                })
+                .collect(),
+            token_discount_pct: Decimal::from(25),
+        }
+    }
+
+    /// A single tier charging `rates` whatever the volume
+    pub fn flat(rates: FeeRates) -> Self {
+        Self {
+            tiers: vec![FeeTier {
+                tier: BASE_FEE_TIER,
+                min_volume_30d: Decimal::ZERO,
+                rates,
+            }],
+            token_discount_pct: Decimal::ZERO,
+        }
+    }
+
+    /// Schedule for `exchange`; exchanges without a modelled schedule pay a flat 0.1%
+    pub fn for_exchange(exchange: &Exchange) -> Self {
+        match exchange {
+            Exchange::Binance => Self::binance_spot(),
+            _ => Self::flat(FeeRates::new(Decimal::from(10), Decimal::from(10))),
+        }
+    }
+
+    /// Highest tier `volume_30d` qualifies for
+    pub fn tier_for_volume(&self, volume_30d: Decimal) -> u8 {
+        self.tiers
+            .iter()
+            .filter(|tier| volume_30d >= tier.min_volume_30d)
+            .map(|tier| tier.tier)
+            .max()
+            .unwrap_or(BASE_FEE_TIER)
+    }
+
+    pub fn max_tier(&self) -> u8 {
+        self.tiers.iter().map(|tier| tier.tier).max().unwrap_or(BASE_FEE_TIER)
+    }
+
+    /// Rates charged at `tier`, less the token discount when fees are paid in the exchange's token
+    pub fn rates(&self, tier: u8, token_discount: bool) -> Result<FeeRates, AppError> {
+        let rates = self.tiers
+            .iter()
+            .find(|t| t.tier == tier)
+            .map(|t| t.rates)
+            .ok_or_else(|| AppError::BadRequest(format!(
+                "Unknown fee tier {}; expected 0 to {}",
+                tier,
+                self.max_tier()
+            )))?;
+
+        Ok(if token_discount { rates.discounted(self.token_discount_pct) } else { rates })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_higher_tier_pays_less_for_the_same_volume() {
+        let schedule = FeeSchedule::binance_spot();
+        let notional = Decimal::from(50_000);
+
+        let base = schedule.rates(BASE_FEE_TIER, false).unwrap();
+        let vip3 = schedule.rates(3, false).unwrap();
+        assert_eq!(base.fee(notional, false), Decimal::from(50));
+        assert_eq!(vip3.fee(notional, false), Decimal::from(30));
+        assert!(vip3.fee(notional, true) < base.fee(notional, true));
+
+        // BNB discount takes a quarter off
+        let discounted = schedule.rates(3, true).unwrap();
+        assert_eq!(discounted.fee(notional, false), Decimal::new(225, 1));
+
+        let mut previous = base;
+        for tier in 1..=schedule.max_tier() {
+            let rates = schedule.rates(tier, false).unwrap();
+            assert!(rates.taker_bps <= previous.taker_bps && rates.maker_bps <= previous.maker_bps);
+            previous = rates;
+        }
+    }
+
+    #[test]
+    fn test_tier_lookup_by_volume() {
+        let schedule = FeeSchedule::binance_spot();
+        assert_eq!(schedule.tier_for_volume(Decimal::ZERO), BASE_FEE_TIER);
+        assert_eq!(schedule.tier_for_volume(Decimal::from(999_999)), 0);
+        assert_eq!(schedule.tier_for_volume(Decimal::from(25_000_000)), 3);
+        assert_eq!(schedule.tier_for_volume(Decimal::from(10_000_000_000i64)), 9);
+
+        assert_eq!(schedule.rates(3, false).unwrap().taker_pct(), Decimal::new(6, 2));
+        assert!(matches!(schedule.rates(10, false), Err(AppError::BadRequest(_))));
+        assert!(FeeSchedule::for_exchange(&Exchange::Kraken).rates(1, false).is_err());
+    }
+}
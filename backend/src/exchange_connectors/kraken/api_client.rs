@@ -0,0 +1,232 @@
+use reqwest::{Client, Method};
+use serde_json::Value;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use base64::{Engine as _, engine::general_purpose};
+use crate::exchange_connectors::{ExchangeCredentials, ExchangeError};
+use crate::utils::http::{request_with_policy, RetryPolicy};
+use crate::utils::metrics::metrics;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Last nonce sent per API key. Kraken rejects any nonce not above the key's previous one,
+/// so every client using the key in this process has to draw from the same counter.
+static LAST_NONCES: Lazy<Mutex<HashMap<String, u64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Kraken REST client. Private endpoints are POSTs carrying an ever-increasing nonce,
+/// signed with the base64-decoded API secret.
+pub struct KrakenApiClient {
+    pub client: Client,
+    pub base_url: String,
+    pub retry_policy: RetryPolicy,
+    api_key: String,
+    secret: Vec<u8>,
+}
+
+impl KrakenApiClient {
+    pub fn new(credentials: ExchangeCredentials) -> Result<Self, ExchangeError> {
+        let secret = general_purpose::STANDARD
+            .decode(credentials.api_secret.trim())
+            .map_err(|_| ExchangeError::InvalidParameter("Kraken API secret must be base64".to_string()))?;
+
+        Ok(Self {
+            client: Client::new(),
+            base_url: "https://api.kraken.com".to_string(),
+            retry_policy: RetryPolicy::default(),
+            api_key: credentials.api_key,
+            secret,
+        })
+    }
+
+    /// Millisecond timestamp, bumped past the key's last nonce when two requests share a millisecond
+    fn next_nonce(&self) -> u64 {
+        let now = Utc::now().timestamp_millis().max(0) as u64;
+        let mut last_nonces = LAST_NONCES.lock().unwrap();
+        let last = last_nonces.entry(self.api_key.clone()).or_insert(0);
+        *last = now.max(*last + 1);
+        *last
+    }
+
+    /// Base64 HMAC-SHA512 of `uri_path + SHA256(nonce + post_data)` keyed with the decoded secret
+    fn sign(&self, uri_path: &str, nonce: u64, post_data: &str) -> String {
+        let digest = Sha256::digest(format!("{}{}", nonce, post_data).as_bytes());
+        let mut mac = HmacSha512::new_from_slice(&self.secret)
+            .expect("HMAC can take key of any size");
+        mac.update(uri_path.as_bytes());
+        mac.update(&digest);
+        general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    /// Authentication headers for a private request whose form body is `post_data`
+    pub fn auth_headers(&self, uri_path: &str, nonce: u64, post_data: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("API-Key", self.api_key.clone()),
+            ("API-Sign", self.sign(uri_path, nonce, post_data)),
+        ]
+    }
+
+    /// Unauthenticated GET against a public endpoint, returning the `result` field
+    pub async fn public_request(&self, path: &str, query: &[(&str, String)]) -> Result<Value, ExchangeError> {
+        let request = self.client
+            .request(Method::GET, format!("{}{}", self.base_url, path))
+            .query(query);
+        self.send(request, &self.retry_policy).await
+    }
+
+    /// Signed POST against a private endpoint, returning the `result` field
+    pub async fn signed_request(&self, path: &str, params: &[(&str, String)]) -> Result<Value, ExchangeError> {
+        let nonce = self.next_nonce();
+        let post_data = std::iter::once(format!("nonce={}", nonce))
+            .chain(params.iter().map(|(k, v)| format!("{}={}", k, v)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let mut request = self.client
+            .request(Method::POST, format!("{}{}", self.base_url, path))
+            .header("Content-Type", "application/x-www-form-urlencoded");
+        for (name, value) in self.auth_headers(path, nonce, &post_data) {
+            request = request.header(name, value);
+        }
+
+        // A replayed nonce is rejected, so a signed request is only sent once
+        self.send(request.body(post_data), &self.retry_policy.without_retries()).await
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, policy: &RetryPolicy) -> Result<Value, ExchangeError> {
+        let response = match request_with_policy(policy, request).await {
+            Ok(response) => response,
+            Err(e) => {
+                metrics().record_exchange_error("kraken", "network");
+                return Err(e.into());
+            }
+        };
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            metrics().record_exchange_error("kraken", status.as_str());
+        }
+
+        parse_kraken_response(status.as_u16(), &text)
+    }
+}
+
+/// Unwrap Kraken's `{"error": [...], "result": ...}` envelope
+pub fn parse_kraken_response(status_code: u16, body: &str) -> Result<Value, ExchangeError> {
+    let json: Value = match serde_json::from_str(body) {
+        Ok(json) => json,
+        Err(_) => {
+            return Err(match status_code {
+                401 | 403 => ExchangeError::AuthenticationError(format!("Authentication failed: {}", body)),
+                429 => ExchangeError::RateLimitExceeded(format!("Rate limit exceeded: {}", body)),
+                503 => ExchangeError::Maintenance,
+                _ => ExchangeError::Unknown(format!("HTTP {}: {}", status_code, body)),
+            });
+        }
+    };
+
+    let first_error = json.get("error")
+        .and_then(|e| e.as_array())
+        .and_then(|errors| errors.first())
+        .and_then(|e| e.as_str());
+    let Some(error) = first_error else {
+        return Ok(json.get("result").cloned().unwrap_or(Value::Null));
+    };
+
+    // Errors read `<severity><category>:<message>`, e.g. `EAPI:Invalid key`
+    Err(match error.strip_prefix(['E', 'W']).unwrap_or(error) {
+        // Authentication errors
+        "API:Invalid key" => ExchangeError::InvalidApiKey,
+        "API:Invalid signature" => ExchangeError::AuthenticationError(format!("Invalid signature: {}", error)),
+        "API:Invalid nonce" => ExchangeError::AuthenticationError(format!("Nonce not increasing: {}", error)),
+        "General:Permission denied" => ExchangeError::AuthenticationError(format!("Access denied: {}", error)),
+
+        // Rate limiting
+        "API:Rate limit exceeded" | "General:Too many requests" => {
+            ExchangeError::RateLimitExceeded(format!("Too many requests: {}", error))
+        }
+
+        // Symbol and parameter errors
+        "Query:Unknown asset pair" => ExchangeError::SymbolNotFound(format!("Unknown asset pair: {}", error)),
+        "General:Invalid arguments" => ExchangeError::InvalidParameter(format!("Parameter error: {}", error)),
+
+        "Service:Unavailable" | "Service:Busy" => ExchangeError::Maintenance,
+
+        _ => ExchangeError::ApiError(format!("Kraken error: {}", error)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials(api_secret: &str) -> ExchangeCredentials {
+        ExchangeCredentials {
+            api_key: "kraken-key".to_string(),
+            api_secret: api_secret.to_string(),
+            passphrase: None,
+        }
+    }
+
+    #[test]
+    fn test_signed_request_headers() {
+        // Example from Kraken's REST authentication guide
+        let secret = "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==";
+        let client = KrakenApiClient::new(credentials(secret)).unwrap();
+        let post_data = "nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25";
+
+        let headers = client.auth_headers("/0/private/AddOrder", 1616492376594, post_data);
+
+        assert_eq!(
+            headers,
+            vec![
+                ("API-Key", "kraken-key".to_string()),
+                (
+                    "API-Sign",
+                    "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ==".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nonces_always_increase() {
+        let client = KrakenApiClient::new(credentials("c2VjcmV0")).unwrap();
+        let first = client.next_nonce();
+        let second = client.next_nonce();
+        assert!(second > first);
+
+        // A second client for the same key carries on from the first one's nonces
+        let other = KrakenApiClient::new(credentials("c2VjcmV0")).unwrap();
+        let mut nonces = vec![second];
+        for _ in 0..20 {
+            nonces.push(other.next_nonce());
+            nonces.push(client.next_nonce());
+        }
+        assert!(nonces.windows(2).all(|pair| pair[1] > pair[0]), "{:?}", nonces);
+    }
+
+    #[test]
+    fn test_client_requires_base64_secret() {
+        assert!(matches!(KrakenApiClient::new(credentials("not base64!")), Err(ExchangeError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_error_envelope_maps_codes() {
+        let result = parse_kraken_response(200, r#"{"error":[],"result":{"XXBT":"0.5"}}"#).unwrap();
+        assert_eq!(result["XXBT"], "0.5");
+
+        let err = parse_kraken_response(200, r#"{"error":["EAPI:Invalid key"]}"#).unwrap_err();
+        assert!(matches!(err, ExchangeError::InvalidApiKey));
+
+        let err = parse_kraken_response(200, r#"{"error":["EQuery:Unknown asset pair"]}"#).unwrap_err();
+        assert!(matches!(err, ExchangeError::SymbolNotFound(_)));
+
+        let err = parse_kraken_response(200, r#"{"error":["EAPI:Rate limit exceeded"]}"#).unwrap_err();
+        assert!(matches!(err, ExchangeError::RateLimitExceeded(_)));
+    }
+}
@@ -0,0 +1,235 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::{error, info};
+
+use crate::exchange_connectors::{
+    traits::{ExchangeConnector, AccountAPI, OrderAPI, TradeExecutionAPI, MarketDataAPI},
+    ExchangeCredentials,
+    ExchangeError,
+    common_types::{ApiKeyPermissions, SpotAccount, MarginAccount, FuturesAccount, AccountBalances, WalletType, FuturesType, OrderSide, TimeInForce, Order, OcoOrder},
+    shared_types::{Ticker, OrderBook, Trade, Kline, KlineInterval, ExchangeInfo, SymbolInfo},
+};
+
+use super::api_client::KrakenApiClient;
+use super::converters::*;
+
+/// Most candles Kraken returns per request
+const MAX_CANDLES_PER_REQUEST: usize = 720;
+
+/// Kraken connector. Covers balances and market data; trading is not implemented yet.
+pub struct KrakenConnector {
+    client: KrakenApiClient,
+}
+
+impl KrakenConnector {
+    pub fn new(credentials: ExchangeCredentials) -> Result<Self, ExchangeError> {
+        let client = KrakenApiClient::new(credentials)?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ExchangeConnector for KrakenConnector {
+    async fn test_connection(&self) -> Result<bool, ExchangeError> {
+        // Public endpoint first so an outage is not reported as bad credentials
+        self.client.public_request("/0/public/Time", &[]).await?;
+
+        match self.client.signed_request("/0/private/Balance", &[]).await {
+            Ok(_) => {
+                info!("Kraken API credentials validated successfully");
+                Ok(true)
+            }
+            Err(e) => {
+                error!("Kraken API credential validation failed: {}", e);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn get_api_permissions(&self) -> Result<ApiKeyPermissions, ExchangeError> {
+        // Kraken has no endpoint describing what a key may do
+        Err(ExchangeError::NotSupported("Kraken does not report API key permissions".to_string()))
+    }
+}
+
+#[async_trait]
+impl AccountAPI for KrakenConnector {
+    async fn get_spot_account(&self) -> Result<SpotAccount, ExchangeError> {
+        let balances = self.client.signed_request("/0/private/Balance", &[]).await?;
+        let asset_pairs = self.client.public_request("/0/public/AssetPairs", &[]).await?;
+        let tickers = self.client.public_request("/0/public/Ticker", &[]).await?;
+        parse_balances_from_json(&balances, &parse_usd_prices(&asset_pairs, &tickers)?)
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken margin account not yet implemented".to_string()))
+    }
+
+    async fn get_futures_account(&self, _account_type: FuturesType) -> Result<FuturesAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken futures account not yet implemented".to_string()))
+    }
+
+    async fn get_all_balances(&self) -> Result<AccountBalances, ExchangeError> {
+        let spot = self.get_spot_account().await?;
+        let total_usd_value = spot.total_usd_value.unwrap_or(Decimal::ZERO);
+
+        Ok(AccountBalances {
+            spot: Some(spot),
+            margin: None,
+            futures_usdm: None,
+            futures_coinm: None,
+            total_usd_value,
+            total_btc_value: Decimal::ZERO,
+        })
+    }
+}
+
+#[async_trait]
+impl OrderAPI for KrakenConnector {
+    async fn get_open_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken orders not yet implemented".to_string()))
+    }
+
+    async fn get_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken orders not yet implemented".to_string()))
+    }
+
+    async fn get_order_history(
+        &self,
+        _symbol: Option<&str>,
+        _wallet_type: WalletType,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+        _limit: Option<u32>
+    ) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_all_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken orders not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl TradeExecutionAPI for KrakenConnector {
+    async fn place_market_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Option<Decimal>,
+        _quote_quantity: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken trading not yet implemented".to_string()))
+    }
+
+    async fn place_limit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _price: Decimal,
+        _quantity: Decimal,
+        _time_in_force: TimeInForce,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken trading not yet implemented".to_string()))
+    }
+
+    async fn place_stop_loss_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken trading not yet implemented".to_string()))
+    }
+
+    async fn place_take_profit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken trading not yet implemented".to_string()))
+    }
+
+    async fn place_oco_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Decimal,
+        _price: Decimal,
+        _stop_price: Decimal,
+        _stop_limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<OcoOrder, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken trading not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl MarketDataAPI for KrakenConnector {
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker, ExchangeError> {
+        let result = self.client
+            .public_request("/0/public/Ticker", &[("pair", to_kraken_pair(symbol)?)])
+            .await?;
+        parse_ticker_from_json(&result, symbol)
+    }
+
+    async fn get_order_book(&self, _symbol: &str, _limit: Option<u32>) -> Result<OrderBook, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken order book not yet implemented".to_string()))
+    }
+
+    async fn get_recent_trades(&self, _symbol: &str, _limit: Option<u32>) -> Result<Vec<Trade>, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken recent trades not yet implemented".to_string()))
+    }
+
+    async fn get_klines(
+        &self,
+        symbol: &str,
+        interval: KlineInterval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>
+    ) -> Result<Vec<Kline>, ExchangeError> {
+        let (minutes, bar_length) = kraken_interval(&interval)?;
+
+        // Kraken takes the start in seconds and has no end or limit parameter
+        let mut query = vec![("pair", to_kraken_pair(symbol)?), ("interval", minutes.to_string())];
+        if let Some(start_time) = start_time {
+            query.push(("since", start_time.timestamp().to_string()));
+        }
+
+        let result = self.client.public_request("/0/public/OHLC", &query).await?;
+        let mut klines = parse_ohlc_from_json(&result, bar_length)?;
+        if let Some(end_time) = end_time {
+            klines.retain(|k| k.open_time <= end_time);
+        }
+
+        // Keep the most recent bars when a limit is given
+        let limit = limit.map_or(MAX_CANDLES_PER_REQUEST, |limit| (limit as usize).min(MAX_CANDLES_PER_REQUEST));
+        if klines.len() > limit {
+            klines.drain(..klines.len() - limit);
+        }
+        Ok(klines)
+    }
+
+    async fn get_exchange_info(&self) -> Result<ExchangeInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken exchange info not yet implemented".to_string()))
+    }
+
+    async fn get_symbol_info(&self, _symbol: &str) -> Result<SymbolInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("Kraken symbol info not yet implemented".to_string()))
+    }
+}
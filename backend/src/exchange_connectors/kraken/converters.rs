@@ -0,0 +1,366 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::exchange_connectors::{
+    Exchange, ExchangeError, SymbolRef,
+    common_types::{AssetBalance, PriceSource, SpotAccount, WalletType},
+    shared_types::{Kline, KlineInterval, Ticker},
+    symbols::from_kraken_asset,
+};
+
+/// Assets Kraken still names with its legacy `X` (crypto) or `Z` (fiat) prefix
+const PREFIXED_ASSETS: [&str; 17] = [
+    "XETC", "XETH", "XLTC", "XMLN", "XREP", "XXBT", "XXDG", "XXLM", "XXMR", "XXRP", "XZEC",
+    "ZAUD", "ZCAD", "ZEUR", "ZGBP", "ZJPY", "ZUSD",
+];
+
+pub use crate::exchange_connectors::numeric::parse_decimal;
+
+fn decimal_field(json: &Value, key: &str) -> Result<Decimal, ExchangeError> {
+    match json.get(key).and_then(|v| v.as_str()) {
+        Some(value) if !value.is_empty() => parse_decimal(value),
+        _ => Ok(Decimal::ZERO),
+    }
+}
+
+/// Element `index` of an array field such as the ticker's `c: [price, lot volume]`
+fn decimal_at(json: &Value, key: &str, index: usize) -> Result<Decimal, ExchangeError> {
+    match json.get(key).and_then(|v| v.get(index)).and_then(|v| v.as_str()) {
+        Some(value) if !value.is_empty() => parse_decimal(value),
+        _ => Ok(Decimal::ZERO),
+    }
+}
+
+/// Canonical code for a Kraken asset: `XXBT` is BTC, `ZUSD` is USD and `XXDG` is DOGE.
+/// Staking and earn suffixes are dropped, so `DOT.S` is DOT.
+pub fn normalize_asset(code: &str) -> String {
+    let upper = code.trim().to_uppercase();
+    let code = upper.split('.').next().unwrap_or_default();
+    let code = if PREFIXED_ASSETS.contains(&code) { &code[1..] } else { code };
+    from_kraken_asset(code)
+}
+
+/// Whether a balance key names funds that are staked or in an earn program (`DOT.S`, `USDC.M`)
+fn is_earning(code: &str) -> bool {
+    code.contains('.')
+}
+
+/// Convert `BTCUSDT`, `BTC/USDT` or `BTC-USDT` to the Kraken pair name `XBTUSDT`
+pub fn to_kraken_pair(symbol: &str) -> Result<String, ExchangeError> {
+    Ok(SymbolRef::from_any(symbol)?.to_native(&Exchange::Kraken)?.replace('/', ""))
+}
+
+/// Kraken OHLC `interval` in minutes and bar length
+pub fn kraken_interval(interval: &KlineInterval) -> Result<(u32, Duration), ExchangeError> {
+    Ok(match interval {
+        KlineInterval::OneMinute => (1, Duration::minutes(1)),
+        KlineInterval::FiveMinutes => (5, Duration::minutes(5)),
+        KlineInterval::FifteenMinutes => (15, Duration::minutes(15)),
+        KlineInterval::ThirtyMinutes => (30, Duration::minutes(30)),
+        KlineInterval::OneHour => (60, Duration::hours(1)),
+        KlineInterval::FourHours => (240, Duration::hours(4)),
+        KlineInterval::OneDay => (1440, Duration::days(1)),
+        KlineInterval::OneWeek => (10080, Duration::weeks(1)),
+        _ => return Err(ExchangeError::NotSupported(format!("Kraken has no {} candles", interval))),
+    })
+}
+
+/// USD prices by canonical asset, from the `result` of `GET /0/public/AssetPairs` and of
+/// `GET /0/public/Ticker` for every pair. Only pairs quoted in USD are used.
+pub fn parse_usd_prices(asset_pairs: &Value, tickers: &Value) -> Result<HashMap<String, Decimal>, ExchangeError> {
+    let mut prices = HashMap::new();
+    if let Some(pairs) = asset_pairs.as_object() {
+        for (pair, info) in pairs {
+            let field = |key: &str| info.get(key).and_then(|v| v.as_str()).map(normalize_asset);
+            let (Some(base), Some(quote)) = (field("base"), field("quote")) else {
+                continue;
+            };
+            if quote != "USD" {
+                continue;
+            }
+
+            if let Some(ticker) = tickers.get(pair) {
+                let price = decimal_at(ticker, "c", 0)?;
+                if price > Decimal::ZERO {
+                    prices.insert(base, price);
+                }
+            }
+        }
+    }
+    Ok(prices)
+}
+
+/// Map the `result` of `POST /0/private/Balance` (`{"XXBT": "0.5", "ZUSD": "100", ...}`) to a
+/// spot account under canonical asset codes. Staked and earn balances are added to their
+/// asset as locked funds; `prices` holds USD prices and USD itself is valued at par.
+pub fn parse_balances_from_json(
+    result: &Value,
+    prices: &HashMap<String, Decimal>,
+) -> Result<SpotAccount, ExchangeError> {
+    let entries = result.as_object()
+        .ok_or_else(|| ExchangeError::ParseError("Kraken balance response is not an object".to_string()))?;
+
+    // (free, locked) per asset, ordered so results are stable
+    let mut totals: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
+    for (code, amount) in entries {
+        let amount = parse_decimal(amount.as_str().unwrap_or("0"))?;
+        let entry = totals.entry(normalize_asset(code)).or_insert((Decimal::ZERO, Decimal::ZERO));
+        if is_earning(code) {
+            entry.1 += amount;
+        } else {
+            entry.0 += amount;
+        }
+    }
+
+    let mut balances = Vec::new();
+    let mut total_usd_value = Decimal::ZERO;
+    let mut unpriced_assets = 0;
+    for (asset, (free, locked)) in totals {
+        let total = free + locked;
+        if total.is_zero() {
+            continue;
+        }
+
+        let (price, price_source) = if asset == "USD" {
+            (Some(Decimal::ONE), PriceSource::StablecoinPeg)
+        } else {
+            (prices.get(&asset).copied().filter(|price| *price > Decimal::ZERO), PriceSource::ExchangeTicker)
+        };
+        let usd_value = price.map(|price| total * price);
+        match usd_value {
+            Some(value) => total_usd_value += value,
+            None => unpriced_assets += 1,
+        }
+
+        balances.push(AssetBalance {
+            asset,
+            free,
+            locked,
+            total,
+            usd_value,
+            btc_value: None,
+            wallet_type: WalletType::Spot,
+            priced: usd_value.is_some(),
+            price_source: usd_value.map(|_| price_source),
+        });
+    }
+
+    Ok(SpotAccount {
+        balances,
+        total_usd_value: Some(total_usd_value),
+        unpriced_assets,
+        total_btc_value: None,
+        maker_commission: None,
+        taker_commission: None,
+        can_trade: true,
+        can_withdraw: false,
+        can_deposit: true,
+        last_update_time: Utc::now(),
+    })
+}
+
+/// Map the `result` of `GET /0/public/Ticker` for one pair to a ticker reported under `symbol`
+pub fn parse_ticker_from_json(result: &Value, symbol: &str) -> Result<Ticker, ExchangeError> {
+    // The result is keyed by Kraken's own pair name (`XXBTZUSD`), not the one requested
+    let ticker = result.as_object()
+        .and_then(|pairs| pairs.values().next())
+        .ok_or_else(|| ExchangeError::SymbolNotFound(symbol.to_string()))?;
+
+    let last_price = decimal_at(ticker, "c", 0)?;
+    if last_price.is_zero() {
+        return Err(ExchangeError::SymbolNotFound(symbol.to_string()));
+    }
+
+    // Change is against today's open (00:00 UTC); volumes are over the last 24 hours
+    let open_price = decimal_field(ticker, "o")?;
+    let price_change = if open_price > Decimal::ZERO { last_price - open_price } else { Decimal::ZERO };
+    let price_change_percent = if open_price > Decimal::ZERO {
+        price_change / open_price * Decimal::from(100)
+    } else {
+        Decimal::ZERO
+    };
+    let volume = decimal_at(ticker, "v", 1)?;
+    let close_time = Utc::now();
+
+    Ok(Ticker {
+        symbol: symbol.to_uppercase(),
+        bid_price: decimal_at(ticker, "b", 0)?,
+        bid_quantity: decimal_at(ticker, "b", 2)?,
+        ask_price: decimal_at(ticker, "a", 0)?,
+        ask_quantity: decimal_at(ticker, "a", 2)?,
+        last_price,
+        price_change,
+        price_change_percent,
+        high_price: decimal_at(ticker, "h", 1)?,
+        low_price: decimal_at(ticker, "l", 1)?,
+        volume,
+        quote_volume: volume * decimal_at(ticker, "p", 1)?,
+        open_time: close_time - Duration::hours(24),
+        close_time,
+    })
+}
+
+/// Map the `result` of `GET /0/public/OHLC` (rows of time in seconds, open, high, low,
+/// close, vwap, volume, count under the pair name, oldest first) to klines
+pub fn parse_ohlc_from_json(result: &Value, bar_length: Duration) -> Result<Vec<Kline>, ExchangeError> {
+    // Besides the pair's rows the result carries `last`, the id to poll from next
+    let rows = result.as_object()
+        .and_then(|fields| fields.iter().find(|(key, _)| key.as_str() != "last"))
+        .and_then(|(_, rows)| rows.as_array())
+        .ok_or_else(|| ExchangeError::ParseError("Kraken OHLC response has no candles".to_string()))?;
+
+    let mut klines = Vec::with_capacity(rows.len());
+    for row in rows {
+        let fields = row.as_array()
+            .filter(|fields| fields.len() >= 8)
+            .ok_or_else(|| ExchangeError::ParseError(format!("Malformed Kraken candle: {}", row)))?;
+        let text = |index: usize| fields[index].as_str().unwrap_or_default();
+
+        let open_time: DateTime<Utc> = fields[0].as_i64()
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+            .ok_or_else(|| ExchangeError::ParseError(format!("Invalid candle time: {}", fields[0])))?;
+        let volume = parse_decimal(text(6))?;
+
+        klines.push(Kline {
+            open_time,
+            close_time: open_time + bar_length - Duration::milliseconds(1),
+            open: parse_decimal(text(1))?,
+            high: parse_decimal(text(2))?,
+            low: parse_decimal(text(3))?,
+            close: parse_decimal(text(4))?,
+            volume,
+            quote_asset_volume: volume * parse_decimal(text(5))?,
+            number_of_trades: fields[7].as_i64().unwrap_or_default(),
+            // Kraken does not report taker volumes on candles
+            taker_buy_base_asset_volume: Decimal::ZERO,
+            taker_buy_quote_asset_volume: Decimal::ZERO,
+        });
+    }
+
+    klines.sort_by_key(|k| k.open_time);
+    Ok(klines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_asset_names_are_normalized() {
+        assert_eq!(normalize_asset("XXBT"), "BTC");
+        assert_eq!(normalize_asset("XETH"), "ETH");
+        assert_eq!(normalize_asset("XXDG"), "DOGE");
+        assert_eq!(normalize_asset("ZUSD"), "USD");
+        assert_eq!(normalize_asset("ZEUR"), "EUR");
+        assert_eq!(normalize_asset("DOT.S"), "DOT");
+        // Newer listings use plain codes, some of which start with X or Z
+        assert_eq!(normalize_asset("USDT"), "USDT");
+        assert_eq!(normalize_asset("XTZ"), "XTZ");
+        assert_eq!(normalize_asset("ZRX"), "ZRX");
+
+        assert_eq!(to_kraken_pair("BTCUSD").unwrap(), "XBTUSD");
+        assert_eq!(to_kraken_pair("ETH/USDT").unwrap(), "ETHUSDT");
+    }
+
+    #[test]
+    fn test_balances_are_normalized_and_valued() {
+        // Recorded POST /0/private/Balance result
+        let balances = json!({
+            "XXBT": "0.5",
+            "XETH": "2.0000000000",
+            "ZUSD": "150.25",
+            "DOT": "10",
+            "DOT.S": "5",
+            "XXDG": "0.0000000000",
+            "NEWCOIN": "3"
+        });
+        // Recorded GET /0/public/AssetPairs and /0/public/Ticker results, trimmed
+        let asset_pairs = json!({
+            "XXBTZUSD": { "altname": "XBTUSD", "base": "XXBT", "quote": "ZUSD" },
+            "XXBTZEUR": { "altname": "XBTEUR", "base": "XXBT", "quote": "ZEUR" },
+            "XETHZUSD": { "altname": "ETHUSD", "base": "XETH", "quote": "ZUSD" },
+            "DOTUSD": { "altname": "DOTUSD", "base": "DOT", "quote": "ZUSD" }
+        });
+        let tickers = json!({
+            "XXBTZUSD": { "c": ["42000.00000", "0.00100000"] },
+            "XXBTZEUR": { "c": ["38500.00000", "0.00100000"] },
+            "XETHZUSD": { "c": ["2500.00", "0.05"] },
+            "DOTUSD": { "c": ["7.0000", "1.2"] }
+        });
+        let prices = parse_usd_prices(&asset_pairs, &tickers).unwrap();
+        assert_eq!(prices.get("BTC"), Some(&Decimal::from(42000)));
+
+        let account = parse_balances_from_json(&balances, &prices).unwrap();
+
+        let assets: Vec<&str> = account.balances.iter().map(|b| b.asset.as_str()).collect();
+        assert_eq!(assets, vec!["BTC", "DOT", "ETH", "NEWCOIN", "USD"]);
+
+        let dot = &account.balances[1];
+        assert_eq!(dot.free, Decimal::from(10));
+        // Staked DOT counts towards the balance but can't be traded
+        assert_eq!(dot.locked, Decimal::from(5));
+        assert_eq!(dot.usd_value, Some(Decimal::from(105)));
+
+        assert_eq!(account.balances[4].price_source, Some(PriceSource::StablecoinPeg));
+        assert!(!account.balances[3].priced);
+        assert_eq!(account.unpriced_assets, 1);
+        // 21000 BTC + 5000 ETH + 105 DOT + 150.25 USD
+        assert_eq!(account.total_usd_value, Some(Decimal::from_str("26255.25").unwrap()));
+    }
+
+    #[test]
+    fn test_ticker_reads_array_fields() {
+        // Recorded GET /0/public/Ticker?pair=XBTUSD result
+        let result = json!({
+            "XXBTZUSD": {
+                "a": ["42010.10000", "1", "1.000"],
+                "b": ["42010.00000", "2", "2.500"],
+                "c": ["42010.10000", "0.00100000"],
+                "v": ["1200.5", "2400.0"],
+                "p": ["41900.0", "41800.0"],
+                "t": [12000, 24000],
+                "l": ["41500.0", "41000.0"],
+                "h": ["42200.0", "42500.0"],
+                "o": "41010.10000"
+            }
+        });
+
+        let ticker = parse_ticker_from_json(&result, "BTCUSD").unwrap();
+
+        assert_eq!(ticker.last_price, Decimal::from_str("42010.1").unwrap());
+        assert_eq!(ticker.bid_quantity, Decimal::from_str("2.5").unwrap());
+        assert_eq!(ticker.price_change, Decimal::from(1000));
+        assert_eq!(ticker.high_price, Decimal::from(42500));
+        assert_eq!(ticker.volume, Decimal::from(2400));
+
+        assert!(matches!(parse_ticker_from_json(&json!({}), "BTCUSD"), Err(ExchangeError::SymbolNotFound(_))));
+    }
+
+    #[test]
+    fn test_ohlc_uses_kraken_field_order() {
+        // Recorded GET /0/public/OHLC?pair=XBTUSD&interval=60 result
+        let result = json!({
+            "XXBTZUSD": [
+                [1705467600, "42300.0", "42550.0", "42250.0", "42500.0", "42400.0", "98.2", 1520],
+                [1705471200, "42500.0", "42700.0", "42400.0", "42625.0", "42550.0", "120.5", 1730]
+            ],
+            "last": 1705471200
+        });
+        let (_, bar_length) = kraken_interval(&KlineInterval::OneHour).unwrap();
+
+        let klines = parse_ohlc_from_json(&result, bar_length).unwrap();
+
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0].open_time.timestamp(), 1705467600);
+        assert_eq!(klines[0].open, Decimal::from(42300));
+        assert_eq!(klines[0].high, Decimal::from(42550));
+        assert_eq!(klines[0].low, Decimal::from(42250));
+        assert_eq!(klines[0].close, Decimal::from(42500));
+        assert_eq!(klines[1].number_of_trades, 1730);
+        assert!(kraken_interval(&KlineInterval::TwoHours).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod connector;
+mod api_client;
+mod converters;
+
+pub use connector::KrakenConnector;
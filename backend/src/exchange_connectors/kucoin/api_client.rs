@@ -0,0 +1,217 @@
+use reqwest::{Client, Method};
+use serde_json::Value;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use base64::{Engine as _, engine::general_purpose};
+use crate::exchange_connectors::{ExchangeCredentials, ExchangeError};
+use crate::utils::http::{request_with_policy, RetryPolicy};
+use crate::utils::metrics::metrics;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// KuCoin's success code in the response envelope
+const SUCCESS_CODE: &str = "200000";
+
+/// KuCoin REST client. Private endpoints are signed with the API secret and carry the
+/// key's passphrase, itself signed with the secret as required by v2 API keys.
+pub struct KucoinApiClient {
+    pub client: Client,
+    pub base_url: String,
+    pub retry_policy: RetryPolicy,
+    credentials: ExchangeCredentials,
+    passphrase: String,
+}
+
+impl KucoinApiClient {
+    pub fn new(credentials: ExchangeCredentials) -> Result<Self, ExchangeError> {
+        let passphrase = credentials.passphrase.clone()
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| ExchangeError::InvalidParameter("KuCoin API keys require a passphrase".to_string()))?;
+
+        Ok(Self {
+            client: Client::new(),
+            base_url: "https://api.kucoin.com".to_string(),
+            // KuCoin rejects a signed request 5 seconds after its timestamp
+            retry_policy: RetryPolicy {
+                base_delay: Duration::from_millis(250),
+                deadline: Some(Duration::from_secs(5)),
+                ..RetryPolicy::default()
+            },
+            credentials,
+            passphrase,
+        })
+    }
+
+    /// Base64 HMAC-SHA256 of `payload` keyed with the API secret
+    fn sign(&self, payload: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.credentials.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(payload.as_bytes());
+        general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    /// Authentication headers for a private request. `endpoint` includes the query string.
+    pub fn auth_headers(
+        &self,
+        method: &Method,
+        endpoint: &str,
+        body: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let timestamp = now.timestamp_millis().to_string();
+        let signature = self.sign(&format!("{}{}{}{}", timestamp, method.as_str(), endpoint, body));
+
+        vec![
+            ("KC-API-KEY", self.credentials.api_key.clone()),
+            ("KC-API-SIGN", signature),
+            ("KC-API-TIMESTAMP", timestamp),
+            ("KC-API-PASSPHRASE", self.sign(&self.passphrase)),
+            ("KC-API-KEY-VERSION", "2".to_string()),
+        ]
+    }
+
+    /// Unauthenticated GET against a public endpoint, returning the `data` field
+    pub async fn public_request(&self, path: &str, query: &[(&str, String)]) -> Result<Value, ExchangeError> {
+        self.send(Method::GET, &endpoint(path, query), false).await
+    }
+
+    /// Signed GET against a private endpoint, returning the `data` field
+    pub async fn signed_request(&self, path: &str, query: &[(&str, String)]) -> Result<Value, ExchangeError> {
+        self.send(Method::GET, &endpoint(path, query), true).await
+    }
+
+    async fn send(&self, method: Method, endpoint: &str, signed: bool) -> Result<Value, ExchangeError> {
+        let mut request = self.client
+            .request(method.clone(), format!("{}{}", self.base_url, endpoint))
+            .header("Content-Type", "application/json");
+
+        if signed {
+            for (name, value) in self.auth_headers(&method, endpoint, "", Utc::now()) {
+                request = request.header(name, value);
+            }
+        }
+
+        let response = match request_with_policy(&self.retry_policy, request).await {
+            Ok(response) => response,
+            Err(e) => {
+                metrics().record_exchange_error("kucoin", "network");
+                return Err(e.into());
+            }
+        };
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            metrics().record_exchange_error("kucoin", status.as_str());
+        }
+
+        parse_kucoin_response(status.as_u16(), &text)
+    }
+}
+
+/// Path plus query string, exactly as it is signed and sent
+pub fn endpoint(path: &str, query: &[(&str, String)]) -> String {
+    if query.is_empty() {
+        return path.to_string();
+    }
+
+    let query_string = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{}", path, query_string)
+}
+
+/// Unwrap KuCoin's `{"code": "200000", "data": ...}` envelope
+pub fn parse_kucoin_response(status_code: u16, body: &str) -> Result<Value, ExchangeError> {
+    let json: Value = match serde_json::from_str(body) {
+        Ok(json) => json,
+        Err(_) => {
+            return Err(match status_code {
+                401 | 403 => ExchangeError::AuthenticationError(format!("Authentication failed: {}", body)),
+                429 => ExchangeError::RateLimitExceeded(format!("Rate limit exceeded: {}", body)),
+                503 => ExchangeError::Maintenance,
+                _ => ExchangeError::Unknown(format!("HTTP {}: {}", status_code, body)),
+            });
+        }
+    };
+
+    let code = json.get("code").and_then(|c| c.as_str()).unwrap_or_default();
+    if code == SUCCESS_CODE {
+        return Ok(json.get("data").cloned().unwrap_or(Value::Null));
+    }
+
+    let msg = json.get("msg").and_then(|m| m.as_str()).unwrap_or("Unknown error").to_string();
+    Err(match code {
+        // Authentication errors
+        "400003" | "400006" => ExchangeError::InvalidApiKey,
+        "400004" => ExchangeError::AuthenticationError(format!("Invalid API passphrase: {}", msg)),
+        "400005" => ExchangeError::AuthenticationError(format!("Invalid signature: {}", msg)),
+        "400002" => ExchangeError::AuthenticationError(format!("Timestamp out of window: {}", msg)),
+        "400007" => ExchangeError::AuthenticationError(format!("Access denied: {}", msg)),
+
+        // Rate limiting
+        "429000" => ExchangeError::RateLimitExceeded(format!("Too many requests: {}", msg)),
+
+        // Symbol and parameter errors
+        "900001" => ExchangeError::SymbolNotFound(format!("Symbol does not exist: {}", msg)),
+        "400100" => ExchangeError::InvalidParameter(format!("Parameter error: {}", msg)),
+
+        _ => ExchangeError::ApiError(format!("KuCoin error {}: {}", code, msg)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn credentials(passphrase: Option<&str>) -> ExchangeCredentials {
+        ExchangeCredentials {
+            api_key: "kucoin-key".to_string(),
+            api_secret: "kucoin-secret".to_string(),
+            passphrase: passphrase.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_signed_request_headers() {
+        let client = KucoinApiClient::new(credentials(Some("kucoin-passphrase"))).unwrap();
+        let now = Utc.timestamp_millis_opt(1705474164160).unwrap();
+        let path = endpoint("/api/v1/accounts", &[("type", "trade".to_string())]);
+
+        let headers = client.auth_headers(&Method::GET, &path, "", now);
+
+        assert_eq!(
+            headers,
+            vec![
+                ("KC-API-KEY", "kucoin-key".to_string()),
+                ("KC-API-SIGN", "LfZyC105yL7SJnXDIjo3dBzznFcZlo6HjIgvZDsGlcY=".to_string()),
+                ("KC-API-TIMESTAMP", "1705474164160".to_string()),
+                // v2 keys send the passphrase signed with the secret, never in plain text
+                ("KC-API-PASSPHRASE", "yBS0D3ATXupjwr4u/rQER6aiYjQSdX5r3qULPp/2shE=".to_string()),
+                ("KC-API-KEY-VERSION", "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_client_requires_passphrase() {
+        assert!(matches!(KucoinApiClient::new(credentials(None)), Err(ExchangeError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_error_envelope_maps_codes() {
+        let data = parse_kucoin_response(200, r#"{"code":"200000","data":{"BTC":"42000"}}"#).unwrap();
+        assert_eq!(data["BTC"], "42000");
+
+        let err = parse_kucoin_response(401, r#"{"code":"400004","msg":"Invalid KC-API-PASSPHRASE"}"#).unwrap_err();
+        assert!(matches!(err, ExchangeError::AuthenticationError(_)));
+
+        let err = parse_kucoin_response(429, r#"{"code":"429000","msg":"Too Many Requests"}"#).unwrap_err();
+        assert!(matches!(err, ExchangeError::RateLimitExceeded(_)));
+    }
+}
@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::{error, info};
+
+use crate::exchange_connectors::{
+    traits::{ExchangeConnector, AccountAPI, OrderAPI, TradeExecutionAPI, MarketDataAPI},
+    ExchangeCredentials,
+    ExchangeError,
+    common_types::{ApiKeyPermissions, SpotAccount, MarginAccount, FuturesAccount, AccountBalances, WalletType, FuturesType, OrderSide, TimeInForce, Order, OcoOrder},
+    shared_types::{Ticker, OrderBook, Trade, Kline, KlineInterval, ExchangeInfo, SymbolInfo},
+};
+
+use super::api_client::KucoinApiClient;
+use super::converters::*;
+
+/// Most candles KuCoin returns per request
+const MAX_CANDLES_PER_REQUEST: usize = 1500;
+
+/// KuCoin connector. Covers balances and market data; trading is not implemented yet.
+pub struct KucoinConnector {
+    client: KucoinApiClient,
+}
+
+impl KucoinConnector {
+    pub fn new(credentials: ExchangeCredentials) -> Result<Self, ExchangeError> {
+        let client = KucoinApiClient::new(credentials)?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ExchangeConnector for KucoinConnector {
+    async fn test_connection(&self) -> Result<bool, ExchangeError> {
+        // Public endpoint first so an outage is not reported as bad credentials
+        self.client.public_request("/api/v1/timestamp", &[]).await?;
+
+        match self.client.signed_request("/api/v1/accounts", &[]).await {
+            Ok(_) => {
+                info!("KuCoin API credentials validated successfully");
+                Ok(true)
+            }
+            Err(e) => {
+                error!("KuCoin API credential validation failed: {}", e);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn get_api_permissions(&self) -> Result<ApiKeyPermissions, ExchangeError> {
+        let data = self.client.signed_request("/api/v1/user/api-key", &[]).await?;
+        let permission = data.get("permission").and_then(|v| v.as_str()).unwrap_or_default();
+        let scopes: Vec<&str> = permission.split(',').map(str::trim).collect();
+
+        Ok(ApiKeyPermissions {
+            can_read: scopes.contains(&"General"),
+            can_trade_spot: scopes.contains(&"Spot"),
+            can_trade_margin: scopes.contains(&"Margin"),
+            can_trade_futures: scopes.contains(&"Futures"),
+            can_withdraw: scopes.contains(&"Withdrawal"),
+        })
+    }
+}
+
+#[async_trait]
+impl AccountAPI for KucoinConnector {
+    async fn get_spot_account(&self) -> Result<SpotAccount, ExchangeError> {
+        let accounts = self.client.signed_request("/api/v1/accounts", &[]).await?;
+        let prices = self.client
+            .public_request("/api/v1/prices", &[("base", "USD".to_string())])
+            .await?;
+        parse_accounts_from_json(&accounts, &parse_prices_from_json(&prices)?)
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin margin account not yet implemented".to_string()))
+    }
+
+    async fn get_futures_account(&self, _account_type: FuturesType) -> Result<FuturesAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin futures account not yet implemented".to_string()))
+    }
+
+    async fn get_all_balances(&self) -> Result<AccountBalances, ExchangeError> {
+        let spot = self.get_spot_account().await?;
+        let total_usd_value = spot.total_usd_value.unwrap_or(Decimal::ZERO);
+
+        Ok(AccountBalances {
+            spot: Some(spot),
+            margin: None,
+            futures_usdm: None,
+            futures_coinm: None,
+            total_usd_value,
+            total_btc_value: Decimal::ZERO,
+        })
+    }
+}
+
+#[async_trait]
+impl OrderAPI for KucoinConnector {
+    async fn get_open_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin orders not yet implemented".to_string()))
+    }
+
+    async fn get_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin orders not yet implemented".to_string()))
+    }
+
+    async fn get_order_history(
+        &self,
+        _symbol: Option<&str>,
+        _wallet_type: WalletType,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+        _limit: Option<u32>
+    ) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_all_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin orders not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl TradeExecutionAPI for KucoinConnector {
+    async fn place_market_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Option<Decimal>,
+        _quote_quantity: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin trading not yet implemented".to_string()))
+    }
+
+    async fn place_limit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _price: Decimal,
+        _quantity: Decimal,
+        _time_in_force: TimeInForce,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin trading not yet implemented".to_string()))
+    }
+
+    async fn place_stop_loss_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin trading not yet implemented".to_string()))
+    }
+
+    async fn place_take_profit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin trading not yet implemented".to_string()))
+    }
+
+    async fn place_oco_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Decimal,
+        _price: Decimal,
+        _stop_price: Decimal,
+        _stop_limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<OcoOrder, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin trading not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl MarketDataAPI for KucoinConnector {
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker, ExchangeError> {
+        let data = self.client
+            .public_request("/api/v1/market/stats", &[("symbol", to_kucoin_symbol(symbol)?)])
+            .await?;
+        parse_ticker_from_json(&data, symbol)
+    }
+
+    async fn get_order_book(&self, _symbol: &str, _limit: Option<u32>) -> Result<OrderBook, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin order book not yet implemented".to_string()))
+    }
+
+    async fn get_recent_trades(&self, _symbol: &str, _limit: Option<u32>) -> Result<Vec<Trade>, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin recent trades not yet implemented".to_string()))
+    }
+
+    async fn get_klines(
+        &self,
+        symbol: &str,
+        interval: KlineInterval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>
+    ) -> Result<Vec<Kline>, ExchangeError> {
+        let (candle_type, bar_length) = kucoin_candle_type(&interval)?;
+
+        // KuCoin takes times in seconds and has no limit parameter
+        let mut query = vec![("type", candle_type.to_string()), ("symbol", to_kucoin_symbol(symbol)?)];
+        if let Some(start_time) = start_time {
+            query.push(("startAt", start_time.timestamp().to_string()));
+        }
+        if let Some(end_time) = end_time {
+            query.push(("endAt", end_time.timestamp().to_string()));
+        }
+
+        let data = self.client.public_request("/api/v1/market/candles", &query).await?;
+        let mut klines = parse_candles_from_json(&data, bar_length)?;
+
+        // Keep the most recent bars when a limit is given
+        let limit = limit.map_or(MAX_CANDLES_PER_REQUEST, |limit| (limit as usize).min(MAX_CANDLES_PER_REQUEST));
+        if klines.len() > limit {
+            klines.drain(..klines.len() - limit);
+        }
+        Ok(klines)
+    }
+
+    async fn get_exchange_info(&self) -> Result<ExchangeInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin exchange info not yet implemented".to_string()))
+    }
+
+    async fn get_symbol_info(&self, _symbol: &str) -> Result<SymbolInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("KuCoin symbol info not yet implemented".to_string()))
+    }
+}
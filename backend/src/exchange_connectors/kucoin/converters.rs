@@ -0,0 +1,263 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::exchange_connectors::{
+    Exchange, ExchangeError, SymbolRef,
+    common_types::{AssetBalance, PriceSource, SpotAccount, WalletType},
+    shared_types::{Kline, KlineInterval, Ticker},
+};
+
+/// Account types that hold spot funds: `main` (funding) and `trade`
+const SPOT_ACCOUNT_TYPES: [&str; 2] = ["main", "trade"];
+
+pub use crate::exchange_connectors::numeric::parse_decimal;
+
+fn decimal_field(json: &Value, key: &str) -> Result<Decimal, ExchangeError> {
+    match json.get(key).and_then(|v| v.as_str()) {
+        Some(value) if !value.is_empty() => parse_decimal(value),
+        _ => Ok(Decimal::ZERO),
+    }
+}
+
+/// Convert `BTCUSDT`, `BTC/USDT` or an already dashed `BTC-USDT` to the KuCoin symbol `BTC-USDT`
+pub fn to_kucoin_symbol(symbol: &str) -> Result<String, ExchangeError> {
+    SymbolRef::from_any(symbol)?.to_native(&Exchange::Kucoin)
+}
+
+/// KuCoin candle `type` value and bar length
+pub fn kucoin_candle_type(interval: &KlineInterval) -> Result<(&'static str, Duration), ExchangeError> {
+    Ok(match interval {
+        KlineInterval::OneMinute => ("1min", Duration::minutes(1)),
+        KlineInterval::ThreeMinutes => ("3min", Duration::minutes(3)),
+        KlineInterval::FiveMinutes => ("5min", Duration::minutes(5)),
+        KlineInterval::FifteenMinutes => ("15min", Duration::minutes(15)),
+        KlineInterval::ThirtyMinutes => ("30min", Duration::minutes(30)),
+        KlineInterval::OneHour => ("1hour", Duration::hours(1)),
+        KlineInterval::TwoHours => ("2hour", Duration::hours(2)),
+        KlineInterval::FourHours => ("4hour", Duration::hours(4)),
+        KlineInterval::SixHours => ("6hour", Duration::hours(6)),
+        KlineInterval::EightHours => ("8hour", Duration::hours(8)),
+        KlineInterval::TwelveHours => ("12hour", Duration::hours(12)),
+        KlineInterval::OneDay => ("1day", Duration::days(1)),
+        KlineInterval::OneWeek => ("1week", Duration::weeks(1)),
+        KlineInterval::OneMonth => ("1month", Duration::days(30)),
+        KlineInterval::OneSecond | KlineInterval::ThreeDays => {
+            return Err(ExchangeError::NotSupported(format!("KuCoin has no {} candles", interval)));
+        }
+    })
+}
+
+/// Parse the `data` of `GET /api/v1/prices` (`{"BTC": "42000.1", ...}`) into USD prices
+pub fn parse_prices_from_json(data: &Value) -> Result<HashMap<String, Decimal>, ExchangeError> {
+    let mut prices = HashMap::new();
+    if let Some(map) = data.as_object() {
+        for (currency, price) in map {
+            if let Some(price) = price.as_str() {
+                prices.insert(currency.to_uppercase(), parse_decimal(price)?);
+            }
+        }
+    }
+    Ok(prices)
+}
+
+/// Map the `data` of `GET /api/v1/accounts` to a spot account. Funding (`main`) and
+/// trading balances of the same currency are combined; `prices` holds USD prices.
+pub fn parse_accounts_from_json(
+    data: &Value,
+    prices: &HashMap<String, Decimal>,
+) -> Result<SpotAccount, ExchangeError> {
+    let accounts = data.as_array()
+        .ok_or_else(|| ExchangeError::ParseError("KuCoin accounts response is not an array".to_string()))?;
+
+    // (available, holds) per currency, ordered so results are stable
+    let mut totals: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
+    for account in accounts {
+        let account_type = account.get("type").and_then(|v| v.as_str()).unwrap_or_default();
+        if !SPOT_ACCOUNT_TYPES.contains(&account_type) {
+            continue;
+        }
+
+        let currency = account.get("currency").and_then(|v| v.as_str()).unwrap_or_default().to_uppercase();
+        let entry = totals.entry(currency).or_insert((Decimal::ZERO, Decimal::ZERO));
+        entry.0 += decimal_field(account, "available")?;
+        entry.1 += decimal_field(account, "holds")?;
+    }
+
+    let mut balances = Vec::new();
+    let mut total_usd_value = Decimal::ZERO;
+    let mut unpriced_assets = 0;
+    for (asset, (free, locked)) in totals {
+        let total = free + locked;
+        if total.is_zero() {
+            continue;
+        }
+
+        let usd_value = prices.get(&asset).filter(|price| **price > Decimal::ZERO).map(|price| total * price);
+        match usd_value {
+            Some(value) => total_usd_value += value,
+            None => unpriced_assets += 1,
+        }
+
+        balances.push(AssetBalance {
+            asset,
+            free,
+            locked,
+            total,
+            usd_value,
+            btc_value: None,
+            wallet_type: WalletType::Spot,
+            priced: usd_value.is_some(),
+            price_source: usd_value.map(|_| PriceSource::ExchangeTicker),
+        });
+    }
+
+    Ok(SpotAccount {
+        balances,
+        total_usd_value: Some(total_usd_value),
+        unpriced_assets,
+        total_btc_value: None,
+        maker_commission: None,
+        taker_commission: None,
+        can_trade: true,
+        can_withdraw: false,
+        can_deposit: true,
+        last_update_time: Utc::now(),
+    })
+}
+
+/// Map the `data` of `GET /api/v1/market/stats` to a ticker reported under `symbol`
+pub fn parse_ticker_from_json(data: &Value, symbol: &str) -> Result<Ticker, ExchangeError> {
+    let last_price = decimal_field(data, "last")?;
+    if last_price.is_zero() {
+        return Err(ExchangeError::SymbolNotFound(symbol.to_string()));
+    }
+
+    let close_time = data.get("time")
+        .and_then(|v| v.as_i64())
+        .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+        .unwrap_or_else(Utc::now);
+
+    Ok(Ticker {
+        symbol: symbol.to_uppercase(),
+        bid_price: decimal_field(data, "buy")?,
+        // The 24h stats carry best prices but not their sizes
+        bid_quantity: Decimal::ZERO,
+        ask_price: decimal_field(data, "sell")?,
+        ask_quantity: Decimal::ZERO,
+        last_price,
+        price_change: decimal_field(data, "changePrice")?,
+        price_change_percent: decimal_field(data, "changeRate")? * Decimal::from(100),
+        high_price: decimal_field(data, "high")?,
+        low_price: decimal_field(data, "low")?,
+        volume: decimal_field(data, "vol")?,
+        quote_volume: decimal_field(data, "volValue")?,
+        open_time: close_time - Duration::hours(24),
+        close_time,
+    })
+}
+
+/// Map the `data` of `GET /api/v1/market/candles` (newest first, times in seconds,
+/// fields ordered open, close, high, low) to klines, oldest first
+pub fn parse_candles_from_json(data: &Value, bar_length: Duration) -> Result<Vec<Kline>, ExchangeError> {
+    let rows = data.as_array()
+        .ok_or_else(|| ExchangeError::ParseError("KuCoin candles response is not an array".to_string()))?;
+
+    let mut klines = Vec::with_capacity(rows.len());
+    for row in rows {
+        let fields: Vec<&str> = row.as_array()
+            .map(|fields| fields.iter().filter_map(|f| f.as_str()).collect())
+            .unwrap_or_default();
+        if fields.len() < 7 {
+            return Err(ExchangeError::ParseError(format!("Malformed KuCoin candle: {}", row)));
+        }
+
+        let open_time: DateTime<Utc> = fields[0].parse::<i64>()
+            .ok()
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+            .ok_or_else(|| ExchangeError::ParseError(format!("Invalid candle time: {}", fields[0])))?;
+
+        klines.push(Kline {
+            open_time,
+            close_time: open_time + bar_length - Duration::milliseconds(1),
+            open: parse_decimal(fields[1])?,
+            close: parse_decimal(fields[2])?,
+            high: parse_decimal(fields[3])?,
+            low: parse_decimal(fields[4])?,
+            volume: parse_decimal(fields[5])?,
+            quote_asset_volume: parse_decimal(fields[6])?,
+            // KuCoin does not report trade counts or taker volumes on candles
+            number_of_trades: 0,
+            taker_buy_base_asset_volume: Decimal::ZERO,
+            taker_buy_quote_asset_volume: Decimal::ZERO,
+        });
+    }
+
+    klines.sort_by_key(|k| k.open_time);
+    Ok(klines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_symbol_conversion() {
+        assert_eq!(to_kucoin_symbol("btcusdt").unwrap(), "BTC-USDT");
+        assert_eq!(to_kucoin_symbol("ETH-BTC").unwrap(), "ETH-BTC");
+        assert_eq!(to_kucoin_symbol("ETH/BTC").unwrap(), "ETH-BTC");
+        assert!(to_kucoin_symbol("DOGE").is_err());
+    }
+
+    #[test]
+    fn test_accounts_are_combined_and_valued() {
+        // Recorded GET /api/v1/accounts data
+        let accounts = json!([
+            { "id": "5bd6e9286d99522a52e458de", "currency": "BTC", "type": "main", "balance": "0.2", "available": "0.2", "holds": "0" },
+            { "id": "5bd6e9216d99522a52e458d6", "currency": "BTC", "type": "trade", "balance": "0.3", "available": "0.25", "holds": "0.05" },
+            { "id": "5bd6e9216d99522a52e458d7", "currency": "USDT", "type": "trade", "balance": "1500", "available": "1500", "holds": "0" },
+            { "id": "5bd6e9216d99522a52e458d8", "currency": "ETH", "type": "margin", "balance": "3", "available": "3", "holds": "0" },
+            { "id": "5bd6e9216d99522a52e458d9", "currency": "XYZ", "type": "trade", "balance": "10", "available": "10", "holds": "0" }
+        ]);
+        // Recorded GET /api/v1/prices?base=USD data
+        let prices = parse_prices_from_json(&json!({ "BTC": "42000", "USDT": "1.0001", "ETH": "2500" })).unwrap();
+
+        let account = parse_accounts_from_json(&accounts, &prices).unwrap();
+
+        let assets: Vec<&str> = account.balances.iter().map(|b| b.asset.as_str()).collect();
+        // Margin balances are not spot funds
+        assert_eq!(assets, vec!["BTC", "USDT", "XYZ"]);
+
+        let btc = &account.balances[0];
+        assert_eq!(btc.free, Decimal::from_str("0.45").unwrap());
+        assert_eq!(btc.locked, Decimal::from_str("0.05").unwrap());
+        assert_eq!(btc.total, Decimal::from_str("0.5").unwrap());
+        assert_eq!(btc.usd_value, Some(Decimal::from(21000)));
+
+        assert!(!account.balances[2].priced);
+        assert_eq!(account.unpriced_assets, 1);
+        assert_eq!(account.total_usd_value, Some(Decimal::from_str("22500.15").unwrap()));
+    }
+
+    #[test]
+    fn test_candles_use_kucoin_field_order() {
+        // Recorded GET /api/v1/market/candles?type=1hour&symbol=BTC-USDT data (newest first)
+        let data = json!([
+            ["1705471200", "42500", "42625", "42700", "42400", "120.5", "5127925.1"],
+            ["1705467600", "42300", "42500", "42550", "42250", "98.2", "4168452.3"]
+        ]);
+        let (_, bar_length) = kucoin_candle_type(&KlineInterval::OneHour).unwrap();
+
+        let klines = parse_candles_from_json(&data, bar_length).unwrap();
+
+        assert_eq!(klines[0].open_time.timestamp(), 1705467600);
+        assert_eq!(klines[0].open, Decimal::from(42300));
+        assert_eq!(klines[0].close, Decimal::from(42500));
+        assert_eq!(klines[0].high, Decimal::from(42550));
+        assert_eq!(klines[0].low, Decimal::from(42250));
+        assert_eq!(klines[1].quote_asset_volume, Decimal::from_str("5127925.1").unwrap());
+    }
+}
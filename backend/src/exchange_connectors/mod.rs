@@ -1,21 +1,32 @@
 pub mod traits;
 pub mod binance;
+pub mod okx;
+pub mod kucoin;
+pub mod coinbase;
+pub mod kraken;
 pub mod factory;
 pub mod errors;
 pub mod shared_types;
 pub mod common_types;
+pub mod numeric;
+pub mod fees;
+pub mod symbols;
 
 use serde::{Deserialize, Serialize};
 
 pub use factory::ExchangeFactory;
 pub use errors::ExchangeError;
 pub use shared_types::*;
+pub use symbols::SymbolRef;
 
-/// Simplified exchange credentials - only API key and secret
+/// Exchange API credentials
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangeCredentials {
     pub api_key: String,
     pub api_secret: String,
+    /// Set when the exchange issues keys with a passphrase (see `Exchange::requires_passphrase`)
+    #[serde(default)]
+    pub passphrase: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -41,4 +52,9 @@ impl Exchange {
         }
     }
 
+    /// Whether API keys for this exchange come with a passphrase that must be sent on
+    /// every private request
+    pub fn requires_passphrase(&self) -> bool {
+        matches!(self, Exchange::OKX | Exchange::Kucoin)
+    }
 }
\ No newline at end of file
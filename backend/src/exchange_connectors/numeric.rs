@@ -0,0 +1,118 @@
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use super::ExchangeError;
+
+/// Largest magnitude accepted from an exchange or market-data response. Prices,
+/// quantities and volumes beyond it are treated as a malformed response.
+pub const MAX_ABS_VALUE: i64 = 1_000_000_000_000_000_000;
+
+/// Parse a number sent as a string. Rejects `NaN`, infinities and values beyond
+/// `MAX_ABS_VALUE`; accepts plain and scientific notation.
+pub fn parse_decimal(s: &str) -> Result<Decimal, ExchangeError> {
+    let trimmed = s.trim();
+    reject_non_finite_literal(trimmed)?;
+
+    let value = Decimal::from_str(trimmed)
+        .or_else(|_| Decimal::from_scientific(trimmed))
+        .map_err(|e| ExchangeError::ParseError(format!("Failed to parse decimal '{}': {}", trimmed, e)))?;
+
+    check_range(value)
+}
+
+/// Convert a float taken from a JSON response, rejecting non-finite and out-of-range values
+pub fn decimal_from_f64(value: f64) -> Result<Decimal, ExchangeError> {
+    let value = finite_f64(value)?;
+    Decimal::try_from(value)
+        .map_err(|e| ExchangeError::ParseError(format!("Failed to convert {} to decimal: {}", value, e)))
+}
+
+/// Parse a float sent as a string, for services that compute in `f64`
+pub fn parse_f64(s: &str) -> Result<f64, ExchangeError> {
+    let trimmed = s.trim();
+    let value = trimmed.parse::<f64>()
+        .map_err(|e| ExchangeError::ParseError(format!("Failed to parse number '{}': {}", trimmed, e)))?;
+    finite_f64(value)
+}
+
+/// `value` if it is finite and within `MAX_ABS_VALUE`
+pub fn finite_f64(value: f64) -> Result<f64, ExchangeError> {
+    if !value.is_finite() {
+        return Err(ExchangeError::ParseError(format!("{} is not a finite number", value)));
+    }
+    if value.abs() > MAX_ABS_VALUE as f64 {
+        return Err(ExchangeError::ParseError(format!("{} is outside the accepted range", value)));
+    }
+    Ok(value)
+}
+
+/// Round `value` down to a whole number of `step`s, as exchanges require of order
+/// quantities and prices. A zero step leaves the value as is.
+pub fn round_down_to_step(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    ((value / step).floor() * step).normalize()
+}
+
+/// `f64::from_str` accepts these spellings, and some APIs emit them for missing data
+fn reject_non_finite_literal(s: &str) -> Result<(), ExchangeError> {
+    let unsigned = s.trim_start_matches(['+', '-']).to_ascii_lowercase();
+    if matches!(unsigned.as_str(), "nan" | "inf" | "infinity") {
+        return Err(ExchangeError::ParseError(format!("{} is not a finite number", s)));
+    }
+    Ok(())
+}
+
+fn check_range(value: Decimal) -> Result<Decimal, ExchangeError> {
+    if value.abs() > Decimal::from(MAX_ABS_VALUE) {
+        return Err(ExchangeError::ParseError(format!("{} is outside the accepted range", value)));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_parse_error<T>(result: Result<T, ExchangeError>) -> bool {
+        matches!(result, Err(ExchangeError::ParseError(_)))
+    }
+
+    #[test]
+    fn test_parse_decimal_rejects_non_finite_and_absurd_values() {
+        for input in ["NaN", "nan", "-NaN", "Infinity", "-Infinity", "inf", "+inf"] {
+            assert!(is_parse_error(parse_decimal(input)), "{} was accepted", input);
+        }
+
+        assert!(is_parse_error(parse_decimal("1e30")));
+        assert!(is_parse_error(parse_decimal("-5000000000000000000000")));
+        assert!(is_parse_error(parse_decimal("99999999999999999999999999999999999")));
+        assert!(is_parse_error(parse_decimal("")));
+        assert!(is_parse_error(parse_decimal("12.3.4")));
+
+        assert_eq!(parse_decimal(" 42000.50 ").unwrap(), Decimal::new(4200050, 2));
+        assert_eq!(parse_decimal("1e-8").unwrap(), Decimal::new(1, 8));
+    }
+
+    #[test]
+    fn test_float_paths_reject_non_finite_and_absurd_values() {
+        for input in ["NaN", "Infinity", "-inf", "1e300"] {
+            assert!(is_parse_error(parse_f64(input)), "{} was accepted", input);
+        }
+        assert_eq!(parse_f64("21080.5").unwrap(), 21080.5);
+
+        assert!(is_parse_error(decimal_from_f64(f64::NAN)));
+        assert!(is_parse_error(decimal_from_f64(f64::INFINITY)));
+        assert!(is_parse_error(decimal_from_f64(1e25)));
+        assert_eq!(decimal_from_f64(104.25).unwrap(), Decimal::new(10425, 2));
+    }
+
+    #[test]
+    fn test_round_down_to_step_never_rounds_up() {
+        let step = parse_decimal("0.00100000").unwrap();
+        assert_eq!(round_down_to_step(parse_decimal("0.123987").unwrap(), step).to_string(), "0.123");
+        assert_eq!(round_down_to_step(parse_decimal("0.0009").unwrap(), step), Decimal::ZERO);
+        assert_eq!(round_down_to_step(Decimal::from(7), Decimal::ZERO), Decimal::from(7));
+    }
+}
@@ -0,0 +1,209 @@
+use reqwest::{Client, Method};
+use serde_json::Value;
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use base64::{Engine as _, engine::general_purpose};
+use crate::exchange_connectors::{ExchangeCredentials, ExchangeError};
+use crate::utils::http::{request_with_policy, RetryPolicy};
+use crate::utils::metrics::metrics;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// OKX v5 REST client. Private endpoints are signed with the API secret and
+/// authenticated with the key's passphrase.
+pub struct OkxApiClient {
+    pub client: Client,
+    pub base_url: String,
+    pub retry_policy: RetryPolicy,
+    credentials: ExchangeCredentials,
+    passphrase: String,
+}
+
+impl OkxApiClient {
+    pub fn new(credentials: ExchangeCredentials) -> Result<Self, ExchangeError> {
+        let passphrase = credentials.passphrase.clone()
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| ExchangeError::InvalidParameter("OKX API keys require a passphrase".to_string()))?;
+
+        Ok(Self {
+            client: Client::new(),
+            base_url: "https://www.okx.com".to_string(),
+            // The default 30 second deadline matches how long OKX accepts a request timestamp
+            retry_policy: RetryPolicy::default(),
+            credentials,
+            passphrase,
+        })
+    }
+
+    /// Base64 HMAC-SHA256 of `timestamp + method + request_path + body`
+    fn create_signature(&self, timestamp: &str, method: &Method, request_path: &str, body: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.credentials.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(format!("{}{}{}{}", timestamp, method.as_str(), request_path, body).as_bytes());
+        general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    /// Authentication headers for a private request. `request_path` includes the query string.
+    pub fn auth_headers(
+        &self,
+        method: &Method,
+        request_path: &str,
+        body: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let timestamp = now.to_rfc3339_opts(SecondsFormat::Millis, true);
+        let signature = self.create_signature(&timestamp, method, request_path, body);
+
+        vec![
+            ("OK-ACCESS-KEY", self.credentials.api_key.clone()),
+            ("OK-ACCESS-SIGN", signature),
+            ("OK-ACCESS-TIMESTAMP", timestamp),
+            ("OK-ACCESS-PASSPHRASE", self.passphrase.clone()),
+        ]
+    }
+
+    /// Unauthenticated GET against a public endpoint, returning the `data` array
+    pub async fn public_request(&self, path: &str, query: &[(&str, String)]) -> Result<Value, ExchangeError> {
+        self.send(Method::GET, &request_path(path, query), false).await
+    }
+
+    /// Signed GET against a private endpoint, returning the `data` array
+    pub async fn signed_request(&self, path: &str, query: &[(&str, String)]) -> Result<Value, ExchangeError> {
+        self.send(Method::GET, &request_path(path, query), true).await
+    }
+
+    async fn send(&self, method: Method, request_path: &str, signed: bool) -> Result<Value, ExchangeError> {
+        let mut request = self.client
+            .request(method.clone(), format!("{}{}", self.base_url, request_path))
+            .header("Content-Type", "application/json");
+
+        if signed {
+            for (name, value) in self.auth_headers(&method, request_path, "", Utc::now()) {
+                request = request.header(name, value);
+            }
+        }
+
+        let response = match request_with_policy(&self.retry_policy, request).await {
+            Ok(response) => response,
+            Err(e) => {
+                metrics().record_exchange_error("okx", "network");
+                return Err(e.into());
+            }
+        };
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            metrics().record_exchange_error("okx", status.as_str());
+        }
+
+        parse_okx_response(status.as_u16(), &text)
+    }
+}
+
+/// Path plus encoded query string, exactly as it is signed and sent
+pub fn request_path(path: &str, query: &[(&str, String)]) -> String {
+    if query.is_empty() {
+        return path.to_string();
+    }
+
+    let query_string = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{}", path, query_string)
+}
+
+/// Unwrap OKX's `{"code": "0", "msg": "", "data": [...]}` envelope
+pub fn parse_okx_response(status_code: u16, body: &str) -> Result<Value, ExchangeError> {
+    let json: Value = match serde_json::from_str(body) {
+        Ok(json) => json,
+        Err(_) => {
+            return Err(match status_code {
+                401 | 403 => ExchangeError::AuthenticationError(format!("Authentication failed: {}", body)),
+                429 => ExchangeError::RateLimitExceeded(format!("Rate limit exceeded: {}", body)),
+                503 => ExchangeError::Maintenance,
+                _ => ExchangeError::Unknown(format!("HTTP {}: {}", status_code, body)),
+            });
+        }
+    };
+
+    let code = json.get("code").and_then(|c| c.as_str()).unwrap_or_default();
+    if code == "0" {
+        return Ok(json.get("data").cloned().unwrap_or(Value::Array(Vec::new())));
+    }
+
+    let msg = json.get("msg").and_then(|m| m.as_str()).unwrap_or("Unknown error").to_string();
+    Err(match code {
+        // Authentication errors
+        "50111" | "50112" | "50119" => ExchangeError::InvalidApiKey,
+        "50105" => ExchangeError::AuthenticationError(format!("Incorrect API passphrase: {}", msg)),
+        "50102" => ExchangeError::AuthenticationError(format!("Timestamp request expired: {}", msg)),
+        "50113" => ExchangeError::AuthenticationError(format!("Invalid signature: {}", msg)),
+
+        // Rate limiting and availability
+        "50011" | "50061" => ExchangeError::RateLimitExceeded(format!("Too many requests: {}", msg)),
+        "50001" => ExchangeError::Maintenance,
+
+        // Instrument and parameter errors
+        "51001" => ExchangeError::SymbolNotFound(format!("Instrument does not exist: {}", msg)),
+        "50014" | "51000" => ExchangeError::InvalidParameter(format!("Parameter error: {}", msg)),
+
+        _ => ExchangeError::ApiError(format!("OKX error {}: {}", code, msg)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn credentials(passphrase: Option<&str>) -> ExchangeCredentials {
+        ExchangeCredentials {
+            api_key: "okx-key".to_string(),
+            api_secret: "okx-secret".to_string(),
+            passphrase: passphrase.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_signed_headers_carry_passphrase_and_signature() {
+        let client = OkxApiClient::new(credentials(Some("okx-passphrase"))).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap() + chrono::Duration::milliseconds(678);
+        let path = request_path("/api/v5/account/balance", &[("ccy", "BTC".to_string())]);
+
+        let headers = client.auth_headers(&Method::GET, &path, "", now);
+
+        assert_eq!(
+            headers,
+            vec![
+                ("OK-ACCESS-KEY", "okx-key".to_string()),
+                ("OK-ACCESS-SIGN", "ToEMNk1p6OLKiI5eEKQTtM7mKdh7t7WzfOFB0YVj/aE=".to_string()),
+                ("OK-ACCESS-TIMESTAMP", "2024-01-02T03:04:05.678Z".to_string()),
+                ("OK-ACCESS-PASSPHRASE", "okx-passphrase".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_client_requires_passphrase() {
+        assert!(matches!(OkxApiClient::new(credentials(None)), Err(ExchangeError::InvalidParameter(_))));
+        assert!(matches!(OkxApiClient::new(credentials(Some(""))), Err(ExchangeError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_error_envelope_maps_codes() {
+        let data = parse_okx_response(200, r#"{"code":"0","msg":"","data":[{"ts":"1"}]}"#).unwrap();
+        assert_eq!(data[0]["ts"], "1");
+
+        let err = parse_okx_response(401, r#"{"code":"50105","msg":"Passphrase incorrect","data":[]}"#).unwrap_err();
+        assert!(matches!(err, ExchangeError::AuthenticationError(_)));
+
+        let err = parse_okx_response(200, r#"{"code":"51001","msg":"Instrument ID does not exist","data":[]}"#).unwrap_err();
+        assert!(matches!(err, ExchangeError::SymbolNotFound(_)));
+
+        assert!(matches!(parse_okx_response(503, "Service Unavailable"), Err(ExchangeError::Maintenance)));
+    }
+}
@@ -0,0 +1,244 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::{error, info};
+
+use crate::exchange_connectors::{
+    traits::{ExchangeConnector, AccountAPI, OrderAPI, TradeExecutionAPI, MarketDataAPI},
+    ExchangeCredentials,
+    ExchangeError,
+    common_types::{ApiKeyPermissions, SpotAccount, MarginAccount, FuturesAccount, AccountBalances, WalletType, FuturesType, OrderSide, TimeInForce, Order, OcoOrder},
+    shared_types::{Ticker, OrderBook, Trade, Kline, KlineInterval, ExchangeInfo, SymbolInfo},
+};
+
+use super::api_client::OkxApiClient;
+use super::converters::*;
+
+/// Most candles OKX returns per request
+const MAX_CANDLES_PER_REQUEST: u32 = 300;
+
+/// OKX v5 connector. Covers balances and market data; trading is not implemented yet.
+pub struct OkxConnector {
+    client: OkxApiClient,
+}
+
+impl OkxConnector {
+    pub fn new(credentials: ExchangeCredentials) -> Result<Self, ExchangeError> {
+        let client = OkxApiClient::new(credentials)?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ExchangeConnector for OkxConnector {
+    async fn test_connection(&self) -> Result<bool, ExchangeError> {
+        // Public endpoint first so an outage is not reported as bad credentials
+        self.client.public_request("/api/v5/public/time", &[]).await?;
+
+        match self.client.signed_request("/api/v5/account/balance", &[]).await {
+            Ok(_) => {
+                info!("OKX API credentials validated successfully");
+                Ok(true)
+            }
+            Err(e) => {
+                error!("OKX API credential validation failed: {}", e);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn get_api_permissions(&self) -> Result<ApiKeyPermissions, ExchangeError> {
+        let data = self.client.signed_request("/api/v5/account/config", &[]).await?;
+        let perm = data.get(0)
+            .and_then(|config| config.get("perm"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let scopes: Vec<&str> = perm.split(',').map(str::trim).collect();
+        let can_trade = scopes.contains(&"trade");
+
+        Ok(ApiKeyPermissions {
+            can_read: scopes.contains(&"read_only") || can_trade,
+            can_trade_spot: can_trade,
+            can_trade_margin: can_trade,
+            can_trade_futures: can_trade,
+            can_withdraw: scopes.contains(&"withdraw"),
+        })
+    }
+}
+
+#[async_trait]
+impl AccountAPI for OkxConnector {
+    async fn get_spot_account(&self) -> Result<SpotAccount, ExchangeError> {
+        let data = self.client.signed_request("/api/v5/account/balance", &[]).await?;
+        parse_balance_from_json(data)
+    }
+
+    async fn get_margin_account(&self) -> Result<MarginAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX margin account not yet implemented".to_string()))
+    }
+
+    async fn get_futures_account(&self, _account_type: FuturesType) -> Result<FuturesAccount, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX futures account not yet implemented".to_string()))
+    }
+
+    async fn get_all_balances(&self) -> Result<AccountBalances, ExchangeError> {
+        // OKX's trading account holds spot, margin and derivatives collateral together,
+        // and its balance endpoint already values everything in USD
+        let spot = self.get_spot_account().await?;
+        let total_usd_value = spot.total_usd_value.unwrap_or(Decimal::ZERO);
+
+        Ok(AccountBalances {
+            spot: Some(spot),
+            margin: None,
+            futures_usdm: None,
+            futures_coinm: None,
+            total_usd_value,
+            total_btc_value: Decimal::ZERO,
+        })
+    }
+}
+
+#[async_trait]
+impl OrderAPI for OkxConnector {
+    async fn get_open_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX orders not yet implemented".to_string()))
+    }
+
+    async fn get_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX orders not yet implemented".to_string()))
+    }
+
+    async fn get_order_history(
+        &self,
+        _symbol: Option<&str>,
+        _wallet_type: WalletType,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+        _limit: Option<u32>
+    ) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_order(&self, _order_id: &str, _symbol: &str, _wallet_type: WalletType) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX orders not yet implemented".to_string()))
+    }
+
+    async fn cancel_all_orders(&self, _symbol: Option<&str>, _wallet_type: WalletType) -> Result<Vec<Order>, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX orders not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl TradeExecutionAPI for OkxConnector {
+    async fn place_market_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Option<Decimal>,
+        _quote_quantity: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX trading not yet implemented".to_string()))
+    }
+
+    async fn place_limit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _price: Decimal,
+        _quantity: Decimal,
+        _time_in_force: TimeInForce,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX trading not yet implemented".to_string()))
+    }
+
+    async fn place_stop_loss_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX trading not yet implemented".to_string()))
+    }
+
+    async fn place_take_profit_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _stop_price: Decimal,
+        _quantity: Decimal,
+        _limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<Order, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX trading not yet implemented".to_string()))
+    }
+
+    async fn place_oco_order(
+        &self,
+        _symbol: &str,
+        _side: OrderSide,
+        _quantity: Decimal,
+        _price: Decimal,
+        _stop_price: Decimal,
+        _stop_limit_price: Option<Decimal>,
+        _wallet_type: WalletType,
+    ) -> Result<OcoOrder, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX trading not yet implemented".to_string()))
+    }
+}
+
+#[async_trait]
+impl MarketDataAPI for OkxConnector {
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker, ExchangeError> {
+        let data = self.client
+            .public_request("/api/v5/market/ticker", &[("instId", to_inst_id(symbol)?)])
+            .await?;
+        parse_ticker_from_json(data, symbol)
+    }
+
+    async fn get_order_book(&self, _symbol: &str, _limit: Option<u32>) -> Result<OrderBook, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX order book not yet implemented".to_string()))
+    }
+
+    async fn get_recent_trades(&self, _symbol: &str, _limit: Option<u32>) -> Result<Vec<Trade>, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX recent trades not yet implemented".to_string()))
+    }
+
+    async fn get_klines(
+        &self,
+        symbol: &str,
+        interval: KlineInterval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>
+    ) -> Result<Vec<Kline>, ExchangeError> {
+        let (bar, bar_length) = okx_bar(&interval)?;
+
+        let mut query = vec![("instId", to_inst_id(symbol)?), ("bar", bar.to_string())];
+        // OKX paginates backwards: `after` returns bars older than it, `before` newer than it
+        if let Some(end_time) = end_time {
+            query.push(("after", end_time.timestamp_millis().to_string()));
+        }
+        if let Some(start_time) = start_time {
+            query.push(("before", (start_time.timestamp_millis() - 1).to_string()));
+        }
+        if let Some(limit) = limit {
+            query.push(("limit", limit.min(MAX_CANDLES_PER_REQUEST).to_string()));
+        }
+
+        let data = self.client.public_request("/api/v5/market/candles", &query).await?;
+        parse_candles_from_json(data, bar_length)
+    }
+
+    async fn get_exchange_info(&self) -> Result<ExchangeInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX exchange info not yet implemented".to_string()))
+    }
+
+    async fn get_symbol_info(&self, _symbol: &str) -> Result<SymbolInfo, ExchangeError> {
+        Err(ExchangeError::NotSupported("OKX symbol info not yet implemented".to_string()))
+    }
+}
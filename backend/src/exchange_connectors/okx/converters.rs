@@ -0,0 +1,297 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+
+use crate::exchange_connectors::{
+    Exchange, ExchangeError, SymbolRef,
+    numeric,
+    common_types::{AssetBalance, PriceSource, SpotAccount, WalletType},
+    shared_types::{Kline, KlineInterval, Ticker},
+};
+
+/// OKX sends numbers as strings and leaves unset fields empty; empty counts as zero
+pub fn parse_decimal(s: &str) -> Result<Decimal, ExchangeError> {
+    if s.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+    numeric::parse_decimal(s)
+}
+
+fn decimal_field(json: &Value, key: &str) -> Result<Decimal, ExchangeError> {
+    parse_decimal(json.get(key).and_then(|v| v.as_str()).unwrap_or_default())
+}
+
+/// Millisecond timestamps, which OKX also sends as strings
+pub fn parse_timestamp(ts: &str) -> Result<DateTime<Utc>, ExchangeError> {
+    ts.parse::<i64>()
+        .ok()
+        .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+        .ok_or_else(|| ExchangeError::ParseError(format!("Invalid timestamp: {}", ts)))
+}
+
+/// Convert `BTCUSDT`, `BTC/USDT` or an already dashed `BTC-USDT` to the OKX instrument id `BTC-USDT`
+pub fn to_inst_id(symbol: &str) -> Result<String, ExchangeError> {
+    SymbolRef::from_any(symbol)?.to_native(&Exchange::OKX)
+}
+
+/// OKX candle `bar` value and bar length. Daily and longer bars use the UTC-aligned variants.
+pub fn okx_bar(interval: &KlineInterval) -> Result<(&'static str, Duration), ExchangeError> {
+    Ok(match interval {
+        KlineInterval::OneSecond => ("1s", Duration::seconds(1)),
+        KlineInterval::OneMinute => ("1m", Duration::minutes(1)),
+        KlineInterval::ThreeMinutes => ("3m", Duration::minutes(3)),
+        KlineInterval::FiveMinutes => ("5m", Duration::minutes(5)),
+        KlineInterval::FifteenMinutes => ("15m", Duration::minutes(15)),
+        KlineInterval::ThirtyMinutes => ("30m", Duration::minutes(30)),
+        KlineInterval::OneHour => ("1H", Duration::hours(1)),
+        KlineInterval::TwoHours => ("2H", Duration::hours(2)),
+        KlineInterval::FourHours => ("4H", Duration::hours(4)),
+        KlineInterval::SixHours => ("6Hutc", Duration::hours(6)),
+        KlineInterval::TwelveHours => ("12Hutc", Duration::hours(12)),
+        KlineInterval::OneDay => ("1Dutc", Duration::days(1)),
+        KlineInterval::ThreeDays => ("3Dutc", Duration::days(3)),
+        KlineInterval::OneWeek => ("1Wutc", Duration::weeks(1)),
+        KlineInterval::OneMonth => ("1Mutc", Duration::days(30)),
+        KlineInterval::EightHours => {
+            return Err(ExchangeError::NotSupported("OKX has no 8h candles".to_string()));
+        }
+    })
+}
+
+/// Map the `data` of `GET /api/v5/account/balance` to a spot account. OKX values every
+/// currency in USD itself (`eqUsd`), so no extra price lookups are needed.
+pub fn parse_balance_from_json(data: Value) -> Result<SpotAccount, ExchangeError> {
+    let account = data.get(0)
+        .ok_or_else(|| ExchangeError::ParseError("Empty OKX balance response".to_string()))?;
+
+    let mut balances = Vec::new();
+    let mut unpriced_assets = 0;
+    if let Some(details) = account.get("details").and_then(|v| v.as_array()) {
+        for detail in details {
+            let asset = detail.get("ccy").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            let total = decimal_field(detail, "eq")?;
+            if total.is_zero() {
+                continue;
+            }
+
+            let usd_value = match detail.get("eqUsd").and_then(|v| v.as_str()) {
+                Some(value) if !value.is_empty() => Some(parse_decimal(value)?),
+                _ => None,
+            };
+            if usd_value.is_none() {
+                unpriced_assets += 1;
+            }
+
+            balances.push(AssetBalance {
+                asset,
+                free: decimal_field(detail, "availBal")?,
+                locked: decimal_field(detail, "frozenBal")?,
+                total,
+                usd_value,
+                btc_value: None,
+                wallet_type: WalletType::Spot,
+                priced: usd_value.is_some(),
+                price_source: usd_value.map(|_| PriceSource::ExchangeTicker),
+            });
+        }
+    }
+
+    let last_update_time = match account.get("uTime").and_then(|v| v.as_str()) {
+        Some(ts) if !ts.is_empty() => parse_timestamp(ts)?,
+        _ => Utc::now(),
+    };
+
+    Ok(SpotAccount {
+        balances,
+        total_usd_value: Some(decimal_field(account, "totalEq")?),
+        unpriced_assets,
+        total_btc_value: None,
+        maker_commission: None,
+        taker_commission: None,
+        can_trade: true,
+        can_withdraw: false,
+        can_deposit: true,
+        last_update_time,
+    })
+}
+
+/// Map the `data` of `GET /api/v5/market/ticker` to a ticker reported under `symbol`
+pub fn parse_ticker_from_json(data: Value, symbol: &str) -> Result<Ticker, ExchangeError> {
+    let ticker = data.get(0)
+        .ok_or_else(|| ExchangeError::SymbolNotFound(symbol.to_string()))?;
+
+    let last_price = decimal_field(ticker, "last")?;
+    let open_price = decimal_field(ticker, "open24h")?;
+    let price_change = last_price - open_price;
+    let price_change_percent = if open_price > Decimal::ZERO {
+        price_change / open_price * Decimal::from(100)
+    } else {
+        Decimal::ZERO
+    };
+    let close_time = parse_timestamp(ticker.get("ts").and_then(|v| v.as_str()).unwrap_or_default())?;
+
+    Ok(Ticker {
+        symbol: symbol.to_uppercase(),
+        bid_price: decimal_field(ticker, "bidPx")?,
+        bid_quantity: decimal_field(ticker, "bidSz")?,
+        ask_price: decimal_field(ticker, "askPx")?,
+        ask_quantity: decimal_field(ticker, "askSz")?,
+        last_price,
+        price_change,
+        price_change_percent,
+        high_price: decimal_field(ticker, "high24h")?,
+        low_price: decimal_field(ticker, "low24h")?,
+        // For spot instruments vol24h is in the base currency and volCcy24h in the quote
+        volume: decimal_field(ticker, "vol24h")?,
+        quote_volume: decimal_field(ticker, "volCcy24h")?,
+        open_time: close_time - Duration::hours(24),
+        close_time,
+    })
+}
+
+/// Map the `data` of `GET /api/v5/market/candles` (newest first) to klines, oldest first
+pub fn parse_candles_from_json(data: Value, bar_length: Duration) -> Result<Vec<Kline>, ExchangeError> {
+    let rows = data.as_array()
+        .ok_or_else(|| ExchangeError::ParseError("OKX candles response is not an array".to_string()))?;
+
+    let mut klines = Vec::with_capacity(rows.len());
+    for row in rows {
+        let fields: Vec<&str> = row.as_array()
+            .map(|fields| fields.iter().filter_map(|f| f.as_str()).collect())
+            .unwrap_or_default();
+        if fields.len() < 8 {
+            return Err(ExchangeError::ParseError(format!("Malformed OKX candle: {}", row)));
+        }
+
+        let open_time = parse_timestamp(fields[0])?;
+        klines.push(Kline {
+            open_time,
+            close_time: open_time + bar_length - Duration::milliseconds(1),
+            open: parse_decimal(fields[1])?,
+            high: parse_decimal(fields[2])?,
+            low: parse_decimal(fields[3])?,
+            close: parse_decimal(fields[4])?,
+            volume: parse_decimal(fields[5])?,
+            quote_asset_volume: parse_decimal(fields[7])?,
+            // OKX does not report trade counts or taker volumes on candles
+            number_of_trades: 0,
+            taker_buy_base_asset_volume: Decimal::ZERO,
+            taker_buy_quote_asset_volume: Decimal::ZERO,
+        });
+    }
+
+    klines.sort_by_key(|k| k.open_time);
+    Ok(klines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_symbol_to_inst_id() {
+        assert_eq!(to_inst_id("btcusdt").unwrap(), "BTC-USDT");
+        assert_eq!(to_inst_id("ETHBTC").unwrap(), "ETH-BTC");
+        assert_eq!(to_inst_id("SOL-USDC").unwrap(), "SOL-USDC");
+        assert_eq!(to_inst_id("sol/usdc").unwrap(), "SOL-USDC");
+        assert!(to_inst_id("USDT").is_err());
+    }
+
+    #[test]
+    fn test_balance_response_maps_usd_values() {
+        // Recorded GET /api/v5/account/balance data
+        let data = json!([{
+            "adjEq": "",
+            "imr": "",
+            "isoEq": "0",
+            "mgnRatio": "",
+            "totalEq": "41624.32",
+            "uTime": "1705474164160",
+            "details": [
+                {
+                    "availBal": "0.4", "availEq": "", "cashBal": "0.5", "ccy": "BTC",
+                    "eq": "0.5", "eqUsd": "21312.5", "frozenBal": "0.1", "ordFrozen": "0.1",
+                    "uTime": "1705449605015", "upl": ""
+                },
+                {
+                    "availBal": "20311.82", "availEq": "", "cashBal": "20311.82", "ccy": "USDT",
+                    "eq": "20311.82", "eqUsd": "20311.82", "frozenBal": "0", "ordFrozen": "0",
+                    "uTime": "1705474164160", "upl": ""
+                },
+                {
+                    "availBal": "0", "availEq": "", "cashBal": "0", "ccy": "ETH",
+                    "eq": "0", "eqUsd": "0", "frozenBal": "0", "ordFrozen": "0",
+                    "uTime": "1705474164160", "upl": ""
+                }
+            ]
+        }]);
+
+        let account = parse_balance_from_json(data).unwrap();
+
+        assert_eq!(account.total_usd_value, Some(Decimal::from_str("41624.32").unwrap()));
+        // Zero balances are dropped
+        assert_eq!(account.balances.len(), 2);
+
+        let btc = &account.balances[0];
+        assert_eq!(btc.asset, "BTC");
+        assert_eq!(btc.free, Decimal::from_str("0.4").unwrap());
+        assert_eq!(btc.locked, Decimal::from_str("0.1").unwrap());
+        assert_eq!(btc.total, Decimal::from_str("0.5").unwrap());
+        assert_eq!(btc.usd_value, Some(Decimal::from_str("21312.5").unwrap()));
+        assert!(btc.priced);
+        assert_eq!(account.unpriced_assets, 0);
+    }
+
+    #[test]
+    fn test_ticker_response_computes_change() {
+        // Recorded GET /api/v5/market/ticker?instId=BTC-USDT data
+        let data = json!([{
+            "instType": "SPOT",
+            "instId": "BTC-USDT",
+            "last": "42625",
+            "lastSz": "0.0012",
+            "askPx": "42625.1",
+            "askSz": "0.6",
+            "bidPx": "42625",
+            "bidSz": "1.2",
+            "open24h": "41000",
+            "high24h": "42900",
+            "low24h": "40800.2",
+            "volCcy24h": "421863912.4",
+            "vol24h": "10020.51",
+            "ts": "1705474164160",
+            "sodUtc0": "42450",
+            "sodUtc8": "42200"
+        }]);
+
+        let ticker = parse_ticker_from_json(data, "BTCUSDT").unwrap();
+
+        assert_eq!(ticker.symbol, "BTCUSDT");
+        assert_eq!(ticker.last_price, Decimal::from(42625));
+        assert_eq!(ticker.price_change, Decimal::from(1625));
+        assert_eq!(ticker.price_change_percent.round_dp(4), Decimal::from_str("3.9634").unwrap());
+        assert_eq!(ticker.volume, Decimal::from_str("10020.51").unwrap());
+        assert_eq!(ticker.close_time.timestamp_millis(), 1705474164160);
+    }
+
+    #[test]
+    fn test_candles_response_is_sorted_oldest_first() {
+        // Recorded GET /api/v5/market/candles?instId=BTC-USDT&bar=1H data (newest first)
+        let data = json!([
+            ["1705471200000", "42500", "42700", "42400", "42625", "120.5", "5127925.1", "5127925.1", "0"],
+            ["1705467600000", "42300", "42550", "42250", "42500", "98.2", "4168452.3", "4168452.3", "1"]
+        ]);
+        let (_, bar_length) = okx_bar(&KlineInterval::OneHour).unwrap();
+
+        let klines = parse_candles_from_json(data, bar_length).unwrap();
+
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0].open_time.timestamp_millis(), 1705467600000);
+        assert_eq!(klines[0].close_time.timestamp_millis(), 1705471199999);
+        assert_eq!(klines[0].close, Decimal::from(42500));
+        assert_eq!(klines[1].open, Decimal::from(42500));
+        assert_eq!(klines[1].quote_asset_volume, Decimal::from_str("5127925.1").unwrap());
+    }
+}
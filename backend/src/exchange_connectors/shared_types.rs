@@ -52,7 +52,9 @@ pub struct Kline {
     pub taker_buy_quote_asset_volume: Decimal,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Candle width. Serialized in its exchange form ("1h", "1d", ...), so an unknown
+/// interval is rejected when a request or config is deserialized.
+#[derive(Debug, Clone, PartialEq)]
 pub enum KlineInterval {
     OneSecond,
     OneMinute,
@@ -73,6 +75,26 @@ pub enum KlineInterval {
 }
 
 impl KlineInterval {
+    /// Every interval, shortest first
+    pub const ALL: [KlineInterval; 16] = [
+        Self::OneSecond,
+        Self::OneMinute,
+        Self::ThreeMinutes,
+        Self::FiveMinutes,
+        Self::FifteenMinutes,
+        Self::ThirtyMinutes,
+        Self::OneHour,
+        Self::TwoHours,
+        Self::FourHours,
+        Self::SixHours,
+        Self::EightHours,
+        Self::TwelveHours,
+        Self::OneDay,
+        Self::ThreeDays,
+        Self::OneWeek,
+        Self::OneMonth,
+    ];
+
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "1s" => Some(Self::OneSecond),
@@ -115,6 +137,56 @@ impl KlineInterval {
             Self::OneMonth => "1M",
         }
     }
+
+    /// Width of one candle; a month counts as 30 days
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            Self::OneSecond => chrono::Duration::seconds(1),
+            Self::OneMinute => chrono::Duration::minutes(1),
+            Self::ThreeMinutes => chrono::Duration::minutes(3),
+            Self::FiveMinutes => chrono::Duration::minutes(5),
+            Self::FifteenMinutes => chrono::Duration::minutes(15),
+            Self::ThirtyMinutes => chrono::Duration::minutes(30),
+            Self::OneHour => chrono::Duration::hours(1),
+            Self::TwoHours => chrono::Duration::hours(2),
+            Self::FourHours => chrono::Duration::hours(4),
+            Self::SixHours => chrono::Duration::hours(6),
+            Self::EightHours => chrono::Duration::hours(8),
+            Self::TwelveHours => chrono::Duration::hours(12),
+            Self::OneDay => chrono::Duration::days(1),
+            Self::ThreeDays => chrono::Duration::days(3),
+            Self::OneWeek => chrono::Duration::weeks(1),
+            Self::OneMonth => chrono::Duration::days(30),
+        }
+    }
+
+    /// Candles in a calendar year of round-the-clock trading
+    pub fn periods_per_year(&self) -> f64 {
+        365.25 * 86_400.0 / self.duration().num_seconds() as f64
+    }
+}
+
+impl Serialize for KlineInterval {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for KlineInterval {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::from_str(&value)
+            // Results saved before intervals serialized in exchange form use the variant name
+            .or_else(|| Self::ALL.iter().find(|interval| format!("{:?}", interval) == value).cloned())
+            .ok_or_else(|| {
+                let expected: Vec<&str> = Self::ALL.iter().map(|interval| interval.as_str()).collect();
+                serde::de::Error::custom(format!(
+                    "invalid interval '{}', expected one of {}",
+                    value,
+                    expected.join(", ")
+                ))
+            })
+    }
 }
 
 impl std::fmt::Display for KlineInterval {
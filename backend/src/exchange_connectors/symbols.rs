@@ -0,0 +1,194 @@
+use super::{Exchange, ExchangeError};
+
+/// Quote assets recognised when splitting a run-together symbol like `BTCUSDT`, longest
+/// first so `BTCFDUSD` isn't read as quoted in `USD`
+const QUOTE_ASSETS: [&str; 15] = [
+    "FDUSD", "USDT", "USDC", "BUSD", "TUSD", "EUR", "USD", "GBP", "TRY", "BTC", "ETH", "BNB", "OKB", "KCS", "DAI",
+];
+
+/// Kraken's codes for assets it lists under a non-standard ticker: (Kraken, canonical)
+const KRAKEN_ALIASES: [(&str, &str); 2] = [("XBT", "BTC"), ("XDG", "DOGE")];
+
+/// Longest asset code accepted
+const MAX_ASSET_LEN: usize = 16;
+
+/// How an exchange writes a trading pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolFormat {
+    /// `BTCUSDT`
+    Concatenated,
+    /// `BTC-USDT`
+    Dashed,
+    /// `XBT/USD`, using Kraken's asset codes
+    Kraken,
+}
+
+impl Exchange {
+    pub fn symbol_format(&self) -> SymbolFormat {
+        match self {
+            Exchange::Binance | Exchange::Bybit => SymbolFormat::Concatenated,
+            Exchange::Coinbase | Exchange::Kucoin | Exchange::OKX => SymbolFormat::Dashed,
+            Exchange::Kraken => SymbolFormat::Kraken,
+        }
+    }
+}
+
+/// Exchange-independent trading pair, written `BTC/USDT`. Strategies and configs name
+/// pairs this way and each connector converts to its own spelling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolRef {
+    pub base: String,
+    pub quote: String,
+}
+
+impl SymbolRef {
+    /// Pair of two asset codes, upper-cased. Codes must be alphanumeric and distinct.
+    fn checked(base: &str, quote: &str) -> Result<Self, String> {
+        let base = validate_asset(base, "base")?;
+        let quote = validate_asset(quote, "quote")?;
+        if base == quote {
+            return Err("base and quote are the same asset".to_string());
+        }
+        Ok(Self { base, quote })
+    }
+
+    /// Parse the canonical `BASE/QUOTE` form
+    pub fn parse(pair: &str) -> Result<Self, ExchangeError> {
+        let (base, quote) = pair
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| invalid_pair(pair, "expected BASE/QUOTE"))?;
+        Self::checked(base, quote).map_err(|reason| invalid_pair(pair, &reason))
+    }
+
+    /// Accept a pair in any of the spellings in use: `BTC/USDT`, `BTC-USDT` or `BTCUSDT`
+    pub fn from_any(symbol: &str) -> Result<Self, ExchangeError> {
+        let symbol = symbol.trim();
+        if symbol.contains('/') {
+            Self::parse(symbol)
+        } else if symbol.contains('-') {
+            Self::from_native(symbol, &Exchange::Coinbase)
+        } else {
+            Self::from_native(symbol, &Exchange::Binance)
+        }
+    }
+
+    /// The pair as `exchange` writes it
+    pub fn to_native(&self, exchange: &Exchange) -> Result<String, ExchangeError> {
+        Ok(match exchange.symbol_format() {
+            SymbolFormat::Concatenated => format!("{}{}", self.base, self.quote),
+            SymbolFormat::Dashed => format!("{}-{}", self.base, self.quote),
+            SymbolFormat::Kraken => format!("{}/{}", to_kraken_asset(&self.base), to_kraken_asset(&self.quote)),
+        })
+    }
+
+    /// Read a pair written the way `exchange` writes it
+    pub fn from_native(native: &str, exchange: &Exchange) -> Result<Self, ExchangeError> {
+        let upper = native.trim().to_uppercase();
+        let (base, quote) = match exchange.symbol_format() {
+            SymbolFormat::Concatenated => QUOTE_ASSETS
+                .iter()
+                .find(|quote| upper.len() > quote.len() && upper.ends_with(*quote))
+                .map(|quote| (upper[..upper.len() - quote.len()].to_string(), quote.to_string()))
+                .ok_or_else(|| invalid_pair(native, &format!("no known quote asset for {:?}", exchange)))?,
+            SymbolFormat::Dashed => split_pair(&upper, '-')
+                .ok_or_else(|| invalid_pair(native, &format!("{:?} pairs are written BASE-QUOTE", exchange)))?,
+            SymbolFormat::Kraken => {
+                let (base, quote) = split_pair(&upper, '/')
+                    .ok_or_else(|| invalid_pair(native, "Kraken pairs are written BASE/QUOTE"))?;
+                (from_kraken_asset(&base), from_kraken_asset(&quote))
+            }
+        };
+
+        Self::checked(&base, &quote).map_err(|reason| invalid_pair(native, &reason))
+    }
+}
+
+fn split_pair(symbol: &str, separator: char) -> Option<(String, String)> {
+    let (base, quote) = symbol.split_once(separator)?;
+    if quote.contains(separator) {
+        return None;
+    }
+    Some((base.to_string(), quote.to_string()))
+}
+
+fn validate_asset(asset: &str, role: &str) -> Result<String, String> {
+    let asset = asset.trim().to_uppercase();
+    if asset.is_empty() {
+        return Err(format!("missing {} asset", role));
+    }
+    if asset.len() > MAX_ASSET_LEN || !asset.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("'{}' is not a valid {} asset", asset, role));
+    }
+    Ok(asset)
+}
+
+fn invalid_pair(pair: &str, reason: &str) -> ExchangeError {
+    ExchangeError::InvalidParameter(format!("Invalid trading pair '{}': {}", pair, reason))
+}
+
+fn to_kraken_asset(asset: &str) -> &str {
+    KRAKEN_ALIASES
+        .iter()
+        .find(|(_, canonical)| *canonical == asset)
+        .map_or(asset, |(kraken, _)| *kraken)
+}
+
+pub(crate) fn from_kraken_asset(asset: &str) -> String {
+    KRAKEN_ALIASES
+        .iter()
+        .find(|(kraken, _)| *kraken == asset)
+        .map_or(asset, |(_, canonical)| *canonical)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXCHANGES: [Exchange; 6] = [
+        Exchange::Binance,
+        Exchange::Bybit,
+        Exchange::Coinbase,
+        Exchange::Kraken,
+        Exchange::Kucoin,
+        Exchange::OKX,
+    ];
+
+    #[test]
+    fn test_native_formats_round_trip() {
+        let btc_usd = SymbolRef::parse("BTC/USD").unwrap();
+        assert_eq!(btc_usd.to_native(&Exchange::Binance).unwrap(), "BTCUSD");
+        assert_eq!(btc_usd.to_native(&Exchange::Kraken).unwrap(), "XBT/USD");
+        assert_eq!(btc_usd.to_native(&Exchange::Coinbase).unwrap(), "BTC-USD");
+
+        for pair in ["BTC/USDT", "ETH/BTC", "DOGE/USD", "SOL/FDUSD", "1INCH/USDC"] {
+            let symbol = SymbolRef::parse(pair).unwrap();
+            for exchange in &EXCHANGES {
+                let native = symbol.to_native(exchange).unwrap();
+                assert_eq!(SymbolRef::from_native(&native, exchange).unwrap(), symbol, "{} on {:?}", native, exchange);
+            }
+            assert_eq!(format!("{}/{}", symbol.base, symbol.quote), pair);
+        }
+
+        assert_eq!(SymbolRef::from_native("xdg/eur", &Exchange::Kraken).unwrap(), SymbolRef::parse("DOGE/EUR").unwrap());
+        assert_eq!(SymbolRef::from_any("btcusdt").unwrap(), SymbolRef::from_any("BTC-USDT").unwrap());
+    }
+
+    #[test]
+    fn test_invalid_pairs_are_rejected() {
+        let invalid = |result: Result<SymbolRef, ExchangeError>| matches!(result, Err(ExchangeError::InvalidParameter(_)));
+
+        assert!(invalid(SymbolRef::parse("BTCUSDT")));
+        assert!(invalid(SymbolRef::parse("BTC/")));
+        assert!(invalid(SymbolRef::parse("BTC/BTC")));
+        assert!(invalid(SymbolRef::parse("BT C/USD")));
+        assert!(invalid(SymbolRef::from_native("USDT", &Exchange::Binance)));
+        assert!(invalid(SymbolRef::from_native("BTCXYZ", &Exchange::Binance)));
+        assert!(invalid(SymbolRef::from_native("BTC-USD-PERP", &Exchange::Coinbase)));
+        assert!(invalid(SymbolRef::from_native("BTCUSD", &Exchange::Kraken)));
+
+        let message = SymbolRef::from_native("BTC_USD", &Exchange::OKX).unwrap_err().to_string();
+        assert!(message.contains("BTC_USD"), "{}", message);
+    }
+}
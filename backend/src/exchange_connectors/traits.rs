@@ -10,6 +10,11 @@ use crate::exchange_connectors::{
 #[async_trait]
 pub trait ExchangeConnector: Send + Sync {
     async fn test_connection(&self) -> Result<bool, ExchangeError>;
+
+    /// Scopes granted to the connector's API key
+    async fn get_api_permissions(&self) -> Result<ApiKeyPermissions, ExchangeError> {
+        Err(ExchangeError::NotSupported("API key permissions".to_string()))
+    }
 }
 
 #[async_trait]
@@ -45,6 +50,36 @@ pub trait OrderAPI: ExchangeConnector {
 
 #[async_trait]
 pub trait TradeExecutionAPI: ExchangeConnector {
+    /// Place the market or limit order `request` describes
+    async fn place_order(&self, request: OrderRequest) -> Result<Order, ExchangeError> {
+        match request.order_type {
+            OrderType::Market => {
+                self.place_market_order(
+                    &request.symbol,
+                    request.side,
+                    request.quantity,
+                    request.quote_quantity,
+                    request.wallet_type,
+                ).await
+            }
+            OrderType::Limit => {
+                let price = request.price
+                    .ok_or_else(|| ExchangeError::InvalidOrder("Limit orders need a price".to_string()))?;
+                let quantity = request.quantity
+                    .ok_or_else(|| ExchangeError::InvalidOrder("Limit orders need a quantity".to_string()))?;
+                self.place_limit_order(
+                    &request.symbol,
+                    request.side,
+                    price,
+                    quantity,
+                    request.time_in_force.unwrap_or(TimeInForce::GTC),
+                    request.wallet_type,
+                ).await
+            }
+            other => Err(ExchangeError::NotSupported(format!("{:?} orders through place_order", other))),
+        }
+    }
+
     async fn place_market_order(
         &self,
         symbol: &str,
@@ -558,12 +558,12 @@ impl ExecutionEngine {
                 warn!("Risk limit exceeded for instance {}, pausing", instance_id);
                 let _ = self.pause_strategy_instance(instance_id).await;
             }
-            ExecutionEvent::OrderFilled { instance_id, order_id, symbol, filled_quantity, execution_price, timestamp } => {
+            ExecutionEvent::OrderFilled { instance_id, order_id, symbol, side, filled_quantity, execution_price, timestamp } => {
                 info!("Order filled for instance {}: {} {} at {} (Order ID: {})", 
                       instance_id, filled_quantity, symbol, execution_price, order_id);
                 
                 // Notify strategy of order fill
-                if let Err(e) = self.notify_strategy_order_fill(instance_id, order_id, symbol, filled_quantity, execution_price, timestamp).await {
+                if let Err(e) = self.notify_strategy_order_fill(instance_id, order_id, symbol, side, filled_quantity, execution_price, timestamp).await {
                     error!("Failed to notify strategy {} of order fill: {}", instance_id, e);
                 }
             }
@@ -611,6 +611,7 @@ impl ExecutionEngine {
         instance_id: Uuid, 
         order_id: String, 
         symbol: String,
+        side: crate::exchange_connectors::common_types::OrderSide,
         filled_quantity: rust_decimal::Decimal,
         execution_price: rust_decimal::Decimal,
         timestamp: DateTime<Utc>
@@ -626,6 +627,7 @@ impl ExecutionEngine {
             let order_update = crate::strategies::core::OrderUpdate {
                 order_id,
                 symbol: symbol.clone(),
+                side,
                 order_type: crate::strategies::core::traits::OrderType::Market,
                 status: crate::strategies::core::traits::OrderStatus::Filled,
                 quantity: filled_quantity,
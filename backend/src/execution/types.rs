@@ -3,6 +3,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::exchange_connectors::common_types::OrderSide;
 use crate::strategies::core::{StrategySignal, StrategyMode, Position, OrderStatus};
 
 /// Execution engine configuration
@@ -230,6 +231,7 @@ pub enum ExecutionEvent {
         instance_id: Uuid,
         order_id: String,
         symbol: String,
+        side: OrderSide,
         filled_quantity: Decimal,
         execution_price: Decimal,
         timestamp: DateTime<Utc>,
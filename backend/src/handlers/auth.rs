@@ -1,7 +1,7 @@
 use actix_web::{web, HttpResponse, Result, cookie::{Cookie, SameSite}, HttpRequest};
 use actix_session::Session;
 use bcrypt::{hash, verify, DEFAULT_COST};
-use chrono::{Utc, Duration};
+use chrono::Utc;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation, Algorithm};
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,7 @@ use crate::models::user::{
     ChangePasswordRequest, CreateUserRequest, LoginRequest, UserResponse,
 };
 use crate::utils::errors::AppError;
+use crate::utils::session_tracker::{SessionPolicy, SessionTracker};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -34,6 +35,8 @@ pub struct Claims {
 #[derive(Clone)]
 pub struct AuthService {
     pub jwt_secret: String,
+    /// Idle and absolute timeouts of login sessions; tokens live for the absolute timeout
+    pub session_policy: SessionPolicy,
 }
 
 // Rate limiting storage - in production, use Redis
@@ -64,13 +67,18 @@ static VALID_PASSWORD_CHARS_REGEX: Lazy<Regex> = Lazy::new(|| {
 
 impl AuthService {
     pub fn new(jwt_secret: String) -> Self {
-        Self { jwt_secret }
+        Self { jwt_secret, session_policy: SessionPolicy::default() }
+    }
+
+    pub fn with_session_policy(mut self, session_policy: SessionPolicy) -> Self {
+        self.session_policy = session_policy;
+        self
     }
 
     pub fn generate_token(&self, user_id: Uuid, email: &str) -> Result<String, AppError> {
         let now = Utc::now();
         let expiration = now
-            .checked_add_signed(Duration::hours(24))
+            .checked_add_signed(self.session_policy.absolute_timeout)
             .expect("valid timestamp")
             .timestamp() as usize;
         let issued_at = now.timestamp() as usize;
@@ -285,6 +293,8 @@ pub async fn signup(
             e
         })?;
 
+    SessionTracker::start_session(db.as_ref().as_ref(), user.id, &token, &http_req, &auth_service.session_policy, Utc::now()).await?;
+
     // Store session information
     session.insert("user_id", user.id.to_string())
         .map_err(|_| AppError::InternalServerError)?;
@@ -304,7 +314,7 @@ pub async fn signup(
 
     let cookie = Cookie::build("auth_token", token.clone())
         .path("/")
-        .max_age(actix_web::cookie::time::Duration::hours(24))
+        .max_age(actix_web::cookie::time::Duration::seconds(auth_service.session_policy.absolute_timeout.num_seconds()))
         .http_only(true)
         .secure(false) // Set to true in production with HTTPS
         .same_site(SameSite::Strict)
@@ -356,6 +366,8 @@ pub async fn login(
     // Generate JWT token
     let token = auth_service.generate_token(user.id, &user.email)?;
 
+    SessionTracker::start_session(db.as_ref().as_ref(), user.id, &token, &http_req, &auth_service.session_policy, Utc::now()).await?;
+
     // Store session information
     session.insert("user_id", user.id.to_string())
         .map_err(|_| AppError::InternalServerError)?;
@@ -375,7 +387,7 @@ pub async fn login(
         .cookie(
             Cookie::build("auth_token", token)
                 .path("/")
-                .max_age(actix_web::cookie::time::Duration::hours(24))
+                .max_age(actix_web::cookie::time::Duration::seconds(auth_service.session_policy.absolute_timeout.num_seconds()))
                 .http_only(true)
                 .secure(false) // Set to true in production with HTTPS
                 .same_site(SameSite::Strict)
@@ -16,6 +16,7 @@ use crate::models::backtest_result::{
     BacktestResultDetailResponse,
     Model as BacktestResultModel,
 };
+use crate::services::FxRateService;
 use crate::utils::errors::AppError;
 
 /// Authenticate user from various sources (token, cookie, session)
@@ -71,11 +72,12 @@ async fn authenticate_user(req: &HttpRequest) -> Result<Uuid, AppError> {
     Err(AppError::MissingToken)
 }
 
-/// Get user's backtest results with pagination
+/// Get user's backtest results, filtered, sorted and paginated
 pub async fn get_user_backtest_results(
     db: web::Data<Arc<DatabaseConnection>>,
     req: HttpRequest,
     query: web::Query<BacktestListQuery>,
+    fx_rates: web::Data<FxRateService>,
 ) -> Result<HttpResponse, AppError> {
     // Get user ID from request extensions (set by auth middleware) or authenticate
     let user_id_value = if let Some(user_id) = req.extensions().get::<Uuid>().copied() {
@@ -88,13 +90,34 @@ pub async fn get_user_backtest_results(
         })?
     };
 
-    let page = query.page.unwrap_or(1).max(1);
-    let limit = query.limit.unwrap_or(20).min(100).max(1); // Max 100, min 1
-    let offset = (page - 1) * limit;
+    let mut response = list_backtest_results(db.get_ref().as_ref(), user_id_value, &query).await?;
+
+    // Stored results stay in USD; convert only what goes back to the user
+    let rate = fx_rates.rate_for_user(db.get_ref().as_ref(), user_id_value).await?;
+    response.results = response.results.into_iter().map(|result| result.valued_in(rate.clone())).collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Most results returned in one page
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// One page of the user's results matching `query`
+async fn list_backtest_results(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    query: &BacktestListQuery,
+) -> Result<BacktestListResponse, AppError> {
+    let limit = u64::from(query.limit.unwrap_or(20).clamp(1, MAX_PAGE_SIZE));
+    // An explicit offset wins over page-based paging
+    let offset = match query.offset {
+        Some(offset) => u64::from(offset),
+        None => u64::from(query.page.unwrap_or(1).max(1) - 1) * limit,
+    };
 
     // Build query with optional filters
     let mut select = BacktestResultEntity::find()
-        .filter(crate::models::backtest_result::Column::UserId.eq(user_id_value));
+        .filter(crate::models::backtest_result::Column::UserId.eq(user_id));
 
     if let Some(ref strategy) = query.strategy_name {
         select = select.filter(crate::models::backtest_result::Column::StrategyName.eq(strategy));
@@ -108,34 +131,30 @@ pub async fn get_user_backtest_results(
         select = select.filter(crate::models::backtest_result::Column::Status.eq(status));
     }
 
-    // Get total count for pagination
-    let total = select.clone().count(db.get_ref().as_ref()).await
+    let total_count = select.clone().count(db).await
         .map_err(AppError::DatabaseError)?;
 
-    // Get paginated results
+    // Ties fall back to newest first, then id, so pages never overlap
+    let select = match query.sort_by.unwrap_or_default() {
+        BacktestSortBy::CreatedAt => select,
+        BacktestSortBy::TotalReturn => select.order_by_desc(crate::models::backtest_result::Column::TotalReturn),
+    };
     let results = select
         .order_by_desc(crate::models::backtest_result::Column::CreatedAt)
-        .offset(Some(offset as u64))
-        .limit(Some(limit as u64))
-        .all(db.get_ref().as_ref())
+        .order_by_asc(crate::models::backtest_result::Column::Id)
+        .offset(Some(offset))
+        .limit(Some(limit))
+        .all(db)
         .await
         .map_err(AppError::DatabaseError)?;
 
-    let backtest_responses: Vec<BacktestResultResponse> = results.into_iter()
-        .map(BacktestResultResponse::from)
-        .collect();
-
-    let response = BacktestListResponse {
-        results: backtest_responses,
-        pagination: PaginationInfo {
-            page,
-            limit,
-            total: total as u32,
-            total_pages: ((total as f64) / (limit as f64)).ceil() as u32,
-        },
-    };
+    let has_more = offset + (results.len() as u64) < total_count;
 
-    Ok(HttpResponse::Ok().json(response))
+    Ok(BacktestListResponse {
+        results: results.into_iter().map(BacktestResultResponse::from).collect(),
+        total_count,
+        has_more,
+    })
 }
 
 /// Get specific backtest result with full details
@@ -143,6 +162,7 @@ pub async fn get_backtest_result_detail(
     db: web::Data<Arc<DatabaseConnection>>,
     req: HttpRequest,
     path: web::Path<Uuid>,
+    fx_rates: web::Data<FxRateService>,
 ) -> Result<HttpResponse, AppError> {
     // Get user ID from request extensions (set by auth middleware)
     let user_id_value = req.extensions()
@@ -162,7 +182,8 @@ pub async fn get_backtest_result_detail(
         .map_err(AppError::DatabaseError)?
         .ok_or_else(|| AppError::NotFound("Backtest result not found".to_string()))?;
 
-    let response = BacktestResultDetailResponse::from(result);
+    let rate = fx_rates.rate_for_user(db.get_ref().as_ref(), user_id_value).await?;
+    let response = BacktestResultDetailResponse::from(result).valued_in(rate).rounded();
     Ok(HttpResponse::Ok().json(response))
 }
 
@@ -203,6 +224,47 @@ pub async fn delete_backtest_result(
     })))
 }
 
+/// Compare two stored backtest results: metric deltas, added/removed trades and
+/// where the equity curves diverge
+pub async fn diff_backtest_results(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    request: web::Json<BacktestDiffRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id_value = if let Some(user_id) = req.extensions().get::<Uuid>().copied() {
+        user_id
+    } else {
+        authenticate_user(&req).await.map_err(|e| {
+            tracing::error!("Authentication failed for backtest diff: {:?}", e);
+            AppError::Unauthorized("Authentication required".to_string())
+        })?
+    };
+
+    let mut results = BacktestResultEntity::find()
+        .filter(crate::models::backtest_result::Column::Id.is_in([request.base_id, request.compare_id]))
+        .filter(crate::models::backtest_result::Column::UserId.eq(user_id_value))
+        .all(db.get_ref().as_ref())
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let mut take = |id: Uuid| {
+        results
+            .iter()
+            .position(|result| result.id == id)
+            .map(|index| results.swap_remove(index))
+            .ok_or_else(|| AppError::NotFound(format!("Backtest result {} not found", id)))
+    };
+    let base = take(request.base_id)?;
+    let compare = if request.compare_id == request.base_id {
+        base.clone()
+    } else {
+        take(request.compare_id)?
+    };
+
+    let diff = crate::backtesting::diff::diff_results(&base, &compare)?;
+    Ok(HttpResponse::Ok().json(diff))
+}
+
 /// Create/save a new backtest result
 pub async fn save_backtest_result(
     db: web::Data<Arc<DatabaseConnection>>,
@@ -245,6 +307,7 @@ pub async fn save_backtest_result(
         drawdown_curve: Set(request.drawdown_curve),
         status: Set(request.status),
         error_message: Set(request.error_message),
+        checkpoint_json: Set(None),
         execution_time_ms: Set(request.execution_time_ms),
         created_at: Set(Utc::now()),
         updated_at: Set(Utc::now()),
@@ -352,10 +415,11 @@ pub async fn update_backtest_results(
     // Store detailed data as JSON
     active_model.trades_data = Set(serde_json::to_value(&engine_result.trades).unwrap_or(serde_json::json!([])));
     active_model.equity_curve = Set(serde_json::to_value(&engine_result.performance_chart).unwrap_or(serde_json::json!([])));
-    active_model.drawdown_curve = Set(serde_json::json!([])); // Could be calculated from performance_chart if needed
+    active_model.drawdown_curve = Set(serde_json::to_value(&engine_result.drawdown_curve).unwrap_or(serde_json::json!([])));
 
-    // Update status and timing
+    // Update status and timing; a finished run has nothing left to resume
     active_model.status = Set("completed".to_string());
+    active_model.checkpoint_json = Set(None);
     active_model.execution_time_ms = Set(Some(execution_time_ms));
     active_model.updated_at = Set(Utc::now());
 
@@ -370,27 +434,45 @@ use serde::{Deserialize, Serialize};
 use rust_decimal::Decimal;
 use chrono::{DateTime, Utc as ChronoUtc};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct BacktestListQuery {
+    /// 1-based page of `limit` results; ignored when `offset` is given
     pub page: Option<u32>,
     pub limit: Option<u32>,
+    /// Results to skip
+    pub offset: Option<u32>,
     pub strategy_name: Option<String>,
     pub symbol: Option<String>,
     pub status: Option<String>,
+    pub sort_by: Option<BacktestSortBy>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct BacktestListResponse {
-    pub results: Vec<BacktestResultResponse>,
-    pub pagination: PaginationInfo,
+/// Order of the results listing, always descending
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BacktestSortBy {
+    /// Newest first
+    #[default]
+    CreatedAt,
+    /// Highest total return first
+    TotalReturn,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BacktestDiffRequest {
+    /// The earlier run
+    pub base_id: Uuid,
+    /// The run to compare against it
+    pub compare_id: Uuid,
 }
 
 #[derive(Debug, Serialize)]
-pub struct PaginationInfo {
-    pub page: u32,
-    pub limit: u32,
-    pub total: u32,
-    pub total_pages: u32,
+pub struct BacktestListResponse {
+    pub results: Vec<BacktestResultResponse>,
+    /// Results matching the filters across all pages
+    pub total_count: u64,
+    /// Further results exist past this page
+    pub has_more: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -427,4 +509,156 @@ pub struct CreateBacktestResultRequest {
     pub status: String,
     pub error_message: Option<String>,
     pub execution_time_ms: Option<i64>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{seed_backtest_result, seed_user, test_db};
+
+    async fn seed_result(db: &DatabaseConnection, user_id: Uuid, strategy_name: &str, total_return: i64) -> Uuid {
+        let id = seed_backtest_result(db, user_id).await;
+        let mut result: BacktestResultActiveModel = BacktestResultEntity::find_by_id(id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        result.strategy_name = Set(strategy_name.to_string());
+        result.total_return = Set(Decimal::from(total_return));
+        result.update(db).await.unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_strategy_name_and_pages() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let other_user = seed_user(&db).await;
+        seed_result(&db, user_id, "rsi", 10).await;
+        seed_result(&db, user_id, "rsi", 20).await;
+        seed_result(&db, user_id, "sma_crossover", 30).await;
+        seed_result(&db, other_user, "rsi", 40).await;
+
+        let query = BacktestListQuery { strategy_name: Some("rsi".to_string()), ..Default::default() };
+        let listed = list_backtest_results(&db, user_id, &query).await.unwrap();
+        assert_eq!(listed.total_count, 2);
+        assert!(!listed.has_more);
+        assert!(listed.results.iter().all(|r| r.strategy_name == "rsi"));
+
+        let query = BacktestListQuery { strategy_name: Some("rsi".to_string()), limit: Some(1), ..Default::default() };
+        let first = list_backtest_results(&db, user_id, &query).await.unwrap();
+        assert_eq!((first.results.len(), first.total_count, first.has_more), (1, 2, true));
+
+        let query = BacktestListQuery {
+            strategy_name: Some("rsi".to_string()),
+            limit: Some(1),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let second = list_backtest_results(&db, user_id, &query).await.unwrap();
+        assert_eq!((second.results.len(), second.has_more), (1, false));
+        assert_ne!(first.results[0].id, second.results[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_list_sorts_by_total_return() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        seed_result(&db, user_id, "rsi", 5).await;
+        seed_result(&db, user_id, "rsi", -2).await;
+        seed_result(&db, user_id, "rsi", 12).await;
+
+        let query = BacktestListQuery { sort_by: Some(BacktestSortBy::TotalReturn), ..Default::default() };
+        let listed = list_backtest_results(&db, user_id, &query).await.unwrap();
+
+        let returns: Vec<Decimal> = listed.results.iter().map(|r| r.total_return).collect();
+        assert_eq!(returns, vec![Decimal::from(12), Decimal::from(5), Decimal::from(-2)]);
+    }
+
+    #[tokio::test]
+    async fn test_stored_trades_keep_every_digit_and_round_in_the_response() {
+        use crate::backtesting::{BacktestTrade, TradeType};
+        use std::str::FromStr;
+
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let id = seed_backtest_result(&db, user_id).await;
+        let trade = BacktestTrade {
+            timestamp: Utc::now(),
+            trade_type: TradeType::Buy,
+            price: Decimal::from_str("43210.123456789").unwrap(),
+            quantity: Decimal::from_str("0.0023142288452").unwrap(),
+            total_value: Decimal::from_str("100.004").unwrap(),
+            portfolio_value: Decimal::from_str("1000.005").unwrap(),
+            balance_remaining: Decimal::from_str("899.996").unwrap(),
+            reason: "DCA buy".to_string(),
+            pnl: None,
+            pnl_percentage: None,
+        };
+        let mut result: BacktestResultActiveModel = BacktestResultEntity::find_by_id(id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        result.trades_data = Set(serde_json::to_value(vec![trade]).unwrap());
+        result.update(&db).await.unwrap();
+
+        let stored = BacktestResultEntity::find_by_id(id).one(&db).await.unwrap().unwrap();
+        assert_eq!(stored.trades_data[0]["price"], "43210.123456789");
+        assert_eq!(stored.trades_data[0]["total_value"], "100.004");
+
+        let response = serde_json::to_value(BacktestResultDetailResponse::from(stored).rounded()).unwrap();
+        let trade = &response["trades_data"][0];
+        assert_eq!(trade["price"], "43210.12345679");
+        assert_eq!(trade["quantity"], "0.00231423");
+        assert_eq!(trade["total_value"], "100.00");
+        assert_eq!(trade["portfolio_value"], "1000.01");
+    }
+
+    #[tokio::test]
+    async fn test_saved_results_are_valued_in_the_base_currency() {
+        use crate::services::fx_rates::FxRate;
+
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let id = seed_backtest_result(&db, user_id).await;
+        let mut result: BacktestResultActiveModel = BacktestResultEntity::find_by_id(id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        result.initial_balance = Set(Decimal::from(1000));
+        result.total_return = Set(Decimal::from(50));
+        result.total_return_percentage = Set(Decimal::from(5));
+        result.equity_curve = Set(serde_json::json!([{
+            "timestamp": Utc::now(),
+            "portfolio_value": "1050",
+            "asset_price": "40000",
+        }]));
+        result.update(&db).await.unwrap();
+
+        let eur = FxRate { base_currency: "EUR".to_string(), usd_rate: Decimal::new(9, 1), as_of: Utc::now() };
+        let stored = BacktestResultEntity::find_by_id(id).one(&db).await.unwrap().unwrap();
+
+        let summary = BacktestResultResponse::from(stored.clone()).valued_in(eur.clone());
+        assert_eq!(summary.initial_balance, Decimal::from(900));
+        assert_eq!(summary.total_return, Decimal::from(45));
+        assert_eq!(summary.total_return_percentage, Decimal::from(5));
+        assert_eq!(summary.valuation.as_ref().map(|rate| rate.base_currency.as_str()), Some("EUR"));
+
+        let detail = serde_json::to_value(BacktestResultDetailResponse::from(stored).valued_in(eur).rounded()).unwrap();
+        assert_eq!(detail["equity_curve"][0]["portfolio_value"], "945.00");
+        assert_eq!(detail["valuation"]["base_currency"], "EUR");
+    }
+
+    #[test]
+    fn test_sort_by_parses_from_the_query_string() {
+        let query = web::Query::<BacktestListQuery>::from_query("sort_by=total_return&offset=20").unwrap();
+        assert_eq!(query.sort_by, Some(BacktestSortBy::TotalReturn));
+        assert_eq!(query.offset, Some(20));
+        assert!(web::Query::<BacktestListQuery>::from_query("sort_by=name").is_err());
+    }
+}
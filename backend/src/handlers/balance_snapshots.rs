@@ -0,0 +1,77 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use chrono::NaiveDate;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::balance_snapshot::{self, BackfillSnapshotsRequest};
+use crate::services::SnapshotBackfillService;
+use crate::utils::errors::AppError;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// List the user's balance snapshots, oldest first
+pub async fn get_balance_snapshots(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    query: web::Query<SnapshotQuery>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    let mut select = balance_snapshot::Entity::find()
+        .filter(balance_snapshot::Column::UserId.eq(user_id));
+    if let Some(start_date) = query.start_date {
+        select = select.filter(balance_snapshot::Column::SnapshotDate.gte(start_date));
+    }
+    if let Some(end_date) = query.end_date {
+        select = select.filter(balance_snapshot::Column::SnapshotDate.lte(end_date));
+    }
+
+    let snapshots = select
+        .order_by_asc(balance_snapshot::Column::SnapshotDate)
+        .all(db.as_ref().as_ref())
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    Ok(HttpResponse::Ok().json(snapshots))
+}
+
+/// Reconstruct snapshots for past dates from trade history. Dates that already have a
+/// snapshot are skipped, so the request can be repeated to resume an interrupted run.
+pub async fn backfill_balance_snapshots(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    body: web::Json<BackfillSnapshotsRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    let service = SnapshotBackfillService::new(db.get_ref().clone());
+    let summary = service
+        .backfill(user_id, body.start_date, body.end_date)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
@@ -1,7 +1,7 @@
 use actix_web::{web, HttpRequest, HttpResponse, Result, HttpMessage};
 use actix_session::{Session, SessionExt};
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, QueryOrder, QuerySelect};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, QueryOrder, QuerySelect, TransactionTrait};
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
@@ -16,10 +16,17 @@ use crate::models::dca_strategy::{
     DCAStrategyResponse, DCAStrategiesResponse, DCAExecutionResponse,
     DCAStatus,
 };
-use crate::services::{DCAExecutionEngine, MarketDataService};
+use crate::handlers::kill_switch::ensure_kill_switch_clear;
+use crate::services::{ensure_symbol_allowed, DCAExecutionEngine, MarketDataService};
+use crate::strategies::core::StrategyMode;
+use crate::strategies::implementations::dca::{DCAConfig, presets::DCAPresets};
 use crate::utils::errors::AppError;
+use crate::utils::versioning::{expected_version, update_versioned};
 use crate::handlers::AuthService;
 
+/// Registry ID of the DCA strategy
+pub(crate) const DCA_STRATEGY_ID: &str = "dca_v2";
+
 /// Extract authenticated user ID from session
 fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
     let session = req.get_session();
@@ -37,23 +44,28 @@ fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
     Err(AppError::Unauthorized("Authentication required".to_string()))
 }
 
-/// Create a new DCA strategy
-pub async fn create_dca_strategy(
-    db: web::Data<Arc<DatabaseConnection>>,
-    req: HttpRequest,
-    body: web::Json<CreateDCAStrategyRequest>,
-) -> Result<HttpResponse, AppError> {
-    // Get user ID from session
-    let user_id = get_user_id_from_session(&req)?;
+/// Insert a new strategy and read it back in a single transaction.
+///
+/// A symbol the user may not trade is refused up front. The name-uniqueness check, the
+/// insert and the read-back either all take effect or, if any step fails (including a
+/// stored config that no longer parses), none do.
+pub(crate) async fn persist_new_dca_strategy(
+    db: &DatabaseConnection,
+    new_strategy: DCAStrategyActiveModel,
+) -> Result<crate::models::dca_strategy::Model, AppError> {
+    let user_id = new_strategy.user_id.clone().unwrap();
+    let name = new_strategy.name.clone().unwrap();
+    let strategy_id = new_strategy.id.clone().unwrap();
 
-    // Validate request
-    body.validate().map_err(AppError::ValidationError)?;
+    ensure_symbol_allowed(db, user_id, new_strategy.asset_symbol.as_ref()).await?;
+
+    let txn = db.begin().await.map_err(AppError::DatabaseError)?;
 
     // Check if user already has a strategy with this name
     let existing_strategy = DCAStrategyEntity::find()
         .filter(crate::models::dca_strategy::Column::UserId.eq(user_id))
-        .filter(crate::models::dca_strategy::Column::Name.eq(&body.name))
-        .one(db.as_ref().as_ref())
+        .filter(crate::models::dca_strategy::Column::Name.eq(&name))
+        .one(&txn)
         .await
         .map_err(AppError::DatabaseError)?;
 
@@ -61,30 +73,48 @@ pub async fn create_dca_strategy(
         return Err(AppError::BadRequest("Strategy with this name already exists".to_string()));
     }
 
+    // Insert without returning (to avoid UnpackInsertId error)
+    DCAStrategyEntity::insert(new_strategy)
+        .exec_without_returning(&txn)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    // Fetch the created strategy
+    let strategy = DCAStrategyEntity::find_by_id(strategy_id)
+        .one(&txn)
+        .await
+        .map_err(AppError::DatabaseError)?
+        .ok_or(AppError::InternalServerError)?;
+
+    strategy.get_dca_config().map_err(AppError::BadRequest)?;
+
+    txn.commit().await.map_err(AppError::DatabaseError)?;
+
+    Ok(strategy)
+}
+
+/// Validate `config` and build a new, active strategy row from it
+pub(crate) fn new_dca_strategy_model(
+    user_id: Uuid,
+    name: &str,
+    asset_symbol: &str,
+    config: &DCAConfig,
+) -> Result<DCAStrategyActiveModel, AppError> {
     // Validate DCAConfig
-    body.config.validate().map_err(|e| AppError::BadRequest(format!("Invalid DCAConfig: {}", e)))?;
+    config.validate().map_err(|e| AppError::BadRequest(format!("Invalid DCAConfig: {}", e)))?;
 
     // Serialize DCAConfig to JSON
-    let config_json = serde_json::to_string(&body.config)
+    let config_json = serde_json::to_string(config)
         .map_err(|e| AppError::BadRequest(format!("Failed to serialize DCAConfig: {}", e)))?;
 
-    // Calculate initial next execution time based on strategy frequency
-    use crate::strategies::implementations::dca::DCAFrequency;
-    let next_execution_at = match body.config.frequency {
-        DCAFrequency::Hourly(hours) => Utc::now() + chrono::Duration::hours(hours as i64),
-        DCAFrequency::Daily(days) => Utc::now() + chrono::Duration::days(days as i64),
-        DCAFrequency::Weekly(weeks) => Utc::now() + chrono::Duration::weeks(weeks as i64),
-        DCAFrequency::Monthly(months) => Utc::now() + chrono::Duration::days((months * 30) as i64),
-        DCAFrequency::Custom(minutes) => Utc::now() + chrono::Duration::minutes(minutes as i64),
-    };
+    // First purchase is one interval (or the next calendar date) from now
+    let next_execution_at = config.next_purchase_after(Utc::now());
 
-    // Create the strategy
-    let strategy_id = Uuid::new_v4();
-    let new_strategy = DCAStrategyActiveModel {
-        id: Set(strategy_id),
+    Ok(DCAStrategyActiveModel {
+        id: Set(Uuid::new_v4()),
         user_id: Set(user_id),
-        name: Set(body.name.clone()),
-        asset_symbol: Set(body.asset_symbol.to_uppercase()),
+        name: Set(name.to_string()),
+        asset_symbol: Set(asset_symbol.to_uppercase()),
         status: Set(DCAStatus::Active.into()),
         config_json: Set(config_json),
         total_invested: Set(Decimal::ZERO),
@@ -92,22 +122,42 @@ pub async fn create_dca_strategy(
         average_buy_price: Set(None),
         last_execution_at: Set(None),
         next_execution_at: Set(Some(next_execution_at)),
+        version: Set(1),
+        state_json: Set(None),
+        kill_switch: Set(false),
+        mode: Set(StrategyMode::Live.as_str().to_string()),
         created_at: Set(Utc::now()),
         updated_at: Set(Utc::now()),
-    };
+    })
+}
 
-    // Insert without returning (to avoid UnpackInsertId error)
-    DCAStrategyEntity::insert(new_strategy)
-        .exec_without_returning(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
+/// Mode requested for a new strategy, live unless `paper` is asked for
+fn requested_mode(mode: Option<&str>) -> Result<StrategyMode, AppError> {
+    match mode {
+        None => Ok(StrategyMode::Live),
+        Some(mode) => StrategyMode::parse_execution_mode(mode).ok_or_else(|| {
+            AppError::BadRequest(format!("Invalid mode '{}': expected 'live' or 'paper'", mode))
+        }),
+    }
+}
 
-    // Fetch the created strategy
-    let strategy = DCAStrategyEntity::find_by_id(strategy_id)
-        .one(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?
-        .ok_or(AppError::InternalServerError)?;
+/// Create a new DCA strategy
+pub async fn create_dca_strategy(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    body: web::Json<CreateDCAStrategyRequest>,
+) -> Result<HttpResponse, AppError> {
+    // Get user ID from session
+    let user_id = get_user_id_from_session(&req)?;
+
+    // Validate request
+    body.validate().map_err(AppError::ValidationError)?;
+
+    let mode = requested_mode(body.mode.as_deref())?;
+    let mut new_strategy = new_dca_strategy_model(user_id, &body.name, &body.asset_symbol, &body.config)?;
+    new_strategy.mode = Set(mode.as_str().to_string());
+
+    let strategy = persist_new_dca_strategy(db.as_ref().as_ref(), new_strategy).await?;
 
     // Convert to response format
     let response = DCAStrategyResponse {
@@ -116,7 +166,9 @@ pub async fn create_dca_strategy(
         name: strategy.name.clone(),
         asset_symbol: strategy.asset_symbol.clone(),
         status: strategy.status.clone(),
+        mode: strategy.mode.clone(),
         config: strategy.get_dca_config().map_err(|e| AppError::BadRequest(e))?,
+        version: strategy.version,
         total_invested: strategy.total_invested,
         total_purchased: strategy.total_purchased,
         average_buy_price: strategy.average_buy_price,
@@ -177,20 +229,7 @@ pub async fn get_dca_strategies(
             .map_err(AppError::DatabaseError)?;
 
         let execution_responses: Vec<DCAExecutionResponse> = recent_executions.into_iter()
-            .map(|exec| DCAExecutionResponse {
-                id: exec.id,
-                strategy_id: exec.strategy_id,
-                execution_type: exec.execution_type,
-                trigger_reason: exec.trigger_reason,
-                amount_usd: exec.amount_usd,
-                amount_asset: exec.amount_asset,
-                price_at_execution: exec.price_at_execution,
-                fear_greed_index: exec.fear_greed_index,
-                market_volatility: exec.market_volatility,
-                order_status: exec.order_status,
-                execution_timestamp: exec.execution_timestamp,
-                error_message: exec.error_message,
-            })
+            .map(DCAExecutionResponse::from)
             .collect();
 
         // Calculate current P&L if we have positions - with graceful error handling
@@ -239,7 +278,9 @@ pub async fn get_dca_strategies(
             name: strategy.name,
             asset_symbol: strategy.asset_symbol,
             status: strategy.status,
+            mode: strategy.mode,
             config: config,
+            version: strategy.version,
             total_invested: strategy.total_invested,
             total_purchased: strategy.total_purchased,
             average_buy_price: strategy.average_buy_price,
@@ -299,20 +340,7 @@ pub async fn get_dca_strategy(
         .map_err(AppError::DatabaseError)?;
 
     let execution_responses: Vec<DCAExecutionResponse> = recent_executions.into_iter()
-        .map(|exec| DCAExecutionResponse {
-            id: exec.id,
-            strategy_id: exec.strategy_id,
-            execution_type: exec.execution_type,
-            trigger_reason: exec.trigger_reason,
-            amount_usd: exec.amount_usd,
-            amount_asset: exec.amount_asset,
-            price_at_execution: exec.price_at_execution,
-            fear_greed_index: exec.fear_greed_index,
-            market_volatility: exec.market_volatility,
-            order_status: exec.order_status,
-            execution_timestamp: exec.execution_timestamp,
-            error_message: exec.error_message,
-        })
+        .map(DCAExecutionResponse::from)
         .collect();
 
     // Calculate current P&L
@@ -345,7 +373,9 @@ pub async fn get_dca_strategy(
         name: strategy.name.clone(),
         asset_symbol: strategy.asset_symbol.clone(),
         status: strategy.status.clone(),
+        mode: strategy.mode.clone(),
         config: strategy.get_dca_config().unwrap_or_else(|_| Default::default()),
+        version: strategy.version,
         total_invested: strategy.total_invested,
         total_purchased: strategy.total_purchased,
         average_buy_price: strategy.average_buy_price,
@@ -364,6 +394,7 @@ pub async fn get_dca_strategy(
 /// Update a DCA strategy
 pub async fn update_dca_strategy(
     db: web::Data<Arc<DatabaseConnection>>,
+    execution_engine: web::Data<DCAExecutionEngine>,
     req: HttpRequest,
     path: web::Path<Uuid>,
     body: web::Json<UpdateDCAStrategyRequest>,
@@ -395,9 +426,14 @@ pub async fn update_dca_strategy(
         strategy.name = Set(name.clone());
     }
 
-    // Update status if provided
+    // Update status if provided; a kill-switched strategy can't be made active
+    let previous_status = strategy.status.as_ref().clone();
     if let Some(status) = &body.status {
-        strategy.status = Set(status.clone().into());
+        let status: String = status.clone().into();
+        if status == "active" {
+            ensure_kill_switch_clear(strategy_id, *strategy.kill_switch.as_ref())?;
+        }
+        strategy.status = Set(status);
     }
 
     // Update config if provided
@@ -414,10 +450,22 @@ pub async fn update_dca_strategy(
 
     strategy.updated_at = Set(Utc::now());
 
-    // Save changes
-    let updated_strategy = strategy.update(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
+    // Save changes, rejecting the write if another client updated the strategy first
+    let expected = expected_version(&req, body.expected_version)?;
+    let updated_strategy = update_versioned(
+        db.as_ref().as_ref(),
+        strategy,
+        strategy_id,
+        crate::models::dca_strategy::Column::Id,
+        crate::models::dca_strategy::Column::Version,
+        expected,
+    ).await?;
+
+    if updated_strategy.status != previous_status {
+        execution_engine
+            .strategy_status_changed(user_id, strategy_id, &updated_strategy.status)
+            .await;
+    }
 
     // Convert to response format
     let response = DCAStrategyResponse {
@@ -426,7 +474,9 @@ pub async fn update_dca_strategy(
         name: updated_strategy.name.clone(),
         asset_symbol: updated_strategy.asset_symbol.clone(),
         status: updated_strategy.status.clone(),
+        mode: updated_strategy.mode.clone(),
         config: updated_strategy.get_dca_config().unwrap_or_else(|_| Default::default()),
+        version: updated_strategy.version,
         total_invested: updated_strategy.total_invested,
         total_purchased: updated_strategy.total_purchased,
         average_buy_price: updated_strategy.average_buy_price,
@@ -504,7 +554,7 @@ pub async fn execute_dca_strategy(
         .and_then(|s| s.parse::<Decimal>().ok());
 
     // Queue the strategy for manual execution
-    execution_engine.queue_manual_execution(strategy_id, manual_amount).await?;
+    execution_engine.queue_manual_execution(strategy_id, user_id, manual_amount).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Strategy queued for execution",
@@ -533,7 +583,6 @@ pub async fn get_dca_presets(
     // Verify authentication
     get_user_id_from_session(&req)?;
 
-    use crate::strategies::implementations::dca::presets::DCAPresets;
     use serde_json::json;
 
     let presets = DCAPresets::get_all_presets()
@@ -588,7 +637,7 @@ pub async fn get_dca_presets(
     })))
 }
 
-/// Create a DCA strategy from a preset
+/// Create a DCA strategy from a preset, scaled to the requested base amount
 pub async fn create_dca_strategy_from_preset(
     db: web::Data<Arc<DatabaseConnection>>,
     req: HttpRequest,
@@ -600,76 +649,12 @@ pub async fn create_dca_strategy_from_preset(
     // Validate request
     body.validate().map_err(AppError::ValidationError)?;
 
-    use crate::strategies::implementations::dca::presets::DCAPresets;
-
-    // Generate config from preset
-    let config = match body.preset_id.as_str() {
-        "conservative" => DCAPresets::conservative(body.base_amount),
-        "aggressive_rsi" => DCAPresets::aggressive_rsi(body.base_amount),
-        "volatility_hunter" => DCAPresets::volatility_hunter(body.base_amount),
-        "dip_buyer" => DCAPresets::dip_buyer(body.base_amount),
-        "balanced_dynamic" => DCAPresets::balanced_dynamic(body.base_amount),
-        "weekend_warrior" => DCAPresets::weekend_warrior(body.base_amount),
-        "business_hours" => DCAPresets::business_hours(body.base_amount),
-        "bear_market_hunter" => DCAPresets::bear_market_hunter(body.base_amount),
-        "micro_dca" => DCAPresets::micro_dca(body.base_amount),
-        "risk_managed" => {
-            let max_position = body.max_position_size
-                .ok_or_else(|| AppError::BadRequest("max_position_size required for risk_managed preset".to_string()))?;
-            DCAPresets::risk_managed(body.base_amount, max_position)
-        },
-        _ => return Err(AppError::BadRequest("Invalid preset_id".to_string())),
-    };
-
-    // Check if user already has a strategy with this name
-    let existing_strategy = DCAStrategyEntity::find()
-        .filter(crate::models::dca_strategy::Column::UserId.eq(user_id))
-        .filter(crate::models::dca_strategy::Column::Name.eq(&body.name))
-        .one(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
-
-    if existing_strategy.is_some() {
-        return Err(AppError::BadRequest("Strategy with this name already exists".to_string()));
+    let config = DCAPresets::build(&body.preset_id, body.base_amount, body.max_position_size)?;
+    let mut new_strategy = new_dca_strategy_model(user_id, &body.name, &body.symbol, &config)?;
+    if body.is_paper_trading == Some(true) {
+        new_strategy.mode = Set(StrategyMode::Paper.as_str().to_string());
     }
-
-    // Validate generated config
-    config.validate().map_err(|e| AppError::BadRequest(format!("Preset generated invalid config: {}", e)))?;
-
-    // Calculate next execution time based on frequency
-    use crate::strategies::implementations::dca::DCAFrequency;
-    let next_execution_at = match config.frequency {
-        DCAFrequency::Hourly(hours) => Utc::now() + chrono::Duration::hours(hours as i64),
-        DCAFrequency::Daily(days) => Utc::now() + chrono::Duration::days(days as i64),
-        DCAFrequency::Weekly(weeks) => Utc::now() + chrono::Duration::weeks(weeks as i64),
-        DCAFrequency::Monthly(months) => Utc::now() + chrono::Duration::days((months * 30) as i64),
-        DCAFrequency::Custom(minutes) => Utc::now() + chrono::Duration::minutes(minutes as i64),
-    };
-
-    // Serialize config to JSON
-    let config_json = serde_json::to_string(&config)
-        .map_err(|e| AppError::BadRequest(format!("Failed to serialize config: {}", e)))?;
-
-    // Create the strategy
-    let new_strategy = DCAStrategyActiveModel {
-        id: Set(Uuid::new_v4()),
-        user_id: Set(user_id),
-        name: Set(body.name.clone()),
-        asset_symbol: Set(body.symbol.clone()),
-        status: Set("active".to_string()),
-        config_json: Set(config_json),
-        total_invested: Set(Decimal::ZERO),
-        total_purchased: Set(Decimal::ZERO),
-        average_buy_price: Set(None),
-        last_execution_at: Set(None),
-        next_execution_at: Set(Some(next_execution_at)),
-        created_at: Set(Utc::now()),
-        updated_at: Set(Utc::now()),
-    };
-
-    let strategy = new_strategy.insert(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
+    let strategy = persist_new_dca_strategy(db.as_ref().as_ref(), new_strategy).await?;
 
     Ok(HttpResponse::Created().json(serde_json::json!({
         "message": "DCA strategy created successfully from preset",
@@ -678,9 +663,72 @@ pub async fn create_dca_strategy_from_preset(
             "name": strategy.name,
             "asset_symbol": strategy.asset_symbol,
             "status": strategy.status,
+            "mode": strategy.mode,
             "preset_id": body.preset_id,
             "base_amount": body.base_amount,
             "created_at": strategy.created_at,
         }
     })))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{seed_user, test_db};
+
+    fn new_strategy(user_id: Uuid, name: &str, config_json: String) -> DCAStrategyActiveModel {
+        DCAStrategyActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            name: Set(name.to_string()),
+            asset_symbol: Set("BTC".to_string()),
+            status: Set(DCAStatus::Active.into()),
+            config_json: Set(config_json),
+            total_invested: Set(Decimal::ZERO),
+            total_purchased: Set(Decimal::ZERO),
+            average_buy_price: Set(None),
+            last_execution_at: Set(None),
+            next_execution_at: Set(None),
+            version: Set(1),
+            state_json: Set(None),
+            kill_switch: Set(false),
+            mode: Set(StrategyMode::Live.as_str().to_string()),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+        }
+    }
+
+    async fn strategies_for(db: &DatabaseConnection, user_id: Uuid) -> usize {
+        DCAStrategyEntity::find()
+            .filter(crate::models::dca_strategy::Column::UserId.eq(user_id))
+            .all(db)
+            .await
+            .unwrap()
+            .len()
+    }
+
+    #[tokio::test]
+    async fn test_persist_new_dca_strategy_commits() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let config_json = serde_json::to_string(&crate::strategies::implementations::dca::DCAConfig::default()).unwrap();
+
+        let strategy = persist_new_dca_strategy(&db, new_strategy(user_id, "Weekly BTC", config_json))
+            .await
+            .unwrap();
+
+        assert_eq!(strategy.name, "Weekly BTC");
+        assert_eq!(strategies_for(&db, user_id).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_persist_new_dca_strategy_rolls_back_on_mid_transaction_failure() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+
+        // The insert succeeds, then reading the stored config back fails
+        let result = persist_new_dca_strategy(&db, new_strategy(user_id, "Broken", "not json".to_string())).await;
+
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        assert_eq!(strategies_for(&db, user_id).await, 0);
+    }
+}
@@ -0,0 +1,56 @@
+use actix_web::{web, HttpResponse};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::dex_connectors::uniswap::UniswapQuoter;
+use crate::dex_connectors::{DexError, DEX};
+use crate::utils::errors::AppError;
+
+#[derive(Debug, Deserialize)]
+pub struct SwapQuoteQuery {
+    pub dex: String,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount: Decimal,
+}
+
+/// Quote a swap on a DEX. Quotes only read on-chain state, so no wallet is needed.
+pub async fn get_swap_quote(
+    query: web::Query<SwapQuoteQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query.into_inner();
+
+    let dex = DEX::from_str(&query.dex)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown DEX: {}", query.dex)))?;
+
+    if query.amount <= Decimal::ZERO {
+        return Err(AppError::BadRequest("Amount must be positive".to_string()));
+    }
+
+    info!("Quoting {} {} -> {} on {}", query.amount, query.token_in, query.token_out, dex.as_str());
+
+    let quote = match dex {
+        DEX::Uniswap => {
+            UniswapQuoter::from_env()
+                .map_err(quote_error)?
+                .quote(&query.token_in, &query.token_out, query.amount)
+                .await
+        }
+        other => Err(DexError::UnsupportedOperation(format!(
+            "Quotes are not yet available for {}",
+            other.as_str()
+        ))),
+    }
+    .map_err(quote_error)?;
+
+    Ok(HttpResponse::Ok().json(quote))
+}
+
+fn quote_error(err: DexError) -> AppError {
+    match err {
+        DexError::PoolNotFound(msg) => AppError::NotFound(msg),
+        DexError::InvalidCredentials(msg) | DexError::UnsupportedOperation(msg) => AppError::BadRequest(msg),
+        other => AppError::ExternalServiceError(other.to_string()),
+    }
+}
@@ -0,0 +1,84 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::handlers::exchange_management::connector_for_connection;
+use crate::models::exchange_connection;
+use crate::services::{DCAExecutionEngine, EmergencyStopService};
+use crate::utils::errors::AppError;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmergencyStopRequest {
+    /// Needed to decrypt the exchange API keys used to flatten positions
+    pub password: String,
+}
+
+/// Pause all of the user's live strategies and close their open positions at market.
+/// Strategies are paused even if flattening fails; each strategy's outcome is reported.
+pub async fn emergency_stop(
+    db: web::Data<Arc<DatabaseConnection>>,
+    execution_engine: web::Data<DCAExecutionEngine>,
+    req: HttpRequest,
+    body: web::Json<EmergencyStopRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    if body.password.is_empty() {
+        return Err(AppError::BadRequest("Password is required to flatten positions".to_string()));
+    }
+
+    let service = EmergencyStopService::new(db.get_ref().clone());
+    let strategies = service.live_strategies(user_id).await?;
+    service.pause(&strategies).await?;
+    for strategy in &strategies {
+        execution_engine.strategy_status_changed(user_id, strategy.id, "paused").await;
+    }
+
+    tracing::warn!("Emergency stop triggered by user {} for {} strategies", user_id, strategies.len());
+
+    // Only connect to exchanges that actually hold a position
+    let connection_ids: HashSet<Uuid> = strategies
+        .iter()
+        .filter(|strategy| !strategy.open_quantity.is_zero())
+        .filter_map(|strategy| strategy.exchange_connection_id)
+        .collect();
+
+    let connections = exchange_connection::Entity::find()
+        .filter(exchange_connection::Column::UserId.eq(user_id))
+        .filter(exchange_connection::Column::Id.is_in(connection_ids.iter().copied()))
+        .all(db.as_ref().as_ref())
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let mut connectors = HashMap::new();
+    for connection in &connections {
+        let connector = connector_for_connection(connection, &body.password, user_id)
+            .map_err(|e| format!("{:?}", e));
+        connectors.insert(connection.id, connector);
+    }
+
+    let report = service.flatten(strategies, &connectors).await;
+
+    Ok(HttpResponse::Ok().json(report))
+}
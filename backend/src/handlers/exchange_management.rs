@@ -1,7 +1,8 @@
 use actix_web::{web, HttpRequest, HttpResponse, Result, HttpMessage};
 use actix_session::SessionExt;
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait};
+use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
@@ -9,15 +10,23 @@ use crate::models::{
     exchange_connection::{
         self, ActiveModel as ExchangeConnectionActiveModel, Entity as ExchangeConnectionEntity,
         CreateExchangeConnectionRequest, UpdateExchangeConnectionRequest, ExchangeConnectionResponse,
-        SupportedExchange,
+        SupportedExchange, ExchangeConnectionExport, ExportedExchangeConnection,
+        ImportExchangeConnectionsResponse, SyncStaleness, CONNECTION_EXPORT_VERSION,
     },
+    connection_balance_snapshot::{parse_history_range, ConnectionHistoryResponse, HistoryQuery, SetHistoryRequest},
     user::Entity as UserEntity,
 };
 use crate::utils::{
     errors::AppError,
     encryption::{EncryptionService, EncryptedData},
+    precision::{round_crypto, round_usd},
 };
 use crate::exchange_connectors::{Exchange, ExchangeFactory, ExchangeCredentials};
+use crate::exchange_connectors::factory::FullExchangeAPI;
+use crate::exchange_connectors::fees::{FeeSchedule, BASE_FEE_TIER};
+use crate::services::connection_history::{bucket_for_range, connection_history, ConnectionHistoryService};
+use crate::services::fx_rates::{FxRate, FxRateService};
+use crate::services::portfolio_consolidation::{consolidate, fetch_all_balances, BalanceSource, FailedSource};
 
 /// Extract authenticated user ID from session
 fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
@@ -46,42 +55,23 @@ pub async fn create_exchange_connection(
     // Get user ID from session
     let user_id = get_user_id_from_session(&req)?;
 
-    // Check if user exists in database
-    let user_exists = UserEntity::find_by_id(user_id)
-        .one(db.get_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
-
-    if user_exists.is_none() {
-        tracing::error!("User with ID {} not found in database", user_id);
-        return Err(AppError::Unauthorized("User not found. Please login again.".to_string()));
-    }
-
     // Validate request
     body.validate().map_err(AppError::ValidationError)?;
 
     // Validate supported exchange
-    let _exchange = SupportedExchange::from_str(&body.exchange_name)
+    let exchange = SupportedExchange::from_str(&body.exchange_name)
         .ok_or_else(|| AppError::BadRequest("Unsupported exchange".to_string()))?;
 
-    // Check if user already has a connection for this exchange
-    let existing_connection = ExchangeConnectionEntity::find()
-        .filter(exchange_connection::Column::UserId.eq(user_id))
-        .filter(exchange_connection::Column::ExchangeName.eq(&body.exchange_name))
-        .one(db.get_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
-
-    let is_updating_existing = existing_connection.is_some();
-
     // Test the connection before storing - validate API credentials
-    let credentials = ExchangeCredentials {
-        api_key: body.api_key.clone(),
-        api_secret: body.api_secret.clone(),
-    };
+    let credentials = connection_credentials(
+        &exchange,
+        body.api_key.clone(),
+        body.api_secret.clone(),
+        body.passphrase.clone(),
+    )?;
 
-    let exchange = Exchange::from_str(&body.exchange_name)
-        .ok_or_else(|| AppError::BadRequest("Unsupported exchange".to_string()))?;
+    // Only kept for exchanges that use one; connection_credentials drops it otherwise
+    let credentials_passphrase = credentials.passphrase.clone();
 
     let connector = ExchangeFactory::create(exchange, credentials)
         .map_err(|e| AppError::BadRequest(format!("Failed to create connector: {}", e)))?;
@@ -95,6 +85,9 @@ pub async fn create_exchange_connection(
 
     tracing::info!("API credentials validated successfully for exchange: {}", body.exchange_name);
 
+    // Record what the key may do so the UI can warn about missing scopes
+    let api_permissions = probe_api_permissions(connector.as_ref()).await;
+
     // Encrypt the API credentials
     let encryption_service = EncryptionService::new();
     let user_id_str = user_id.to_string();
@@ -103,150 +96,185 @@ pub async fn create_exchange_connection(
         .encrypt_api_credentials(&body.api_key, &body.password, &user_id_str)?;
     let encrypted_api_secret = encryption_service
         .encrypt_api_credentials(&body.api_secret, &body.password, &user_id_str)?;
+    let encrypted_passphrase = credentials_passphrase
+        .as_deref()
+        .map(|passphrase| encryption_service.encrypt_api_credentials(passphrase, &body.password, &user_id_str))
+        .transpose()?;
 
-    // Passphrase support removed - focusing on API key/secret only
-
-    // Prepare connection data - use existing ID if updating, new ID if creating
-    let (connection_id, created_at_timestamp) = if let Some(ref existing) = existing_connection {
-        (existing.id, existing.created_at)
-    } else {
-        (Uuid::new_v4(), Utc::now())
-    };
-
-    if is_updating_existing {
-        tracing::info!("Updating existing exchange connection: id={}, user_id={}, exchange={}",
-                       connection_id, user_id, body.exchange_name);
-    } else {
-        tracing::info!("Creating new exchange connection: id={}, user_id={}, exchange={}",
-                       connection_id, user_id, body.exchange_name);
-    }
-
-    // Store values before using them since we need them for both ActiveModel and potential manual construction
-    let stored_id = connection_id;
-    let stored_user_id = user_id;
-    let stored_exchange_name = body.exchange_name.clone();
-    let stored_display_name = body.display_name.clone();
-    let stored_encrypted_api_key = encrypted_api_key.ciphertext.clone();
-    let stored_encrypted_api_secret = encrypted_api_secret.ciphertext.clone();
-    let stored_api_key_nonce = encrypted_api_key.nonce.clone();
-    let stored_api_secret_nonce = encrypted_api_secret.nonce.clone();
-    let stored_api_key_salt = encrypted_api_key.salt.clone();
-    let stored_api_secret_salt = encrypted_api_secret.salt.clone();
-    let stored_created_at = created_at_timestamp;
-    let stored_updated_at = Utc::now();
-
+    let now = Utc::now();
     let connection_model = ExchangeConnectionActiveModel {
-        id: Set(stored_id),
-        user_id: Set(stored_user_id),
-        exchange_name: Set(stored_exchange_name.clone()),
-        display_name: Set(stored_display_name.clone()),
-        encrypted_api_key: Set(stored_encrypted_api_key.clone()),
-        encrypted_api_secret: Set(stored_encrypted_api_secret.clone()),
-        encrypted_passphrase: Set(None),
-        api_key_nonce: Set(stored_api_key_nonce.clone()),
-        api_secret_nonce: Set(stored_api_secret_nonce.clone()),
-        passphrase_nonce: Set(None),
-        api_key_salt: Set(stored_api_key_salt.clone()),
-        api_secret_salt: Set(stored_api_secret_salt.clone()),
-        passphrase_salt: Set(None),
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        exchange_name: Set(body.exchange_name.clone()),
+        display_name: Set(body.display_name.clone()),
+        encrypted_api_key: Set(encrypted_api_key.ciphertext),
+        encrypted_api_secret: Set(encrypted_api_secret.ciphertext),
+        encrypted_passphrase: Set(encrypted_passphrase.as_ref().map(|p| p.ciphertext.clone())),
+        api_key_nonce: Set(encrypted_api_key.nonce),
+        api_secret_nonce: Set(encrypted_api_secret.nonce),
+        passphrase_nonce: Set(encrypted_passphrase.as_ref().map(|p| p.nonce.clone())),
+        api_key_salt: Set(encrypted_api_key.salt),
+        api_secret_salt: Set(encrypted_api_secret.salt),
+        passphrase_salt: Set(encrypted_passphrase.map(|p| p.salt)),
         is_active: Set(true),
         last_sync: Set(None),
         connection_status: Set("connected".to_string()),
         last_error: Set(None),
-        created_at: Set(stored_created_at),
-        updated_at: Set(stored_updated_at),
+        api_permissions: Set(api_permissions),
+        fee_tier: Set(i32::from(BASE_FEE_TIER)),
+        fee_token_discount: Set(false),
+        history_credentials: Set(None),
+        history_credentials_nonce: Set(None),
+        history_credentials_salt: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
     };
 
-    // Handle create vs update operations
-    let connection = if is_updating_existing {
-        // For updates, use save() and convert ActiveModel to Model
-        tracing::info!("Updating existing exchange connection");
-        let save_result = connection_model.save(db.get_ref()).await;
-        
-        match save_result {
-            Ok(connection_active) => {
-                tracing::info!("Exchange connection update successful");
-                // Convert ActiveModel to Model for updates
-                exchange_connection::Model {
-                    id: connection_active.id.unwrap(),
-                    user_id: connection_active.user_id.unwrap(),
-                    exchange_name: connection_active.exchange_name.unwrap(),
-                    display_name: connection_active.display_name.unwrap(),
-                    encrypted_api_key: connection_active.encrypted_api_key.unwrap(),
-                    encrypted_api_secret: connection_active.encrypted_api_secret.unwrap(),
-                    encrypted_passphrase: connection_active.encrypted_passphrase.unwrap(),
-                    api_key_nonce: connection_active.api_key_nonce.unwrap(),
-                    api_secret_nonce: connection_active.api_secret_nonce.unwrap(),
-                    passphrase_nonce: connection_active.passphrase_nonce.unwrap(),
-                    api_key_salt: connection_active.api_key_salt.unwrap(),
-                    api_secret_salt: connection_active.api_secret_salt.unwrap(),
-                    passphrase_salt: connection_active.passphrase_salt.unwrap(),
-                    is_active: connection_active.is_active.unwrap(),
-                    last_sync: connection_active.last_sync.unwrap(),
-                    connection_status: connection_active.connection_status.unwrap(),
-                    last_error: connection_active.last_error.unwrap(),
-                    created_at: connection_active.created_at.unwrap(),
-                    updated_at: connection_active.updated_at.unwrap(),
-                }
-            },
-            Err(e) => {
-                tracing::error!("Failed to update exchange connection: {:?}", e);
-                return Err(AppError::InternalServerError)
-            }
-        }
+    let connection = persist_new_exchange_connection(db.get_ref(), connection_model).await?;
+
+    Ok(HttpResponse::Created().json(ExchangeConnectionResponse::from(connection)))
+}
+
+/// Build connector credentials for `exchange`. Exchanges that issue keys with a passphrase
+/// must be given one; for every other exchange a stray passphrase is dropped.
+pub(crate) fn connection_credentials(
+    exchange: &Exchange,
+    api_key: String,
+    api_secret: String,
+    passphrase: Option<String>,
+) -> Result<ExchangeCredentials, AppError> {
+    let passphrase = if exchange.requires_passphrase() {
+        let passphrase = passphrase
+            .filter(|p| !p.trim().is_empty())
+            .ok_or_else(|| AppError::BadRequest(format!("{:?} API keys require a passphrase", exchange)))?;
+        Some(passphrase)
     } else {
-        // For new connections, use insert() and handle UnpackInsertId
-        tracing::info!("Creating new exchange connection");
-        let insert_result = connection_model.insert(db.get_ref()).await;
-        
-        match insert_result {
-            Ok(connection) => {
-                tracing::info!("Exchange connection insert successful");
-                connection
-            },
-            Err(e) => {
-                tracing::error!("Failed to insert exchange connection: {:?}", e);
-                let error_debug = format!("{:?}", e);
-                if error_debug.contains("UnpackInsertId") {
-                    tracing::info!("Insert succeeded but couldn't unpack ID, constructing connection manually");
-                    // Construct connection manually from stored values
-                    exchange_connection::Model {
-                        id: stored_id,
-                        user_id: stored_user_id,
-                        exchange_name: stored_exchange_name,
-                        display_name: stored_display_name,
-                        encrypted_api_key: stored_encrypted_api_key,
-                        encrypted_api_secret: stored_encrypted_api_secret,
-                        encrypted_passphrase: None,
-                        api_key_nonce: stored_api_key_nonce,
-                        api_secret_nonce: stored_api_secret_nonce,
-                        passphrase_nonce: None,
-                        api_key_salt: stored_api_key_salt,
-                        api_secret_salt: stored_api_secret_salt,
-                        passphrase_salt: None,
-                        is_active: true,
-                        last_sync: None,
-                        connection_status: "connected".to_string(),
-                        last_error: None,
-                        created_at: stored_created_at,
-                        updated_at: stored_updated_at,
-                    }
-                } else {
-                    tracing::error!("Actual database error, not UnpackInsertId: {}", error_debug);
-                    return Err(AppError::InternalServerError)
-                }
-            }
-        }
+        None
     };
 
-    // Return appropriate status code based on operation
-    let response = if is_updating_existing {
-        HttpResponse::Ok().json(ExchangeConnectionResponse::from(connection))
-    } else {
-        HttpResponse::Created().json(ExchangeConnectionResponse::from(connection))
+    Ok(ExchangeCredentials { api_key, api_secret, passphrase })
+}
+
+/// Decrypt the stored API key, secret and (when present) passphrase of a connection
+pub(crate) fn decrypt_connection_credentials(
+    connection: &exchange_connection::Model,
+    password: &str,
+    user_id: Uuid,
+) -> Result<ExchangeCredentials, AppError> {
+    let encryption_service = EncryptionService::new();
+    let user_id_str = user_id.to_string();
+
+    let encrypted_api_key = EncryptedData {
+        ciphertext: connection.encrypted_api_key.clone(),
+        nonce: connection.api_key_nonce.clone(),
+        salt: connection.api_key_salt.clone(),
+    };
+
+    let encrypted_api_secret = EncryptedData {
+        ciphertext: connection.encrypted_api_secret.clone(),
+        nonce: connection.api_secret_nonce.clone(),
+        salt: connection.api_secret_salt.clone(),
     };
 
-    Ok(response)
+    let api_key = encryption_service
+        .decrypt_api_credentials(&encrypted_api_key, password, &user_id_str)?;
+    let api_secret = encryption_service
+        .decrypt_api_credentials(&encrypted_api_secret, password, &user_id_str)?;
+
+    let passphrase = match (&connection.encrypted_passphrase, &connection.passphrase_nonce, &connection.passphrase_salt) {
+        (Some(ciphertext), Some(nonce), Some(salt)) => {
+            let encrypted_passphrase = EncryptedData {
+                ciphertext: ciphertext.clone(),
+                nonce: nonce.clone(),
+                salt: salt.clone(),
+            };
+            Some(encryption_service.decrypt_api_credentials(&encrypted_passphrase, password, &user_id_str)?)
+        }
+        _ => None,
+    };
+
+    Ok(ExchangeCredentials { api_key, api_secret, passphrase })
+}
+
+/// Insert a new exchange connection. A user may hold several connections to the same
+/// exchange (e.g. subaccounts) as long as each has its own display name.
+pub(crate) async fn persist_new_exchange_connection(
+    db: &DatabaseConnection,
+    new_connection: ExchangeConnectionActiveModel,
+) -> Result<exchange_connection::Model, AppError> {
+    // The user check, duplicate lookup and insert commit together, so a
+    // failure part-way leaves no half-written connection behind
+    let txn = db.begin().await.map_err(AppError::DatabaseError)?;
+    let connection = insert_exchange_connection(&txn, new_connection).await?;
+    txn.commit().await.map_err(AppError::DatabaseError)?;
+
+    Ok(connection)
+}
+
+/// Insert a connection on `db` (normally a transaction) after checking the user exists
+/// and the display name is free on that exchange
+async fn insert_exchange_connection<C: ConnectionTrait>(
+    db: &C,
+    new_connection: ExchangeConnectionActiveModel,
+) -> Result<exchange_connection::Model, AppError> {
+    let connection_id = new_connection.id.clone().unwrap();
+    let user_id = new_connection.user_id.clone().unwrap();
+    let exchange_name = new_connection.exchange_name.clone().unwrap();
+    let display_name = new_connection.display_name.clone().unwrap();
+
+    // Check if user exists in database
+    let user_exists = UserEntity::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    if user_exists.is_none() {
+        tracing::error!("User with ID {} not found in database", user_id);
+        return Err(AppError::Unauthorized("User not found. Please login again.".to_string()));
+    }
+
+    ensure_display_name_available(db, user_id, &exchange_name, &display_name, None).await?;
+
+    tracing::info!("Creating new exchange connection: id={}, user_id={}, exchange={}",
+                   connection_id, user_id, exchange_name);
+
+    // Insert without returning (to avoid UnpackInsertId error)
+    ExchangeConnectionEntity::insert(new_connection)
+        .exec_without_returning(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    ExchangeConnectionEntity::find_by_id(connection_id)
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?
+        .ok_or(AppError::InternalServerError)
+}
+
+async fn ensure_display_name_available<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    exchange_name: &str,
+    display_name: &str,
+    excluding: Option<Uuid>,
+) -> Result<(), AppError> {
+    let mut query = ExchangeConnectionEntity::find()
+        .filter(exchange_connection::Column::UserId.eq(user_id))
+        .filter(exchange_connection::Column::ExchangeName.eq(exchange_name))
+        .filter(exchange_connection::Column::DisplayName.eq(display_name));
+    if let Some(id) = excluding {
+        query = query.filter(exchange_connection::Column::Id.ne(id));
+    }
+
+    let existing = query.one(db).await.map_err(AppError::DatabaseError)?;
+    if existing.is_some() {
+        return Err(AppError::Conflict(format!(
+            "A {} connection named \"{}\" already exists",
+            exchange_name, display_name
+        )));
+    }
+
+    Ok(())
 }
 
 /// Get all exchange connections for a user
@@ -304,15 +332,27 @@ pub async fn update_exchange_connection(
 
     body.validate().map_err(AppError::ValidationError)?;
 
+    let txn = db.begin().await.map_err(AppError::DatabaseError)?;
+
     // Find the connection
     let connection = ExchangeConnectionEntity::find()
         .filter(exchange_connection::Column::Id.eq(connection_id))
         .filter(exchange_connection::Column::UserId.eq(user_id))
-        .one(db.get_ref())
+        .one(&txn)
         .await
         .map_err(AppError::DatabaseError)?
         .ok_or_else(|| AppError::NotFound("Exchange connection not found".to_string()))?;
 
+    if let Some(display_name) = &body.display_name {
+        ensure_display_name_available(&txn, user_id, &connection.exchange_name, display_name, Some(connection.id)).await?;
+    }
+
+    let exchange = SupportedExchange::from_str(&connection.exchange_name)
+        .ok_or_else(|| AppError::BadRequest("Unsupported exchange".to_string()))?;
+    if body.passphrase.is_some() && !exchange.requires_passphrase() {
+        return Err(AppError::BadRequest(format!("{:?} API keys do not use a passphrase", exchange)));
+    }
+
     let mut active_model: ExchangeConnectionActiveModel = connection.into();
 
     // Update display name if provided
@@ -320,8 +360,16 @@ pub async fn update_exchange_connection(
         active_model.display_name = Set(display_name.clone());
     }
 
+    if let Some(fee_tier) = body.fee_tier {
+        FeeSchedule::for_exchange(&exchange).rates(fee_tier, false)?;
+        active_model.fee_tier = Set(i32::from(fee_tier));
+    }
+    if let Some(fee_token_discount) = body.fee_token_discount {
+        active_model.fee_token_discount = Set(fee_token_discount);
+    }
+
     // Update API credentials if provided
-    if body.api_key.is_some() || body.api_secret.is_some() {
+    if body.api_key.is_some() || body.api_secret.is_some() || body.passphrase.is_some() {
         let encryption_service = EncryptionService::new();
         let user_id_str = user_id.to_string();
 
@@ -343,16 +391,33 @@ pub async fn update_exchange_connection(
             active_model.api_secret_salt = Set(encrypted_api_secret.salt);
         }
 
+        if let Some(passphrase) = &body.passphrase {
+            let encrypted_passphrase = encryption_service
+                .encrypt_api_credentials(passphrase, &body.password, &user_id_str)?;
+
+            active_model.encrypted_passphrase = Set(Some(encrypted_passphrase.ciphertext));
+            active_model.passphrase_nonce = Set(Some(encrypted_passphrase.nonce));
+            active_model.passphrase_salt = Set(Some(encrypted_passphrase.salt));
+        }
+
         // Reset connection status to pending for re-validation
         active_model.connection_status = Set("pending".to_string());
         active_model.last_error = Set(None);
+        // Permissions belong to the old key; they're re-read on the next sync
+        active_model.api_permissions = Set(None);
+        // The history copy holds the old key; history must be turned on again for the new one
+        active_model.history_credentials = Set(None);
+        active_model.history_credentials_nonce = Set(None);
+        active_model.history_credentials_salt = Set(None);
     }
 
     active_model.updated_at = Set(Utc::now());
 
-    let updated_connection = active_model.update(db.get_ref()).await
+    let updated_connection = active_model.update(&txn).await
         .map_err(AppError::DatabaseError)?;
 
+    txn.commit().await.map_err(AppError::DatabaseError)?;
+
     Ok(HttpResponse::Ok().json(ExchangeConnectionResponse::from(updated_connection)))
 }
 
@@ -388,11 +453,217 @@ pub async fn delete_exchange_connection(
     })))
 }
 
+/// Turn background balance history on or off for a connection. Turning it on seals a
+/// copy of the credentials under the server's history key, so the password is needed.
+pub async fn set_connection_history(
+    db: web::Data<DatabaseConnection>,
+    history: web::Data<ConnectionHistoryService>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<SetHistoryRequest>,
+) -> Result<HttpResponse, AppError> {
+    let connection_id = Uuid::parse_str(&path.into_inner())
+        .map_err(|_| AppError::BadRequest("Invalid connection ID format".to_string()))?;
+
+    let user_id = get_user_id_from_session(&req)?;
+
+    let connection = ExchangeConnectionEntity::find()
+        .filter(exchange_connection::Column::Id.eq(connection_id))
+        .filter(exchange_connection::Column::UserId.eq(user_id))
+        .one(db.get_ref())
+        .await
+        .map_err(AppError::DatabaseError)?
+        .ok_or_else(|| AppError::NotFound("Exchange connection not found".to_string()))?;
+
+    let sealed = if body.enabled {
+        let password = body.password.as_deref()
+            .ok_or_else(|| AppError::BadRequest("Password is required to turn on balance history".to_string()))?;
+        let credentials = decrypt_connection_credentials(&connection, password, user_id)?;
+        Some(history.seal_credentials(&credentials, user_id)?)
+    } else {
+        None
+    };
+
+    let mut active_model: ExchangeConnectionActiveModel = connection.into();
+    active_model.history_credentials = Set(sealed.as_ref().map(|s| s.ciphertext.clone()));
+    active_model.history_credentials_nonce = Set(sealed.as_ref().map(|s| s.nonce.clone()));
+    active_model.history_credentials_salt = Set(sealed.map(|s| s.salt));
+    active_model.updated_at = Set(Utc::now());
+
+    let updated_connection = active_model.update(db.get_ref()).await
+        .map_err(AppError::DatabaseError)?;
+
+    Ok(HttpResponse::Ok().json(ExchangeConnectionResponse::from(updated_connection)))
+}
+
+/// Recorded USD value of a connection over `range` (default 30 days), reduced to one
+/// point per hour or day
+pub async fn get_connection_history(
+    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse, AppError> {
+    let connection_id = Uuid::parse_str(&path.into_inner())
+        .map_err(|_| AppError::BadRequest("Invalid connection ID format".to_string()))?;
+
+    let user_id = get_user_id_from_session(&req)?;
+
+    let range = query.into_inner().range.unwrap_or_else(|| "30d".to_string());
+    let span = parse_history_range(&range)?;
+
+    let connection = ExchangeConnectionEntity::find()
+        .filter(exchange_connection::Column::Id.eq(connection_id))
+        .filter(exchange_connection::Column::UserId.eq(user_id))
+        .one(db.get_ref())
+        .await
+        .map_err(AppError::DatabaseError)?
+        .ok_or_else(|| AppError::NotFound("Exchange connection not found".to_string()))?;
+
+    let bucket = bucket_for_range(span);
+    let points = connection_history(db.get_ref(), connection.id, Utc::now() - span, bucket).await?;
+
+    Ok(HttpResponse::Ok().json(ConnectionHistoryResponse {
+        connection_id: connection.id,
+        range,
+        bucket_seconds: bucket.num_seconds(),
+        points,
+    }))
+}
+
+/// Export the user's exchange connections for backup. Credentials are returned only
+/// in their encrypted form.
+pub async fn export_exchange_connections(
+    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    let export = export_connections(db.get_ref(), user_id).await?;
+
+    Ok(HttpResponse::Ok().json(export))
+}
+
+/// Request body limit of the import route; an export holds every connection's encrypted keys
+pub const CONNECTION_IMPORT_BODY_LIMIT: usize = 256 * 1024;
+
+/// Restore connections from an export of the same account
+pub async fn import_exchange_connections(
+    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+    body: web::Json<ExchangeConnectionExport>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    let response = import_connections(db.get_ref(), user_id, body.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub(crate) async fn export_connections(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<ExchangeConnectionExport, AppError> {
+    let connections = ExchangeConnectionEntity::find()
+        .filter(exchange_connection::Column::UserId.eq(user_id))
+        .all(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    Ok(ExchangeConnectionExport {
+        version: CONNECTION_EXPORT_VERSION,
+        user_id,
+        exported_at: Utc::now(),
+        connections: connections.into_iter().map(ExportedExchangeConnection::from).collect(),
+    })
+}
+
+/// Re-insert exported connections for the user they were exported from. The blobs are
+/// stored as-is; they are decrypted with the user's password on first use, and stay
+/// `pending` until the next sync confirms the key still works.
+pub(crate) async fn import_connections(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    export: ExchangeConnectionExport,
+) -> Result<ImportExchangeConnectionsResponse, AppError> {
+    if export.version != CONNECTION_EXPORT_VERSION {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported export version {}",
+            export.version
+        )));
+    }
+    // Keys are derived from the user id, so another account could never decrypt these
+    if export.user_id != user_id {
+        return Err(AppError::Forbidden(
+            "Connections can only be imported into the account they were exported from".to_string(),
+        ));
+    }
+
+    // Reject the whole file before writing anything
+    for connection in &export.connections {
+        SupportedExchange::from_str(&connection.exchange_name).ok_or_else(|| {
+            AppError::BadRequest(format!("Unsupported exchange {}", connection.exchange_name))
+        })?;
+    }
+
+    // All connections are restored together or not at all
+    let txn = db.begin().await.map_err(AppError::DatabaseError)?;
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for connection in export.connections {
+        let now = Utc::now();
+        let model = ExchangeConnectionActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            exchange_name: Set(connection.exchange_name),
+            display_name: Set(connection.display_name.clone()),
+            encrypted_api_key: Set(connection.encrypted_api_key),
+            encrypted_api_secret: Set(connection.encrypted_api_secret),
+            encrypted_passphrase: Set(connection.encrypted_passphrase),
+            api_key_nonce: Set(connection.api_key_nonce),
+            api_secret_nonce: Set(connection.api_secret_nonce),
+            passphrase_nonce: Set(connection.passphrase_nonce),
+            api_key_salt: Set(connection.api_key_salt),
+            api_secret_salt: Set(connection.api_secret_salt),
+            passphrase_salt: Set(connection.passphrase_salt),
+            is_active: Set(connection.is_active),
+            last_sync: Set(None),
+            connection_status: Set("pending".to_string()),
+            last_error: Set(None),
+            api_permissions: Set(None),
+            fee_tier: Set(i32::from(BASE_FEE_TIER)),
+            fee_token_discount: Set(false),
+            history_credentials: Set(None),
+            history_credentials_nonce: Set(None),
+            history_credentials_salt: Set(None),
+            created_at: Set(connection.created_at),
+            updated_at: Set(now),
+        };
+
+        match insert_exchange_connection(&txn, model).await {
+            Ok(connection) => imported.push(ExchangeConnectionResponse::from(connection)),
+            Err(AppError::Conflict(_)) => skipped.push(connection.display_name),
+            Err(e) => return Err(e),
+        }
+    }
+    txn.commit().await.map_err(AppError::DatabaseError)?;
+
+    tracing::info!(
+        "Imported {} exchange connections for user {} ({} already present)",
+        imported.len(),
+        user_id,
+        skipped.len()
+    );
+
+    Ok(ImportExchangeConnectionsResponse { imported, skipped })
+}
+
 /// Sync balances for a specific exchange connection
 pub async fn sync_exchange_balances(
     db: web::Data<DatabaseConnection>,
     req: HttpRequest,
     path: web::Path<String>,
+    fx_rates: web::Data<FxRateService>,
     body: web::Json<serde_json::Value>, // Expecting { "password": "user_password" }
 ) -> Result<HttpResponse, AppError> {
     let connection_id_str = path.into_inner();
@@ -415,30 +686,7 @@ pub async fn sync_exchange_balances(
         .ok_or_else(|| AppError::NotFound("Exchange connection not found".to_string()))?;
 
     // Decrypt the API credentials
-    let encryption_service = EncryptionService::new();
-    let user_id_str = user_id.to_string();
-
-    let encrypted_api_key = EncryptedData {
-        ciphertext: connection.encrypted_api_key.clone(),
-        nonce: connection.api_key_nonce.clone(),
-        salt: connection.api_key_salt.clone(),
-    };
-
-    let encrypted_api_secret = EncryptedData {
-        ciphertext: connection.encrypted_api_secret.clone(),
-        nonce: connection.api_secret_nonce.clone(),
-        salt: connection.api_secret_salt.clone(),
-    };
-
-    let api_key = encryption_service
-        .decrypt_api_credentials(&encrypted_api_key, password, &user_id_str)?;
-    let api_secret = encryption_service
-        .decrypt_api_credentials(&encrypted_api_secret, password, &user_id_str)?;
-
-    let credentials = ExchangeCredentials {
-        api_key,
-        api_secret,
-    };
+    let credentials = decrypt_connection_credentials(&connection, password, user_id)?;
 
     let exchange = Exchange::from_str(&connection.exchange_name)
         .ok_or_else(|| AppError::BadRequest("Unsupported exchange".to_string()))?;
@@ -450,6 +698,8 @@ pub async fn sync_exchange_balances(
     let account_balances = connector.get_all_balances().await
         .map_err(|e| AppError::ExternalServiceError(format!("Failed to fetch balances: {}", e)))?;
 
+    let api_permissions = probe_api_permissions(connector.as_ref()).await;
+
     // Debug logging to see account balances
     tracing::info!("=== ACCOUNT BALANCES DEBUG ===");
     tracing::info!("Total USD Value: ${}", account_balances.total_usd_value);
@@ -521,26 +771,37 @@ pub async fn sync_exchange_balances(
     connection_update.last_sync = Set(Some(now));
     connection_update.connection_status = Set("connected".to_string());
     connection_update.last_error = Set(None);
+    if api_permissions.is_some() {
+        connection_update.api_permissions = Set(api_permissions.clone());
+    }
     connection_update.updated_at = Set(now);
 
-    connection_update.update(db.get_ref()).await
+    let connection = connection_update.update(db.get_ref()).await
         .map_err(AppError::DatabaseError)?;
 
     // Note: We don't store balance data in database - all balance data is fetched live
 
+    let rate = fx_rates.rate_for_user(db.get_ref(), user_id).await?;
+
     // Return live balance data
+    let staleness = SyncStaleness::now(connection.last_sync);
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "connection_id": connection_id,
         "exchange_name": exchange_name,
         "display_name": display_name,
-        "total_usd_value": account_balances.total_usd_value.to_string(),
-        "total_btc_value": account_balances.total_btc_value.to_string(),
+        "total_usd_value": round_usd(account_balances.total_usd_value).to_string(),
+        "total_value": base_currency_total(account_balances.total_usd_value, &rate),
+        "valuation": rate,
+        "total_btc_value": round_crypto(account_balances.total_btc_value).to_string(),
         "accounts": {
             "spot": account_balances.spot,
             "margin": account_balances.margin,
             "futures_usdm": account_balances.futures_usdm,
             "futures_coinm": account_balances.futures_coinm,
         },
+        "api_permissions": ExchangeConnectionResponse::from(connection).api_permissions,
+        "sync_age_seconds": staleness.age_seconds,
+        "is_stale": staleness.is_stale,
         "last_updated": now,
         "is_live": true
     })))
@@ -552,6 +813,7 @@ pub async fn get_live_wallet_balances(
     db: web::Data<DatabaseConnection>,
     req: HttpRequest,
     path: web::Path<String>,
+    fx_rates: web::Data<FxRateService>,
     body: web::Json<serde_json::Value>, // Expecting { "password": "user_password" }
 ) -> Result<HttpResponse, AppError> {
     let connection_id_str = path.into_inner();
@@ -574,30 +836,7 @@ pub async fn get_live_wallet_balances(
         .ok_or_else(|| AppError::NotFound("Exchange connection not found".to_string()))?;
 
     // Decrypt the API credentials
-    let encryption_service = EncryptionService::new();
-    let user_id_str = user_id.to_string();
-
-    let encrypted_api_key = EncryptedData {
-        ciphertext: connection.encrypted_api_key.clone(),
-        nonce: connection.api_key_nonce.clone(),
-        salt: connection.api_key_salt.clone(),
-    };
-
-    let encrypted_api_secret = EncryptedData {
-        ciphertext: connection.encrypted_api_secret.clone(),
-        nonce: connection.api_secret_nonce.clone(),
-        salt: connection.api_secret_salt.clone(),
-    };
-
-    let api_key = encryption_service
-        .decrypt_api_credentials(&encrypted_api_key, password, &user_id_str)?;
-    let api_secret = encryption_service
-        .decrypt_api_credentials(&encrypted_api_secret, password, &user_id_str)?;
-
-    let credentials = ExchangeCredentials {
-        api_key,
-        api_secret,
-    };
+    let credentials = decrypt_connection_credentials(&connection, password, user_id)?;
 
     let exchange = Exchange::from_str(&connection.exchange_name)
         .ok_or_else(|| AppError::BadRequest("Unsupported exchange".to_string()))?;
@@ -627,13 +866,17 @@ pub async fn get_live_wallet_balances(
     }
     tracing::info!("=== END LIVE BALANCE FETCH DEBUG ===");
 
+    let rate = fx_rates.rate_for_user(db.get_ref(), user_id).await?;
+
     // Convert to response format
     let response = serde_json::json!({
         "exchange_connection_id": connection_id,
         "exchange_name": connection.exchange_name,
         "display_name": connection.display_name,
-        "total_usd_value": account_balances.total_usd_value.to_string(),
-        "total_btc_value": account_balances.total_btc_value.to_string(),
+        "total_usd_value": round_usd(account_balances.total_usd_value).to_string(),
+        "total_value": base_currency_total(account_balances.total_usd_value, &rate),
+        "valuation": rate,
+        "total_btc_value": round_crypto(account_balances.total_btc_value).to_string(),
         "accounts": {
             "spot": account_balances.spot,
             "margin": account_balances.margin,
@@ -652,6 +895,7 @@ pub async fn get_live_wallet_balances(
 pub async fn get_all_live_user_balances(
     db: web::Data<DatabaseConnection>,
     req: HttpRequest,
+    fx_rates: web::Data<FxRateService>,
     body: web::Json<serde_json::Value>, // Expecting { "password": "user_password" }
 ) -> Result<HttpResponse, AppError> {
     let user_id = get_user_id_from_session(&req)?;
@@ -667,22 +911,24 @@ pub async fn get_all_live_user_balances(
         .await
         .map_err(AppError::DatabaseError)?;
 
+    let (connectors, failed) = connectors_for_connections(&connections, password, user_id);
+    let results = fetch_all_balances(connectors).await;
+    let rate = fx_rates.rate_for_user(db.get_ref(), user_id).await?;
+
     let mut all_summaries = Vec::new();
     let mut grand_total_usd = rust_decimal::Decimal::ZERO;
 
-    for connection in connections {
-        let connection_id = connection.id;
-        
-        // Try to fetch live balances for this connection
-        match get_live_balances_for_connection(&connection, password, user_id).await {
+    for (source, result) in results {
+        match result {
             Ok(account_balances) => {
                 grand_total_usd += account_balances.total_usd_value;
-                
+
                 all_summaries.push(serde_json::json!({
-                    "exchange_connection_id": connection_id,
-                    "exchange_name": connection.exchange_name,
-                    "display_name": connection.display_name,
-                    "total_usd_value": account_balances.total_usd_value.to_string(),
+                    "exchange_connection_id": source.connection_id,
+                    "exchange_name": source.exchange_name,
+                    "display_name": source.display_name,
+                    "total_usd_value": round_usd(account_balances.total_usd_value).to_string(),
+                    "total_value": base_currency_total(account_balances.total_usd_value, &rate),
                     "accounts": {
                         "spot": account_balances.spot,
                         "margin": account_balances.margin,
@@ -695,73 +941,137 @@ pub async fn get_all_live_user_balances(
                 }));
             }
             Err(e) => {
-                tracing::error!("Failed to fetch live balances for {}: {:?}", connection.display_name, e);
-                all_summaries.push(serde_json::json!({
-                    "exchange_connection_id": connection_id,
-                    "exchange_name": connection.exchange_name,
-                    "display_name": connection.display_name,
-                    "total_usd_value": "0",
-                    "accounts": null,
-                    "status": "error",
-                    "error": format!("{:?}", e),
-                    "last_updated": chrono::Utc::now(),
-                    "is_live": false
-                }));
+                tracing::error!("Failed to fetch live balances for {}: {:?}", source.display_name, e);
+                let exchange = Exchange::from_str(&source.exchange_name)
+                    .map_or_else(|| source.exchange_name.clone(), |exchange| format!("{:?}", exchange));
+                all_summaries.push(error_summary(&source, format!("{:?}", AppError::ExternalServiceError(format!("{} API Error: {}", exchange, e)))));
             }
         }
     }
 
+    for failure in failed {
+        tracing::error!("Failed to fetch live balances for {}: {}", failure.source.display_name, failure.error);
+        all_summaries.push(error_summary(&failure.source, failure.error));
+    }
+
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "balances": all_summaries,
-        "total_usd_value": grand_total_usd.to_string(),
+        "total_usd_value": round_usd(grand_total_usd).to_string(),
+        "total_value": base_currency_total(grand_total_usd, &rate),
+        "valuation": rate,
         "is_live": true,
         "last_updated": chrono::Utc::now()
     })))
 }
 
-// Helper function to get live balances for a specific connection
-async fn get_live_balances_for_connection(
-    connection: &crate::models::exchange_connection::Model,
+/// Summary entry for a connection whose live balances could not be fetched
+fn error_summary(source: &BalanceSource, error: String) -> serde_json::Value {
+    serde_json::json!({
+        "exchange_connection_id": source.connection_id,
+        "exchange_name": source.exchange_name,
+        "display_name": source.display_name,
+        "total_usd_value": "0",
+        "total_value": "0",
+        "accounts": null,
+        "status": "error",
+        "error": error,
+        "last_updated": chrono::Utc::now(),
+        "is_live": false
+    })
+}
+
+/// A USD total in the user's base currency, rounded for display
+fn base_currency_total(total_usd_value: rust_decimal::Decimal, rate: &FxRate) -> String {
+    round_usd(rate.convert(total_usd_value)).to_string()
+}
+
+/// Get LIVE holdings merged per asset across all of the user's exchange connections (requires password)
+pub async fn get_consolidated_portfolio(
+    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+    fx_rates: web::Data<FxRateService>,
+    body: web::Json<serde_json::Value>, // Expecting { "password": "user_password" }
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    let password = body.get("password")
+        .and_then(|p| p.as_str())
+        .ok_or_else(|| AppError::BadRequest("Password is required for live balance fetching".to_string()))?;
+
+    let connections = ExchangeConnectionEntity::find()
+        .filter(exchange_connection::Column::UserId.eq(user_id))
+        .filter(exchange_connection::Column::IsActive.eq(true))
+        .all(db.get_ref())
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let (connectors, failed) = connectors_for_connections(&connections, password, user_id);
+    let mut portfolio = consolidate(fetch_all_balances(connectors).await);
+    portfolio.failed_connections.extend(failed);
+    let rate = fx_rates.rate_for_user(db.get_ref(), user_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total_value": base_currency_total(portfolio.total_usd_value, &rate),
+        "valuation": rate,
+        "portfolio": portfolio,
+        "is_live": true,
+        "last_updated": chrono::Utc::now()
+    })))
+}
+
+/// Build a connector for each connection; connections whose credentials can't be
+/// decrypted or whose exchange isn't supported are returned as failures
+fn connectors_for_connections(
+    connections: &[crate::models::exchange_connection::Model],
     password: &str,
     user_id: Uuid,
-) -> Result<crate::exchange_connectors::common_types::AccountBalances, AppError> {
-    // Decrypt the API credentials
-    let encryption_service = EncryptionService::new();
-    let user_id_str = user_id.to_string();
+) -> (Vec<(BalanceSource, Arc<dyn FullExchangeAPI>)>, Vec<FailedSource>) {
+    let mut connectors = Vec::new();
+    let mut failed = Vec::new();
 
-    let encrypted_api_key = EncryptedData {
-        ciphertext: connection.encrypted_api_key.clone(),
-        nonce: connection.api_key_nonce.clone(),
-        salt: connection.api_key_salt.clone(),
-    };
+    for connection in connections {
+        let source = BalanceSource {
+            connection_id: connection.id,
+            exchange_name: connection.exchange_name.clone(),
+            display_name: connection.display_name.clone(),
+        };
+
+        match connector_for_connection(connection, password, user_id) {
+            Ok(connector) => connectors.push((source, connector)),
+            Err(e) => failed.push(FailedSource { source, error: format!("{:?}", e) }),
+        }
+    }
 
-    let encrypted_api_secret = EncryptedData {
-        ciphertext: connection.encrypted_api_secret.clone(),
-        nonce: connection.api_secret_nonce.clone(),
-        salt: connection.api_secret_salt.clone(),
-    };
+    (connectors, failed)
+}
 
-    let api_key = encryption_service
-        .decrypt_api_credentials(&encrypted_api_key, password, &user_id_str)
-        .map_err(|e| AppError::BadRequest(format!("Decryption failed - wrong password? Error: {:?}", e)))?;
-    let api_secret = encryption_service
-        .decrypt_api_credentials(&encrypted_api_secret, password, &user_id_str)
-        .map_err(|e| AppError::BadRequest(format!("Decryption failed - wrong password? Error: {:?}", e)))?;
+/// Ask the exchange what the key is allowed to do. A failed probe isn't fatal;
+/// the permissions are just left unknown.
+async fn probe_api_permissions(connector: &dyn FullExchangeAPI) -> Option<serde_json::Value> {
+    match connector.get_api_permissions().await {
+        Ok(permissions) => serde_json::to_value(permissions).ok(),
+        Err(e) => {
+            tracing::warn!("Could not read API key permissions: {}", e);
+            None
+        }
+    }
+}
 
-    let credentials = ExchangeCredentials {
-        api_key,
-        api_secret,
-    };
+// Helper function to build a live connector for a specific connection
+pub(crate) fn connector_for_connection(
+    connection: &crate::models::exchange_connection::Model,
+    password: &str,
+    user_id: Uuid,
+) -> Result<Arc<dyn FullExchangeAPI>, AppError> {
+    // Decrypt the API credentials
+    let credentials = decrypt_connection_credentials(connection, password, user_id)
+        .map_err(|e| AppError::BadRequest(format!("Decryption failed - wrong password? Error: {:?}", e)))?;
 
     let exchange = Exchange::from_str(&connection.exchange_name)
         .ok_or_else(|| AppError::BadRequest("Unsupported exchange".to_string()))?;
 
-    let connector = ExchangeFactory::create(exchange, credentials)
-        .map_err(|e| AppError::BadRequest(format!("Failed to create connector: {}", e)))?;
-
-    // Fetch live balance data from the exchange
-    connector.get_all_balances().await
-        .map_err(|e| AppError::ExternalServiceError(format!("Binance API Error: {}", e)))
+    ExchangeFactory::create(exchange, credentials)
+        .map_err(|e| AppError::BadRequest(format!("Failed to create connector: {}", e)))
 }
 
 /// Get specific account type data (spot/margin/futures) - replacement for exchange_connector_handler
@@ -847,6 +1157,8 @@ pub async fn test_connection_status(
         return Err(AppError::Unauthorized("Access denied".to_string()));
     }
 
+    let staleness = SyncStaleness::now(connection.last_sync);
+
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "connection_id": connection_id,
         "exchange_name": connection.exchange_name,
@@ -854,8 +1166,332 @@ pub async fn test_connection_status(
         "status": connection.connection_status,
         "is_active": connection.is_active,
         "last_sync": connection.last_sync,
+        "sync_age_seconds": staleness.age_seconds,
+        "is_stale": staleness.is_stale,
         "last_error": connection.last_error,
         "message": "Use the sync endpoint with your password to test live connection",
         "tested_at": chrono::Utc::now()
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use crate::database::test_support::{seed_user, test_db};
+    use crate::exchange_connectors::common_types::{AccountBalances, AssetBalance, SpotAccount, WalletType};
+
+    fn new_connection(user_id: Uuid, display_name: &str) -> ExchangeConnectionActiveModel {
+        ExchangeConnectionActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            exchange_name: Set("binance".to_string()),
+            display_name: Set(display_name.to_string()),
+            encrypted_api_key: Set("key".to_string()),
+            encrypted_api_secret: Set("secret".to_string()),
+            encrypted_passphrase: Set(None),
+            api_key_nonce: Set("nonce".to_string()),
+            api_secret_nonce: Set("nonce".to_string()),
+            passphrase_nonce: Set(None),
+            api_key_salt: Set("salt".to_string()),
+            api_secret_salt: Set("salt".to_string()),
+            passphrase_salt: Set(None),
+            is_active: Set(true),
+            last_sync: Set(None),
+            connection_status: Set("connected".to_string()),
+            last_error: Set(None),
+            api_permissions: Set(None),
+            fee_tier: Set(i32::from(BASE_FEE_TIER)),
+            fee_token_discount: Set(false),
+            history_credentials: Set(None),
+            history_credentials_nonce: Set(None),
+            history_credentials_salt: Set(None),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+        }
+    }
+
+    fn btc_balances(quantity: i64) -> AccountBalances {
+        let usd_value = Decimal::from(quantity * 60000);
+        AccountBalances {
+            spot: Some(SpotAccount {
+                balances: vec![AssetBalance {
+                    asset: "BTC".to_string(),
+                    free: Decimal::from(quantity),
+                    locked: Decimal::ZERO,
+                    total: Decimal::from(quantity),
+                    usd_value: Some(usd_value),
+                    btc_value: None,
+                    wallet_type: WalletType::Spot,
+                    priced: true,
+                    price_source: None,
+                }],
+                total_usd_value: Some(usd_value),
+                unpriced_assets: 0,
+                total_btc_value: None,
+                maker_commission: None,
+                taker_commission: None,
+                can_trade: true,
+                can_withdraw: true,
+                can_deposit: true,
+                last_update_time: Utc::now(),
+            }),
+            margin: None,
+            futures_usdm: None,
+            futures_coinm: None,
+            total_usd_value: usd_value,
+            total_btc_value: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_base_currency_total_scales_by_the_fx_rate() {
+        let total = btc_balances(2).total_usd_value;
+        let as_of = Utc::now();
+        let usd = FxRate::usd(as_of);
+        let gbp = FxRate { base_currency: "GBP".to_string(), usd_rate: Decimal::new(79, 2), as_of };
+
+        assert_eq!(base_currency_total(total, &usd), "120000.00");
+        assert_eq!(base_currency_total(total, &gbp), "94800.00");
+    }
+
+    #[tokio::test]
+    async fn test_two_binance_connections_persist_and_aggregate() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+
+        let main = persist_new_exchange_connection(&db, new_connection(user_id, "Main")).await.unwrap();
+        let sub = persist_new_exchange_connection(&db, new_connection(user_id, "Subaccount")).await.unwrap();
+
+        let connections = ExchangeConnectionEntity::find()
+            .filter(exchange_connection::Column::UserId.eq(user_id))
+            .filter(exchange_connection::Column::IsActive.eq(true))
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(connections.len(), 2);
+
+        let results = connections
+            .iter()
+            .map(|connection| {
+                let source = BalanceSource {
+                    connection_id: connection.id,
+                    exchange_name: connection.exchange_name.clone(),
+                    display_name: connection.display_name.clone(),
+                };
+                let quantity = if connection.id == main.id { 1 } else { 2 };
+                (source, Ok(btc_balances(quantity)))
+            })
+            .collect();
+        let portfolio = consolidate(results);
+
+        let btc = &portfolio.assets[0];
+        assert_eq!(btc.total_quantity, Decimal::from(3));
+        assert_eq!(btc.exchanges.len(), 2);
+        assert!(btc.exchanges.iter().any(|h| h.connection_id == main.id && h.display_name == "Main"));
+        assert!(btc.exchanges.iter().any(|h| h.connection_id == sub.id && h.display_name == "Subaccount"));
+    }
+
+    fn encrypted_connection(user_id: Uuid, display_name: &str, api_key: &str, api_secret: &str) -> ExchangeConnectionActiveModel {
+        let encryption_service = EncryptionService::new();
+        let key = encryption_service.encrypt_api_credentials(api_key, "correct horse", &user_id.to_string()).unwrap();
+        let secret = encryption_service.encrypt_api_credentials(api_secret, "correct horse", &user_id.to_string()).unwrap();
+
+        let mut connection = new_connection(user_id, display_name);
+        connection.encrypted_api_key = Set(key.ciphertext);
+        connection.api_key_nonce = Set(key.nonce);
+        connection.api_key_salt = Set(key.salt);
+        connection.encrypted_api_secret = Set(secret.ciphertext);
+        connection.api_secret_nonce = Set(secret.nonce);
+        connection.api_secret_salt = Set(secret.salt);
+        connection
+    }
+
+    fn decrypt(model: &exchange_connection::Model, password: &str) -> Result<(String, String), AppError> {
+        let encryption_service = EncryptionService::new();
+        let user_id = model.user_id.to_string();
+        let key = encryption_service.decrypt_api_credentials(
+            &EncryptedData {
+                ciphertext: model.encrypted_api_key.clone(),
+                nonce: model.api_key_nonce.clone(),
+                salt: model.api_key_salt.clone(),
+            },
+            password,
+            &user_id,
+        )?;
+        let secret = encryption_service.decrypt_api_credentials(
+            &EncryptedData {
+                ciphertext: model.encrypted_api_secret.clone(),
+                nonce: model.api_secret_nonce.clone(),
+                salt: model.api_secret_salt.clone(),
+            },
+            password,
+            &user_id,
+        )?;
+        Ok((key, secret))
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip_keeps_credentials_encrypted() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+
+        persist_new_exchange_connection(&db, encrypted_connection(user_id, "Main", "plain-api-key", "plain-api-secret"))
+            .await
+            .unwrap();
+        persist_new_exchange_connection(&db, encrypted_connection(user_id, "Subaccount", "sub-api-key", "sub-api-secret"))
+            .await
+            .unwrap();
+
+        let export = export_connections(&db, user_id).await.unwrap();
+        let backup = serde_json::to_string(&export).unwrap();
+        assert_eq!(export.connections.len(), 2);
+        for plaintext in ["plain-api-key", "plain-api-secret", "sub-api-key", "sub-api-secret", "correct horse"] {
+            assert!(!backup.contains(plaintext));
+        }
+
+        // Simulate a reinstall: the connections are gone, the backup file comes back
+        ExchangeConnectionEntity::delete_many()
+            .filter(exchange_connection::Column::UserId.eq(user_id))
+            .exec(&db)
+            .await
+            .unwrap();
+        let restored: ExchangeConnectionExport = serde_json::from_str(&backup).unwrap();
+        let response = import_connections(&db, user_id, restored).await.unwrap();
+        assert_eq!(response.imported.len(), 2);
+        assert!(response.skipped.is_empty());
+        assert!(!serde_json::to_string(&response).unwrap().contains("plain-api-key"));
+
+        let main = ExchangeConnectionEntity::find()
+            .filter(exchange_connection::Column::UserId.eq(user_id))
+            .filter(exchange_connection::Column::DisplayName.eq("Main"))
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(main.connection_status, "pending");
+        assert_eq!(
+            decrypt(&main, "correct horse").unwrap(),
+            ("plain-api-key".to_string(), "plain-api-secret".to_string())
+        );
+        assert!(decrypt(&main, "wrong password").is_err());
+
+        // Importing the same backup again leaves the restored connections alone
+        let again = import_connections(&db, user_id, serde_json::from_str(&backup).unwrap()).await.unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.skipped.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_another_users_export() {
+        let db = test_db().await;
+        let owner = seed_user(&db).await;
+        persist_new_exchange_connection(&db, encrypted_connection(owner, "Main", "plain-api-key", "plain-api-secret"))
+            .await
+            .unwrap();
+        let export = export_connections(&db, owner).await.unwrap();
+
+        let result = import_connections(&db, Uuid::new_v4(), export).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_import_with_an_invalid_entry_restores_nothing() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        persist_new_exchange_connection(&db, encrypted_connection(user_id, "Main", "plain-api-key", "plain-api-secret"))
+            .await
+            .unwrap();
+        persist_new_exchange_connection(&db, encrypted_connection(user_id, "Subaccount", "sub-api-key", "sub-api-secret"))
+            .await
+            .unwrap();
+        let mut export = export_connections(&db, user_id).await.unwrap();
+        ExchangeConnectionEntity::delete_many()
+            .filter(exchange_connection::Column::UserId.eq(user_id))
+            .exec(&db)
+            .await
+            .unwrap();
+
+        // The bad entry comes last, after one that would import fine
+        export.connections[1].exchange_name = "mtgox".to_string();
+        let result = import_connections(&db, user_id, export).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let remaining = ExchangeConnectionEntity::find()
+            .filter(exchange_connection::Column::UserId.eq(user_id))
+            .all(&db)
+            .await
+            .unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_display_name_on_same_exchange_conflicts() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+
+        persist_new_exchange_connection(&db, new_connection(user_id, "Main")).await.unwrap();
+        let duplicate = persist_new_exchange_connection(&db, new_connection(user_id, "Main")).await;
+        assert!(matches!(duplicate, Err(AppError::Conflict(_))));
+
+        let count = ExchangeConnectionEntity::find()
+            .filter(exchange_connection::Column::UserId.eq(user_id))
+            .all(&db)
+            .await
+            .unwrap()
+            .len();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_passphrase_exchange_without_passphrase_is_rejected() {
+        for passphrase in [None, Some("  ".to_string())] {
+            let result = connection_credentials(&Exchange::Kucoin, "key".to_string(), "secret".to_string(), passphrase);
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+
+        let okx = connection_credentials(&Exchange::OKX, "key".to_string(), "secret".to_string(), None);
+        assert!(matches!(okx, Err(AppError::BadRequest(_))));
+
+        // Exchanges without passphrases ignore one sent by mistake
+        let binance = connection_credentials(&Exchange::Binance, "key".to_string(), "secret".to_string(), Some("extra".to_string()))
+            .unwrap();
+        assert_eq!(binance.passphrase, None);
+    }
+
+    #[tokio::test]
+    async fn test_passphrase_round_trips_through_storage() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let credentials = connection_credentials(
+            &Exchange::OKX,
+            "okx-key".to_string(),
+            "okx-secret".to_string(),
+            Some("okx-passphrase".to_string()),
+        )
+        .unwrap();
+
+        let passphrase = EncryptionService::new()
+            .encrypt_api_credentials(credentials.passphrase.as_deref().unwrap(), "correct horse", &user_id.to_string())
+            .unwrap();
+        let mut connection = encrypted_connection(user_id, "Main", &credentials.api_key, &credentials.api_secret);
+        connection.exchange_name = Set("okx".to_string());
+        connection.encrypted_passphrase = Set(Some(passphrase.ciphertext));
+        connection.passphrase_nonce = Set(Some(passphrase.nonce));
+        connection.passphrase_salt = Set(Some(passphrase.salt));
+        let stored = persist_new_exchange_connection(&db, connection).await.unwrap();
+        assert_ne!(stored.encrypted_passphrase.as_deref(), Some("okx-passphrase"));
+
+        let decrypted = decrypt_connection_credentials(&stored, "correct horse", user_id).unwrap();
+        assert_eq!(decrypted.api_key, "okx-key");
+        assert_eq!(decrypted.api_secret, "okx-secret");
+        assert_eq!(decrypted.passphrase.as_deref(), Some("okx-passphrase"));
+        assert!(decrypt_connection_credentials(&stored, "wrong password", user_id).is_err());
+
+        // Connections stored without a passphrase still decrypt, just without one
+        let plain = persist_new_exchange_connection(&db, encrypted_connection(user_id, "Binance", "key", "secret"))
+            .await
+            .unwrap();
+        assert_eq!(decrypt_connection_credentials(&plain, "correct horse", user_id).unwrap().passphrase, None);
+    }
+}
@@ -0,0 +1,247 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use actix_ws::{Message, MessageStream, Session};
+use futures::StreamExt;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::services::{DCAExecutionEngine, ExecutionEvent};
+use crate::utils::errors::AppError;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+/// Open a WebSocket pushing the user's strategy executions and status changes as JSON
+/// as they happen
+pub async fn stream_execution_events(
+    execution_engine: web::Data<DCAExecutionEngine>,
+    req: HttpRequest,
+    body: web::Payload,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    // Subscribe before answering the handshake so no event after it is missed
+    let events = execution_engine.subscribe_events();
+    let (response, session, messages) = actix_ws::handle(&req, body)
+        .map_err(|e| AppError::BadRequest(format!("WebSocket handshake failed: {}", e)))?;
+
+    actix_web::rt::spawn(forward_events(user_id, events, session, messages));
+    Ok(response)
+}
+
+/// Push the user's events to the socket until the client goes away or the engine stops
+async fn forward_events(
+    user_id: Uuid,
+    mut events: broadcast::Receiver<ExecutionEvent>,
+    mut session: Session,
+    mut messages: MessageStream,
+) {
+    loop {
+        tokio::select! {
+            event = next_event_for(&mut events, user_id) => {
+                let Some(event) = event else {
+                    break;
+                };
+                let json = match serde_json::to_string(&event) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        warn!("Failed to serialize execution event: {}", e);
+                        continue;
+                    }
+                };
+                if session.text(json).await.is_err() {
+                    debug!("Execution event stream of user {} closed by the client", user_id);
+                    return;
+                }
+            }
+            message = messages.next() => match message {
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(reason))) => {
+                    let _ = session.close(reason).await;
+                    return;
+                }
+                Some(Ok(_)) => {}
+                // The connection dropped without a close frame
+                Some(Err(_)) | None => return,
+            },
+        }
+    }
+
+    let _ = session.close(None).await;
+}
+
+/// The next event about one of the user's strategies, or None once the engine is gone.
+/// A subscriber that fell behind skips what it missed rather than closing the stream.
+async fn next_event_for(
+    events: &mut broadcast::Receiver<ExecutionEvent>,
+    user_id: Uuid,
+) -> Option<ExecutionEvent> {
+    loop {
+        match events.recv().await {
+            Ok(event) if event.user_id() == user_id => return Some(event),
+            Ok(_) => {}
+            Err(RecvError::Lagged(missed)) => {
+                warn!("Execution event stream of user {} missed {} events", user_id, missed);
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::test_db;
+    use crate::services::MarketDataService;
+    use crate::utils::encryption::EncryptionService;
+    use crate::utils::mock_http::read_head;
+    use actix_session::{storage::CookieSessionStore, SessionMiddleware};
+    use actix_web::{cookie::Key, App, HttpServer};
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    async fn sign_in(req: HttpRequest, path: web::Path<Uuid>) -> HttpResponse {
+        let session = req.get_session();
+        session.insert("user_id", path.into_inner().to_string()).unwrap();
+        session.insert("authenticated", true).unwrap();
+        HttpResponse::Ok().finish()
+    }
+
+    /// Sign the user in and return their session cookie
+    async fn session_cookie(addr: SocketAddr, user_id: Uuid) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST /sign-in/{} HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            user_id
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        response
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("set-cookie")
+                    .then(|| value.trim().split(';').next().unwrap().to_string())
+            })
+            .expect("sign-in sets a session cookie")
+    }
+
+    /// Send the WebSocket handshake and return the response head and the socket
+    async fn open_socket(addr: SocketAddr, cookie: Option<&str>) -> (String, TcpStream) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let cookie = cookie.map(|cookie| format!("Cookie: {}\r\n", cookie)).unwrap_or_default();
+        let request = format!(
+            "GET /ws/executions HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n{}\r\n",
+            cookie
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        (read_head(&mut stream).await, stream)
+    }
+
+    /// Read one unmasked server frame: its opcode and payload
+    async fn read_frame(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let opcode = stream.read_u8().await.unwrap() & 0x0f;
+        let len = match stream.read_u8().await.unwrap() & 0x7f {
+            126 => stream.read_u16().await.unwrap() as usize,
+            127 => stream.read_u64().await.unwrap() as usize,
+            len => len as usize,
+        };
+        let mut payload = vec![0; len];
+        stream.read_exact(&mut payload).await.unwrap();
+        (opcode, payload)
+    }
+
+    async fn read_event(stream: &mut TcpStream) -> serde_json::Value {
+        let (opcode, payload) = tokio::time::timeout(Duration::from_secs(5), read_frame(stream))
+            .await
+            .expect("an event arrives");
+        assert_eq!(opcode, 0x1, "expected a text frame");
+        serde_json::from_slice(&payload).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_each_user_receives_only_their_own_strategy_events() {
+        let db = test_db().await;
+        let engine = DCAExecutionEngine::new(Arc::new(db), MarketDataService::new(), EncryptionService::new());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let key = Key::generate();
+        let engine_data = web::Data::new(engine.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(engine_data.clone())
+                .wrap(SessionMiddleware::new(CookieSessionStore::default(), key.clone()))
+                .route("/sign-in/{user_id}", web::post().to(sign_in))
+                .route("/ws/executions", web::get().to(stream_execution_events))
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        // No session, no stream
+        let (head, _) = open_socket(addr, None).await;
+        assert!(head.starts_with("HTTP/1.1 401"), "{}", head);
+
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let (alice_strategy, bob_strategy) = (Uuid::new_v4(), Uuid::new_v4());
+        let (head, mut alice_socket) = open_socket(addr, Some(&session_cookie(addr, alice).await)).await;
+        assert!(head.starts_with("HTTP/1.1 101"), "{}", head);
+        let (head, mut bob_socket) = open_socket(addr, Some(&session_cookie(addr, bob).await)).await;
+        assert!(head.starts_with("HTTP/1.1 101"), "{}", head);
+
+        engine.strategy_status_changed(bob, bob_strategy, "paused").await;
+        engine.strategy_status_changed(alice, alice_strategy, "paused").await;
+        engine.strategy_status_changed(alice, alice_strategy, "active").await;
+
+        let event = read_event(&mut alice_socket).await;
+        assert_eq!(event["event"], "status_changed");
+        assert_eq!(event["strategy_id"], alice_strategy.to_string());
+        assert_eq!(event["status"], "paused");
+        assert!(event.get("user_id").is_none());
+        assert_eq!(read_event(&mut alice_socket).await["status"], "active");
+
+        let event = read_event(&mut bob_socket).await;
+        assert_eq!(event["strategy_id"], bob_strategy.to_string());
+        // Alice's two events never reach Bob
+        assert!(tokio::time::timeout(Duration::from_millis(200), read_frame(&mut bob_socket)).await.is_err());
+
+        // A client closing the socket gets the close frame echoed back
+        alice_socket.write_all(&[0x88, 0x82, 0, 0, 0, 0, 0x03, 0xe8]).await.unwrap();
+        let (opcode, _) = tokio::time::timeout(Duration::from_secs(5), read_frame(&mut alice_socket))
+            .await
+            .expect("the server closes the socket");
+        assert_eq!(opcode, 0x8);
+
+        handle.stop(false).await;
+    }
+}
@@ -1,7 +1,7 @@
 use actix_web::{web, HttpRequest, HttpResponse, Result, HttpMessage};
 use std::sync::Arc;
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, QueryOrder, QuerySelect};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, QueryOrder, QuerySelect};
 use uuid::Uuid;
 use validator::Validate;
 use rust_decimal::Decimal;
@@ -14,8 +14,11 @@ use crate::models::grid_trading_strategy::{
     GridTradingStrategyResponse, GridTradingStrategiesResponse, GridTradingExecutionResponse,
     GridTradingStatus,
 };
-use crate::services::MarketDataService;
+use crate::handlers::kill_switch::ensure_kill_switch_clear;
+use crate::services::{ensure_symbol_allowed, MarketDataService};
+use crate::strategies::implementations::grid_trading::GridTradingConfig;
 use crate::utils::errors::AppError;
+use crate::utils::versioning::{expected_version, update_versioned};
 use actix_session::SessionExt;
 
 /// Extract authenticated user ID from session
@@ -35,51 +38,32 @@ fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
     Err(AppError::Unauthorized("Authentication required".to_string()))
 }
 
-/// Create a new Grid Trading strategy
-pub async fn create_grid_trading_strategy(
-    db: web::Data<Arc<DatabaseConnection>>,
-    req: HttpRequest,
-    body: web::Json<CreateGridTradingStrategyRequest>,
-) -> Result<HttpResponse, AppError> {
-    // Get user ID from session
-    let user_id = get_user_id_from_session(&req)?;
-
-    // Validate request
-    body.validate().map_err(AppError::ValidationError)?;
-
-    // Check if user already has a strategy with this name
-    let existing_strategy = GridTradingStrategyEntity::find()
-        .filter(crate::models::grid_trading_strategy::Column::UserId.eq(user_id))
-        .filter(crate::models::grid_trading_strategy::Column::Name.eq(&body.name))
-        .one(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
-
-    if existing_strategy.is_some() {
-        return Err(AppError::BadRequest("Strategy with this name already exists".to_string()));
-    }
-
+/// Validate `config` and build a new, active strategy row from it
+pub(crate) fn new_grid_trading_strategy_model(
+    user_id: Uuid,
+    name: &str,
+    asset_symbol: &str,
+    config: &GridTradingConfig,
+) -> Result<GridTradingStrategyActiveModel, AppError> {
     // Validate GridTradingConfig
-    body.config.validate().map_err(|e| AppError::BadRequest(format!("Invalid GridTradingConfig: {}", e)))?;
+    config.validate().map_err(|e| AppError::BadRequest(format!("Invalid GridTradingConfig: {}", e)))?;
 
     // Serialize GridTradingConfig to JSON
-    let config_json = serde_json::to_string(&body.config)
+    let config_json = serde_json::to_string(config)
         .map_err(|e| AppError::BadRequest(format!("Failed to serialize GridTradingConfig: {}", e)))?;
 
-    // Create the strategy
-    let strategy_id = Uuid::new_v4();
-    let new_strategy = GridTradingStrategyActiveModel {
-        id: Set(strategy_id),
+    Ok(GridTradingStrategyActiveModel {
+        id: Set(Uuid::new_v4()),
         user_id: Set(user_id),
-        name: Set(body.name.clone()),
-        asset_symbol: Set(body.asset_symbol.to_uppercase()),
+        name: Set(name.to_string()),
+        asset_symbol: Set(asset_symbol.to_uppercase()),
         status: Set(GridTradingStatus::Active.into()),
         config_json: Set(config_json),
         total_invested: Set(Decimal::ZERO),
         total_purchased: Set(Decimal::ZERO),
         average_buy_price: Set(None),
         current_inventory: Set(Decimal::ZERO),
-        grid_levels_count: Set(body.config.grid_levels as i32),
+        grid_levels_count: Set(config.grid_levels as i32),
         total_trades: Set(0),
         winning_trades: Set(0),
         losing_trades: Set(0),
@@ -94,22 +78,66 @@ pub async fn create_grid_trading_strategy(
         active_buy_orders: Set(0),
         active_sell_orders: Set(0),
         last_execution_at: Set(None),
+        version: Set(1),
+        state_json: Set(None),
+        kill_switch: Set(false),
         created_at: Set(Utc::now()),
         updated_at: Set(Utc::now()),
-    };
+    })
+}
+
+/// Insert a new strategy, rejecting a name the user already uses or a symbol they may
+/// not trade, and read it back
+pub(crate) async fn persist_new_grid_trading_strategy(
+    db: &DatabaseConnection,
+    new_strategy: GridTradingStrategyActiveModel,
+) -> Result<crate::models::grid_trading_strategy::Model, AppError> {
+    let user_id = new_strategy.user_id.clone().unwrap();
+    let name = new_strategy.name.clone().unwrap();
+    let strategy_id = new_strategy.id.clone().unwrap();
+
+    ensure_symbol_allowed(db, user_id, new_strategy.asset_symbol.as_ref()).await?;
+
+    // Check if user already has a strategy with this name
+    let existing_strategy = GridTradingStrategyEntity::find()
+        .filter(crate::models::grid_trading_strategy::Column::UserId.eq(user_id))
+        .filter(crate::models::grid_trading_strategy::Column::Name.eq(&name))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    if existing_strategy.is_some() {
+        return Err(AppError::BadRequest("Strategy with this name already exists".to_string()));
+    }
 
     // Insert without returning (to avoid UnpackInsertId error)
     GridTradingStrategyEntity::insert(new_strategy)
-        .exec_without_returning(db.as_ref().as_ref())
+        .exec_without_returning(db)
         .await
         .map_err(AppError::DatabaseError)?;
 
     // Fetch the created strategy
-    let strategy = GridTradingStrategyEntity::find_by_id(strategy_id)
-        .one(db.as_ref().as_ref())
+    GridTradingStrategyEntity::find_by_id(strategy_id)
+        .one(db)
         .await
         .map_err(AppError::DatabaseError)?
-        .ok_or(AppError::InternalServerError)?;
+        .ok_or(AppError::InternalServerError)
+}
+
+/// Create a new Grid Trading strategy
+pub async fn create_grid_trading_strategy(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    body: web::Json<CreateGridTradingStrategyRequest>,
+) -> Result<HttpResponse, AppError> {
+    // Get user ID from session
+    let user_id = get_user_id_from_session(&req)?;
+
+    // Validate request
+    body.validate().map_err(AppError::ValidationError)?;
+
+    let new_strategy = new_grid_trading_strategy_model(user_id, &body.name, &body.asset_symbol, &body.config)?;
+    let strategy = persist_new_grid_trading_strategy(db.as_ref().as_ref(), new_strategy).await?;
 
     // Convert to response format
     let response = GridTradingStrategyResponse {
@@ -119,6 +147,7 @@ pub async fn create_grid_trading_strategy(
         asset_symbol: strategy.asset_symbol.clone(),
         status: strategy.status.clone(),
         config: strategy.get_grid_trading_config().map_err(|e| AppError::BadRequest(e))?,
+        version: strategy.version,
         total_invested: strategy.total_invested,
         total_purchased: strategy.total_purchased,
         average_buy_price: strategy.average_buy_price,
@@ -244,6 +273,7 @@ pub async fn get_grid_trading_strategies(
             asset_symbol: strategy.asset_symbol.clone(),
             status: strategy.status.clone(),
             config: strategy.get_grid_trading_config().unwrap_or_else(|_| Default::default()),
+            version: strategy.version,
             total_invested: strategy.total_invested,
             total_purchased: strategy.total_purchased,
             average_buy_price: strategy.average_buy_price,
@@ -375,6 +405,7 @@ pub async fn get_grid_trading_strategy(
         asset_symbol: strategy.asset_symbol.clone(),
         status: strategy.status.clone(),
         config: strategy.get_grid_trading_config().unwrap_or_else(|_| Default::default()),
+        version: strategy.version,
         total_invested: strategy.total_invested,
         total_purchased: strategy.total_purchased,
         average_buy_price: strategy.average_buy_price,
@@ -441,9 +472,13 @@ pub async fn update_grid_trading_strategy(
         strategy.name = Set(name.clone());
     }
 
-    // Update status if provided
+    // Update status if provided; a kill-switched strategy can't be made active
     if let Some(status) = &body.status {
-        strategy.status = Set(status.clone().into());
+        let status: String = status.clone().into();
+        if status == "active" {
+            ensure_kill_switch_clear(strategy_id, *strategy.kill_switch.as_ref())?;
+        }
+        strategy.status = Set(status);
     }
 
     // Update config if provided
@@ -461,10 +496,16 @@ pub async fn update_grid_trading_strategy(
 
     strategy.updated_at = Set(Utc::now());
 
-    // Save changes
-    let updated_strategy = strategy.update(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
+    // Save changes, rejecting the write if another client updated the strategy first
+    let expected = expected_version(&req, body.expected_version)?;
+    let updated_strategy = update_versioned(
+        db.as_ref().as_ref(),
+        strategy,
+        strategy_id,
+        crate::models::grid_trading_strategy::Column::Id,
+        crate::models::grid_trading_strategy::Column::Version,
+        expected,
+    ).await?;
 
     // Convert to response format
     let response = GridTradingStrategyResponse {
@@ -474,6 +515,7 @@ pub async fn update_grid_trading_strategy(
         asset_symbol: updated_strategy.asset_symbol.clone(),
         status: updated_strategy.status.clone(),
         config: updated_strategy.get_grid_trading_config().unwrap_or_else(|_| Default::default()),
+        version: updated_strategy.version,
         total_invested: updated_strategy.total_invested,
         total_purchased: updated_strategy.total_purchased,
         average_buy_price: updated_strategy.average_buy_price,
@@ -0,0 +1,183 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::{dca_strategy, grid_trading_strategy, sma_crossover_strategy};
+use crate::services::DCAExecutionEngine;
+use crate::utils::errors::AppError;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+/// Refuse to make a strategy active while its kill switch is set
+pub(crate) fn ensure_kill_switch_clear(strategy_id: Uuid, kill_switch: bool) -> Result<(), AppError> {
+    if kill_switch {
+        return Err(AppError::Conflict(format!(
+            "Strategy {} has its kill switch set; clear it before resuming",
+            strategy_id
+        )));
+    }
+    Ok(())
+}
+
+/// Keep a strategy from ever running until the kill switch is cleared. Its status is
+/// left as is; the execution engine skips it whatever the status says.
+pub async fn set_kill_switch(
+    db: web::Data<Arc<DatabaseConnection>>,
+    execution_engine: web::Data<DCAExecutionEngine>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let strategy_id = path.into_inner();
+
+    let status = update_kill_switch(db.as_ref().as_ref(), user_id, strategy_id, true).await?;
+    execution_engine.strategy_status_changed(user_id, strategy_id, &status).await;
+
+    warn!("Kill switch set on strategy {} by user {}", strategy_id, user_id);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "strategy_id": strategy_id,
+        "kill_switch": true
+    })))
+}
+
+/// Clear a strategy's kill switch. The strategy keeps its status; a paused strategy
+/// still has to be resumed.
+pub async fn clear_kill_switch(
+    db: web::Data<Arc<DatabaseConnection>>,
+    execution_engine: web::Data<DCAExecutionEngine>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let strategy_id = path.into_inner();
+
+    let status = update_kill_switch(db.as_ref().as_ref(), user_id, strategy_id, false).await?;
+    execution_engine.strategy_status_changed(user_id, strategy_id, &status).await;
+
+    warn!("Kill switch cleared on strategy {} by user {}", strategy_id, user_id);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "strategy_id": strategy_id,
+        "kill_switch": false
+    })))
+}
+
+/// Set the kill switch on the user's strategy, whichever strategy type it is, and return
+/// the strategy's status
+pub(crate) async fn update_kill_switch(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    strategy_id: Uuid,
+    kill_switch: bool,
+) -> Result<String, AppError> {
+    let now = Utc::now();
+
+    let dca = dca_strategy::Entity::update_many()
+        .col_expr(dca_strategy::Column::KillSwitch, Expr::value(kill_switch))
+        .col_expr(dca_strategy::Column::UpdatedAt, Expr::value(now))
+        .filter(dca_strategy::Column::Id.eq(strategy_id))
+        .filter(dca_strategy::Column::UserId.eq(user_id))
+        .exec(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let sma = sma_crossover_strategy::Entity::update_many()
+        .col_expr(sma_crossover_strategy::Column::KillSwitch, Expr::value(kill_switch))
+        .col_expr(sma_crossover_strategy::Column::UpdatedAt, Expr::value(now))
+        .filter(sma_crossover_strategy::Column::Id.eq(strategy_id))
+        .filter(sma_crossover_strategy::Column::UserId.eq(user_id))
+        .exec(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let grid = grid_trading_strategy::Entity::update_many()
+        .col_expr(grid_trading_strategy::Column::KillSwitch, Expr::value(kill_switch))
+        .col_expr(grid_trading_strategy::Column::UpdatedAt, Expr::value(now))
+        .filter(grid_trading_strategy::Column::Id.eq(strategy_id))
+        .filter(grid_trading_strategy::Column::UserId.eq(user_id))
+        .exec(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let status = if dca.rows_affected > 0 {
+        dca_strategy::Entity::find_by_id(strategy_id).one(db).await.map(|s| s.map(|s| s.status))
+    } else if sma.rows_affected > 0 {
+        sma_crossover_strategy::Entity::find_by_id(strategy_id).one(db).await.map(|s| s.map(|s| s.status))
+    } else if grid.rows_affected > 0 {
+        grid_trading_strategy::Entity::find_by_id(strategy_id).one(db).await.map(|s| s.map(|s| s.status))
+    } else {
+        Ok(None)
+    }
+    .map_err(AppError::DatabaseError)?;
+
+    status.ok_or_else(|| AppError::NotFound("Strategy not found".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{seed_dca_strategy, seed_user, test_db};
+    use crate::handlers::sma_crossover_strategy_management::{
+        new_sma_crossover_strategy_model, persist_new_sma_crossover_strategy, pause_strategy, resume_strategy,
+    };
+    use crate::strategies::implementations::sma_crossover::SMACrossoverConfig;
+
+    #[tokio::test]
+    async fn test_kill_switched_strategy_cannot_be_resumed_until_cleared() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let model = new_sma_crossover_strategy_model(user_id, "Crossover", "btcusdt", &SMACrossoverConfig::default()).unwrap();
+        let strategy = persist_new_sma_crossover_strategy(&db, model).await.unwrap();
+
+        pause_strategy(&db, user_id, strategy.id).await.unwrap();
+        let status = update_kill_switch(&db, user_id, strategy.id, true).await.unwrap();
+        assert_eq!(status, "paused");
+
+        let resumed = resume_strategy(&db, user_id, strategy.id).await;
+        assert!(matches!(resumed, Err(AppError::Conflict(_))));
+        let stored = sma_crossover_strategy::Entity::find_by_id(strategy.id).one(&db).await.unwrap().unwrap();
+        assert_eq!(stored.status, "paused");
+        assert!(stored.kill_switch);
+
+        update_kill_switch(&db, user_id, strategy.id, false).await.unwrap();
+        resume_strategy(&db, user_id, strategy.id).await.unwrap();
+        let stored = sma_crossover_strategy::Entity::find_by_id(strategy.id).one(&db).await.unwrap().unwrap();
+        assert_eq!(stored.status, "active");
+    }
+
+    #[tokio::test]
+    async fn test_kill_switch_is_scoped_to_the_owner() {
+        let db = test_db().await;
+        let owner = seed_user(&db).await;
+        let strategy_id = seed_dca_strategy(&db, owner, "BTC").await;
+
+        let other = update_kill_switch(&db, Uuid::new_v4(), strategy_id, true).await;
+        assert!(matches!(other, Err(AppError::NotFound(_))));
+
+        update_kill_switch(&db, owner, strategy_id, true).await.unwrap();
+        let stored = dca_strategy::Entity::find_by_id(strategy_id).one(&db).await.unwrap().unwrap();
+        assert!(stored.kill_switch);
+        assert_eq!(stored.status, "active");
+    }
+}
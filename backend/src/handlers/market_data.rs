@@ -1,11 +1,19 @@
 use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
 use reqwest::Client;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use tracing::{info, error, debug};
 
+use crate::backtesting::BinanceFetcher;
+use crate::exchange_connectors::binance::{binance_rate_limiter, binance_retry_policy};
+use crate::exchange_connectors::{Kline, KlineInterval};
 use crate::utils::errors::AppError;
+use crate::utils::http::request_with_rate_limit;
 use crate::services::{DxyService, MarketIndicatorsService, MarketDataService};
 
 const BINANCE_API_BASE: &str = "https://api.binance.com";
@@ -51,10 +59,11 @@ pub async fn get_current_price(
 
     debug!("Requesting price for trading pair: {}", trading_pair);
 
-    let response = client
+    let request = client
         .get(&url)
-        .query(&[("symbol", &trading_pair)])
-        .send()
+        .query(&[("symbol", &trading_pair)]);
+
+    let response = request_with_rate_limit(&binance_retry_policy(), &binance_rate_limiter(), request)
         .await
         .map_err(|e| {
             error!("Failed to send request to Binance: {}", e);
@@ -183,3 +192,258 @@ pub async fn get_fear_greed_index(
 
     Ok(HttpResponse::Ok().json(response))
 }
+
+/// Most symbols one correlation request may compare
+const MAX_CORRELATION_SYMBOLS: usize = 20;
+
+/// Most bars per symbol one correlation request may span
+const MAX_CORRELATION_BARS: i64 = 5000;
+
+#[derive(Debug, Deserialize)]
+pub struct CorrelationRequest {
+    pub symbols: Vec<String>,
+    pub interval: String,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CorrelationMatrixResponse {
+    pub symbols: Vec<String>,
+    pub interval: String,
+    pub start_date: String,
+    pub end_date: String,
+    /// Correlation of close-to-close returns, rows and columns in `symbols` order.
+    /// `None` where either series is constant or the pair shares too few bars.
+    pub matrix: Vec<Vec<Option<f64>>>,
+}
+
+/// Pairwise return correlations for a basket of symbols
+pub async fn get_correlations(
+    request: web::Json<CorrelationRequest>,
+) -> Result<HttpResponse, AppError> {
+    let request = request.into_inner();
+
+    let mut symbols: Vec<String> = Vec::new();
+    for symbol in &request.symbols {
+        let symbol = symbol.trim().to_uppercase();
+        BinanceFetcher::validate_symbol(&symbol)?;
+        if !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    }
+    if symbols.len() < 2 || symbols.len() > MAX_CORRELATION_SYMBOLS {
+        return Err(AppError::BadRequest(format!(
+            "Between 2 and {} distinct symbols are required",
+            MAX_CORRELATION_SYMBOLS
+        )));
+    }
+
+    let interval = KlineInterval::from_str(&request.interval)
+        .ok_or_else(|| AppError::BadRequest(format!("Invalid interval: {}", request.interval)))?;
+    let start_time = DateTime::parse_from_rfc3339(&request.start_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid start date: {}", e)))?
+        .with_timezone(&Utc);
+    let end_time = DateTime::parse_from_rfc3339(&request.end_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid end date: {}", e)))?
+        .with_timezone(&Utc);
+    if start_time >= end_time {
+        return Err(AppError::BadRequest("Start date must be before end date".to_string()));
+    }
+    if (end_time - start_time).num_seconds() / interval.duration().num_seconds() > MAX_CORRELATION_BARS {
+        return Err(AppError::BadRequest(format!(
+            "Date range too large. Maximum {} {} bars allowed",
+            MAX_CORRELATION_BARS, request.interval
+        )));
+    }
+
+    info!("Computing {} correlations for {:?}", request.interval, symbols);
+
+    // The fetcher serves repeated ranges from the shared kline cache and paces the
+    // concurrent fetches through the shared Binance limiter
+    let fetcher = BinanceFetcher::new();
+    let series = try_join_all(
+        symbols
+            .iter()
+            .map(|symbol| fetcher.fetch_klines(symbol, &interval, start_time, end_time)),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(CorrelationMatrixResponse {
+        symbols,
+        interval: request.interval,
+        start_date: request.start_date,
+        end_date: request.end_date,
+        matrix: correlation_matrix(&series),
+    }))
+}
+
+/// Pairwise return correlations of kline series. Each pair is aligned on the bars
+/// both series have, so a gap in one series doesn't shift the other.
+fn correlation_matrix(series: &[Vec<Kline>]) -> Vec<Vec<Option<f64>>> {
+    let closes: Vec<BTreeMap<DateTime<Utc>, f64>> = series
+        .iter()
+        .map(|klines| {
+            klines
+                .iter()
+                .filter_map(|k| k.close.to_f64().map(|close| (k.open_time, close)))
+                .collect()
+        })
+        .collect();
+
+    closes
+        .iter()
+        .map(|a| closes.iter().map(|b| return_correlation(a, b)).collect())
+        .collect()
+}
+
+fn return_correlation(a: &BTreeMap<DateTime<Utc>, f64>, b: &BTreeMap<DateTime<Utc>, f64>) -> Option<f64> {
+    let common: Vec<(f64, f64)> = a
+        .iter()
+        .filter_map(|(time, x)| b.get(time).map(|y| (*x, *y)))
+        .collect();
+
+    let (returns_a, returns_b): (Vec<f64>, Vec<f64>) = common
+        .windows(2)
+        .filter(|pair| pair[0].0 > 0.0 && pair[0].1 > 0.0)
+        .map(|pair| (pair[1].0 / pair[0].0 - 1.0, pair[1].1 / pair[0].1 - 1.0))
+        .unzip();
+
+    pearson(&returns_a, &returns_b)
+}
+
+/// Pearson correlation, `None` for fewer than two points or a series with no variance
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    if x.len() < 2 || x.len() != y.len() {
+        return None;
+    }
+
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        let (dx, dy) = (a - mean_x, b - mean_y);
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+
+    if variance_x <= 0.0 || variance_y <= 0.0 {
+        return None;
+    }
+
+    Some((covariance / (variance_x.sqrt() * variance_y.sqrt())).clamp(-1.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use crate::exchange_connectors::numeric::decimal_from_f64;
+
+    /// Hourly klines closing at `closes`, skipping the hours in `gaps`
+    fn series(closes: &[f64], gaps: &[usize]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !gaps.contains(i))
+            .map(|(i, close)| {
+                let close = decimal_from_f64(*close).unwrap();
+                Kline {
+                    open_time: base_time + Duration::hours(i as i64),
+                    close_time: base_time + Duration::hours(i as i64 + 1),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: Decimal::ONE,
+                    quote_asset_volume: close,
+                    number_of_trades: 1,
+                    taker_buy_base_asset_volume: Decimal::ZERO,
+                    taker_buy_quote_asset_volume: Decimal::ZERO,
+                }
+            })
+            .collect()
+    }
+
+    /// Prices compounding the given per-bar returns from 100
+    fn compounded(returns: &[f64]) -> Vec<f64> {
+        let mut prices = vec![100.0];
+        for r in returns {
+            prices.push(prices.last().unwrap() * (1.0 + r));
+        }
+        prices
+    }
+
+    fn assert_close(value: Option<f64>, expected: f64) {
+        let value = value.expect("correlation should be defined");
+        assert!((value - expected).abs() < 1e-6, "expected {} but got {}", expected, value);
+    }
+
+    #[test]
+    fn test_scaled_series_correlate_perfectly() {
+        let a = [100.0, 104.0, 101.0, 107.0, 103.0, 110.0];
+        let b: Vec<f64> = a.iter().map(|p| p * 3.0).collect();
+
+        let matrix = correlation_matrix(&[series(&a, &[]), series(&b, &[])]);
+
+        assert_close(matrix[0][1], 1.0);
+        assert_close(matrix[1][0], 1.0);
+        assert_close(matrix[0][0], 1.0);
+    }
+
+    #[test]
+    fn test_opposite_returns_anti_correlate() {
+        let returns = [0.04, -0.03, 0.05, -0.02, 0.01];
+        let opposite: Vec<f64> = returns.iter().map(|r| -r).collect();
+
+        let matrix = correlation_matrix(&[series(&compounded(&returns), &[]), series(&compounded(&opposite), &[])]);
+
+        assert_close(matrix[0][1], -1.0);
+    }
+
+    #[test]
+    fn test_series_align_on_common_timestamps() {
+        let a = [100.0, 104.0, 101.0, 107.0, 103.0, 110.0, 108.0];
+        let b: Vec<f64> = a.iter().map(|p| p * 2.0).collect();
+
+        // Bars missing from either side are dropped from the pair, not shifted
+        let matrix = correlation_matrix(&[series(&a, &[2]), series(&b, &[4])]);
+
+        assert_close(matrix[0][1], 1.0);
+    }
+
+    #[test]
+    fn test_constant_series_has_no_correlation() {
+        let flat = [50.0; 6];
+        let moving = [100.0, 104.0, 101.0, 107.0, 103.0, 110.0];
+
+        let matrix = correlation_matrix(&[series(&flat, &[]), series(&moving, &[])]);
+
+        assert_eq!(matrix[0][1], None);
+        assert_eq!(matrix[0][0], None);
+        assert_close(matrix[1][1], 1.0);
+    }
+
+    #[actix_web::test]
+    async fn test_correlation_range_is_bounded() {
+        let request = |start_date: &str, end_date: &str| {
+            web::Json(CorrelationRequest {
+                symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+                interval: "1m".to_string(),
+                start_date: start_date.to_string(),
+                end_date: end_date.to_string(),
+            })
+        };
+
+        // A year of minute bars per symbol
+        let too_long = get_correlations(request("2023-01-01T00:00:00Z", "2024-01-01T00:00:00Z")).await;
+        assert!(matches!(too_long, Err(AppError::BadRequest(_))));
+
+        let backwards = get_correlations(request("2024-01-02T00:00:00Z", "2024-01-01T00:00:00Z")).await;
+        assert!(matches!(backwards, Err(AppError::BadRequest(_))));
+    }
+}
@@ -4,6 +4,7 @@ pub mod two_factor;
 pub mod session_management;
 pub mod exchange_management;
 pub mod wallet_management;
+pub mod wallet_signatures;
 pub mod dca_strategy_management;
 pub mod sma_crossover_strategy_management;
 pub mod grid_trading_strategy_management;
@@ -11,5 +12,14 @@ pub mod strategy_summary;
 pub mod backtest_management;
 pub mod market_data;
 pub mod stock_data;
+pub mod balance_snapshots;
+pub mod emergency_stop;
+pub mod strategy_presets;
+pub mod strategy_export;
+pub mod strategy_validation;
+pub mod kill_switch;
+pub mod paper_trading;
+pub mod execution_events;
+pub mod dex_quotes;
 // Removed legacy strategy_templates_handler - using new modular system
 pub use auth::*;
\ No newline at end of file
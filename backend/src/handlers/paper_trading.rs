@@ -0,0 +1,82 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use rust_decimal::Decimal;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::services::emergency_stop::StrategyKind;
+use crate::services::PaperTradingService;
+use crate::utils::errors::AppError;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaperTradingConfigRequest {
+    pub strategy_type: StrategyKind,
+    /// Defaults to the current starting balance, or 10,000 for a new portfolio
+    #[serde(default)]
+    pub paper_starting_balance: Option<Decimal>,
+}
+
+/// Get a strategy's paper portfolio and its paper trades
+pub async fn get_paper_portfolio(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let service = PaperTradingService::new(db.get_ref().clone());
+
+    Ok(HttpResponse::Ok().json(service.portfolio(user_id, path.into_inner()).await?))
+}
+
+/// Enable paper trading for a strategy or change its paper starting balance
+pub async fn configure_paper_trading(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<PaperTradingConfigRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let strategy_id = path.into_inner();
+    let service = PaperTradingService::new(db.get_ref().clone());
+
+    service
+        .configure(user_id, strategy_id, body.strategy_type, body.paper_starting_balance)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(service.portfolio(user_id, strategy_id).await?))
+}
+
+/// Restore the paper starting balance and clear the paper trade history
+pub async fn reset_paper_portfolio(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let strategy_id = path.into_inner();
+    let service = PaperTradingService::new(db.get_ref().clone());
+
+    service.reset(user_id, strategy_id).await?;
+    tracing::info!("User {} reset paper trading for strategy {}", user_id, strategy_id);
+
+    Ok(HttpResponse::Ok().json(service.portfolio(user_id, strategy_id).await?))
+}
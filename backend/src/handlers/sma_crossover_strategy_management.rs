@@ -14,7 +14,11 @@ use crate::models::sma_crossover_strategy::{
     CreateSMACrossoverStrategyRequest, UpdateSMACrossoverStrategyRequest,
     SMACrossoverStrategyResponse, SMACrossoverStrategiesResponse, SMACrossoverExecutionResponse,
 };
+use crate::handlers::kill_switch::ensure_kill_switch_clear;
+use crate::services::ensure_symbol_allowed;
+use crate::strategies::implementations::sma_crossover::SMACrossoverConfig;
 use crate::utils::errors::AppError;
+use crate::utils::versioning::{expected_version, update_versioned};
 
 /// Extract authenticated user ID from session
 fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
@@ -33,45 +37,26 @@ fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
     Err(AppError::Unauthorized("Authentication required".to_string()))
 }
 
-/// Create a new SMA Crossover strategy
-pub async fn create_sma_crossover_strategy(
-    db: web::Data<Arc<DatabaseConnection>>,
-    req: HttpRequest,
-    body: web::Json<CreateSMACrossoverStrategyRequest>,
-) -> Result<HttpResponse, AppError> {
-    let user_id = get_user_id_from_session(&req)?;
-
-    // Validate request
-    body.validate().map_err(AppError::ValidationError)?;
-
-    // Check if user already has a strategy with this name
-    let existing_strategy = SMACrossoverStrategyEntity::find()
-        .filter(crate::models::sma_crossover_strategy::Column::UserId.eq(user_id))
-        .filter(crate::models::sma_crossover_strategy::Column::Name.eq(&body.name))
-        .one(db.as_ref().as_ref())
-        .await
-        .map_err(AppError::DatabaseError)?;
-
-    if existing_strategy.is_some() {
-        return Err(AppError::BadRequest("Strategy with this name already exists".to_string()));
-    }
-
+/// Validate `config` and build a new, active strategy row from it
+pub(crate) fn new_sma_crossover_strategy_model(
+    user_id: Uuid,
+    name: &str,
+    asset_symbol: &str,
+    config: &SMACrossoverConfig,
+) -> Result<SMACrossoverStrategyActiveModel, AppError> {
     // Validate SMACrossoverConfig
-    body.config.validate().map_err(|e| AppError::BadRequest(format!("Invalid SMACrossoverConfig: {}", e)))?;
+    config.validate().map_err(|e| AppError::BadRequest(format!("Invalid SMACrossoverConfig: {}", e)))?;
 
     // Serialize SMACrossoverConfig to JSON
-    let config_json = serde_json::to_string(&body.config)
+    let config_json = serde_json::to_string(config)
         .map_err(|e| AppError::BadRequest(format!("Failed to serialize config: {}", e)))?;
 
-    // Create new strategy
     let now = Utc::now();
-    let strategy_id = Uuid::new_v4();
-
-    let new_strategy = SMACrossoverStrategyActiveModel {
-        id: Set(strategy_id),
+    Ok(SMACrossoverStrategyActiveModel {
+        id: Set(Uuid::new_v4()),
         user_id: Set(user_id),
-        name: Set(body.name.clone()),
-        asset_symbol: Set(body.asset_symbol.clone().to_uppercase()),
+        name: Set(name.to_string()),
+        asset_symbol: Set(asset_symbol.to_uppercase()),
         status: Set("active".to_string()),
         config_json: Set(config_json),
         total_invested: Set(Decimal::ZERO),
@@ -90,22 +75,65 @@ pub async fn create_sma_crossover_strategy(
         last_signal_type: Set(None),
         last_signal_time: Set(None),
         last_execution_at: Set(None),
+        version: Set(1),
+        state_json: Set(None),
+        kill_switch: Set(false),
         created_at: Set(now),
         updated_at: Set(now),
-    };
+    })
+}
+
+/// Insert a new strategy, rejecting a name the user already uses or a symbol they may
+/// not trade, and read it back
+pub(crate) async fn persist_new_sma_crossover_strategy(
+    db: &DatabaseConnection,
+    new_strategy: SMACrossoverStrategyActiveModel,
+) -> Result<crate::models::sma_crossover_strategy::Model, AppError> {
+    let user_id = new_strategy.user_id.clone().unwrap();
+    let name = new_strategy.name.clone().unwrap();
+    let strategy_id = new_strategy.id.clone().unwrap();
+
+    ensure_symbol_allowed(db, user_id, new_strategy.asset_symbol.as_ref()).await?;
+
+    // Check if user already has a strategy with this name
+    let existing_strategy = SMACrossoverStrategyEntity::find()
+        .filter(crate::models::sma_crossover_strategy::Column::UserId.eq(user_id))
+        .filter(crate::models::sma_crossover_strategy::Column::Name.eq(&name))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    if existing_strategy.is_some() {
+        return Err(AppError::BadRequest("Strategy with this name already exists".to_string()));
+    }
 
     // Insert without returning (to avoid UnpackInsertId error)
     SMACrossoverStrategyEntity::insert(new_strategy)
-        .exec_without_returning(db.as_ref().as_ref())
+        .exec_without_returning(db)
         .await
         .map_err(AppError::DatabaseError)?;
 
     // Fetch the created strategy
-    let saved_strategy = SMACrossoverStrategyEntity::find_by_id(strategy_id)
-        .one(db.as_ref().as_ref())
+    SMACrossoverStrategyEntity::find_by_id(strategy_id)
+        .one(db)
         .await
         .map_err(AppError::DatabaseError)?
-        .ok_or(AppError::InternalServerError)?;
+        .ok_or(AppError::InternalServerError)
+}
+
+/// Create a new SMA Crossover strategy
+pub async fn create_sma_crossover_strategy(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    body: web::Json<CreateSMACrossoverStrategyRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    // Validate request
+    body.validate().map_err(AppError::ValidationError)?;
+
+    let new_strategy = new_sma_crossover_strategy_model(user_id, &body.name, &body.asset_symbol, &body.config)?;
+    let saved_strategy = persist_new_sma_crossover_strategy(db.as_ref().as_ref(), new_strategy).await?;
 
     // Convert to response format
     let response = SMACrossoverStrategyResponse {
@@ -115,6 +143,7 @@ pub async fn create_sma_crossover_strategy(
         asset_symbol: saved_strategy.asset_symbol.clone(),
         status: saved_strategy.status.clone(),
         config: body.config.clone(),
+        version: saved_strategy.version,
         total_invested: saved_strategy.total_invested,
         total_purchased: saved_strategy.total_purchased,
         average_buy_price: saved_strategy.average_buy_price,
@@ -216,6 +245,7 @@ pub async fn get_user_sma_crossover_strategies(
             asset_symbol: strategy.asset_symbol.clone(),
             status: strategy.status.clone(),
             config,
+            version: strategy.version,
             total_invested: strategy.total_invested,
             total_purchased: strategy.total_purchased,
             average_buy_price: strategy.average_buy_price,
@@ -318,6 +348,7 @@ pub async fn get_sma_crossover_strategy(
         asset_symbol: strategy.asset_symbol.clone(),
         status: strategy.status.clone(),
         config,
+        version: strategy.version,
         total_invested: strategy.total_invested,
         total_purchased: strategy.total_purchased,
         average_buy_price: strategy.average_buy_price,
@@ -377,7 +408,11 @@ pub async fn update_sma_crossover_strategy(
     }
 
     if let Some(ref status) = body.status {
-        strategy_update.status = Set(status.clone().into());
+        let status: String = status.clone().into();
+        if status == "active" {
+            ensure_kill_switch_clear(strategy_id, *strategy_update.kill_switch.as_ref())?;
+        }
+        strategy_update.status = Set(status);
         updated = true;
     }
 
@@ -394,8 +429,17 @@ pub async fn update_sma_crossover_strategy(
 
     if updated {
         strategy_update.updated_at = Set(Utc::now());
-        let updated_strategy = strategy_update.update(db.as_ref().as_ref()).await
-            .map_err(AppError::DatabaseError)?;
+
+        // Reject the write if another client updated the strategy first
+        let expected = expected_version(&req, body.expected_version)?;
+        let updated_strategy = update_versioned(
+            db.as_ref().as_ref(),
+            strategy_update,
+            strategy_id,
+            crate::models::sma_crossover_strategy::Column::Id,
+            crate::models::sma_crossover_strategy::Column::Version,
+            expected,
+        ).await?;
 
         let config = updated_strategy.get_sma_crossover_config().map_err(|e| AppError::BadRequest(e))?;
 
@@ -406,6 +450,7 @@ pub async fn update_sma_crossover_strategy(
             asset_symbol: updated_strategy.asset_symbol.clone(),
             status: updated_strategy.status.clone(),
             config,
+            version: updated_strategy.version,
             total_invested: updated_strategy.total_invested,
             total_purchased: updated_strategy.total_purchased,
             average_buy_price: updated_strategy.average_buy_price,
@@ -479,10 +524,35 @@ pub async fn pause_sma_crossover_strategy(
 
     let strategy_id = path.into_inner();
 
+    pause_strategy(db.as_ref().as_ref(), user_id, strategy_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Strategy paused successfully"
+    })))
+}
+
+/// Resume an SMA Crossover strategy
+pub async fn resume_sma_crossover_strategy(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    let strategy_id = path.into_inner();
+
+    resume_strategy(db.as_ref().as_ref(), user_id, strategy_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Strategy resumed successfully"
+    })))
+}
+
+pub(crate) async fn pause_strategy(db: &DatabaseConnection, user_id: Uuid, strategy_id: Uuid) -> Result<(), AppError> {
     // Find and validate ownership
     let strategy = SMACrossoverStrategyEntity::find_by_id(strategy_id)
         .filter(crate::models::sma_crossover_strategy::Column::UserId.eq(user_id))
-        .one(db.as_ref().as_ref())
+        .one(db)
         .await
         .map_err(AppError::DatabaseError)?
         .ok_or_else(|| AppError::NotFound("Strategy not found".to_string()))?;
@@ -495,28 +565,17 @@ pub async fn pause_sma_crossover_strategy(
     strategy_update.status = Set("paused".to_string());
     strategy_update.updated_at = Set(Utc::now());
 
-    strategy_update.update(db.as_ref().as_ref()).await
+    strategy_update.update(db).await
         .map_err(AppError::DatabaseError)?;
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "message": "Strategy paused successfully"
-    })))
+    Ok(())
 }
 
-/// Resume an SMA Crossover strategy
-pub async fn resume_sma_crossover_strategy(
-    db: web::Data<Arc<DatabaseConnection>>,
-    req: HttpRequest,
-    path: web::Path<Uuid>,
-) -> Result<HttpResponse, AppError> {
-    let user_id = get_user_id_from_session(&req)?;
-
-    let strategy_id = path.into_inner();
-
+pub(crate) async fn resume_strategy(db: &DatabaseConnection, user_id: Uuid, strategy_id: Uuid) -> Result<(), AppError> {
     // Find and validate ownership
     let strategy = SMACrossoverStrategyEntity::find_by_id(strategy_id)
         .filter(crate::models::sma_crossover_strategy::Column::UserId.eq(user_id))
-        .one(db.as_ref().as_ref())
+        .one(db)
         .await
         .map_err(AppError::DatabaseError)?
         .ok_or_else(|| AppError::NotFound("Strategy not found".to_string()))?;
@@ -524,15 +583,14 @@ pub async fn resume_sma_crossover_strategy(
     if strategy.status != "paused" {
         return Err(AppError::BadRequest("Strategy is not paused".to_string()));
     }
+    ensure_kill_switch_clear(strategy.id, strategy.kill_switch)?;
 
     let mut strategy_update: SMACrossoverStrategyActiveModel = strategy.into();
     strategy_update.status = Set("active".to_string());
     strategy_update.updated_at = Set(Utc::now());
 
-    strategy_update.update(db.as_ref().as_ref()).await
+    strategy_update.update(db).await
         .map_err(AppError::DatabaseError)?;
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "message": "Strategy resumed successfully"
-    })))
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,386 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::handlers::dca_strategy_management::{new_dca_strategy_model, persist_new_dca_strategy, DCA_STRATEGY_ID};
+use crate::handlers::grid_trading_strategy_management::{new_grid_trading_strategy_model, persist_new_grid_trading_strategy};
+use crate::handlers::sma_crossover_strategy_management::{new_sma_crossover_strategy_model, persist_new_sma_crossover_strategy};
+use crate::handlers::strategy_presets::{GRID_TRADING_STRATEGY_ID, SMA_CROSSOVER_STRATEGY_ID};
+use crate::models::{dca_strategy, grid_trading_strategy, sma_crossover_strategy};
+use crate::strategies::{create_strategy, Strategy};
+use crate::utils::errors::AppError;
+
+pub const STRATEGY_EXPORT_VERSION: u32 = 1;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+/// Portable copy of one strategy's configuration and state. Unlike a connection
+/// export it holds nothing account-specific, so it can be shared with other users.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyExport {
+    pub version: u32,
+    /// Registry ID of the strategy, e.g. `grid_trading_v2`
+    pub strategy_type: String,
+    pub name: String,
+    pub asset_symbol: String,
+    /// Validated strategy parameters
+    pub parameters: Value,
+    /// Framework state from `Strategy::get_state`, if the strategy has one saved
+    pub state: Option<Value>,
+    pub metadata: StrategyExportMetadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyExportMetadata {
+    /// Version of the strategy implementation that produced `state`
+    pub strategy_version: String,
+    pub status: String,
+    pub exported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ImportStrategyRequest {
+    pub export: StrategyExport,
+    /// Name for the new strategy; defaults to the exported one
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    /// Restore the exported state instead of starting the strategy fresh
+    #[serde(default)]
+    pub restore_state: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportStrategyResponse {
+    pub id: Uuid,
+    pub strategy_type: String,
+    pub name: String,
+    pub state_restored: bool,
+    /// Why requested state was not restored
+    pub warning: Option<String>,
+}
+
+/// A user's strategy of any type, as stored
+struct StoredStrategy {
+    strategy_type: &'static str,
+    name: String,
+    asset_symbol: String,
+    status: String,
+    config_json: String,
+    state_json: Option<String>,
+}
+
+/// Export a strategy's parameters, state and metadata as portable JSON
+pub async fn export_strategy(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+
+    let export = build_export(db.as_ref().as_ref(), user_id, path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(export))
+}
+
+/// Create a strategy from an export, optionally restoring its state
+pub async fn import_strategy(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    body: web::Json<ImportStrategyRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    body.validate().map_err(AppError::ValidationError)?;
+
+    let response = create_from_export(db.as_ref().as_ref(), user_id, body.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+async fn find_strategy(db: &DatabaseConnection, user_id: Uuid, strategy_id: Uuid) -> Result<StoredStrategy, AppError> {
+    if let Some(s) = dca_strategy::Entity::find_by_id(strategy_id)
+        .filter(dca_strategy::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?
+    {
+        return Ok(StoredStrategy {
+            strategy_type: DCA_STRATEGY_ID,
+            name: s.name,
+            asset_symbol: s.asset_symbol,
+            status: s.status,
+            config_json: s.config_json,
+            state_json: s.state_json,
+        });
+    }
+
+    if let Some(s) = sma_crossover_strategy::Entity::find_by_id(strategy_id)
+        .filter(sma_crossover_strategy::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?
+    {
+        return Ok(StoredStrategy {
+            strategy_type: SMA_CROSSOVER_STRATEGY_ID,
+            name: s.name,
+            asset_symbol: s.asset_symbol,
+            status: s.status,
+            config_json: s.config_json,
+            state_json: s.state_json,
+        });
+    }
+
+    if let Some(s) = grid_trading_strategy::Entity::find_by_id(strategy_id)
+        .filter(grid_trading_strategy::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?
+    {
+        return Ok(StoredStrategy {
+            strategy_type: GRID_TRADING_STRATEGY_ID,
+            name: s.name,
+            asset_symbol: s.asset_symbol,
+            status: s.status,
+            config_json: s.config_json,
+            state_json: s.state_json,
+        });
+    }
+
+    Err(AppError::NotFound("Strategy not found".to_string()))
+}
+
+pub(crate) async fn build_export(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    strategy_id: Uuid,
+) -> Result<StrategyExport, AppError> {
+    let stored = find_strategy(db, user_id, strategy_id).await?;
+
+    let parameters: Value = serde_json::from_str(&stored.config_json).map_err(|e| {
+        tracing::error!("Strategy {} has unreadable config: {}", strategy_id, e);
+        AppError::InternalServerError
+    })?;
+    let mut strategy = create_strategy(stored.strategy_type)?;
+    strategy.validate_parameters(&parameters)?;
+
+    // Round-trip the saved state through the strategy so the export is what it produces
+    let state = match stored.state_json {
+        Some(state_json) => {
+            let saved: Value = serde_json::from_str(&state_json).map_err(|e| {
+                tracing::error!("Strategy {} has unreadable state: {}", strategy_id, e);
+                AppError::InternalServerError
+            })?;
+            strategy.restore_state(&saved)?;
+            Some(strategy.get_state()?)
+        }
+        None => None,
+    };
+
+    Ok(StrategyExport {
+        version: STRATEGY_EXPORT_VERSION,
+        strategy_type: stored.strategy_type.to_string(),
+        name: stored.name,
+        asset_symbol: stored.asset_symbol,
+        parameters,
+        state,
+        metadata: StrategyExportMetadata {
+            strategy_version: strategy.metadata().version,
+            status: stored.status,
+            exported_at: Utc::now(),
+        },
+    })
+}
+
+/// Recreate an exported strategy for `user_id`. Parameters must validate; state that
+/// the current strategy version can't restore is dropped with a warning and the
+/// strategy starts fresh.
+pub(crate) async fn create_from_export(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    request: ImportStrategyRequest,
+) -> Result<ImportStrategyResponse, AppError> {
+    let export = request.export;
+    if export.version != STRATEGY_EXPORT_VERSION {
+        return Err(AppError::BadRequest(format!("Unsupported export version {}", export.version)));
+    }
+
+    let mut strategy = create_strategy(&export.strategy_type)
+        .map_err(|_| AppError::BadRequest(format!("Unknown strategy type {}", export.strategy_type)))?;
+    strategy.validate_parameters(&export.parameters)?;
+
+    let (state_json, warning) = match (&export.state, request.restore_state) {
+        (Some(state), true) => match restore(strategy.as_mut(), state, &export.metadata.strategy_version) {
+            Ok(restored) => (Some(restored.to_string()), None),
+            Err(reason) => {
+                tracing::warn!("Importing {} strategy without its state: {}", export.strategy_type, reason);
+                (None, Some(format!("Exported state was not restored: {}", reason)))
+            }
+        },
+        _ => (None, None),
+    };
+
+    let name = request.name.unwrap_or(export.name);
+    let invalid = |e: serde_json::Error| AppError::BadRequest(format!("Invalid strategy parameters: {}", e));
+
+    let id = match export.strategy_type.as_str() {
+        DCA_STRATEGY_ID => {
+            let config = serde_json::from_value(export.parameters).map_err(invalid)?;
+            let mut model = new_dca_strategy_model(user_id, &name, &export.asset_symbol, &config)?;
+            model.state_json = Set(state_json.clone());
+            persist_new_dca_strategy(db, model).await?.id
+        }
+        SMA_CROSSOVER_STRATEGY_ID => {
+            let config = serde_json::from_value(export.parameters).map_err(invalid)?;
+            let mut model = new_sma_crossover_strategy_model(user_id, &name, &export.asset_symbol, &config)?;
+            model.state_json = Set(state_json.clone());
+            persist_new_sma_crossover_strategy(db, model).await?.id
+        }
+        GRID_TRADING_STRATEGY_ID => {
+            let config = serde_json::from_value(export.parameters).map_err(invalid)?;
+            let mut model = new_grid_trading_strategy_model(user_id, &name, &export.asset_symbol, &config)?;
+            model.state_json = Set(state_json.clone());
+            persist_new_grid_trading_strategy(db, model).await?.id
+        }
+        other => return Err(AppError::BadRequest(format!("Strategy {} cannot be imported", other))),
+    };
+
+    tracing::info!("User {} imported {} strategy {}", user_id, export.strategy_type, id);
+
+    Ok(ImportStrategyResponse {
+        id,
+        strategy_type: export.strategy_type,
+        name,
+        state_restored: state_json.is_some(),
+        warning,
+    })
+}
+
+/// Restore `state` into a fresh instance and return the state it now reports
+fn restore(strategy: &mut dyn Strategy, state: &Value, exported_version: &str) -> Result<Value, String> {
+    let current_version = strategy.metadata().version;
+    if exported_version != current_version {
+        return Err(format!(
+            "it was saved by version {} of the strategy, this is version {}",
+            exported_version, current_version
+        ));
+    }
+
+    strategy.restore_state(state).map_err(|e| e.to_string())?;
+    strategy.get_state().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use crate::database::test_support::{seed_user, test_db};
+    use crate::strategies::core::{StrategyContextBuilder, StrategyMode};
+    use crate::strategies::implementations::grid_trading::{GridTradingConfig, GridTradingStrategy};
+    use crate::strategies::init_all_strategies;
+
+    /// Export of a grid strategy initialized at 100, with the state that produced
+    async fn grid_export() -> StrategyExport {
+        let config = GridTradingConfig::simple(10, Decimal::from(1000), Decimal::ONE);
+        let parameters = serde_json::to_value(&config).unwrap();
+        let context = StrategyContextBuilder::new()
+            .strategy_id(Uuid::new_v4())
+            .user_id(Uuid::new_v4())
+            .symbol("BTCUSDT".to_string())
+            .interval("1h".to_string())
+            .mode(StrategyMode::Live)
+            .current_price(Decimal::from(100))
+            .available_balance(Decimal::from(10000))
+            .build()
+            .unwrap();
+        let mut strategy = GridTradingStrategy::new();
+        strategy.initialize(&parameters, StrategyMode::Live, &context).await.unwrap();
+
+        StrategyExport {
+            version: STRATEGY_EXPORT_VERSION,
+            strategy_type: GRID_TRADING_STRATEGY_ID.to_string(),
+            name: "Shared grid".to_string(),
+            asset_symbol: "BTCUSDT".to_string(),
+            parameters,
+            state: Some(strategy.get_state().unwrap()),
+            metadata: StrategyExportMetadata {
+                strategy_version: strategy.metadata().version,
+                status: "active".to_string(),
+                exported_at: Utc::now(),
+            },
+        }
+    }
+
+    fn request(export: StrategyExport, name: &str) -> ImportStrategyRequest {
+        ImportStrategyRequest { export, name: Some(name.to_string()), restore_state: true }
+    }
+
+    #[tokio::test]
+    async fn test_grid_config_and_state_round_trip_through_export_and_import() {
+        init_all_strategies().unwrap();
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let original = grid_export().await;
+        assert_eq!(original.state.as_ref().unwrap()["grid_levels"].as_array().unwrap().len(), 10);
+
+        let imported = create_from_export(&db, user_id, request(original.clone(), "Imported grid")).await.unwrap();
+        assert!(imported.state_restored);
+        assert!(imported.warning.is_none());
+
+        let exported = build_export(&db, user_id, imported.id).await.unwrap();
+        assert_eq!(exported.strategy_type, GRID_TRADING_STRATEGY_ID);
+        assert_eq!(exported.name, "Imported grid");
+        assert_eq!(exported.parameters, original.parameters);
+        assert_eq!(exported.state, original.state);
+        assert_eq!(exported.metadata.strategy_version, original.metadata.strategy_version);
+
+        // The export imports again, for another user
+        let other_user = seed_user(&db).await;
+        let again = create_from_export(&db, other_user, request(exported, "Imported grid")).await.unwrap();
+        assert!(again.state_restored);
+        assert!(matches!(build_export(&db, user_id, again.id).await, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_incompatible_state_imports_fresh_with_a_warning() {
+        init_all_strategies().unwrap();
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+
+        let mut garbled = grid_export().await;
+        garbled.state = Some(serde_json::json!({ "grid_levels": "not a list" }));
+        let imported = create_from_export(&db, user_id, request(garbled, "Garbled")).await.unwrap();
+        assert!(!imported.state_restored);
+        assert!(imported.warning.is_some());
+        assert!(build_export(&db, user_id, imported.id).await.unwrap().state.is_none());
+
+        let mut outdated = grid_export().await;
+        outdated.metadata.strategy_version = "0.0.1".to_string();
+        let imported = create_from_export(&db, user_id, request(outdated, "Outdated")).await.unwrap();
+        assert!(!imported.state_restored);
+        assert!(imported.warning.unwrap().contains("0.0.1"));
+
+        let mut invalid = grid_export().await;
+        invalid.parameters["grid_levels"] = serde_json::json!(0);
+        assert!(create_from_export(&db, user_id, request(invalid, "Invalid")).await.is_err());
+    }
+}
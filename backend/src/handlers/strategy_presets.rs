@@ -0,0 +1,204 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::handlers::dca_strategy_management::{new_dca_strategy_model, persist_new_dca_strategy, DCA_STRATEGY_ID};
+use crate::handlers::grid_trading_strategy_management::{new_grid_trading_strategy_model, persist_new_grid_trading_strategy};
+use crate::handlers::sma_crossover_strategy_management::{new_sma_crossover_strategy_model, persist_new_sma_crossover_strategy};
+use crate::strategies::{list_strategy_presets, resolve_strategy_preset};
+use crate::utils::errors::AppError;
+
+/// Registry ID of the SMA crossover strategy
+pub(crate) const SMA_CROSSOVER_STRATEGY_ID: &str = "sma_crossover_v2";
+/// Registry ID of the grid trading strategy
+pub(crate) const GRID_TRADING_STRATEGY_ID: &str = "grid_trading_v2";
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateStrategyFromPresetRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, max = 20))]
+    pub asset_symbol: String,
+    #[validate(length(min = 1, max = 50))]
+    pub preset_id: String,
+    /// Top-level parameters replacing the preset's values, e.g. `{"base_amount": 250}`
+    pub overrides: Option<Value>,
+}
+
+/// List the presets a registered strategy offers
+pub async fn get_strategy_presets(
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    get_user_id_from_session(&req)?;
+
+    let strategy_id = path.into_inner();
+    let presets = list_strategy_presets(&strategy_id)?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "strategy_id": strategy_id,
+        "total_count": presets.len(),
+        "presets": presets,
+    })))
+}
+
+/// Create a strategy of any registered type from one of its presets
+pub async fn create_strategy_from_preset(
+    db: web::Data<Arc<DatabaseConnection>>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<CreateStrategyFromPresetRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    body.validate().map_err(AppError::ValidationError)?;
+
+    let strategy = create_from_preset(db.as_ref().as_ref(), user_id, &path.into_inner(), &body).await?;
+
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Strategy created successfully from preset",
+        "strategy": strategy,
+    })))
+}
+
+/// Resolve the preset, validate it against the strategy and store it in that strategy's table
+pub(crate) async fn create_from_preset(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    strategy_id: &str,
+    request: &CreateStrategyFromPresetRequest,
+) -> Result<Value, AppError> {
+    let parameters = resolve_strategy_preset(strategy_id, &request.preset_id, request.overrides.as_ref())?;
+    let invalid = |e: serde_json::Error| AppError::BadRequest(format!("Invalid preset parameters: {}", e));
+
+    let (id, name, asset_symbol, status, created_at) = match strategy_id {
+        DCA_STRATEGY_ID => {
+            let config = serde_json::from_value(parameters.clone()).map_err(invalid)?;
+            let model = new_dca_strategy_model(user_id, &request.name, &request.asset_symbol, &config)?;
+            let strategy = persist_new_dca_strategy(db, model).await?;
+            (strategy.id, strategy.name, strategy.asset_symbol, strategy.status, strategy.created_at)
+        }
+        SMA_CROSSOVER_STRATEGY_ID => {
+            let config = serde_json::from_value(parameters.clone()).map_err(invalid)?;
+            let model = new_sma_crossover_strategy_model(user_id, &request.name, &request.asset_symbol, &config)?;
+            let strategy = persist_new_sma_crossover_strategy(db, model).await?;
+            (strategy.id, strategy.name, strategy.asset_symbol, strategy.status, strategy.created_at)
+        }
+        GRID_TRADING_STRATEGY_ID => {
+            let config = serde_json::from_value(parameters.clone()).map_err(invalid)?;
+            let model = new_grid_trading_strategy_model(user_id, &request.name, &request.asset_symbol, &config)?;
+            let strategy = persist_new_grid_trading_strategy(db, model).await?;
+            (strategy.id, strategy.name, strategy.asset_symbol, strategy.status, strategy.created_at)
+        }
+        _ => return Err(AppError::BadRequest(format!("Strategy {} cannot be created from a preset", strategy_id))),
+    };
+
+    Ok(json!({
+        "id": id,
+        "strategy_type": strategy_id,
+        "name": name,
+        "asset_symbol": asset_symbol,
+        "status": status,
+        "preset_id": request.preset_id,
+        "config": parameters,
+        "created_at": created_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{seed_user, test_db};
+    use crate::strategies::{create_strategy, get_global_registry, init_all_strategies};
+
+    fn request(preset_id: &str, overrides: Option<Value>) -> CreateStrategyFromPresetRequest {
+        CreateStrategyFromPresetRequest {
+            name: format!("From {}", preset_id),
+            asset_symbol: "btcusdt".to_string(),
+            preset_id: preset_id.to_string(),
+            overrides,
+        }
+    }
+
+    #[test]
+    fn test_every_registered_strategy_lists_valid_presets() {
+        init_all_strategies().unwrap();
+        let strategy_ids = get_global_registry().read().unwrap().get_strategy_ids();
+
+        for strategy_id in [DCA_STRATEGY_ID, SMA_CROSSOVER_STRATEGY_ID, GRID_TRADING_STRATEGY_ID] {
+            assert!(strategy_ids.iter().any(|id| id == strategy_id), "{} is not registered", strategy_id);
+        }
+
+        for strategy_id in strategy_ids {
+            let presets = list_strategy_presets(&strategy_id).unwrap();
+            assert!(!presets.is_empty(), "{} has no presets", strategy_id);
+
+            let strategy = create_strategy(&strategy_id).unwrap();
+            for preset in presets {
+                assert!(
+                    strategy.validate_parameters(&preset.parameters).is_ok(),
+                    "{} preset {} does not validate",
+                    strategy_id,
+                    preset.id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_preset_overrides_are_validated() {
+        init_all_strategies().unwrap();
+
+        let parameters = resolve_strategy_preset(SMA_CROSSOVER_STRATEGY_ID, "balanced", Some(&json!({ "fast_period": 5 })))
+            .unwrap();
+        assert_eq!(parameters["fast_period"], 5);
+
+        let inverted = resolve_strategy_preset(SMA_CROSSOVER_STRATEGY_ID, "balanced", Some(&json!({ "fast_period": 50 })));
+        assert!(matches!(inverted, Err(AppError::BadRequest(_))));
+
+        let unknown = resolve_strategy_preset(GRID_TRADING_STRATEGY_ID, "yolo", None);
+        assert!(matches!(unknown, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_from_preset_stores_each_strategy_type() {
+        init_all_strategies().unwrap();
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+
+        let dca = create_from_preset(&db, user_id, DCA_STRATEGY_ID, &request("conservative", Some(json!({ "base_amount": "250" }))))
+            .await
+            .unwrap();
+        assert_eq!(dca["asset_symbol"], "BTCUSDT");
+        assert_eq!(dca["config"]["base_amount"], "250");
+
+        for strategy_id in [SMA_CROSSOVER_STRATEGY_ID, GRID_TRADING_STRATEGY_ID] {
+            let created = create_from_preset(&db, user_id, strategy_id, &request("balanced", None)).await.unwrap();
+            assert_eq!(created["strategy_type"], strategy_id);
+        }
+
+        let duplicate = create_from_preset(&db, user_id, SMA_CROSSOVER_STRATEGY_ID, &request("balanced", None)).await;
+        assert!(matches!(duplicate, Err(AppError::BadRequest(_))));
+    }
+}
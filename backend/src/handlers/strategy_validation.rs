@@ -0,0 +1,124 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::strategies::create_strategy;
+use crate::utils::errors::AppError;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+/// Outcome of a dry-run parameter check; `errors` is left out when the parameters are valid
+#[derive(Debug, Serialize)]
+pub struct ParameterValidationResponse {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ParameterValidationError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParameterValidationError {
+    pub message: String,
+}
+
+/// Check parameters against a registered strategy without creating anything
+pub async fn validate_strategy_parameters(
+    req: HttpRequest,
+    path: web::Path<String>,
+    parameters: web::Json<Value>,
+) -> Result<HttpResponse, AppError> {
+    get_user_id_from_session(&req)?;
+
+    let response = check_parameters(&path.into_inner(), &parameters)?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Run the strategy's own `validate_parameters`. Rejected parameters are a normal
+/// result; only an unknown strategy is an error.
+fn check_parameters(strategy_name: &str, parameters: &Value) -> Result<ParameterValidationResponse, AppError> {
+    let strategy = create_strategy(strategy_name)?;
+
+    match strategy.validate_parameters(parameters) {
+        Ok(()) => Ok(ParameterValidationResponse { valid: true, errors: Vec::new() }),
+        Err(AppError::BadRequest(message)) => Ok(ParameterValidationResponse {
+            valid: false,
+            errors: vec![ParameterValidationError { message }],
+        }),
+        Err(other) => Err(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::dca_strategy_management::DCA_STRATEGY_ID;
+    use crate::handlers::strategy_presets::GRID_TRADING_STRATEGY_ID;
+    use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency, RSIConfig};
+    use crate::strategies::{init_all_strategies, list_strategy_presets};
+    use rust_decimal::Decimal;
+    use serde_json::json;
+
+    fn rsi_dca_parameters() -> Value {
+        let config = DCAConfig::rsi_based(Decimal::from(100), DCAFrequency::Daily(1), RSIConfig::default());
+        serde_json::to_value(config).unwrap()
+    }
+
+    #[test]
+    fn test_rsi_based_dca_needs_its_rsi_settings() {
+        init_all_strategies().unwrap();
+
+        let valid = check_parameters(DCA_STRATEGY_ID, &rsi_dca_parameters()).unwrap();
+        assert!(valid.valid);
+        assert_eq!(serde_json::to_value(&valid).unwrap(), json!({ "valid": true }));
+
+        let mut without_period = rsi_dca_parameters();
+        without_period["rsi_config"].as_object_mut().unwrap().remove("period");
+        let rejected = check_parameters(DCA_STRATEGY_ID, &without_period).unwrap();
+        assert!(!rejected.valid);
+        assert_eq!(rejected.errors.len(), 1);
+        assert!(rejected.errors[0].message.contains("period"), "{}", rejected.errors[0].message);
+
+        let mut without_rsi = rsi_dca_parameters();
+        without_rsi["rsi_config"] = Value::Null;
+        let rejected = check_parameters(DCA_STRATEGY_ID, &without_rsi).unwrap();
+        assert!(!rejected.valid);
+        assert!(rejected.errors[0].message.contains("RSI configuration is required"));
+    }
+
+    #[test]
+    fn test_grid_trading_rejects_non_positive_bounds() {
+        init_all_strategies().unwrap();
+        let mut parameters = list_strategy_presets(GRID_TRADING_STRATEGY_ID).unwrap().remove(0).parameters;
+        assert!(check_parameters(GRID_TRADING_STRATEGY_ID, &parameters).unwrap().valid);
+
+        parameters["bounds"]["lower_bound"] = json!("-5");
+        let rejected = check_parameters(GRID_TRADING_STRATEGY_ID, &parameters).unwrap();
+        assert!(!rejected.valid);
+        assert_eq!(rejected.errors[0].message, "Grid bounds must be positive");
+
+        let body = serde_json::to_value(&rejected).unwrap();
+        assert_eq!(body["errors"][0]["message"], "Grid bounds must be positive");
+    }
+
+    #[test]
+    fn test_unknown_strategy_is_not_found() {
+        init_all_strategies().unwrap();
+        assert!(matches!(check_parameters("no_such_strategy", &json!({})), Err(AppError::NotFound(_))));
+    }
+}
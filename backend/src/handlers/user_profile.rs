@@ -12,6 +12,8 @@ use crate::models::{
     },
     CreateUserProfileRequest, UpdateUserProfileRequest, UserProfileResponse,
 };
+use crate::services::fx_rates::{normalize_base_currency, USD};
+use crate::strategies::core::validate_max_order_notional;
 use crate::utils::errors::AppError;
 
 /// Extract authenticated user ID from session
@@ -52,6 +54,11 @@ pub async fn create_profile(
         ));
     }
 
+    let base_currency = match &req.base_currency {
+        Some(code) => normalize_base_currency(code).map_err(AppError::BadRequest)?,
+        None => USD.to_string(),
+    };
+
     let join_date = Utc::now().format("%B %Y").to_string();
     let profile_id = Uuid::new_v4();
 
@@ -66,6 +73,11 @@ pub async fn create_profile(
         join_date: Set(join_date),
         avatar_url: Set(req.avatar_url.clone()),
         is_verified: Set(false),
+        max_order_notional: Set(None),
+        clamp_oversized_orders: Set(false),
+        symbol_allow_list: Set(None),
+        symbol_deny_list: Set(None),
+        base_currency: Set(base_currency),
         created_at: Set(Utc::now()),
         updated_at: Set(Utc::now()),
     };
@@ -165,6 +177,16 @@ pub async fn update_profile(
     if req.avatar_url.is_some() {
         profile_active_model.avatar_url = Set(req.avatar_url.clone());
     }
+    if let Some(max_order_notional) = req.max_order_notional {
+        validate_max_order_notional(max_order_notional).map_err(AppError::BadRequest)?;
+        profile_active_model.max_order_notional = Set(max_order_notional);
+    }
+    if let Some(clamp) = req.clamp_oversized_orders {
+        profile_active_model.clamp_oversized_orders = Set(clamp);
+    }
+    if let Some(code) = &req.base_currency {
+        profile_active_model.base_currency = Set(normalize_base_currency(code).map_err(AppError::BadRequest)?);
+    }
 
     profile_active_model.updated_at = Set(Utc::now());
 
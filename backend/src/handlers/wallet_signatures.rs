@@ -0,0 +1,194 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_session::SessionExt;
+use ethers::types::Address;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::dex_connectors::signer::{wallet_connect_requests, SignatureRequest, SignatureRequests};
+use crate::dex_connectors::DexError;
+use crate::models::wallet_connection::{self, BlockchainNetwork, Entity as WalletConnectionEntity};
+use crate::utils::errors::AppError;
+
+/// Extract authenticated user ID from session
+fn get_user_id_from_session(req: &HttpRequest) -> Result<Uuid, AppError> {
+    let session = req.get_session();
+
+    if let Ok(Some(user_id_str)) = session.get::<String>("user_id") {
+        if let Ok(Some(authenticated)) = session.get::<bool>("authenticated") {
+            if authenticated {
+                if let Ok(user_id) = Uuid::parse_str(&user_id_str) {
+                    return Ok(user_id);
+                }
+            }
+        }
+    }
+
+    Err(AppError::Unauthorized("Authentication required".to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PendingSignaturesQuery {
+    /// WalletConnect session topic the frontend holds
+    pub topic: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitSignatureRequest {
+    /// 65-byte signature as hex
+    pub signature: String,
+}
+
+/// Addresses of the user's EVM wallets. A user only sees and answers signature requests
+/// from these, whatever session topic they name.
+async fn user_wallet_addresses(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<Address>, AppError> {
+    let connections = WalletConnectionEntity::find()
+        .filter(wallet_connection::Column::UserId.eq(user_id))
+        .filter(wallet_connection::Column::BlockchainNetwork.is_in([
+            BlockchainNetwork::Ethereum.as_str(),
+            BlockchainNetwork::BNBChain.as_str(),
+        ]))
+        .all(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    Ok(connections
+        .iter()
+        .filter_map(|connection| Address::from_str(&connection.wallet_address).ok())
+        .collect())
+}
+
+/// Requests in session `topic` waiting on one of `addresses`
+fn visible_requests(requests: &SignatureRequests, topic: &str, addresses: &[Address]) -> Vec<SignatureRequest> {
+    requests
+        .pending(topic)
+        .into_iter()
+        .filter(|request| addresses.contains(&request.from))
+        .collect()
+}
+
+/// Request `id` if it is waiting on one of `addresses`. Other users' requests are
+/// reported as missing so their ids can't be probed.
+fn owned_request(requests: &SignatureRequests, id: Uuid, addresses: &[Address]) -> Result<SignatureRequest, AppError> {
+    requests
+        .get(id)
+        .filter(|request| addresses.contains(&request.from))
+        .ok_or_else(|| AppError::NotFound("Signature request not found".to_string()))
+}
+
+/// Signature requests waiting on the user's wallets in a WalletConnect session
+pub async fn get_pending_signatures(
+    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+    query: web::Query<PendingSignaturesQuery>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let addresses = user_wallet_addresses(db.get_ref(), user_id).await?;
+
+    let requests = visible_requests(&wallet_connect_requests(), &query.topic, &addresses);
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "requests": requests })))
+}
+
+/// Hand back the signature the user's wallet made for a request
+pub async fn submit_signature(
+    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<SubmitSignatureRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let addresses = user_wallet_addresses(db.get_ref(), user_id).await?;
+
+    let requests = wallet_connect_requests();
+    let request = owned_request(&requests, path.into_inner(), &addresses)?;
+    requests.submit(request.id, &body.signature).map_err(signature_error)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Signature submitted"
+    })))
+}
+
+/// Decline a request, failing the transaction waiting on it
+pub async fn reject_signature(
+    db: web::Data<DatabaseConnection>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = get_user_id_from_session(&req)?;
+    let addresses = user_wallet_addresses(db.get_ref(), user_id).await?;
+
+    let requests = wallet_connect_requests();
+    let request = owned_request(&requests, path.into_inner(), &addresses)?;
+    if !requests.reject(request.id) {
+        return Err(AppError::NotFound("Signature request not found".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Signature request rejected"
+    })))
+}
+
+fn signature_error(error: DexError) -> AppError {
+    match error {
+        DexError::TransactionFailed(message) => AppError::Conflict(message),
+        other => AppError::BadRequest(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex_connectors::signer::{MockSigner, WalletConnectSigner};
+    use crate::dex_connectors::{TransactionSigner, WalletConnectSession};
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use ethers::types::TransactionRequest;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_only_the_wallets_owner_can_answer_its_requests() {
+        let requests = Arc::new(SignatureRequests::default());
+        let owner = MockSigner::new();
+        let session = WalletConnectSession { topic: "shared-topic".to_string() };
+        let signer = WalletConnectSigner::new(session, owner.address(), requests.clone());
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            .value(1_000u64)
+            .nonce(1u64)
+            .gas(21_000u64)
+            .gas_price(30_000_000_000u64)
+            .chain_id(1u64)
+            .into();
+
+        let frontend = {
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                let request = loop {
+                    if let Some(request) = requests.pending("shared-topic").pop() {
+                        break request;
+                    }
+                    tokio::task::yield_now().await;
+                };
+
+                // Someone else who knows the topic sees nothing and can't touch the request
+                let stranger = [Address::repeat_byte(0x33)];
+                assert!(visible_requests(&requests, "shared-topic", &stranger).is_empty());
+                assert!(matches!(owned_request(&requests, request.id, &stranger), Err(AppError::NotFound(_))));
+
+                let owner = [MockSigner::new().address()];
+                assert_eq!(visible_requests(&requests, "shared-topic", &owner).len(), 1);
+                let owned = owned_request(&requests, request.id, &owner).unwrap();
+
+                let bad = requests.submit(owned.id, "not a signature").map_err(signature_error);
+                assert!(matches!(bad, Err(AppError::BadRequest(_))));
+
+                let signature = MockSigner::new().sign_transaction(&owned.transaction).await.unwrap();
+                requests.submit(owned.id, &signature.to_string()).unwrap();
+            })
+        };
+
+        signer.sign_transaction(&tx).await.unwrap();
+        frontend.await.unwrap();
+        assert!(requests.get(Uuid::new_v4()).is_none());
+    }
+}
@@ -11,7 +11,6 @@ mod dex_connectors;
 mod backtesting;
 mod strategies;
 
-use actix_cors::Cors;
 use actix_web::{web, App, HttpServer, middleware::Logger, cookie::Key, dev::Service as _};
 use actix_session::{SessionMiddleware, storage::CookieSessionStore};
 use anyhow::{Result, Context};
@@ -21,10 +20,11 @@ use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
 use config::Config;
 use handlers::AuthService;
-use middleware::{SessionTrackingMiddleware, auth::AuthMiddleware};
-use routes::configure_routes;
-use services::{MarketDataService, DCAExecutionEngine, DxyService, MarketIndicatorsService, StockDataService};
+use middleware::{SessionTrackingMiddleware, MetricsMiddleware, cors_from_config, auth::AuthMiddleware};
+use routes::{configure_routes, configure_metrics_route};
+use services::{MarketDataService, DCAExecutionEngine, ConnectionHistoryService, DxyService, FxRateService, MarketIndicatorsService, StockDataService};
 use utils::encryption::EncryptionService;
+use utils::session_tracker::SessionPolicy;
 
 /// Initialize application services
 struct AppServices {
@@ -33,8 +33,10 @@ struct AppServices {
     market_service: MarketDataService,
     execution_engine: DCAExecutionEngine,
     dxy_service: DxyService,
+    fx_rates: FxRateService,
     market_indicators: MarketIndicatorsService,
     stock_service: StockDataService,
+    connection_history: ConnectionHistoryService,
 }
 
 impl AppServices {
@@ -47,7 +49,8 @@ impl AppServices {
         );
 
         // Initialize services
-        let auth_service = AuthService::new(config.jwt_secret.clone());
+        let auth_service = AuthService::new(config.jwt_secret.clone())
+            .with_session_policy(SessionPolicy::from_config(config));
         let market_service = MarketDataService::new();
         let encryption_service = EncryptionService::new();
 
@@ -66,20 +69,28 @@ impl AppServices {
         // Initialize DXY service
         let dxy_service = DxyService::default();
 
+        // Initialize FX rate service for base currency valuations
+        let fx_rates = FxRateService::default();
+
         // Initialize Market Indicators service
         let market_indicators = MarketIndicatorsService::default();
 
         // Initialize Stock Data service
         let stock_service = StockDataService::new(config.alpha_vantage_api_key.clone());
 
+        // Initialize opt-in balance history recording
+        let connection_history = ConnectionHistoryService::from_env(database.clone());
+
         Ok(Self {
             database,
             auth_service,
             market_service,
             execution_engine,
             dxy_service,
+            fx_rates,
             market_indicators,
             stock_service,
+            connection_history,
         })
     }
 
@@ -90,6 +101,11 @@ impl AppServices {
         tokio::spawn(async move {
             engine_clone.start_engine().await;
         });
+
+        let history_clone = self.connection_history.clone();
+        tokio::spawn(async move {
+            history_clone.start().await;
+        });
     }
 }
 
@@ -145,29 +161,18 @@ fn create_server(
         let market_service = services.market_service.clone();
         let execution_engine = services.execution_engine.clone();
         let dxy_service = services.dxy_service.clone();
+        let fx_rates = services.fx_rates.clone();
         let market_indicators = services.market_indicators.clone();
         let stock_service = services.stock_service.clone();
+        let connection_history = services.connection_history.clone();
         // Legacy strategy_template_service removed
         let secret_key = secret_key.clone();
-        let cors_origin = config.cors_origin.clone();
+        let cors_config = config.clone();
+        // Metrics live on the main server unless a dedicated port is configured
+        let serve_metrics = config.metrics_port.is_none();
         
         move || {
-            let cors = Cors::default()
-                .allowed_origin(&cors_origin)
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "PATCH", "OPTIONS"])
-            .allowed_headers(vec![
-                "Authorization",
-                "Content-Type",
-                "Accept",
-                "X-CSRF-Token",
-                "X-Requested-With",
-                "Origin",
-                "Access-Control-Request-Method",
-                "Access-Control-Request-Headers"
-            ])
-            .expose_headers(vec!["Set-Cookie"])
-            .supports_credentials()
-            .max_age(3600);
+            let cors = cors_from_config(&cors_config);
 
         App::new()
                 .app_data(web::Data::new(database.clone()))
@@ -175,8 +180,10 @@ fn create_server(
             .app_data(web::Data::new(market_service.clone()))
             .app_data(web::Data::new(execution_engine.clone()))
             .app_data(web::Data::new(dxy_service.clone()))
+            .app_data(web::Data::new(fx_rates.clone()))
             .app_data(web::Data::new(market_indicators.clone()))
             .app_data(web::Data::new(stock_service.clone()))
+            .app_data(web::Data::new(connection_history.clone()))
             // Custom JSON error handler for better error logging
             .app_data(
                 web::JsonConfig::default()
@@ -222,7 +229,13 @@ fn create_server(
                     .build()
             )
                     .wrap(SessionTrackingMiddleware)
+                .wrap(MetricsMiddleware)
                 .configure(configure_routes)
+                .configure(|cfg| {
+                    if serve_metrics {
+                        configure_metrics_route(cfg);
+                    }
+                })
         }
     })
     .bind(&bind_address)
@@ -233,6 +246,20 @@ fn create_server(
     Ok(server)
 }
 
+/// Create a standalone server exposing only `/metrics` on the configured metrics port
+fn create_metrics_server(config: &Config, port: u16) -> Result<actix_web::dev::Server> {
+    let bind_address = format!("{}:{}", config.server_host, port);
+
+    let server = HttpServer::new(|| App::new().configure(configure_metrics_route))
+        .workers(1)
+        .bind(&bind_address)
+        .context(format!("Failed to bind metrics server to {}", bind_address))?
+        .run();
+
+    info!("Metrics server configured to run on {}", bind_address);
+    Ok(server)
+}
+
 #[actix_web::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Set panic hook to log panics
@@ -289,7 +316,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("🔧 Available Strategies: DCA, SMA Crossover, Grid Trading, RSI, MACD");
     info!("📊 Backtesting Engine: Active | 🤖 Execution Engine: Active");
     
-    // Run the server
-    server.await
-        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    // Run the server, alongside the metrics server if it has its own port
+    match config.metrics_port {
+        Some(port) => {
+            let metrics_server = create_metrics_server(&config, port)
+                .context("Failed to create metrics server")?;
+            futures::future::try_join(server, metrics_server).await
+                .map(|_| ())
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+        }
+        None => server.await
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>),
+    }
 }
@@ -3,16 +3,21 @@ use actix_web::{
     Error, HttpMessage,
 };
 use actix_session::{Session, SessionExt};
+use actix_web::web;
+use chrono::Utc;
 use futures_util::future::LocalBoxFuture;
+use sea_orm::DatabaseConnection;
 use std::{
     future::{ready, Ready},
     rc::Rc,
+    sync::Arc,
 };
 use uuid::Uuid;
 use tracing::warn;
 
 use crate::handlers::AuthService;
 use crate::utils::errors::AppError;
+use crate::utils::session_tracker::SessionTracker;
 
 pub struct AuthMiddleware {
     auth_service: Rc<AuthService>,
@@ -154,6 +159,17 @@ where
                 }
             }
 
+            // Reject revoked or expired sessions and slide the live one's expiry forward
+            let db = req
+                .app_data::<web::Data<Arc<DatabaseConnection>>>()
+                .map(|db| db.get_ref().clone())
+                .ok_or(AppError::InternalServerError)?;
+            if let Err(e) = SessionTracker::refresh_session(&db, user_id, &token, &auth_service.session_policy, Utc::now()).await {
+                warn!("Session rejected for user {}: {:?}", user_id, e);
+                session.clear();
+                return Err(e.into());
+            }
+
             req.extensions_mut().insert(user_id);
 
             let res = service.call(req).await?;
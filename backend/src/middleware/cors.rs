@@ -0,0 +1,113 @@
+use actix_cors::Cors;
+
+use crate::config::Config;
+
+/// CORS policy for the API: the configured origin, methods, headers and preflight
+/// max-age, with credentials allowed so the session cookie is sent cross-origin.
+/// `Config::validate` must have accepted the lists, otherwise the middleware fails
+/// to start.
+pub fn cors_from_config(config: &Config) -> Cors {
+    Cors::default()
+        .allowed_origin(&config.cors_origin)
+        .allowed_methods(config.cors_allowed_methods.iter().map(String::as_str))
+        .allowed_headers(config.cors_allowed_headers.iter().map(String::as_str))
+        .expose_headers(vec!["Set-Cookie"])
+        .supports_credentials()
+        .max_age(config.cors_max_age_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header;
+    use actix_web::{test, web, App, HttpResponse};
+    use crate::config::{
+        DEFAULT_CORS_HEADERS, DEFAULT_CORS_MAX_AGE_SECS, DEFAULT_CORS_METHODS,
+        DEFAULT_SESSION_ABSOLUTE_TIMEOUT_SECS, DEFAULT_SESSION_IDLE_TIMEOUT_SECS,
+    };
+
+    fn config() -> Config {
+        Config {
+            database_url: "sqlite::memory:".to_string(),
+            jwt_secret: "x".repeat(32),
+            server_host: "127.0.0.1".to_string(),
+            server_port: 8080,
+            cors_origin: "http://localhost:3000".to_string(),
+            cors_allowed_methods: DEFAULT_CORS_METHODS.iter().map(|m| m.to_string()).collect(),
+            cors_allowed_headers: DEFAULT_CORS_HEADERS.iter().map(|h| h.to_string()).collect(),
+            cors_max_age_secs: DEFAULT_CORS_MAX_AGE_SECS,
+            alpha_vantage_api_key: "demo".to_string(),
+            session_idle_timeout_secs: DEFAULT_SESSION_IDLE_TIMEOUT_SECS,
+            session_absolute_timeout_secs: DEFAULT_SESSION_ABSOLUTE_TIMEOUT_SECS,
+            metrics_port: None,
+        }
+    }
+
+    /// Send a preflight for `method` with `request_header` and return the response headers
+    async fn preflight(config: &Config, method: &str, request_header: &str) -> (u16, header::HeaderMap) {
+        let app = test::init_service(
+            App::new()
+                .wrap(cors_from_config(config))
+                .route("/ping", web::route().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+
+        let req = test::TestRequest::default()
+            .method(actix_web::http::Method::OPTIONS)
+            .uri("/ping")
+            .insert_header((header::ORIGIN, "http://localhost:3000"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, method))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, request_header))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        (resp.status().as_u16(), resp.headers().clone())
+    }
+
+    #[actix_web::test]
+    async fn test_defaults_match_previous_policy() {
+        let config = config();
+        assert!(config.validate().is_ok());
+
+        let (status, headers) = preflight(&config, "PATCH", "x-csrf-token").await;
+        assert_eq!(status, 200);
+        assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "3600");
+    }
+
+    #[actix_web::test]
+    async fn test_overrides_apply() {
+        let config = Config {
+            cors_allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+            cors_allowed_headers: vec!["Content-Type".to_string(), "X-Team-Trace".to_string()],
+            cors_max_age_secs: 600,
+            ..config()
+        };
+        assert!(config.validate().is_ok());
+
+        let (status, headers) = preflight(&config, "POST", "x-team-trace").await;
+        assert_eq!(status, 200);
+        assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "600");
+        let methods = headers.get(header::ACCESS_CONTROL_ALLOW_METHODS).unwrap().to_str().unwrap();
+        assert!(methods.contains("POST") && !methods.contains("DELETE"));
+
+        // Methods and headers that are no longer listed fail the preflight
+        let (status, _) = preflight(&config, "DELETE", "content-type").await;
+        assert_eq!(status, 400);
+        let (status, _) = preflight(&config, "POST", "x-csrf-token").await;
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn test_invalid_cors_settings_fail_validation() {
+        let bad_method = Config { cors_allowed_methods: vec!["FETCH".to_string()], ..config() };
+        assert!(bad_method.validate().is_err());
+
+        let no_methods = Config { cors_allowed_methods: vec![], ..config() };
+        assert!(no_methods.validate().is_err());
+
+        let bad_header = Config { cors_allowed_headers: vec!["X Bad Header".to_string()], ..config() };
+        assert!(bad_header.validate().is_err());
+
+        let long_max_age = Config { cors_max_age_secs: 90_000, ..config() };
+        assert!(long_max_age.validate().is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+    time::Instant,
+};
+
+use crate::utils::metrics::metrics;
+
+/// Records request counts and latencies for the `/metrics` endpoint.
+/// Requests are labelled by matched route pattern to keep label cardinality bounded.
+pub struct MetricsMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for MetricsMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = MetricsMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MetricsMiddlewareService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct MetricsMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for MetricsMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let method = req.method().as_str().to_string();
+            let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+
+            let result = service.call(req).await;
+
+            let status = match &result {
+                Ok(res) => res.status(),
+                Err(e) => e.error_response().status(),
+            };
+
+            let app_metrics = metrics();
+            app_metrics
+                .http_requests_total
+                .with_label_values(&[&method, &route, status.as_str()])
+                .inc();
+            app_metrics
+                .http_request_duration_seconds
+                .with_label_values(&[&method, &route])
+                .observe(started.elapsed().as_secs_f64());
+
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+    use crate::routes::configure_metrics_route;
+
+    /// Value of the `http_requests_total` sample for `route`, or 0 if absent
+    fn requests_for(body: &str, route: &str) -> u64 {
+        let prefix = format!(
+            "http_requests_total{{method=\"GET\",route=\"{}\",status=\"200\"}} ",
+            route
+        );
+        body.lines()
+            .find_map(|line| line.strip_prefix(&prefix))
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    #[actix_web::test]
+    async fn test_metrics_endpoint_counts_requests() {
+        let app = test::init_service(
+            App::new()
+                .wrap(MetricsMiddleware)
+                .route("/metrics-test-ping", web::get().to(|| async { HttpResponse::Ok().finish() }))
+                .configure(configure_metrics_route),
+        )
+        .await;
+
+        let before = test::call_and_read_body(&app, test::TestRequest::get().uri("/metrics").to_request()).await;
+        let before = requests_for(&String::from_utf8(before.to_vec()).unwrap(), "/metrics-test-ping");
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/metrics-test-ping").to_request()).await;
+        assert!(resp.status().is_success());
+
+        let after = test::call_and_read_body(&app, test::TestRequest::get().uri("/metrics").to_request()).await;
+        let body = String::from_utf8(after.to_vec()).unwrap();
+        assert_eq!(requests_for(&body, "/metrics-test-ping"), before + 1);
+        assert!(body.contains("# TYPE http_request_duration_seconds histogram"));
+    }
+}
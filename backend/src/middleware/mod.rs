@@ -1,4 +1,8 @@
 pub mod auth;
 pub mod session_tracking;
+pub mod metrics;
+pub mod cors;
 
-pub use session_tracking::*;
\ No newline at end of file
+pub use session_tracking::*;
+pub use metrics::MetricsMiddleware;
+pub use cors::cors_from_config;
\ No newline at end of file
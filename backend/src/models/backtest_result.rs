@@ -2,6 +2,10 @@ use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+
+use crate::backtesting::{BacktestTrade, PerformancePoint};
+use crate::services::fx_rates::FxRate;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "backtest_results")]
@@ -40,6 +44,7 @@ pub struct Model {
     pub drawdown_curve: Json, // Store drawdown curve data points
     pub status: String, // completed, failed, running
     pub error_message: Option<String>,
+    pub checkpoint_json: Option<Json>, // Progress of a running backtest, to resume from after a restart
     pub execution_time_ms: Option<i64>,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
@@ -75,10 +80,14 @@ pub struct BacktestResultResponse {
     pub interval: String,
     pub start_date: ChronoDateTimeUtc,
     pub end_date: ChronoDateTimeUtc,
+    #[serde(with = "crate::utils::precision::usd")]
     pub initial_balance: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub final_balance: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub total_return: Decimal,
     pub total_return_percentage: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub max_drawdown: Decimal,
     pub max_drawdown_percentage: Decimal,
     pub sharpe_ratio: Option<Decimal>,
@@ -87,16 +96,24 @@ pub struct BacktestResultResponse {
     pub losing_trades: i32,
     pub win_rate: Decimal,
     pub profit_factor: Option<Decimal>,
+    #[serde(with = "crate::utils::precision::usd")]
     pub largest_win: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub largest_loss: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub average_win: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub average_loss: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub total_invested: Decimal,
     pub status: String,
     pub error_message: Option<String>,
     pub execution_time_ms: Option<i64>,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
+    /// Rate the money values were converted from USD with; `None` means they are in USD
+    #[serde(default)]
+    pub valuation: Option<FxRate>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -110,10 +127,14 @@ pub struct BacktestResultDetailResponse {
     pub interval: String,
     pub start_date: ChronoDateTimeUtc,
     pub end_date: ChronoDateTimeUtc,
+    #[serde(with = "crate::utils::precision::usd")]
     pub initial_balance: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub final_balance: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub total_return: Decimal,
     pub total_return_percentage: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub max_drawdown: Decimal,
     pub max_drawdown_percentage: Decimal,
     pub sharpe_ratio: Option<Decimal>,
@@ -122,10 +143,15 @@ pub struct BacktestResultDetailResponse {
     pub losing_trades: i32,
     pub win_rate: Decimal,
     pub profit_factor: Option<Decimal>,
+    #[serde(with = "crate::utils::precision::usd")]
     pub largest_win: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub largest_loss: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub average_win: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub average_loss: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
     pub total_invested: Decimal,
     pub strategy_parameters: serde_json::Value,
     pub trades_data: serde_json::Value,
@@ -136,6 +162,9 @@ pub struct BacktestResultDetailResponse {
     pub execution_time_ms: Option<i64>,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
+    /// Rate the money values were converted from USD with; `None` means they are in USD
+    #[serde(default)]
+    pub valuation: Option<FxRate>,
 }
 
 impl From<Model> for BacktestResultResponse {
@@ -172,6 +201,7 @@ impl From<Model> for BacktestResultResponse {
             execution_time_ms: model.execution_time_ms,
             created_at: model.created_at,
             updated_at: model.updated_at,
+            valuation: None,
         }
     }
 }
@@ -214,6 +244,73 @@ impl From<Model> for BacktestResultDetailResponse {
             execution_time_ms: model.execution_time_ms,
             created_at: model.created_at,
             updated_at: model.updated_at,
+            valuation: None,
+        }
+    }
+}
+
+impl BacktestResultResponse {
+    /// Convert the money values from USD into `rate`'s base currency. Results are
+    /// stored in USD.
+    pub fn valued_in(mut self, rate: FxRate) -> Self {
+        for value in [
+            &mut self.initial_balance,
+            &mut self.final_balance,
+            &mut self.total_return,
+            &mut self.max_drawdown,
+            &mut self.largest_win,
+            &mut self.largest_loss,
+            &mut self.average_win,
+            &mut self.average_loss,
+            &mut self.total_invested,
+        ] {
+            *value = rate.convert(*value);
+        }
+        self.valuation = Some(rate);
+        self
+    }
+}
+
+impl BacktestResultDetailResponse {
+    /// Convert the money values, stored trades and equity curve included, from USD into
+    /// `rate`'s base currency
+    pub fn valued_in(mut self, rate: FxRate) -> Self {
+        for value in [
+            &mut self.initial_balance,
+            &mut self.final_balance,
+            &mut self.total_return,
+            &mut self.max_drawdown,
+            &mut self.largest_win,
+            &mut self.largest_loss,
+            &mut self.average_win,
+            &mut self.average_loss,
+            &mut self.total_invested,
+        ] {
+            *value = rate.convert(*value);
+        }
+        self.trades_data = map_stored(self.trades_data, |trade: BacktestTrade| trade.valued_in(&rate));
+        self.equity_curve = map_stored(self.equity_curve, |point: PerformancePoint| point.valued_in(&rate));
+        self.valuation = Some(rate);
+        self
+    }
+
+    /// Round the stored trades and equity curve for the response; they keep every digit
+    /// in the database
+    pub fn rounded(mut self) -> Self {
+        self.trades_data = map_stored(self.trades_data, BacktestTrade::rounded);
+        self.equity_curve = map_stored(self.equity_curve, PerformancePoint::rounded);
+        self
+    }
+}
+
+/// Apply `f` to each item of a stored JSON list. Anything that doesn't parse as a list
+/// of `T` is passed through as stored.
+fn map_stored<T: Serialize + DeserializeOwned>(value: serde_json::Value, f: impl Fn(T) -> T) -> serde_json::Value {
+    match serde_json::from_value::<Vec<T>>(value.clone()) {
+        Ok(items) => {
+            let mapped: Vec<T> = items.into_iter().map(f).collect();
+            serde_json::to_value(mapped).unwrap_or(value)
         }
+        Err(_) => value,
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Daily portfolio value for a user. At most one row per user per day.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "balance_snapshots")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub snapshot_date: NaiveDate,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub total_usd_value: Decimal,
+    pub source: String, // live, backfill
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Snapshot source for rows reconstructed from trade history
+pub const SOURCE_BACKFILL: &str = "backfill";
+
+/// Request to backfill snapshots for a past date range (inclusive)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackfillSnapshotsRequest {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// Outcome of a backfill run
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackfillSummary {
+    /// Snapshots written by this run
+    pub created: u32,
+    /// Dates that already had a snapshot
+    pub skipped_existing: u32,
+    /// Dates before the first recorded trade or without price data
+    pub skipped_no_data: u32,
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::utils::errors::AppError;
+
+/// USD value of one exchange connection at a point in time, recorded by the
+/// background job for connections that opted into balance history.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "connection_balance_snapshots")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub connection_id: Uuid,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub total_usd_value: Decimal,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::exchange_connection::Entity",
+        from = "Column::ConnectionId",
+        to = "super::exchange_connection::Column::Id"
+    )]
+    ExchangeConnection,
+}
+
+impl Related<super::exchange_connection::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExchangeConnection.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Longest history range that can be requested
+pub const MAX_HISTORY_DAYS: i64 = 366;
+
+/// Parse a history range such as `24h`, `30d` or `12w`
+pub fn parse_history_range(range: &str) -> Result<Duration, AppError> {
+    let invalid = || AppError::BadRequest(format!("Invalid range '{}': expected e.g. 24h, 30d or 12w", range));
+
+    let unit = range.chars().last().ok_or_else(invalid)?;
+    let count: i64 = range[..range.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+    if count <= 0 {
+        return Err(invalid());
+    }
+
+    let too_long = || AppError::BadRequest(format!("Range may cover at most {} days", MAX_HISTORY_DAYS));
+    if count > MAX_HISTORY_DAYS * 24 {
+        return Err(too_long());
+    }
+
+    let duration = match unit {
+        'h' => Duration::hours(count),
+        'd' => Duration::days(count),
+        'w' => Duration::weeks(count),
+        _ => return Err(invalid()),
+    };
+
+    if duration > Duration::days(MAX_HISTORY_DAYS) {
+        return Err(too_long());
+    }
+    Ok(duration)
+}
+
+/// Query for a connection's balance history
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// How far back to look, e.g. `30d`; defaults to 30 days
+    pub range: Option<String>,
+}
+
+/// One point of a connection's value over time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    pub timestamp: DateTime<Utc>,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub total_usd_value: Decimal,
+}
+
+/// Time series returned by the history endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConnectionHistoryResponse {
+    pub connection_id: Uuid,
+    pub range: String,
+    /// Width of the buckets the snapshots were reduced to
+    pub bucket_seconds: i64,
+    pub points: Vec<HistoryPoint>,
+}
+
+/// Request to turn balance history on or off for a connection
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetHistoryRequest {
+    pub enabled: bool,
+    /// Needed to unlock the credentials when turning history on
+    pub password: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_range() {
+        assert_eq!(parse_history_range("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_history_range("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_history_range("2w").unwrap(), Duration::weeks(2));
+
+        for range in ["", "d", "0d", "-3d", "30m", "30", "400d", "99999999999w", "3é"] {
+            assert!(parse_history_range(range).is_err(), "{} should be rejected", range);
+        }
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::strategies::core::StrategyMode;
 use crate::strategies::implementations::dca::{DCAConfig, DCAStrategy as StrategyFrameworkDCA};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
@@ -22,6 +23,14 @@ pub struct Model {
     pub average_buy_price: Option<Decimal>,
     pub last_execution_at: Option<DateTime<Utc>>,
     pub next_execution_at: Option<DateTime<Utc>>,
+    pub version: i32, // incremented on every user edit, for optimistic concurrency
+    /// Framework state saved with `Strategy::get_state`, restored on import
+    pub state_json: Option<String>,
+    /// Set by the user to keep the strategy from ever running, whatever its status.
+    /// Only the kill switch endpoint clears it.
+    pub kill_switch: bool,
+    /// `live` or `paper`; paper strategies fill simulated buys against a paper portfolio
+    pub mode: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -66,6 +75,7 @@ pub mod execution {
         pub order_status: String, // pending, filled, cancelled, failed
         pub execution_timestamp: DateTime<Utc>,
         pub error_message: Option<String>,
+        pub mode: String, // live, paper
         pub created_at: DateTime<Utc>,
     }
 
@@ -150,6 +160,10 @@ pub struct CreateDCAStrategyRequest {
 
     // The DCAConfig contains all strategy configuration
     pub config: DCAConfig,
+
+    /// `live` (the default) or `paper`
+    #[serde(default)]
+    pub mode: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -157,6 +171,10 @@ pub struct UpdateDCAStrategyRequest {
     pub name: Option<String>,
     pub status: Option<DCAStatus>,
     pub config: Option<DCAConfig>,
+    /// Version the client last read; the update is rejected with 409 if it is stale.
+    /// An `If-Match` header may be sent instead.
+    #[serde(default)]
+    pub expected_version: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -185,7 +203,9 @@ pub struct DCAStrategyResponse {
     pub name: String,
     pub asset_symbol: String,
     pub status: String,
+    pub mode: String,
     pub config: DCAConfig,
+    pub version: i32,
     pub total_invested: Decimal,
     pub total_purchased: Decimal,
     pub average_buy_price: Option<Decimal>,
@@ -198,7 +218,7 @@ pub struct DCAStrategyResponse {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DCAExecutionResponse {
     pub id: Uuid,
     pub strategy_id: Uuid,
@@ -212,6 +232,27 @@ pub struct DCAExecutionResponse {
     pub order_status: String,
     pub execution_timestamp: DateTime<Utc>,
     pub error_message: Option<String>,
+    pub mode: String,
+}
+
+impl From<execution::Model> for DCAExecutionResponse {
+    fn from(exec: execution::Model) -> Self {
+        Self {
+            id: exec.id,
+            strategy_id: exec.strategy_id,
+            execution_type: exec.execution_type,
+            trigger_reason: exec.trigger_reason,
+            amount_usd: exec.amount_usd,
+            amount_asset: exec.amount_asset,
+            price_at_execution: exec.price_at_execution,
+            fear_greed_index: exec.fear_greed_index,
+            market_volatility: exec.market_volatility,
+            order_status: exec.order_status,
+            execution_timestamp: exec.execution_timestamp,
+            error_message: exec.error_message,
+            mode: exec.mode,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -296,6 +337,14 @@ impl From<TriggerReason> for String {
 
 // Implementation helpers
 impl Model {
+    /// Mode the strategy executes in; anything but `paper` runs live
+    pub fn execution_mode(&self) -> StrategyMode {
+        match StrategyMode::parse_execution_mode(&self.mode) {
+            Some(StrategyMode::Paper) => StrategyMode::Paper,
+            _ => StrategyMode::Live,
+        }
+    }
+
     /// Get the DCAConfig from stored JSON
     pub fn get_dca_config(&self) -> Result<DCAConfig, String> {
         serde_json::from_str::<DCAConfig>(&self.config_json)
@@ -310,7 +359,7 @@ impl Model {
 
         let mut strategy = StrategyFrameworkDCA::new();
 
-        use crate::strategies::core::{Strategy, StrategyMode, StrategyContextBuilder};
+        use crate::strategies::core::{Strategy, StrategyContextBuilder};
         use rust_decimal::prelude::FromPrimitive;
 
         // Create context for strategy initialization
@@ -333,11 +382,28 @@ impl Model {
         Ok(strategy)
     }
 
+    /// `to_strategy_framework`, carrying on from the state the execution engine saved.
+    /// Signal checks use a fresh instance, since the engine keeps the schedule itself.
+    pub async fn to_restored_strategy_framework(&self, historical_data: Vec<crate::exchange_connectors::Kline>) -> Result<StrategyFrameworkDCA, String> {
+        let mut strategy = self.to_strategy_framework(historical_data).await?;
+
+        if let Some(state_json) = &self.state_json {
+            use crate::strategies::core::Strategy;
+
+            let state: serde_json::Value = serde_json::from_str(state_json)
+                .map_err(|e| format!("Failed to parse strategy state: {}", e))?;
+            strategy.restore_state(&state)
+                .map_err(|e| format!("Failed to restore strategy state: {:?}", e))?;
+        }
+
+        Ok(strategy)
+    }
+
     /// Calculate current tranche size using the strategy framework
     pub async fn calculate_current_tranche_size(&self, historical_data: Vec<crate::exchange_connectors::Kline>) -> Result<Decimal, String> {
         let mut strategy = self.to_strategy_framework(historical_data.clone()).await?;
 
-        use crate::strategies::core::{StrategyContextBuilder, StrategyMode, Strategy};
+        use crate::strategies::core::{StrategyContextBuilder, Strategy};
         use rust_decimal::prelude::FromPrimitive;
 
         let context = StrategyContextBuilder::new()
@@ -371,7 +437,7 @@ impl Model {
         let historical_data_clone = historical_data.clone();
         let mut strategy = self.to_strategy_framework(historical_data_clone).await?;
 
-        use crate::strategies::core::{StrategyContextBuilder, StrategyMode, Strategy};
+        use crate::strategies::core::{StrategyContextBuilder, Strategy};
         use rust_decimal::prelude::FromPrimitive;
 
         let context = StrategyContextBuilder::new()
@@ -386,9 +452,10 @@ impl Model {
             .build()
             .map_err(|e| format!("Failed to build context: {:?}", e))?;
 
-        // Use strategy framework to analyze if we should execute
+        // Use strategy framework to analyze if we should execute; low-confidence signals are skipped
+        let min_confidence = self.get_dca_config()?.min_confidence;
         match strategy.analyze(&context).await {
-            Ok(signal) => Ok(signal.is_some()),
+            Ok(signal) => Ok(signal.and_then(|s| crate::strategies::core::apply_min_confidence(s, min_confidence)).is_some()),
             Err(e) => Err(format!("Strategy analysis failed: {:?}", e))
         }
     }
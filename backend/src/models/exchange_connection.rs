@@ -1,8 +1,14 @@
+use once_cell::sync::Lazy;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::exchange_connectors::common_types::ApiKeyPermissions;
+use crate::exchange_connectors::fees::{FeeRates, FeeSchedule};
+use crate::exchange_connectors::Exchange;
+use crate::utils::errors::AppError;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "exchange_connections")]
 pub struct Model {
@@ -24,6 +30,12 @@ pub struct Model {
     pub last_sync: Option<ChronoDateTimeUtc>,
     pub connection_status: String,     // "connected", "error", "pending"
     pub last_error: Option<String>,
+    pub api_permissions: Option<Json>, // ApiKeyPermissions reported by the exchange on the last test/sync
+    pub fee_tier: i32,                 // VIP tier in the exchange's fee schedule; 0 is the base tier
+    pub fee_token_discount: bool,      // Fees are paid in the exchange's token (e.g. BNB) at a discount
+    pub history_credentials: Option<String>,       // Credentials sealed with the server's history key; set while balance history is on
+    pub history_credentials_nonce: Option<String>, // Base64 encoded nonce for history credentials
+    pub history_credentials_salt: Option<String>,  // Base64 encoded salt for history credentials
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
 }
@@ -46,6 +58,17 @@ impl Related<super::user::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl Model {
+    /// Commission this account pays, from its exchange's schedule at the stored tier
+    pub fn fee_rates(&self) -> Result<FeeRates, AppError> {
+        let exchange = Exchange::from_str(&self.exchange_name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unsupported exchange: {}", self.exchange_name)))?;
+        let tier = u8::try_from(self.fee_tier)
+            .map_err(|_| AppError::BadRequest(format!("Invalid fee tier {}", self.fee_tier)))?;
+        FeeSchedule::for_exchange(&exchange).rates(tier, self.fee_token_discount)
+    }
+}
+
 /// Request to create a new exchange connection
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateExchangeConnectionRequest {
@@ -57,6 +80,9 @@ pub struct CreateExchangeConnectionRequest {
     pub api_key: String,
     #[validate(length(min = 1, max = 500))]
     pub api_secret: String,
+    /// API key passphrase, required for exchanges that issue one (KuCoin, OKX)
+    #[validate(length(min = 1, max = 500))]
+    pub passphrase: Option<String>,
     #[validate(length(min = 8))]
     pub password: String, // User's password for encryption
 }
@@ -70,6 +96,13 @@ pub struct UpdateExchangeConnectionRequest {
     pub api_key: Option<String>,
     #[validate(length(min = 1, max = 500))]
     pub api_secret: Option<String>,
+    /// Replacement passphrase; only accepted for exchanges that use one
+    #[validate(length(min = 1, max = 500))]
+    pub passphrase: Option<String>,
+    /// VIP tier in the exchange's fee schedule
+    pub fee_tier: Option<u8>,
+    /// Whether fees are paid in the exchange's token at a discount
+    pub fee_token_discount: Option<bool>,
     #[validate(length(min = 8))]
     pub password: String, // User's password for encryption/decryption
 }
@@ -84,13 +117,61 @@ pub struct ExchangeConnectionResponse {
     pub last_sync: Option<ChronoDateTimeUtc>,
     pub connection_status: String,
     pub last_error: Option<String>,
+    /// Unknown until the exchange has been asked (or if it can't report them)
+    pub api_permissions: Option<ApiKeyPermissions>,
+    pub fee_tier: i32,
+    pub fee_token_discount: bool,
+    /// The connection's USD value is recorded in the background
+    pub history_enabled: bool,
+    /// Seconds since `last_sync`; `None` if the connection has never synced
+    pub sync_age_seconds: Option<i64>,
+    /// Never synced, or last synced longer ago than `CONNECTION_STALE_AFTER_SECS`
+    pub is_stale: bool,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
     // Note: Never include encrypted credentials in responses
 }
 
+/// Seconds after its last sync a connection's balances count as stale unless
+/// `CONNECTION_STALE_AFTER_SECS` is set
+pub const DEFAULT_CONNECTION_STALE_AFTER_SECS: i64 = 86_400;
+
+static CONNECTION_STALE_AFTER: Lazy<chrono::Duration> = Lazy::new(|| {
+    let secs = std::env::var("CONNECTION_STALE_AFTER_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_CONNECTION_STALE_AFTER_SECS);
+    chrono::Duration::seconds(secs)
+});
+
+/// How long ago a connection last synced, and whether that is too long ago
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStaleness {
+    pub age_seconds: Option<i64>,
+    pub is_stale: bool,
+}
+
+impl SyncStaleness {
+    pub fn at(last_sync: Option<ChronoDateTimeUtc>, now: ChronoDateTimeUtc, stale_after: chrono::Duration) -> Self {
+        match last_sync {
+            Some(last_sync) => {
+                let age = (now - last_sync).max(chrono::Duration::zero());
+                Self { age_seconds: Some(age.num_seconds()), is_stale: age > stale_after }
+            }
+            None => Self { age_seconds: None, is_stale: true },
+        }
+    }
+
+    /// Staleness right now, against the configured threshold
+    pub fn now(last_sync: Option<ChronoDateTimeUtc>) -> Self {
+        Self::at(last_sync, chrono::Utc::now(), *CONNECTION_STALE_AFTER)
+    }
+}
+
 impl From<Model> for ExchangeConnectionResponse {
     fn from(model: Model) -> Self {
+        let staleness = SyncStaleness::now(model.last_sync);
         Self {
             id: model.id,
             exchange_name: model.exchange_name,
@@ -99,12 +180,106 @@ impl From<Model> for ExchangeConnectionResponse {
             last_sync: model.last_sync,
             connection_status: model.connection_status,
             last_error: model.last_error,
+            api_permissions: model
+                .api_permissions
+                .and_then(|permissions| serde_json::from_value(permissions).ok()),
+            fee_tier: model.fee_tier,
+            fee_token_discount: model.fee_token_discount,
+            history_enabled: model.history_credentials.is_some(),
+            sync_age_seconds: staleness.age_seconds,
+            is_stale: staleness.is_stale,
             created_at: model.created_at,
             updated_at: model.updated_at,
         }
     }
 }
 
+/// Current layout of [`ExchangeConnectionExport`]
+pub const CONNECTION_EXPORT_VERSION: u32 = 1;
+
+/// Backup of a user's exchange connections.
+///
+/// Credentials stay encrypted exactly as stored: the keys are derived from the user's
+/// password and id, so the file is useless without the password and can only be
+/// imported back into the same account.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExchangeConnectionExport {
+    pub version: u32,
+    pub user_id: Uuid,
+    pub exported_at: ChronoDateTimeUtc,
+    pub connections: Vec<ExportedExchangeConnection>,
+}
+
+/// One connection in an export: metadata plus the encrypted credential blobs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedExchangeConnection {
+    pub exchange_name: String,
+    pub display_name: String,
+    pub encrypted_api_key: String,
+    pub encrypted_api_secret: String,
+    pub encrypted_passphrase: Option<String>,
+    pub api_key_nonce: String,
+    pub api_secret_nonce: String,
+    pub passphrase_nonce: Option<String>,
+    pub api_key_salt: String,
+    pub api_secret_salt: String,
+    pub passphrase_salt: Option<String>,
+    pub is_active: bool,
+    pub created_at: ChronoDateTimeUtc,
+}
+
+impl From<Model> for ExportedExchangeConnection {
+    fn from(model: Model) -> Self {
+        Self {
+            exchange_name: model.exchange_name,
+            display_name: model.display_name,
+            encrypted_api_key: model.encrypted_api_key,
+            encrypted_api_secret: model.encrypted_api_secret,
+            encrypted_passphrase: model.encrypted_passphrase,
+            api_key_nonce: model.api_key_nonce,
+            api_secret_nonce: model.api_secret_nonce,
+            passphrase_nonce: model.passphrase_nonce,
+            api_key_salt: model.api_key_salt,
+            api_secret_salt: model.api_secret_salt,
+            passphrase_salt: model.passphrase_salt,
+            is_active: model.is_active,
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// Result of importing an [`ExchangeConnectionExport`]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportExchangeConnectionsResponse {
+    pub imported: Vec<ExchangeConnectionResponse>,
+    /// Display names skipped because a connection with that name already exists
+    pub skipped: Vec<String>,
+}
+
 // Re-export exchange connector types for convenience
 #[allow(unused_imports)]
-pub use crate::exchange_connectors::{Exchange as SupportedExchange, ExchangeCredentials};
\ No newline at end of file
+pub use crate::exchange_connectors::{Exchange as SupportedExchange, ExchangeCredentials};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_sync_staleness_for_fresh_stale_and_never_synced_connections() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let stale_after = Duration::hours(24);
+
+        let fresh = SyncStaleness::at(Some(now - Duration::minutes(90)), now, stale_after);
+        assert_eq!(fresh, SyncStaleness { age_seconds: Some(5400), is_stale: false });
+
+        let stale = SyncStaleness::at(Some(now - Duration::hours(25)), now, stale_after);
+        assert_eq!(stale, SyncStaleness { age_seconds: Some(90_000), is_stale: true });
+
+        let never = SyncStaleness::at(None, now, stale_after);
+        assert_eq!(never, SyncStaleness { age_seconds: None, is_stale: true });
+
+        // Exactly at the threshold still counts as fresh
+        assert!(!SyncStaleness::at(Some(now - stale_after), now, stale_after).is_stale);
+    }
+}
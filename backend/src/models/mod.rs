@@ -7,6 +7,9 @@ pub mod dca_strategy;
 pub mod sma_crossover_strategy;
 pub mod grid_trading_strategy;
 pub mod backtest_result;
+pub mod balance_snapshot;
+pub mod connection_balance_snapshot;
+pub mod paper_portfolio;
 
 pub use user::*;
 pub use user_profile::*;
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Virtual account a strategy trades against in paper mode. One per strategy.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "paper_portfolios")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[sea_orm(unique)]
+    pub strategy_id: Uuid,
+    pub strategy_type: String, // dca, sma_crossover, grid_trading
+    /// Cash the portfolio is seeded with, and restored to on reset
+    #[serde(with = "crate::utils::precision::usd")]
+    pub starting_balance: Decimal,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub cash_balance: Decimal,
+    #[serde(with = "crate::utils::precision::crypto")]
+    pub asset_quantity: Decimal,
+    pub reset_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// Paper fills
+pub mod trade {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "paper_trades")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub portfolio_id: Uuid,
+        pub side: String, // buy, sell
+        #[serde(with = "crate::utils::precision::crypto")]
+        pub price: Decimal,
+        #[serde(with = "crate::utils::precision::crypto")]
+        pub quantity: Decimal,
+        #[serde(with = "crate::utils::precision::usd")]
+        pub total_value: Decimal,
+        pub reason: String,
+        pub executed_at: DateTime<Utc>,
+        pub created_at: DateTime<Utc>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::Entity",
+            from = "Column::PortfolioId",
+            to = "super::Column::Id"
+        )]
+        Portfolio,
+    }
+
+    impl Related<super::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Portfolio.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Paper portfolio with its fills, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperPortfolioResponse {
+    pub portfolio: Model,
+    pub trades: Vec<trade::Model>,
+}
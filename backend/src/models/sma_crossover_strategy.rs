@@ -33,6 +33,12 @@ pub struct Model {
     pub last_signal_type: Option<String>, // bullish_crossover, bearish_crossover
     pub last_signal_time: Option<DateTime<Utc>>,
     pub last_execution_at: Option<DateTime<Utc>>,
+    pub version: i32, // incremented on every user edit, for optimistic concurrency
+    /// Framework state saved with `Strategy::get_state`, restored on import
+    pub state_json: Option<String>,
+    /// Set by the user to keep the strategy from ever running, whatever its status.
+    /// Only the kill switch endpoint clears it.
+    pub kill_switch: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -138,6 +144,10 @@ pub struct UpdateSMACrossoverStrategyRequest {
     pub name: Option<String>,
     pub status: Option<SMACrossoverStatus>,
     pub config: Option<SMACrossoverConfig>,
+    /// Version the client last read; the update is rejected with 409 if it is stale.
+    /// An `If-Match` header may be sent instead.
+    #[serde(default)]
+    pub expected_version: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -148,6 +158,7 @@ pub struct SMACrossoverStrategyResponse {
     pub asset_symbol: String,
     pub status: String,
     pub config: SMACrossoverConfig,
+    pub version: i32,
     pub total_invested: Decimal,
     pub total_purchased: Decimal,
     pub average_buy_price: Option<Decimal>,
@@ -1,8 +1,11 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use rust_decimal::Decimal;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::strategies::core::NotionalLimit;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "user_profiles")]
 pub struct Model {
@@ -18,10 +21,31 @@ pub struct Model {
     pub join_date: String,
     pub avatar_url: Option<String>,
     pub is_verified: bool,
+    /// Largest quote value any single live order of this user may have
+    pub max_order_notional: Option<Decimal>,
+    /// Shrink oversized orders to `max_order_notional` instead of rejecting them
+    pub clamp_oversized_orders: bool,
+    /// Comma-separated symbols this user may only trade and backtest, e.g. "*USDT,ETHBTC";
+    /// set by an operator
+    pub symbol_allow_list: Option<String>,
+    /// Comma-separated symbols this user may never trade or backtest; set by an operator
+    pub symbol_deny_list: Option<String>,
+    /// Currency balances and backtest results are valued in, e.g. "USD" or "EUR"
+    pub base_currency: String,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
 }
 
+impl Model {
+    /// The user-wide order notional limit, if one is set
+    pub fn notional_limit(&self) -> Option<NotionalLimit> {
+        self.max_order_notional.map(|max_order_notional| NotionalLimit {
+            max_order_notional,
+            clamp: self.clamp_oversized_orders,
+        })
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(
@@ -51,6 +75,7 @@ pub struct CreateUserProfileRequest {
     pub bio: Option<String>,
     #[validate(url)]
     pub avatar_url: Option<String>,
+    pub base_currency: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -64,6 +89,21 @@ pub struct UpdateUserProfileRequest {
     pub bio: Option<String>,
     #[validate(url)]
     pub avatar_url: Option<String>,
+    /// Absent leaves the limit as is; `null` clears it
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub max_order_notional: Option<Option<Decimal>>,
+    pub clamp_oversized_orders: Option<bool>,
+    pub base_currency: Option<String>,
+}
+
+/// Wrap a field that is present, `null` included, in `Some`, so it can be told apart
+/// from an absent one
+fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -78,6 +118,11 @@ pub struct UserProfileResponse {
     pub join_date: String,
     pub avatar_url: Option<String>,
     pub is_verified: bool,
+    pub max_order_notional: Option<Decimal>,
+    pub clamp_oversized_orders: bool,
+    pub symbol_allow_list: Option<String>,
+    pub symbol_deny_list: Option<String>,
+    pub base_currency: String,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
 }
@@ -95,6 +140,11 @@ impl From<Model> for UserProfileResponse {
             join_date: profile.join_date,
             avatar_url: profile.avatar_url,
             is_verified: profile.is_verified,
+            max_order_notional: profile.max_order_notional,
+            clamp_oversized_orders: profile.clamp_oversized_orders,
+            symbol_allow_list: profile.symbol_allow_list,
+            symbol_deny_list: profile.symbol_deny_list,
+            base_currency: profile.base_currency,
             created_at: profile.created_at,
             updated_at: profile.updated_at,
         }
@@ -2,19 +2,22 @@ use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde_json::json;
 use tracing::info;
 use uuid::Uuid;
 
 use crate::backtesting::{
-    BacktestEngine, BacktestConfig, BacktestRequest, BinanceFetcher, StockFetcher,
-    get_cache
+    BacktestCheckpoint, BacktestEngine, BacktestConfig, BacktestRequest, BacktestResult, CompareStrategiesRequest, SensitivityRequest, ShortDataAction,
+    BinanceFetcher, StockFetcher, get_cache
 };
-use crate::services::StockDataService;
+use crate::backtesting::replay::{live_strategy, ReplayEvent, ReplayRequest, StrategyReplay};
+use crate::services::{ensure_symbol_allowed, FxRateService, StockDataService};
 use crate::exchange_connectors::KlineInterval;
 use crate::strategies::{list_all_strategies, get_strategy_metadata};
 use crate::utils::errors::AppError;
 use crate::handlers::backtest_management;
+use crate::models::backtest_result;
 use crate::handlers::AuthService;
 use actix_session::SessionExt;
 
@@ -24,6 +27,7 @@ pub async fn run_backtest(
     req: HttpRequest,
     request: web::Json<BacktestRequest>,
     stock_service: web::Data<StockDataService>,
+    fx_rates: web::Data<FxRateService>,
 ) -> Result<HttpResponse, AppError> {
     // Get user ID from request extensions (set by auth middleware)
     // First try to get from extensions (if auth middleware set it)
@@ -38,6 +42,7 @@ pub async fn run_backtest(
     };
 
     info!("User {} starting backtest for {}", user_id_value, request.symbol);
+    ensure_symbol_allowed(db.as_ref().as_ref(), user_id_value, &request.symbol).await?;
     tracing::debug!("Backtest request: {:?}", request);
 
     // Parse dates
@@ -91,7 +96,27 @@ pub async fn run_backtest(
         stop_loss_percentage: request.stop_loss_percentage,
         take_profit_percentage: request.take_profit_percentage,
         unlimited_capital: is_dca, // Auto-enable for DCA strategies
+        dca_mode: request.dca_mode,
         asset_type: request.asset_type.clone(),
+        initial_positions: request.resolve_initial_positions()?,
+        fee_percentage: request.fee_percentage,
+        fee_tier: request.fee_tier,
+        fee_token_discount: request.fee_token_discount,
+        maker_fee_percentage: request.maker_fee_percentage,
+        taker_fee_percentage: request.taker_fee_percentage,
+        slippage_percentage: request.slippage_percentage,
+        fill_price: request.fill_price,
+        apply_min_confidence: request.apply_min_confidence,
+        use_heikin_ashi: request.use_heikin_ashi,
+        log_trades: request.log_trades,
+        strict_lookahead: request.strict_lookahead,
+        benchmark_symbol: request.benchmark_symbol.clone(),
+        benchmark_asset_type: request.benchmark_asset_type.clone(),
+        min_data_coverage_pct: request.min_data_coverage_pct,
+        on_short_data: request.on_short_data,
+        max_curve_points: request.max_curve_points,
+        risk_free_rate: request.risk_free_rate,
+        periods_per_year: request.periods_per_year,
     };
 
     // Create backtest name
@@ -156,24 +181,92 @@ pub async fn run_backtest(
     })?;
     tracing::debug!("Saved backtest with ID: {}", saved_backtest.id);
 
-    // Run backtest
+    // Run backtest, checkpointing into the saved row so a crash can be resumed
     tracing::debug!("Initializing backtest engine...");
-    let engine = if request.asset_type == "stock" {
+    let engine = if request.needs_stock_data() {
         BacktestEngine::new_with_stock_support(stock_service.api_key().to_string())
     } else {
         BacktestEngine::new()
-    };
+    }
+    .with_checkpoints(db.get_ref().clone(), saved_backtest.id);
     let start_time = std::time::Instant::now();
 
     tracing::debug!("Starting backtest execution...");
-    match engine.run_backtest(config).await {
+    let outcome = engine.run_backtest(config).await;
+    finish_backtest(&db, &fx_rates, user_id_value, saved_backtest.id, outcome, start_time).await
+}
+
+/// Resume a backtest that stopped before finishing from its last checkpoint
+pub async fn resume_backtest(
+    db: web::Data<std::sync::Arc<sea_orm::DatabaseConnection>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    stock_service: web::Data<StockDataService>,
+    fx_rates: web::Data<FxRateService>,
+) -> Result<HttpResponse, AppError> {
+    let user_id_value = if let Some(user_id) = req.extensions().get::<Uuid>().copied() {
+        user_id
+    } else {
+        authenticate_user(&req).await.map_err(|e| {
+            tracing::error!("Authentication failed for resume backtest: {:?}", e);
+            AppError::Unauthorized("Authentication required".to_string())
+        })?
+    };
+    let backtest_id = path.into_inner();
+
+    let saved_backtest = backtest_result::Entity::find_by_id(backtest_id)
+        .filter(backtest_result::Column::UserId.eq(user_id_value))
+        .one(db.get_ref().as_ref())
+        .await
+        .map_err(AppError::DatabaseError)?
+        .ok_or_else(|| AppError::NotFound("Backtest result not found".to_string()))?;
+
+    if saved_backtest.status != "running" {
+        return Err(AppError::Conflict(format!(
+            "Backtest is {}; only an unfinished backtest can be resumed",
+            saved_backtest.status
+        )));
+    }
+    let checkpoint: BacktestCheckpoint = saved_backtest
+        .checkpoint_json
+        .clone()
+        .ok_or_else(|| AppError::Conflict("Backtest has no checkpoint to resume from".to_string()))
+        .and_then(|json| {
+            serde_json::from_value(json)
+                .map_err(|e| AppError::BadRequest(format!("Invalid backtest checkpoint: {}", e)))
+        })?;
+    ensure_symbol_allowed(db.as_ref().as_ref(), user_id_value, &checkpoint.config.symbol).await?;
+
+    info!("User {} resuming backtest {} at bar {}", user_id_value, backtest_id, checkpoint.next_index);
+    let engine = if checkpoint.config.needs_stock_data() {
+        BacktestEngine::new_with_stock_support(stock_service.api_key().to_string())
+    } else {
+        BacktestEngine::new()
+    }
+    .with_checkpoints(db.get_ref().clone(), backtest_id);
+    let start_time = std::time::Instant::now();
+
+    let outcome = engine.resume_backtest(backtest_id).await;
+    finish_backtest(&db, &fx_rates, user_id_value, backtest_id, outcome, start_time).await
+}
+
+/// Store a finished (or failed) backtest on its saved row and build the response
+async fn finish_backtest(
+    db: &web::Data<std::sync::Arc<sea_orm::DatabaseConnection>>,
+    fx_rates: &FxRateService,
+    user_id_value: Uuid,
+    backtest_id: Uuid,
+    outcome: Result<BacktestResult, AppError>,
+    start_time: std::time::Instant,
+) -> Result<HttpResponse, AppError> {
+    match outcome {
         Ok(result) => {
             let execution_time = start_time.elapsed().as_millis() as i64;
 
             // Update backtest with complete results
             let update_result = backtest_management::update_backtest_results(
                 web::Data::new(db.get_ref().clone()),
-                saved_backtest.id,
+                backtest_id,
                 &result,
                 execution_time,
             ).await;
@@ -190,10 +283,13 @@ pub async fn run_backtest(
                 execution_time
             );
 
+            // Stored results stay in USD; convert only what goes back to the user
+            let rate = fx_rates.rate_for_user(db.get_ref().as_ref(), user_id_value).await?;
+
             // Return result with backtest ID
-            let mut response = serde_json::to_value(result)?;
+            let mut response = serde_json::to_value(result.valued_in(rate).rounded())?;
             if let Some(obj) = response.as_object_mut() {
-                obj.insert("backtest_id".to_string(), serde_json::Value::String(saved_backtest.id.to_string()));
+                obj.insert("backtest_id".to_string(), serde_json::Value::String(backtest_id.to_string()));
             }
 
             Ok(HttpResponse::Ok().json(response))
@@ -204,7 +300,7 @@ pub async fn run_backtest(
             // Update backtest with error
             let update_result = backtest_management::update_backtest_status(
                 web::Data::new(db.get_ref().clone()),
-                saved_backtest.id,
+                backtest_id,
                 "failed".to_string(),
                 Some(e.to_string()),
                 Some(execution_time),
@@ -409,11 +505,12 @@ pub async fn get_intervals(
 
 /// Validate backtest parameters
 pub async fn validate_backtest(
+    db: web::Data<std::sync::Arc<sea_orm::DatabaseConnection>>,
     req: HttpRequest,
     request: web::Json<BacktestRequest>,
 ) -> Result<HttpResponse, AppError> {
     // Get user ID from request extensions for authentication
-    let _user_id = req.extensions()
+    let user_id = req.extensions()
         .get::<Uuid>()
         .copied()
         .ok_or_else(|| {
@@ -421,7 +518,8 @@ pub async fn validate_backtest(
             AppError::Unauthorized("Authentication required".to_string())
         })?;
     // Validate symbol
-    BinanceFetcher::validate_symbol(&request.symbol)?;
+    BinanceFetcher::validate_symbol(&BinanceFetcher::native_symbol(&request.symbol)?)?;
+    ensure_symbol_allowed(db.as_ref().as_ref(), user_id, &request.symbol).await?;
 
     // Parse and validate dates
     let start_time = DateTime::parse_from_rfc3339(&request.start_date)
@@ -454,6 +552,272 @@ pub async fn validate_backtest(
     })))
 }
 
+/// Preview a strategy's decision on the last bar of a range, with its explanation
+pub async fn preview_strategy(
+    db: web::Data<std::sync::Arc<sea_orm::DatabaseConnection>>,
+    req: HttpRequest,
+    request: web::Json<BacktestRequest>,
+    stock_service: web::Data<StockDataService>,
+) -> Result<HttpResponse, AppError> {
+    // Get user ID from request extensions for authentication
+    let user_id = req.extensions()
+        .get::<Uuid>()
+        .copied()
+        .ok_or_else(|| {
+            tracing::error!("User ID not found in request extensions - authentication required");
+            AppError::Unauthorized("Authentication required".to_string())
+        })?;
+
+    ensure_symbol_allowed(db.as_ref().as_ref(), user_id, &request.symbol).await?;
+    let config = build_backtest_config(&request)?;
+
+    let engine = if request.asset_type == "stock" {
+        BacktestEngine::new_with_stock_support(stock_service.api_key().to_string())
+    } else {
+        BacktestEngine::new()
+    };
+
+    let preview = engine.preview_strategy(config).await?;
+
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+/// Rerun a backtest across a grid of fee and slippage levels
+pub async fn run_sensitivity(
+    db: web::Data<std::sync::Arc<sea_orm::DatabaseConnection>>,
+    req: HttpRequest,
+    request: web::Json<SensitivityRequest>,
+    stock_service: web::Data<StockDataService>,
+) -> Result<HttpResponse, AppError> {
+    // Get user ID from request extensions for authentication
+    let user_id = req.extensions()
+        .get::<Uuid>()
+        .copied()
+        .ok_or_else(|| {
+            tracing::error!("User ID not found in request extensions - authentication required");
+            AppError::Unauthorized("Authentication required".to_string())
+        })?;
+
+    let request = request.into_inner();
+    info!(
+        "User {} running {}x{} cost sensitivity for {} on {}",
+        user_id,
+        request.fee_levels.len(),
+        request.slippage_levels.len(),
+        request.backtest.strategy_name,
+        request.backtest.symbol
+    );
+
+    ensure_symbol_allowed(db.as_ref().as_ref(), user_id, &request.backtest.symbol).await?;
+    let config = build_backtest_config(&request.backtest)?;
+
+    let engine = if request.backtest.asset_type == "stock" {
+        BacktestEngine::new_with_stock_support(stock_service.api_key().to_string())
+    } else {
+        BacktestEngine::new()
+    };
+
+    let report = engine.run_sensitivity(config, request.fee_levels, request.slippage_levels).await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Build an engine config from an API request (dates, interval and imported positions)
+fn build_backtest_config(request: &BacktestRequest) -> Result<BacktestConfig, AppError> {
+    let start_time = DateTime::parse_from_rfc3339(&request.start_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid start date: {}", e)))?
+        .with_timezone(&Utc);
+
+    let end_time = DateTime::parse_from_rfc3339(&request.end_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid end date: {}", e)))?
+        .with_timezone(&Utc);
+
+    let interval = KlineInterval::from_str(&request.interval)
+        .ok_or_else(|| AppError::BadRequest(format!("Invalid interval: {}", request.interval)))?;
+
+    Ok(BacktestConfig {
+        symbol: request.symbol.clone(),
+        interval,
+        start_time,
+        end_time,
+        initial_balance: request.initial_balance,
+        strategy_name: request.strategy_name.clone(),
+        strategy_type: None,
+        strategy_parameters: request.strategy_parameters.clone().unwrap_or(json!({})),
+        stop_loss_percentage: request.stop_loss_percentage,
+        take_profit_percentage: request.take_profit_percentage,
+        unlimited_capital: request.strategy_name.contains("dca"),
+        dca_mode: request.dca_mode,
+        asset_type: request.asset_type.clone(),
+        initial_positions: request.resolve_initial_positions()?,
+        fee_percentage: request.fee_percentage,
+        fee_tier: request.fee_tier,
+        fee_token_discount: request.fee_token_discount,
+        maker_fee_percentage: request.maker_fee_percentage,
+        taker_fee_percentage: request.taker_fee_percentage,
+        slippage_percentage: request.slippage_percentage,
+        fill_price: request.fill_price,
+        apply_min_confidence: request.apply_min_confidence,
+        use_heikin_ashi: request.use_heikin_ashi,
+        log_trades: request.log_trades,
+        strict_lookahead: request.strict_lookahead,
+        benchmark_symbol: request.benchmark_symbol.clone(),
+        benchmark_asset_type: request.benchmark_asset_type.clone(),
+        min_data_coverage_pct: request.min_data_coverage_pct,
+        on_short_data: request.on_short_data,
+        max_curve_points: request.max_curve_points,
+        risk_free_rate: request.risk_free_rate,
+        periods_per_year: request.periods_per_year,
+    })
+}
+
+/// Run several strategies on identical data and return aligned equity curves with their metrics
+pub async fn compare_strategies(
+    db: web::Data<std::sync::Arc<sea_orm::DatabaseConnection>>,
+    req: HttpRequest,
+    request: web::Json<CompareStrategiesRequest>,
+    stock_service: web::Data<StockDataService>,
+    fx_rates: web::Data<FxRateService>,
+) -> Result<HttpResponse, AppError> {
+    // Get user ID from request extensions for authentication
+    let user_id = req.extensions()
+        .get::<Uuid>()
+        .copied()
+        .ok_or_else(|| {
+            tracing::error!("User ID not found in request extensions - authentication required");
+            AppError::Unauthorized("Authentication required".to_string())
+        })?;
+
+    let request = request.into_inner();
+    info!("User {} comparing {} strategies on {}", user_id, request.strategies.len(), request.symbol);
+    ensure_symbol_allowed(db.as_ref().as_ref(), user_id, &request.symbol).await?;
+
+    let start_time = DateTime::parse_from_rfc3339(&request.start_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid start date: {}", e)))?
+        .with_timezone(&Utc);
+
+    let end_time = DateTime::parse_from_rfc3339(&request.end_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid end date: {}", e)))?
+        .with_timezone(&Utc);
+
+    let interval = KlineInterval::from_str(&request.interval)
+        .ok_or_else(|| AppError::BadRequest(format!("Invalid interval: {}", request.interval)))?;
+
+    // Strategy-specific fields are filled in per entry by the engine
+    let base_config = BacktestConfig {
+        symbol: request.symbol.clone(),
+        interval,
+        start_time,
+        end_time,
+        initial_balance: request.initial_balance,
+        strategy_name: String::new(),
+        strategy_type: None,
+        strategy_parameters: json!({}),
+        stop_loss_percentage: None,
+        take_profit_percentage: None,
+        unlimited_capital: false,
+        dca_mode: None,
+        asset_type: request.asset_type.clone(),
+        initial_positions: Vec::new(),
+        fee_percentage: request.fee_percentage,
+        fee_tier: request.fee_tier,
+        fee_token_discount: request.fee_token_discount,
+        maker_fee_percentage: request.maker_fee_percentage,
+        taker_fee_percentage: request.taker_fee_percentage,
+        slippage_percentage: request.slippage_percentage,
+        fill_price: request.fill_price,
+        apply_min_confidence: false,
+        use_heikin_ashi: false,
+        log_trades: false,
+        strict_lookahead: false,
+        benchmark_symbol: None,
+        benchmark_asset_type: None,
+        min_data_coverage_pct: Decimal::from(95),
+        on_short_data: ShortDataAction::Warn,
+        max_curve_points: None,
+        risk_free_rate: Decimal::from(2),
+        periods_per_year: None,
+    };
+
+    let engine = if request.asset_type == "stock" {
+        BacktestEngine::new_with_stock_support(stock_service.api_key().to_string())
+    } else {
+        BacktestEngine::new()
+    };
+
+    let comparison = engine.compare_strategies(base_config, request.strategies).await?;
+    let rate = fx_rates.rate_for_user(db.get_ref().as_ref(), user_id).await?;
+
+    Ok(HttpResponse::Ok().json(comparison.valued_in(rate).rounded()))
+}
+
+/// Stream historical klines through a strategy's live code path in paper mode.
+/// The response is newline-delimited JSON, one `ReplayEvent` per line.
+pub async fn replay_strategy(
+    db: web::Data<std::sync::Arc<sea_orm::DatabaseConnection>>,
+    req: HttpRequest,
+    request: web::Json<ReplayRequest>,
+) -> Result<HttpResponse, AppError> {
+    // Get user ID from request extensions for authentication
+    let user_id = req.extensions()
+        .get::<Uuid>()
+        .copied()
+        .ok_or_else(|| {
+            tracing::error!("User ID not found in request extensions - authentication required");
+            AppError::Unauthorized("Authentication required".to_string())
+        })?;
+
+    let request = request.into_inner();
+    ensure_symbol_allowed(db.as_ref().as_ref(), user_id, &request.symbol).await?;
+
+    let start_time = DateTime::parse_from_rfc3339(&request.start_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid start date: {}", e)))?
+        .with_timezone(&Utc);
+
+    let end_time = DateTime::parse_from_rfc3339(&request.end_date)
+        .map_err(|e| AppError::BadRequest(format!("Invalid end date: {}", e)))?
+        .with_timezone(&Utc);
+
+    let interval = KlineInterval::from_str(&request.interval)
+        .ok_or_else(|| AppError::BadRequest(format!("Invalid interval: {}", request.interval)))?;
+
+    if request.initial_balance <= Decimal::ZERO {
+        return Err(AppError::BadRequest("Initial balance must be positive".to_string()));
+    }
+
+    // Fail before streaming starts if the strategy or data are unusable
+    let mut strategy = live_strategy(&request.strategy_name)?;
+    let klines = BinanceFetcher::new()
+        .fetch_klines(&request.symbol, &interval, start_time, end_time)
+        .await?;
+
+    info!(
+        "User {} replaying {} bars of {} through {}",
+        user_id, klines.len(), request.symbol, request.strategy_name
+    );
+
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    let parameters = request.strategy_parameters.unwrap_or(json!({}));
+    let replay = StrategyReplay::new(&request.symbol, &request.interval, request.initial_balance, request.pace);
+    tokio::spawn(async move {
+        if let Err(e) = replay.run(strategy.as_mut(), &parameters, &klines, Some(sender.clone())).await {
+            tracing::warn!("Replay failed: {}", e);
+            let _ = sender.send(ReplayEvent::Error { message: e.to_string() });
+        }
+    });
+
+    let body = futures::stream::unfold(receiver, |mut receiver| async move {
+        let event = receiver.recv().await?;
+        let mut line = serde_json::to_vec(&event).unwrap_or_default();
+        line.push(b'\n');
+        Some((Ok::<_, AppError>(web::Bytes::from(line)), receiver))
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(body))
+}
+
 /// Query parameters for historical data
 #[derive(Debug, Deserialize)]
 pub struct HistoricalDataQuery {
@@ -530,9 +894,15 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         web::scope("/backtesting")
             .route("/run", web::post().to(run_backtest))
             .route("/validate", web::post().to(validate_backtest))
+            .route("/preview", web::post().to(preview_strategy))
+            .route("/compare-strategies", web::post().to(compare_strategies))
+            .route("/sensitivity", web::post().to(run_sensitivity))
+            .route("/replay", web::post().to(replay_strategy))
             .route("/results", web::get().to(backtest_management::get_user_backtest_results))
             .route("/results/{backtest_id}", web::get().to(backtest_management::get_backtest_result_detail))
             .route("/results/{backtest_id}", web::delete().to(backtest_management::delete_backtest_result))
+            .route("/results/{backtest_id}/resume", web::post().to(resume_backtest))
+            .route("/diff", web::post().to(backtest_management::diff_backtest_results))
             .route("/historical", web::get().to(fetch_historical_data))
             .route("/strategies", web::get().to(list_strategies))
             .route("/strategies/{name}", web::get().to(get_strategy_details))
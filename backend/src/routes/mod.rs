@@ -5,9 +5,11 @@ use serde_json::json;
 use crate::middleware::auth::AuthMiddleware;
 
 use crate::handlers::{
-    auth, user_profile, two_factor, session_management, exchange_management, wallet_management,
+    auth, user_profile, two_factor, session_management, exchange_management, wallet_management, wallet_signatures,
     dca_strategy_management, sma_crossover_strategy_management,
-    grid_trading_strategy_management, strategy_summary, market_data, stock_data,
+    grid_trading_strategy_management, strategy_summary, market_data, stock_data, balance_snapshots,
+    emergency_stop, strategy_presets, strategy_export, strategy_validation, kill_switch, paper_trading, execution_events,
+    dex_quotes,
 };
 
 /// Configure all application routes
@@ -21,17 +23,33 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .configure(configure_exchange_routes)
             .configure(configure_wallet_routes)
             .configure(configure_dca_routes)
+            .configure(configure_ws_routes)
             .configure(configure_sma_crossover_routes)
             .configure(configure_grid_trading_routes)
+            .configure(configure_strategy_routes)
             .configure(configure_exchange_connector_routes)
             .configure(configure_backtesting_routes)
             .configure(configure_market_data_routes)
             .configure(configure_stock_data_routes)
+            .configure(configure_portfolio_routes)
+            .configure(configure_dex_routes)
             .configure(configure_public_routes)
     )
     .route("/health", web::get().to(health_check));
 }
 
+/// Configure the unauthenticated Prometheus scrape endpoint
+pub fn configure_metrics_route(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(metrics_endpoint));
+}
+
+/// Prometheus metrics in text exposition format
+async fn metrics_endpoint() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(crate::utils::metrics::metrics().render())
+}
+
 /// Health check endpoint with API information
 async fn health_check() -> HttpResponse {
     HttpResponse::Ok().json(json!({
@@ -56,8 +74,8 @@ async fn health_check() -> HttpResponse {
             "platforms": {
                 "cex": [
                     "Binance",
-                    "Coinbase (coming soon)",
-                    "Kraken (coming soon)"
+                    "Coinbase",
+                    "Kraken"
                 ],
                 "dex": [
                     "Uniswap (coming soon)",
@@ -82,6 +100,7 @@ async fn health_check() -> HttpResponse {
                 "backtesting": "/api/v1/backtesting",
                 "exchanges": "/api/v1/exchanges",
                 "market_data": "/api/v1/market-data",
+                "dex": "/api/v1/dex",
                 "profile": "/api/v1/profile"
             }
         },
@@ -155,11 +174,21 @@ fn configure_exchange_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/exchanges")
             .route("/connections", web::post().to(exchange_management::create_exchange_connection))
             .route("/connections", web::get().to(exchange_management::get_exchange_connections))
+            .route("/connections/export", web::get().to(exchange_management::export_exchange_connections))
+            // Backups carry every connection's encrypted keys, well past the global 4 KB body limit
+            .service(
+                web::resource("/connections/import")
+                    .app_data(web::JsonConfig::default().limit(exchange_management::CONNECTION_IMPORT_BODY_LIMIT))
+                    .route(web::post().to(exchange_management::import_exchange_connections))
+            )
             .route("/connections/{connection_id}", web::put().to(exchange_management::update_exchange_connection))
             .route("/connections/{connection_id}", web::delete().to(exchange_management::delete_exchange_connection))
             .route("/connections/{connection_id}/sync", web::post().to(exchange_management::sync_exchange_balances))
             .route("/connections/{connection_id}/live-balances", web::post().to(exchange_management::get_live_wallet_balances))
+            .route("/connections/{connection_id}/history", web::get().to(exchange_management::get_connection_history))
+            .route("/connections/{connection_id}/history", web::put().to(exchange_management::set_connection_history))
             .route("/live-balances", web::post().to(exchange_management::get_all_live_user_balances))
+            .route("/consolidated-balances", web::post().to(exchange_management::get_consolidated_portfolio))
     );
     tracing::info!("Exchange routes configured");
 }
@@ -180,6 +209,14 @@ fn configure_dca_routes(cfg: &mut web::ServiceConfig) {
     );
 }
 
+/// Configure WebSocket routes pushing live events
+fn configure_ws_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/ws")
+            .route("/executions", web::get().to(execution_events::stream_execution_events))
+    );
+}
+
 /// Configure exchange connector routes
 fn configure_exchange_connector_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -219,6 +256,24 @@ fn configure_grid_trading_routes(cfg: &mut web::ServiceConfig) {
     );
 }
 
+/// Configure routes acting on all of a user's strategies at once
+fn configure_strategy_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/strategies")
+            .route("/emergency-stop", web::post().to(emergency_stop::emergency_stop))
+            .route("/import", web::post().to(strategy_export::import_strategy))
+            .route("/paper/{strategy_id}", web::get().to(paper_trading::get_paper_portfolio))
+            .route("/paper/{strategy_id}", web::put().to(paper_trading::configure_paper_trading))
+            .route("/paper/{strategy_id}/reset", web::post().to(paper_trading::reset_paper_portfolio))
+            .route("/{strategy_id}/presets", web::get().to(strategy_presets::get_strategy_presets))
+            .route("/{strategy_id}/from-preset", web::post().to(strategy_presets::create_strategy_from_preset))
+            .route("/{strategy_id}/validate", web::post().to(strategy_validation::validate_strategy_parameters))
+            .route("/{strategy_id}/export", web::get().to(strategy_export::export_strategy))
+            .route("/{strategy_id}/kill-switch", web::post().to(kill_switch::set_kill_switch))
+            .route("/{strategy_id}/kill-switch", web::delete().to(kill_switch::clear_kill_switch))
+    );
+}
+
 /// Configure backtesting routes
 fn configure_backtesting_routes(cfg: &mut web::ServiceConfig) {
     // Use the new backtesting module
@@ -235,6 +290,7 @@ fn configure_market_data_routes(cfg: &mut web::ServiceConfig) {
             .route("/m2", web::get().to(market_data::get_m2))
             .route("/btc-price", web::get().to(market_data::get_btc_price))
             .route("/fear-greed", web::get().to(market_data::get_fear_greed_index))
+            .route("/correlations", web::post().to(market_data::get_correlations))
     );
 }
 
@@ -247,6 +303,23 @@ fn configure_stock_data_routes(cfg: &mut web::ServiceConfig) {
     );
 }
 
+/// Configure portfolio history routes
+fn configure_portfolio_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/portfolio")
+            .route("/snapshots", web::get().to(balance_snapshots::get_balance_snapshots))
+            .route("/snapshots/backfill", web::post().to(balance_snapshots::backfill_balance_snapshots))
+    );
+}
+
+/// Configure DEX routes
+fn configure_dex_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/dex")
+            .route("/quote", web::get().to(dex_quotes::get_swap_quote))
+    );
+}
+
 /// Configure wallet connection routes
 fn configure_wallet_routes(cfg: &mut web::ServiceConfig) {
     tracing::info!("Configuring wallet routes...");
@@ -258,6 +331,9 @@ fn configure_wallet_routes(cfg: &mut web::ServiceConfig) {
             .route("/connections/{connection_id}", web::put().to(wallet_management::update_wallet_connection))
             .route("/connections/{connection_id}", web::delete().to(wallet_management::delete_wallet_connection))
             .route("/connections/{connection_id}/balance", web::post().to(wallet_management::get_wallet_balance))
+            .route("/signature-requests", web::get().to(wallet_signatures::get_pending_signatures))
+            .route("/signature-requests/{request_id}/signature", web::post().to(wallet_signatures::submit_signature))
+            .route("/signature-requests/{request_id}/reject", web::post().to(wallet_signatures::reject_signature))
     );
     tracing::info!("Wallet routes configured");
 }
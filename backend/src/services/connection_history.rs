@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::exchange_connectors::{Exchange, ExchangeCredentials, ExchangeFactory};
+use crate::models::connection_balance_snapshot::{self, HistoryPoint};
+use crate::models::exchange_connection;
+use crate::utils::encryption::{EncryptedData, EncryptionService};
+use crate::utils::errors::AppError;
+
+/// Seconds between snapshots unless `BALANCE_HISTORY_INTERVAL_SECS` is set
+pub const DEFAULT_HISTORY_INTERVAL_SECS: u64 = 3600;
+
+/// Shortest interval accepted, to stay well inside exchange rate limits
+const MIN_HISTORY_INTERVAL_SECS: u64 = 60;
+
+/// Records the USD value of every exchange connection that opted into balance history.
+///
+/// Stored credentials are encrypted with the user's password, which the server never
+/// keeps. Opting in seals a second copy under the server-held `BALANCE_HISTORY_KEY`;
+/// without that key nothing is recorded and opting in is refused.
+#[derive(Clone)]
+pub struct ConnectionHistoryService {
+    db: Arc<DatabaseConnection>,
+    key: Option<Arc<str>>,
+    interval: std::time::Duration,
+}
+
+impl ConnectionHistoryService {
+    pub fn new(db: Arc<DatabaseConnection>, key: Option<String>, interval: std::time::Duration) -> Self {
+        Self { db, key: key.map(Arc::from), interval }
+    }
+
+    /// Read `BALANCE_HISTORY_KEY` and `BALANCE_HISTORY_INTERVAL_SECS`
+    pub fn from_env(db: Arc<DatabaseConnection>) -> Self {
+        let key = std::env::var("BALANCE_HISTORY_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+        let interval_secs = std::env::var("BALANCE_HISTORY_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_HISTORY_INTERVAL_SECS)
+            .max(MIN_HISTORY_INTERVAL_SECS);
+
+        Self::new(db, key, std::time::Duration::from_secs(interval_secs))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.key.is_some()
+    }
+
+    fn key(&self) -> Result<&str, AppError> {
+        self.key
+            .as_deref()
+            .ok_or_else(|| AppError::BadRequest("Balance history is not enabled on this server".to_string()))
+    }
+
+    /// Encrypt `credentials` under the history key so the background job can use them
+    pub fn seal_credentials(&self, credentials: &ExchangeCredentials, user_id: Uuid) -> Result<EncryptedData, AppError> {
+        let json = serde_json::to_string(credentials)?;
+        EncryptionService::new().encrypt_api_credentials(&json, self.key()?, &user_id.to_string())
+    }
+
+    fn open_credentials(&self, connection: &exchange_connection::Model) -> Result<ExchangeCredentials, AppError> {
+        let (Some(ciphertext), Some(nonce), Some(salt)) = (
+            &connection.history_credentials,
+            &connection.history_credentials_nonce,
+            &connection.history_credentials_salt,
+        ) else {
+            return Err(AppError::BadRequest("Balance history is not enabled for this connection".to_string()));
+        };
+
+        let sealed = EncryptedData {
+            ciphertext: ciphertext.clone(),
+            nonce: nonce.clone(),
+            salt: salt.clone(),
+        };
+        let json = EncryptionService::new()
+            .decrypt_api_credentials(&sealed, self.key()?, &connection.user_id.to_string())?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Record a snapshot of every opted-in connection once per interval, forever
+    pub async fn start(&self) {
+        if !self.is_enabled() {
+            info!("Balance history is off: BALANCE_HISTORY_KEY is not set");
+            return;
+        }
+
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            match self.record_all().await {
+                Ok(recorded) => info!("Recorded balance history for {} connections", recorded),
+                Err(e) => warn!("Balance history run failed: {}", e),
+            }
+        }
+    }
+
+    /// Snapshot every active connection with history on. A connection that fails is
+    /// logged and skipped; the rest are still recorded.
+    pub async fn record_all(&self) -> Result<usize, AppError> {
+        let connections = exchange_connection::Entity::find()
+            .filter(exchange_connection::Column::IsActive.eq(true))
+            .filter(exchange_connection::Column::HistoryCredentials.is_not_null())
+            .all(self.db.as_ref())
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let mut recorded = 0;
+        for connection in connections {
+            match self.record(&connection).await {
+                Ok(()) => recorded += 1,
+                Err(e) => warn!("Could not record balance history for connection {}: {}", connection.id, e),
+            }
+        }
+        Ok(recorded)
+    }
+
+    async fn record(&self, connection: &exchange_connection::Model) -> Result<(), AppError> {
+        let credentials = self.open_credentials(connection)?;
+        let exchange = Exchange::from_str(&connection.exchange_name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unsupported exchange: {}", connection.exchange_name)))?;
+
+        let connector = ExchangeFactory::create(exchange, credentials)
+            .map_err(|e| AppError::ExternalServiceError(format!("Failed to create connector: {}", e)))?;
+        let balances = connector
+            .get_all_balances()
+            .await
+            .map_err(|e| AppError::ExternalServiceError(format!("Failed to fetch balances: {}", e)))?;
+
+        record_snapshot(self.db.as_ref(), connection.id, balances.total_usd_value, Utc::now()).await
+    }
+}
+
+/// Store one point of a connection's value
+pub async fn record_snapshot(
+    db: &DatabaseConnection,
+    connection_id: Uuid,
+    total_usd_value: Decimal,
+    recorded_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+    connection_balance_snapshot::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        connection_id: Set(connection_id),
+        total_usd_value: Set(total_usd_value),
+        recorded_at: Set(recorded_at),
+    }
+    .insert(db)
+    .await
+    .map_err(AppError::DatabaseError)?;
+    Ok(())
+}
+
+/// Width of the buckets a history range is reduced to: hourly up to a week, daily beyond
+pub fn bucket_for_range(range: Duration) -> Duration {
+    if range <= Duration::weeks(1) {
+        Duration::hours(1)
+    } else {
+        Duration::days(1)
+    }
+}
+
+/// A connection's value since `since`, oldest first, keeping the last snapshot of each `bucket`
+pub async fn connection_history(
+    db: &DatabaseConnection,
+    connection_id: Uuid,
+    since: DateTime<Utc>,
+    bucket: Duration,
+) -> Result<Vec<HistoryPoint>, AppError> {
+    let snapshots = connection_balance_snapshot::Entity::find()
+        .filter(connection_balance_snapshot::Column::ConnectionId.eq(connection_id))
+        .filter(connection_balance_snapshot::Column::RecordedAt.gte(since))
+        .order_by_asc(connection_balance_snapshot::Column::RecordedAt)
+        .all(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    Ok(last_per_bucket(snapshots, bucket))
+}
+
+/// Reduce snapshots sorted by time to the last one in each bucket (buckets aligned to the Unix epoch)
+fn last_per_bucket(snapshots: Vec<connection_balance_snapshot::Model>, bucket: Duration) -> Vec<HistoryPoint> {
+    let width = bucket.num_seconds().max(1);
+    let mut points: Vec<(i64, HistoryPoint)> = Vec::new();
+
+    for snapshot in snapshots {
+        let bucket_index = snapshot.recorded_at.timestamp().div_euclid(width);
+        let point = HistoryPoint {
+            timestamp: snapshot.recorded_at,
+            total_usd_value: snapshot.total_usd_value,
+        };
+        match points.last_mut() {
+            Some((last_index, last)) if *last_index == bucket_index => *last = point,
+            _ => points.push((bucket_index, point)),
+        }
+    }
+
+    points.into_iter().map(|(_, point)| point).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{seed_exchange_connection, seed_user, test_db};
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, hour, minute, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_history_keeps_the_last_snapshot_of_each_bucket_in_range() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let connection_id = seed_exchange_connection(&db, user_id, "binance").await;
+        let other_connection = seed_exchange_connection(&db, user_id, "okx").await;
+
+        // The day before the range starts
+        record_snapshot(&db, connection_id, Decimal::from(50), at(0, 0) - Duration::days(1)).await.unwrap();
+        record_snapshot(&db, connection_id, Decimal::from(100), at(0, 10)).await.unwrap();
+        record_snapshot(&db, connection_id, Decimal::from(110), at(0, 50)).await.unwrap();
+        record_snapshot(&db, connection_id, Decimal::from(120), at(1, 20)).await.unwrap();
+        record_snapshot(&db, other_connection, Decimal::from(999), at(0, 30)).await.unwrap();
+
+        let points = connection_history(&db, connection_id, at(0, 0), Duration::hours(1)).await.unwrap();
+
+        assert_eq!(
+            points,
+            vec![
+                HistoryPoint { timestamp: at(0, 50), total_usd_value: Decimal::from(110) },
+                HistoryPoint { timestamp: at(1, 20), total_usd_value: Decimal::from(120) },
+            ]
+        );
+
+        let daily = connection_history(&db, connection_id, at(0, 0) - Duration::days(2), Duration::days(1)).await.unwrap();
+        let values: Vec<Decimal> = daily.iter().map(|p| p.total_usd_value).collect();
+        assert_eq!(values, vec![Decimal::from(50), Decimal::from(120)]);
+    }
+
+    #[test]
+    fn test_bucket_grows_with_the_range() {
+        assert_eq!(bucket_for_range(Duration::hours(24)), Duration::hours(1));
+        assert_eq!(bucket_for_range(Duration::weeks(1)), Duration::hours(1));
+        assert_eq!(bucket_for_range(Duration::days(30)), Duration::days(1));
+    }
+
+    #[tokio::test]
+    async fn test_sealed_credentials_need_the_history_key() {
+        let db = Arc::new(test_db().await);
+        let user_id = seed_user(&db).await;
+        let connection_id = seed_exchange_connection(&db, user_id, "binance").await;
+        let credentials = ExchangeCredentials {
+            api_key: "api-key".to_string(),
+            api_secret: "api-secret".to_string(),
+            passphrase: None,
+        };
+
+        let disabled = ConnectionHistoryService::new(db.clone(), None, std::time::Duration::from_secs(60));
+        assert!(disabled.seal_credentials(&credentials, user_id).is_err());
+
+        let service = ConnectionHistoryService::new(db.clone(), Some("history-key".to_string()), std::time::Duration::from_secs(60));
+        let sealed = service.seal_credentials(&credentials, user_id).unwrap();
+
+        let mut connection: exchange_connection::ActiveModel = exchange_connection::Entity::find_by_id(connection_id)
+            .one(db.as_ref())
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        connection.history_credentials = Set(Some(sealed.ciphertext));
+        connection.history_credentials_nonce = Set(Some(sealed.nonce));
+        connection.history_credentials_salt = Set(Some(sealed.salt));
+        let connection = connection.update(db.as_ref()).await.unwrap();
+
+        let opened = service.open_credentials(&connection).unwrap();
+        assert_eq!((opened.api_key.as_str(), opened.api_secret.as_str()), ("api-key", "api-secret"));
+
+        let wrong_key = ConnectionHistoryService::new(db, Some("other-key".to_string()), std::time::Duration::from_secs(60));
+        assert!(wrong_key.open_credentials(&connection).is_err());
+    }
+}
@@ -3,25 +3,33 @@ use rust_decimal::Decimal;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{RwLock, Mutex, broadcast};
+use tokio::sync::{RwLock, broadcast};
 use tokio::time::{interval, sleep};
 use tracing::{info, warn, error, debug};
 use uuid::Uuid;
 use futures;
 
+use crate::exchange_connectors::common_types::OrderSide;
+use crate::exchange_connectors::Exchange;
 use crate::models::{
     dca_strategy::{
-        Entity as DCAStrategyEntity, Model as DCAStrategy,
-        execution::ActiveModel as ExecutionActiveModel,
+        ActiveModel as DCAStrategyActiveModel, Entity as DCAStrategyEntity, Model as DCAStrategy,
+        execution::ActiveModel as ExecutionActiveModel, DCAExecutionResponse,
         ExecutionType, TriggerReason,
         market_data::Model as MarketDataModel,
     },
     exchange_connection::Entity as ExchangeConnectionEntity,
 };
-use crate::services::MarketDataService;
+use crate::services::emergency_stop::StrategyKind;
+use crate::services::order_execution::{fee_rates_for, notional_limit_for};
+use crate::services::{ensure_symbol_allowed, ExecutionScheduler, MarketDataService, PaperTradingService};
+use crate::strategies::core::{OrderStatus, OrderType, OrderUpdate, Strategy, StrategyMode};
+use crate::strategies::implementations::dca::DCAStrategy as StrategyFrameworkDCA;
 use crate::utils::{
     errors::AppError,
     encryption::EncryptionService,
+    metrics::metrics,
+    versioning::update_versioned,
 };
 
 /// High-performance DCA execution engine optimized for Rust's capabilities
@@ -36,19 +44,91 @@ pub struct DCAExecutionEngine {
     strategy_cache: Arc<RwLock<HashMap<Uuid, DCAStrategy>>>,
     market_data_cache: Arc<RwLock<HashMap<String, MarketDataModel>>>,
 
-    // Execution queue for batch processing
-    execution_queue: Arc<Mutex<Vec<ExecutionRequest>>>,
+    // Fair execution queue with a cap on concurrently executing strategies
+    scheduler: ExecutionScheduler,
 
     // Performance metrics
     execution_stats: Arc<RwLock<ExecutionStats>>,
 
     // Graceful shutdown mechanism
     shutdown_tx: broadcast::Sender<()>,
+
+    // Executions and status changes, for live event streams
+    events_tx: broadcast::Sender<ExecutionEvent>,
+
+    // Strategy scan cadence
+    schedule: EngineSchedule,
+}
+
+/// How often the engine scans strategies for due executions
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineSchedule {
+    /// Minimum gap between scans, even when a strategy is overdue
+    pub poll_interval: std::time::Duration,
+    /// Longest the engine sleeps when nothing is due, so new strategies are picked up
+    pub max_idle: std::time::Duration,
+    /// Upper bound of the random delay added to each sleep to spread exchange load
+    pub jitter: std::time::Duration,
+}
+
+impl Default for EngineSchedule {
+    fn default() -> Self {
+        Self {
+            poll_interval: std::time::Duration::from_secs(30),
+            max_idle: std::time::Duration::from_secs(300),
+            jitter: std::time::Duration::from_secs(5),
+        }
+    }
+}
+
+impl EngineSchedule {
+    /// Read `EXECUTION_POLL_INTERVAL_SECS`, `EXECUTION_MAX_IDLE_SECS` and `EXECUTION_POLL_JITTER_SECS`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |key: &str, default: std::time::Duration| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(default)
+        };
+
+        let poll_interval = read("EXECUTION_POLL_INTERVAL_SECS", defaults.poll_interval).max(std::time::Duration::from_secs(1));
+        Self {
+            poll_interval,
+            max_idle: read("EXECUTION_MAX_IDLE_SECS", defaults.max_idle).max(poll_interval),
+            jitter: read("EXECUTION_POLL_JITTER_SECS", defaults.jitter),
+        }
+    }
+
+    /// Time to sleep before the next scan: until the earliest upcoming execution, but
+    /// never less than `poll_interval` nor more than `max_idle`, plus `jitter`
+    pub fn next_delay(&self, now: DateTime<Utc>, next_due: Option<DateTime<Utc>>, jitter: std::time::Duration) -> std::time::Duration {
+        let base = match next_due {
+            Some(due) => (due - now)
+                .to_std()
+                .unwrap_or_default()
+                .clamp(self.poll_interval, self.max_idle),
+            None => self.max_idle,
+        };
+
+        base + jitter.min(self.jitter)
+    }
+
+    fn random_jitter(&self) -> std::time::Duration {
+        use rand::Rng;
+        let max_ms = self.jitter.as_millis() as u64;
+        if max_ms == 0 {
+            return std::time::Duration::ZERO;
+        }
+        std::time::Duration::from_millis(rand::thread_rng().gen_range(0..=max_ms))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ExecutionRequest {
     pub strategy_id: Uuid,
+    pub user_id: Uuid,
     pub trigger_reason: TriggerReason,
     pub manual_amount: Option<Decimal>,
     #[allow(dead_code)]
@@ -63,6 +143,44 @@ pub struct ExecutionStats {
     pub total_volume_usd: Decimal,
     pub average_execution_time_ms: f64,
     pub last_execution_batch: Option<DateTime<Utc>>,
+    /// Executions waiting for a free execution slot
+    pub queue_depth: usize,
+    /// Executions currently running
+    pub running_executions: usize,
+    /// Most executions allowed to run at once
+    pub max_concurrent_executions: usize,
+}
+
+/// Events a slow subscriber may fall behind by before it misses the oldest ones
+const EXECUTION_EVENT_CAPACITY: usize = 256;
+
+/// Something that happened to one of a user's strategies, pushed to their live event
+/// streams
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// The engine recorded an execution, filled or failed
+    ExecutionRecorded {
+        #[serde(skip)]
+        user_id: Uuid,
+        execution: DCAExecutionResponse,
+    },
+    /// A strategy was paused, resumed or otherwise changed status
+    StatusChanged {
+        #[serde(skip)]
+        user_id: Uuid,
+        strategy_id: Uuid,
+        status: String,
+    },
+}
+
+impl ExecutionEvent {
+    /// The user whose strategy the event is about
+    pub fn user_id(&self) -> Uuid {
+        match self {
+            Self::ExecutionRecorded { user_id, .. } | Self::StatusChanged { user_id, .. } => *user_id,
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -87,6 +205,7 @@ impl DCAExecutionEngine {
         encryption_service: EncryptionService,
     ) -> Self {
         let (shutdown_tx, _) = broadcast::channel(1);
+        let (events_tx, _) = broadcast::channel(EXECUTION_EVENT_CAPACITY);
 
         Self {
             db,
@@ -94,9 +213,11 @@ impl DCAExecutionEngine {
             encryption_service: Arc::new(encryption_service),
             strategy_cache: Arc::new(RwLock::new(HashMap::new())),
             market_data_cache: Arc::new(RwLock::new(HashMap::new())),
-            execution_queue: Arc::new(Mutex::new(Vec::new())),
+            scheduler: ExecutionScheduler::from_env(),
             execution_stats: Arc::new(RwLock::new(ExecutionStats::default())),
             shutdown_tx,
+            events_tx,
+            schedule: EngineSchedule::from_env(),
         }
     }
 
@@ -126,18 +247,25 @@ impl DCAExecutionEngine {
         });
     }
 
-    /// Monitor strategies and queue executions (runs every 30 seconds)
+    /// Monitor strategies and queue executions, sleeping until the next one is due
     async fn strategy_monitoring_loop(&self) {
-        let mut interval = interval(tokio::time::Duration::from_secs(30));
         let mut shutdown_rx = self.shutdown_tx.subscribe();
 
         loop {
-            tokio::select! {
-                _ = interval.tick() => {
-                    if let Err(e) = self.scan_and_queue_strategies().await {
-                        error!("Error in strategy monitoring loop: {:?}", e);
-                    }
+            let next_due = match self.scan_and_queue_strategies().await {
+                Ok(next_due) => next_due,
+                Err(e) => {
+                    error!("Error in strategy monitoring loop: {:?}", e);
+                    // Retry soon rather than waiting out a full idle period
+                    Some(Utc::now())
                 }
+            };
+
+            let delay = self.schedule.next_delay(Utc::now(), next_due, self.schedule.random_jitter());
+            debug!("Next strategy scan in {:?}", delay);
+
+            tokio::select! {
+                _ = sleep(delay) => {}
                 _ = shutdown_rx.recv() => {
                     info!("Strategy monitoring loop shutting down gracefully");
                     break;
@@ -206,8 +334,9 @@ impl DCAExecutionEngine {
         }
     }
 
-    /// Scan active strategies and queue those ready for execution
-    async fn scan_and_queue_strategies(&self) -> Result<(), AppError> {
+    /// Scan active strategies and queue those ready for execution. Returns when the
+    /// next strategy that wasn't queued becomes due, if any.
+    async fn scan_and_queue_strategies(&self) -> Result<Option<DateTime<Utc>>, AppError> {
         let start_time = std::time::Instant::now();
 
         // Load active strategies from cache or database
@@ -216,34 +345,42 @@ impl DCAExecutionEngine {
         debug!("Scanning {} active strategies for execution", strategies.len());
 
         let mut queue_requests = Vec::new();
+        let mut waiting = Vec::new();
 
         for strategy in strategies {
             // Check if strategy should execute
             if let Some(trigger_reason) = self.should_strategy_execute(&strategy).await? {
                 queue_requests.push(ExecutionRequest {
                     strategy_id: strategy.id,
+                    user_id: strategy.user_id,
                     trigger_reason,
                     manual_amount: None,
                     created_at: Utc::now(),
                 });
+            } else if strategy.status == "active" {
+                waiting.push(strategy.next_execution_at);
             }
         }
 
-        // Add to execution queue
-        if !queue_requests.is_empty() {
-            let mut queue = self.execution_queue.lock().await;
-            queue.extend(queue_requests.clone());
-            info!("Queued {} strategies for execution", queue_requests.len());
+        // Add to execution queue; strategies still queued or executing are skipped
+        let mut queued = 0;
+        for request in queue_requests {
+            if self.scheduler.enqueue(request).await {
+                queued += 1;
+            }
+        }
+        if queued > 0 {
+            info!("Queued {} strategies for execution", queued);
         }
 
         debug!("Strategy scan completed in {}ms", start_time.elapsed().as_millis());
-        Ok(())
+        Ok(earliest_due(waiting, Utc::now()))
     }
 
     /// Check if a strategy should execute using the strategy framework
     async fn should_strategy_execute(&self, strategy: &DCAStrategy) -> Result<Option<TriggerReason>, AppError> {
-        // Check if strategy is active
-        if strategy.status != "active" {
+        // Check if strategy is active and not kill-switched
+        if strategy.status != "active" || strategy.kill_switch {
             return Ok(None);
         }
 
@@ -269,28 +406,27 @@ impl DCAExecutionEngine {
         }
     }
 
-    /// Process queued executions in optimized batches
+    /// Process queued executions, at most `EXECUTION_MAX_CONCURRENT` at a time
     async fn process_execution_batch(&self) -> Result<(), AppError> {
-        let mut queue = self.execution_queue.lock().await;
-        if queue.is_empty() {
+        let queue_depth = self.scheduler.queue_depth().await;
+        if queue_depth == 0 {
             return Ok(());
         }
 
-        // Take up to 50 executions per batch for optimal performance
-        let batch_size = std::cmp::min(queue.len(), 50);
-        let batch: Vec<ExecutionRequest> = queue.drain(..batch_size).collect();
-        drop(queue); // Release lock early
-
-        info!("Processing execution batch of {} requests", batch.len());
+        info!(
+            "Processing {} queued executions, up to {} at a time",
+            queue_depth,
+            self.scheduler.max_concurrent()
+        );
 
         let start_time = std::time::Instant::now();
 
-        // Process executions concurrently using Rust's async capabilities
-        let execution_futures: Vec<_> = batch.into_iter()
-            .map(|request| self.execute_strategy_request(request))
-            .collect();
-
-        let results = futures::future::join_all(execution_futures).await;
+        let results = self.scheduler
+            .run(|request| self.execute_strategy_request(request))
+            .await;
+        if results.is_empty() {
+            return Ok(());
+        }
 
         // Update statistics
         let mut stats = self.execution_stats.write().await;
@@ -332,6 +468,37 @@ impl DCAExecutionEngine {
             }
         };
 
+        if strategy.kill_switch {
+            warn!("Skipping execution of kill-switched strategy {}", strategy.id);
+            return ExecutionResult {
+                strategy_id: request.strategy_id,
+                execution_id: Uuid::new_v4(),
+                success: false,
+                execution_type: ExecutionType::Skip,
+                amount_usd: Decimal::ZERO,
+                amount_asset: None,
+                price: None,
+                error_message: Some("Strategy kill switch is set".to_string()),
+                execution_time_ms: start_time.elapsed().as_millis(),
+            };
+        }
+
+        // Symbol lists can change after the strategy was created
+        if let Err(e) = ensure_symbol_allowed(self.db.as_ref(), strategy.user_id, &strategy.asset_symbol).await {
+            warn!("Skipping execution of strategy {}: {}", strategy.id, e);
+            return ExecutionResult {
+                strategy_id: request.strategy_id,
+                execution_id: Uuid::new_v4(),
+                success: false,
+                execution_type: ExecutionType::Skip,
+                amount_usd: Decimal::ZERO,
+                amount_asset: None,
+                price: None,
+                error_message: Some(e.to_string()),
+                execution_time_ms: start_time.elapsed().as_millis(),
+            };
+        }
+
         let market_data = match self.get_market_data_for_asset(&strategy.asset_symbol).await {
             Ok(data) => data,
             Err(e) => {
@@ -399,6 +566,24 @@ impl DCAExecutionEngine {
             };
         }
 
+        // The strategy framework carries the saved state: the cost basis and any held-back buys
+        let mut framework = match strategy.to_restored_strategy_framework(historical_data.clone()).await {
+            Ok(framework) => framework,
+            Err(e) => {
+                return ExecutionResult {
+                    strategy_id: request.strategy_id,
+                    execution_id: Uuid::new_v4(),
+                    success: false,
+                    execution_type: ExecutionType::Skip,
+                    amount_usd: Decimal::ZERO,
+                    amount_asset: None,
+                    price: Some(market_data.price),
+                    error_message: Some(format!("Failed to restore strategy state: {}", e)),
+                    execution_time_ms: start_time.elapsed().as_millis(),
+                };
+            }
+        };
+
         // For now, we default to buy execution (most DCA strategies are buy-heavy)
         let execution_type = ExecutionType::Buy;
 
@@ -424,12 +609,101 @@ impl DCAExecutionEngine {
             }
         };
 
-        // Execute the actual trade
-        match self.execute_trade(&strategy, execution_type.clone(), amount_usd, market_data.price).await {
+        // Scheduled buys below the exchange's minimum order value are combined into a later order
+        let min_notional = match request.manual_amount {
+            Some(_) => None,
+            None => match self.market_service.get_min_notional(&strategy.asset_symbol).await {
+                Ok(min_notional) => Some(min_notional),
+                Err(e) => {
+                    warn!("No minimum order value for {}, placing the buy as is: {:?}", strategy.asset_symbol, e);
+                    None
+                }
+            },
+        };
+        let mut pending = framework.pending_buys().clone();
+        let amount_usd = match min_notional {
+            Some(min_notional) => match pending.accumulate(amount_usd, min_notional) {
+                Some(total) => total,
+                None => {
+                    info!("Holding ${} for strategy {} until the ${} minimum order is reached",
+                          pending.pending_amount, strategy.name, min_notional);
+                    framework.set_pending_buys(pending);
+                    if let Err(e) = self.save_strategy_state(&strategy, &framework).await {
+                        warn!("Failed to save the held-back buys of strategy {}: {:?}", strategy.id, e);
+                    }
+                    if let Err(e) = self.schedule_next_purchase(&strategy, Utc::now()).await {
+                        warn!("Failed to schedule the next purchase of strategy {}: {:?}", strategy.id, e);
+                    }
+                    return ExecutionResult {
+                        strategy_id: request.strategy_id,
+                        execution_id: Uuid::new_v4(),
+                        success: true,
+                        execution_type: ExecutionType::Skip,
+                        amount_usd: Decimal::ZERO,
+                        amount_asset: None,
+                        price: Some(market_data.price),
+                        error_message: None,
+                        execution_time_ms: start_time.elapsed().as_millis(),
+                    };
+                }
+            },
+            None => amount_usd,
+        };
+
+        // Paper strategies fill against their paper portfolio and never reach an exchange.
+        // Either way the buy has to fit the user's and the strategy's order size limits.
+        let mode = strategy.execution_mode();
+        let (amount_usd, trade) = match self.apply_notional_limit(&strategy, amount_usd).await {
+            Ok(amount_usd) => {
+                let trade = match mode {
+                    StrategyMode::Paper => {
+                        let reason = String::from(request.trigger_reason.clone());
+                        self.execute_paper_trade(&strategy, &reason, amount_usd, market_data.price).await
+                    }
+                    _ => self.execute_trade(&strategy, execution_type.clone(), amount_usd, market_data.price).await,
+                };
+                (amount_usd, trade)
+            }
+            Err(e) => (amount_usd, Err(e)),
+        };
+
+        // Record the fill with the strategy framework and save its state. Held-back buys
+        // are only dropped once the combined order goes through; after a failure they stay
+        // pending and are retried with the next buy.
+        if let Ok((amount_asset, actual_price)) = &trade {
+            let fill = OrderUpdate {
+                order_id: Uuid::new_v4().to_string(),
+                symbol: strategy.asset_symbol.clone(),
+                side: OrderSide::Buy,
+                order_type: OrderType::Market,
+                status: OrderStatus::Filled,
+                quantity: *amount_asset,
+                price: Some(*actual_price),
+                filled_quantity: *amount_asset,
+                timestamp: Utc::now(),
+            };
+            if let Err(e) = framework.on_order_update(&fill).await {
+                warn!("Strategy {} failed to record its fill: {:?}", strategy.id, e);
+            }
+            if min_notional.is_some() {
+                pending.clear();
+            }
+        }
+        framework.set_pending_buys(pending);
+        if let Err(e) = self.save_strategy_state(&strategy, &framework).await {
+            warn!("Failed to save the state of strategy {}: {:?}", strategy.id, e);
+        }
+
+        match trade {
             Ok((amount_asset, actual_price)) => {
+                if mode == StrategyMode::Live {
+                    metrics().record_live_order("dca_engine");
+                }
+
                 // Record execution in database
                 if let Err(e) = self.record_execution(
                     request.strategy_id,
+                    strategy.user_id,
                     execution_type.clone(),
                     request.trigger_reason,
                     amount_usd,
@@ -438,10 +712,15 @@ impl DCAExecutionEngine {
                     market_data.fear_greed_index,
                     market_data.volatility_7d,
                     None,
+                    &mode,
                 ).await {
                     warn!("Failed to record execution: {:?}", e);
                 }
 
+                if let Err(e) = self.schedule_next_purchase(&strategy, Utc::now()).await {
+                    warn!("Failed to schedule the next purchase of strategy {}: {:?}", strategy.id, e);
+                }
+
                 // Update strategy statistics
                 if let Err(e) = self.update_strategy_stats(
                     request.strategy_id,
@@ -469,6 +748,7 @@ impl DCAExecutionEngine {
                 // Record failed execution
                 if let Err(record_err) = self.record_execution(
                     request.strategy_id,
+                    strategy.user_id,
                     execution_type.clone(),
                     request.trigger_reason,
                     amount_usd,
@@ -477,6 +757,7 @@ impl DCAExecutionEngine {
                     market_data.fear_greed_index,
                     market_data.volatility_7d,
                     Some(e.to_string()),
+                    &mode,
                 ).await {
                     warn!("Failed to record failed execution: {:?}", record_err);
                 }
@@ -496,6 +777,32 @@ impl DCAExecutionEngine {
         }
     }
 
+    /// Clamp or reject a buy of `amount_usd` against the tighter of the user's and the
+    /// strategy's `max_order_notional`
+    async fn apply_notional_limit(&self, strategy: &DCAStrategy, amount_usd: Decimal) -> Result<Decimal, AppError> {
+        let parameters: serde_json::Value = serde_json::from_str(&strategy.config_json)
+            .map_err(|e| AppError::BadRequest(format!("Invalid DCA config: {}", e)))?;
+        let Some(limit) = notional_limit_for(self.db.as_ref(), strategy.user_id, &parameters).await? else {
+            return Ok(amount_usd);
+        };
+
+        // A USD amount is its own notional
+        match limit.apply(amount_usd, Decimal::ONE) {
+            Ok(allowed) if allowed == amount_usd => Ok(amount_usd),
+            Ok(allowed) => {
+                warn!("Clamped buy of ${} for strategy {} to ${} to stay within max notional {}",
+                      amount_usd, strategy.name, allowed, limit.max_order_notional);
+                metrics().record_notional_limit_hit("clamped");
+                Ok(allowed)
+            }
+            Err(reason) => {
+                warn!("Rejected buy of ${} for strategy {}: {}", amount_usd, strategy.name, reason);
+                metrics().record_notional_limit_hit("rejected");
+                Err(AppError::BadRequest(reason))
+            }
+        }
+    }
+
     /// Execute actual trade on exchange with error handling
     async fn execute_trade(
         &self,
@@ -515,7 +822,8 @@ impl DCAExecutionEngine {
 
         // For now, simulate trade execution
         // In production, this would place actual orders on the exchange
-        let amount_asset = amount_usd / current_price;
+        let fee = self.estimate_buy_fee(strategy, amount_usd).await?;
+        let amount_asset = (amount_usd - fee) / current_price;
 
         // Simulate small price slippage (0.1%)
         let actual_price = current_price * Decimal::try_from(1.001)
@@ -524,20 +832,64 @@ impl DCAExecutionEngine {
         // Add small delay to simulate network latency
         sleep(tokio::time::Duration::from_millis(100)).await;
 
-        info!("Simulated {} of {:.6} {} at ${:.2} for strategy {}",
+        info!("Simulated {} of {:.6} {} at ${:.2} (${:.4} fee) for strategy {}",
               String::from(execution_type.clone()),
               amount_asset,
               strategy.asset_symbol,
               actual_price,
+              fee,
               strategy.name);
 
         Ok((amount_asset, actual_price))
     }
 
-    /// Record execution in database
+    /// Buy `amount_usd` of the asset at the market price from the strategy's paper
+    /// portfolio, opening one with the default starting balance on the first paper buy
+    async fn execute_paper_trade(
+        &self,
+        strategy: &DCAStrategy,
+        reason: &str,
+        amount_usd: Decimal,
+        current_price: Decimal,
+    ) -> Result<(Decimal, Decimal), AppError> {
+        if current_price <= Decimal::ZERO {
+            return Err(AppError::ExternalServiceError(format!(
+                "No market price for {}",
+                strategy.asset_symbol
+            )));
+        }
+
+        let paper = PaperTradingService::new(self.db.clone());
+        paper.configure(strategy.user_id, strategy.id, StrategyKind::Dca, None).await?;
+
+        let fee = self.estimate_buy_fee(strategy, amount_usd).await?;
+        let amount_asset = (amount_usd - fee) / current_price;
+        paper
+            .fill(strategy.user_id, strategy.id, OrderSide::Buy, amount_asset, current_price, fee, reason, Utc::now())
+            .await?;
+
+        info!("Paper buy of {:.6} {} at ${:.2} (${:.4} fee) for strategy {}",
+              amount_asset,
+              strategy.asset_symbol,
+              current_price,
+              fee,
+              strategy.name);
+
+        Ok((amount_asset, current_price))
+    }
+
+    /// Taker commission on a market buy of `amount_usd`, at the fee tier of the user's
+    /// Binance connection. The fee comes out of the amount, so less of the asset is bought.
+    async fn estimate_buy_fee(&self, strategy: &DCAStrategy, amount_usd: Decimal) -> Result<Decimal, AppError> {
+        let rates = fee_rates_for(self.db.as_ref(), strategy.user_id, &Exchange::Binance).await?;
+        Ok(rates.fee(amount_usd, false))
+    }
+
+    /// Record execution in database and tell the user's event streams about it
     async fn record_execution(
         &self,
         strategy_id: Uuid,
+        user_id: Uuid,
         execution_type: ExecutionType,
         trigger_reason: TriggerReason,
         amount_usd: Decimal,
@@ -546,6 +898,7 @@ impl DCAExecutionEngine {
         fear_greed_index: Option<i32>,
         volatility: Option<Decimal>,
         error_message: Option<String>,
+        mode: &StrategyMode,
     ) -> Result<(), AppError> {
         let execution = ExecutionActiveModel {
             id: Set(Uuid::new_v4()),
@@ -562,13 +915,61 @@ impl DCAExecutionEngine {
             order_status: Set("filled".to_string()),
             execution_timestamp: Set(Utc::now()),
             error_message: Set(error_message),
+            mode: Set(mode.as_str().to_string()),
             created_at: Set(Utc::now()),
         };
 
-        execution.insert(self.db.as_ref())
+        let execution = execution.insert(self.db.as_ref())
             .await
             .map_err(AppError::DatabaseError)?;
 
+        self.publish_event(ExecutionEvent::ExecutionRecorded {
+            user_id,
+            execution: execution.into(),
+        });
+        Ok(())
+    }
+
+    /// Record a purchase made at `executed_at` and schedule the next one from the
+    /// strategy's interval
+    async fn schedule_next_purchase(&self, strategy: &DCAStrategy, executed_at: DateTime<Utc>) -> Result<(), AppError> {
+        let config = strategy.get_dca_config().map_err(AppError::BadRequest)?;
+        let changes = DCAStrategyActiveModel {
+            last_execution_at: Set(Some(executed_at)),
+            next_execution_at: Set(Some(config.next_purchase_after(executed_at))),
+            updated_at: Set(executed_at),
+            ..Default::default()
+        };
+
+        let updated = update_versioned(
+            self.db.as_ref(),
+            changes,
+            strategy.id,
+            crate::models::dca_strategy::Column::Id,
+            crate::models::dca_strategy::Column::Version,
+            None,
+        ).await?;
+        self.strategy_cache.write().await.insert(updated.id, updated);
+        Ok(())
+    }
+
+    /// Save the strategy framework's state, held-back buys included, so a restart or an
+    /// export carries on from it
+    async fn save_strategy_state(&self, strategy: &DCAStrategy, framework: &StrategyFrameworkDCA) -> Result<(), AppError> {
+        let changes = DCAStrategyActiveModel {
+            state_json: Set(Some(framework.get_state()?.to_string())),
+            ..Default::default()
+        };
+
+        let updated = update_versioned(
+            self.db.as_ref(),
+            changes,
+            strategy.id,
+            crate::models::dca_strategy::Column::Id,
+            crate::models::dca_strategy::Column::Version,
+            None,
+        ).await?;
+        self.strategy_cache.write().await.insert(updated.id, updated);
         Ok(())
     }
 
@@ -595,9 +996,10 @@ impl DCAExecutionEngine {
         }
         drop(cache);
 
-        // Load from database
+        // Load from database; kill-switched strategies never run, whatever their status
         let strategies = DCAStrategyEntity::find()
             .filter(crate::models::dca_strategy::Column::Status.eq("active"))
+            .filter(crate::models::dca_strategy::Column::KillSwitch.eq(false))
             .all(self.db.as_ref())
             .await
             .map_err(AppError::DatabaseError)?;
@@ -719,29 +1121,68 @@ impl DCAExecutionEngine {
 
     /// Get execution statistics
     pub async fn get_execution_stats(&self) -> ExecutionStats {
-        self.execution_stats.read().await.clone()
+        let mut stats = self.execution_stats.read().await.clone();
+        stats.queue_depth = self.scheduler.queue_depth().await;
+        stats.running_executions = self.scheduler.running();
+        stats.max_concurrent_executions = self.scheduler.max_concurrent();
+        stats
     }
 
     /// Manually queue a strategy for execution
     pub async fn queue_manual_execution(
         &self,
         strategy_id: Uuid,
+        user_id: Uuid,
         amount_usd: Option<Decimal>,
     ) -> Result<(), AppError> {
         let request = ExecutionRequest {
             strategy_id,
+            user_id,
             trigger_reason: TriggerReason::Manual,
             manual_amount: amount_usd,
             created_at: Utc::now(),
         };
 
-        let mut queue = self.execution_queue.lock().await;
-        queue.push(request);
+        if !self.scheduler.enqueue(request).await {
+            return Err(AppError::Conflict(format!(
+                "Strategy {} is already queued or executing",
+                strategy_id
+            )));
+        }
 
         info!("Manually queued strategy {} for execution", strategy_id);
         Ok(())
     }
 
+    /// Drop a strategy from the cache and the execution queue, so the next scan reads it
+    /// from the database again
+    pub async fn forget_strategy(&self, strategy_id: Uuid) {
+        self.strategy_cache.write().await.remove(&strategy_id);
+        self.scheduler.cancel(strategy_id).await;
+    }
+
+    /// Drop a strategy whose status changed from the cache, so the next scan sees the new
+    /// status, and tell the user's event streams about it
+    pub async fn strategy_status_changed(&self, user_id: Uuid, strategy_id: Uuid, status: &str) {
+        self.forget_strategy(strategy_id).await;
+        self.publish_event(ExecutionEvent::StatusChanged {
+            user_id,
+            strategy_id,
+            status: status.to_string(),
+        });
+    }
+
+    /// Receive every execution and status change event from now on. Events cover all
+    /// users; check `ExecutionEvent::user_id` before passing one on.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ExecutionEvent> {
+        self.events_tx.subscribe()
+    }
+
+    fn publish_event(&self, event: ExecutionEvent) {
+        // Sending only fails when nobody is listening, which is fine
+        let _ = self.events_tx.send(event);
+    }
+
     /// Initiate graceful shutdown of all background loops  
     #[allow(dead_code)]
     pub async fn shutdown(&self) -> Result<(), AppError> {
@@ -765,3 +1206,313 @@ impl DCAExecutionEngine {
     }
 }
 
+/// Earliest time any of the given strategies becomes due. A strategy with no
+/// scheduled time (never run) is due now.
+fn earliest_due(next_executions: Vec<Option<DateTime<Utc>>>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    next_executions
+        .into_iter()
+        .map(|next| next.unwrap_or(now))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::Duration as StdDuration;
+
+    fn schedule() -> EngineSchedule {
+        EngineSchedule {
+            poll_interval: StdDuration::from_secs(30),
+            max_idle: StdDuration::from_secs(3600),
+            jitter: StdDuration::from_secs(5),
+        }
+    }
+
+    #[test]
+    fn test_sleeps_until_next_due_strategy() {
+        let now = Utc::now();
+        let next_due = earliest_due(
+            vec![Some(now + Duration::minutes(45)), Some(now + Duration::minutes(10))],
+            now,
+        );
+
+        let delay = schedule().next_delay(now, next_due, StdDuration::ZERO);
+        assert_eq!(delay, StdDuration::from_secs(600));
+    }
+
+    #[test]
+    fn test_overdue_strategy_does_not_busy_loop() {
+        let now = Utc::now();
+        let delay = schedule().next_delay(now, Some(now - Duration::minutes(5)), StdDuration::ZERO);
+        assert_eq!(delay, StdDuration::from_secs(30));
+
+        // A never-run strategy counts as due now, and still waits the minimum interval
+        let delay = schedule().next_delay(now, earliest_due(vec![None], now), StdDuration::ZERO);
+        assert_eq!(delay, StdDuration::from_secs(30));
+    }
+
+    #[test]
+    fn test_idle_engine_sleeps_max_idle() {
+        let now = Utc::now();
+        assert_eq!(earliest_due(vec![], now), None);
+        assert_eq!(schedule().next_delay(now, None, StdDuration::ZERO), StdDuration::from_secs(3600));
+        assert_eq!(
+            schedule().next_delay(now, Some(now + Duration::days(2)), StdDuration::ZERO),
+            StdDuration::from_secs(3600)
+        );
+    }
+
+    #[test]
+    fn test_jitter_is_added_and_bounded() {
+        let now = Utc::now();
+        let schedule = schedule();
+        let delay = schedule.next_delay(now, None, StdDuration::from_secs(60));
+        assert_eq!(delay, StdDuration::from_secs(3605));
+
+        for _ in 0..50 {
+            assert!(schedule.random_jitter() <= schedule.jitter);
+        }
+    }
+    #[tokio::test]
+    async fn test_kill_switched_strategy_is_skipped_on_startup() {
+        use crate::database::test_support::{seed_dca_strategy, seed_user, test_db};
+        use crate::handlers::kill_switch::update_kill_switch;
+
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let running = seed_dca_strategy(&db, user_id, "BTC").await;
+        let killed = seed_dca_strategy(&db, user_id, "ETH").await;
+        // Still "active": the kill switch alone keeps it from running
+        update_kill_switch(&db, user_id, killed, true).await.unwrap();
+
+        let engine = DCAExecutionEngine::new(Arc::new(db), MarketDataService::new(), EncryptionService::new());
+
+        let strategies = engine.get_active_strategies().await.unwrap();
+        assert_eq!(strategies.iter().map(|s| s.id).collect::<Vec<_>>(), vec![running]);
+
+        let killed_strategy = engine.get_strategy_from_cache(killed).await.unwrap();
+        assert!(engine.should_strategy_execute(&killed_strategy).await.unwrap().is_none());
+
+        let result = engine
+            .execute_strategy_request(ExecutionRequest {
+                strategy_id: killed,
+                user_id,
+                trigger_reason: TriggerReason::Manual,
+                manual_amount: None,
+                created_at: Utc::now(),
+            })
+            .await;
+        assert!(!result.success);
+        assert_eq!(result.error_message.as_deref(), Some("Strategy kill switch is set"));
+    }
+
+    #[tokio::test]
+    async fn test_purchase_schedules_the_next_one_from_the_interval() {
+        use crate::database::test_support::{seed_dca_strategy, seed_user, test_db};
+        use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency, DCAInterval};
+
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let strategy_id = seed_dca_strategy(&db, user_id, "BTC").await;
+        let config = DCAConfig {
+            interval: Some(DCAInterval::Monthly { day_of_month: 31 }),
+            ..DCAConfig::simple(Decimal::from(100), DCAFrequency::Monthly(1))
+        };
+        let strategy = DCAStrategyActiveModel {
+            id: Set(strategy_id),
+            config_json: Set(serde_json::to_string(&config).unwrap()),
+            ..Default::default()
+        }
+        .update(&db)
+        .await
+        .unwrap();
+
+        let engine = DCAExecutionEngine::new(Arc::new(db), MarketDataService::new(), EncryptionService::new());
+        let executed_at = Utc.with_ymd_and_hms(2024, 1, 31, 9, 0, 0).unwrap();
+        engine.schedule_next_purchase(&strategy, executed_at).await.unwrap();
+
+        let scheduled = engine.get_strategy_from_cache(strategy_id).await.unwrap();
+        assert_eq!(scheduled.last_execution_at, Some(executed_at));
+        assert_eq!(scheduled.next_execution_at, Some(Utc.with_ymd_and_hms(2024, 2, 29, 9, 0, 0).unwrap()));
+        assert_eq!(scheduled.version, strategy.version + 1);
+    }
+
+    #[tokio::test]
+    async fn test_held_back_buys_survive_an_engine_restart() {
+        use crate::database::test_support::{seed_dca_strategy, seed_user, test_db};
+        use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency};
+
+        let db = Arc::new(test_db().await);
+        let user_id = seed_user(&db).await;
+        let strategy_id = seed_dca_strategy(&db, user_id, "BTC").await;
+        let config = serde_json::to_string(&DCAConfig::simple(Decimal::from(5), DCAFrequency::Daily(1))).unwrap();
+        let strategy = DCAStrategyActiveModel {
+            id: Set(strategy_id),
+            config_json: Set(config),
+            state_json: Set(Some(r#"{"total_invested":"40"}"#.to_string())),
+            ..Default::default()
+        }
+        .update(db.as_ref())
+        .await
+        .unwrap();
+
+        let engine = DCAExecutionEngine::new(db.clone(), MarketDataService::new(), EncryptionService::new());
+        let mut framework = strategy.to_restored_strategy_framework(Vec::new()).await.unwrap();
+        let mut pending = framework.pending_buys().clone();
+        assert_eq!(pending.accumulate(Decimal::from(4), Decimal::from(10)), None);
+        framework.set_pending_buys(pending.clone());
+        engine.save_strategy_state(&strategy, &framework).await.unwrap();
+
+        let restarted = DCAExecutionEngine::new(db, MarketDataService::new(), EncryptionService::new());
+        let reloaded = restarted.get_strategy_from_cache(strategy_id).await.unwrap();
+        let restored = reloaded.to_restored_strategy_framework(Vec::new()).await.unwrap();
+        assert_eq!(restored.pending_buys(), &pending);
+        let state: serde_json::Value = serde_json::from_str(reloaded.state_json.as_deref().unwrap()).unwrap();
+        assert_eq!(state["total_invested"], "40");
+    }
+
+    #[tokio::test]
+    async fn test_paper_strategy_fills_without_touching_an_exchange() {
+        use crate::database::test_support::{seed_dca_strategy, seed_user, test_db};
+        use crate::models::dca_strategy::execution;
+        use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency};
+
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let paper_id = seed_dca_strategy(&db, user_id, "BTC").await;
+        let live_id = seed_dca_strategy(&db, user_id, "ETH").await;
+        let config = serde_json::to_string(&DCAConfig::simple(Decimal::from(100), DCAFrequency::Daily(1))).unwrap();
+        for (id, mode) in [(paper_id, StrategyMode::Paper), (live_id, StrategyMode::Live)] {
+            DCAStrategyActiveModel {
+                id: Set(id),
+                config_json: Set(config.clone()),
+                mode: Set(mode.as_str().to_string()),
+                ..Default::default()
+            }
+            .update(&db)
+            .await
+            .unwrap();
+        }
+
+        let db = Arc::new(db);
+        let engine = DCAExecutionEngine::new(db.clone(), MarketDataService::new(), EncryptionService::new());
+        for symbol in ["BTC", "ETH"] {
+            engine.market_data_cache.write().await.insert(symbol.to_string(), MarketDataModel {
+                id: Uuid::new_v4(),
+                asset_symbol: symbol.to_string(),
+                price: Decimal::from(50_000),
+                volume_24h: None,
+                market_cap: None,
+                fear_greed_index: Some(40),
+                volatility_7d: None,
+                volatility_30d: None,
+                rsi_14: None,
+                ema_20: None,
+                ema_50: None,
+                ema_200: None,
+                support_level: None,
+                resistance_level: None,
+                trend_direction: None,
+                timestamp: Utc::now(),
+                created_at: Utc::now(),
+            });
+        }
+        let request = |strategy_id| ExecutionRequest {
+            strategy_id,
+            user_id,
+            trigger_reason: TriggerReason::Manual,
+            manual_amount: Some(Decimal::from(100)),
+            created_at: Utc::now(),
+        };
+
+        let mut events = engine.subscribe_events();
+
+        // The user has no exchange connection, so only a path that never looks for one can fill
+        let live = engine.execute_strategy_request(request(live_id)).await;
+        assert!(!live.success);
+        assert_eq!(live.error_message.as_deref(), Some("Bad request: No active exchange connection found"));
+
+        let paper = engine.execute_strategy_request(request(paper_id)).await;
+        assert!(paper.success, "{:?}", paper.error_message);
+        assert_eq!(paper.price, Some(Decimal::from(50_000)));
+        // $100 at the base tier's 0.1% taker fee buys $99.90 of BTC
+        assert_eq!(paper.amount_asset, Some(Decimal::new(1998, 6)));
+
+        // Both executions, the failed one too, reach the user's event streams
+        let recorded: Vec<ExecutionEvent> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(recorded.len(), 2);
+        assert!(recorded.iter().all(|event| event.user_id() == user_id));
+        match &recorded[1] {
+            ExecutionEvent::ExecutionRecorded { execution, .. } => {
+                assert_eq!(execution.strategy_id, paper_id);
+                assert_eq!(execution.mode, "paper");
+            }
+            other => panic!("expected a recorded execution, got {:?}", other),
+        }
+
+        let executions = execution::Entity::find()
+            .filter(execution::Column::StrategyId.eq(paper_id))
+            .all(db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].mode, "paper");
+        assert_eq!(executions[0].amount_asset, Some(Decimal::new(1998, 6)));
+
+        let portfolio = PaperTradingService::new(db.clone()).portfolio(user_id, paper_id).await.unwrap();
+        assert_eq!(portfolio.trades.len(), 1);
+        assert_eq!(portfolio.portfolio.cash_balance, Decimal::from(9_900));
+        assert_eq!(portfolio.portfolio.asset_quantity, Decimal::new(1998, 6));
+
+        // The fill is saved with the strategy's state, which is what an export carries
+        crate::strategies::init_all_strategies().unwrap();
+        let export = crate::handlers::strategy_export::build_export(db.as_ref(), user_id, paper_id).await.unwrap();
+        let state = export.state.unwrap();
+        assert_eq!(state["purchase_count"], 1);
+        let total_quantity: Decimal = state["total_quantity"].as_str().unwrap().parse().unwrap();
+        assert_eq!(total_quantity, Decimal::new(1998, 6));
+    }
+
+    #[tokio::test]
+    async fn test_buys_are_held_to_the_tighter_notional_limit() {
+        use crate::database::test_support::{seed_dca_strategy, seed_user, seed_user_profile, test_db};
+        use crate::models::user_profile;
+        use crate::strategies::implementations::dca::{DCAConfig, DCAFrequency};
+
+        let db = Arc::new(test_db().await);
+        let user_id = seed_user(&db).await;
+        let strategy_id = seed_dca_strategy(&db, user_id, "BTC").await;
+        let mut config = DCAConfig::simple(Decimal::from(100), DCAFrequency::Daily(1));
+        config.max_order_notional = Some(Decimal::from(250));
+        config.clamp_oversized_orders = true;
+        let strategy = DCAStrategyActiveModel {
+            id: Set(strategy_id),
+            config_json: Set(serde_json::to_string(&config).unwrap()),
+            ..Default::default()
+        }
+        .update(db.as_ref())
+        .await
+        .unwrap();
+        let engine = DCAExecutionEngine::new(db.clone(), MarketDataService::new(), EncryptionService::new());
+
+        assert_eq!(engine.apply_notional_limit(&strategy, Decimal::from(100)).await.unwrap(), Decimal::from(100));
+        assert_eq!(engine.apply_notional_limit(&strategy, Decimal::from(400)).await.unwrap(), Decimal::from(250));
+
+        // A tighter profile limit that rejects wins over the strategy's
+        let profile = seed_user_profile(&db, user_id).await;
+        user_profile::ActiveModel {
+            id: Set(profile.id),
+            max_order_notional: Set(Some(Decimal::from(200))),
+            clamp_oversized_orders: Set(false),
+            ..Default::default()
+        }
+        .update(db.as_ref())
+        .await
+        .unwrap();
+        assert!(matches!(
+            engine.apply_notional_limit(&strategy, Decimal::from(400)).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
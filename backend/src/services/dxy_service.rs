@@ -7,6 +7,8 @@ use tracing::{error, warn, debug, info};
 use rust_decimal::Decimal;
 
 use crate::utils::errors::AppError;
+use crate::utils::http::{request_with_policy, RetryPolicy};
+use crate::exchange_connectors::numeric::decimal_from_f64;
 
 /// Yahoo Finance API response for DXY quote
 #[derive(Debug, Deserialize)]
@@ -97,6 +99,7 @@ impl RateLimiter {
 pub struct DxyService {
     client: Client,
     rate_limiter: RateLimiter,
+    retry_policy: RetryPolicy,
     cached_data: Arc<RwLock<Option<(DxyData, Instant)>>>,
     cache_duration: Duration,
 }
@@ -110,6 +113,12 @@ impl DxyService {
                 .build()
                 .expect("Failed to create HTTP client"),
             rate_limiter: RateLimiter::new(2000), // 2 seconds between calls
+            // DXY is display-only market data; fail fast rather than hold the request up
+            retry_policy: RetryPolicy {
+                max_attempts: 2,
+                deadline: Some(Duration::from_secs(10)),
+                ..RetryPolicy::default()
+            },
             cached_data: Arc::new(RwLock::new(None)),
             cache_duration: Duration::from_secs(300), // Cache for 5 minutes
         }
@@ -151,10 +160,11 @@ impl DxyService {
 
         debug!("Fetching DXY data from Yahoo Finance API");
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-            .send()
+            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36");
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch DXY data from Yahoo Finance: {}", e);
@@ -191,7 +201,7 @@ impl DxyService {
 
         let previous_close = result.meta.chart_previous_close.unwrap_or(current_price);
 
-        let value = Decimal::try_from(current_price)
+        let value = decimal_from_f64(current_price)
             .map_err(|e| {
                 error!("Failed to convert DXY value to Decimal: {}", e);
                 AppError::InternalServerError
@@ -199,7 +209,7 @@ impl DxyService {
 
         // Calculate change
         let change_val = current_price - previous_close;
-        let change = Decimal::try_from(change_val).ok();
+        let change = decimal_from_f64(change_val).ok();
 
         // Calculate percent change
         let percent_change_val = if previous_close != 0.0 {
@@ -207,18 +217,18 @@ impl DxyService {
         } else {
             0.0
         };
-        let percent_change = Decimal::try_from(percent_change_val).ok();
+        let percent_change = decimal_from_f64(percent_change_val).ok();
 
         // Get high/low from quote data
         let quote = result.indicators.quote.first();
         let (high_24h, low_24h) = if let Some(q) = quote {
             let high = q.high.as_ref()
                 .and_then(|h| h.iter().filter_map(|v| *v).max_by(|a, b| a.partial_cmp(b).unwrap()))
-                .and_then(|v| Decimal::try_from(v).ok());
+                .and_then(|v| decimal_from_f64(v).ok());
 
             let low = q.low.as_ref()
                 .and_then(|l| l.iter().filter_map(|v| *v).min_by(|a, b| a.partial_cmp(b).unwrap()))
-                .and_then(|v| Decimal::try_from(v).ok());
+                .and_then(|v| decimal_from_f64(v).ok());
 
             (high, low)
         } else {
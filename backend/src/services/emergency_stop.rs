@@ -0,0 +1,677 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::exchange_connectors::common_types::{OrderSide, WalletType};
+use crate::exchange_connectors::traits::{MarketDataAPI, TradeExecutionAPI};
+use crate::models::{dca_strategy, grid_trading_strategy, sma_crossover_strategy};
+use crate::services::order_execution::notional_limit_for;
+use crate::services::symbol_access::trading_pair;
+use crate::strategies::core::{NotionalLimit, StrategyMode};
+use crate::strategies::implementations::dca::DCAState;
+use crate::utils::errors::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    Dca,
+    SmaCrossover,
+    GridTrading,
+}
+
+impl StrategyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StrategyKind::Dca => "dca",
+            StrategyKind::SmaCrossover => "sma_crossover",
+            StrategyKind::GridTrading => "grid_trading",
+        }
+    }
+}
+
+/// An active strategy and the position it holds
+#[derive(Debug, Clone)]
+pub struct LiveStrategy {
+    pub id: Uuid,
+    pub kind: StrategyKind,
+    pub name: String,
+    pub asset_symbol: String,
+    /// Signed base-asset position: positive is long, negative is short
+    pub open_quantity: Decimal,
+    /// Connection used by the strategy's most recent execution
+    pub exchange_connection_id: Option<Uuid>,
+    /// Largest closing order the user's and the strategy's limits allow
+    pub notional_limit: Option<NotionalLimit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlattenStatus {
+    Flattened,
+    /// The notional limit clamped the closing order; part of the position is still open
+    PartiallyFlattened,
+    NoPosition,
+    Failed,
+}
+
+/// Outcome of the emergency stop for one strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyStopResult {
+    pub strategy_id: Uuid,
+    pub strategy_type: StrategyKind,
+    pub name: String,
+    pub paused: bool,
+    pub status: FlattenStatus,
+    pub symbol: Option<String>,
+    pub side: Option<OrderSide>,
+    #[serde(with = "crate::utils::precision::crypto_option")]
+    pub quantity: Option<Decimal>,
+    pub order_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyStopReport {
+    pub paused: usize,
+    pub flattened: usize,
+    pub failed: usize,
+    pub results: Vec<EmergencyStopResult>,
+}
+
+/// Pauses every live strategy of a user and closes their open positions with market orders
+pub struct EmergencyStopService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl EmergencyStopService {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// All of the user's active strategies across strategy types
+    pub async fn live_strategies(&self, user_id: Uuid) -> Result<Vec<LiveStrategy>, AppError> {
+        let db = self.db.as_ref();
+        let mut strategies = Vec::new();
+
+        let dca = dca_strategy::Entity::find()
+            .filter(dca_strategy::Column::UserId.eq(user_id))
+            .filter(dca_strategy::Column::Status.eq("active"))
+            .all(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        for strategy in dca {
+            let exchange_connection_id = dca_strategy::execution::Entity::find()
+                .filter(dca_strategy::execution::Column::StrategyId.eq(strategy.id))
+                .order_by_desc(dca_strategy::execution::Column::ExecutionTimestamp)
+                .one(db)
+                .await
+                .map_err(AppError::DatabaseError)?
+                .map(|execution| execution.exchange_connection_id);
+
+            let notional_limit = notional_limit_for(db, user_id, &parameters_of(&strategy.config_json)).await?;
+            strategies.push(LiveStrategy {
+                id: strategy.id,
+                kind: StrategyKind::Dca,
+                // A paper strategy holds nothing on the exchange to close
+                open_quantity: match strategy.execution_mode() {
+                    StrategyMode::Paper => Decimal::ZERO,
+                    _ => dca_open_quantity(&strategy),
+                },
+                name: strategy.name,
+                asset_symbol: strategy.asset_symbol,
+                exchange_connection_id,
+                notional_limit,
+            });
+        }
+
+        let sma = sma_crossover_strategy::Entity::find()
+            .filter(sma_crossover_strategy::Column::UserId.eq(user_id))
+            .filter(sma_crossover_strategy::Column::Status.eq("active"))
+            .all(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        for strategy in sma {
+            let latest = sma_crossover_strategy::execution::Entity::find()
+                .filter(sma_crossover_strategy::execution::Column::StrategyId.eq(strategy.id))
+                .order_by_desc(sma_crossover_strategy::execution::Column::ExecutionTimestamp)
+                .one(db)
+                .await
+                .map_err(AppError::DatabaseError)?;
+            // A crossover strategy holds at most one position, opened by its latest execution
+            let entry_quantity = latest.as_ref().and_then(|execution| execution.amount_asset).unwrap_or_default();
+
+            let notional_limit = notional_limit_for(db, user_id, &parameters_of(&strategy.config_json)).await?;
+            strategies.push(LiveStrategy {
+                id: strategy.id,
+                kind: StrategyKind::SmaCrossover,
+                name: strategy.name,
+                asset_symbol: strategy.asset_symbol,
+                open_quantity: entry_quantity * Decimal::from(strategy.current_position.signum()),
+                exchange_connection_id: latest.map(|execution| execution.exchange_connection_id),
+                notional_limit,
+            });
+        }
+
+        let grid = grid_trading_strategy::Entity::find()
+            .filter(grid_trading_strategy::Column::UserId.eq(user_id))
+            .filter(grid_trading_strategy::Column::Status.eq("active"))
+            .all(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        for strategy in grid {
+            let exchange_connection_id = grid_trading_strategy::execution::Entity::find()
+                .filter(grid_trading_strategy::execution::Column::StrategyId.eq(strategy.id))
+                .order_by_desc(grid_trading_strategy::execution::Column::ExecutionTimestamp)
+                .one(db)
+                .await
+                .map_err(AppError::DatabaseError)?
+                .map(|execution| execution.exchange_connection_id);
+
+            let notional_limit = notional_limit_for(db, user_id, &parameters_of(&strategy.config_json)).await?;
+            strategies.push(LiveStrategy {
+                id: strategy.id,
+                kind: StrategyKind::GridTrading,
+                name: strategy.name,
+                asset_symbol: strategy.asset_symbol,
+                open_quantity: strategy.current_inventory,
+                exchange_connection_id,
+                notional_limit,
+            });
+        }
+
+        Ok(strategies)
+    }
+
+    /// Pause the given strategies in one transaction, so none keeps trading while others stop
+    pub async fn pause(&self, strategies: &[LiveStrategy]) -> Result<(), AppError> {
+        let ids_of = |kind: StrategyKind| -> Vec<Uuid> {
+            strategies.iter().filter(|s| s.kind == kind).map(|s| s.id).collect()
+        };
+        let now = Utc::now();
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        dca_strategy::Entity::update_many()
+            .col_expr(dca_strategy::Column::Status, Expr::value("paused"))
+            .col_expr(dca_strategy::Column::UpdatedAt, Expr::value(now))
+            .filter(dca_strategy::Column::Id.is_in(ids_of(StrategyKind::Dca)))
+            .exec(&txn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        sma_crossover_strategy::Entity::update_many()
+            .col_expr(sma_crossover_strategy::Column::Status, Expr::value("paused"))
+            .col_expr(sma_crossover_strategy::Column::UpdatedAt, Expr::value(now))
+            .filter(sma_crossover_strategy::Column::Id.is_in(ids_of(StrategyKind::SmaCrossover)))
+            .exec(&txn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        grid_trading_strategy::Entity::update_many()
+            .col_expr(grid_trading_strategy::Column::Status, Expr::value("paused"))
+            .col_expr(grid_trading_strategy::Column::UpdatedAt, Expr::value(now))
+            .filter(grid_trading_strategy::Column::Id.is_in(ids_of(StrategyKind::GridTrading)))
+            .exec(&txn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+
+        info!("Emergency stop paused {} strategies", strategies.len());
+        Ok(())
+    }
+
+    /// Submit a market order closing each strategy's position on its exchange connection,
+    /// within the strategy's notional limit. `connectors` maps connection ids to a
+    /// connector, or to why one couldn't be built. Every strategy gets a result; one
+    /// failure doesn't stop the others.
+    pub async fn flatten<C>(
+        &self,
+        strategies: Vec<LiveStrategy>,
+        connectors: &HashMap<Uuid, Result<Arc<C>, String>>,
+    ) -> EmergencyStopReport
+    where
+        C: TradeExecutionAPI + MarketDataAPI + ?Sized,
+    {
+        let paused = strategies.len();
+        let mut results = Vec::with_capacity(strategies.len());
+
+        for strategy in strategies {
+            let mut result = EmergencyStopResult {
+                strategy_id: strategy.id,
+                strategy_type: strategy.kind,
+                name: strategy.name.clone(),
+                paused: true,
+                status: FlattenStatus::NoPosition,
+                symbol: None,
+                side: None,
+                quantity: None,
+                order_id: None,
+                error: None,
+            };
+
+            if strategy.open_quantity.is_zero() {
+                results.push(result);
+                continue;
+            }
+
+            let symbol = trading_pair(&strategy.asset_symbol);
+            // Close a long by selling, a short by buying
+            let side = if strategy.open_quantity > Decimal::ZERO { OrderSide::Sell } else { OrderSide::Buy };
+            let quantity = strategy.open_quantity.abs();
+            result.symbol = Some(symbol.clone());
+            result.side = Some(side.clone());
+            result.quantity = Some(quantity);
+
+            let connector = match strategy.exchange_connection_id.and_then(|id| connectors.get(&id)) {
+                Some(Ok(connector)) => connector,
+                Some(Err(e)) => {
+                    result.status = FlattenStatus::Failed;
+                    result.error = Some(e.clone());
+                    results.push(result);
+                    continue;
+                }
+                None => {
+                    result.status = FlattenStatus::Failed;
+                    result.error = Some("No exchange connection available for this strategy".to_string());
+                    results.push(result);
+                    continue;
+                }
+            };
+
+            let quantity = match strategy.notional_limit {
+                Some(limit) => match self.limit_quantity(&**connector, &symbol, quantity, limit).await {
+                    Ok(allowed) => allowed,
+                    Err(e) => {
+                        warn!("Emergency flatten of strategy {} blocked: {}", strategy.id, e);
+                        result.status = FlattenStatus::Failed;
+                        result.error = Some(e);
+                        results.push(result);
+                        continue;
+                    }
+                },
+                None => quantity,
+            };
+            result.quantity = Some(quantity);
+
+            match connector.place_market_order(&symbol, side, Some(quantity), None, WalletType::Spot).await {
+                Ok(order) => {
+                    result.status = if quantity < strategy.open_quantity.abs() {
+                        FlattenStatus::PartiallyFlattened
+                    } else {
+                        FlattenStatus::Flattened
+                    };
+                    result.order_id = Some(order.order_id);
+                    if let Err(e) = self.reduce_position(&strategy, quantity).await {
+                        warn!("Flattened strategy {} but could not update its position: {:?}", strategy.id, e);
+                    }
+                }
+                Err(e) => {
+                    error!("Emergency flatten failed for strategy {}: {}", strategy.id, e);
+                    result.status = FlattenStatus::Failed;
+                    result.error = Some(e.to_string());
+                }
+            }
+
+            results.push(result);
+        }
+
+        EmergencyStopReport {
+            paused,
+            flattened: results
+                .iter()
+                .filter(|r| matches!(r.status, FlattenStatus::Flattened | FlattenStatus::PartiallyFlattened))
+                .count(),
+            failed: results.iter().filter(|r| r.status == FlattenStatus::Failed).count(),
+            results,
+        }
+    }
+
+    /// Closing quantity the notional limit allows at the current price
+    async fn limit_quantity<C>(&self, connector: &C, symbol: &str, quantity: Decimal, limit: NotionalLimit) -> Result<Decimal, String>
+    where
+        C: MarketDataAPI + ?Sized,
+    {
+        let ticker = connector
+            .get_ticker(symbol)
+            .await
+            .map_err(|e| format!("No price to check the notional limit against: {}", e))?;
+        let allowed = limit.apply(quantity, ticker.last_price)?;
+        if allowed < quantity {
+            warn!("Clamped emergency close on {} from {} to {} to stay within max notional {}",
+                  symbol, quantity, allowed, limit.max_order_notional);
+        }
+        Ok(allowed)
+    }
+
+    /// Take `closed` off a strategy's open position. Only the position is touched; the
+    /// strategy's purchase history stays as it was.
+    async fn reduce_position(&self, strategy: &LiveStrategy, closed: Decimal) -> Result<(), AppError> {
+        let db = self.db.as_ref();
+        let now = Utc::now();
+        let fully_closed = closed >= strategy.open_quantity.abs();
+
+        match strategy.kind {
+            StrategyKind::Dca => {
+                let Some(model) = dca_strategy::Entity::find_by_id(strategy.id)
+                    .one(db)
+                    .await
+                    .map_err(AppError::DatabaseError)?
+                else {
+                    return Ok(());
+                };
+                let mut state: DCAState = model
+                    .state_json
+                    .as_deref()
+                    .and_then(|json| serde_json::from_str(json).ok())
+                    .unwrap_or_else(|| DCAState { total_quantity: model.total_purchased, ..Default::default() });
+                // Same bookkeeping as a sale through the strategy: the average entry is kept
+                // for what is left
+                if fully_closed {
+                    state.total_quantity = Decimal::ZERO;
+                    state.total_invested = Decimal::ZERO;
+                    state.average_price = Decimal::ZERO;
+                } else {
+                    state.total_quantity -= closed;
+                    state.total_invested = state.average_price * state.total_quantity;
+                }
+                let state_json = serde_json::to_string(&state)?;
+
+                dca_strategy::Entity::update_many()
+                    .col_expr(dca_strategy::Column::StateJson, Expr::value(Some(state_json)))
+                    .col_expr(dca_strategy::Column::UpdatedAt, Expr::value(now))
+                    .filter(dca_strategy::Column::Id.eq(strategy.id))
+                    .exec(db)
+                    .await
+            }
+            // A partly closed crossover position is still open in the same direction
+            StrategyKind::SmaCrossover if !fully_closed => return Ok(()),
+            StrategyKind::SmaCrossover => sma_crossover_strategy::Entity::update_many()
+                .col_expr(sma_crossover_strategy::Column::CurrentPosition, Expr::value(0))
+                .col_expr(sma_crossover_strategy::Column::UpdatedAt, Expr::value(now))
+                .filter(sma_crossover_strategy::Column::Id.eq(strategy.id))
+                .exec(db)
+                .await,
+            StrategyKind::GridTrading => {
+                let remaining = strategy.open_quantity - closed * strategy.open_quantity.signum();
+                grid_trading_strategy::Entity::update_many()
+                    .col_expr(grid_trading_strategy::Column::CurrentInventory, Expr::value(remaining))
+                    .col_expr(grid_trading_strategy::Column::UpdatedAt, Expr::value(now))
+                    .filter(grid_trading_strategy::Column::Id.eq(strategy.id))
+                    .exec(db)
+                    .await
+            }
+        }
+        .map_err(AppError::DatabaseError)?;
+
+        Ok(())
+    }
+}
+
+/// A strategy's stored config as JSON, for reading its order limits
+fn parameters_of(config_json: &str) -> serde_json::Value {
+    serde_json::from_str(config_json).unwrap_or(serde_json::Value::Null)
+}
+
+/// What a DCA strategy still holds: the position its saved framework state tracks, or
+/// everything it bought when it has no saved state yet
+fn dca_open_quantity(strategy: &dca_strategy::Model) -> Decimal {
+    strategy
+        .state_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<DCAState>(json).ok())
+        .map(|state| state.total_quantity)
+        .unwrap_or(strategy.total_purchased)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use crate::database::test_support::{
+        seed_dca_execution, seed_dca_strategy, seed_exchange_connection, seed_user, test_db,
+    };
+    use crate::exchange_connectors::common_types::{OcoOrder, Order, OrderStatus, OrderType, TimeInForce};
+    use crate::exchange_connectors::traits::ExchangeConnector;
+    use crate::exchange_connectors::{
+        ExchangeError, ExchangeInfo, Kline, KlineInterval, OrderBook, SymbolInfo, Ticker, Trade,
+    };
+    use chrono::DateTime;
+
+    /// Records market orders; rejects everything when `fail` is set
+    struct MockConnector {
+        fail: bool,
+        orders: Mutex<Vec<(String, OrderSide, Decimal)>>,
+    }
+
+    impl MockConnector {
+        fn new(fail: bool) -> Self {
+            Self { fail, orders: Mutex::new(Vec::new()) }
+        }
+    }
+
+    #[async_trait]
+    impl ExchangeConnector for MockConnector {
+        async fn test_connection(&self) -> Result<bool, ExchangeError> {
+            Ok(true)
+        }
+    }
+
+    #[async_trait]
+    impl TradeExecutionAPI for MockConnector {
+        async fn place_market_order(
+            &self,
+            symbol: &str,
+            side: OrderSide,
+            quantity: Option<Decimal>,
+            _quote_quantity: Option<Decimal>,
+            wallet_type: WalletType,
+        ) -> Result<Order, ExchangeError> {
+            if self.fail {
+                return Err(ExchangeError::InsufficientBalance("simulated".to_string()));
+            }
+
+            let quantity = quantity.unwrap_or_default();
+            self.orders.lock().unwrap().push((symbol.to_string(), side.clone(), quantity));
+            Ok(Order {
+                order_id: "42".to_string(),
+                client_order_id: None,
+                symbol: symbol.to_string(),
+                side,
+                order_type: OrderType::Market,
+                status: OrderStatus::Filled,
+                time_in_force: TimeInForce::GTC,
+                price: None,
+                stop_price: None,
+                quantity,
+                executed_quantity: quantity,
+                cumulative_quote_quantity: Decimal::ZERO,
+                average_price: None,
+                fee: None,
+                fee_asset: None,
+                pnl: None,
+                created_time: Utc::now(),
+                updated_time: Utc::now(),
+                wallet_type,
+            })
+        }
+
+        async fn place_limit_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: TimeInForce, _: WalletType) -> Result<Order, ExchangeError> {
+            Err(ExchangeError::NotSupported("limit".to_string()))
+        }
+
+        async fn place_stop_loss_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<Order, ExchangeError> {
+            Err(ExchangeError::NotSupported("stop loss".to_string()))
+        }
+
+        async fn place_take_profit_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<Order, ExchangeError> {
+            Err(ExchangeError::NotSupported("take profit".to_string()))
+        }
+
+        async fn place_oco_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<OcoOrder, ExchangeError> {
+            Err(ExchangeError::NotSupported("oco".to_string()))
+        }
+    }
+
+    /// Every symbol trades at 100
+    #[async_trait]
+    impl MarketDataAPI for MockConnector {
+        async fn get_ticker(&self, symbol: &str) -> Result<Ticker, ExchangeError> {
+            let price = Decimal::from(100);
+            Ok(Ticker {
+                symbol: symbol.to_string(),
+                bid_price: price,
+                bid_quantity: Decimal::ONE,
+                ask_price: price,
+                ask_quantity: Decimal::ONE,
+                last_price: price,
+                price_change: Decimal::ZERO,
+                price_change_percent: Decimal::ZERO,
+                high_price: price,
+                low_price: price,
+                volume: Decimal::ZERO,
+                quote_volume: Decimal::ZERO,
+                open_time: Utc::now(),
+                close_time: Utc::now(),
+            })
+        }
+
+        async fn get_order_book(&self, _: &str, _: Option<u32>) -> Result<OrderBook, ExchangeError> {
+            Err(ExchangeError::NotSupported("order book".to_string()))
+        }
+
+        async fn get_recent_trades(&self, _: &str, _: Option<u32>) -> Result<Vec<Trade>, ExchangeError> {
+            Err(ExchangeError::NotSupported("trades".to_string()))
+        }
+
+        async fn get_klines(&self, _: &str, _: KlineInterval, _: Option<DateTime<Utc>>, _: Option<DateTime<Utc>>, _: Option<u32>) -> Result<Vec<Kline>, ExchangeError> {
+            Err(ExchangeError::NotSupported("klines".to_string()))
+        }
+
+        async fn get_exchange_info(&self) -> Result<ExchangeInfo, ExchangeError> {
+            Err(ExchangeError::NotSupported("exchange info".to_string()))
+        }
+
+        async fn get_symbol_info(&self, _: &str) -> Result<SymbolInfo, ExchangeError> {
+            Err(ExchangeError::NotSupported("symbol info".to_string()))
+        }
+    }
+
+    async fn set_position(db: &DatabaseConnection, strategy_id: Uuid, quantity: Decimal) {
+        dca_strategy::Entity::update_many()
+            .col_expr(dca_strategy::Column::TotalPurchased, Expr::value(quantity))
+            .filter(dca_strategy::Column::Id.eq(strategy_id))
+            .exec(db)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_emergency_stop_pauses_and_flattens_reporting_failures() {
+        let db = Arc::new(test_db().await);
+        let user_id = seed_user(&db).await;
+        let healthy = seed_exchange_connection(&db, user_id, "binance").await;
+        let broken = seed_exchange_connection(&db, user_id, "binance-sub").await;
+
+        let btc = seed_dca_strategy(&db, user_id, "BTC").await;
+        set_position(&db, btc, Decimal::new(5, 1)).await;
+        seed_dca_execution(&db, btc, healthy, "buy", Decimal::new(5, 1), Utc::now()).await;
+
+        let eth = seed_dca_strategy(&db, user_id, "ETH").await;
+        set_position(&db, eth, Decimal::from(2)).await;
+        seed_dca_execution(&db, eth, broken, "buy", Decimal::from(2), Utc::now()).await;
+
+        let sol = seed_dca_strategy(&db, user_id, "SOL").await;
+
+        let service = EmergencyStopService::new(db.clone());
+        let strategies = service.live_strategies(user_id).await.unwrap();
+        assert_eq!(strategies.len(), 3);
+
+        service.pause(&strategies).await.unwrap();
+
+        let healthy_connector = Arc::new(MockConnector::new(false));
+        let mut connectors = HashMap::new();
+        connectors.insert(healthy, Ok(healthy_connector.clone()));
+        connectors.insert(broken, Ok(Arc::new(MockConnector::new(true))));
+
+        let report = service.flatten(strategies, &connectors).await;
+
+        assert_eq!(report.paused, 3);
+        assert_eq!(report.flattened, 1);
+        assert_eq!(report.failed, 1);
+
+        let result_for = |id: Uuid| report.results.iter().find(|r| r.strategy_id == id).unwrap();
+        assert_eq!(result_for(btc).status, FlattenStatus::Flattened);
+        assert_eq!(result_for(btc).order_id.as_deref(), Some("42"));
+        assert_eq!(result_for(eth).status, FlattenStatus::Failed);
+        assert!(result_for(eth).error.is_some());
+        assert_eq!(result_for(sol).status, FlattenStatus::NoPosition);
+
+        assert_eq!(
+            *healthy_connector.orders.lock().unwrap(),
+            vec![("BTCUSDT".to_string(), OrderSide::Sell, Decimal::new(5, 1))]
+        );
+
+        let stored = dca_strategy::Entity::find()
+            .filter(dca_strategy::Column::UserId.eq(user_id))
+            .all(db.as_ref())
+            .await
+            .unwrap();
+        assert!(stored.iter().all(|s| s.status == "paused"));
+        let stored_strategy = |id: Uuid| stored.iter().find(|s| s.id == id).unwrap();
+        assert_eq!(dca_open_quantity(stored_strategy(btc)), Decimal::ZERO);
+        assert_eq!(dca_open_quantity(stored_strategy(eth)), Decimal::from(2));
+        // Closing the position leaves what the strategy bought on record
+        assert_eq!(stored_strategy(btc).total_purchased, Decimal::new(5, 1));
+    }
+
+    #[tokio::test]
+    async fn test_flatten_stays_within_the_notional_limit() {
+        use crate::database::test_support::seed_user_profile;
+        use crate::models::user_profile;
+        use sea_orm::{ActiveModelTrait, Set};
+
+        let db = Arc::new(test_db().await);
+        let user_id = seed_user(&db).await;
+        let connection = seed_exchange_connection(&db, user_id, "binance").await;
+        let profile = seed_user_profile(&db, user_id).await;
+        user_profile::ActiveModel {
+            id: Set(profile.id),
+            max_order_notional: Set(Some(Decimal::from(100))),
+            clamp_oversized_orders: Set(true),
+            ..Default::default()
+        }
+        .update(db.as_ref())
+        .await
+        .unwrap();
+
+        let btc = seed_dca_strategy(&db, user_id, "btc").await;
+        set_position(&db, btc, Decimal::from(3)).await;
+        seed_dca_execution(&db, btc, connection, "buy", Decimal::from(3), Utc::now()).await;
+
+        let service = EmergencyStopService::new(db.clone());
+        let strategies = service.live_strategies(user_id).await.unwrap();
+        let connector = Arc::new(MockConnector::new(false));
+        let connectors = HashMap::from([(connection, Ok(connector.clone()))]);
+
+        // $100 buys one unit at 100, so two stay open
+        let report = service.flatten(strategies, &connectors).await;
+        assert_eq!(report.results[0].status, FlattenStatus::PartiallyFlattened);
+        assert_eq!(
+            *connector.orders.lock().unwrap(),
+            vec![("BTCUSDT".to_string(), OrderSide::Sell, Decimal::ONE)]
+        );
+
+        let stored = dca_strategy::Entity::find_by_id(btc).one(db.as_ref()).await.unwrap().unwrap();
+        assert_eq!(dca_open_quantity(&stored), Decimal::from(2));
+    }
+}
@@ -0,0 +1,270 @@
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{Mutex, Semaphore};
+use uuid::Uuid;
+
+use crate::services::dca_execution_engine::ExecutionRequest;
+use crate::utils::metrics::metrics;
+
+/// Default cap on strategies executing at once
+const DEFAULT_MAX_CONCURRENT_EXECUTIONS: usize = 20;
+
+/// Caps how many strategies execute at once and hands queued executions out fairly.
+///
+/// Users take turns, and each user's executions run in the order they were queued, so
+/// a user with hundreds of live strategies cannot starve anyone else's, and no strategy
+/// waits behind an unbounded stream of newer work.
+#[derive(Clone)]
+pub struct ExecutionScheduler {
+    max_concurrent: usize,
+    permits: Arc<Semaphore>,
+    queue: Arc<Mutex<FairQueue>>,
+}
+
+#[derive(Default)]
+struct FairQueue {
+    /// Pending executions per user, oldest first
+    per_user: HashMap<Uuid, VecDeque<ExecutionRequest>>,
+    /// Users with pending executions, in the order they get their next turn
+    turns: VecDeque<Uuid>,
+    /// Strategies queued or executing; a strategy is never queued twice
+    active: HashSet<Uuid>,
+    depth: usize,
+}
+
+impl FairQueue {
+    fn push(&mut self, request: ExecutionRequest) -> bool {
+        if !self.active.insert(request.strategy_id) {
+            return false;
+        }
+
+        let pending = self.per_user.entry(request.user_id).or_default();
+        if pending.is_empty() {
+            self.turns.push_back(request.user_id);
+        }
+        pending.push_back(request);
+        self.depth += 1;
+        true
+    }
+
+    fn pop(&mut self) -> Option<ExecutionRequest> {
+        let user_id = self.turns.pop_front()?;
+        let pending = self.per_user.get_mut(&user_id)?;
+        let request = pending.pop_front()?;
+
+        if pending.is_empty() {
+            self.per_user.remove(&user_id);
+        } else {
+            self.turns.push_back(user_id);
+        }
+        self.depth -= 1;
+        Some(request)
+    }
+
+    fn remove(&mut self, strategy_id: Uuid) {
+        let mut removed = 0;
+        for pending in self.per_user.values_mut() {
+            let before = pending.len();
+            pending.retain(|request| request.strategy_id != strategy_id);
+            removed += before - pending.len();
+        }
+        if removed == 0 {
+            return;
+        }
+
+        self.per_user.retain(|_, pending| !pending.is_empty());
+        let per_user = &self.per_user;
+        self.turns.retain(|user_id| per_user.contains_key(user_id));
+        self.active.remove(&strategy_id);
+        self.depth -= removed;
+    }
+}
+
+impl ExecutionScheduler {
+    /// Scheduler running at most `max_concurrent` executions at once (at least one)
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            max_concurrent,
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            queue: Arc::new(Mutex::new(FairQueue::default())),
+        }
+    }
+
+    /// Read `EXECUTION_MAX_CONCURRENT`, defaulting to 20
+    pub fn from_env() -> Self {
+        let max_concurrent = std::env::var("EXECUTION_MAX_CONCURRENT")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_EXECUTIONS);
+        Self::new(max_concurrent)
+    }
+
+    /// Most executions allowed to run at once
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Queue an execution. Returns false, leaving the queue as is, when the strategy is
+    /// already queued or executing.
+    pub async fn enqueue(&self, request: ExecutionRequest) -> bool {
+        let mut queue = self.queue.lock().await;
+        let queued = queue.push(request);
+        metrics().execution_queue_depth.set(queue.depth as i64);
+        queued
+    }
+
+    /// Drop a strategy's queued execution, if any. An execution already running finishes.
+    pub async fn cancel(&self, strategy_id: Uuid) {
+        let mut queue = self.queue.lock().await;
+        queue.remove(strategy_id);
+        metrics().execution_queue_depth.set(queue.depth as i64);
+    }
+
+    /// Executions waiting for a free slot
+    pub async fn queue_depth(&self) -> usize {
+        self.queue.lock().await.depth
+    }
+
+    /// Executions currently running
+    pub fn running(&self) -> usize {
+        self.max_concurrent - self.permits.available_permits()
+    }
+
+    /// Run queued executions with `execute`, never more than `max_concurrent` at once,
+    /// until the queue is empty. Executions queued meanwhile are picked up too.
+    pub async fn run<F, Fut, T>(&self, execute: F) -> Vec<T>
+    where
+        F: Fn(ExecutionRequest) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let mut in_flight = FuturesUnordered::new();
+        let mut results = Vec::new();
+
+        loop {
+            // Start queued executions while slots are free
+            while let Ok(permit) = self.permits.clone().try_acquire_owned() {
+                let Some(request) = self.next().await else {
+                    break;
+                };
+                let strategy_id = request.strategy_id;
+                let execution = execute(request);
+                in_flight.push(async move {
+                    let result = execution.await;
+                    drop(permit);
+                    (strategy_id, result)
+                });
+            }
+            metrics().executions_in_flight.set(self.running() as i64);
+
+            let Some((strategy_id, result)) = in_flight.next().await else {
+                break;
+            };
+            self.queue.lock().await.active.remove(&strategy_id);
+            results.push(result);
+        }
+
+        metrics().executions_in_flight.set(self.running() as i64);
+        results
+    }
+
+    async fn next(&self) -> Option<ExecutionRequest> {
+        let mut queue = self.queue.lock().await;
+        let request = queue.pop();
+        metrics().execution_queue_depth.set(queue.depth as i64);
+        request
+    }
+}
+
+impl Default for ExecutionScheduler {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_EXECUTIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::dca_strategy::TriggerReason;
+    use chrono::Utc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex as StdMutex;
+    use std::time::Duration;
+
+    fn request(user_id: Uuid, strategy_id: Uuid) -> ExecutionRequest {
+        ExecutionRequest {
+            strategy_id,
+            user_id,
+            trigger_reason: TriggerReason::Scheduled,
+            manual_amount: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_limit_executions_run_at_once() {
+        let scheduler = ExecutionScheduler::new(2);
+        let user_id = Uuid::new_v4();
+        for _ in 0..6 {
+            assert!(scheduler.enqueue(request(user_id, Uuid::new_v4())).await);
+        }
+        assert_eq!(scheduler.queue_depth().await, 6);
+
+        let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let (running, peak) = (&running, &peak);
+        let results = scheduler
+            .run(move |request| async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                request.strategy_id
+            })
+            .await;
+
+        assert_eq!(results.len(), 6);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(scheduler.queue_depth().await, 0);
+        assert_eq!(scheduler.running(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_users_take_turns() {
+        let scheduler = ExecutionScheduler::new(1);
+        let busy_user = Uuid::new_v4();
+        let other_user = Uuid::new_v4();
+        let busy: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let other = Uuid::new_v4();
+
+        for strategy_id in &busy {
+            scheduler.enqueue(request(busy_user, *strategy_id)).await;
+        }
+        scheduler.enqueue(request(other_user, other)).await;
+
+        let order = StdMutex::new(Vec::new());
+        let order_ref = &order;
+        scheduler
+            .run(move |request| async move { order_ref.lock().unwrap().push(request.strategy_id) })
+            .await;
+
+        // The late user's only strategy runs second, not behind all four of the busy user's
+        assert_eq!(order.into_inner().unwrap(), vec![busy[0], other, busy[1], busy[2], busy[3]]);
+    }
+
+    #[tokio::test]
+    async fn test_strategy_is_not_queued_twice() {
+        let scheduler = ExecutionScheduler::new(1);
+        let user_id = Uuid::new_v4();
+        let strategy_id = Uuid::new_v4();
+
+        assert!(scheduler.enqueue(request(user_id, strategy_id)).await);
+        assert!(!scheduler.enqueue(request(user_id, strategy_id)).await);
+        assert_eq!(scheduler.queue_depth().await, 1);
+
+        scheduler.cancel(strategy_id).await;
+        assert_eq!(scheduler.queue_depth().await, 0);
+        assert!(scheduler.enqueue(request(user_id, strategy_id)).await);
+    }
+}
@@ -0,0 +1,288 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::exchange_connectors::numeric::decimal_from_f64;
+use crate::models::user_profile::{self, Entity as UserProfileEntity};
+use crate::utils::errors::AppError;
+use crate::utils::http::{request_with_policy, RetryPolicy};
+
+/// Currency every valuation is computed in before conversion
+pub const USD: &str = "USD";
+
+/// Currencies a user can pick as their base currency
+pub const SUPPORTED_BASE_CURRENCIES: &[&str] = &["USD", "EUR", "GBP", "CHF", "JPY", "CAD", "AUD"];
+
+/// Upper-case a requested base currency and check it is supported
+pub fn normalize_base_currency(code: &str) -> Result<String, String> {
+    let code = code.trim().to_uppercase();
+    if SUPPORTED_BASE_CURRENCIES.contains(&code.as_str()) {
+        Ok(code)
+    } else {
+        Err(format!(
+            "Unsupported base currency '{}'; expected one of {}",
+            code,
+            SUPPORTED_BASE_CURRENCIES.join(", ")
+        ))
+    }
+}
+
+/// Frankfurter (ECB reference rates) response
+#[derive(Debug, Deserialize)]
+struct FrankfurterResponse {
+    date: NaiveDate,
+    rates: HashMap<String, f64>,
+}
+
+/// USD to base currency rate values are converted with.
+///
+/// Crypto prices are quoted in USDT, which is taken at par with USD, so a value is
+/// converted exactly once: from USD into `base_currency` at `usd_rate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxRate {
+    pub base_currency: String,
+    /// Units of `base_currency` one US dollar buys
+    pub usd_rate: Decimal,
+    /// When the rate was published
+    pub as_of: DateTime<Utc>,
+}
+
+impl FxRate {
+    /// The identity rate, for users valuing in USD
+    pub fn usd(as_of: DateTime<Utc>) -> Self {
+        Self {
+            base_currency: USD.to_string(),
+            usd_rate: Decimal::ONE,
+            as_of,
+        }
+    }
+
+    /// Value of `usd_value` in the base currency
+    pub fn convert(&self, usd_value: Decimal) -> Decimal {
+        usd_value * self.usd_rate
+    }
+}
+
+/// Fetches and caches USD to base currency rates
+#[derive(Clone)]
+pub struct FxRateService {
+    client: Client,
+    retry_policy: RetryPolicy,
+    cached_rates: Arc<RwLock<HashMap<String, (FxRate, Instant)>>>,
+    cache_duration: Duration,
+}
+
+impl FxRateService {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .expect("Failed to create HTTP client"),
+            retry_policy: RetryPolicy {
+                max_attempts: 2,
+                deadline: Some(Duration::from_secs(10)),
+                ..RetryPolicy::default()
+            },
+            cached_rates: Arc::new(RwLock::new(HashMap::new())),
+            // Reference rates are published once a working day
+            cache_duration: Duration::from_secs(3600),
+        }
+    }
+
+    /// Rate for converting USD values into `base_currency`, with caching
+    pub async fn get_rate(&self, base_currency: &str) -> Result<FxRate, AppError> {
+        let base_currency = normalize_base_currency(base_currency).map_err(AppError::BadRequest)?;
+        if base_currency == USD {
+            return Ok(FxRate::usd(Utc::now()));
+        }
+
+        {
+            let cache = self.cached_rates.read().await;
+            if let Some((rate, cached_at)) = cache.get(&base_currency) {
+                if cached_at.elapsed() < self.cache_duration {
+                    debug!("Returning cached USD/{} rate", base_currency);
+                    return Ok(rate.clone());
+                }
+            }
+        }
+
+        let rate = self.fetch_rate(&base_currency).await?;
+        self.cache_rate(rate.clone()).await;
+
+        Ok(rate)
+    }
+
+    /// Rate for the user's base currency. Users without a profile value in USD, and so
+    /// does everyone when the rate can't be fetched; the returned rate says which applied.
+    pub async fn rate_for_user(&self, db: &DatabaseConnection, user_id: Uuid) -> Result<FxRate, AppError> {
+        let profile = UserProfileEntity::find()
+            .filter(user_profile::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let Some(profile) = profile else {
+            return Ok(FxRate::usd(Utc::now()));
+        };
+
+        match self.get_rate(&profile.base_currency).await {
+            Ok(rate) => Ok(rate),
+            Err(e) => {
+                warn!("Valuing in USD for user {}; no {} rate: {:?}", user_id, profile.base_currency, e);
+                Ok(FxRate::usd(Utc::now()))
+            }
+        }
+    }
+
+    /// Store a rate as freshly fetched
+    pub async fn cache_rate(&self, rate: FxRate) {
+        let mut cache = self.cached_rates.write().await;
+        cache.insert(rate.base_currency.clone(), (rate, Instant::now()));
+    }
+
+    async fn fetch_rate(&self, base_currency: &str) -> Result<FxRate, AppError> {
+        let url = format!("https://api.frankfurter.app/latest?from={}&to={}", USD, base_currency);
+
+        debug!("Fetching USD/{} rate", base_currency);
+
+        let response = request_with_policy(&self.retry_policy, self.client.get(&url))
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch USD/{} rate: {}", base_currency, e);
+                AppError::ExternalServiceError(format!("Failed to fetch USD/{} exchange rate", base_currency))
+            })?;
+
+        if !response.status().is_success() {
+            warn!("FX rate API returned status: {}", response.status());
+            return Err(AppError::ExternalServiceError(format!(
+                "Failed to fetch USD/{} exchange rate",
+                base_currency
+            )));
+        }
+
+        let body: FrankfurterResponse = response.json().await.map_err(|e| {
+            error!("Failed to parse FX rate response: {}", e);
+            AppError::InternalServerError
+        })?;
+
+        let rate = parse_rate(body, base_currency)?;
+        info!("Fetched USD/{} rate {} as of {}", base_currency, rate.usd_rate, rate.as_of);
+
+        Ok(rate)
+    }
+}
+
+impl Default for FxRateService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_rate(body: FrankfurterResponse, base_currency: &str) -> Result<FxRate, AppError> {
+    let usd_rate = body
+        .rates
+        .get(base_currency)
+        .and_then(|rate| decimal_from_f64(*rate).ok())
+        .filter(|rate| *rate > Decimal::ZERO)
+        .ok_or_else(|| {
+            error!("FX rate response has no usable {} rate", base_currency);
+            AppError::InternalServerError
+        })?;
+
+    Ok(FxRate {
+        base_currency: base_currency.to_string(),
+        usd_rate: usd_rate.round_dp(6),
+        as_of: body.date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn eur_rate() -> FxRate {
+        FxRate {
+            base_currency: "EUR".to_string(),
+            usd_rate: Decimal::new(92, 2),
+            as_of: Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_base_currency() {
+        assert_eq!(normalize_base_currency(" eur ").unwrap(), "EUR");
+        assert!(normalize_base_currency("XYZ").is_err());
+    }
+
+    #[test]
+    fn test_parse_rate_takes_the_publication_date() {
+        let body: FrankfurterResponse = serde_json::from_str(
+            r#"{"amount":1.0,"base":"USD","date":"2024-03-01","rates":{"EUR":0.92}}"#,
+        ).unwrap();
+
+        assert_eq!(parse_rate(body, "EUR").unwrap(), eur_rate());
+    }
+
+    #[tokio::test]
+    async fn test_cached_rate_is_served_with_its_timestamp() {
+        let service = FxRateService::new();
+        service.cache_rate(eur_rate()).await;
+
+        let rate = service.get_rate("eur").await.unwrap();
+        assert_eq!(rate, eur_rate());
+
+        let json = serde_json::to_value(&rate).unwrap();
+        assert_eq!(json["as_of"], "2024-03-01T00:00:00Z");
+        assert_eq!(service.get_rate("USD").await.unwrap().usd_rate, Decimal::ONE);
+    }
+
+    #[tokio::test]
+    async fn test_rate_for_user_follows_the_profile_base_currency() {
+        use crate::database::test_support::{seed_user, test_db};
+        use sea_orm::{ActiveModelTrait, Set};
+
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let service = FxRateService::new();
+        service.cache_rate(eur_rate()).await;
+
+        assert_eq!(service.rate_for_user(&db, user_id).await.unwrap().base_currency, USD);
+
+        user_profile::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            name: Set("Trader".to_string()),
+            email: Set("trader@example.com".to_string()),
+            phone: Set(None),
+            location: Set(None),
+            bio: Set(None),
+            join_date: Set("March 2024".to_string()),
+            avatar_url: Set(None),
+            is_verified: Set(false),
+            max_order_notional: Set(None),
+            clamp_oversized_orders: Set(false),
+            symbol_allow_list: Set(None),
+            symbol_deny_list: Set(None),
+            base_currency: Set("EUR".to_string()),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let rate = service.rate_for_user(&db, user_id).await.unwrap();
+        assert_eq!(rate, eur_rate());
+        assert_eq!(rate.convert(Decimal::from(1000)), Decimal::from(920));
+    }
+}
@@ -9,7 +9,11 @@ use tokio::time::{Duration, interval, sleep, Instant};
 use tracing::{info, warn, error, debug};
 
 use crate::utils::errors::AppError;
+use crate::utils::http::{request_with_policy, request_with_rate_limit, RetryPolicy, TokenBucket};
+use crate::exchange_connectors::binance::{binance_rate_limiter, parse_exchange_info_from_json};
+use crate::exchange_connectors::numeric::{decimal_from_f64, finite_f64};
 use crate::models::dca_strategy::MarketDataModel;
+use crate::services::symbol_access::trading_pair;
 
 /// Fear & Greed Index data structure
 #[derive(Debug, Deserialize)]
@@ -124,6 +128,19 @@ impl RateLimiter {
     }
 }
 
+/// Prices from a CoinGecko `[timestamp, price]` series, rejecting non-finite and
+/// out-of-range values
+fn chart_prices(points: &[[f64; 2]], symbol: &str) -> Result<Vec<f64>, AppError> {
+    points
+        .iter()
+        .map(|point| finite_f64(point[1]))
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|e| {
+            error!("Invalid market chart price for {}: {}", symbol, e);
+            AppError::InternalServerError
+        })
+}
+
 /// Market data service for real-time crypto market analysis
 #[derive(Clone)]
 pub struct MarketDataService {
@@ -132,6 +149,11 @@ pub struct MarketDataService {
     coingecko_url: String,
     binance_url: String,
     rate_limiter: RateLimiter,
+    // Binance calls share the process-wide limiter with the exchange clients
+    binance_limiter: Arc<TokenBucket>,
+    retry_policy: RetryPolicy,
+    // Symbol filters rarely change, so each minimum is looked up once
+    min_notionals: Arc<RwLock<HashMap<String, Decimal>>>,
 }
 
 impl MarketDataService {
@@ -145,6 +167,9 @@ impl MarketDataService {
             coingecko_url: "https://api.coingecko.com/api/v3".to_string(),
             binance_url: "https://api.binance.com".to_string(),
             rate_limiter: RateLimiter::new(1000), // 1 second between calls
+            binance_limiter: binance_rate_limiter(),
+            retry_policy: RetryPolicy::default(),
+            min_notionals: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -155,10 +180,11 @@ impl MarketDataService {
         // Get current and yesterday's data
         let url = format!("{}?limit=2", self.fear_greed_url);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("User-Agent", "E-Squared DCA Bot 1.0")
-            .send()
+            .header("User-Agent", "E-Squared DCA Bot 1.0");
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch Fear & Greed Index: {}", e);
@@ -206,10 +232,11 @@ impl MarketDataService {
         let url = format!("{}/simple/price?ids={}&vs_currencies=usd",
                          self.coingecko_url, coin_id);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("User-Agent", "E-Squared DCA Bot 1.0")
-            .send()
+            .header("User-Agent", "E-Squared DCA Bot 1.0");
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch price for {}: {}", symbol, e);
@@ -230,7 +257,7 @@ impl MarketDataService {
 
         if let Some(coin_data) = price_data.get(&coin_id) {
             if let Some(usd_price) = coin_data.get("usd") {
-                Decimal::try_from(*usd_price)
+                decimal_from_f64(*usd_price)
                     .map_err(|e| {
                         error!("Failed to convert price to Decimal: {}", e);
                         AppError::InternalServerError
@@ -249,9 +276,9 @@ impl MarketDataService {
         let binance_symbol = format!("{}USDT", symbol.to_uppercase());
         let url = format!("{}/api/v3/ticker/24hr?symbol={}", self.binance_url, binance_symbol);
 
-        let response = self.client
-            .get(&url)
-            .send()
+        let request = self.client.get(&url);
+
+        let response = request_with_rate_limit(&self.retry_policy, &self.binance_limiter, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch Binance ticker for {}: {}", symbol, e);
@@ -271,6 +298,45 @@ impl MarketDataService {
             })
     }
 
+    /// Smallest order value Binance accepts for the symbol, from its `NOTIONAL` or
+    /// `MIN_NOTIONAL` filter
+    pub async fn get_min_notional(&self, symbol: &str) -> Result<Decimal, AppError> {
+        let pair = trading_pair(symbol);
+        if let Some(min_notional) = self.min_notionals.read().await.get(&pair) {
+            return Ok(*min_notional);
+        }
+
+        let url = format!("{}/api/v3/exchangeInfo?symbol={}", self.binance_url, pair);
+        let response = request_with_rate_limit(&self.retry_policy, &self.binance_limiter, self.client.get(&url))
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch Binance exchange info for {}: {}", pair, e);
+                AppError::InternalServerError
+            })?;
+
+        if !response.status().is_success() {
+            warn!("Binance API returned status: {} for exchange info of {}", response.status(), pair);
+            return Err(AppError::InternalServerError);
+        }
+
+        let json: serde_json::Value = response.json()
+            .await
+            .map_err(|e| {
+                error!("Failed to parse Binance exchange info for {}: {}", pair, e);
+                AppError::InternalServerError
+            })?;
+        let min_notional = parse_exchange_info_from_json(json)
+            .map_err(|e| AppError::ExternalServiceError(format!("Invalid exchange info for {}: {}", pair, e)))?
+            .symbols
+            .into_iter()
+            .find(|info| info.symbol == pair)
+            .map(|info| info.min_notional)
+            .ok_or_else(|| AppError::ExternalServiceError(format!("Symbol {} not found on Binance", pair)))?;
+
+        self.min_notionals.write().await.insert(pair, min_notional);
+        Ok(min_notional)
+    }
+
     /// Calculate volatility from price data
     #[allow(dead_code)]
     pub async fn calculate_volatility(&self, symbol: &str, days: u32) -> Result<Decimal, AppError> {
@@ -278,10 +344,11 @@ impl MarketDataService {
         let url = format!("{}/coins/{}/market_chart?vs_currency=usd&days={}&interval=daily",
                          self.coingecko_url, coin_id, days);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("User-Agent", "E-Squared DCA Bot 1.0")
-            .send()
+            .header("User-Agent", "E-Squared DCA Bot 1.0");
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch market chart for {}: {}", symbol, e);
@@ -304,12 +371,13 @@ impl MarketDataService {
         if chart.prices.len() < 2 {
             return Err(AppError::InternalServerError);
         }
+        let prices = chart_prices(&chart.prices, symbol)?;
 
         // Calculate daily returns
         let mut returns = Vec::new();
-        for i in 1..chart.prices.len() {
-            let current_price = chart.prices[i][1];
-            let previous_price = chart.prices[i-1][1];
+        for i in 1..prices.len() {
+            let current_price = prices[i];
+            let previous_price = prices[i-1];
             let daily_return = (current_price - previous_price) / previous_price;
             returns.push(daily_return);
         }
@@ -325,7 +393,7 @@ impl MarketDataService {
 
         let volatility = variance.sqrt() * 100.0; // Convert to percentage
 
-        Decimal::try_from(volatility)
+        decimal_from_f64(volatility)
             .map_err(|_| AppError::InternalServerError)
     }
 
@@ -336,10 +404,11 @@ impl MarketDataService {
         let url = format!("{}/coins/{}/market_chart?vs_currency=usd&days=200&interval=daily",
                          self.coingecko_url, coin_id);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("User-Agent", "E-Squared DCA Bot 1.0")
-            .send()
+            .header("User-Agent", "E-Squared DCA Bot 1.0");
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|_| AppError::InternalServerError)?;
 
@@ -380,7 +449,7 @@ impl MarketDataService {
             });
         }
 
-        let prices: Vec<f64> = chart.prices.iter().map(|p| p[1]).collect();
+        let prices = chart_prices(&chart.prices, symbol)?;
 
         // Calculate EMAs
         let ema_20 = self.calculate_ema(&prices, 20);
@@ -413,14 +482,14 @@ impl MarketDataService {
         };
 
         Ok(TechnicalIndicators {
-            rsi_14: rsi_14.and_then(|v| Decimal::try_from(v).ok()),
-            ema_20: ema_20.and_then(|v| Decimal::try_from(v).ok()),
-            ema_50: ema_50.and_then(|v| Decimal::try_from(v).ok()),
-            ema_200: ema_200.and_then(|v| Decimal::try_from(v).ok()),
-            volatility_7d: volatility_7d.and_then(|v| Decimal::try_from(v).ok()),
-            volatility_30d: volatility_30d.and_then(|v| Decimal::try_from(v).ok()),
-            support_level: Decimal::try_from(support_level).ok(),
-            resistance_level: Decimal::try_from(resistance_level).ok(),
+            rsi_14: rsi_14.and_then(|v| decimal_from_f64(v).ok()),
+            ema_20: ema_20.and_then(|v| decimal_from_f64(v).ok()),
+            ema_50: ema_50.and_then(|v| decimal_from_f64(v).ok()),
+            ema_200: ema_200.and_then(|v| decimal_from_f64(v).ok()),
+            volatility_7d: volatility_7d.and_then(|v| decimal_from_f64(v).ok()),
+            volatility_30d: volatility_30d.and_then(|v| decimal_from_f64(v).ok()),
+            support_level: decimal_from_f64(support_level).ok(),
+            resistance_level: decimal_from_f64(resistance_level).ok(),
             trend_direction,
         })
     }
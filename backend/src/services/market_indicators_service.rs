@@ -1,4 +1,6 @@
+use async_trait::async_trait;
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -7,6 +9,8 @@ use tracing::{error, warn, debug, info};
 use rust_decimal::Decimal;
 
 use crate::utils::errors::AppError;
+use crate::utils::http::{request_with_policy, RetryPolicy};
+use crate::exchange_connectors::numeric::{decimal_from_f64, parse_decimal, parse_f64};
 
 /// CoinGecko API response for BTC dominance
 #[derive(Debug, Deserialize)]
@@ -32,6 +36,24 @@ pub struct CoinGeckoBtcPriceDetail {
     pub usd_24h_change: Option<f64>,
 }
 
+/// CoinPaprika API response for global market data
+#[derive(Debug, Deserialize)]
+pub struct CoinPaprikaGlobal {
+    pub bitcoin_dominance_percentage: f64,
+    pub market_cap_change_24h: Option<f64>,
+}
+
+/// Binance API response for a 24h ticker
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceTicker24h {
+    pub last_price: String,
+    pub price_change: String,
+    pub price_change_percent: String,
+    pub high_price: String,
+    pub low_price: String,
+}
+
 /// FRED API response for M2 Money Supply
 #[derive(Debug, Deserialize)]
 pub struct FredResponse {
@@ -50,6 +72,9 @@ pub struct BtcDominanceData {
     pub value: Decimal,
     pub change_24h: Option<Decimal>,
     pub timestamp: i64,
+    /// Provider that served the value
+    #[serde(default)]
+    pub source: String,
 }
 
 /// M2 Money Supply data model
@@ -60,6 +85,9 @@ pub struct M2Data {
     pub percent_change: Option<Decimal>,
     pub date: String,
     pub timestamp: i64,
+    /// Provider that served the value
+    #[serde(default)]
+    pub source: String,
 }
 
 /// Bitcoin Price data model
@@ -71,6 +99,9 @@ pub struct BtcPriceData {
     pub high_24h: Option<Decimal>,
     pub low_24h: Option<Decimal>,
     pub timestamp: i64,
+    /// Provider that served the value
+    #[serde(default)]
+    pub source: String,
 }
 
 /// Rate limiter for API calls
@@ -108,142 +139,113 @@ impl RateLimiter {
     }
 }
 
-/// Market Indicators Service for fetching M2 and BTC dominance
+
+/// One source for a market indicator. `MarketIndicatorsService` tries an indicator's
+/// providers in order and serves the first value that comes back.
+#[async_trait]
+pub trait IndicatorProvider<T>: Send + Sync {
+    /// Name reported as the `source` of values this provider serves
+    fn name(&self) -> &str;
+
+    /// Fetch the current value
+    async fn fetch(&self) -> Result<T, AppError>;
+}
+
+/// Ordered providers for one indicator, most preferred first
+pub type ProviderChain<T> = Vec<Arc<dyn IndicatorProvider<T>>>;
+
+/// Try each provider in order and return the first value along with the name of the
+/// provider that served it. Fails with the last provider's error if all of them fail.
+pub async fn fetch_with_fallback<T>(
+    indicator: &str,
+    providers: &[Arc<dyn IndicatorProvider<T>>],
+) -> Result<(T, String), AppError> {
+    let mut last_error = None;
+
+    for provider in providers {
+        match provider.fetch().await {
+            Ok(value) => return Ok((value, provider.name().to_string())),
+            Err(e) => {
+                warn!("{} provider '{}' failed, trying the next one: {}", indicator, provider.name(), e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        AppError::ExternalServiceError(format!("No providers configured for {}", indicator))
+    }))
+}
+
+/// HTTP client, rate limiter and retry policy shared by the built-in providers
 #[derive(Clone)]
-pub struct MarketIndicatorsService {
+struct IndicatorApiClient {
     client: Client,
     rate_limiter: RateLimiter,
-    btc_cached_data: Arc<RwLock<Option<(BtcDominanceData, Instant)>>>,
-    btc_price_cached_data: Arc<RwLock<Option<(BtcPriceData, Instant)>>>,
-    m2_cached_data: Arc<RwLock<Option<(M2Data, Instant)>>>,
-    cache_duration: Duration,
-    btc_price_cache_duration: Duration,
+    retry_policy: RetryPolicy,
 }
 
-impl MarketIndicatorsService {
-    /// Create a new market indicators service
-    pub fn new() -> Self {
+impl IndicatorApiClient {
+    fn new() -> Self {
         Self {
             client: Client::builder()
                 .timeout(Duration::from_secs(30))
                 .build()
                 .expect("Failed to create HTTP client"),
             rate_limiter: RateLimiter::new(2000), // 2 seconds between calls
-            btc_cached_data: Arc::new(RwLock::new(None)),
-            btc_price_cached_data: Arc::new(RwLock::new(None)),
-            m2_cached_data: Arc::new(RwLock::new(None)),
-            cache_duration: Duration::from_secs(3600), // Cache for 1 hour (these update slowly)
-            btc_price_cache_duration: Duration::from_secs(60), // Cache BTC price for 1 minute
+            retry_policy: RetryPolicy::default(),
         }
     }
 
-    /// Get Bitcoin Dominance with caching
-    pub async fn get_btc_dominance(&self) -> Result<BtcDominanceData, AppError> {
-        // Check cache first
-        {
-            let cache = self.btc_cached_data.read().await;
-            if let Some((data, cached_at)) = cache.as_ref() {
-                if cached_at.elapsed() < self.cache_duration {
-                    debug!("Returning cached BTC dominance data");
-                    return Ok(data.clone());
-                }
-            }
-        }
-
-        // Fetch fresh data
-        let btc_data = self.fetch_btc_dominance_from_api().await?;
-
-        // Update cache
-        {
-            let mut cache = self.btc_cached_data.write().await;
-            *cache = Some((btc_data.clone(), Instant::now()));
-        }
-
-        Ok(btc_data)
-    }
-
-    /// Get M2 Money Supply with caching
-    pub async fn get_m2(&self) -> Result<M2Data, AppError> {
-        // Check cache first
-        {
-            let cache = self.m2_cached_data.read().await;
-            if let Some((data, cached_at)) = cache.as_ref() {
-                if cached_at.elapsed() < self.cache_duration {
-                    debug!("Returning cached M2 data");
-                    return Ok(data.clone());
-                }
-            }
-        }
-
-        // Fetch fresh data
-        let m2_data = self.fetch_m2_from_api().await?;
-
-        // Update cache
-        {
-            let mut cache = self.m2_cached_data.write().await;
-            *cache = Some((m2_data.clone(), Instant::now()));
-        }
-
-        Ok(m2_data)
-    }
-
-    /// Get Bitcoin Price with caching
-    pub async fn get_btc_price(&self) -> Result<BtcPriceData, AppError> {
-        // Check cache first
-        {
-            let cache = self.btc_price_cached_data.read().await;
-            if let Some((data, cached_at)) = cache.as_ref() {
-                if cached_at.elapsed() < self.btc_price_cache_duration {
-                    debug!("Returning cached BTC price data");
-                    return Ok(data.clone());
-                }
-            }
-        }
-
-        // Fetch fresh data
-        let btc_price_data = self.fetch_btc_price_from_api().await?;
-
-        // Update cache
-        {
-            let mut cache = self.btc_price_cached_data.write().await;
-            *cache = Some((btc_price_data.clone(), Instant::now()));
-        }
-
-        Ok(btc_price_data)
-    }
-
-    /// Fetch BTC Dominance from CoinGecko API (free, no API key needed)
-    async fn fetch_btc_dominance_from_api(&self) -> Result<BtcDominanceData, AppError> {
+    /// GET `url` and parse the JSON body, logging failures against `source`
+    async fn get_json<R: DeserializeOwned>(&self, source: &str, url: &str) -> Result<R, AppError> {
         self.rate_limiter.wait_if_needed().await;
 
-        let url = "https://api.coingecko.com/api/v3/global";
-
-        debug!("Fetching BTC dominance from CoinGecko API");
-
-        let response = self.client
+        let request = self.client
             .get(url)
-            .header("User-Agent", "E-Squared Trading Platform 1.0")
-            .send()
+            .header("User-Agent", "E-Squared Trading Platform 1.0");
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
-                error!("Failed to fetch BTC dominance from CoinGecko: {}", e);
+                error!("Failed to fetch from {}: {}", source, e);
                 AppError::InternalServerError
             })?;
 
         if !response.status().is_success() {
-            warn!("CoinGecko API returned status: {}", response.status());
+            warn!("{} API returned status: {}", source, response.status());
             if let Ok(text) = response.text().await {
                 error!("API error response: {}", text);
             }
             return Err(AppError::InternalServerError);
         }
 
-        let coingecko_response: CoinGeckoGlobal = response.json()
+        response.json::<R>()
             .await
             .map_err(|e| {
-                error!("Failed to parse CoinGecko response: {}", e);
+                error!("Failed to parse {} response: {}", source, e);
                 AppError::InternalServerError
-            })?;
+            })
+    }
+}
+
+/// BTC dominance from CoinGecko (free, no API key needed)
+pub struct CoinGeckoDominanceProvider {
+    api: IndicatorApiClient,
+}
+
+#[async_trait]
+impl IndicatorProvider<BtcDominanceData> for CoinGeckoDominanceProvider {
+    fn name(&self) -> &str {
+        "coingecko"
+    }
+
+    async fn fetch(&self) -> Result<BtcDominanceData, AppError> {
+        debug!("Fetching BTC dominance from CoinGecko API");
+
+        let coingecko_response: CoinGeckoGlobal = self.api
+            .get_json("CoinGecko", "https://api.coingecko.com/api/v3/global")
+            .await?;
 
         // Extract BTC dominance percentage
         let btc_dominance = coingecko_response.data.market_cap_percentage
@@ -253,7 +255,7 @@ impl MarketIndicatorsService {
                 AppError::InternalServerError
             })?;
 
-        let value = Decimal::try_from(*btc_dominance)
+        let value = decimal_from_f64(*btc_dominance)
             .map_err(|e| {
                 error!("Failed to convert BTC dominance to Decimal: {}", e);
                 AppError::InternalServerError
@@ -261,70 +263,79 @@ impl MarketIndicatorsService {
 
         // Get 24h change if available
         let change_24h = coingecko_response.data.market_cap_change_percentage_24h_usd
-            .and_then(|change| Decimal::try_from(change).ok());
-
-        let timestamp = chrono::Utc::now().timestamp();
+            .and_then(|change| decimal_from_f64(change).ok());
 
         info!("Successfully fetched BTC dominance from CoinGecko: {}%", value);
 
         Ok(BtcDominanceData {
             value,
             change_24h,
-            timestamp,
+            timestamp: chrono::Utc::now().timestamp(),
+            source: String::new(),
         })
     }
+}
 
-    /// Fetch M2 Money Supply from FRED API
-    /// Note: This returns US M2. For global M2, you'd need to aggregate multiple sources
-    async fn fetch_m2_from_api(&self) -> Result<M2Data, AppError> {
-        self.rate_limiter.wait_if_needed().await;
+/// BTC dominance from CoinPaprika (free, no API key needed)
+pub struct CoinPaprikaDominanceProvider {
+    api: IndicatorApiClient,
+}
 
-        // Get FRED API key from environment, or return static fallback data
-        let api_key = match std::env::var("FRED_API_KEY") {
-            Ok(key) => key,
-            Err(_) => {
-                warn!("FRED_API_KEY not set, returning static M2 data");
-                // Return recent M2 value as fallback (as of Oct 2024)
-                let timestamp = chrono::Utc::now().timestamp();
-                return Ok(M2Data {
-                    value: Decimal::try_from(21080.0).unwrap(), // ~$21.08 trillion
-                    change: None,
-                    percent_change: None,
-                    date: "2024-10-14".to_string(),
-                    timestamp,
-                });
-            }
-        };
+#[async_trait]
+impl IndicatorProvider<BtcDominanceData> for CoinPaprikaDominanceProvider {
+    fn name(&self) -> &str {
+        "coinpaprika"
+    }
 
-        // FRED API endpoint for M2 Money Stock (US) - get last 2 observations for change calculation
-        let url = format!("https://api.stlouisfed.org/fred/series/observations?series_id=WM2NS&api_key={}&file_type=json&limit=2&sort_order=desc", api_key);
+    async fn fetch(&self) -> Result<BtcDominanceData, AppError> {
+        debug!("Fetching BTC dominance from CoinPaprika API");
 
-        debug!("Fetching M2 data from FRED API");
+        let paprika_response: CoinPaprikaGlobal = self.api
+            .get_json("CoinPaprika", "https://api.coinpaprika.com/v1/global")
+            .await?;
 
-        let response = self.client
-            .get(url)
-            .header("User-Agent", "E-Squared Trading Platform 1.0")
-            .send()
-            .await
+        let value = decimal_from_f64(paprika_response.bitcoin_dominance_percentage)
             .map_err(|e| {
-                error!("Failed to fetch M2 from FRED: {}", e);
+                error!("Failed to convert BTC dominance to Decimal: {}", e);
                 AppError::InternalServerError
             })?;
 
-        if !response.status().is_success() {
-            warn!("FRED API returned status: {}", response.status());
-            if let Ok(text) = response.text().await {
-                error!("API error response: {}", text);
-            }
-            return Err(AppError::InternalServerError);
-        }
+        let change_24h = paprika_response.market_cap_change_24h
+            .and_then(|change| decimal_from_f64(change).ok());
 
-        let fred_response: FredResponse = response.json()
-            .await
-            .map_err(|e| {
-                error!("Failed to parse FRED response: {}", e);
-                AppError::InternalServerError
-            })?;
+        info!("Successfully fetched BTC dominance from CoinPaprika: {}%", value);
+
+        Ok(BtcDominanceData {
+            value,
+            change_24h,
+            timestamp: chrono::Utc::now().timestamp(),
+            source: String::new(),
+        })
+    }
+}
+
+/// M2 Money Supply from the FRED API; needs `FRED_API_KEY`
+/// Note: This returns US M2. For global M2, you'd need to aggregate multiple sources
+pub struct FredM2Provider {
+    api: IndicatorApiClient,
+}
+
+#[async_trait]
+impl IndicatorProvider<M2Data> for FredM2Provider {
+    fn name(&self) -> &str {
+        "fred"
+    }
+
+    async fn fetch(&self) -> Result<M2Data, AppError> {
+        let api_key = std::env::var("FRED_API_KEY")
+            .map_err(|_| AppError::ExternalServiceError("FRED_API_KEY not set".to_string()))?;
+
+        // FRED API endpoint for M2 Money Stock (US) - get last 2 observations for change calculation
+        let url = format!("https://api.stlouisfed.org/fred/series/observations?series_id=WM2NS&api_key={}&file_type=json&limit=2&sort_order=desc", api_key);
+
+        debug!("Fetching M2 data from FRED API");
+
+        let fred_response: FredResponse = self.api.get_json("FRED", &url).await?;
 
         // Get the latest observation
         let observation = fred_response.observations.first()
@@ -334,13 +345,13 @@ impl MarketIndicatorsService {
             })?;
 
         // Parse the value (in billions of dollars)
-        let m2_value = observation.value.parse::<f64>()
+        let m2_value = parse_f64(&observation.value)
             .map_err(|e| {
                 error!("Failed to parse M2 value: {}", e);
                 AppError::InternalServerError
             })?;
 
-        let value = Decimal::try_from(m2_value)
+        let value = decimal_from_f64(m2_value)
             .map_err(|e| {
                 error!("Failed to convert M2 value to Decimal: {}", e);
                 AppError::InternalServerError
@@ -349,7 +360,7 @@ impl MarketIndicatorsService {
         // Calculate change if previous observation is available
         let (change, percent_change) = if fred_response.observations.len() >= 2 {
             let previous_observation = &fred_response.observations[1];
-            if let Ok(previous_value) = previous_observation.value.parse::<f64>() {
+            if let Ok(previous_value) = parse_f64(&previous_observation.value) {
                 let change_val = m2_value - previous_value;
                 let percent_change_val = if previous_value != 0.0 {
                     (change_val / previous_value) * 100.0
@@ -357,8 +368,8 @@ impl MarketIndicatorsService {
                     0.0
                 };
 
-                let change_decimal = Decimal::try_from(change_val).ok();
-                let percent_decimal = Decimal::try_from(percent_change_val).ok();
+                let change_decimal = decimal_from_f64(change_val).ok();
+                let percent_decimal = decimal_from_f64(percent_change_val).ok();
                 (change_decimal, percent_decimal)
             } else {
                 (None, None)
@@ -367,8 +378,6 @@ impl MarketIndicatorsService {
             (None, None)
         };
 
-        let timestamp = chrono::Utc::now().timestamp();
-
         info!("Successfully fetched M2 from FRED: ${} billion (as of {})", value, observation.date);
 
         Ok(M2Data {
@@ -376,47 +385,60 @@ impl MarketIndicatorsService {
             change,
             percent_change,
             date: observation.date.clone(),
-            timestamp,
+            timestamp: chrono::Utc::now().timestamp(),
+            source: String::new(),
         })
     }
+}
 
-    /// Fetch Bitcoin Price from CoinGecko API (free, no API key needed)
-    async fn fetch_btc_price_from_api(&self) -> Result<BtcPriceData, AppError> {
-        self.rate_limiter.wait_if_needed().await;
+/// Last known M2 value, used when no live source is reachable
+pub struct StaticM2Provider;
 
-        // CoinGecko simple price endpoint with 24h data
-        let url = "https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd&include_24hr_change=true&include_24hr_vol=true";
+#[async_trait]
+impl IndicatorProvider<M2Data> for StaticM2Provider {
+    fn name(&self) -> &str {
+        "static"
+    }
 
-        debug!("Fetching BTC price from CoinGecko API");
+    async fn fetch(&self) -> Result<M2Data, AppError> {
+        warn!("Serving static M2 data");
+        // Recent M2 value (as of Oct 2024)
+        Ok(M2Data {
+            value: Decimal::from(21080), // ~$21.08 trillion
+            change: None,
+            percent_change: None,
+            date: "2024-10-14".to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            source: String::new(),
+        })
+    }
+}
 
-        let response = self.client
-            .get(url)
-            .header("User-Agent", "E-Squared Trading Platform 1.0")
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Failed to fetch BTC price from CoinGecko: {}", e);
-                AppError::InternalServerError
-            })?;
+/// Bitcoin price from CoinGecko (free, no API key needed)
+pub struct CoinGeckoBtcPriceProvider {
+    api: IndicatorApiClient,
+}
 
-        if !response.status().is_success() {
-            warn!("CoinGecko API returned status: {}", response.status());
-            if let Ok(text) = response.text().await {
-                error!("API error response: {}", text);
-            }
-            return Err(AppError::InternalServerError);
-        }
+#[async_trait]
+impl IndicatorProvider<BtcPriceData> for CoinGeckoBtcPriceProvider {
+    fn name(&self) -> &str {
+        "coingecko"
+    }
 
-        let price_response: CoinGeckoBtcPrice = response.json()
-            .await
-            .map_err(|e| {
-                error!("Failed to parse CoinGecko price response: {}", e);
-                AppError::InternalServerError
-            })?;
+    async fn fetch(&self) -> Result<BtcPriceData, AppError> {
+        debug!("Fetching BTC price from CoinGecko API");
+
+        // CoinGecko simple price endpoint with 24h data
+        let price_response: CoinGeckoBtcPrice = self.api
+            .get_json(
+                "CoinGecko",
+                "https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd&include_24hr_change=true&include_24hr_vol=true",
+            )
+            .await?;
 
         let btc_data = &price_response.bitcoin;
 
-        let price = Decimal::try_from(btc_data.usd)
+        let price = decimal_from_f64(btc_data.usd)
             .map_err(|e| {
                 error!("Failed to convert BTC price to Decimal: {}", e);
                 AppError::InternalServerError
@@ -424,20 +446,14 @@ impl MarketIndicatorsService {
 
         // Calculate 24h change
         let (change_24h, percent_change_24h) = if let Some(percent_change) = btc_data.usd_24h_change {
-            let percent_decimal = Decimal::try_from(percent_change).ok();
-            let change_decimal = if let Some(pct) = percent_decimal {
-                // Calculate absolute change from percentage
-                Some(price * pct / Decimal::from(100))
-            } else {
-                None
-            };
+            let percent_decimal = decimal_from_f64(percent_change).ok();
+            // Calculate absolute change from percentage
+            let change_decimal = percent_decimal.map(|pct| price * pct / Decimal::from(100));
             (change_decimal, percent_decimal)
         } else {
             (None, None)
         };
 
-        let timestamp = chrono::Utc::now().timestamp();
-
         info!("Successfully fetched BTC price from CoinGecko: ${}", price);
 
         Ok(BtcPriceData {
@@ -446,9 +462,189 @@ impl MarketIndicatorsService {
             percent_change_24h,
             high_24h: None, // Simple API doesn't provide this
             low_24h: None,  // Simple API doesn't provide this
-            timestamp,
+            timestamp: chrono::Utc::now().timestamp(),
+            source: String::new(),
         })
     }
+}
+
+/// Bitcoin price from the Binance BTCUSDT 24h ticker (public, no API key needed)
+pub struct BinanceBtcPriceProvider {
+    api: IndicatorApiClient,
+}
+
+#[async_trait]
+impl IndicatorProvider<BtcPriceData> for BinanceBtcPriceProvider {
+    fn name(&self) -> &str {
+        "binance"
+    }
+
+    async fn fetch(&self) -> Result<BtcPriceData, AppError> {
+        debug!("Fetching BTC price from Binance API");
+
+        let ticker: BinanceTicker24h = self.api
+            .get_json("Binance", "https://api.binance.com/api/v3/ticker/24hr?symbol=BTCUSDT")
+            .await?;
+
+        let price = parse_decimal(&ticker.last_price)
+            .map_err(|e| {
+                error!("Failed to parse Binance BTC price: {}", e);
+                AppError::InternalServerError
+            })?;
+
+        info!("Successfully fetched BTC price from Binance: ${}", price);
+
+        Ok(BtcPriceData {
+            price,
+            change_24h: parse_decimal(&ticker.price_change).ok(),
+            percent_change_24h: parse_decimal(&ticker.price_change_percent).ok(),
+            high_24h: parse_decimal(&ticker.high_price).ok(),
+            low_24h: parse_decimal(&ticker.low_price).ok(),
+            timestamp: chrono::Utc::now().timestamp(),
+            source: String::new(),
+        })
+    }
+}
+
+/// Market Indicators Service for fetching M2 and BTC dominance
+///
+/// Each indicator is served by an ordered chain of providers; a failing provider
+/// falls through to the next and the value reports which one served it.
+#[derive(Clone)]
+pub struct MarketIndicatorsService {
+    btc_dominance_providers: ProviderChain<BtcDominanceData>,
+    m2_providers: ProviderChain<M2Data>,
+    btc_price_providers: ProviderChain<BtcPriceData>,
+    btc_cached_data: Arc<RwLock<Option<(BtcDominanceData, Instant)>>>,
+    btc_price_cached_data: Arc<RwLock<Option<(BtcPriceData, Instant)>>>,
+    m2_cached_data: Arc<RwLock<Option<(M2Data, Instant)>>>,
+    cache_duration: Duration,
+    btc_price_cache_duration: Duration,
+}
+
+impl MarketIndicatorsService {
+    /// Create a new market indicators service with the built-in provider chains:
+    /// CoinGecko then CoinPaprika for BTC dominance, FRED then a static snapshot for
+    /// M2, and CoinGecko then Binance for the BTC price
+    pub fn new() -> Self {
+        let api = IndicatorApiClient::new();
+
+        Self {
+            btc_dominance_providers: vec![
+                Arc::new(CoinGeckoDominanceProvider { api: api.clone() }),
+                Arc::new(CoinPaprikaDominanceProvider { api: api.clone() }),
+            ],
+            m2_providers: vec![
+                Arc::new(FredM2Provider { api: api.clone() }),
+                Arc::new(StaticM2Provider),
+            ],
+            btc_price_providers: vec![
+                Arc::new(CoinGeckoBtcPriceProvider { api: api.clone() }),
+                Arc::new(BinanceBtcPriceProvider { api }),
+            ],
+            btc_cached_data: Arc::new(RwLock::new(None)),
+            btc_price_cached_data: Arc::new(RwLock::new(None)),
+            m2_cached_data: Arc::new(RwLock::new(None)),
+            cache_duration: Duration::from_secs(3600), // Cache for 1 hour (these update slowly)
+            btc_price_cache_duration: Duration::from_secs(60), // Cache BTC price for 1 minute
+        }
+    }
+
+    /// Replace the BTC dominance providers, most preferred first
+    pub fn with_btc_dominance_providers(mut self, providers: ProviderChain<BtcDominanceData>) -> Self {
+        self.btc_dominance_providers = providers;
+        self
+    }
+
+    /// Replace the M2 providers, most preferred first
+    pub fn with_m2_providers(mut self, providers: ProviderChain<M2Data>) -> Self {
+        self.m2_providers = providers;
+        self
+    }
+
+    /// Replace the BTC price providers, most preferred first
+    pub fn with_btc_price_providers(mut self, providers: ProviderChain<BtcPriceData>) -> Self {
+        self.btc_price_providers = providers;
+        self
+    }
+
+    /// Get Bitcoin Dominance with caching
+    pub async fn get_btc_dominance(&self) -> Result<BtcDominanceData, AppError> {
+        // Check cache first
+        {
+            let cache = self.btc_cached_data.read().await;
+            if let Some((data, cached_at)) = cache.as_ref() {
+                if cached_at.elapsed() < self.cache_duration {
+                    debug!("Returning cached BTC dominance data");
+                    return Ok(data.clone());
+                }
+            }
+        }
+
+        // Fetch fresh data
+        let (mut btc_data, source) = fetch_with_fallback("BTC dominance", &self.btc_dominance_providers).await?;
+        btc_data.source = source;
+
+        // Update cache
+        {
+            let mut cache = self.btc_cached_data.write().await;
+            *cache = Some((btc_data.clone(), Instant::now()));
+        }
+
+        Ok(btc_data)
+    }
+
+    /// Get M2 Money Supply with caching
+    pub async fn get_m2(&self) -> Result<M2Data, AppError> {
+        // Check cache first
+        {
+            let cache = self.m2_cached_data.read().await;
+            if let Some((data, cached_at)) = cache.as_ref() {
+                if cached_at.elapsed() < self.cache_duration {
+                    debug!("Returning cached M2 data");
+                    return Ok(data.clone());
+                }
+            }
+        }
+
+        // Fetch fresh data
+        let (mut m2_data, source) = fetch_with_fallback("M2", &self.m2_providers).await?;
+        m2_data.source = source;
+
+        // Update cache
+        {
+            let mut cache = self.m2_cached_data.write().await;
+            *cache = Some((m2_data.clone(), Instant::now()));
+        }
+
+        Ok(m2_data)
+    }
+
+    /// Get Bitcoin Price with caching
+    pub async fn get_btc_price(&self) -> Result<BtcPriceData, AppError> {
+        // Check cache first
+        {
+            let cache = self.btc_price_cached_data.read().await;
+            if let Some((data, cached_at)) = cache.as_ref() {
+                if cached_at.elapsed() < self.btc_price_cache_duration {
+                    debug!("Returning cached BTC price data");
+                    return Ok(data.clone());
+                }
+            }
+        }
+
+        // Fetch fresh data
+        let (mut btc_price_data, source) = fetch_with_fallback("BTC price", &self.btc_price_providers).await?;
+        btc_price_data.source = source;
+
+        // Update cache
+        {
+            let mut cache = self.btc_price_cached_data.write().await;
+            *cache = Some((btc_price_data.clone(), Instant::now()));
+        }
+
+        Ok(btc_price_data)
+    }
 
     /// Clear all caches
     pub async fn clear_cache(&self) {
@@ -470,6 +666,44 @@ impl Default for MarketIndicatorsService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serves `value` or fails, counting how often it is asked
+    struct FakeProvider<T> {
+        name: &'static str,
+        value: Option<T>,
+        calls: AtomicUsize,
+    }
+
+    impl<T> FakeProvider<T> {
+        fn new(name: &'static str, value: Option<T>) -> Arc<Self> {
+            Arc::new(Self { name, value, calls: AtomicUsize::new(0) })
+        }
+    }
+
+    #[async_trait]
+    impl<T: Clone + Send + Sync + 'static> IndicatorProvider<T> for FakeProvider<T> {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn fetch(&self) -> Result<T, AppError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.value.clone()
+                .ok_or_else(|| AppError::ExternalServiceError(format!("{} is down", self.name)))
+        }
+    }
+
+    fn m2(value: i64) -> M2Data {
+        M2Data {
+            value: Decimal::from(value),
+            change: None,
+            percent_change: None,
+            date: "2025-01-06".to_string(),
+            timestamp: 0,
+            source: String::new(),
+        }
+    }
 
     #[tokio::test]
     async fn test_service_creation() {
@@ -477,4 +711,57 @@ mod tests {
         assert!(service.btc_cached_data.read().await.is_none());
         assert!(service.m2_cached_data.read().await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_falls_back_to_secondary_provider() {
+        let primary = FakeProvider::new("primary", None);
+        let secondary = FakeProvider::new("secondary", Some(m2(21500)));
+        let service = MarketIndicatorsService::new()
+            .with_m2_providers(vec![primary.clone(), secondary.clone()]);
+
+        let data = service.get_m2().await.unwrap();
+        assert_eq!(data.value, Decimal::from(21500));
+        assert_eq!(data.source, "secondary");
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+
+        // Served from cache, source and all
+        let cached = service.get_m2().await.unwrap();
+        assert_eq!(cached.source, "secondary");
+        assert_eq!(secondary.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_primary_provider_wins_when_healthy() {
+        let price = BtcPriceData {
+            price: Decimal::from(65000),
+            change_24h: None,
+            percent_change_24h: None,
+            high_24h: None,
+            low_24h: None,
+            timestamp: 0,
+            source: String::new(),
+        };
+        let primary = FakeProvider::new("primary", Some(price));
+        let secondary = FakeProvider::<BtcPriceData>::new("secondary", None);
+        let service = MarketIndicatorsService::new()
+            .with_btc_price_providers(vec![primary, secondary.clone()]);
+
+        let data = service.get_btc_price().await.unwrap();
+        assert_eq!(data.price, Decimal::from(65000));
+        assert_eq!(data.source, "primary");
+        assert_eq!(secondary.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fails_when_every_provider_fails() {
+        let providers: ProviderChain<M2Data> = vec![
+            FakeProvider::new("primary", None),
+            FakeProvider::new("secondary", None),
+        ];
+        let result = fetch_with_fallback("M2", &providers).await;
+        assert!(matches!(result, Err(AppError::ExternalServiceError(msg)) if msg == "secondary is down"));
+
+        let none: ProviderChain<M2Data> = Vec::new();
+        assert!(fetch_with_fallback("M2", &none).await.is_err());
+    }
 }
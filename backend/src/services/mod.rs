@@ -1,12 +1,29 @@
 pub mod market_data_service;
 pub mod dca_execution_engine;
+pub mod execution_scheduler;
 pub mod dxy_service;
 pub mod market_indicators_service;
 pub mod stock_data_service;
+pub mod snapshot_backfill;
+pub mod connection_history;
+pub mod portfolio_consolidation;
+pub mod emergency_stop;
+pub mod order_execution;
+pub mod paper_trading;
+pub mod fx_rates;
+pub mod symbol_access;
 // Removed legacy strategy_templates - using new modular system
 
 pub use market_data_service::*;
 pub use dca_execution_engine::*;
+pub use execution_scheduler::ExecutionScheduler;
 pub use dxy_service::*;
 pub use market_indicators_service::*;
-pub use stock_data_service::*;
\ No newline at end of file
+pub use stock_data_service::*;
+pub use snapshot_backfill::SnapshotBackfillService;
+pub use connection_history::ConnectionHistoryService;
+pub use emergency_stop::EmergencyStopService;
+pub use order_execution::OrderExecutor;
+pub use paper_trading::PaperTradingService;
+pub use fx_rates::FxRateService;
+pub use symbol_access::ensure_symbol_allowed;
@@ -0,0 +1,534 @@
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tokio::time::{sleep, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::exchange_connectors::common_types::{Order, OrderRequest, OrderSide, OrderStatus, OrderType, TimeInForce, WalletType};
+use crate::exchange_connectors::fees::{FeeRates, FeeSchedule, BASE_FEE_TIER};
+use crate::exchange_connectors::traits::{OrderAPI, TradeExecutionAPI};
+use crate::exchange_connectors::ExchangeError;
+use crate::strategies::core::{ExecutionOrderType, NotionalLimit};
+use crate::models::{exchange_connection, user_profile};
+use crate::exchange_connectors::Exchange;
+use crate::utils::errors::AppError;
+use crate::utils::metrics::metrics;
+
+/// How often a resting limit order is polled for fills
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Effective notional limit for a strategy's orders: the tighter of the user's profile
+/// limit and the strategy's own `max_order_notional`
+pub async fn notional_limit_for(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    strategy_parameters: &serde_json::Value,
+) -> Result<Option<NotionalLimit>, AppError> {
+    let user_limit = user_profile::Entity::find()
+        .filter(user_profile::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?
+        .and_then(|profile| profile.notional_limit());
+    let strategy_limit = NotionalLimit::from_parameters(strategy_parameters)?;
+
+    Ok(NotionalLimit::strictest(user_limit, strategy_limit))
+}
+
+/// Commission a user pays on `exchange`: the fee tier of their active connection to it,
+/// or the exchange's base tier when they have none
+pub async fn fee_rates_for(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    exchange: &Exchange,
+) -> Result<FeeRates, AppError> {
+    let connection = exchange_connection::Entity::find()
+        .filter(exchange_connection::Column::UserId.eq(user_id))
+        .filter(exchange_connection::Column::IsActive.eq(true))
+        .all(db)
+        .await
+        .map_err(AppError::DatabaseError)?
+        .into_iter()
+        .find(|connection| Exchange::from_str(&connection.exchange_name).as_ref() == Some(exchange));
+
+    match connection {
+        Some(connection) => connection.fee_rates(),
+        None => FeeSchedule::for_exchange(exchange).rates(BASE_FEE_TIER, false),
+    }
+}
+
+/// Orders placed to execute one strategy trade
+#[derive(Debug, Clone)]
+pub struct ExecutedOrder {
+    /// The limit order (if any) followed by the market order (if any)
+    pub orders: Vec<Order>,
+    pub filled_quantity: Decimal,
+    /// The limit order timed out and the remainder was sent at market
+    pub fell_back_to_market: bool,
+    /// Commission the fills cost at the executor's fee rates, in the quote asset
+    pub estimated_fee: Decimal,
+}
+
+/// Places spot orders the way a strategy's `execution_order_type` asks for.
+///
+/// Nothing places live orders through this yet: the live DCA engine still simulates its
+/// fills, because stored API keys can only be decrypted with the user's password.
+pub struct OrderExecutor<'a, C: ?Sized> {
+    connector: &'a C,
+    poll_interval: Duration,
+    notional_limit: Option<NotionalLimit>,
+    fee_rates: Option<FeeRates>,
+}
+
+impl<'a, C> OrderExecutor<'a, C>
+where
+    C: TradeExecutionAPI + OrderAPI + ?Sized,
+{
+    pub fn new(connector: &'a C) -> Self {
+        Self {
+            connector,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            notional_limit: None,
+            fee_rates: None,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Reject (or clamp) any order whose notional exceeds `limit`, whatever the strategy asked for
+    pub fn with_notional_limit(mut self, limit: Option<NotionalLimit>) -> Self {
+        self.notional_limit = limit;
+        self
+    }
+
+    /// Estimate the commission of each execution from the account's fee tier
+    pub fn with_fee_rates(mut self, rates: FeeRates) -> Self {
+        self.fee_rates = Some(rates);
+        self
+    }
+
+    /// Buy or sell `quantity` of `symbol`. Limit orders rest at the configured offset
+    /// from `reference_price`; once the fallback timeout passes the order is cancelled
+    /// and any unfilled quantity is sent as a market order.
+    pub async fn execute(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        quantity: Decimal,
+        reference_price: Decimal,
+        order_type: &ExecutionOrderType,
+    ) -> Result<ExecutedOrder, ExchangeError> {
+        let quantity = self.check_notional(symbol, &side, quantity, reference_price)?;
+
+        let (limit_price, fallback_after) = match (
+            order_type.limit_price(&side, reference_price),
+            order_type.fallback_after(),
+        ) {
+            (Some(price), Some(fallback_after)) => (price, fallback_after),
+            _ => {
+                let order = self.market(symbol, side, quantity).await?;
+                return Ok(self.executed(vec![order], false, reference_price));
+            }
+        };
+
+        let placed = self
+            .connector
+            .place_order(OrderRequest {
+                symbol: symbol.to_string(),
+                side: side.clone(),
+                order_type: OrderType::Limit,
+                quantity: Some(quantity),
+                quote_quantity: None,
+                price: Some(limit_price),
+                time_in_force: Some(TimeInForce::GTC),
+                wallet_type: WalletType::Spot,
+            })
+            .await?;
+        info!("Placed {:?} limit order {} for {} {} @ {}", side, placed.order_id, quantity, symbol, limit_price);
+
+        let deadline = Instant::now() + fallback_after;
+        let mut order = placed;
+        while order.status != OrderStatus::Filled && Instant::now() < deadline {
+            sleep(self.poll_interval.min(deadline.saturating_duration_since(Instant::now()))).await;
+            order = self.connector.get_order(&order.order_id, symbol, WalletType::Spot).await?;
+            if matches!(order.status, OrderStatus::Canceled | OrderStatus::Rejected | OrderStatus::Expired) {
+                break;
+            }
+        }
+
+        if order.status == OrderStatus::Filled {
+            return Ok(self.executed(vec![order], false, reference_price));
+        }
+
+        // Still open: cancel first so the limit can't fill alongside the market order
+        if matches!(order.status, OrderStatus::New | OrderStatus::PartiallyFilled) {
+            order = match self.connector.cancel_order(&order.order_id, symbol, WalletType::Spot).await {
+                Ok(cancelled) => cancelled,
+                Err(e) => {
+                    // It may have filled between the last poll and the cancel
+                    warn!("Failed to cancel limit order {}: {}", order.order_id, e);
+                    let current = self.connector.get_order(&order.order_id, symbol, WalletType::Spot).await?;
+                    // A market order now could fill alongside the resting limit order
+                    if matches!(current.status, OrderStatus::New | OrderStatus::PartiallyFilled) {
+                        return Err(ExchangeError::ApiError(format!(
+                            "Limit order {} on {} could not be cancelled and is still open: {}",
+                            current.order_id, symbol, e
+                        )));
+                    }
+                    current
+                }
+            };
+        }
+
+        let remaining = quantity - order.executed_quantity;
+        let mut orders = vec![order];
+        let mut fell_back_to_market = false;
+        if remaining > Decimal::ZERO {
+            info!("Limit order on {} unfilled after {:?}; sending {} at market", symbol, fallback_after, remaining);
+            orders.push(self.market(symbol, side, remaining).await?);
+            fell_back_to_market = true;
+        }
+
+        Ok(self.executed(orders, fell_back_to_market, reference_price))
+    }
+
+    /// Summarize the orders of one execution; limit fills pay the maker rate, market fills the taker rate
+    fn executed(&self, orders: Vec<Order>, fell_back_to_market: bool, reference_price: Decimal) -> ExecutedOrder {
+        let filled_quantity = orders.iter().map(|order| order.executed_quantity).sum();
+        let estimated_fee = match self.fee_rates {
+            Some(rates) => orders
+                .iter()
+                .map(|order| {
+                    let price = order.average_price.or(order.price).unwrap_or(reference_price);
+                    rates.fee(order.executed_quantity * price, order.order_type == OrderType::Limit)
+                })
+                .sum(),
+            None => Decimal::ZERO,
+        };
+
+        ExecutedOrder {
+            orders,
+            filled_quantity,
+            fell_back_to_market,
+            estimated_fee,
+        }
+    }
+
+    /// Last-line guard against oversized orders, independent of strategy sizing
+    fn check_notional(&self, symbol: &str, side: &OrderSide, quantity: Decimal, price: Decimal) -> Result<Decimal, ExchangeError> {
+        let Some(limit) = self.notional_limit else {
+            return Ok(quantity);
+        };
+
+        match limit.apply(quantity, price) {
+            Ok(allowed) if allowed == quantity => Ok(quantity),
+            Ok(allowed) => {
+                warn!(
+                    "Clamped {:?} order on {} from {} to {} to stay within max notional {}",
+                    side, symbol, quantity, allowed, limit.max_order_notional
+                );
+                metrics().record_notional_limit_hit("clamped");
+                Ok(allowed)
+            }
+            Err(reason) => {
+                warn!("Rejected {:?} order for {} {}: {}", side, quantity, symbol, reason);
+                metrics().record_notional_limit_hit("rejected");
+                Err(ExchangeError::InvalidOrder(reason))
+            }
+        }
+    }
+
+    async fn market(&self, symbol: &str, side: OrderSide, quantity: Decimal) -> Result<Order, ExchangeError> {
+        self.connector
+            .place_order(OrderRequest {
+                symbol: symbol.to_string(),
+                side,
+                order_type: OrderType::Market,
+                quantity: Some(quantity),
+                quote_quantity: None,
+                price: None,
+                time_in_force: None,
+                wallet_type: WalletType::Spot,
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use std::sync::Mutex;
+    use crate::exchange_connectors::common_types::{OcoOrder, OrderType};
+    use crate::exchange_connectors::traits::ExchangeConnector;
+
+    /// Exchange where limit orders fill `limit_fill` of their quantity and market orders fill fully
+    struct MockExchange {
+        limit_fill: Decimal,
+        placed: Mutex<Vec<(OrderType, Decimal, Option<Decimal>)>>,
+        cancelled: Mutex<bool>,
+        cancel_fails: bool,
+    }
+
+    impl MockExchange {
+        fn new(limit_fill: Decimal) -> Self {
+            Self {
+                limit_fill,
+                placed: Mutex::new(Vec::new()),
+                cancelled: Mutex::new(false),
+                cancel_fails: false,
+            }
+        }
+
+        fn order(&self, order_type: OrderType, status: OrderStatus, quantity: Decimal, executed: Decimal, price: Option<Decimal>) -> Order {
+            Order {
+                order_id: format!("{:?}", order_type),
+                client_order_id: None,
+                symbol: "BTCUSDT".to_string(),
+                side: OrderSide::Buy,
+                order_type,
+                status,
+                time_in_force: TimeInForce::GTC,
+                price,
+                stop_price: None,
+                quantity,
+                executed_quantity: executed,
+                cumulative_quote_quantity: Decimal::ZERO,
+                average_price: price,
+                fee: None,
+                fee_asset: None,
+                pnl: None,
+                created_time: Utc::now(),
+                updated_time: Utc::now(),
+                wallet_type: WalletType::Spot,
+            }
+        }
+
+        fn limit_state(&self) -> Order {
+            let quantity = Decimal::ONE;
+            let status = if *self.cancelled.lock().unwrap() {
+                OrderStatus::Canceled
+            } else if self.limit_fill >= quantity {
+                OrderStatus::Filled
+            } else if self.limit_fill > Decimal::ZERO {
+                OrderStatus::PartiallyFilled
+            } else {
+                OrderStatus::New
+            };
+            self.order(OrderType::Limit, status, quantity, self.limit_fill, Some(Decimal::from(99)))
+        }
+    }
+
+    #[async_trait]
+    impl ExchangeConnector for MockExchange {
+        async fn test_connection(&self) -> Result<bool, ExchangeError> {
+            Ok(true)
+        }
+    }
+
+    #[async_trait]
+    impl TradeExecutionAPI for MockExchange {
+        async fn place_market_order(&self, _: &str, _: OrderSide, quantity: Option<Decimal>, _: Option<Decimal>, _: WalletType) -> Result<Order, ExchangeError> {
+            let quantity = quantity.unwrap_or_default();
+            self.placed.lock().unwrap().push((OrderType::Market, quantity, None));
+            Ok(self.order(OrderType::Market, OrderStatus::Filled, quantity, quantity, None))
+        }
+
+        async fn place_limit_order(&self, _: &str, _: OrderSide, price: Decimal, quantity: Decimal, _: TimeInForce, _: WalletType) -> Result<Order, ExchangeError> {
+            self.placed.lock().unwrap().push((OrderType::Limit, quantity, Some(price)));
+            Ok(self.order(OrderType::Limit, OrderStatus::New, quantity, Decimal::ZERO, Some(price)))
+        }
+
+        async fn place_stop_loss_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<Order, ExchangeError> {
+            Err(ExchangeError::NotSupported("stop loss".to_string()))
+        }
+
+        async fn place_take_profit_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<Order, ExchangeError> {
+            Err(ExchangeError::NotSupported("take profit".to_string()))
+        }
+
+        async fn place_oco_order(&self, _: &str, _: OrderSide, _: Decimal, _: Decimal, _: Decimal, _: Option<Decimal>, _: WalletType) -> Result<OcoOrder, ExchangeError> {
+            Err(ExchangeError::NotSupported("oco".to_string()))
+        }
+    }
+
+    #[async_trait]
+    impl OrderAPI for MockExchange {
+        async fn get_open_orders(&self, _: Option<&str>, _: WalletType) -> Result<Vec<Order>, ExchangeError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_order(&self, _: &str, _: &str, _: WalletType) -> Result<Order, ExchangeError> {
+            Ok(self.limit_state())
+        }
+
+        async fn get_order_history(&self, _: Option<&str>, _: WalletType, _: Option<DateTime<Utc>>, _: Option<DateTime<Utc>>, _: Option<u32>) -> Result<Vec<Order>, ExchangeError> {
+            Ok(Vec::new())
+        }
+
+        async fn cancel_order(&self, _: &str, _: &str, _: WalletType) -> Result<Order, ExchangeError> {
+            if self.cancel_fails {
+                return Err(ExchangeError::NetworkError("connection reset".to_string()));
+            }
+            *self.cancelled.lock().unwrap() = true;
+            Ok(self.limit_state())
+        }
+
+        async fn cancel_all_orders(&self, _: Option<&str>, _: WalletType) -> Result<Vec<Order>, ExchangeError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn limit(offset_bps: u32, fallback_after_secs: u64) -> ExecutionOrderType {
+        ExecutionOrderType::Limit { offset_bps, fallback_after_secs }
+    }
+
+    #[tokio::test]
+    async fn test_filled_limit_order_does_not_fall_back() {
+        let exchange = MockExchange::new(Decimal::ONE);
+        let executed = OrderExecutor::new(&exchange)
+            .with_poll_interval(Duration::from_millis(1))
+            .execute("BTCUSDT", OrderSide::Buy, Decimal::ONE, Decimal::from(100), &limit(100, 60))
+            .await
+            .unwrap();
+
+        assert!(!executed.fell_back_to_market);
+        assert_eq!(executed.filled_quantity, Decimal::ONE);
+        let placed = exchange.placed.lock().unwrap();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0], (OrderType::Limit, Decimal::ONE, Some(Decimal::from(99))));
+    }
+
+    #[tokio::test]
+    async fn test_partially_filled_limit_falls_back_to_market_after_timeout() {
+        let exchange = MockExchange::new(Decimal::new(4, 1));
+        let executed = OrderExecutor::new(&exchange)
+            .execute("BTCUSDT", OrderSide::Buy, Decimal::ONE, Decimal::from(100), &limit(100, 0))
+            .await
+            .unwrap();
+
+        // 0.4 filled as maker before the timeout; the other 0.6 goes at market
+        assert!(*exchange.cancelled.lock().unwrap());
+        assert!(executed.fell_back_to_market);
+        assert_eq!(executed.filled_quantity, Decimal::ONE);
+        let placed = exchange.placed.lock().unwrap();
+        assert_eq!(placed[1], (OrderType::Market, Decimal::new(6, 1), None));
+    }
+
+    #[tokio::test]
+    async fn test_open_limit_order_that_cannot_be_cancelled_does_not_fall_back() {
+        let exchange = MockExchange { cancel_fails: true, ..MockExchange::new(Decimal::new(4, 1)) };
+        let result = OrderExecutor::new(&exchange)
+            .execute("BTCUSDT", OrderSide::Buy, Decimal::ONE, Decimal::from(100), &limit(100, 0))
+            .await;
+
+        // The limit order is still resting, so a market order could double the position
+        assert!(matches!(result, Err(ExchangeError::ApiError(_))));
+        let placed = exchange.placed.lock().unwrap();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].0, OrderType::Limit);
+    }
+
+    #[tokio::test]
+    async fn test_market_order_type_places_market_order() {
+        let exchange = MockExchange::new(Decimal::ZERO);
+        let executed = OrderExecutor::new(&exchange)
+            .execute("BTCUSDT", OrderSide::Sell, Decimal::from(2), Decimal::from(100), &ExecutionOrderType::Market)
+            .await
+            .unwrap();
+
+        assert!(!executed.fell_back_to_market);
+        assert_eq!(exchange.placed.lock().unwrap()[0], (OrderType::Market, Decimal::from(2), None));
+    }
+
+    #[tokio::test]
+    async fn test_higher_fee_tier_lowers_estimated_fee() {
+        let schedule = FeeSchedule::binance_spot();
+        let mut fees = Vec::new();
+        for tier in [BASE_FEE_TIER, 5] {
+            let exchange = MockExchange::new(Decimal::new(4, 1));
+            let executed = OrderExecutor::new(&exchange)
+                .with_fee_rates(schedule.rates(tier, false).unwrap())
+                .execute("BTCUSDT", OrderSide::Buy, Decimal::ONE, Decimal::from(100), &limit(100, 0))
+                .await
+                .unwrap();
+            fees.push(executed.estimated_fee);
+        }
+
+        // Base tier: 0.4 @ 99 as maker plus 0.6 @ 100 as taker, both at 10 bps
+        assert_eq!(fees[0], Decimal::new(996, 4));
+        // VIP 5: 3.6 bps maker, 4.8 bps taker
+        assert_eq!(fees[1], Decimal::new(43056, 6));
+    }
+
+    #[tokio::test]
+    async fn test_fee_rates_follow_the_connection_tier() {
+        use crate::database::test_support::{seed_exchange_connection, seed_user, test_db};
+        use sea_orm::{ActiveModelTrait, Set};
+
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let schedule = FeeSchedule::binance_spot();
+
+        // No connection yet: base tier
+        let rates = fee_rates_for(&db, user_id, &Exchange::Binance).await.unwrap();
+        assert_eq!(rates, schedule.rates(BASE_FEE_TIER, false).unwrap());
+
+        let connection_id = seed_exchange_connection(&db, user_id, "binance").await;
+        let connection = exchange_connection::Entity::find_by_id(connection_id).one(&db).await.unwrap().unwrap();
+        let mut active: exchange_connection::ActiveModel = connection.into();
+        active.fee_tier = Set(3);
+        active.fee_token_discount = Set(true);
+        active.update(&db).await.unwrap();
+
+        let rates = fee_rates_for(&db, user_id, &Exchange::Binance).await.unwrap();
+        assert_eq!(rates, schedule.rates(3, true).unwrap());
+    }
+
+    fn notional_limit(clamp: bool) -> Option<NotionalLimit> {
+        Some(NotionalLimit { max_order_notional: Decimal::from(1000), clamp })
+    }
+
+    #[tokio::test]
+    async fn test_oversized_order_is_rejected() {
+        let exchange = MockExchange::new(Decimal::ZERO);
+        let result = OrderExecutor::new(&exchange)
+            .with_notional_limit(notional_limit(false))
+            .execute("BTCUSDT", OrderSide::Buy, Decimal::from(20), Decimal::from(100), &ExecutionOrderType::Market)
+            .await;
+
+        assert!(matches!(result, Err(ExchangeError::InvalidOrder(_))));
+        assert!(exchange.placed.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_order_within_notional_limit_passes() {
+        let exchange = MockExchange::new(Decimal::ZERO);
+        let executed = OrderExecutor::new(&exchange)
+            .with_notional_limit(notional_limit(false))
+            .execute("BTCUSDT", OrderSide::Buy, Decimal::from(10), Decimal::from(100), &ExecutionOrderType::Market)
+            .await
+            .unwrap();
+
+        assert_eq!(executed.filled_quantity, Decimal::from(10));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_order_is_clamped_in_clamp_mode() {
+        let exchange = MockExchange::new(Decimal::ZERO);
+        let executed = OrderExecutor::new(&exchange)
+            .with_notional_limit(notional_limit(true))
+            .execute("BTCUSDT", OrderSide::Sell, Decimal::from(25), Decimal::from(100), &ExecutionOrderType::Market)
+            .await
+            .unwrap();
+
+        assert_eq!(executed.filled_quantity, Decimal::from(10));
+        assert_eq!(exchange.placed.lock().unwrap()[0], (OrderType::Market, Decimal::from(10), None));
+    }
+}
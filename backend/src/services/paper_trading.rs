@@ -0,0 +1,337 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::exchange_connectors::common_types::OrderSide;
+use crate::models::paper_portfolio::{self, trade, PaperPortfolioResponse};
+use crate::models::{dca_strategy, grid_trading_strategy, sma_crossover_strategy};
+use crate::services::emergency_stop::StrategyKind;
+use crate::utils::errors::AppError;
+
+/// Starting balance of a paper portfolio when none is configured
+pub const DEFAULT_PAPER_STARTING_BALANCE: i64 = 10_000;
+
+/// Persistent virtual portfolios strategies trade against in paper mode. Each strategy
+/// gets its own portfolio, seeded with a configurable starting balance that is separate
+/// from any backtest balance.
+pub struct PaperTradingService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl PaperTradingService {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// Turn on paper trading for a strategy, or change its starting balance. A new portfolio
+    /// is seeded with `starting_balance`; for an existing one the new balance applies from
+    /// the next reset, or straight away if it hasn't traded yet.
+    pub async fn configure(
+        &self,
+        user_id: Uuid,
+        strategy_id: Uuid,
+        kind: StrategyKind,
+        starting_balance: Option<Decimal>,
+    ) -> Result<paper_portfolio::Model, AppError> {
+        if starting_balance.is_some_and(|balance| balance <= Decimal::ZERO) {
+            return Err(AppError::BadRequest("Paper starting balance must be positive".to_string()));
+        }
+        self.ensure_owned(user_id, strategy_id, kind).await?;
+
+        let db = self.db.as_ref();
+        let now = Utc::now();
+
+        let Some(existing) = self.find(user_id, strategy_id).await? else {
+            let starting_balance = starting_balance.unwrap_or(Decimal::from(DEFAULT_PAPER_STARTING_BALANCE));
+            let portfolio = paper_portfolio::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                strategy_id: Set(strategy_id),
+                strategy_type: Set(kind.as_str().to_string()),
+                starting_balance: Set(starting_balance),
+                cash_balance: Set(starting_balance),
+                asset_quantity: Set(Decimal::ZERO),
+                reset_at: Set(None),
+                created_at: Set(now),
+                updated_at: Set(now),
+            }
+            .insert(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+            info!("Paper trading enabled for strategy {} with {}", strategy_id, starting_balance);
+            return Ok(portfolio);
+        };
+
+        let Some(starting_balance) = starting_balance else {
+            return Ok(existing);
+        };
+
+        let untouched = existing.asset_quantity.is_zero() && existing.cash_balance == existing.starting_balance
+            && self.trade_count(existing.id).await? == 0;
+        let mut portfolio: paper_portfolio::ActiveModel = existing.into();
+        portfolio.starting_balance = Set(starting_balance);
+        if untouched {
+            portfolio.cash_balance = Set(starting_balance);
+        }
+        portfolio.updated_at = Set(now);
+        portfolio.update(db).await.map_err(AppError::DatabaseError)
+    }
+
+    /// The strategy's paper portfolio and its fills, newest first
+    pub async fn portfolio(&self, user_id: Uuid, strategy_id: Uuid) -> Result<PaperPortfolioResponse, AppError> {
+        let portfolio = self.get(user_id, strategy_id).await?;
+        let trades = trade::Entity::find()
+            .filter(trade::Column::PortfolioId.eq(portfolio.id))
+            .order_by_desc(trade::Column::ExecutedAt)
+            .all(self.db.as_ref())
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        Ok(PaperPortfolioResponse { portfolio, trades })
+    }
+
+    /// Fill a market order of `quantity` at `price` against the paper portfolio, paying
+    /// `fee` in cash on top of a buy or out of a sale's proceeds
+    pub async fn fill(
+        &self,
+        user_id: Uuid,
+        strategy_id: Uuid,
+        side: OrderSide,
+        quantity: Decimal,
+        price: Decimal,
+        fee: Decimal,
+        reason: &str,
+        executed_at: DateTime<Utc>,
+    ) -> Result<trade::Model, AppError> {
+        if quantity <= Decimal::ZERO || price <= Decimal::ZERO {
+            return Err(AppError::BadRequest("Paper order quantity and price must be positive".to_string()));
+        }
+        if fee < Decimal::ZERO {
+            return Err(AppError::BadRequest("Paper order fee cannot be negative".to_string()));
+        }
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        let existing = paper_portfolio::Entity::find()
+            .filter(paper_portfolio::Column::UserId.eq(user_id))
+            .filter(paper_portfolio::Column::StrategyId.eq(strategy_id))
+            .one(&txn)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(not_enabled)?;
+
+        let total_value = quantity * price;
+        let (cash, asset) = match side {
+            OrderSide::Buy if existing.cash_balance < total_value + fee => {
+                return Err(AppError::BadRequest(format!(
+                    "Insufficient paper balance: {} needed, {} available",
+                    total_value + fee, existing.cash_balance
+                )));
+            }
+            OrderSide::Buy => (existing.cash_balance - total_value - fee, existing.asset_quantity + quantity),
+            OrderSide::Sell if existing.asset_quantity < quantity => {
+                return Err(AppError::BadRequest(format!(
+                    "Insufficient paper position: {} needed, {} held",
+                    quantity, existing.asset_quantity
+                )));
+            }
+            OrderSide::Sell => (existing.cash_balance + total_value - fee, existing.asset_quantity - quantity),
+        };
+
+        let portfolio_id = existing.id;
+        let mut portfolio: paper_portfolio::ActiveModel = existing.into();
+        portfolio.cash_balance = Set(cash);
+        portfolio.asset_quantity = Set(asset);
+        portfolio.updated_at = Set(Utc::now());
+        portfolio.update(&txn).await.map_err(AppError::DatabaseError)?;
+
+        let fill = trade::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            portfolio_id: Set(portfolio_id),
+            side: Set(match side {
+                OrderSide::Buy => "buy",
+                OrderSide::Sell => "sell",
+            }.to_string()),
+            price: Set(price),
+            quantity: Set(quantity),
+            total_value: Set(total_value),
+            reason: Set(reason.to_string()),
+            executed_at: Set(executed_at),
+            created_at: Set(Utc::now()),
+        }
+        .insert(&txn)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        Ok(fill)
+    }
+
+    /// Start paper trading fresh: restore the starting balance, drop the position and
+    /// clear the paper trade history
+    pub async fn reset(&self, user_id: Uuid, strategy_id: Uuid) -> Result<paper_portfolio::Model, AppError> {
+        let existing = self.get(user_id, strategy_id).await?;
+        let now = Utc::now();
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        let cleared = trade::Entity::delete_many()
+            .filter(trade::Column::PortfolioId.eq(existing.id))
+            .exec(&txn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let starting_balance = existing.starting_balance;
+        let mut portfolio: paper_portfolio::ActiveModel = existing.into();
+        portfolio.cash_balance = Set(starting_balance);
+        portfolio.asset_quantity = Set(Decimal::ZERO);
+        portfolio.reset_at = Set(Some(now));
+        portfolio.updated_at = Set(now);
+        let portfolio = portfolio.update(&txn).await.map_err(AppError::DatabaseError)?;
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+
+        info!("Paper portfolio of strategy {} reset to {}, {} trades cleared", strategy_id, starting_balance, cleared.rows_affected);
+        Ok(portfolio)
+    }
+
+    async fn find(&self, user_id: Uuid, strategy_id: Uuid) -> Result<Option<paper_portfolio::Model>, AppError> {
+        paper_portfolio::Entity::find()
+            .filter(paper_portfolio::Column::UserId.eq(user_id))
+            .filter(paper_portfolio::Column::StrategyId.eq(strategy_id))
+            .one(self.db.as_ref())
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn get(&self, user_id: Uuid, strategy_id: Uuid) -> Result<paper_portfolio::Model, AppError> {
+        self.find(user_id, strategy_id).await?.ok_or_else(not_enabled)
+    }
+
+    async fn trade_count(&self, portfolio_id: Uuid) -> Result<u64, AppError> {
+        trade::Entity::find()
+            .filter(trade::Column::PortfolioId.eq(portfolio_id))
+            .count(self.db.as_ref())
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    /// Fails with `NotFound` unless `strategy_id` is a `kind` strategy of the user
+    async fn ensure_owned(&self, user_id: Uuid, strategy_id: Uuid, kind: StrategyKind) -> Result<(), AppError> {
+        let db = self.db.as_ref();
+        let owner = match kind {
+            StrategyKind::Dca => dca_strategy::Entity::find_by_id(strategy_id)
+                .one(db)
+                .await
+                .map(|strategy| strategy.map(|s| s.user_id)),
+            StrategyKind::SmaCrossover => sma_crossover_strategy::Entity::find_by_id(strategy_id)
+                .one(db)
+                .await
+                .map(|strategy| strategy.map(|s| s.user_id)),
+            StrategyKind::GridTrading => grid_trading_strategy::Entity::find_by_id(strategy_id)
+                .one(db)
+                .await
+                .map(|strategy| strategy.map(|s| s.user_id)),
+        }
+        .map_err(AppError::DatabaseError)?;
+
+        match owner {
+            Some(owner) if owner == user_id => Ok(()),
+            _ => Err(AppError::NotFound("Strategy not found".to_string())),
+        }
+    }
+}
+
+fn not_enabled() -> AppError {
+    AppError::NotFound("Paper trading is not enabled for this strategy".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{seed_dca_strategy, seed_user, test_db};
+
+    async fn setup() -> (PaperTradingService, Uuid, Uuid) {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let strategy_id = seed_dca_strategy(&db, user_id, "BTC").await;
+        (PaperTradingService::new(Arc::new(db)), user_id, strategy_id)
+    }
+
+    #[tokio::test]
+    async fn test_configure_seeds_the_starting_balance() {
+        let (service, user_id, strategy_id) = setup().await;
+
+        let portfolio = service
+            .configure(user_id, strategy_id, StrategyKind::Dca, Some(Decimal::from(2500)))
+            .await
+            .unwrap();
+        assert_eq!(portfolio.starting_balance, Decimal::from(2500));
+        assert_eq!(portfolio.cash_balance, Decimal::from(2500));
+        assert_eq!(portfolio.asset_quantity, Decimal::ZERO);
+        assert_eq!(portfolio.strategy_type, "dca");
+
+        // An untouched portfolio is reseeded when the balance changes
+        let portfolio = service
+            .configure(user_id, strategy_id, StrategyKind::Dca, Some(Decimal::from(4000)))
+            .await
+            .unwrap();
+        assert_eq!(portfolio.cash_balance, Decimal::from(4000));
+
+        let other_user = seed_user(service.db.as_ref()).await;
+        assert!(matches!(
+            service.configure(other_user, strategy_id, StrategyKind::Dca, None).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.configure(user_id, strategy_id, StrategyKind::GridTrading, None).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.configure(user_id, strategy_id, StrategyKind::Dca, Some(Decimal::ZERO)).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reset_restores_balance_and_clears_trades() {
+        let (service, user_id, strategy_id) = setup().await;
+        service.configure(user_id, strategy_id, StrategyKind::Dca, Some(Decimal::from(1000))).await.unwrap();
+
+        service
+            .fill(user_id, strategy_id, OrderSide::Buy, Decimal::from(2), Decimal::from(300), Decimal::ZERO, "scheduled", Utc::now())
+            .await
+            .unwrap();
+        service
+            .fill(user_id, strategy_id, OrderSide::Sell, Decimal::ONE, Decimal::from(350), Decimal::ZERO, "take_profit", Utc::now())
+            .await
+            .unwrap();
+        assert!(service
+            .fill(user_id, strategy_id, OrderSide::Buy, Decimal::from(5), Decimal::from(300), Decimal::ZERO, "scheduled", Utc::now())
+            .await
+            .is_err());
+
+        let traded = service.portfolio(user_id, strategy_id).await.unwrap();
+        assert_eq!(traded.trades.len(), 2);
+        assert_eq!(traded.portfolio.cash_balance, Decimal::from(750));
+        assert_eq!(traded.portfolio.asset_quantity, Decimal::ONE);
+
+        // A new starting balance on a traded portfolio waits for the reset
+        let portfolio = service
+            .configure(user_id, strategy_id, StrategyKind::Dca, Some(Decimal::from(5000)))
+            .await
+            .unwrap();
+        assert_eq!(portfolio.cash_balance, Decimal::from(750));
+
+        let reset = service.reset(user_id, strategy_id).await.unwrap();
+        assert_eq!(reset.cash_balance, Decimal::from(5000));
+        assert_eq!(reset.asset_quantity, Decimal::ZERO);
+        assert!(reset.reset_at.is_some());
+        assert!(service.portfolio(user_id, strategy_id).await.unwrap().trades.is_empty());
+    }
+}
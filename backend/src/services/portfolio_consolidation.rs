@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use futures::future::join_all;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::exchange_connectors::common_types::{AccountBalances, AssetBalance};
+use crate::exchange_connectors::traits::AccountAPI;
+use crate::exchange_connectors::ExchangeError;
+
+/// The exchange connection a set of balances was fetched from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceSource {
+    pub connection_id: Uuid,
+    pub exchange_name: String,
+    pub display_name: String,
+}
+
+/// One connection's share of an asset (all of its wallets combined)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeHolding {
+    pub connection_id: Uuid,
+    pub exchange_name: String,
+    pub display_name: String,
+    #[serde(with = "crate::utils::precision::crypto")]
+    pub quantity: Decimal,
+    #[serde(with = "crate::utils::precision::usd_option")]
+    pub usd_value: Option<Decimal>,
+}
+
+/// An asset merged across every connection that holds it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidatedAsset {
+    pub asset: String,
+    #[serde(with = "crate::utils::precision::crypto")]
+    pub total_quantity: Decimal,
+    /// Sum of the priced holdings; unpriced holdings are left out
+    #[serde(with = "crate::utils::precision::usd")]
+    pub total_usd_value: Decimal,
+    pub exchanges: Vec<ExchangeHolding>,
+}
+
+/// A connection whose balances could not be fetched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedSource {
+    #[serde(flatten)]
+    pub source: BalanceSource,
+    pub error: String,
+}
+
+/// Per-asset view of a user's holdings across all exchange connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidatedPortfolio {
+    /// Assets ordered by USD value, largest first
+    pub assets: Vec<ConsolidatedAsset>,
+    #[serde(with = "crate::utils::precision::usd")]
+    pub total_usd_value: Decimal,
+    pub failed_connections: Vec<FailedSource>,
+}
+
+/// Fetch balances from every connector concurrently. Results are returned in input order.
+pub async fn fetch_all_balances<C>(
+    connectors: Vec<(BalanceSource, Arc<C>)>,
+) -> Vec<(BalanceSource, Result<AccountBalances, ExchangeError>)>
+where
+    C: AccountAPI + ?Sized,
+{
+    join_all(connectors.into_iter().map(|(source, connector)| async move {
+        let balances = connector.get_all_balances().await;
+        (source, balances)
+    }))
+    .await
+}
+
+/// Merge holdings of the same asset across connections and wallet types
+pub fn consolidate(results: Vec<(BalanceSource, Result<AccountBalances, ExchangeError>)>) -> ConsolidatedPortfolio {
+    let mut assets: BTreeMap<String, ConsolidatedAsset> = BTreeMap::new();
+    let mut failed_connections = Vec::new();
+
+    for (source, result) in results {
+        let balances = match result {
+            Ok(balances) => balances,
+            Err(e) => {
+                failed_connections.push(FailedSource { source, error: e.to_string() });
+                continue;
+            }
+        };
+
+        // Combine this connection's wallets first so it appears once per asset
+        let mut per_asset: BTreeMap<String, (Decimal, Option<Decimal>)> = BTreeMap::new();
+        for balance in wallet_balances(&balances) {
+            if balance.total <= Decimal::ZERO {
+                continue;
+            }
+            let entry = per_asset
+                .entry(balance.asset.to_uppercase())
+                .or_insert((Decimal::ZERO, None));
+            entry.0 += balance.total;
+            if let Some(usd_value) = balance.usd_value {
+                entry.1 = Some(entry.1.unwrap_or_default() + usd_value);
+            }
+        }
+
+        for (asset, (quantity, usd_value)) in per_asset {
+            let consolidated = assets.entry(asset.clone()).or_insert_with(|| ConsolidatedAsset {
+                asset,
+                total_quantity: Decimal::ZERO,
+                total_usd_value: Decimal::ZERO,
+                exchanges: Vec::new(),
+            });
+            consolidated.total_quantity += quantity;
+            consolidated.total_usd_value += usd_value.unwrap_or_default();
+            consolidated.exchanges.push(ExchangeHolding {
+                connection_id: source.connection_id,
+                exchange_name: source.exchange_name.clone(),
+                display_name: source.display_name.clone(),
+                quantity,
+                usd_value,
+            });
+        }
+    }
+
+    let mut assets: Vec<ConsolidatedAsset> = assets.into_values().collect();
+    assets.sort_by(|a, b| b.total_usd_value.cmp(&a.total_usd_value));
+    let total_usd_value = assets.iter().map(|a| a.total_usd_value).sum();
+
+    ConsolidatedPortfolio {
+        assets,
+        total_usd_value,
+        failed_connections,
+    }
+}
+
+/// Every asset balance across the spot, margin and futures wallets
+fn wallet_balances(balances: &AccountBalances) -> impl Iterator<Item = &AssetBalance> {
+    balances
+        .spot
+        .iter()
+        .flat_map(|spot| spot.balances.iter())
+        .chain(balances.margin.iter().flat_map(|margin| margin.balances.iter()))
+        .chain(balances.futures_usdm.iter().flat_map(|futures| futures.balances.iter()))
+        .chain(balances.futures_coinm.iter().flat_map(|futures| futures.balances.iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use crate::exchange_connectors::common_types::{
+        FuturesAccount, FuturesType, MarginAccount, PriceSource, SpotAccount, WalletType,
+    };
+    use crate::exchange_connectors::traits::ExchangeConnector;
+
+    /// Connector that reports a fixed spot account
+    struct MockConnector {
+        balances: Vec<(&'static str, Decimal, Decimal)>,
+    }
+
+    #[async_trait]
+    impl ExchangeConnector for MockConnector {
+        async fn test_connection(&self) -> Result<bool, ExchangeError> {
+            Ok(true)
+        }
+    }
+
+    #[async_trait]
+    impl AccountAPI for MockConnector {
+        async fn get_spot_account(&self) -> Result<SpotAccount, ExchangeError> {
+            let balances: Vec<AssetBalance> = self
+                .balances
+                .iter()
+                .map(|(asset, quantity, usd_value)| AssetBalance {
+                    asset: asset.to_string(),
+                    free: *quantity,
+                    locked: Decimal::ZERO,
+                    total: *quantity,
+                    usd_value: Some(*usd_value),
+                    btc_value: None,
+                    wallet_type: WalletType::Spot,
+                    priced: true,
+                    price_source: Some(PriceSource::ExchangeTicker),
+                })
+                .collect();
+
+            Ok(SpotAccount {
+                total_usd_value: Some(balances.iter().filter_map(|b| b.usd_value).sum()),
+                balances,
+                unpriced_assets: 0,
+                total_btc_value: None,
+                maker_commission: None,
+                taker_commission: None,
+                can_trade: true,
+                can_withdraw: true,
+                can_deposit: true,
+                last_update_time: Utc::now(),
+            })
+        }
+
+        async fn get_margin_account(&self) -> Result<MarginAccount, ExchangeError> {
+            Err(ExchangeError::NotSupported("margin".to_string()))
+        }
+
+        async fn get_futures_account(&self, _account_type: FuturesType) -> Result<FuturesAccount, ExchangeError> {
+            Err(ExchangeError::NotSupported("futures".to_string()))
+        }
+
+        async fn get_all_balances(&self) -> Result<AccountBalances, ExchangeError> {
+            let spot = self.get_spot_account().await?;
+            Ok(AccountBalances {
+                total_usd_value: spot.total_usd_value.unwrap_or_default(),
+                total_btc_value: Decimal::ZERO,
+                spot: Some(spot),
+                margin: None,
+                futures_usdm: None,
+                futures_coinm: None,
+            })
+        }
+    }
+
+    fn source(exchange_name: &str) -> BalanceSource {
+        BalanceSource {
+            connection_id: Uuid::new_v4(),
+            exchange_name: exchange_name.to_string(),
+            display_name: format!("My {}", exchange_name),
+        }
+    }
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_merges_same_asset_across_exchanges() {
+        let binance = source("binance");
+        let coinbase = source("coinbase");
+        let connectors = vec![
+            (
+                binance.clone(),
+                Arc::new(MockConnector {
+                    balances: vec![("BTC", dec("1.5"), dec("90000")), ("ETH", dec("2"), dec("6000"))],
+                }),
+            ),
+            (
+                coinbase.clone(),
+                Arc::new(MockConnector {
+                    balances: vec![("btc", dec("0.8"), dec("48000"))],
+                }),
+            ),
+        ];
+
+        let portfolio = consolidate(fetch_all_balances(connectors).await);
+
+        assert!(portfolio.failed_connections.is_empty());
+        assert_eq!(portfolio.total_usd_value, dec("144000"));
+
+        let btc = &portfolio.assets[0];
+        assert_eq!(btc.asset, "BTC");
+        assert_eq!(btc.total_quantity, dec("2.3"));
+        assert_eq!(btc.total_usd_value, dec("138000"));
+        assert_eq!(btc.exchanges.len(), 2);
+        assert_eq!(btc.exchanges[0].connection_id, binance.connection_id);
+        assert_eq!(btc.exchanges[0].quantity, dec("1.5"));
+        assert_eq!(btc.exchanges[1].connection_id, coinbase.connection_id);
+        assert_eq!(btc.exchanges[1].quantity, dec("0.8"));
+
+        let eth = &portfolio.assets[1];
+        assert_eq!(eth.asset, "ETH");
+        assert_eq!(eth.exchanges.len(), 1);
+    }
+
+    #[test]
+    fn test_failed_connection_is_reported_not_merged() {
+        let failing = source("binance");
+        let portfolio = consolidate(vec![(
+            failing.clone(),
+            Err(ExchangeError::NotSupported("down".to_string())),
+        )]);
+
+        assert!(portfolio.assets.is_empty());
+        assert_eq!(portfolio.failed_connections.len(), 1);
+        assert_eq!(portfolio.failed_connections[0].source.connection_id, failing.connection_id);
+    }
+}
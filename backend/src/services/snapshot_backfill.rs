@@ -0,0 +1,337 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::backtesting::BinanceFetcher;
+use crate::exchange_connectors::KlineInterval;
+use crate::models::balance_snapshot::{self, BackfillSummary, SOURCE_BACKFILL};
+use crate::models::dca_strategy::{self, execution};
+use crate::strategies::core::StrategyMode;
+use crate::utils::errors::AppError;
+
+/// Longest range accepted in a single backfill request
+const MAX_BACKFILL_DAYS: i64 = 366;
+
+/// A filled trade reduced to its effect on holdings
+#[derive(Debug, Clone)]
+pub struct HistoricalTrade {
+    pub asset: String,
+    pub timestamp: DateTime<Utc>,
+    /// Positive for buys, negative for sells
+    pub quantity: Decimal,
+}
+
+/// Reconstructs past daily portfolio values from recorded executions and historical
+/// daily closes, writing one `balance_snapshots` row per missing date.
+///
+/// Dates that already have a snapshot are never touched, so an interrupted run can be
+/// resumed by calling it again with the same range.
+#[derive(Clone)]
+pub struct SnapshotBackfillService {
+    db: Arc<DatabaseConnection>,
+    fetcher: Arc<BinanceFetcher>,
+}
+
+impl SnapshotBackfillService {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self::with_fetcher(db, Arc::new(BinanceFetcher::new()))
+    }
+
+    /// Use a specific fetcher for historical prices
+    pub fn with_fetcher(db: Arc<DatabaseConnection>, fetcher: Arc<BinanceFetcher>) -> Self {
+        Self { db, fetcher }
+    }
+
+    /// Backfill snapshots for `user_id` between `start_date` and `end_date` (inclusive)
+    pub async fn backfill(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<BackfillSummary, AppError> {
+        if start_date > end_date {
+            return Err(AppError::BadRequest("start_date must not be after end_date".to_string()));
+        }
+        if end_date >= Utc::now().date_naive() {
+            return Err(AppError::BadRequest(
+                "Backfill only covers past dates; today's value comes from live balances".to_string(),
+            ));
+        }
+        if (end_date - start_date).num_days() >= MAX_BACKFILL_DAYS {
+            return Err(AppError::BadRequest(format!(
+                "Backfill range cannot exceed {} days",
+                MAX_BACKFILL_DAYS
+            )));
+        }
+
+        let mut summary = BackfillSummary::default();
+
+        let existing: HashSet<NaiveDate> = balance_snapshot::Entity::find()
+            .filter(balance_snapshot::Column::UserId.eq(user_id))
+            .filter(balance_snapshot::Column::SnapshotDate.gte(start_date))
+            .filter(balance_snapshot::Column::SnapshotDate.lte(end_date))
+            .all(self.db.as_ref())
+            .await
+            .map_err(AppError::DatabaseError)?
+            .into_iter()
+            .map(|snapshot| snapshot.snapshot_date)
+            .collect();
+
+        let pending: Vec<NaiveDate> = start_date
+            .iter_days()
+            .take_while(|date| *date <= end_date)
+            .filter(|date| {
+                let present = existing.contains(date);
+                if present {
+                    summary.skipped_existing += 1;
+                }
+                !present
+            })
+            .collect();
+
+        if pending.is_empty() {
+            info!("Snapshot backfill for user {}: nothing to do", user_id);
+            return Ok(summary);
+        }
+
+        let trades = self.load_trades(user_id).await?;
+        let prices = self.load_daily_closes(&trades, start_date, end_date).await?;
+
+        for date in pending {
+            let Some(value) = value_on(&trades, &prices, date) else {
+                summary.skipped_no_data += 1;
+                continue;
+            };
+
+            let snapshot = balance_snapshot::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                snapshot_date: Set(date),
+                total_usd_value: Set(value),
+                source: Set(SOURCE_BACKFILL.to_string()),
+                created_at: Set(Utc::now()),
+            };
+
+            // The unique (user_id, snapshot_date) index makes a concurrent run's row win
+            match snapshot.insert(self.db.as_ref()).await {
+                Ok(_) => summary.created += 1,
+                Err(e) => {
+                    warn!("Skipping snapshot for user {} on {}: {}", user_id, date, e);
+                    summary.skipped_existing += 1;
+                }
+            }
+        }
+
+        info!(
+            "Snapshot backfill for user {}: {} created, {} already present, {} without data",
+            user_id, summary.created, summary.skipped_existing, summary.skipped_no_data
+        );
+
+        Ok(summary)
+    }
+
+    /// Filled DCA buys and sells across all of the user's strategies; paper fills hold
+    /// nothing and are left out
+    async fn load_trades(&self, user_id: Uuid) -> Result<Vec<HistoricalTrade>, AppError> {
+        let rows = execution::Entity::find()
+            .find_also_related(dca_strategy::Entity)
+            .filter(dca_strategy::Column::UserId.eq(user_id))
+            .filter(execution::Column::OrderStatus.eq("filled"))
+            .filter(execution::Column::Mode.eq(StrategyMode::Live.as_str()))
+            .all(self.db.as_ref())
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let trades = rows
+            .into_iter()
+            .filter_map(|(exec, strategy)| {
+                let strategy = strategy?;
+                let quantity = exec.amount_asset?;
+                let quantity = match exec.execution_type.as_str() {
+                    "buy" => quantity,
+                    "sell" => -quantity,
+                    _ => return None,
+                };
+                Some(HistoricalTrade {
+                    asset: strategy.asset_symbol.to_uppercase(),
+                    timestamp: exec.execution_timestamp,
+                    quantity,
+                })
+            })
+            .collect();
+
+        Ok(trades)
+    }
+
+    /// Daily closes per traded asset, fetched through the shared kline cache
+    async fn load_daily_closes(
+        &self,
+        trades: &[HistoricalTrade],
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<HashMap<String, BTreeMap<NaiveDate, Decimal>>, AppError> {
+        let assets: HashSet<&str> = trades.iter().map(|t| t.asset.as_str()).collect();
+        let start_time = start_date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let end_time = (end_date + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+
+        let mut prices = HashMap::new();
+        for asset in assets {
+            let klines = self
+                .fetcher
+                .fetch_klines(asset, &KlineInterval::OneDay, start_time, end_time)
+                .await?;
+            debug!("Loaded {} daily closes for {}", klines.len(), asset);
+
+            let closes = klines
+                .into_iter()
+                .map(|k| (k.open_time.date_naive(), k.close))
+                .collect();
+            prices.insert(asset.to_string(), closes);
+        }
+
+        Ok(prices)
+    }
+}
+
+/// End-of-day USD value of the holdings built up by `trades` on `date`.
+///
+/// Returns `None` before the first trade, or when a held asset has no close for that day.
+pub fn value_on(
+    trades: &[HistoricalTrade],
+    prices: &HashMap<String, BTreeMap<NaiveDate, Decimal>>,
+    date: NaiveDate,
+) -> Option<Decimal> {
+    let day_end = (date + Duration::days(1)).and_hms_opt(0, 0, 0)?.and_utc();
+
+    let mut holdings: HashMap<&str, Decimal> = HashMap::new();
+    for trade in trades.iter().filter(|t| t.timestamp < day_end) {
+        *holdings.entry(trade.asset.as_str()).or_default() += trade.quantity;
+    }
+
+    if holdings.is_empty() {
+        return None;
+    }
+
+    let mut total = Decimal::ZERO;
+    for (asset, quantity) in holdings {
+        if quantity <= Decimal::ZERO {
+            continue;
+        }
+        let close = prices.get(asset)?.get(&date)?;
+        total += quantity * close;
+    }
+
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::backtesting::get_cache;
+    use crate::database::test_support::{seed_dca_execution, seed_dca_strategy, seed_exchange_connection, seed_user, test_db};
+    use crate::exchange_connectors::Kline;
+    use sea_orm::QueryOrder;
+
+    fn daily_kline(date: NaiveDate, close: i64) -> Kline {
+        let open_time = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let close = Decimal::from(close);
+        Kline {
+            open_time,
+            close_time: open_time + Duration::days(1) - Duration::milliseconds(1),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: Decimal::ONE,
+            quote_asset_volume: close,
+            number_of_trades: 1,
+            taker_buy_base_asset_volume: Decimal::ZERO,
+            taker_buy_quote_asset_volume: Decimal::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backfill_reconstructs_values_and_rerun_is_noop() {
+        let db = Arc::new(test_db().await);
+        let user_id = seed_user(&db).await;
+        let connection_id = seed_exchange_connection(&db, user_id, "binance").await;
+        // Unique asset so the cached prices below can't collide with other tests
+        let strategy_id = seed_dca_strategy(&db, user_id, "BACKFILLTEST").await;
+
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+
+        // Buy 2 on day 2, buy 1 on day 3, sell 0.5 on day 4
+        seed_dca_execution(&db, strategy_id, connection_id, "buy", Decimal::from(2), Utc.with_ymd_and_hms(2024, 3, 2, 10, 0, 0).unwrap()).await;
+        seed_dca_execution(&db, strategy_id, connection_id, "buy", Decimal::ONE, Utc.with_ymd_and_hms(2024, 3, 3, 10, 0, 0).unwrap()).await;
+        seed_dca_execution(&db, strategy_id, connection_id, "sell", Decimal::new(5, 1), Utc.with_ymd_and_hms(2024, 3, 4, 10, 0, 0).unwrap()).await;
+
+        let (start, end) = (day(1), day(4));
+        get_cache()
+            .store(
+                "BACKFILLTEST",
+                &KlineInterval::OneDay,
+                start.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+                (end + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+                vec![daily_kline(day(1), 90), daily_kline(day(2), 100), daily_kline(day(3), 110), daily_kline(day(4), 120)],
+            )
+            .await;
+
+        let service = SnapshotBackfillService::new(db.clone());
+
+        let summary = service.backfill(user_id, start, end).await.unwrap();
+        assert_eq!(summary, BackfillSummary { created: 3, skipped_existing: 0, skipped_no_data: 1 });
+
+        let snapshots = balance_snapshot::Entity::find()
+            .filter(balance_snapshot::Column::UserId.eq(user_id))
+            .order_by_asc(balance_snapshot::Column::SnapshotDate)
+            .all(db.as_ref())
+            .await
+            .unwrap();
+        let values: Vec<(NaiveDate, Decimal)> = snapshots
+            .iter()
+            .map(|s| (s.snapshot_date, s.total_usd_value))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (day(2), Decimal::from(200)),
+                (day(3), Decimal::from(330)),
+                (day(4), Decimal::from(300)),
+            ]
+        );
+        assert!(snapshots.iter().all(|s| s.source == SOURCE_BACKFILL));
+
+        // Second run finds every priced date already present and writes nothing
+        let rerun = service.backfill(user_id, start, end).await.unwrap();
+        assert_eq!(rerun, BackfillSummary { created: 0, skipped_existing: 3, skipped_no_data: 1 });
+
+        let count = balance_snapshot::Entity::find()
+            .filter(balance_snapshot::Column::UserId.eq(user_id))
+            .all(db.as_ref())
+            .await
+            .unwrap()
+            .len();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_value_on_requires_price_for_held_assets() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let trades = vec![HistoricalTrade {
+            asset: "BTC".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+            quantity: Decimal::ONE,
+        }];
+
+        assert_eq!(value_on(&trades, &HashMap::new(), date), None);
+
+        let prices = HashMap::from([("BTC".to_string(), BTreeMap::from([(date, Decimal::from(50_000))]))]);
+        assert_eq!(value_on(&trades, &prices, date), Some(Decimal::from(50_000)));
+    }
+}
@@ -3,15 +3,26 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{info, error, debug};
 
+use crate::exchange_connectors::numeric::parse_f64;
 use crate::utils::errors::AppError;
+use crate::utils::http::{request_with_policy, RetryPolicy};
 
 const ALPHA_VANTAGE_BASE: &str = "https://www.alphavantage.co/query";
 
+/// Alpha Vantage quotas are per minute and per day, so only server errors are worth retrying
+pub fn alpha_vantage_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        retryable_statuses: vec![500, 502, 503, 504],
+        ..RetryPolicy::default()
+    }
+}
+
 /// Stock data service for fetching stock market data from Alpha Vantage
 #[derive(Clone)]
 pub struct StockDataService {
     client: Client,
     api_key: String,
+    retry_policy: RetryPolicy,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -84,9 +95,16 @@ impl StockDataService {
         Self {
             client: Client::new(),
             api_key,
+            retry_policy: alpha_vantage_retry_policy(),
         }
     }
 
+    /// Use a different retry policy for Alpha Vantage requests
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     /// Get the API key
     pub fn api_key(&self) -> &str {
         &self.api_key
@@ -99,14 +117,15 @@ impl StockDataService {
     pub async fn get_current_price(&self, symbol: &str) -> Result<StockPrice, AppError> {
         info!("Fetching current price for stock: {}", symbol);
 
-        let response = self.client
+        let request = self.client
             .get(ALPHA_VANTAGE_BASE)
             .query(&[
                 ("function", "GLOBAL_QUOTE"),
                 ("symbol", symbol),
                 ("apikey", &self.api_key),
-            ])
-            .send()
+            ]);
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch stock price from Alpha Vantage: {}", e);
@@ -128,7 +147,7 @@ impl StockDataService {
             AppError::ExternalServiceError(format!("Failed to parse response: {}", e))
         })?;
 
-        let price: f64 = quote.global_quote.price.parse().map_err(|e| {
+        let price = parse_f64(&quote.global_quote.price).map_err(|e| {
             AppError::ParseError(format!("Invalid price format: {}", e))
         })?;
 
@@ -155,15 +174,16 @@ impl StockDataService {
 
         let output = outputsize.unwrap_or("compact");
 
-        let response = self.client
+        let request = self.client
             .get(ALPHA_VANTAGE_BASE)
             .query(&[
                 ("function", "TIME_SERIES_DAILY"),
                 ("symbol", symbol),
                 ("outputsize", output),
                 ("apikey", &self.api_key),
-            ])
-            .send()
+            ]);
+
+        let response = request_with_policy(&self.retry_policy, request)
             .await
             .map_err(|e| {
                 error!("Failed to fetch historical data from Alpha Vantage: {}", e);
@@ -191,16 +211,16 @@ impl StockDataService {
             .map(|(date, entry)| {
                 Ok(HistoricalDataPoint {
                     date,
-                    open: entry.open.parse().map_err(|e| {
+                    open: parse_f64(&entry.open).map_err(|e| {
                         AppError::ParseError(format!("Invalid open price: {}", e))
                     })?,
-                    high: entry.high.parse().map_err(|e| {
+                    high: parse_f64(&entry.high).map_err(|e| {
                         AppError::ParseError(format!("Invalid high price: {}", e))
                     })?,
-                    low: entry.low.parse().map_err(|e| {
+                    low: parse_f64(&entry.low).map_err(|e| {
                         AppError::ParseError(format!("Invalid low price: {}", e))
                     })?,
-                    close: entry.close.parse().map_err(|e| {
+                    close: parse_f64(&entry.close).map_err(|e| {
                         AppError::ParseError(format!("Invalid close price: {}", e))
                     })?,
                     volume: entry.volume.parse().map_err(|e| {
@@ -0,0 +1,219 @@
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use crate::backtesting::BinanceFetcher;
+use crate::models::user_profile;
+use crate::utils::errors::AppError;
+
+/// Fiat and stablecoin quotes; a symbol that doesn't end in one is a bare base asset
+const QUOTE_ASSETS: &[&str] = &["USDT", "USDC", "BUSD", "FDUSD", "TUSD", "DAI", "EUR", "GBP", "TRY", "BRL"];
+
+/// The pair a symbol trades as: bare base assets ("btc") become their USDT pair the
+/// way the market data fetchers resolve them, pairs written with a separator ("BTC/USDT")
+/// take their Binance spelling and anything else already quoted is kept
+pub fn trading_pair(symbol: &str) -> String {
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.contains(['/', '-']) {
+        if let Ok(native) = BinanceFetcher::native_symbol(&symbol) {
+            return native;
+        }
+    }
+    let quoted = QUOTE_ASSETS
+        .iter()
+        .any(|quote| symbol.len() > quote.len() && symbol.ends_with(quote));
+    if quoted {
+        symbol
+    } else {
+        BinanceFetcher::convert_to_trading_pair(&symbol)
+    }
+}
+
+/// One entry of a symbol list: an exact symbol ("BTCUSDT"), every symbol quoted in an
+/// asset ("*USDT"), or every symbol ("*")
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolPattern {
+    Exact(String),
+    QuotedIn(String),
+    Any,
+}
+
+impl SymbolPattern {
+    /// Parse one list entry; blank entries are skipped
+    pub fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim().to_uppercase();
+        match entry.as_str() {
+            "" => None,
+            "*" => Some(Self::Any),
+            _ => match entry.strip_prefix('*') {
+                Some(quote) => Some(Self::QuotedIn(quote.to_string())),
+                None => Some(Self::Exact(entry)),
+            },
+        }
+    }
+
+    pub fn matches(&self, symbol: &str) -> bool {
+        let symbol = symbol.trim().to_uppercase();
+        match self {
+            Self::Exact(exact) => symbol == *exact,
+            Self::QuotedIn(quote) => symbol.len() > quote.len() && symbol.ends_with(quote.as_str()),
+            Self::Any => true,
+        }
+    }
+}
+
+/// Symbols that may be traded or backtested: never a denied one and, when an allow
+/// list is set, only the ones on it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolAccessList {
+    pub allow: Vec<SymbolPattern>,
+    pub deny: Vec<SymbolPattern>,
+}
+
+impl SymbolAccessList {
+    /// Build from comma-separated allow and deny lists, e.g. `"*USDT,ETHBTC"`
+    pub fn parse(allow: Option<&str>, deny: Option<&str>) -> Self {
+        let patterns = |list: Option<&str>| -> Vec<SymbolPattern> {
+            list.map(|list| list.split(',').filter_map(SymbolPattern::parse).collect())
+                .unwrap_or_default()
+        };
+        Self {
+            allow: patterns(allow),
+            deny: patterns(deny),
+        }
+    }
+
+    /// The platform-wide lists in `SYMBOL_ALLOW_LIST` and `SYMBOL_DENY_LIST`
+    pub fn from_env() -> Self {
+        Self::parse(
+            std::env::var("SYMBOL_ALLOW_LIST").ok().as_deref(),
+            std::env::var("SYMBOL_DENY_LIST").ok().as_deref(),
+        )
+    }
+
+    /// The lists an operator set on a user's profile
+    pub fn for_profile(profile: &user_profile::Model) -> Self {
+        Self::parse(profile.symbol_allow_list.as_deref(), profile.symbol_deny_list.as_deref())
+    }
+
+    pub fn permits(&self, symbol: &str) -> bool {
+        if self.deny.iter().any(|pattern| pattern.matches(symbol)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|pattern| pattern.matches(symbol))
+    }
+}
+
+/// Refuse a symbol the platform-wide lists or the user's own lists don't permit. Bare
+/// base assets are matched as the pair they trade as.
+pub async fn ensure_symbol_allowed(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    symbol: &str,
+) -> Result<(), AppError> {
+    let symbol = trading_pair(symbol);
+    if !SymbolAccessList::from_env().permits(&symbol) {
+        return Err(AppError::Forbidden(format!(
+            "Trading {} is not allowed on this platform",
+            symbol
+        )));
+    }
+
+    let profile = user_profile::Entity::find()
+        .filter(user_profile::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    if let Some(profile) = profile {
+        if !SymbolAccessList::for_profile(&profile).permits(&symbol) {
+            return Err(AppError::Forbidden(format!(
+                "Trading {} is not allowed for this account",
+                symbol
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{seed_user, seed_user_profile, test_db};
+    use crate::handlers::dca_strategy_management::{new_dca_strategy_model, persist_new_dca_strategy};
+    use crate::handlers::sma_crossover_strategy_management::{
+        new_sma_crossover_strategy_model, persist_new_sma_crossover_strategy,
+    };
+    use crate::strategies::implementations::dca::DCAConfig;
+    use crate::strategies::implementations::sma_crossover::SMACrossoverConfig;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    #[test]
+    fn test_quote_wildcard_allow_list_permits_matching_symbols_only() {
+        let list = SymbolAccessList::parse(Some("*USDT, ethbtc"), None);
+
+        assert!(list.permits("BTCUSDT"));
+        assert!(list.permits("solusdt"));
+        assert!(list.permits("ETHBTC"));
+        assert!(!list.permits("BTCEUR"));
+        assert!(!list.permits("LINKBTC"));
+        // The quote alone is not a symbol quoted in it
+        assert!(!list.permits("USDT"));
+    }
+
+    #[test]
+    fn test_deny_list_wins_over_allow_list() {
+        let list = SymbolAccessList::parse(Some("*"), Some("LUNAUSDT,*TRY"));
+
+        assert!(list.permits("BTCUSDT"));
+        assert!(!list.permits("LUNAUSDT"));
+        assert!(!list.permits("BTCTRY"));
+        assert!(SymbolAccessList::parse(None, Some(" , ")).permits("ANYTHING"));
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_symbol_is_rejected_at_strategy_creation() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let mut profile: user_profile::ActiveModel = seed_user_profile(&db, user_id).await.into();
+        profile.symbol_allow_list = Set(Some("*USDT".to_string()));
+        profile.symbol_deny_list = Set(Some("DOGEUSDT".to_string()));
+        profile.update(&db).await.unwrap();
+
+        let config = SMACrossoverConfig::default();
+        let off_list = new_sma_crossover_strategy_model(user_id, "Euro", "btceur", &config).unwrap();
+        let result = persist_new_sma_crossover_strategy(&db, off_list).await;
+        assert!(matches!(result, Err(AppError::Forbidden(message)) if message.contains("BTCEUR")));
+
+        let denied = new_dca_strategy_model(user_id, "Doge", "DOGEUSDT", &DCAConfig::default()).unwrap();
+        assert!(matches!(persist_new_dca_strategy(&db, denied).await, Err(AppError::Forbidden(_))));
+
+        let allowed = new_sma_crossover_strategy_model(user_id, "Tether", "btcusdt", &config).unwrap();
+        let strategy = persist_new_sma_crossover_strategy(&db, allowed).await.unwrap();
+        assert_eq!(strategy.asset_symbol, "BTCUSDT");
+    }
+
+    #[tokio::test]
+    async fn test_bare_base_asset_is_matched_as_its_pair() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let mut profile: user_profile::ActiveModel = seed_user_profile(&db, user_id).await.into();
+        profile.symbol_allow_list = Set(Some("*USDT".to_string()));
+        profile.symbol_deny_list = Set(Some("DOGEUSDT".to_string()));
+        profile.update(&db).await.unwrap();
+
+        // "doge" trades as DOGEUSDT, so the deny list applies to it
+        let result = ensure_symbol_allowed(&db, user_id, "doge").await;
+        assert!(matches!(result, Err(AppError::Forbidden(message)) if message.contains("DOGEUSDT")));
+        // and "btc" is on the allow list as BTCUSDT
+        assert!(ensure_symbol_allowed(&db, user_id, "btc").await.is_ok());
+
+        assert_eq!(trading_pair(" eth "), "ETHUSDT");
+        assert_eq!(trading_pair("btceur"), "BTCEUR");
+
+        // A canonical pair is checked under its Binance spelling
+        let result = ensure_symbol_allowed(&db, user_id, "DOGE/USDT").await;
+        assert!(matches!(result, Err(AppError::Forbidden(message)) if message.contains("DOGEUSDT")));
+        assert_eq!(trading_pair("eth-btc"), "ETHBTC");
+    }
+}
@@ -0,0 +1,348 @@
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::exchange_connectors::common_types::OrderSide;
+use crate::strategies::core::signals::StrategySignal;
+use crate::utils::errors::AppError;
+
+/// Largest accepted limit offset (50%)
+pub const MAX_LIMIT_OFFSET_BPS: u32 = 5_000;
+
+fn default_fallback_after_secs() -> u64 {
+    300
+}
+
+/// How a strategy's orders are sent to the exchange
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionOrderType {
+    /// Take liquidity at the current price
+    #[default]
+    Market,
+    /// Rest a maker order `offset_bps` below (buys) or above (sells) the current price.
+    /// Whatever is still unfilled after `fallback_after_secs` is sent as a market order.
+    Limit {
+        offset_bps: u32,
+        #[serde(default = "default_fallback_after_secs")]
+        fallback_after_secs: u64,
+    },
+}
+
+impl ExecutionOrderType {
+    /// Read the `execution_order_type` key of a strategy config; missing means market
+    pub fn from_parameters(parameters: &serde_json::Value) -> Result<Self, AppError> {
+        let order_type = match parameters.get("execution_order_type") {
+            None | Some(serde_json::Value::Null) => Self::Market,
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+                AppError::BadRequest(format!("Invalid execution_order_type: {}", e))
+            })?,
+        };
+        order_type.validate().map_err(AppError::BadRequest)?;
+        Ok(order_type)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Self::Limit { offset_bps, .. } = self {
+            if *offset_bps > MAX_LIMIT_OFFSET_BPS {
+                return Err(format!("Limit offset must be at most {} bps", MAX_LIMIT_OFFSET_BPS));
+            }
+        }
+        Ok(())
+    }
+
+    /// JSON schema fragment shared by the strategy config schemas
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "description": "Order type used for live and backtest fills (default market)",
+            "properties": {
+                "type": {"type": "string", "enum": ["market", "limit"]},
+                "offset_bps": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": MAX_LIMIT_OFFSET_BPS,
+                    "description": "Limit price offset from the current price, in basis points"
+                },
+                "fallback_after_secs": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Seconds an unfilled limit order rests before the remainder is sent at market"
+                }
+            }
+        })
+    }
+
+    /// Price to rest the order at, or `None` for market orders
+    pub fn limit_price(&self, side: &OrderSide, reference_price: Decimal) -> Option<Decimal> {
+        match self {
+            Self::Market => None,
+            Self::Limit { offset_bps, .. } => Some(limit_price(side, reference_price, *offset_bps)),
+        }
+    }
+
+    /// How long an unfilled limit order rests before falling back to market
+    pub fn fallback_after(&self) -> Option<Duration> {
+        match self {
+            Self::Market => None,
+            Self::Limit { fallback_after_secs, .. } => Some(Duration::from_secs(*fallback_after_secs)),
+        }
+    }
+}
+
+/// Maker price `offset_bps` on the passive side of `reference_price`
+pub fn limit_price(side: &OrderSide, reference_price: Decimal, offset_bps: u32) -> Decimal {
+    let offset = Decimal::from(offset_bps) / Decimal::from(10_000);
+    match side {
+        OrderSide::Buy => reference_price * (Decimal::ONE - offset),
+        OrderSide::Sell => reference_price * (Decimal::ONE + offset),
+    }
+}
+
+/// Largest notional a single live order may have, and what to do with larger ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotionalLimit {
+    pub max_order_notional: Decimal,
+    /// Shrink oversized orders to the limit instead of rejecting them
+    pub clamp: bool,
+}
+
+impl NotionalLimit {
+    /// Read the optional `max_order_notional` / `clamp_oversized_orders` keys of a strategy config
+    pub fn from_parameters(parameters: &serde_json::Value) -> Result<Option<Self>, AppError> {
+        let max_order_notional: Option<Decimal> = match parameters.get("max_order_notional") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(value) => Some(serde_json::from_value(value.clone()).map_err(|e| {
+                AppError::BadRequest(format!("Invalid max_order_notional: {}", e))
+            })?),
+        };
+        validate_max_order_notional(max_order_notional).map_err(AppError::BadRequest)?;
+
+        let clamp = parameters
+            .get("clamp_oversized_orders")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        Ok(max_order_notional.map(|max_order_notional| Self { max_order_notional, clamp }))
+    }
+
+    /// The tighter of a user-wide and a per-strategy limit
+    pub fn strictest(user: Option<Self>, strategy: Option<Self>) -> Option<Self> {
+        match (user, strategy) {
+            (Some(user), Some(strategy)) if strategy.max_order_notional < user.max_order_notional => Some(strategy),
+            (Some(user), _) => Some(user),
+            (None, strategy) => strategy,
+        }
+    }
+
+    /// Quantity allowed for an order of `quantity` at `price`: unchanged when within the
+    /// limit, shrunk to fit when clamping, otherwise an error describing the breach
+    pub fn apply(&self, quantity: Decimal, price: Decimal) -> Result<Decimal, String> {
+        let notional = quantity * price;
+        if notional <= self.max_order_notional {
+            return Ok(quantity);
+        }
+        if self.clamp && price > Decimal::ZERO {
+            return Ok(self.max_order_notional / price);
+        }
+        Err(format!(
+            "Order notional {} exceeds the maximum of {}",
+            notional.round_dp(2),
+            self.max_order_notional
+        ))
+    }
+}
+
+pub fn validate_max_order_notional(max_order_notional: Option<Decimal>) -> Result<(), String> {
+    match max_order_notional {
+        Some(max) if max <= Decimal::ZERO => Err("Maximum order notional must be positive".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// JSON schema fragment for the order notional limit, shared by the strategy config schemas
+pub fn max_order_notional_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": ["number", "null"],
+        "exclusiveMinimum": 0,
+        "description": "Largest quote value a single live order may have"
+    })
+}
+
+/// Intended buys too small to place on their own, held back until together they reach
+/// the minimum order notional
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinNotionalAccumulator {
+    /// Quote amount held back so far
+    pub pending_amount: Decimal,
+    /// Number of buys held back so far
+    pub pending_count: u32,
+}
+
+impl MinNotionalAccumulator {
+    /// Add an intended buy of `amount`. Once everything held back reaches `min_notional`
+    /// the combined amount is returned to be placed as one order; until then the buy is
+    /// held back and `None` is returned. The amounts stay pending until `clear` is called
+    /// once the order has filled, so a failed order is retried with the next buy.
+    pub fn accumulate(&mut self, amount: Decimal, min_notional: Decimal) -> Option<Decimal> {
+        self.pending_amount += amount;
+        self.pending_count += 1;
+        (self.pending_amount >= min_notional).then_some(self.pending_amount)
+    }
+
+    /// Forget the held-back buys once the combined order has been placed
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Read the optional `min_confidence` key of a strategy config
+pub fn min_confidence_from_parameters(parameters: &serde_json::Value) -> Result<Option<Decimal>, AppError> {
+    let min_confidence = match parameters.get("min_confidence") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(value) => Some(serde_json::from_value(value.clone()).map_err(|e| {
+            AppError::BadRequest(format!("Invalid min_confidence: {}", e))
+        })?),
+    };
+    validate_min_confidence(min_confidence).map_err(AppError::BadRequest)?;
+    Ok(min_confidence)
+}
+
+pub fn validate_min_confidence(min_confidence: Option<Decimal>) -> Result<(), String> {
+    match min_confidence {
+        Some(min) if min < Decimal::ZERO || min > Decimal::ONE => {
+            Err("Minimum confidence must be between 0 and 1".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// JSON schema fragment for `min_confidence`, shared by the strategy config schemas
+pub fn min_confidence_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": ["number", "null"],
+        "minimum": 0,
+        "maximum": 1,
+        "description": "Signals with a lower confidence are not executed"
+    })
+}
+
+/// Drop a signal whose confidence is below `min_confidence`. Signals that carry no
+/// confidence are passed through, as are all signals when no minimum is set.
+pub fn apply_min_confidence(signal: StrategySignal, min_confidence: Option<Decimal>) -> Option<StrategySignal> {
+    match (min_confidence, signal.metadata.confidence) {
+        (Some(min), Some(confidence)) if confidence < min => {
+            info!(
+                "Suppressed {:?} signal for {}: confidence {} is below the minimum {}",
+                signal.signal_type, signal.symbol, confidence, min
+            );
+            None
+        }
+        _ => Some(signal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::strategies::core::signals::QuantityType;
+
+    #[test]
+    fn test_limit_price_is_offset_to_the_passive_side() {
+        let limit = ExecutionOrderType::Limit { offset_bps: 25, fallback_after_secs: 60 };
+        let price = Decimal::from(40_000);
+
+        assert_eq!(limit.limit_price(&OrderSide::Buy, price), Some(Decimal::from(39_900)));
+        assert_eq!(limit.limit_price(&OrderSide::Sell, price), Some(Decimal::from(40_100)));
+        assert_eq!(ExecutionOrderType::Market.limit_price(&OrderSide::Buy, price), None);
+        assert_eq!(limit_price(&OrderSide::Buy, price, 0), price);
+    }
+
+    #[test]
+    fn test_from_parameters() {
+        assert_eq!(
+            ExecutionOrderType::from_parameters(&json!({ "base_amount": "100" })).unwrap(),
+            ExecutionOrderType::Market
+        );
+        assert_eq!(
+            ExecutionOrderType::from_parameters(&json!({
+                "execution_order_type": { "type": "limit", "offset_bps": 10 }
+            }))
+            .unwrap(),
+            ExecutionOrderType::Limit { offset_bps: 10, fallback_after_secs: 300 }
+        );
+        assert!(ExecutionOrderType::from_parameters(&json!({
+            "execution_order_type": { "type": "limit", "offset_bps": 6000 }
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_min_confidence_gate() {
+        let signal = |confidence: Decimal| {
+            StrategySignal::buy("BTCUSDT".to_string(), QuantityType::Fixed(Decimal::ONE), "test".to_string(), None)
+                .with_confidence(confidence)
+        };
+        let threshold = Some(Decimal::new(6, 1));
+
+        assert!(apply_min_confidence(signal(Decimal::new(4, 1)), threshold).is_none());
+        assert!(apply_min_confidence(signal(Decimal::new(8, 1)), threshold).is_some());
+        assert!(apply_min_confidence(signal(Decimal::new(4, 1)), None).is_some());
+    }
+
+    #[test]
+    fn test_min_confidence_from_parameters() {
+        assert_eq!(min_confidence_from_parameters(&json!({})).unwrap(), None);
+        assert_eq!(
+            min_confidence_from_parameters(&json!({ "min_confidence": "0.6" })).unwrap(),
+            Some(Decimal::new(6, 1))
+        );
+        assert!(min_confidence_from_parameters(&json!({ "min_confidence": "1.5" })).is_err());
+    }
+
+    #[test]
+    fn test_sub_minimum_buys_accumulate_into_one_order() {
+        let mut accumulator = MinNotionalAccumulator::default();
+        let min_notional = Decimal::from(10);
+
+        assert_eq!(accumulator.accumulate(Decimal::from(4), min_notional), None);
+        assert_eq!(accumulator.accumulate(Decimal::from(4), min_notional), None);
+        assert_eq!(accumulator.pending_amount, Decimal::from(8));
+        assert_eq!(accumulator.pending_count, 2);
+
+        assert_eq!(accumulator.accumulate(Decimal::from(4), min_notional), Some(Decimal::from(12)));
+        // Nothing is dropped until the order goes through; a failed one is retried with the next buy
+        assert_eq!(accumulator.pending_amount, Decimal::from(12));
+        assert_eq!(accumulator.accumulate(Decimal::from(4), min_notional), Some(Decimal::from(16)));
+        accumulator.clear();
+        assert_eq!(accumulator, MinNotionalAccumulator::default());
+
+        // A buy that is large enough on its own goes straight through
+        assert_eq!(accumulator.accumulate(Decimal::from(25), min_notional), Some(Decimal::from(25)));
+    }
+
+    #[test]
+    fn test_notional_limit() {
+        let reject = NotionalLimit { max_order_notional: Decimal::from(1000), clamp: false };
+        let clamp = NotionalLimit { clamp: true, ..reject };
+        let price = Decimal::from(100);
+
+        assert_eq!(reject.apply(Decimal::from(5), price), Ok(Decimal::from(5)));
+        assert!(reject.apply(Decimal::from(20), price).is_err());
+        assert_eq!(clamp.apply(Decimal::from(20), price), Ok(Decimal::from(10)));
+
+        let tight = NotionalLimit { max_order_notional: Decimal::from(500), clamp: true };
+        assert_eq!(NotionalLimit::strictest(Some(reject), Some(tight)), Some(tight));
+        assert_eq!(NotionalLimit::strictest(Some(tight), Some(reject)), Some(tight));
+        assert_eq!(NotionalLimit::strictest(None, Some(reject)), Some(reject));
+
+        let parsed = NotionalLimit::from_parameters(&json!({
+            "max_order_notional": "1000", "clamp_oversized_orders": true
+        }))
+        .unwrap();
+        assert_eq!(parsed, Some(clamp));
+        assert!(NotionalLimit::from_parameters(&json!({ "max_order_notional": "-5" })).is_err());
+    }
+}
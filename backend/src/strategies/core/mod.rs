@@ -3,9 +3,13 @@ pub mod registry;
 pub mod factory;
 pub mod signals;
 pub mod context;
+pub mod execution;
+pub mod presets;
 
 pub use traits::*;
 pub use registry::*;
 pub use factory::*;
 pub use signals::*;
-pub use context::*;
\ No newline at end of file
+pub use context::*;
+pub use execution::*;
+pub use presets::*;
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::traits::RiskLevel;
+use crate::utils::errors::AppError;
+
+/// A named parameter bundle a strategy offers as a starting point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyPreset {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub risk_level: RiskLevel,
+    /// Complete strategy parameters, in the shape `Strategy::validate_parameters` accepts
+    pub parameters: Value,
+}
+
+impl StrategyPreset {
+    /// Build a preset from a strategy's typed configuration
+    pub fn new<C: Serialize>(id: &str, name: &str, description: &str, risk_level: RiskLevel, config: &C) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            risk_level,
+            parameters: serde_json::to_value(config).expect("strategy configs serialize to JSON"),
+        }
+    }
+
+    /// The preset's parameters with `overrides` laid over the top-level fields
+    pub fn parameters_with(&self, overrides: Option<&Value>) -> Result<Value, AppError> {
+        let mut parameters = self.parameters.clone();
+
+        match overrides {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(fields)) => {
+                let target = parameters.as_object_mut()
+                    .ok_or_else(|| AppError::BadRequest(format!("Preset {} cannot be overridden", self.id)))?;
+                for (key, value) in fields {
+                    target.insert(key.clone(), value.clone());
+                }
+            }
+            Some(_) => return Err(AppError::BadRequest("Preset overrides must be a JSON object".to_string())),
+        }
+
+        Ok(parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn preset() -> StrategyPreset {
+        StrategyPreset::new(
+            "balanced",
+            "Balanced",
+            "Middle of the road",
+            RiskLevel::Moderate,
+            &json!({ "fast_period": 10, "slow_period": 30 }),
+        )
+    }
+
+    #[test]
+    fn test_overrides_replace_top_level_fields() {
+        let parameters = preset().parameters_with(Some(&json!({ "fast_period": 5 }))).unwrap();
+        assert_eq!(parameters, json!({ "fast_period": 5, "slow_period": 30 }));
+
+        assert_eq!(preset().parameters_with(None).unwrap(), preset().parameters);
+        assert!(matches!(preset().parameters_with(Some(&json!([1, 2]))), Err(AppError::BadRequest(_))));
+    }
+}
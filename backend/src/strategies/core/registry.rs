@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
 use super::traits::{Strategy, StrategyFactory, StrategyMetadata, StrategyCategory, RiskLevel, StrategyMode};
+use super::presets::StrategyPreset;
 use crate::utils::errors::AppError;
 
 /// Global strategy registry
@@ -170,6 +171,32 @@ impl StrategyRegistry {
         self.list_filtered(&filter)
     }
 
+    /// Presets offered by a strategy
+    pub fn get_presets(&self, strategy_id: &str) -> Result<Vec<StrategyPreset>, AppError> {
+        let factory = self.strategies.get(strategy_id)
+            .ok_or_else(|| AppError::NotFound(format!("Strategy not found: {}", strategy_id)))?;
+
+        Ok(factory.presets())
+    }
+
+    /// Parameters for `preset_id` with `overrides` applied, validated by the strategy itself
+    pub fn resolve_preset(
+        &self,
+        strategy_id: &str,
+        preset_id: &str,
+        overrides: Option<&serde_json::Value>,
+    ) -> Result<serde_json::Value, AppError> {
+        let preset = self.get_presets(strategy_id)?
+            .into_iter()
+            .find(|preset| preset.id == preset_id)
+            .ok_or_else(|| AppError::NotFound(format!("Preset {} not found for strategy {}", preset_id, strategy_id)))?;
+
+        let parameters = preset.parameters_with(overrides)?;
+        self.create_strategy(strategy_id)?.validate_parameters(&parameters)?;
+
+        Ok(parameters)
+    }
+
     /// Check if strategy exists
     pub fn contains(&self, strategy_id: &str) -> bool {
         self.strategies.contains_key(strategy_id)
@@ -330,6 +357,30 @@ pub fn list_strategies_filtered(filter: &StrategyFilter) -> Result<Vec<StrategyL
     Ok(registry.list_filtered(filter))
 }
 
+/// List a strategy's presets from global registry
+pub fn list_strategy_presets(strategy_id: &str) -> Result<Vec<StrategyPreset>, AppError> {
+    let registry = get_global_registry();
+    let registry = registry.read().map_err(|e| {
+        AppError::InternalServerError
+    })?;
+
+    registry.get_presets(strategy_id)
+}
+
+/// Resolve and validate a strategy preset from global registry
+pub fn resolve_strategy_preset(
+    strategy_id: &str,
+    preset_id: &str,
+    overrides: Option<&serde_json::Value>,
+) -> Result<serde_json::Value, AppError> {
+    let registry = get_global_registry();
+    let registry = registry.read().map_err(|e| {
+        AppError::InternalServerError
+    })?;
+
+    registry.resolve_preset(strategy_id, preset_id, overrides)
+}
+
 impl Default for StrategyRegistry {
     fn default() -> Self {
         Self::new()
@@ -2,6 +2,9 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::exchange_connectors::Kline;
+use crate::strategies::indicators;
+
 /// Signal generated by a trading strategy
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrategySignal {
@@ -81,6 +84,89 @@ pub enum QuantityType {
     AllAvailable,
     /// All current position
     AllPosition,
+    /// Dollar amount sized so the position's annualized volatility is roughly
+    /// `target_annual_vol_pct` of the available balance
+    VolatilityTargeted { target_annual_vol_pct: Decimal },
+    /// Share of the available balance given by the Kelly criterion on the strategy's
+    /// closed trades, scaled by `fraction` (0.5 = half Kelly)
+    Kelly { fraction: Decimal },
+}
+
+/// Largest share of the balance a Kelly-sized order may use
+pub const MAX_KELLY_BET: Decimal = Decimal::from_parts(25, 0, 0, false, 2);
+
+/// Closed trades needed before Kelly sizing trusts the observed stats
+pub const KELLY_MIN_TRADES: usize = 5;
+
+/// Win/loss record Kelly sizing is computed from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeStats {
+    pub trades: usize,
+    pub win_probability: Decimal,
+    pub average_win: Decimal,
+    /// Average losing trade, as a positive amount
+    pub average_loss: Decimal,
+}
+
+impl TradeStats {
+    /// Stats from realized P&L values; `None` if there are none
+    pub fn from_pnls(pnls: impl IntoIterator<Item = Decimal>) -> Option<Self> {
+        let (mut wins, mut losses) = (Vec::new(), Vec::new());
+        for pnl in pnls {
+            if pnl > Decimal::ZERO {
+                wins.push(pnl);
+            } else {
+                losses.push(-pnl);
+            }
+        }
+        let trades = wins.len() + losses.len();
+        if trades == 0 {
+            return None;
+        }
+
+        let average = |values: &[Decimal]| match values.len() {
+            0 => Decimal::ZERO,
+            n => values.iter().sum::<Decimal>() / Decimal::from(n),
+        };
+        Some(Self {
+            trades,
+            win_probability: Decimal::from(wins.len()) / Decimal::from(trades),
+            average_win: average(&wins),
+            average_loss: average(&losses),
+        })
+    }
+}
+
+/// Fractional Kelly bet as a share of the balance: `fraction * (p - (1 - p) / b)` with
+/// `b = average_win / average_loss`, clamped to `[0, MAX_KELLY_BET]`. A negative edge
+/// means no bet.
+pub fn kelly_fraction(win_probability: Decimal, average_win: Decimal, average_loss: Decimal, fraction: Decimal) -> Decimal {
+    let full_kelly = if average_loss.is_zero() {
+        // Never lost: the edge is as large as it gets
+        if win_probability > Decimal::ZERO { Decimal::ONE } else { Decimal::ZERO }
+    } else if average_win.is_zero() {
+        Decimal::ZERO
+    } else {
+        let payoff = average_win / average_loss;
+        win_probability - (Decimal::ONE - win_probability) / payoff
+    };
+
+    (full_kelly * fraction).max(Decimal::ZERO).min(MAX_KELLY_BET)
+}
+
+/// Bars of returns used to measure realized volatility for volatility-targeted sizing
+pub const VOLATILITY_TARGET_LOOKBACK: usize = 20;
+
+impl QuantityType {
+    /// Dollar amount for a volatility-targeted position: `balance * target / realized vol`,
+    /// never more than `balance`. `None` until there is enough data to measure volatility.
+    pub fn volatility_targeted_amount(target_annual_vol_pct: Decimal, balance: Decimal, data: &[Kline]) -> Option<Decimal> {
+        let realized = indicators::realized_volatility(data, VOLATILITY_TARGET_LOOKBACK)?;
+        if realized.is_zero() {
+            return Some(balance);
+        }
+        Some((balance * target_annual_vol_pct / realized).min(balance))
+    }
 }
 
 /// Price constraints
@@ -350,4 +436,121 @@ impl StrategySignal {
         self.metadata.indicators = indicators;
         self
     }
-}
\ No newline at end of file
+
+    /// Turn a Kelly quantity into a dollar amount from the strategy's closed-trade `stats`.
+    /// With fewer than `KELLY_MIN_TRADES` trades the capped bet is scaled by `fraction`.
+    pub fn resolve_kelly(mut self, stats: Option<TradeStats>, balance: Decimal) -> Self {
+        if let QuantityType::Kelly { fraction } = self.action.quantity {
+            let bet = match stats {
+                Some(stats) if stats.trades >= KELLY_MIN_TRADES => {
+                    kelly_fraction(stats.win_probability, stats.average_win, stats.average_loss, fraction)
+                }
+                _ => (MAX_KELLY_BET * fraction).max(Decimal::ZERO).min(MAX_KELLY_BET),
+            };
+            self.action.quantity = QuantityType::DollarAmount(balance * bet);
+        }
+        self
+    }
+
+    /// Turn a volatility-targeted quantity into a dollar amount using `data` (bars up
+    /// to now) and the available balance. Returns `None` if volatility can't be measured yet.
+    pub fn resolve_volatility_target(mut self, data: &[Kline], balance: Decimal) -> Option<Self> {
+        if let QuantityType::VolatilityTargeted { target_annual_vol_pct } = self.action.quantity {
+            let amount = QuantityType::volatility_targeted_amount(target_annual_vol_pct, balance, data)?;
+            self.action.quantity = QuantityType::DollarAmount(amount);
+        }
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    /// Hourly closes alternating `swing` percent around 100
+    fn choppy_klines(swing: i64) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..30)
+            .map(|i| {
+                let close = Decimal::from(100) + Decimal::from(if i % 2 == 0 { swing } else { -swing });
+                Kline {
+                    open_time: base_time + Duration::hours(i),
+                    close_time: base_time + Duration::hours(i + 1),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: Decimal::ONE,
+                    quote_asset_volume: close,
+                    number_of_trades: 1,
+                    taker_buy_base_asset_volume: Decimal::ZERO,
+                    taker_buy_quote_asset_volume: Decimal::ZERO,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_higher_volatility_gives_smaller_position() {
+        let balance = Decimal::from(10_000);
+        let target = Decimal::from(20);
+
+        let calm = QuantityType::volatility_targeted_amount(target, balance, &choppy_klines(1)).unwrap();
+        let wild = QuantityType::volatility_targeted_amount(target, balance, &choppy_klines(5)).unwrap();
+
+        assert!(wild < calm);
+        assert!(calm <= balance);
+        assert!(wild > Decimal::ZERO);
+        assert!(QuantityType::volatility_targeted_amount(target, balance, &choppy_klines(5)[..10]).is_none());
+    }
+
+    #[test]
+    fn test_resolve_volatility_target_sets_dollar_amount() {
+        let signal = StrategySignal::buy(
+            "BTCUSDT".to_string(),
+            QuantityType::VolatilityTargeted { target_annual_vol_pct: Decimal::from(20) },
+            "test".to_string(),
+            None,
+        );
+        let resolved = signal.resolve_volatility_target(&choppy_klines(5), Decimal::from(10_000)).unwrap();
+
+        assert!(matches!(resolved.action.quantity, QuantityType::DollarAmount(amount) if amount > Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_kelly_fraction_matches_formula_and_is_clamped() {
+        // p = 0.6, b = 2: full Kelly = 0.6 - 0.4 / 2 = 0.4; half Kelly = 0.2
+        let p = Decimal::new(6, 1);
+        let half = kelly_fraction(p, Decimal::from(200), Decimal::from(100), Decimal::new(5, 1));
+        assert_eq!(half, Decimal::new(2, 1));
+
+        // Full Kelly of 0.4 is above the cap
+        assert_eq!(kelly_fraction(p, Decimal::from(200), Decimal::from(100), Decimal::ONE), MAX_KELLY_BET);
+
+        // p = 0.4, b = 1: 0.4 - 0.6 = -0.2, so no bet
+        assert_eq!(
+            kelly_fraction(Decimal::new(4, 1), Decimal::from(100), Decimal::from(100), Decimal::ONE),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_trade_stats_and_resolve_kelly() {
+        let pnls = [300, -100, 100, -100, 200].map(Decimal::from);
+        let stats = TradeStats::from_pnls(pnls).unwrap();
+        assert_eq!(stats.trades, 5);
+        assert_eq!(stats.win_probability, Decimal::new(6, 1));
+        assert_eq!(stats.average_win, Decimal::from(200));
+        assert_eq!(stats.average_loss, Decimal::from(100));
+
+        let signal = StrategySignal::buy(
+            "BTCUSDT".to_string(),
+            QuantityType::Kelly { fraction: Decimal::new(5, 1) },
+            "test".to_string(),
+            None,
+        );
+        let resolved = signal.resolve_kelly(Some(stats), Decimal::from(1000));
+        assert!(matches!(resolved.action.quantity, QuantityType::DollarAmount(amount) if amount == Decimal::from(200)));
+    }
+}
@@ -6,8 +6,10 @@ use serde_json::Value;
 use uuid::Uuid;
 
 use crate::exchange_connectors::Kline;
+use crate::exchange_connectors::common_types::OrderSide;
 use crate::utils::errors::AppError;
 use super::signals::{StrategySignal, StrategySignalType};
+use super::presets::StrategyPreset;
 
 /// Strategy execution mode
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -17,6 +19,26 @@ pub enum StrategyMode {
     Live,        // Live trading with real money
 }
 
+impl StrategyMode {
+    /// Name stored in a strategy's `mode` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StrategyMode::Backtest => "backtest",
+            StrategyMode::Paper => "paper",
+            StrategyMode::Live => "live",
+        }
+    }
+
+    /// Mode a stored strategy runs in: `live` or `paper`. Backtests are never stored.
+    pub fn parse_execution_mode(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "live" => Some(StrategyMode::Live),
+            "paper" => Some(StrategyMode::Paper),
+            _ => None,
+        }
+    }
+}
+
 /// Risk level classification
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum RiskLevel {
@@ -40,6 +62,95 @@ pub enum StrategyCategory {
     Custom,           // User-defined strategies
 }
 
+/// Where a strategy instance is in its lifecycle. Replaces separate paused/running/
+/// active flags, which could drift into contradictory combinations.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyLifecycle {
+    /// Constructed, no parameters yet
+    #[default]
+    Created,
+    /// Parameters accepted; analyzes on demand (backtests) but not started live
+    Initialized,
+    /// Executing live
+    Running,
+    /// Suspended by the user; produces no signals until resumed
+    Paused,
+    /// Stopped itself (risk limit, grid breakout); must be re-initialized
+    Halted,
+    /// Live execution ended; must be re-initialized
+    Stopped,
+}
+
+/// Operations that move a strategy between lifecycle states
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    Initialize,
+    Start,
+    Pause,
+    Resume,
+    Halt,
+    Stop,
+}
+
+impl std::fmt::Display for StrategyLifecycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Created => "created",
+            Self::Initialized => "initialized",
+            Self::Running => "running",
+            Self::Paused => "paused",
+            Self::Halted => "halted",
+            Self::Stopped => "stopped",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::fmt::Display for LifecycleEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Initialize => "initialize",
+            Self::Start => "start",
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Halt => "halt",
+            Self::Stop => "stop",
+        };
+        f.write_str(name)
+    }
+}
+
+impl StrategyLifecycle {
+    /// State reached by applying `event`, or a conflict error if the event is not
+    /// allowed from this state
+    pub fn transition(self, event: LifecycleEvent) -> Result<Self, AppError> {
+        use LifecycleEvent as Event;
+        use StrategyLifecycle::*;
+
+        match (self, event) {
+            (Created | Initialized | Halted | Stopped, Event::Initialize) => Ok(Initialized),
+            (Initialized, Event::Start) => Ok(Running),
+            (Initialized | Running, Event::Pause) => Ok(Paused),
+            (Paused, Event::Resume) => Ok(Running),
+            (Initialized | Running | Paused, Event::Halt) => Ok(Halted),
+            (Created | Initialized | Running | Paused | Halted, Event::Stop) => Ok(Stopped),
+            (from, event) => Err(AppError::Conflict(format!("Cannot {} a {} strategy", event, from))),
+        }
+    }
+
+    /// Apply `event` in place; the state is left unchanged when the transition is invalid
+    pub fn apply(&mut self, event: LifecycleEvent) -> Result<(), AppError> {
+        *self = self.transition(event)?;
+        Ok(())
+    }
+
+    /// Whether `analyze` may produce signals in this state
+    pub fn accepts_signals(self) -> bool {
+        matches!(self, Self::Initialized | Self::Running)
+    }
+}
+
 /// Base trait for all trading strategies
 #[async_trait]
 pub trait Strategy: Send + Sync {
@@ -105,6 +216,42 @@ pub trait Strategy: Send + Sync {
     fn get_metrics(&self) -> StrategyMetrics {
         StrategyMetrics::default()
     }
+
+    /// Structured explanation of the most recent `analyze` call: indicator values,
+    /// thresholds and pass/fail per rule. Strategies without one return `Value::Null`.
+    fn explain(&self) -> Value {
+        Value::Null
+    }
+
+    /// Current lifecycle state. Strategies that don't track one report `Created`.
+    fn lifecycle(&self) -> StrategyLifecycle {
+        StrategyLifecycle::Created
+    }
+
+    /// The strategy as a `LiveExecutableStrategy`, or `None` if it has no live code path
+    fn into_live(self: Box<Self>) -> Option<Box<dyn LiveExecutableStrategy>> {
+        None
+    }
+}
+
+/// A single condition evaluated during `analyze`, reported by `Strategy::explain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleEvaluation {
+    pub rule: String,
+    pub value: Option<Decimal>,
+    pub threshold: Option<Decimal>,
+    pub passed: bool,
+}
+
+impl RuleEvaluation {
+    pub fn new(rule: &str, value: Option<Decimal>, threshold: Option<Decimal>, passed: bool) -> Self {
+        Self {
+            rule: rule.to_string(),
+            value,
+            threshold,
+            passed,
+        }
+    }
 }
 
 /// Strategy metadata for discovery and management
@@ -148,6 +295,8 @@ pub struct MarketData {
     pub bid_price: Option<Decimal>,
     pub ask_price: Option<Decimal>,
     pub spread: Option<Decimal>,
+    /// Smallest order value the exchange accepts for the symbol (`SymbolInfo.min_notional`)
+    pub min_notional: Option<Decimal>,
 }
 
 /// Current position information
@@ -178,6 +327,7 @@ pub struct PositionUpdate {
 pub struct OrderUpdate {
     pub order_id: String,
     pub symbol: String,
+    pub side: OrderSide,
     pub order_type: OrderType,
     pub status: OrderStatus,
     pub quantity: Decimal,
@@ -232,6 +382,11 @@ pub struct StrategyConfig {
 pub trait StrategyFactory: Send + Sync {
     fn create(&self) -> Box<dyn Strategy>;
     fn metadata(&self) -> &StrategyMetadata;
+
+    /// Named parameter bundles offered for this strategy
+    fn presets(&self) -> Vec<StrategyPreset> {
+        Vec::new()
+    }
 }
 
 /// Trait for strategies that support backtesting
@@ -283,7 +438,9 @@ pub trait LiveExecutableStrategy: Strategy {
     async fn stop_live_execution(&mut self) -> Result<(), AppError>;
 
     /// Check if strategy is currently running
-    fn is_running(&self) -> bool;
+    fn is_running(&self) -> bool {
+        self.lifecycle() == StrategyLifecycle::Running
+    }
 
     /// Get next execution time (for scheduled strategies)
     fn next_execution_time(&self) -> Option<DateTime<Utc>>;
@@ -299,5 +456,49 @@ pub trait ControllableStrategy: Strategy {
     async fn resume(&mut self) -> Result<(), AppError>;
 
     /// Check if strategy is paused
-    fn is_paused(&self) -> bool;
+    fn is_paused(&self) -> bool {
+        self.lifecycle() == StrategyLifecycle::Paused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LifecycleEvent as Event;
+
+    #[test]
+    fn test_lifecycle_transitions() {
+        let mut lifecycle = StrategyLifecycle::default();
+        lifecycle.apply(Event::Initialize).unwrap();
+        lifecycle.apply(Event::Start).unwrap();
+        assert_eq!(lifecycle, StrategyLifecycle::Running);
+
+        lifecycle.apply(Event::Pause).unwrap();
+        assert!(!lifecycle.accepts_signals());
+        lifecycle.apply(Event::Resume).unwrap();
+        assert_eq!(lifecycle, StrategyLifecycle::Running);
+
+        // Halting a running strategy is allowed; it has to be re-initialized afterwards
+        assert_eq!(StrategyLifecycle::Running.transition(Event::Halt).unwrap(), StrategyLifecycle::Halted);
+        assert!(StrategyLifecycle::Halted.transition(Event::Resume).is_err());
+        assert!(StrategyLifecycle::Halted.transition(Event::Start).is_err());
+        assert_eq!(
+            StrategyLifecycle::Halted.transition(Event::Initialize).unwrap(),
+            StrategyLifecycle::Initialized
+        );
+    }
+
+    #[test]
+    fn test_invalid_transitions_are_rejected() {
+        let mut stopped = StrategyLifecycle::Stopped;
+        assert!(matches!(stopped.apply(Event::Resume), Err(AppError::Conflict(_))));
+        assert_eq!(stopped, StrategyLifecycle::Stopped);
+        assert!(stopped.transition(Event::Start).is_err());
+        assert!(stopped.transition(Event::Stop).is_err());
+
+        assert!(StrategyLifecycle::Created.transition(Event::Start).is_err());
+        assert!(StrategyLifecycle::Running.transition(Event::Start).is_err());
+        assert!(StrategyLifecycle::Running.transition(Event::Initialize).is_err());
+        assert!(StrategyLifecycle::Running.transition(Event::Resume).is_err());
+    }
 }
\ No newline at end of file
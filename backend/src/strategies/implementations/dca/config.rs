@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use rust_decimal::Decimal;
+use chrono::{DateTime, Duration, Utc};
 
 use super::types::*;
+use crate::strategies::core::{
+    max_order_notional_schema, min_confidence_schema, validate_max_order_notional, validate_min_confidence,
+    ExecutionOrderType,
+};
 
 /// Complete DCA strategy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,9 +15,14 @@ pub struct DCAConfig {
     /// Base amount to invest each interval (in quote currency)
     pub base_amount: Decimal,
 
-    /// DCA execution frequency
+    /// DCA execution frequency. Deprecated in favour of `interval`, and only used when
+    /// that is not set
     pub frequency: DCAFrequency,
 
+    /// Purchase schedule on the calendar, e.g. monthly on the 1st
+    #[serde(default)]
+    pub interval: Option<DCAInterval>,
+
     /// Type of DCA strategy
     pub strategy_type: DCAType,
 
@@ -60,6 +70,32 @@ pub struct DCAConfig {
 
     /// Additional filters and conditions
     pub filters: DCAFilters,
+
+    /// Market or offset limit orders for purchases
+    #[serde(default)]
+    pub execution_order_type: ExecutionOrderType,
+    /// Signals below this confidence (0-1) are not executed
+    #[serde(default)]
+    pub min_confidence: Option<Decimal>,
+    /// Largest quote value a single live order may have
+    #[serde(default)]
+    pub max_order_notional: Option<Decimal>,
+    /// Shrink orders above `max_order_notional` to the limit instead of rejecting them
+    #[serde(default)]
+    pub clamp_oversized_orders: bool,
+
+    /// Sell the whole position once the price falls `stop_loss_percentage` below the
+    /// average entry price
+    #[serde(default)]
+    pub enable_stop_loss: bool,
+    #[serde(default)]
+    pub stop_loss_percentage: Option<Decimal>,
+    /// Sell the whole position once the price rises `take_profit_percentage` above the
+    /// average entry price
+    #[serde(default)]
+    pub enable_take_profit: bool,
+    #[serde(default)]
+    pub take_profit_percentage: Option<Decimal>,
 }
 
 /// Additional filters for DCA execution
@@ -93,6 +129,7 @@ impl DCAConfig {
         Self {
             base_amount,
             frequency,
+            interval: None,
             strategy_type: DCAType::Simple,
             rsi_config: None,
             volatility_config: None,
@@ -109,6 +146,14 @@ impl DCAConfig {
             pause_on_bear_market: false,
             bear_market_threshold: None,
             filters: DCAFilters::default(),
+            execution_order_type: ExecutionOrderType::default(),
+            min_confidence: None,
+            max_order_notional: None,
+            clamp_oversized_orders: false,
+            enable_stop_loss: false,
+            stop_loss_percentage: None,
+            enable_take_profit: false,
+            take_profit_percentage: None,
         }
     }
 
@@ -121,6 +166,7 @@ impl DCAConfig {
         Self {
             base_amount,
             frequency,
+            interval: None,
             strategy_type: DCAType::RSIBased,
             rsi_config: Some(rsi_config),
             volatility_config: None,
@@ -137,6 +183,14 @@ impl DCAConfig {
             pause_on_bear_market: false,
             bear_market_threshold: None,
             filters: DCAFilters::default(),
+            execution_order_type: ExecutionOrderType::default(),
+            min_confidence: None,
+            max_order_notional: None,
+            clamp_oversized_orders: false,
+            enable_stop_loss: false,
+            stop_loss_percentage: None,
+            enable_take_profit: false,
+            take_profit_percentage: None,
         }
     }
 
@@ -149,6 +203,7 @@ impl DCAConfig {
         Self {
             base_amount,
             frequency,
+            interval: None,
             strategy_type: DCAType::VolatilityBased,
             rsi_config: None,
             volatility_config: Some(volatility_config),
@@ -165,6 +220,14 @@ impl DCAConfig {
             pause_on_bear_market: false,
             bear_market_threshold: None,
             filters: DCAFilters::default(),
+            execution_order_type: ExecutionOrderType::default(),
+            min_confidence: None,
+            max_order_notional: None,
+            clamp_oversized_orders: false,
+            enable_stop_loss: false,
+            stop_loss_percentage: None,
+            enable_take_profit: false,
+            take_profit_percentage: None,
         }
     }
 
@@ -178,6 +241,7 @@ impl DCAConfig {
         Self {
             base_amount,
             frequency,
+            interval: None,
             strategy_type: DCAType::DipBuying,
             rsi_config: None,
             volatility_config: None,
@@ -194,6 +258,14 @@ impl DCAConfig {
             pause_on_bear_market: false,
             bear_market_threshold: None,
             filters: DCAFilters::default(),
+            execution_order_type: ExecutionOrderType::default(),
+            min_confidence: None,
+            max_order_notional: None,
+            clamp_oversized_orders: false,
+            enable_stop_loss: false,
+            stop_loss_percentage: None,
+            enable_take_profit: false,
+            take_profit_percentage: None,
         }
     }
 
@@ -208,6 +280,7 @@ impl DCAConfig {
         Self {
             base_amount,
             frequency,
+            interval: None,
             strategy_type: DCAType::Dynamic,
             rsi_config: Some(rsi_config),
             volatility_config: Some(volatility_config),
@@ -224,6 +297,14 @@ impl DCAConfig {
             pause_on_bear_market: false,
             bear_market_threshold: None,
             filters: DCAFilters::default(),
+            execution_order_type: ExecutionOrderType::default(),
+            min_confidence: None,
+            max_order_notional: None,
+            clamp_oversized_orders: false,
+            enable_stop_loss: false,
+            stop_loss_percentage: None,
+            enable_take_profit: false,
+            take_profit_percentage: None,
         }
     }
 
@@ -278,9 +359,39 @@ impl DCAConfig {
             }
         }
 
+        if let Some(interval) = &self.interval {
+            interval.validate()?;
+        }
+
+        self.execution_order_type.validate()?;
+        validate_min_confidence(self.min_confidence)?;
+        validate_max_order_notional(self.max_order_notional)?;
+
+        if self.enable_stop_loss {
+            match self.stop_loss_percentage {
+                Some(pct) if pct > Decimal::ZERO && pct < Decimal::from(100) => {}
+                _ => return Err("Stop loss percentage must be between 0 and 100 when stop loss is enabled".to_string()),
+            }
+        }
+        if self.enable_take_profit {
+            match self.take_profit_percentage {
+                Some(pct) if pct > Decimal::ZERO => {}
+                _ => return Err("Take profit percentage must be positive when take profit is enabled".to_string()),
+            }
+        }
+
         Ok(())
     }
 
+    /// When the purchase after one at `last` is due: on `interval` when it is set,
+    /// otherwise `frequency` later
+    pub fn next_purchase_after(&self, last: DateTime<Utc>) -> DateTime<Utc> {
+        match &self.interval {
+            Some(interval) => interval.next_after(last),
+            None => last + Duration::minutes(self.frequency.to_minutes() as i64),
+        }
+    }
+
     /// Get JSON schema for this configuration
     pub fn json_schema() -> Value {
         json!({
@@ -294,7 +405,7 @@ impl DCAConfig {
                 },
                 "frequency": {
                     "type": "object",
-                    "description": "DCA execution frequency",
+                    "description": "DCA execution frequency (deprecated, use interval)",
                     "oneOf": [
                         {
                             "properties": {
@@ -323,6 +434,31 @@ impl DCAConfig {
                         }
                     ]
                 },
+                "interval": {
+                    "description": "Calendar purchase schedule; replaces frequency when set",
+                    "oneOf": [
+                        {"type": "object", "properties": {"Hours": {"type": "integer", "minimum": 1}}},
+                        {"type": "string", "enum": ["Daily"]},
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Weekly": {
+                                    "type": "object",
+                                    "properties": {"weekday": {"type": "string", "enum": ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]}}
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Monthly": {
+                                    "type": "object",
+                                    "properties": {"day_of_month": {"type": "integer", "minimum": 1, "maximum": 31}}
+                                }
+                            }
+                        }
+                    ]
+                },
                 "strategy_type": {
                     "type": "string",
                     "enum": ["Simple", "RSIBased", "VolatilityBased", "Dynamic", "DipBuying", "SentimentBased"],
@@ -366,6 +502,30 @@ impl DCAConfig {
                     "type": "number",
                     "minimum": 0,
                     "description": "Maximum total position size (stop DCA when reached)"
+                },
+                "execution_order_type": ExecutionOrderType::json_schema(),
+                "min_confidence": min_confidence_schema(),
+                "max_order_notional": max_order_notional_schema(),
+                "clamp_oversized_orders": {
+                    "type": "boolean",
+                    "description": "Shrink oversized orders to the limit instead of rejecting them"
+                },
+                "enable_stop_loss": {
+                    "type": "boolean",
+                    "description": "Sell the whole position when the price falls stop_loss_percentage below the average entry price"
+                },
+                "stop_loss_percentage": {
+                    "type": ["number", "null"],
+                    "exclusiveMinimum": 0,
+                    "exclusiveMaximum": 100
+                },
+                "enable_take_profit": {
+                    "type": "boolean",
+                    "description": "Sell the whole position when the price rises take_profit_percentage above the average entry price"
+                },
+                "take_profit_percentage": {
+                    "type": ["number", "null"],
+                    "exclusiveMinimum": 0
                 }
             }
         })
@@ -1,5 +1,6 @@
-use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata};
+use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata, StrategyPreset};
 use super::DCAStrategy;
+use super::presets::DCAPresets;
 
 /// Factory for creating DCA strategy instances
 pub struct DCAStrategyFactory {
@@ -22,6 +23,10 @@ impl StrategyFactory for DCAStrategyFactory {
     fn metadata(&self) -> &StrategyMetadata {
         &self.metadata
     }
+
+    fn presets(&self) -> Vec<StrategyPreset> {
+        DCAPresets::strategy_presets()
+    }
 }
 
 impl Default for DCAStrategyFactory {
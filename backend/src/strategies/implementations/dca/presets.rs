@@ -1,5 +1,12 @@
 use rust_decimal::Decimal;
 use super::{DCAConfig, DCAFrequency, RSIConfig, VolatilityConfig, DipBuyingLevel, DynamicFactors, DCAFilters};
+use crate::strategies::core::{RiskLevel, StrategyPreset};
+use crate::utils::errors::AppError;
+
+/// Amount per purchase the registry presets are expressed in
+const REFERENCE_BASE_AMOUNT: i64 = 100;
+/// Position cap used for the registry's risk-managed preset
+const REFERENCE_MAX_POSITION: i64 = 10_000;
 
 /// Preset configurations for common DCA strategies
 pub struct DCAPresets;
@@ -161,6 +168,67 @@ impl DCAPresets {
     pub fn get_risk_managed_preset() -> (&'static str, &'static str, fn(Decimal, Decimal) -> DCAConfig) {
         ("risk_managed", "Risk-managed DCA with strict limits", Self::risk_managed)
     }
+
+    /// Build a preset scaled to `base_amount`. `max_position` is required for `risk_managed`.
+    pub fn build(preset_id: &str, base_amount: Decimal, max_position: Option<Decimal>) -> Result<DCAConfig, AppError> {
+        let (risk_id, _, risk_managed) = Self::get_risk_managed_preset();
+        if preset_id == risk_id {
+            let max_position = max_position
+                .ok_or_else(|| AppError::BadRequest("max_position_size required for risk_managed preset".to_string()))?;
+            return Ok(risk_managed(base_amount, max_position));
+        }
+
+        Self::get_all_presets()
+            .into_iter()
+            .find(|(id, _, _)| *id == preset_id)
+            .map(|(_, _, preset_fn)| preset_fn(base_amount))
+            .ok_or_else(|| AppError::BadRequest("Invalid preset_id".to_string()))
+    }
+
+    /// Presets registered with the strategy registry, at a base amount of 100 per purchase
+    pub fn strategy_presets() -> Vec<StrategyPreset> {
+        let base_amount = Decimal::from(REFERENCE_BASE_AMOUNT);
+        let (risk_id, risk_description, risk_managed) = Self::get_risk_managed_preset();
+
+        let mut presets: Vec<StrategyPreset> = Self::get_all_presets()
+            .into_iter()
+            .map(|(id, description, preset_fn)| {
+                StrategyPreset::new(id, &display_name(id), description, Self::risk_level(id), &preset_fn(base_amount))
+            })
+            .collect();
+        presets.push(StrategyPreset::new(
+            risk_id,
+            &display_name(risk_id),
+            risk_description,
+            Self::risk_level(risk_id),
+            &risk_managed(base_amount, Decimal::from(REFERENCE_MAX_POSITION)),
+        ));
+
+        presets
+    }
+
+    fn risk_level(preset_id: &str) -> RiskLevel {
+        match preset_id {
+            "conservative" | "weekend_warrior" | "business_hours" | "risk_managed" => RiskLevel::Conservative,
+            "aggressive_rsi" | "dip_buyer" | "bear_market_hunter" => RiskLevel::Aggressive,
+            _ => RiskLevel::Moderate,
+        }
+    }
+}
+
+/// "bear_market_hunter" -> "Bear Market Hunter"
+fn display_name(preset_id: &str) -> String {
+    preset_id
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 #[cfg(test)]
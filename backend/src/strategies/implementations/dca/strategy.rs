@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc, Duration, Datelike, Timelike};
+use chrono::{DateTime, Utc, Datelike, Timelike};
 use rust_decimal::Decimal;
 use serde_json::Value;
 use tracing::{debug, info, warn};
@@ -8,6 +8,7 @@ use uuid::Uuid;
 use crate::strategies::core::{
     Strategy, StrategyMetadata, StrategyMode, StrategyContext, StrategySignal,
     StrategyCategory, RiskLevel, LiveExecutableStrategy, ControllableStrategy, IndicatorValue, MarketData,
+    StrategyLifecycle, LifecycleEvent, QuantityType, MinNotionalAccumulator,
 };
 use crate::strategies::indicators;
 use crate::utils::errors::AppError;
@@ -23,10 +24,8 @@ pub struct DCAStrategy {
     state: DCAState,
     /// Execution history
     execution_history: Vec<DCAExecution>,
-    /// Is strategy currently paused
-    is_paused: bool,
-    /// Is strategy currently running (for live execution)
-    is_running: bool,
+    /// Lifecycle state; gates analysis and live execution
+    lifecycle: StrategyLifecycle,
     /// Last signal reason
     last_signal_reason: String,
     /// Strategy metadata
@@ -40,8 +39,7 @@ impl DCAStrategy {
             config: None,
             state: DCAState::default(),
             execution_history: Vec::new(),
-            is_paused: false,
-            is_running: false,
+            lifecycle: StrategyLifecycle::Created,
             last_signal_reason: String::new(),
             metadata: Self::create_metadata(),
         }
@@ -86,19 +84,18 @@ impl DCAStrategy {
             None => return false,
         };
 
-        // Check if strategy is paused
-        if self.is_paused {
-            debug!("DCA execution skipped - strategy is paused");
+        // Check if strategy is paused, halted or stopped
+        if !self.lifecycle.accepts_signals() {
+            debug!("DCA execution skipped - strategy is {}", self.lifecycle);
             return false;
         }
 
         // Check time-based execution
         if let Some(last_execution) = self.state.last_execution {
-            let time_diff = context.current_time - last_execution;
-            let required_interval = Duration::minutes(config.frequency.to_minutes() as i64);
+            let next_purchase = config.next_purchase_after(last_execution);
 
-            if time_diff < required_interval {
-                let remaining = required_interval - time_diff;
+            if context.current_time < next_purchase {
+                let remaining = next_purchase - context.current_time;
                 debug!("DCA execution too early - {} minutes remaining", remaining.num_minutes());
                 return false;
             }
@@ -127,6 +124,40 @@ impl DCAStrategy {
         true
     }
 
+    /// Sell the whole position when the price has fallen to the stop loss or risen to the
+    /// take profit, both measured from the average entry price across buys. The cost basis
+    /// is kept until the sell fills (see `record_sale`), so a rejected exit is signalled
+    /// again on the next bar.
+    fn check_exit(&mut self, context: &StrategyContext) -> Option<StrategySignal> {
+        let config = self.config.as_ref()?;
+        if !self.lifecycle.accepts_signals()
+            || self.state.total_quantity <= Decimal::ZERO
+            || self.state.average_price <= Decimal::ZERO
+        {
+            return None;
+        }
+
+        let average_price = self.state.average_price;
+        let change_pct = (context.current_price - average_price) / average_price * Decimal::from(100);
+
+        let reason = match (config.stop_loss_percentage, config.take_profit_percentage) {
+            (Some(stop_loss_pct), _) if config.enable_stop_loss && change_pct <= -stop_loss_pct => {
+                format!("Stop loss: price {:.2}% below average entry {:.2}", -change_pct, average_price)
+            }
+            (_, Some(take_profit_pct)) if config.enable_take_profit && change_pct >= take_profit_pct => {
+                format!("Take profit: price {:.2}% above average entry {:.2}", change_pct, average_price)
+            }
+            _ => return None,
+        };
+
+        info!("DCA exit of {} {} at {} ({})",
+              self.state.total_quantity, context.symbol, context.current_price, reason);
+
+        self.last_signal_reason = reason.clone();
+
+        Some(StrategySignal::sell(context.symbol.clone(), QuantityType::AllPosition, reason, None))
+    }
+
     /// Check if execution passes all filters
     fn passes_filters(&self, context: &StrategyContext, config: &DCAConfig) -> bool {
         let filters = &config.filters;
@@ -493,6 +524,29 @@ impl DCAStrategy {
         conditions
     }
 
+    /// Buys held back because they were below the exchange minimum
+    pub fn pending_buys(&self) -> &MinNotionalAccumulator {
+        &self.state.pending_buys
+    }
+
+    /// Replace the held-back buys, for an execution engine that combines them itself
+    pub fn set_pending_buys(&mut self, pending: MinNotionalAccumulator) {
+        self.state.pending_buys = pending;
+    }
+
+    /// Take a filled sell off the cost basis. Selling everything clears it, so later buys
+    /// start a new one; a partial sale keeps the average entry for what is left.
+    fn record_sale(&mut self, quantity: Decimal) {
+        if quantity >= self.state.total_quantity {
+            self.state.total_invested = Decimal::ZERO;
+            self.state.total_quantity = Decimal::ZERO;
+            self.state.average_price = Decimal::ZERO;
+        } else {
+            self.state.total_quantity -= quantity;
+            self.state.total_invested = self.state.average_price * self.state.total_quantity;
+        }
+    }
+
     /// Record execution in state and history
     fn record_execution(&mut self, context: &StrategyContext, amount: Decimal, market_conditions: MarketConditions) {
         let quantity = amount / context.current_price;
@@ -572,11 +626,12 @@ impl Strategy for DCAStrategy {
         // Validate configuration
         config.validate()
             .map_err(|e| AppError::BadRequest(e))?;
+        let lifecycle = self.lifecycle.transition(LifecycleEvent::Initialize)?;
 
         self.config = Some(config.clone());
         self.state = DCAState::default();
         self.execution_history.clear();
-        self.is_paused = false;
+        self.lifecycle = lifecycle;
         self.last_signal_reason = "Strategy initialized".to_string();
 
         info!("DCA strategy initialized successfully with type: {:?}, has RSI config: {}",
@@ -588,6 +643,11 @@ impl Strategy for DCAStrategy {
         &mut self,
         context: &StrategyContext,
     ) -> Result<Option<StrategySignal>, AppError> {
+        // Exits don't wait for the next scheduled purchase
+        if let Some(exit) = self.check_exit(context) {
+            return Ok(Some(exit));
+        }
+
         if !self.should_execute(context) {
             return Ok(None);
         }
@@ -599,6 +659,23 @@ impl Strategy for DCAStrategy {
             return Ok(None);
         }
 
+        // Buys the exchange would reject as too small are combined into a later order
+        let amount = match context.market_data.min_notional {
+            Some(min_notional) => match self.state.pending_buys.accumulate(amount, min_notional) {
+                Some(total) => total,
+                None => {
+                    self.state.last_execution = Some(context.current_time);
+                    self.last_signal_reason = format!(
+                        "Holding ${} across {} buys until the ${} minimum order is reached",
+                        self.state.pending_buys.pending_amount, self.state.pending_buys.pending_count, min_notional
+                    );
+                    debug!("{}", self.last_signal_reason);
+                    return Ok(None);
+                }
+            },
+            None => amount,
+        };
+
         // Capture market conditions for signal metadata
         let market_conditions = self.capture_market_conditions(context);
 
@@ -654,6 +731,14 @@ impl Strategy for DCAStrategy {
         DCAConfig::json_schema()
     }
 
+    fn lifecycle(&self) -> StrategyLifecycle {
+        self.lifecycle
+    }
+
+    fn into_live(self: Box<Self>) -> Option<Box<dyn LiveExecutableStrategy>> {
+        Some(self)
+    }
+
     fn get_state(&self) -> Result<Value, AppError> {
         let mut state_with_metadata = serde_json::to_value(&self.state)
             .map_err(|e| AppError::BadRequest(format!("Failed to serialize state: {}", e)))?;
@@ -683,10 +768,19 @@ impl Strategy for DCAStrategy {
 
     /// Handle order updates - record executions when orders are filled
     async fn on_order_update(&mut self, order: &crate::strategies::core::OrderUpdate) -> Result<(), AppError> {
-        // Only process filled market buy orders (DCA purchases)  
-        if order.status == crate::strategies::core::traits::OrderStatus::Filled && 
-           matches!(order.order_type, crate::strategies::core::traits::OrderType::Market) &&
-           order.filled_quantity > Decimal::ZERO {
+        if order.status != crate::strategies::core::traits::OrderStatus::Filled || order.filled_quantity <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        // A filled exit sells down the position
+        if order.side == crate::exchange_connectors::common_types::OrderSide::Sell {
+            self.record_sale(order.filled_quantity);
+            return Ok(());
+        }
+
+        // Filled market buys are DCA purchases, including any held-back buys they combined
+        if matches!(order.order_type, crate::strategies::core::traits::OrderType::Market) {
+            self.state.pending_buys.clear();
             
             if let Some(execution_price) = order.price {
                 let amount = order.filled_quantity * execution_price;
@@ -719,25 +813,20 @@ impl Strategy for DCAStrategy {
 #[async_trait]
 impl LiveExecutableStrategy for DCAStrategy {
     async fn start_live_execution(&mut self, _context: &StrategyContext) -> Result<(), AppError> {
-        self.is_running = true;
+        self.lifecycle.apply(LifecycleEvent::Start)?;
         info!("DCA strategy started for live execution");
         Ok(())
     }
 
     async fn stop_live_execution(&mut self) -> Result<(), AppError> {
-        self.is_running = false;
+        self.lifecycle.apply(LifecycleEvent::Stop)?;
         info!("DCA strategy stopped");
         Ok(())
     }
 
-    fn is_running(&self) -> bool {
-        self.is_running
-    }
-
     fn next_execution_time(&self) -> Option<DateTime<Utc>> {
         if let (Some(config), Some(last_execution)) = (&self.config, self.state.last_execution) {
-            let interval_minutes = config.frequency.to_minutes() as i64;
-            Some(last_execution + Duration::minutes(interval_minutes))
+            Some(config.next_purchase_after(last_execution))
         } else {
             None
         }
@@ -747,20 +836,16 @@ impl LiveExecutableStrategy for DCAStrategy {
 #[async_trait]
 impl ControllableStrategy for DCAStrategy {
     async fn pause(&mut self) -> Result<(), AppError> {
-        self.is_paused = true;
+        self.lifecycle.apply(LifecycleEvent::Pause)?;
         info!("DCA strategy paused");
         Ok(())
     }
 
     async fn resume(&mut self) -> Result<(), AppError> {
-        self.is_paused = false;
+        self.lifecycle.apply(LifecycleEvent::Resume)?;
         info!("DCA strategy resumed");
         Ok(())
     }
-
-    fn is_paused(&self) -> bool {
-        self.is_paused
-    }
 }
 
 
@@ -3,15 +3,17 @@ mod tests {
     // Import all required types explicitly
     use crate::strategies::core::{
         StrategyContext, StrategyMode, StrategyContextBuilder,
-        Strategy, StrategySignalType, QuantityType, StrategyCategory, RiskLevel,
+        Strategy, StrategySignal, StrategySignalType, QuantityType, StrategyCategory, RiskLevel,
         ControllableStrategy
     };
+    use crate::strategies::core::traits::{OrderStatus, OrderType, OrderUpdate};
     use crate::strategies::implementations::dca::{
-        DCAStrategy, DCAConfig, DCAFrequency, RSIConfig, 
+        DCAStrategy, DCAConfig, DCAFrequency, DCAInterval, RSIConfig, 
         DipBuyingLevel, presets::DCAPresets
     };
     use crate::exchange_connectors::Kline;
-    use chrono::{Utc, Duration};
+    use crate::exchange_connectors::common_types::OrderSide;
+    use chrono::{DateTime, Duration, TimeZone, Utc, Weekday};
     use rust_decimal::Decimal;
     use uuid::Uuid;
 
@@ -233,6 +235,135 @@ mod tests {
         assert!(signal.is_none(), "Should not generate signal due to weekday filter");
     }
 
+    #[tokio::test]
+    async fn test_sub_minimum_buys_accumulate_into_one_order() {
+        let mut strategy = DCAStrategy::new();
+        let config = DCAConfig::simple(Decimal::from(4), DCAFrequency::Daily(1));
+        let config_json = serde_json::to_value(&config).unwrap();
+
+        let historical_data = create_test_klines(50, Decimal::from(50000), Decimal::from(1000));
+        let mut context = create_test_context(historical_data, Decimal::from(50000), Decimal::from(10000));
+        // The exchange's minimum order value for the symbol
+        context.market_data.min_notional = Some(Decimal::from(10));
+        strategy.initialize(&config_json, StrategyMode::Paper, &context).await.unwrap();
+
+        let start = context.current_time;
+        let mut signals = Vec::new();
+        for day in 0..3 {
+            context.current_time = start + Duration::days(day);
+            signals.push(strategy.analyze(&context).await.unwrap());
+        }
+
+        // The first two $4 buys are below the $10 minimum and are held back
+        assert!(signals[0].is_none());
+        assert!(signals[1].is_none());
+        let combined = signals[2].as_ref().expect("third buy should place the combined order");
+        assert!(matches!(combined.action.quantity, QuantityType::DollarAmount(amount) if amount == Decimal::from(12)));
+        // The held-back buys are only dropped once the combined order fills
+        assert_eq!(strategy.get_state().unwrap()["pending_buys"]["pending_count"], 3);
+        fill_buy(&mut strategy, combined, &context).await;
+        assert_eq!(strategy.get_state().unwrap()["pending_buys"]["pending_count"], 0);
+    }
+
+    /// Fill a DCA buy signal at the context's price, as the engine would
+    async fn fill_buy(strategy: &mut DCAStrategy, signal: &StrategySignal, context: &StrategyContext) {
+        let QuantityType::DollarAmount(amount) = signal.action.quantity else {
+            panic!("Expected DollarAmount quantity type, got {:?}", signal.action.quantity);
+        };
+        let quantity = amount / context.current_price;
+        let order = OrderUpdate {
+            order_id: Uuid::new_v4().to_string(),
+            symbol: context.symbol.clone(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            status: OrderStatus::Filled,
+            quantity,
+            price: Some(context.current_price),
+            filled_quantity: quantity,
+            timestamp: context.current_time,
+        };
+        strategy.on_order_update(&order).await.unwrap();
+    }
+
+    /// Fill a sell of `quantity` at the context's price
+    async fn fill_sell(strategy: &mut DCAStrategy, quantity: Decimal, context: &StrategyContext) {
+        let order = OrderUpdate {
+            order_id: Uuid::new_v4().to_string(),
+            symbol: context.symbol.clone(),
+            side: OrderSide::Sell,
+            order_type: OrderType::Market,
+            status: OrderStatus::Filled,
+            quantity,
+            price: Some(context.current_price),
+            filled_quantity: quantity,
+            timestamp: context.current_time,
+        };
+        strategy.on_order_update(&order).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exits_at_take_profit_and_stop_loss_from_average_entry() {
+        let mut strategy = DCAStrategy::new();
+        let config = DCAConfig {
+            enable_stop_loss: true,
+            stop_loss_percentage: Some(Decimal::from(50)),
+            enable_take_profit: true,
+            take_profit_percentage: Some(Decimal::from(10)),
+            ..DCAConfig::simple(Decimal::from(100), DCAFrequency::Daily(1))
+        };
+        let historical_data = create_test_klines(50, Decimal::from(100), Decimal::ONE);
+        let mut context = create_test_context(historical_data, Decimal::from(100), Decimal::from(10000));
+        strategy.initialize(&serde_json::to_value(&config).unwrap(), StrategyMode::Paper, &context).await.unwrap();
+
+        // $100 a day at 100, 80 and 50: 4.25 units for $300. None of the dips reaches the
+        // stop loss measured from the average entry at the time.
+        let start = context.current_time;
+        for (day, price) in [(0, 100), (1, 80), (2, 50)] {
+            context.current_time = start + Duration::days(day);
+            context.current_price = Decimal::from(price);
+            let signal = strategy.analyze(&context).await.unwrap().expect("scheduled buy");
+            assert_eq!(signal.signal_type, StrategySignalType::AddToPosition);
+            fill_buy(&mut strategy, &signal, &context).await;
+        }
+        let state = strategy.get_state().unwrap();
+        let average: Decimal = state["average_price"].as_str().unwrap().parse().unwrap();
+        assert_eq!(average, Decimal::from(300) / Decimal::new(425, 2));
+
+        // 78 is 22% below the first entry but 10.5% above the ~70.59 average
+        context.current_time = start + Duration::days(2) + Duration::hours(12);
+        context.current_price = Decimal::from(78);
+        let exit = strategy.analyze(&context).await.unwrap().expect("take profit exit");
+        assert_eq!(exit.signal_type, StrategySignalType::Exit);
+        assert!(matches!(exit.action.quantity, QuantityType::AllPosition));
+        assert!(exit.reason.starts_with("Take profit"), "{}", exit.reason);
+        // The cost basis stays until the sell fills, so an unfilled exit is signalled again
+        assert_eq!(strategy.get_state().unwrap()["total_quantity"], "4.25");
+        assert!(strategy.analyze(&context).await.unwrap().is_some());
+
+        fill_sell(&mut strategy, Decimal::new(425, 2), &context).await;
+        assert_eq!(strategy.get_state().unwrap()["total_quantity"], "0");
+        // With nothing held, the same price signals nothing until the next purchase
+        assert!(strategy.analyze(&context).await.unwrap().is_none());
+
+        // The next buy starts a new cost basis, so a fall to 49 is a 51% loss
+        context.current_time = start + Duration::days(3);
+        context.current_price = Decimal::from(100);
+        let signal = strategy.analyze(&context).await.unwrap().expect("scheduled buy");
+        fill_buy(&mut strategy, &signal, &context).await;
+
+        context.current_time += Duration::hours(6);
+        context.current_price = Decimal::from(51);
+        assert!(strategy.analyze(&context).await.unwrap().is_none());
+        context.current_price = Decimal::from(49);
+        let exit = strategy.analyze(&context).await.unwrap().expect("stop loss exit");
+        assert_eq!(exit.signal_type, StrategySignalType::Exit);
+        assert!(exit.reason.starts_with("Stop loss"), "{}", exit.reason);
+
+        // Enabled thresholds need a percentage
+        let missing = DCAConfig { take_profit_percentage: None, ..config };
+        assert!(missing.validate().is_err());
+    }
+
     #[tokio::test]
     async fn test_strategy_pause_resume() {
         let mut strategy = DCAStrategy::new();
@@ -305,4 +436,61 @@ mod tests {
         assert!(metadata.min_balance.is_some());
         assert!(metadata.min_balance.unwrap() > Decimal::ZERO);
     }
-}
\ No newline at end of file
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_monthly_interval_clamps_to_the_last_day_of_short_months() {
+        let monthly = DCAInterval::Monthly { day_of_month: 31 };
+
+        // A strategy started mid-month buys at the end of that same month
+        assert_eq!(monthly.next_after(at(2024, 1, 10, 9)), at(2024, 1, 31, 9));
+        assert_eq!(monthly.next_after(at(2024, 1, 31, 9)), at(2024, 2, 29, 9));
+        // Clamping in February does not drag later months back to the 29th
+        assert_eq!(monthly.next_after(at(2024, 2, 29, 9)), at(2024, 3, 31, 9));
+        assert_eq!(monthly.next_after(at(2024, 3, 31, 9)), at(2024, 4, 30, 9));
+        assert_eq!(monthly.next_after(at(2023, 2, 1, 9)), at(2023, 2, 28, 9));
+        assert_eq!(monthly.next_after(at(2024, 12, 31, 9)), at(2025, 1, 31, 9));
+
+        assert!(DCAInterval::Monthly { day_of_month: 0 }.validate().is_err());
+        assert!(DCAInterval::Monthly { day_of_month: 32 }.validate().is_err());
+        assert!(monthly.validate().is_ok());
+    }
+
+    #[test]
+    fn test_weekly_interval_buys_on_its_weekday() {
+        let weekly = DCAInterval::Weekly { weekday: Weekday::Mon };
+
+        // 2024-01-03 is a Wednesday
+        assert_eq!(weekly.next_after(at(2024, 1, 3, 14)), at(2024, 1, 8, 14));
+        assert_eq!(weekly.next_after(at(2024, 1, 8, 14)), at(2024, 1, 15, 14));
+    }
+
+    #[test]
+    fn test_interval_takes_precedence_over_deprecated_frequency() {
+        let last = at(2024, 1, 31, 9);
+        let mut config = DCAConfig::simple(Decimal::from(100), DCAFrequency::Monthly(1));
+        assert_eq!(config.next_purchase_after(last), last + Duration::days(30));
+
+        config.interval = Some(DCAInterval::Monthly { day_of_month: 31 });
+        assert_eq!(config.next_purchase_after(last), at(2024, 2, 29, 9));
+
+        config.interval = Some(DCAInterval::Hours(0));
+        assert!(config.validate().is_err());
+
+        // Configs saved before `interval` existed keep using their frequency
+        let mut saved = serde_json::to_value(DCAConfig::simple(Decimal::from(100), DCAFrequency::Hourly(6))).unwrap();
+        saved.as_object_mut().unwrap().remove("interval");
+        let saved: DCAConfig = serde_json::from_value(saved).unwrap();
+        assert_eq!(saved.interval, None);
+        assert_eq!(saved.next_purchase_after(last), last + Duration::hours(6));
+
+        config.interval = Some(DCAInterval::Weekly { weekday: Weekday::Fri });
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["interval"], serde_json::json!({ "Weekly": { "weekday": "Fri" } }));
+        let round_trip: DCAConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip.interval, config.interval);
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use rust_decimal::Decimal;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+
+use crate::strategies::core::MinNotionalAccumulator;
 
 /// DCA strategy variants
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -46,6 +49,67 @@ impl DCAFrequency {
     }
 }
 
+/// Calendar schedule for DCA purchases
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DCAInterval {
+    /// Every N hours
+    Hours(i64),
+    /// Once a day
+    Daily,
+    /// Once a week, on `weekday`
+    Weekly { weekday: Weekday },
+    /// Once a month, on `day_of_month`; in shorter months a later day falls on the last one
+    Monthly { day_of_month: u32 },
+}
+
+impl DCAInterval {
+    /// Next purchase time after one at `last`. Weekly and monthly purchases keep the
+    /// time of day of `last`.
+    pub fn next_after(&self, last: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            DCAInterval::Hours(hours) => last + Duration::hours(*hours),
+            DCAInterval::Daily => last + Duration::days(1),
+            DCAInterval::Weekly { weekday } => {
+                let days_ahead = (weekday.num_days_from_monday() + 7 - last.weekday().num_days_from_monday()) % 7;
+                last + Duration::days(if days_ahead == 0 { 7 } else { days_ahead as i64 })
+            }
+            DCAInterval::Monthly { day_of_month } => {
+                let this_month = on_day_of_month(last, last.year(), last.month(), *day_of_month);
+                if this_month > last {
+                    return this_month;
+                }
+                let (year, month) = match last.month() {
+                    12 => (last.year() + 1, 1),
+                    month => (last.year(), month + 1),
+                };
+                on_day_of_month(last, year, month, *day_of_month)
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            DCAInterval::Hours(hours) if *hours < 1 => Err("Interval must be at least 1 hour".to_string()),
+            DCAInterval::Monthly { day_of_month } if !(1..=31).contains(day_of_month) => {
+                Err("Day of month must be between 1 and 31".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `day` of the given month, or its last day if the month is shorter, at the time of day of `time`
+fn on_day_of_month(time: DateTime<Utc>, year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    let first_of_next = match month {
+        12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        _ => NaiveDate::from_ymd_opt(year, month + 1, 1),
+    };
+    let last_day = first_of_next.and_then(|date| date.pred_opt()).map_or(28, |date| date.day());
+
+    NaiveDate::from_ymd_opt(year, month, day.clamp(1, last_day))
+        .map_or(time, |date| date.and_time(time.time()).and_utc())
+}
+
 /// Price level configuration for dip buying
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DipBuyingLevel {
@@ -149,8 +213,9 @@ pub struct DynamicFactors {
     pub min_multiplier: Decimal,
 }
 
-/// DCA execution state
+/// DCA execution state. Fields missing from saved state start at their defaults.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct DCAState {
     /// Last execution timestamp
     pub last_execution: Option<chrono::DateTime<chrono::Utc>>,
@@ -164,6 +229,9 @@ pub struct DCAState {
     pub purchase_count: u32,
     /// Execution count for each dip level
     pub dip_level_executions: std::collections::HashMap<String, u32>,
+    /// Buys held back because they were below the minimum order notional
+    #[serde(default)]
+    pub pending_buys: MinNotionalAccumulator,
 }
 
 /// DCA execution record
@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use rust_decimal::Decimal;
 
-use super::types::{GridRiskSettings, GridSpacing, GridBounds, GridTradingMode, BoundsType};
+use crate::strategies::core::{
+    max_order_notional_schema, min_confidence_schema, validate_max_order_notional, validate_min_confidence,
+    ExecutionOrderType,
+};
+use super::types::{GridRiskSettings, GridSpacing, GridBounds, GridTradingMode, BoundsType, OrderSizeMode, BoundBreachAction, GridTrendFilter};
 
 /// Complete Grid Trading strategy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +35,30 @@ pub struct GridTradingConfig {
     pub stop_loss_threshold: Option<Decimal>,
     /// Market making settings
     pub market_making: MarketMakingSettings,
+    /// How total investment is distributed across levels
+    #[serde(default)]
+    pub order_size_mode: OrderSizeMode,
+    /// For geometric sizing: size ratio between consecutive levels (default 1.5)
+    #[serde(default)]
+    pub order_size_ratio: Option<Decimal>,
+    /// What to do when price breaks out of the grid bounds
+    #[serde(default)]
+    pub on_bound_breach: BoundBreachAction,
+    /// Market or offset limit orders for grid fills
+    #[serde(default)]
+    pub execution_order_type: ExecutionOrderType,
+    /// Signals below this confidence (0-1) are not executed
+    #[serde(default)]
+    pub min_confidence: Option<Decimal>,
+    /// Largest quote value a single live order may have
+    #[serde(default)]
+    pub max_order_notional: Option<Decimal>,
+    /// Shrink orders above `max_order_notional` to the limit instead of rejecting them
+    #[serde(default)]
+    pub clamp_oversized_orders: bool,
+    /// Pause fills (or flatten) while a strong RSI/MACD trend runs against the inventory
+    #[serde(default)]
+    pub trend_filter: Option<GridTrendFilter>,
 }
 
 /// Market making specific settings
@@ -78,6 +106,14 @@ impl Default for GridTradingConfig {
             take_profit_threshold: Some(Decimal::new(5, 2)), // 5%
             stop_loss_threshold: Some(Decimal::new(10, 2)), // 10%
             market_making: MarketMakingSettings::default(),
+            order_size_mode: OrderSizeMode::Equal,
+            order_size_ratio: None,
+            on_bound_breach: BoundBreachAction::Rebalance,
+            execution_order_type: ExecutionOrderType::Market,
+            min_confidence: None,
+            max_order_notional: None,
+            clamp_oversized_orders: false,
+            trend_filter: None,
         }
     }
 }
@@ -330,6 +366,13 @@ impl GridTradingConfig {
             return Err("Max drawdown percentage must be between 0 and 50".to_string());
         }
 
+        // Validate order sizing
+        if let Some(ratio) = self.order_size_ratio {
+            if ratio <= Decimal::ZERO {
+                return Err("Order size ratio must be positive".to_string());
+            }
+        }
+
         // Validate market making settings
         if self.market_making.enabled {
             if self.market_making.spread_pct <= Decimal::ZERO {
@@ -341,6 +384,14 @@ impl GridTradingConfig {
             }
         }
 
+        if let Some(trend_filter) = &self.trend_filter {
+            trend_filter.validate()?;
+        }
+
+        self.execution_order_type.validate()?;
+        validate_min_confidence(self.min_confidence)?;
+        validate_max_order_notional(self.max_order_notional)?;
+
         Ok(())
     }
 
@@ -352,6 +403,60 @@ impl GridTradingConfig {
         base_size.max(self.min_order_size)
     }
 
+    /// Calculate the order size for each level price according to `order_size_mode`.
+    ///
+    /// Non-equal modes distribute `total_investment` across the given levels so the
+    /// sizes sum to it exactly; levels further from `center_price` get more.
+    pub fn calculate_level_sizes(&self, level_prices: &[Decimal], center_price: Decimal) -> Vec<Decimal> {
+        if level_prices.is_empty() {
+            return Vec::new();
+        }
+
+        let weights: Vec<Decimal> = match self.order_size_mode {
+            OrderSizeMode::Equal => {
+                return vec![self.calculate_order_size_per_level(); level_prices.len()];
+            }
+            OrderSizeMode::Geometric => {
+                let ratio = self.order_size_ratio.unwrap_or(Decimal::new(15, 1)); // 1.5x default
+                level_prices
+                    .iter()
+                    .map(|price| {
+                        // Rank among levels on the same side of the center, nearest first
+                        let distance = (*price - center_price).abs();
+                        let rank = level_prices
+                            .iter()
+                            .filter(|other| (**other < center_price) == (*price < center_price))
+                            .filter(|other| (**other - center_price).abs() < distance)
+                            .count();
+                        (0..rank).fold(Decimal::ONE, |weight, _| weight * ratio)
+                    })
+                    .collect()
+            }
+            OrderSizeMode::WeightedByDistance => {
+                level_prices.iter().map(|price| (*price - center_price).abs()).collect()
+            }
+        };
+
+        let total_weight: Decimal = weights.iter().sum();
+        if total_weight <= Decimal::ZERO {
+            let equal = self.total_investment / Decimal::from(level_prices.len());
+            return vec![equal; level_prices.len()];
+        }
+
+        let mut sizes: Vec<Decimal> = weights
+            .iter()
+            .map(|weight| self.total_investment * weight / total_weight)
+            .collect();
+
+        // Put any rounding remainder on the last level so the sizes sum exactly
+        let allocated: Decimal = sizes[..sizes.len() - 1].iter().sum();
+        if let Some(last) = sizes.last_mut() {
+            *last = self.total_investment - allocated;
+        }
+
+        sizes
+    }
+
     /// Get JSON schema for this configuration
     pub fn json_schema() -> Value {
         json!({
@@ -449,6 +554,16 @@ impl GridTradingConfig {
                     "minimum": 0,
                     "description": "Minimum order size"
                 },
+                "order_size_mode": {
+                    "type": "string",
+                    "enum": ["Equal", "Geometric", "WeightedByDistance"],
+                    "description": "How total investment is split across grid levels"
+                },
+                "order_size_ratio": {
+                    "type": "number",
+                    "minimum": 0,
+                    "description": "Size ratio between consecutive levels for Geometric sizing"
+                },
                 "enable_rebalancing": {
                     "type": "boolean",
                     "description": "Enable grid rebalancing"
@@ -458,6 +573,36 @@ impl GridTradingConfig {
                     "minimum": 1,
                     "description": "Rebalancing interval in hours"
                 },
+                "on_bound_breach": {
+                    "type": "string",
+                    "enum": ["Rebalance", "Expand", "Halt"],
+                    "description": "Action when price breaks out of the grid bounds"
+                },
+                "execution_order_type": ExecutionOrderType::json_schema(),
+                "min_confidence": min_confidence_schema(),
+                "max_order_notional": max_order_notional_schema(),
+                "clamp_oversized_orders": {
+                    "type": "boolean",
+                    "description": "Shrink oversized orders to the limit instead of rejecting them"
+                },
+                "trend_filter": {
+                    "type": ["object", "null"],
+                    "description": "Pause buy fills (or flatten) during a strong downtrend confirmed by RSI and MACD",
+                    "properties": {
+                        "rsi_period": { "type": "integer", "minimum": 1 },
+                        "rsi_oversold": { "type": "number", "minimum": 0, "maximum": 100 },
+                        "rsi_overbought": { "type": "number", "minimum": 0, "maximum": 100 },
+                        "confirmation_bars": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Consecutive bars the RSI must stay past its threshold"
+                        },
+                        "require_macd": { "type": "boolean" },
+                        "macd_fast_period": { "type": "integer", "minimum": 1 },
+                        "macd_slow_period": { "type": "integer", "minimum": 2 },
+                        "action": { "type": "string", "enum": ["PauseFills", "Flatten"] }
+                    }
+                },
                 "market_making": {
                     "type": "object",
                     "properties": {
@@ -1,5 +1,5 @@
-use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata};
-use super::GridTradingStrategy;
+use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata, StrategyPreset};
+use super::{GridTradingStrategy, grid_trading_presets};
 
 /// Factory for creating Grid Trading strategy instances
 pub struct GridTradingStrategyFactory {
@@ -23,6 +23,10 @@ impl StrategyFactory for GridTradingStrategyFactory {
     fn metadata(&self) -> &StrategyMetadata {
         &self.metadata
     }
+
+    fn presets(&self) -> Vec<StrategyPreset> {
+        grid_trading_presets()
+    }
 }
 
 impl Default for GridTradingStrategyFactory {
@@ -3,9 +3,14 @@ mod config;
 mod types;
 mod factory;
 mod registration;
+mod presets;
+
+#[cfg(test)]
+mod tests;
 
 pub use strategy::*;
 pub use config::*;
 pub use types::*;
 pub use factory::*;
 pub use registration::*;
+pub use presets::*;
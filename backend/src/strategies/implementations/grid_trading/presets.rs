@@ -0,0 +1,36 @@
+use rust_decimal::Decimal;
+
+use crate::strategies::core::{RiskLevel, StrategyPreset};
+use super::GridTradingConfig;
+
+/// Total investment the registry presets are expressed in
+const REFERENCE_INVESTMENT: i64 = 1_000;
+
+/// Presets registered with the strategy registry, sized for a 1,000 investment
+pub fn grid_trading_presets() -> Vec<StrategyPreset> {
+    let investment = Decimal::from(REFERENCE_INVESTMENT);
+
+    vec![
+        StrategyPreset::new(
+            "conservative",
+            "Conservative",
+            "10 levels 0.5% apart within 5% of the center, with tight drawdown limits",
+            RiskLevel::Conservative,
+            &GridTradingConfig::conservative(10, investment),
+        ),
+        StrategyPreset::new(
+            "balanced",
+            "Balanced",
+            "15 levels whose spacing follows volatility, rebalanced every 8 hours",
+            RiskLevel::Moderate,
+            &GridTradingConfig::dynamic(15, investment),
+        ),
+        StrategyPreset::new(
+            "aggressive",
+            "Aggressive",
+            "20 levels 2% apart across a 20% range with no rebalancing",
+            RiskLevel::Aggressive,
+            &GridTradingConfig::aggressive(20, investment),
+        ),
+    ]
+}
@@ -1,13 +1,15 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc, Duration};
 use rust_decimal::Decimal;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::{info, warn};
 
 use crate::strategies::core::{
     Strategy, StrategyMetadata, StrategyMode, StrategyContext, StrategySignal,
     StrategyCategory, RiskLevel, LiveExecutableStrategy, ControllableStrategy, IndicatorValue, QuantityType,
+    RuleEvaluation, StrategyLifecycle, LifecycleEvent,
 };
+use crate::exchange_connectors::Kline;
 use crate::strategies::indicators::{self};
 use crate::utils::errors::AppError;
 
@@ -22,12 +24,12 @@ pub struct GridTradingStrategy {
     state: GridTradingState,
     /// Execution history
     execution_history: Vec<GridExecution>,
-    /// Is strategy currently paused
-    is_paused: bool,
-    /// Is strategy currently running (for live execution)
-    is_running: bool,
+    /// Lifecycle state; gates analysis and live execution
+    lifecycle: StrategyLifecycle,
     /// Last signal reason
     last_signal_reason: String,
+    /// Structured explanation of the last analyze call
+    last_explanation: Value,
     /// Strategy metadata
     metadata: StrategyMetadata,
 }
@@ -39,9 +41,9 @@ impl GridTradingStrategy {
             config: None,
             state: GridTradingState::default(),
             execution_history: Vec::new(),
-            is_paused: false,
-            is_running: false,
+            lifecycle: StrategyLifecycle::Created,
             last_signal_reason: String::new(),
+            last_explanation: Value::Null,
             metadata: Self::create_metadata(),
         }
     }
@@ -116,8 +118,14 @@ impl GridTradingStrategy {
             }
         }
 
+        // Size each level according to the configured sizing mode
+        let level_prices: Vec<Decimal> = levels.iter().map(|level| level.price).collect();
+        let sizes = config.calculate_level_sizes(&level_prices, center_price);
+        for (level, size) in levels.iter_mut().zip(sizes) {
+            level.quantity = size;
+        }
+
         self.state.grid_levels = levels;
-        self.state.is_active = true;
 
         info!("Grid initialized with {} levels between {} and {}",
               self.state.grid_levels.len(), lower_bound, upper_bound);
@@ -375,10 +383,11 @@ impl GridTradingStrategy {
         self.create_standard_grid(levels, center_price, spacing, upper_bound, lower_bound)
     }
 
-    /// Check if any grid levels should be filled
-    fn check_grid_fills(&mut self, context: &StrategyContext) -> Vec<(usize, TradeSide)> {
+    /// Evaluate the fill conditions of every active grid level. Levels on `paused_side`
+    /// never trigger.
+    fn evaluate_grid_levels(&self, context: &StrategyContext, paused_side: Option<TradeSide>) -> Vec<GridLevelCheck> {
         let current_price = context.current_price;
-        let mut fills = Vec::new();
+        let mut checks = Vec::new();
         let config = self.config.as_ref().unwrap();
 
         for (index, level) in self.state.grid_levels.iter().enumerate() {
@@ -386,20 +395,16 @@ impl GridTradingStrategy {
                 continue;
             }
 
-            match level.order_type {
+            let (side, price_reached, funds_available) = match level.order_type {
                 GridOrderType::Buy => {
-                    if current_price <= level.price {
-                        // Check if we have sufficient balance for this buy
-                        // The backtesting engine will also check this, but we check here
-                        // to avoid generating signals that will fail
-                        let order_cost = level.quantity; // quantity is dollar amount
-
-                        // Allow buys only if we have sufficient balance
-                        // Note: context.available_balance is updated by the backtesting engine
-                        if context.available_balance >= order_cost {
-                            fills.push((index, TradeSide::Buy));
-                        }
-                    }
+                    // Check if we have sufficient balance for this buy
+                    // The backtesting engine will also check this, but we check here
+                    // to avoid generating signals that will fail
+                    let order_cost = level.quantity; // quantity is dollar amount
+
+                    // Allow buys only if we have sufficient balance
+                    // Note: context.available_balance is updated by the backtesting engine
+                    (TradeSide::Buy, current_price <= level.price, context.available_balance >= order_cost)
                 }
                 GridOrderType::Sell => {
                     // Calculate how much BTC this sell would require
@@ -411,15 +416,113 @@ impl GridTradingStrategy {
                     let can_sell = config.market_making.enabled ||
                                    self.state.inventory >= btc_quantity_needed;
 
-                    if can_sell && current_price >= level.price {
-                        fills.push((index, TradeSide::Sell));
-                    }
+                    (TradeSide::Sell, current_price >= level.price, can_sell)
                 }
-                _ => {} // Handle other order types if needed
-            }
+                _ => continue, // Handle other order types if needed
+            };
+
+            let paused_by_trend = paused_side == Some(side);
+            checks.push(GridLevelCheck {
+                index,
+                side,
+                level_price: level.price,
+                price_reached,
+                funds_available,
+                paused_by_trend,
+                triggered: price_reached && funds_available && !paused_by_trend,
+            });
+        }
+
+        checks
+    }
+
+    /// Strong trend in `data` according to the trend filter, with the latest RSI
+    fn detect_trend(filter: &GridTrendFilter, data: &[Kline]) -> (Option<MarketTrend>, Option<Decimal>) {
+        let latest_rsi = indicators::rsi(data, filter.rsi_period);
+        if data.len() < filter.confirmation_bars {
+            return (None, latest_rsi);
         }
 
-        fills
+        // RSI at each of the last `confirmation_bars` bars
+        let Some(rsis) = (0..filter.confirmation_bars)
+            .map(|bars_back| indicators::rsi(&data[..data.len() - bars_back], filter.rsi_period))
+            .collect::<Option<Vec<Decimal>>>()
+        else {
+            return (None, latest_rsi);
+        };
+
+        let macd_line = indicators::macd(data, filter.macd_fast_period, filter.macd_slow_period, 9)
+            .map(|macd| macd.macd_line);
+        let macd_agrees = |trend: MarketTrend| {
+            !filter.require_macd
+                || match (trend, macd_line) {
+                    (MarketTrend::Down, Some(line)) => line < Decimal::ZERO,
+                    (MarketTrend::Up, Some(line)) => line > Decimal::ZERO,
+                    (_, None) => false,
+                }
+        };
+
+        let trend = if rsis.iter().all(|rsi| *rsi <= filter.rsi_oversold) && macd_agrees(MarketTrend::Down) {
+            Some(MarketTrend::Down)
+        } else if rsis.iter().all(|rsi| *rsi >= filter.rsi_overbought) && macd_agrees(MarketTrend::Up) {
+            Some(MarketTrend::Up)
+        } else {
+            None
+        };
+
+        (trend, latest_rsi)
+    }
+
+    /// Sell the whole inventory at market once the trend filter has called a strong downtrend
+    fn flatten_inventory(&mut self, context: &StrategyContext) -> StrategySignal {
+        let price = context.current_price;
+        let quantity = self.state.inventory;
+        let pnl = self.state.record_sell(price, quantity);
+
+        self.state.stats.sell_fills += 1;
+        self.state.total_trades += 1;
+        self.state.stats.total_volume += quantity * price;
+        self.state.stats.min_inventory = self.state.stats.min_inventory.min(self.state.inventory);
+
+        self.last_signal_reason = format!("Trend filter flattened {} at {} (PnL {})", quantity, price, pnl);
+        warn!("{}", self.last_signal_reason);
+
+        StrategySignal::reduce_position(
+            context.symbol.clone(),
+            QuantityType::Fixed(quantity),
+            self.last_signal_reason.clone(),
+            None,
+        )
+        .with_confidence(Decimal::new(9, 1))
+    }
+
+    /// Build the structured explanation returned by `explain`
+    fn build_explanation(
+        &self,
+        context: &StrategyContext,
+        decision: &str,
+        rules: &[RuleEvaluation],
+        levels: &[GridLevelCheck],
+        rebalanced: bool,
+    ) -> Value {
+        json!({
+            "strategy": self.metadata.id,
+            "timestamp": context.current_time,
+            "price": context.current_price,
+            "decision": decision,
+            "indicators": {
+                "grid_center": self.state.grid_center,
+                "grid_lower_bound": self.state.grid_lower_bound,
+                "grid_upper_bound": self.state.grid_upper_bound,
+                "inventory": self.state.inventory,
+                "average_entry_price": self.state.average_entry_price,
+                "realized_pnl": self.state.realized_pnl,
+            },
+            "rebalanced": rebalanced,
+            "rules": rules,
+            "levels": levels,
+            "gated_by": rules.iter().find(|rule| !rule.passed).map(|rule| rule.rule.clone()),
+        })
     }
 
     /// Execute grid level fill
@@ -460,24 +563,19 @@ impl GridTradingStrategy {
             level.total_filled += quantity;
         }
 
+        let inventory_before = self.state.inventory;
+
         // Update strategy state
         match side {
             TradeSide::Buy => {
-                self.state.inventory += btc_quantity;
+                self.state.record_buy(price, btc_quantity);
                 self.state.stats.buy_fills += 1;
                 self.state.stats.total_deployed += quantity; // Track capital deployed
-                self.update_average_price(price, btc_quantity, true);
             }
             TradeSide::Sell => {
-                self.state.inventory -= btc_quantity;
+                // Realize PnL against the average cost before inventory shrinks
+                self.state.record_sell(price, btc_quantity);
                 self.state.stats.sell_fills += 1;
-                self.update_average_price(price, btc_quantity, false);
-
-                // Calculate realized PnL
-                if let Some(avg_price) = self.state.average_entry_price {
-                    let pnl = (price - avg_price) * btc_quantity;
-                    self.state.realized_pnl += pnl;
-                }
             }
         }
 
@@ -497,7 +595,7 @@ impl GridTradingStrategy {
             order_type,
             grid_level_before: level_price,
             grid_level_after: level_price, // Same for grid trading
-            inventory_before: self.state.inventory - if matches!(side, TradeSide::Buy) { quantity } else { -quantity },
+            inventory_before,
             inventory_after: self.state.inventory,
             realized_pnl: self.state.realized_pnl,
             market_conditions: self.capture_market_conditions(context),
@@ -542,22 +640,6 @@ impl GridTradingStrategy {
         Ok(signal)
     }
 
-    /// Update average entry price
-    fn update_average_price(&mut self, price: Decimal, quantity: Decimal, is_buy: bool) {
-        if is_buy {
-            match self.state.average_entry_price {
-                Some(avg_price) => {
-                    let total_cost = avg_price * self.state.inventory.abs() + price * quantity;
-                    let total_quantity = self.state.inventory.abs() + quantity;
-                    self.state.average_entry_price = Some(total_cost / total_quantity);
-                }
-                None => {
-                    self.state.average_entry_price = Some(price);
-                }
-            }
-        }
-    }
-
     /// Check if grid needs rebalancing
     fn needs_rebalancing(&self, context: &StrategyContext) -> bool {
         let config = self.config.as_ref().unwrap();
@@ -566,10 +648,8 @@ impl GridTradingStrategy {
             return false;
         }
 
-        let current_price = context.current_price;
-
         // Check if price is outside grid bounds
-        if current_price >= self.state.grid_upper_bound || current_price <= self.state.grid_lower_bound {
+        if self.is_out_of_bounds(context.current_price) {
             return true;
         }
 
@@ -588,6 +668,84 @@ impl GridTradingStrategy {
         false
     }
 
+    /// Check if price is at or beyond the grid bounds
+    fn is_out_of_bounds(&self, price: Decimal) -> bool {
+        price >= self.state.grid_upper_bound || price <= self.state.grid_lower_bound
+    }
+
+    /// Extend the grid past the breached bound, keeping existing levels and inventory.
+    /// New levels continue the spacing of the outermost levels on the breached side, and
+    /// every level is then resized around the breakout price by the configured sizing mode.
+    fn expand_grid(&mut self, current_price: Decimal) {
+        let config = self.config.as_ref().unwrap();
+        let max_new_levels = config.grid_levels;
+
+        let mut prices: Vec<Decimal> = self.state.grid_levels.iter().map(|level| level.price).collect();
+        prices.sort();
+
+        let fallback_spacing = if config.grid_levels > 1 {
+            (self.state.grid_upper_bound - self.state.grid_lower_bound) / Decimal::from(config.grid_levels - 1)
+        } else {
+            self.state.grid_upper_bound - self.state.grid_lower_bound
+        };
+
+        let expanding_up = current_price >= self.state.grid_upper_bound;
+        let spacing = match (expanding_up, prices.len()) {
+            (true, n) if n >= 2 => prices[n - 1] - prices[n - 2],
+            (false, n) if n >= 2 => prices[1] - prices[0],
+            _ => fallback_spacing,
+        };
+
+        if spacing <= Decimal::ZERO {
+            warn!("Cannot expand grid with non-positive spacing {}", spacing);
+            return;
+        }
+
+        let mut added = 0;
+        if expanding_up {
+            let mut price = self.state.grid_upper_bound;
+            while price <= current_price && added < max_new_levels {
+                price += spacing;
+                self.state.grid_levels.push(GridLevel {
+                    price,
+                    order_type: GridOrderType::Sell,
+                    quantity: Decimal::ZERO,
+                    is_active: true,
+                    fill_count: 0,
+                    last_fill_time: None,
+                    total_filled: Decimal::ZERO,
+                });
+                added += 1;
+            }
+            self.state.grid_upper_bound = price;
+        } else {
+            let mut price = self.state.grid_lower_bound;
+            while price >= current_price && price - spacing > Decimal::ZERO && added < max_new_levels {
+                price -= spacing;
+                self.state.grid_levels.insert(0, GridLevel {
+                    price,
+                    order_type: GridOrderType::Buy,
+                    quantity: Decimal::ZERO,
+                    is_active: true,
+                    fill_count: 0,
+                    last_fill_time: None,
+                    total_filled: Decimal::ZERO,
+                });
+                added += 1;
+            }
+            self.state.grid_lower_bound = price;
+        }
+
+        let level_prices: Vec<Decimal> = self.state.grid_levels.iter().map(|level| level.price).collect();
+        let sizes = config.calculate_level_sizes(&level_prices, current_price);
+        for (level, size) in self.state.grid_levels.iter_mut().zip(sizes) {
+            level.quantity = size;
+        }
+
+        info!("Expanded grid by {} levels to bounds {}-{}",
+              added, self.state.grid_lower_bound, self.state.grid_upper_bound);
+    }
+
     /// Rebalance the grid
     fn rebalance_grid(&mut self, context: &StrategyContext, reason: RebalanceReason) -> Result<(), AppError> {
         let current_price = context.current_price;
@@ -669,6 +827,14 @@ impl GridTradingStrategy {
             }
         }
 
+        // Support/resistance from the previous bar's classic pivot levels
+        if context.historical_data.len() >= 2 {
+            let previous = &context.historical_data[context.historical_data.len() - 2];
+            let pivots = indicators::pivot_points(previous.high, previous.low, previous.close, indicators::PivotMethod::Classic);
+            conditions.support_level = Some(pivots.s1);
+            conditions.resistance_level = Some(pivots.r1);
+        }
+
         conditions
     }
 
@@ -700,6 +866,7 @@ impl Strategy for GridTradingStrategy {
         // Validate configuration
         config.validate()
             .map_err(|e| AppError::BadRequest(e))?;
+        let lifecycle = self.lifecycle.transition(LifecycleEvent::Initialize)?;
 
         info!("GRID STRATEGY CONFIG - Total Investment: ${}, Grid Levels: {}, Investment per Grid: ${}, Available Balance: ${}",
               config.total_investment, config.grid_levels, config.calculate_order_size_per_level(), context.available_balance);
@@ -707,8 +874,9 @@ impl Strategy for GridTradingStrategy {
         self.config = Some(config);
         self.state = GridTradingState::default();
         self.execution_history.clear();
-        self.is_paused = false;
+        self.lifecycle = lifecycle;
         self.last_signal_reason = "Strategy initialized".to_string();
+        self.last_explanation = Value::Null;
 
         // Initialize grid with current market price
         self.initialize_grid(context.current_price)?;
@@ -721,42 +889,118 @@ impl Strategy for GridTradingStrategy {
         &mut self,
         context: &StrategyContext,
     ) -> Result<Option<StrategySignal>, AppError> {
-        if self.is_paused || !self.state.is_active {
+        if !self.lifecycle.accepts_signals() {
+            let rules = [RuleEvaluation::new("grid_active", None, None, false)];
+            self.last_explanation = self.build_explanation(context, "inactive", &rules, &[], false);
             return Ok(None);
         }
 
-        let _config = self.config.as_ref()
+        let config = self.config.as_ref()
             .ok_or_else(|| AppError::BadRequest("Strategy not initialized".to_string()))?;
+        let max_inventory = config.risk_settings.max_inventory;
+        let on_bound_breach = config.on_bound_breach;
+        let trend_filter = config.trend_filter.clone();
+        let can_short = config.market_making.enabled;
 
         // Update unrealized PnL
         self.calculate_unrealized_pnl(context.current_price);
 
         // Check risk management
-        if let Some(risk_message) = self.check_risk_management(context) {
+        let risk_message = self.check_risk_management(context);
+        let mut rules = vec![RuleEvaluation::new(
+            "risk_management",
+            Some(self.state.inventory.abs()),
+            Some(max_inventory),
+            risk_message.is_none(),
+        )];
+
+        if let Some(risk_message) = risk_message {
             warn!("Risk management triggered: {}", risk_message);
-            self.state.is_active = false;
+            self.lifecycle.apply(LifecycleEvent::Halt)?;
+            self.last_explanation = self.build_explanation(context, "halted", &rules, &[], false);
             return Ok(None);
         }
 
-        // Check if grid needs rebalancing
-        if self.needs_rebalancing(context) {
-            let reason = if context.current_price >= self.state.grid_upper_bound || context.current_price <= self.state.grid_lower_bound {
-                RebalanceReason::PriceOutOfBounds
-            } else {
-                RebalanceReason::TimeInterval
-            };
+        // Handle bound breaches and rebalancing
+        let mut rebalanced = false;
+        let out_of_bounds = self.is_out_of_bounds(context.current_price);
+        match on_bound_breach {
+            BoundBreachAction::Halt if out_of_bounds => {
+                rules.push(RuleEvaluation::new("within_bounds", Some(context.current_price), None, false));
+                warn!("Price {} broke grid bounds {}-{}, halting grid",
+                      context.current_price, self.state.grid_lower_bound, self.state.grid_upper_bound);
+                self.lifecycle.apply(LifecycleEvent::Halt)?;
+                self.last_explanation = self.build_explanation(context, "halted", &rules, &[], false);
+                return Ok(None);
+            }
+            BoundBreachAction::Expand if out_of_bounds => {
+                self.expand_grid(context.current_price);
+            }
+            _ => {
+                rebalanced = self.needs_rebalancing(context);
+                if rebalanced {
+                    let reason = if out_of_bounds {
+                        RebalanceReason::PriceOutOfBounds
+                    } else {
+                        RebalanceReason::TimeInterval
+                    };
+
+                    self.rebalance_grid(context, reason)?;
+                }
+            }
+        }
 
-            self.rebalance_grid(context, reason)?;
+        // Hold back fills that would build inventory against a strong trend
+        let mut paused_side = None;
+        if let Some(filter) = &trend_filter {
+            let (trend, rsi) = Self::detect_trend(filter, &context.historical_data);
+            paused_side = match trend {
+                Some(MarketTrend::Down) => Some(TradeSide::Buy),
+                Some(MarketTrend::Up) if can_short => Some(TradeSide::Sell),
+                _ => None,
+            };
+            let threshold = match trend {
+                Some(MarketTrend::Up) => filter.rsi_overbought,
+                _ => filter.rsi_oversold,
+            };
+            rules.push(RuleEvaluation::new("trend_filter", rsi, Some(threshold), paused_side.is_none()));
+
+            if filter.action == TrendFilterAction::Flatten
+                && trend == Some(MarketTrend::Down)
+                && self.state.inventory > Decimal::ZERO
+            {
+                let signal = self.flatten_inventory(context);
+                self.last_explanation = self.build_explanation(context, "flatten", &rules, &[], rebalanced);
+                return Ok(Some(signal));
+            }
         }
 
         // Check for grid fills
-        let fills = self.check_grid_fills(context);
+        let level_checks = self.evaluate_grid_levels(context, paused_side);
+        let fills: Vec<(usize, TradeSide)> = level_checks
+            .iter()
+            .filter(|check| check.triggered)
+            .map(|check| (check.index, check.side))
+            .collect();
+
+        rules.push(RuleEvaluation::new(
+            "grid_level_triggered",
+            Some(Decimal::from(fills.len())),
+            Some(Decimal::ONE),
+            !fills.is_empty(),
+        ));
 
         if !fills.is_empty() {
             // Execute the first fill (in practice, you might want to handle multiple fills)
             let (level_index, side) = fills[0];
             let signal = self.execute_grid_fill(context, level_index, side)?;
 
+            let decision = match side {
+                TradeSide::Buy => "buy",
+                TradeSide::Sell => "sell",
+            };
+            self.last_explanation = self.build_explanation(context, decision, &rules, &level_checks, rebalanced);
+
             // Add grid-specific indicators to signal
             let indicators = vec![
                 IndicatorValue {
@@ -783,6 +1027,7 @@ impl Strategy for GridTradingStrategy {
             return Ok(Some(enhanced_signal));
         }
 
+        self.last_explanation = self.build_explanation(context, "hold", &rules, &level_checks, rebalanced);
         Ok(None)
     }
 
@@ -800,6 +1045,18 @@ impl Strategy for GridTradingStrategy {
         GridTradingConfig::json_schema()
     }
 
+    fn explain(&self) -> Value {
+        self.last_explanation.clone()
+    }
+
+    fn lifecycle(&self) -> StrategyLifecycle {
+        self.lifecycle
+    }
+
+    fn into_live(self: Box<Self>) -> Option<Box<dyn LiveExecutableStrategy>> {
+        Some(self)
+    }
+
     fn get_state(&self) -> Result<Value, AppError> {
         let mut state_with_metadata = serde_json::to_value(&self.state)
             .map_err(|e| AppError::BadRequest(format!("Failed to serialize state: {}", e)))?;
@@ -826,6 +1083,10 @@ impl Strategy for GridTradingStrategy {
 
             state_obj.insert("grid_efficiency".to_string(),
                 serde_json::Value::String(efficiency.to_string()));
+
+            state_obj.insert("lifecycle".to_string(), serde_json::json!(self.lifecycle));
+            state_obj.insert("is_active".to_string(),
+                serde_json::Value::Bool(self.lifecycle.accepts_signals()));
         }
 
         Ok(state_with_metadata)
@@ -834,6 +1095,11 @@ impl Strategy for GridTradingStrategy {
     fn restore_state(&mut self, state: &Value) -> Result<(), AppError> {
         self.state = serde_json::from_value(state.clone())
             .map_err(|e| AppError::BadRequest(format!("Failed to deserialize state: {}", e)))?;
+        // A halted grid stays halted across restarts
+        if let Some(lifecycle) = state.get("lifecycle") {
+            self.lifecycle = serde_json::from_value(lifecycle.clone())
+                .map_err(|e| AppError::BadRequest(format!("Failed to deserialize lifecycle: {}", e)))?;
+        }
         Ok(())
     }
 }
@@ -841,23 +1107,17 @@ impl Strategy for GridTradingStrategy {
 #[async_trait]
 impl LiveExecutableStrategy for GridTradingStrategy {
     async fn start_live_execution(&mut self, _context: &StrategyContext) -> Result<(), AppError> {
-        self.is_running = true;
-        self.state.is_active = true;
+        self.lifecycle.apply(LifecycleEvent::Start)?;
         info!("Grid Trading strategy started for live execution");
         Ok(())
     }
 
     async fn stop_live_execution(&mut self) -> Result<(), AppError> {
-        self.is_running = false;
-        self.state.is_active = false;
+        self.lifecycle.apply(LifecycleEvent::Stop)?;
         info!("Grid Trading strategy stopped");
         Ok(())
     }
 
-    fn is_running(&self) -> bool {
-        self.is_running
-    }
-
     fn next_execution_time(&self) -> Option<DateTime<Utc>> {
         // Grid trading is event-driven, but we might have rebalancing intervals
         if let Some(config) = &self.config {
@@ -874,20 +1134,16 @@ impl LiveExecutableStrategy for GridTradingStrategy {
 #[async_trait]
 impl ControllableStrategy for GridTradingStrategy {
     async fn pause(&mut self) -> Result<(), AppError> {
-        self.is_paused = true;
+        self.lifecycle.apply(LifecycleEvent::Pause)?;
         info!("Grid Trading strategy paused");
         Ok(())
     }
 
     async fn resume(&mut self) -> Result<(), AppError> {
-        self.is_paused = false;
+        self.lifecycle.apply(LifecycleEvent::Resume)?;
         info!("Grid Trading strategy resumed");
         Ok(())
     }
-
-    fn is_paused(&self) -> bool {
-        self.is_paused
-    }
 }
 
 impl Default for GridTradingStrategy {
@@ -0,0 +1,421 @@
+#[cfg(test)]
+mod tests {
+    use crate::strategies::core::{
+        StrategyContext, StrategyContextBuilder, StrategyMode, Strategy, StrategyLifecycle, ControllableStrategy,
+        LiveExecutableStrategy,
+    };
+    use crate::strategies::implementations::grid_trading::{
+        GridTradingStrategy, GridTradingConfig, GridTradingState, OrderSizeMode, BoundBreachAction, GridTrendFilter,
+        TrendFilterAction,
+    };
+    use crate::strategies::core::{QuantityType, StrategySignalType};
+    use crate::exchange_connectors::Kline;
+    use chrono::{Duration, Utc, TimeZone};
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    /// Helper function to create a test context at the given price
+    fn create_test_context(price: Decimal, balance: Decimal) -> StrategyContext {
+        StrategyContextBuilder::new()
+            .strategy_id(Uuid::new_v4())
+            .user_id(Uuid::new_v4())
+            .symbol("BTCUSDT".to_string())
+            .interval("1h".to_string())
+            .mode(StrategyMode::Backtest)
+            .current_time(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+            .current_price(price)
+            .available_balance(balance)
+            .build()
+            .expect("Failed to build test context")
+    }
+
+    /// 10-level grid, 10% either side of 100, no rebalancing
+    fn test_config() -> GridTradingConfig {
+        GridTradingConfig {
+            enable_rebalancing: false,
+            ..GridTradingConfig::simple(10, Decimal::from(1000), Decimal::ONE)
+        }
+    }
+
+    async fn initialized_strategy(config: GridTradingConfig) -> GridTradingStrategy {
+        let mut strategy = GridTradingStrategy::new();
+        let params = serde_json::to_value(config).unwrap();
+        strategy
+            .initialize(&params, StrategyMode::Backtest, &create_test_context(Decimal::from(100), Decimal::from(10000)))
+            .await
+            .unwrap();
+        strategy
+    }
+
+    #[tokio::test]
+    async fn test_explain_lists_evaluated_levels_on_fill() {
+        let mut strategy = initialized_strategy(test_config()).await;
+
+        // 98 is below the closest buy level (~98.89)
+        let signal = strategy
+            .analyze(&create_test_context(Decimal::from(98), Decimal::from(10000)))
+            .await
+            .unwrap();
+        assert!(signal.is_some());
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["decision"], "buy");
+        assert!(explanation["gated_by"].is_null());
+
+        let rules: Vec<&str> = explanation["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["rule"].as_str().unwrap())
+            .collect();
+        assert_eq!(rules, vec!["risk_management", "grid_level_triggered"]);
+
+        let levels = explanation["levels"].as_array().unwrap();
+        assert_eq!(levels.len(), 10);
+        let triggered: Vec<&serde_json::Value> = levels.iter().filter(|l| l["triggered"] == true).collect();
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0]["side"], "Buy");
+    }
+
+    #[tokio::test]
+    async fn test_explain_reports_hold_when_no_level_reached() {
+        let mut strategy = initialized_strategy(test_config()).await;
+
+        let signal = strategy
+            .analyze(&create_test_context(Decimal::from(100), Decimal::from(10000)))
+            .await
+            .unwrap();
+        assert!(signal.is_none());
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["decision"], "hold");
+        assert_eq!(explanation["gated_by"], "grid_level_triggered");
+        // Sell levels are evaluated but blocked by missing inventory
+        assert!(explanation["levels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|l| l["side"] == "Sell")
+            .all(|l| l["funds_available"] == false));
+    }
+
+    fn sizing_config(mode: OrderSizeMode) -> GridTradingConfig {
+        GridTradingConfig {
+            grid_levels: 4,
+            order_size_mode: mode,
+            order_size_ratio: Some(Decimal::from(2)),
+            ..test_config()
+        }
+    }
+
+    fn level_prices() -> Vec<Decimal> {
+        vec![Decimal::from(90), Decimal::from(95), Decimal::from(105), Decimal::from(110)]
+    }
+
+    #[test]
+    fn test_equal_sizing_splits_evenly() {
+        let sizes = sizing_config(OrderSizeMode::Equal).calculate_level_sizes(&level_prices(), Decimal::from(100));
+
+        assert_eq!(sizes, vec![Decimal::from(250); 4]);
+        assert_eq!(sizes.iter().sum::<Decimal>(), Decimal::from(1000));
+    }
+
+    #[test]
+    fn test_geometric_sizing_grows_by_ratio_away_from_center() {
+        let sizes = sizing_config(OrderSizeMode::Geometric).calculate_level_sizes(&level_prices(), Decimal::from(100));
+
+        assert_eq!(sizes.iter().sum::<Decimal>(), Decimal::from(1000));
+        // Outer levels are twice the size of the inner ones on each side
+        assert!((sizes[0] / sizes[1] - Decimal::from(2)).abs() < Decimal::new(1, 6));
+        assert!((sizes[3] / sizes[2] - Decimal::from(2)).abs() < Decimal::new(1, 6));
+        assert!((sizes[1] - sizes[2]).abs() < Decimal::new(1, 6));
+    }
+
+    #[test]
+    fn test_weighted_by_distance_sizing_is_proportional_to_distance() {
+        let prices = vec![Decimal::from(90), Decimal::from(98), Decimal::from(104), Decimal::from(110)];
+        let sizes = sizing_config(OrderSizeMode::WeightedByDistance).calculate_level_sizes(&prices, Decimal::from(100));
+
+        assert_eq!(sizes.iter().sum::<Decimal>(), Decimal::from(1000));
+        // Distances 10, 2, 4, 10 out of 26
+        assert!((sizes[0] - Decimal::from(10000) / Decimal::from(26)).abs() < Decimal::new(1, 6));
+        assert!((sizes[1] - Decimal::from(2000) / Decimal::from(26)).abs() < Decimal::new(1, 6));
+        assert!(sizes[1] < sizes[2] && sizes[2] < sizes[3]);
+    }
+
+    #[tokio::test]
+    async fn test_grid_levels_use_configured_sizing_mode() {
+        let config = GridTradingConfig {
+            order_size_mode: OrderSizeMode::WeightedByDistance,
+            ..test_config()
+        };
+        let strategy = initialized_strategy(config).await;
+        let state = strategy.get_state().unwrap();
+        let quantities: Vec<Decimal> = state["grid_levels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|level| serde_json::from_value(level["quantity"].clone()).unwrap())
+            .collect();
+
+        assert_eq!(quantities.len(), 10);
+        assert_eq!(quantities.iter().sum::<Decimal>(), Decimal::from(1000));
+        // Outermost level is larger than the one nearest the center
+        assert!(quantities[0] > quantities[4]);
+    }
+
+    #[test]
+    fn test_sells_realize_pnl_against_average_cost() {
+        let mut state = GridTradingState::default();
+
+        state.record_buy(Decimal::from(100), Decimal::from(2));
+        state.record_buy(Decimal::from(90), Decimal::from(2));
+        assert_eq!(state.average_entry_price, Some(Decimal::from(95)));
+
+        // Selling part of the inventory keeps the remaining average unchanged
+        let pnl = state.record_sell(Decimal::from(105), Decimal::from(1));
+        assert_eq!(pnl, Decimal::from(10));
+        assert_eq!(state.inventory, Decimal::from(3));
+        assert_eq!(state.average_entry_price, Some(Decimal::from(95)));
+
+        // A new buy blends with the remaining inventory only
+        state.record_buy(Decimal::from(75), Decimal::from(1));
+        assert_eq!(state.average_entry_price, Some(Decimal::from(90)));
+
+        let pnl = state.record_sell(Decimal::from(80), Decimal::from(2));
+        assert_eq!(pnl, Decimal::from(-20));
+        assert_eq!(state.realized_pnl, Decimal::from(-10));
+        assert_eq!(state.average_entry_price, Some(Decimal::from(90)));
+    }
+
+    #[test]
+    fn test_average_entry_price_resets_when_flat() {
+        let mut state = GridTradingState::default();
+
+        state.record_buy(Decimal::from(100), Decimal::from(1));
+        state.record_sell(Decimal::from(110), Decimal::from(1));
+        assert_eq!(state.inventory, Decimal::ZERO);
+        assert_eq!(state.average_entry_price, None);
+        assert_eq!(state.realized_pnl, Decimal::from(10));
+
+        // The next cycle starts from a fresh average
+        state.record_buy(Decimal::from(120), Decimal::from(1));
+        assert_eq!(state.average_entry_price, Some(Decimal::from(120)));
+        state.record_sell(Decimal::from(115), Decimal::from(1));
+        assert_eq!(state.average_entry_price, None);
+        assert_eq!(state.realized_pnl, Decimal::from(5));
+    }
+
+    fn level_prices_of(strategy: &GridTradingStrategy) -> Vec<Decimal> {
+        strategy.get_state().unwrap()["grid_levels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|level| serde_json::from_value(level["price"].clone()).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_expand_adds_levels_past_upper_bound_on_breakout() {
+        let config = GridTradingConfig {
+            on_bound_breach: BoundBreachAction::Expand,
+            ..test_config()
+        };
+        let mut strategy = initialized_strategy(config).await;
+        let original_prices = level_prices_of(&strategy);
+        let original_upper = *original_prices.last().unwrap();
+
+        strategy
+            .analyze(&create_test_context(Decimal::from(115), Decimal::from(10000)))
+            .await
+            .unwrap();
+
+        let prices = level_prices_of(&strategy);
+        assert!(prices.len() > original_prices.len());
+        // Existing levels are kept as they were
+        assert_eq!(&prices[..original_prices.len()], &original_prices[..]);
+        // New levels sit above the old bound and reach past the breakout price
+        assert!(prices[original_prices.len()..].iter().all(|price| *price > original_upper));
+        assert!(*prices.last().unwrap() > Decimal::from(115));
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["rebalanced"], false);
+        let upper: Decimal = serde_json::from_value(explanation["indicators"]["grid_upper_bound"].clone()).unwrap();
+        assert_eq!(upper, *prices.last().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_expanded_grid_keeps_geometric_sizing() {
+        let config = GridTradingConfig {
+            on_bound_breach: BoundBreachAction::Expand,
+            order_size_mode: OrderSizeMode::Geometric,
+            order_size_ratio: Some(Decimal::from(2)),
+            ..test_config()
+        };
+        let mut strategy = initialized_strategy(config).await;
+        let original_len = level_prices_of(&strategy).len();
+
+        let breakout = Decimal::from(115);
+        strategy
+            .analyze(&create_test_context(breakout, Decimal::from(10000)))
+            .await
+            .unwrap();
+
+        let prices = level_prices_of(&strategy);
+        let quantities: Vec<Decimal> = strategy.get_state().unwrap()["grid_levels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|level| serde_json::from_value(level["quantity"].clone()).unwrap())
+            .collect();
+        assert!(prices.len() > original_len);
+        assert_eq!(quantities.iter().sum::<Decimal>(), Decimal::from(1000));
+
+        // Re-centred on the breakout: each level below it is twice the one above it
+        let below = prices.iter().filter(|price| **price < breakout).count();
+        assert!(below > original_len);
+        for pair in quantities[..below].windows(2) {
+            assert!((pair[0] / pair[1] - Decimal::from(2)).abs() < Decimal::new(1, 6), "{:?}", quantities);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_halt_stops_grid_on_breakout() {
+        let config = GridTradingConfig {
+            on_bound_breach: BoundBreachAction::Halt,
+            ..test_config()
+        };
+        let mut strategy = initialized_strategy(config).await;
+
+        let signal = strategy
+            .analyze(&create_test_context(Decimal::from(85), Decimal::from(10000)))
+            .await
+            .unwrap();
+        assert!(signal.is_none());
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["decision"], "halted");
+        assert_eq!(explanation["gated_by"], "within_bounds");
+        assert_eq!(strategy.get_state().unwrap()["is_active"], false);
+        assert_eq!(strategy.lifecycle(), StrategyLifecycle::Halted);
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_transitions_are_enforced() {
+        let mut strategy = initialized_strategy(test_config()).await;
+        let context = create_test_context(Decimal::from(100), Decimal::from(10000));
+        assert_eq!(strategy.lifecycle(), StrategyLifecycle::Initialized);
+
+        strategy.start_live_execution(&context).await.unwrap();
+        assert!(strategy.is_running());
+        strategy.pause().await.unwrap();
+        assert!(strategy.is_paused() && !strategy.is_running());
+        strategy.resume().await.unwrap();
+        assert!(strategy.is_running());
+
+        strategy.stop_live_execution().await.unwrap();
+        assert_eq!(strategy.lifecycle(), StrategyLifecycle::Stopped);
+        assert!(strategy.resume().await.is_err());
+        assert!(strategy.start_live_execution(&context).await.is_err());
+        assert_eq!(strategy.lifecycle(), StrategyLifecycle::Stopped);
+        assert!(strategy.analyze(&context).await.unwrap().is_none());
+    }
+
+    /// Hourly bars closing from 130 down to `last` in even steps
+    fn downtrend(last: i64, bars: i64) -> Vec<Kline> {
+        let start = Utc.with_ymd_and_hms(2023, 12, 30, 0, 0, 0).unwrap();
+        let step = Decimal::from(130 - last) / Decimal::from(bars - 1);
+        (0..bars)
+            .map(|i| {
+                let close = Decimal::from(130) - step * Decimal::from(i);
+                Kline {
+                    open_time: start + Duration::hours(i),
+                    close_time: start + Duration::hours(i) + Duration::minutes(59),
+                    open: close + step,
+                    high: close + step,
+                    low: close,
+                    close,
+                    volume: Decimal::from(1000),
+                    quote_asset_volume: Decimal::from(1000) * close,
+                    number_of_trades: 100,
+                    taker_buy_base_asset_volume: Decimal::from(500),
+                    taker_buy_quote_asset_volume: Decimal::from(500) * close,
+                }
+            })
+            .collect()
+    }
+
+    fn trending_context(price: i64) -> StrategyContext {
+        StrategyContext {
+            historical_data: downtrend(price, 40),
+            ..create_test_context(Decimal::from(price), Decimal::from(10000))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trend_filter_suppresses_buys_in_a_strong_downtrend() {
+        // Without the filter the grid buys into the decline
+        let mut unfiltered = initialized_strategy(test_config()).await;
+        let signal = unfiltered.analyze(&trending_context(98)).await.unwrap();
+        assert!(matches!(signal.map(|s| s.signal_type), Some(StrategySignalType::AddToPosition)));
+
+        let config = GridTradingConfig {
+            trend_filter: Some(GridTrendFilter::default()),
+            ..test_config()
+        };
+        let mut strategy = initialized_strategy(config).await;
+        for price in [98, 96, 93] {
+            assert!(strategy.analyze(&trending_context(price)).await.unwrap().is_none());
+        }
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["decision"], "hold");
+        assert_eq!(explanation["gated_by"], "trend_filter");
+        let paused: Vec<&serde_json::Value> = explanation["levels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|l| l["paused_by_trend"] == true)
+            .collect();
+        assert!(!paused.is_empty() && paused.iter().all(|l| l["side"] == "Buy"));
+        assert!(paused.iter().any(|l| l["price_reached"] == true));
+
+        // Without a confirmed trend the grid buys again
+        let signal = strategy.analyze(&create_test_context(Decimal::from(93), Decimal::from(10000))).await.unwrap();
+        assert!(signal.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_trend_filter_flattens_inventory_held_into_a_downtrend() {
+        let config = GridTradingConfig {
+            trend_filter: Some(GridTrendFilter {
+                action: TrendFilterAction::Flatten,
+                ..GridTrendFilter::default()
+            }),
+            ..test_config()
+        };
+        let mut strategy = initialized_strategy(config).await;
+
+        // No history yet, so the filter can't call a trend and the first buy goes through
+        strategy.analyze(&create_test_context(Decimal::from(98), Decimal::from(10000))).await.unwrap().unwrap();
+        let inventory: Decimal = serde_json::from_value(strategy.get_state().unwrap()["inventory"].clone()).unwrap();
+        assert!(inventory > Decimal::ZERO);
+
+        let signal = strategy.analyze(&trending_context(96)).await.unwrap().unwrap();
+        assert!(matches!(signal.signal_type, StrategySignalType::ReducePosition));
+        assert!(matches!(signal.action.quantity, QuantityType::Fixed(quantity) if quantity == inventory));
+        assert_eq!(strategy.explain()["decision"], "flatten");
+        let inventory: Decimal = serde_json::from_value(strategy.get_state().unwrap()["inventory"].clone()).unwrap();
+        assert!(inventory.is_zero());
+
+        // Flat and still trending down: nothing more to do
+        assert!(strategy.analyze(&trending_context(94)).await.unwrap().is_none());
+
+        assert!(GridTradingConfig {
+            trend_filter: Some(GridTrendFilter { rsi_oversold: Decimal::from(80), ..GridTrendFilter::default() }),
+            ..test_config()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -41,6 +41,26 @@ pub struct GridLevel {
     pub total_filled: Decimal,
 }
 
+/// Fill conditions evaluated for one grid level during analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridLevelCheck {
+    /// Index of the level in the grid
+    pub index: usize,
+    /// Side the level would trade
+    pub side: TradeSide,
+    /// Level price
+    pub level_price: Decimal,
+    /// Whether the current price has reached the level
+    pub price_reached: bool,
+    /// Whether balance (buys) or inventory (sells) covers the order
+    pub funds_available: bool,
+    /// Whether the trend filter is holding this side back
+    #[serde(default)]
+    pub paused_by_trend: bool,
+    /// Whether the level would fill on this bar
+    pub triggered: bool,
+}
+
 /// Grid trading strategy state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GridTradingState {
@@ -62,8 +82,6 @@ pub struct GridTradingState {
     pub grid_upper_bound: Decimal,
     /// Grid lower bound
     pub grid_lower_bound: Decimal,
-    /// Is grid currently active
-    pub is_active: bool,
     /// Last rebalance timestamp
     pub last_rebalance_time: Option<DateTime<Utc>>,
     /// Grid statistics
@@ -82,13 +100,44 @@ impl Default for GridTradingState {
             grid_center: Decimal::ZERO,
             grid_upper_bound: Decimal::ZERO,
             grid_lower_bound: Decimal::ZERO,
-            is_active: false,
             last_rebalance_time: None,
             stats: GridStats::default(),
         }
     }
 }
 
+impl GridTradingState {
+    /// Record a buy fill, blending it into the average entry price
+    pub fn record_buy(&mut self, price: Decimal, quantity: Decimal) {
+        self.average_entry_price = Some(match self.average_entry_price {
+            Some(avg_price) if self.inventory > Decimal::ZERO => {
+                let total_cost = avg_price * self.inventory + price * quantity;
+                total_cost / (self.inventory + quantity)
+            }
+            _ => price,
+        });
+        self.inventory += quantity;
+    }
+
+    /// Record a sell fill at the current average cost and return the realized PnL.
+    /// The average is left untouched and cleared once inventory is flat.
+    pub fn record_sell(&mut self, price: Decimal, quantity: Decimal) -> Decimal {
+        let pnl = match self.average_entry_price {
+            Some(avg_price) => (price - avg_price) * quantity,
+            None => Decimal::ZERO,
+        };
+        self.realized_pnl += pnl;
+        self.inventory -= quantity;
+
+        if self.inventory <= Decimal::ZERO {
+            self.inventory = self.inventory.max(Decimal::ZERO);
+            self.average_entry_price = None;
+        }
+
+        pnl
+    }
+}
+
 /// Grid trading statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GridStats {
@@ -200,6 +249,97 @@ pub enum TrendDirection {
     Sideways,
 }
 
+/// Action taken when price breaks out of the grid bounds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BoundBreachAction {
+    /// Recenter the grid around the current price
+    Rebalance,
+    /// Append levels beyond the breached bound, keeping existing levels
+    Expand,
+    /// Stop trading until the strategy is re-initialized
+    Halt,
+}
+
+impl Default for BoundBreachAction {
+    fn default() -> Self {
+        Self::Rebalance
+    }
+}
+
+/// Direction of a strong trend found by the trend filter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MarketTrend {
+    Up,
+    Down,
+}
+
+/// What the trend filter does while a strong trend runs against the grid
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TrendFilterAction {
+    /// Skip fills that would add inventory against the trend; the other side keeps trading
+    PauseFills,
+    /// Also close the inventory held against the trend at market
+    Flatten,
+}
+
+impl Default for TrendFilterAction {
+    fn default() -> Self {
+        Self::PauseFills
+    }
+}
+
+/// RSI/MACD filter that stops the grid accumulating inventory into a strong trend.
+/// A downtrend is the RSI at or below `rsi_oversold` for `confirmation_bars` bars in a
+/// row, with the MACD line below zero when `require_macd` is set; an uptrend mirrors it.
+/// Downtrends pause buys. Uptrends pause sells only when market making lets the grid short.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridTrendFilter {
+    pub rsi_period: usize,
+    pub rsi_oversold: Decimal,
+    pub rsi_overbought: Decimal,
+    /// Consecutive bars the RSI must stay past its threshold
+    pub confirmation_bars: usize,
+    /// Require the MACD line to agree with the RSI
+    pub require_macd: bool,
+    pub macd_fast_period: usize,
+    pub macd_slow_period: usize,
+    #[serde(default)]
+    pub action: TrendFilterAction,
+}
+
+impl Default for GridTrendFilter {
+    fn default() -> Self {
+        Self {
+            rsi_period: 14,
+            rsi_oversold: Decimal::from(30),
+            rsi_overbought: Decimal::from(70),
+            confirmation_bars: 3,
+            require_macd: true,
+            macd_fast_period: 12,
+            macd_slow_period: 26,
+            action: TrendFilterAction::PauseFills,
+        }
+    }
+}
+
+impl GridTrendFilter {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rsi_period == 0 || self.confirmation_bars == 0 {
+            return Err("Trend filter RSI period and confirmation bars must be at least 1".to_string());
+        }
+        if self.rsi_oversold <= Decimal::ZERO
+            || self.rsi_overbought >= Decimal::from(100)
+            || self.rsi_oversold >= self.rsi_overbought
+        {
+            return Err("Trend filter RSI thresholds must satisfy 0 < oversold < overbought < 100".to_string());
+        }
+        if self.macd_fast_period == 0 || self.macd_fast_period >= self.macd_slow_period {
+            return Err("Trend filter MACD fast period must be positive and shorter than the slow period".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// Grid rebalancing reasons
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RebalanceReason {
@@ -316,6 +456,23 @@ pub enum GridTradingMode {
     ZoneBased,
 }
 
+/// How total investment is split across grid levels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrderSizeMode {
+    /// Same size at every level
+    Equal,
+    /// Size grows by a fixed ratio per level moving away from the center
+    Geometric,
+    /// Size proportional to the level's distance from the center
+    WeightedByDistance,
+}
+
+impl Default for OrderSizeMode {
+    fn default() -> Self {
+        OrderSizeMode::Equal
+    }
+}
+
 /// Grid spacing configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GridSpacing {
@@ -0,0 +1,109 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::strategies::indicators::MaType;
+
+/// Configuration for the moving average ribbon strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaRibbonConfig {
+    /// Moving average periods making up the ribbon
+    #[serde(default = "default_periods")]
+    pub periods: Vec<usize>,
+    /// Kind of moving average the ribbon is drawn with
+    #[serde(default)]
+    pub ma_type: MaType,
+    /// Percentage of available balance to enter with
+    #[serde(default = "default_position_size_pct")]
+    pub position_size_pct: Decimal,
+}
+
+fn default_periods() -> Vec<usize> {
+    vec![8, 13, 21, 34, 55]
+}
+
+fn default_position_size_pct() -> Decimal {
+    Decimal::from(10)
+}
+
+impl Default for MaRibbonConfig {
+    fn default() -> Self {
+        Self {
+            periods: default_periods(),
+            ma_type: MaType::default(),
+            position_size_pct: default_position_size_pct(),
+        }
+    }
+}
+
+impl MaRibbonConfig {
+    /// A ribbon of `periods` drawn with `ma_type`
+    pub fn new(periods: &[usize], ma_type: MaType) -> Self {
+        Self {
+            periods: periods.to_vec(),
+            ma_type,
+            ..Self::default()
+        }
+    }
+
+    /// Bars needed before the ribbon can be drawn
+    pub fn longest_period(&self) -> usize {
+        self.periods.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        let mut periods = self.periods.clone();
+        periods.sort_unstable();
+        periods.dedup();
+
+        if periods.len() < 2 {
+            return Err("A ribbon needs at least two distinct periods".to_string());
+        }
+        if periods.len() != self.periods.len() {
+            return Err("Ribbon periods must not repeat".to_string());
+        }
+        if periods[0] < 2 {
+            return Err("Ribbon periods must be at least 2".to_string());
+        }
+        if self.longest_period() > 500 {
+            return Err("Ribbon periods cannot exceed 500".to_string());
+        }
+
+        if self.position_size_pct <= Decimal::ZERO || self.position_size_pct > Decimal::from(100) {
+            return Err("Position size must be between 0 and 100 percent".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Get JSON schema for this configuration
+    pub fn json_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "periods": {
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 2, "maximum": 500 },
+                    "minItems": 2,
+                    "uniqueItems": true,
+                    "default": default_periods(),
+                    "description": "Moving average periods making up the ribbon"
+                },
+                "ma_type": {
+                    "type": "string",
+                    "enum": ["sma", "ema"],
+                    "default": "sma",
+                    "description": "Kind of moving average the ribbon is drawn with"
+                },
+                "position_size_pct": {
+                    "type": "number",
+                    "exclusiveMinimum": 0,
+                    "maximum": 100,
+                    "default": 10,
+                    "description": "Percentage of available balance to enter with"
+                }
+            }
+        })
+    }
+}
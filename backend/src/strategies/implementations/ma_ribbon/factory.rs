@@ -0,0 +1,36 @@
+use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata, StrategyPreset};
+use super::{MaRibbonStrategy, ma_ribbon_presets};
+
+/// Factory for creating moving average ribbon strategy instances
+pub struct MaRibbonStrategyFactory {
+    metadata: StrategyMetadata,
+}
+
+impl MaRibbonStrategyFactory {
+    /// Create a new moving average ribbon strategy factory
+    pub fn new() -> Self {
+        Self {
+            metadata: MaRibbonStrategy::create_metadata(),
+        }
+    }
+}
+
+impl StrategyFactory for MaRibbonStrategyFactory {
+    fn create(&self) -> Box<dyn Strategy> {
+        Box::new(MaRibbonStrategy::new())
+    }
+
+    fn metadata(&self) -> &StrategyMetadata {
+        &self.metadata
+    }
+
+    fn presets(&self) -> Vec<StrategyPreset> {
+        ma_ribbon_presets()
+    }
+}
+
+impl Default for MaRibbonStrategyFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,30 @@
+use crate::strategies::core::{RiskLevel, StrategyPreset};
+use crate::strategies::indicators::MaType;
+use super::MaRibbonConfig;
+
+/// Presets registered with the strategy registry
+pub fn ma_ribbon_presets() -> Vec<StrategyPreset> {
+    vec![
+        StrategyPreset::new(
+            "conservative",
+            "Conservative",
+            "Slow SMA ribbon (21 to 200); only rides long, established trends",
+            RiskLevel::Conservative,
+            &MaRibbonConfig::new(&[21, 34, 55, 100, 200], MaType::Sma),
+        ),
+        StrategyPreset::new(
+            "balanced",
+            "Balanced",
+            "Fibonacci SMA ribbon (8 to 55)",
+            RiskLevel::Moderate,
+            &MaRibbonConfig::new(&[8, 13, 21, 34, 55], MaType::Sma),
+        ),
+        StrategyPreset::new(
+            "aggressive",
+            "Aggressive",
+            "Fast EMA ribbon (5 to 34); enters and exits trends early",
+            RiskLevel::Aggressive,
+            &MaRibbonConfig::new(&[5, 8, 13, 21, 34], MaType::Ema),
+        ),
+    ]
+}
@@ -0,0 +1,52 @@
+use crate::strategies::core::{register_strategy, FactorizableStrategy};
+use crate::utils::errors::AppError;
+use super::{MaRibbonStrategy, MaRibbonStrategyFactory};
+
+/// Register the moving average ribbon strategy in the global registry
+pub fn register_ma_ribbon_strategy() -> Result<(), AppError> {
+    let factory = MaRibbonStrategyFactory::new();
+    register_strategy(factory)?;
+    tracing::info!("MA Ribbon strategy registered successfully");
+    Ok(())
+}
+
+impl FactorizableStrategy for MaRibbonStrategy {
+    fn get_metadata() -> crate::strategies::core::StrategyMetadata {
+        MaRibbonStrategy::create_metadata()
+    }
+}
+
+/// Initialize moving average ribbon strategies during application startup
+pub fn init_ma_ribbon_strategies() -> Result<(), AppError> {
+    tracing::info!("Initializing MA Ribbon strategies...");
+
+    match register_ma_ribbon_strategy() {
+        Ok(_) => {
+            tracing::info!("All MA Ribbon strategies initialized successfully");
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("Failed to initialize MA Ribbon strategies: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::core::{get_global_registry, create_strategy};
+
+    #[test]
+    fn test_ma_ribbon_strategy_registration() {
+        assert!(register_ma_ribbon_strategy().is_ok());
+
+        let registry = get_global_registry();
+        let registry = registry.read().unwrap();
+        assert!(registry.contains("ma_ribbon_v1"));
+
+        drop(registry);
+        let strategy = create_strategy("ma_ribbon_v1").unwrap();
+        assert_eq!(strategy.metadata().name, "Moving Average Ribbon");
+    }
+}
@@ -0,0 +1,314 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::strategies::core::{
+    Strategy, StrategyMetadata, StrategyMode, StrategyContext, StrategySignal,
+    StrategyCategory, RiskLevel, LiveExecutableStrategy, ControllableStrategy, IndicatorValue, QuantityType,
+    RuleEvaluation, StrategyLifecycle, LifecycleEvent,
+};
+use crate::strategies::indicators::{self, MaRibbon};
+use crate::utils::errors::AppError;
+
+use super::config::MaRibbonConfig;
+use super::types::*;
+
+/// Trend follower on a ribbon of stacked moving averages.
+///
+/// Enters long once every shorter average sits above every longer one (alignment 1)
+/// and exits as soon as any pair crosses back, so it only holds while the whole
+/// ribbon agrees the trend is up.
+pub struct MaRibbonStrategy {
+    /// Strategy configuration
+    config: Option<MaRibbonConfig>,
+    /// Current execution state
+    state: MaRibbonState,
+    /// Lifecycle state; gates analysis and live execution
+    lifecycle: StrategyLifecycle,
+    /// Structured explanation of the last analyze call
+    last_explanation: Value,
+    /// Strategy metadata
+    metadata: StrategyMetadata,
+}
+
+impl MaRibbonStrategy {
+    /// Create a new moving average ribbon strategy instance
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            state: MaRibbonState::default(),
+            lifecycle: StrategyLifecycle::Created,
+            last_explanation: Value::Null,
+            metadata: Self::create_metadata(),
+        }
+    }
+
+    /// Create strategy metadata
+    pub fn create_metadata() -> StrategyMetadata {
+        StrategyMetadata {
+            id: "ma_ribbon_v1".to_string(),
+            name: "Moving Average Ribbon".to_string(),
+            description: "Rides trends while a ribbon of stacked moving averages is fully bullishly ordered, and steps aside as soon as the ordering breaks".to_string(),
+            version: "1.0.0".to_string(),
+            author: "E-Squared Trading Bot".to_string(),
+            category: StrategyCategory::Momentum,
+            risk_level: RiskLevel::Moderate,
+            supported_modes: vec![
+                StrategyMode::Backtest,
+                StrategyMode::Paper,
+                StrategyMode::Live,
+            ],
+            min_balance: Some(Decimal::from(100)),
+            max_positions: Some(1),
+            supported_intervals: vec![
+                "15m".to_string(), "1h".to_string(), "4h".to_string(), "1d".to_string(),
+            ],
+            tags: vec![
+                "trend_following".to_string(),
+                "moving_averages".to_string(),
+                "ribbon".to_string(),
+                "technical".to_string(),
+            ],
+        }
+    }
+
+    fn ribbon_indicators(ribbon: &MaRibbon) -> Vec<IndicatorValue> {
+        let mut values = vec![IndicatorValue {
+            name: "Ribbon Alignment".to_string(),
+            value: ribbon.alignment,
+            signal: if ribbon.alignment == Decimal::ONE { "bullish" } else { "neutral" }.to_string(),
+        }];
+        values.extend(ribbon.averages.iter().map(|average| IndicatorValue {
+            name: format!("MA {}", average.period),
+            value: average.value,
+            signal: "trend".to_string(),
+        }));
+        values
+    }
+
+    fn build_explanation(
+        &self,
+        context: &StrategyContext,
+        decision: &str,
+        ribbon: Option<&MaRibbon>,
+        rules: &[RuleEvaluation],
+    ) -> Value {
+        json!({
+            "strategy": self.metadata.id,
+            "timestamp": context.current_time,
+            "decision": decision,
+            "price": context.current_price,
+            "ribbon": ribbon,
+            "in_position": self.state.in_position,
+            "entry_price": self.state.entry_price,
+            "rules": rules,
+            "gated_by": rules.iter().find(|r| !r.passed).map(|r| r.rule.clone()),
+        })
+    }
+}
+
+#[async_trait]
+impl Strategy for MaRibbonStrategy {
+    fn metadata(&self) -> StrategyMetadata {
+        self.metadata.clone()
+    }
+
+    async fn initialize(
+        &mut self,
+        parameters: &Value,
+        _mode: StrategyMode,
+        _context: &StrategyContext,
+    ) -> Result<(), AppError> {
+        let config: MaRibbonConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid MA ribbon parameters: {}", e)))?;
+
+        config.validate()
+            .map_err(AppError::BadRequest)?;
+        let lifecycle = self.lifecycle.transition(LifecycleEvent::Initialize)?;
+
+        self.config = Some(config);
+        self.state = MaRibbonState::default();
+        self.lifecycle = lifecycle;
+        self.last_explanation = Value::Null;
+
+        info!("MA Ribbon strategy initialized successfully");
+        Ok(())
+    }
+
+    async fn analyze(
+        &mut self,
+        context: &StrategyContext,
+    ) -> Result<Option<StrategySignal>, AppError> {
+        let config = self.config.clone()
+            .ok_or_else(|| AppError::BadRequest("Strategy not initialized".to_string()))?;
+
+        if !self.lifecycle.accepts_signals() {
+            return Ok(None);
+        }
+
+        let longest = config.longest_period();
+        let ribbon = indicators::ma_ribbon(&context.historical_data, config.periods.clone(), config.ma_type);
+        let Some(ribbon) = ribbon else {
+            self.last_explanation = self.build_explanation(
+                context,
+                "hold",
+                None,
+                &[RuleEvaluation::new(
+                    "min_data_points",
+                    Some(Decimal::from(context.historical_data.len())),
+                    Some(Decimal::from(longest)),
+                    false,
+                )],
+            );
+            return Ok(None);
+        };
+
+        self.state.last_alignment = Some(ribbon.alignment);
+        let fully_bullish = ribbon.alignment == Decimal::ONE;
+
+        if !self.state.in_position {
+            let rules = [RuleEvaluation::new("ribbon_fully_bullish", Some(ribbon.alignment), Some(Decimal::ONE), fully_bullish)];
+            if !fully_bullish {
+                self.last_explanation = self.build_explanation(context, "hold", Some(&ribbon), &rules);
+                return Ok(None);
+            }
+
+            self.state.in_position = true;
+            self.state.entry_price = Some(context.current_price);
+            self.state.entry_time = Some(context.current_time);
+            self.state.trade_count += 1;
+            self.last_explanation = self.build_explanation(context, "buy", Some(&ribbon), &rules);
+
+            let signal = StrategySignal::buy(
+                context.symbol.clone(),
+                QuantityType::BalancePercentage(config.position_size_pct),
+                format!("Ribbon of {} averages fully bullishly aligned", ribbon.averages.len()),
+                None,
+            );
+            return Ok(Some(
+                signal
+                    .with_indicators(Self::ribbon_indicators(&ribbon))
+                    .with_confidence(Decimal::ONE),
+            ));
+        }
+
+        let rules = [RuleEvaluation::new("ribbon_alignment_holds", Some(ribbon.alignment), Some(Decimal::ONE), fully_bullish)];
+        if fully_bullish {
+            self.last_explanation = self.build_explanation(context, "hold", Some(&ribbon), &rules);
+            return Ok(None);
+        }
+
+        self.state.in_position = false;
+        self.state.entry_price = None;
+        self.state.entry_time = None;
+        self.state.trade_count += 1;
+        self.last_explanation = self.build_explanation(context, "sell", Some(&ribbon), &rules);
+
+        let signal = StrategySignal::sell(
+            context.symbol.clone(),
+            QuantityType::AllPosition,
+            format!("Ribbon alignment broke down to {}", ribbon.alignment.round_dp(2)),
+            None,
+        );
+        Ok(Some(
+            signal
+                .with_indicators(Self::ribbon_indicators(&ribbon))
+                .with_confidence(Decimal::ONE),
+        ))
+    }
+
+    fn validate_parameters(&self, parameters: &Value) -> Result<(), AppError> {
+        let config: MaRibbonConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid parameters: {}", e)))?;
+
+        config.validate()
+            .map_err(AppError::BadRequest)?;
+
+        Ok(())
+    }
+
+    fn parameter_schema(&self) -> Value {
+        MaRibbonConfig::json_schema()
+    }
+
+    fn min_data_points(&self) -> usize {
+        self.config.as_ref().map(|config| config.longest_period()).unwrap_or(1)
+    }
+
+    fn explain(&self) -> Value {
+        self.last_explanation.clone()
+    }
+
+    fn lifecycle(&self) -> StrategyLifecycle {
+        self.lifecycle
+    }
+
+    fn into_live(self: Box<Self>) -> Option<Box<dyn LiveExecutableStrategy>> {
+        Some(self)
+    }
+
+    fn get_state(&self) -> Result<Value, AppError> {
+        let mut state = serde_json::to_value(&self.state)
+            .map_err(|e| AppError::BadRequest(format!("Failed to serialize state: {}", e)))?;
+
+        if let Some(state_obj) = state.as_object_mut() {
+            state_obj.insert("lifecycle".to_string(), json!(self.lifecycle));
+            state_obj.insert("is_active".to_string(), Value::Bool(self.lifecycle.accepts_signals()));
+        }
+
+        Ok(state)
+    }
+
+    fn restore_state(&mut self, state: &Value) -> Result<(), AppError> {
+        self.state = serde_json::from_value(state.clone())
+            .map_err(|e| AppError::BadRequest(format!("Failed to deserialize state: {}", e)))?;
+        if let Some(lifecycle) = state.get("lifecycle") {
+            self.lifecycle = serde_json::from_value(lifecycle.clone())
+                .map_err(|e| AppError::BadRequest(format!("Failed to deserialize lifecycle: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LiveExecutableStrategy for MaRibbonStrategy {
+    async fn start_live_execution(&mut self, _context: &StrategyContext) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Start)?;
+        info!("MA Ribbon strategy started for live execution");
+        Ok(())
+    }
+
+    async fn stop_live_execution(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Stop)?;
+        info!("MA Ribbon strategy stopped");
+        Ok(())
+    }
+
+    fn next_execution_time(&self) -> Option<DateTime<Utc>> {
+        // Analyzed on every new candle
+        None
+    }
+}
+
+#[async_trait]
+impl ControllableStrategy for MaRibbonStrategy {
+    async fn pause(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Pause)?;
+        info!("MA Ribbon strategy paused");
+        Ok(())
+    }
+
+    async fn resume(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Resume)?;
+        info!("MA Ribbon strategy resumed");
+        Ok(())
+    }
+}
+
+impl Default for MaRibbonStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use crate::exchange_connectors::Kline;
+    use crate::strategies::core::{QuantityType, Strategy, StrategyContext, StrategyContextBuilder, StrategyMode, StrategySignalType};
+    use crate::strategies::implementations::ma_ribbon::{MaRibbonConfig, MaRibbonStrategy};
+    use crate::strategies::indicators::MaType;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn create_klines(closes: &[i64]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let price = Decimal::from(*close);
+                Kline {
+                    open_time: base_time + Duration::hours(i as i64),
+                    close_time: base_time + Duration::hours(i as i64) + Duration::minutes(59),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::from(1000),
+                    quote_asset_volume: Decimal::from(1000) * price,
+                    number_of_trades: 100,
+                    taker_buy_base_asset_volume: Decimal::from(500),
+                    taker_buy_quote_asset_volume: Decimal::from(500) * price,
+                }
+            })
+            .collect()
+    }
+
+    fn context_at(klines: &[Kline], index: usize) -> StrategyContext {
+        StrategyContextBuilder::new()
+            .strategy_id(Uuid::new_v4())
+            .user_id(Uuid::new_v4())
+            .symbol("BTCUSDT".to_string())
+            .interval("1h".to_string())
+            .mode(StrategyMode::Backtest)
+            .current_time(klines[index].close_time)
+            .historical_data(klines[..=index].to_vec())
+            .current_price(klines[index].close)
+            .available_balance(Decimal::from(10000))
+            .build()
+            .expect("Failed to build test context")
+    }
+
+    #[tokio::test]
+    async fn test_enters_on_full_alignment_and_exits_when_it_breaks() {
+        // Flat, then a steady climb, then a pullback that tangles the ribbon
+        let klines = create_klines(&[100, 100, 100, 100, 100, 102, 104, 106, 108, 110, 104, 98]);
+        let mut strategy = MaRibbonStrategy::new();
+        let config = serde_json::to_value(MaRibbonConfig::new(&[2, 3, 5], MaType::Sma)).unwrap();
+        strategy.initialize(&config, StrategyMode::Backtest, &context_at(&klines, 0)).await.unwrap();
+
+        let mut signals = Vec::new();
+        for index in 0..klines.len() {
+            if let Some(signal) = strategy.analyze(&context_at(&klines, index)).await.unwrap() {
+                signals.push((index, signal));
+            }
+        }
+
+        assert_eq!(signals.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![5, 10]);
+
+        let (_, entry) = &signals[0];
+        assert_eq!(entry.signal_type, StrategySignalType::Enter);
+        assert!(matches!(entry.action.quantity, QuantityType::BalancePercentage(pct) if pct == Decimal::from(10)));
+        assert_eq!(entry.metadata.indicators[0].value, Decimal::ONE);
+
+        let (_, exit) = &signals[1];
+        assert_eq!(exit.signal_type, StrategySignalType::Exit);
+        assert!(matches!(exit.action.quantity, QuantityType::AllPosition));
+        assert_eq!(strategy.explain()["gated_by"], "ribbon_fully_bullish");
+
+        let state = strategy.get_state().unwrap();
+        assert_eq!(state["in_position"], false);
+        assert_eq!(state["trade_count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_enough_bars() {
+        let klines = create_klines(&[100, 101, 102]);
+        let mut strategy = MaRibbonStrategy::new();
+        let config = serde_json::to_value(MaRibbonConfig::new(&[2, 3, 5], MaType::Ema)).unwrap();
+        strategy.initialize(&config, StrategyMode::Backtest, &context_at(&klines, 0)).await.unwrap();
+
+        assert!(strategy.analyze(&context_at(&klines, 2)).await.unwrap().is_none());
+        assert_eq!(strategy.explain()["gated_by"], "min_data_points");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let strategy = MaRibbonStrategy::new();
+        assert!(strategy.validate_parameters(&json!({})).is_ok());
+        assert!(strategy.validate_parameters(&json!({ "periods": [5, 10, 20], "ma_type": "ema" })).is_ok());
+
+        assert!(strategy.validate_parameters(&json!({ "periods": [20] })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "periods": [10, 10, 20] })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "periods": [1, 10] })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "position_size_pct": 150 })).is_err());
+    }
+}
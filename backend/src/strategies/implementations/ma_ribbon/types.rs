@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Persistent ribbon strategy state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaRibbonState {
+    /// Whether the strategy has entered and not yet exited
+    pub in_position: bool,
+    /// Price the open position was entered at
+    pub entry_price: Option<Decimal>,
+    /// When the open position was entered
+    pub entry_time: Option<DateTime<Utc>>,
+    /// Ribbon alignment at the last analysis
+    pub last_alignment: Option<Decimal>,
+    /// Number of entries and exits signalled
+    pub trade_count: u32,
+}
@@ -1,5 +1,8 @@
 pub mod dca;
 pub mod sma_crossover;
 pub mod grid_trading;
+pub mod rebalance;
+pub mod ma_ribbon;
+pub mod rsi_macd_confluence;
 
 // Re-export all strategy implementations
@@ -0,0 +1,136 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Configuration for the target-weight rebalancing strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceConfig {
+    /// Target weight per symbol, in percent of basket value. Whatever is left below
+    /// 100 is held as cash.
+    pub target_weights: BTreeMap<String, Decimal>,
+    /// Rebalance once any asset is this many percentage points away from its target
+    pub drift_threshold_pct: Decimal,
+    /// Only check drift every this many hours; `None` checks on every analysis.
+    /// With a zero drift threshold this rebalances on a fixed schedule.
+    #[serde(default)]
+    pub rebalance_interval_hours: Option<u32>,
+    /// Trades worth less than this, in quote currency, are not worth placing
+    #[serde(default = "default_min_trade_value")]
+    pub min_trade_value: Decimal,
+}
+
+fn default_min_trade_value() -> Decimal {
+    Decimal::from(10)
+}
+
+impl RebalanceConfig {
+    /// Drift-triggered rebalancing across `weights`
+    pub fn new(weights: &[(&str, Decimal)], drift_threshold_pct: Decimal) -> Self {
+        Self {
+            target_weights: weights
+                .iter()
+                .map(|(symbol, weight)| (symbol.to_uppercase(), *weight))
+                .collect(),
+            drift_threshold_pct,
+            rebalance_interval_hours: None,
+            min_trade_value: default_min_trade_value(),
+        }
+    }
+
+    /// Rebalance on a fixed schedule, whatever the drift
+    pub fn scheduled(weights: &[(&str, Decimal)], interval_hours: u32) -> Self {
+        Self {
+            rebalance_interval_hours: Some(interval_hours),
+            ..Self::new(weights, Decimal::ZERO)
+        }
+    }
+
+    /// Target weight of `symbol`, if it is part of the basket
+    pub fn target_weight(&self, symbol: &str) -> Option<Decimal> {
+        self.target_weights
+            .iter()
+            .find(|(target, _)| target.eq_ignore_ascii_case(symbol))
+            .map(|(_, weight)| *weight)
+    }
+
+    /// Percentage of the basket value kept in cash
+    pub fn cash_weight(&self) -> Decimal {
+        Decimal::from(100) - self.target_weights.values().copied().sum::<Decimal>()
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.target_weights.len() < 2 {
+            return Err("At least two assets are required to rebalance between".to_string());
+        }
+
+        for (symbol, weight) in &self.target_weights {
+            if symbol.trim().is_empty() {
+                return Err("Target weight symbols cannot be empty".to_string());
+            }
+            if *weight <= Decimal::ZERO {
+                return Err(format!("Target weight for {} must be positive", symbol));
+            }
+        }
+
+        if self.cash_weight() < Decimal::ZERO {
+            return Err("Target weights cannot add up to more than 100%".to_string());
+        }
+
+        if self.drift_threshold_pct < Decimal::ZERO || self.drift_threshold_pct >= Decimal::from(100) {
+            return Err("Drift threshold must be between 0 and 100 percentage points".to_string());
+        }
+
+        if self.drift_threshold_pct == Decimal::ZERO && self.rebalance_interval_hours.is_none() {
+            return Err("A zero drift threshold requires a rebalance interval".to_string());
+        }
+
+        if self.rebalance_interval_hours == Some(0) {
+            return Err("Rebalance interval must be at least 1 hour".to_string());
+        }
+
+        if self.min_trade_value < Decimal::ZERO {
+            return Err("Minimum trade value cannot be negative".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Get JSON schema for this configuration
+    pub fn json_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["target_weights", "drift_threshold_pct"],
+            "properties": {
+                "target_weights": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "number",
+                        "exclusiveMinimum": 0,
+                        "maximum": 100
+                    },
+                    "minProperties": 2,
+                    "description": "Target weight per symbol as a percentage of basket value; the remainder is held as cash"
+                },
+                "drift_threshold_pct": {
+                    "type": "number",
+                    "minimum": 0,
+                    "maximum": 100,
+                    "description": "Percentage points an asset may drift from its target before rebalancing"
+                },
+                "rebalance_interval_hours": {
+                    "type": ["integer", "null"],
+                    "minimum": 1,
+                    "description": "Only check drift this often; leave empty to check on every candle"
+                },
+                "min_trade_value": {
+                    "type": "number",
+                    "minimum": 0,
+                    "default": 10,
+                    "description": "Smallest rebalancing trade worth placing, in quote currency"
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata, StrategyPreset};
+use super::{RebalanceStrategy, rebalance_presets};
+
+/// Factory for creating rebalancing strategy instances
+pub struct RebalanceStrategyFactory {
+    metadata: StrategyMetadata,
+}
+
+impl RebalanceStrategyFactory {
+    /// Create a new rebalancing strategy factory
+    pub fn new() -> Self {
+        Self {
+            metadata: RebalanceStrategy::create_metadata(),
+        }
+    }
+}
+
+impl StrategyFactory for RebalanceStrategyFactory {
+    fn create(&self) -> Box<dyn Strategy> {
+        Box::new(RebalanceStrategy::new())
+    }
+
+    fn metadata(&self) -> &StrategyMetadata {
+        &self.metadata
+    }
+
+    fn presets(&self) -> Vec<StrategyPreset> {
+        rebalance_presets()
+    }
+}
+
+impl Default for RebalanceStrategyFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,33 @@
+use rust_decimal::Decimal;
+
+use crate::strategies::core::{RiskLevel, StrategyPreset};
+use super::RebalanceConfig;
+
+/// Presets registered with the strategy registry, over a BTC/ETH basket
+pub fn rebalance_presets() -> Vec<StrategyPreset> {
+    let pct = Decimal::from;
+
+    vec![
+        StrategyPreset::new(
+            "conservative",
+            "Conservative",
+            "40% BTC and 20% ETH with 40% cash, rebalanced once drift reaches 10 points",
+            RiskLevel::Conservative,
+            &RebalanceConfig::new(&[("BTCUSDT", pct(40)), ("ETHUSDT", pct(20))], pct(10)),
+        ),
+        StrategyPreset::new(
+            "balanced",
+            "Balanced",
+            "60% BTC and 40% ETH, rebalanced once drift reaches 5 points",
+            RiskLevel::Moderate,
+            &RebalanceConfig::new(&[("BTCUSDT", pct(60)), ("ETHUSDT", pct(40))], pct(5)),
+        ),
+        StrategyPreset::new(
+            "weekly",
+            "Weekly",
+            "50% BTC and 50% ETH, reset to target every week regardless of drift",
+            RiskLevel::Moderate,
+            &RebalanceConfig::scheduled(&[("BTCUSDT", pct(50)), ("ETHUSDT", pct(50))], 24 * 7),
+        ),
+    ]
+}
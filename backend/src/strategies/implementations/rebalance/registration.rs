@@ -0,0 +1,52 @@
+use crate::strategies::core::{register_strategy, FactorizableStrategy};
+use crate::utils::errors::AppError;
+use super::{RebalanceStrategy, RebalanceStrategyFactory};
+
+/// Register the rebalancing strategy in the global registry
+pub fn register_rebalance_strategy() -> Result<(), AppError> {
+    let factory = RebalanceStrategyFactory::new();
+    register_strategy(factory)?;
+    tracing::info!("Rebalance strategy registered successfully");
+    Ok(())
+}
+
+impl FactorizableStrategy for RebalanceStrategy {
+    fn get_metadata() -> crate::strategies::core::StrategyMetadata {
+        RebalanceStrategy::create_metadata()
+    }
+}
+
+/// Initialize rebalancing strategies during application startup
+pub fn init_rebalance_strategies() -> Result<(), AppError> {
+    tracing::info!("Initializing Rebalance strategies...");
+
+    match register_rebalance_strategy() {
+        Ok(_) => {
+            tracing::info!("All Rebalance strategies initialized successfully");
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("Failed to initialize Rebalance strategies: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::core::{get_global_registry, create_strategy};
+
+    #[test]
+    fn test_rebalance_strategy_registration() {
+        assert!(register_rebalance_strategy().is_ok());
+
+        let registry = get_global_registry();
+        let registry = registry.read().unwrap();
+        assert!(registry.contains("rebalance_v1"));
+
+        drop(registry);
+        let strategy = create_strategy("rebalance_v1").unwrap();
+        assert_eq!(strategy.metadata().name, "Target Weight Rebalancing");
+    }
+}
@@ -0,0 +1,452 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::strategies::core::{
+    Strategy, StrategyMetadata, StrategyMode, StrategyContext, StrategySignal,
+    StrategyCategory, RiskLevel, LiveExecutableStrategy, ControllableStrategy, IndicatorValue, QuantityType,
+    RuleEvaluation, StrategyLifecycle, LifecycleEvent,
+};
+use crate::utils::errors::AppError;
+
+use super::config::RebalanceConfig;
+use super::types::*;
+
+/// Keeps a basket of assets at target percentage weights.
+///
+/// The basket is read from `current_positions` (valued at each position's current
+/// price) plus `available_balance` as cash. Once drift exceeds the threshold a
+/// rebalance starts and one trade is signalled per analysis, overweight assets first
+/// so their proceeds fund the buys, until every asset is back within a minimum trade
+/// of its target.
+pub struct RebalanceStrategy {
+    /// Strategy configuration
+    config: Option<RebalanceConfig>,
+    /// Current execution state
+    state: RebalanceState,
+    /// Lifecycle state; gates analysis and live execution
+    lifecycle: StrategyLifecycle,
+    /// Structured explanation of the last analyze call
+    last_explanation: Value,
+    /// Strategy metadata
+    metadata: StrategyMetadata,
+}
+
+impl RebalanceStrategy {
+    /// Create a new rebalancing strategy instance
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            state: RebalanceState::default(),
+            lifecycle: StrategyLifecycle::Created,
+            last_explanation: Value::Null,
+            metadata: Self::create_metadata(),
+        }
+    }
+
+    /// Create strategy metadata
+    pub fn create_metadata() -> StrategyMetadata {
+        StrategyMetadata {
+            id: "rebalance_v1".to_string(),
+            name: "Target Weight Rebalancing".to_string(),
+            description: "Holds a basket of assets at target percentage weights, trimming winners and topping up laggards when they drift out of band".to_string(),
+            version: "1.0.0".to_string(),
+            author: "E-Squared Trading Bot".to_string(),
+            category: StrategyCategory::Custom,
+            risk_level: RiskLevel::Conservative,
+            supported_modes: vec![
+                StrategyMode::Backtest,
+                StrategyMode::Paper,
+                StrategyMode::Live,
+            ],
+            min_balance: Some(Decimal::from(100)),
+            max_positions: None,
+            supported_intervals: vec![
+                "1h".to_string(), "4h".to_string(), "1d".to_string(), "1w".to_string(),
+            ],
+            tags: vec![
+                "portfolio".to_string(),
+                "rebalancing".to_string(),
+                "multi_asset".to_string(),
+                "passive".to_string(),
+            ],
+        }
+    }
+
+    /// Value every basket asset and compare it with its target. Returns the basket
+    /// value (holdings plus cash) alongside the allocations.
+    pub fn allocations(config: &RebalanceConfig, context: &StrategyContext) -> (Decimal, Vec<AssetAllocation>) {
+        let mut allocations: Vec<AssetAllocation> = config.target_weights
+            .iter()
+            .map(|(symbol, target_weight)| {
+                let position = context.current_positions
+                    .iter()
+                    .find(|p| p.symbol.eq_ignore_ascii_case(symbol) && p.quantity > Decimal::ZERO);
+
+                // The context price is the freshest one for its own symbol
+                let price = if context.symbol.eq_ignore_ascii_case(symbol) {
+                    Some(context.current_price)
+                } else {
+                    position.map(|p| p.current_price)
+                };
+                let quantity = position.map(|p| p.quantity).unwrap_or(Decimal::ZERO);
+
+                AssetAllocation {
+                    symbol: symbol.clone(),
+                    quantity,
+                    price,
+                    value: quantity * price.unwrap_or(Decimal::ZERO),
+                    current_weight: Decimal::ZERO,
+                    target_weight: *target_weight,
+                    drift: Decimal::ZERO,
+                }
+            })
+            .collect();
+
+        let total = context.available_balance.max(Decimal::ZERO)
+            + allocations.iter().map(|a| a.value).sum::<Decimal>();
+
+        if total > Decimal::ZERO {
+            for allocation in &mut allocations {
+                allocation.current_weight = allocation.value / total * Decimal::from(100);
+                allocation.drift = allocation.current_weight - allocation.target_weight;
+            }
+        }
+
+        (total, allocations)
+    }
+
+    /// Trades that bring every asset back to target: sells first, largest first,
+    /// then buys, largest first. Trades under the minimum trade value are dropped.
+    pub fn plan_trades(config: &RebalanceConfig, total: Decimal, allocations: &[AssetAllocation]) -> Vec<RebalanceTrade> {
+        let mut sells = Vec::new();
+        let mut buys = Vec::new();
+
+        for allocation in allocations {
+            let value = (allocation.drift.abs() / Decimal::from(100) * total).round_dp(8);
+            if value.is_zero() || value < config.min_trade_value {
+                continue;
+            }
+
+            if allocation.drift > Decimal::ZERO {
+                // Overweight assets are held, so they always have a price
+                let Some(price) = allocation.price.filter(|p| *p > Decimal::ZERO) else {
+                    continue;
+                };
+                sells.push(RebalanceTrade {
+                    symbol: allocation.symbol.clone(),
+                    side: TradeSide::Sell,
+                    value,
+                    quantity: Some((value / price).round_dp(8).min(allocation.quantity)),
+                });
+            } else {
+                buys.push(RebalanceTrade {
+                    symbol: allocation.symbol.clone(),
+                    side: TradeSide::Buy,
+                    value,
+                    quantity: None,
+                });
+            }
+        }
+
+        sells.sort_by(|a, b| b.value.cmp(&a.value));
+        buys.sort_by(|a, b| b.value.cmp(&a.value));
+        sells.extend(buys);
+        sells
+    }
+
+    /// Whether the configured interval has passed since drift was last checked
+    fn check_due(&self, config: &RebalanceConfig, now: DateTime<Utc>) -> bool {
+        match (config.rebalance_interval_hours, self.state.last_check_time) {
+            (Some(hours), Some(last_check)) => now >= last_check + Duration::hours(hours as i64),
+            _ => true,
+        }
+    }
+
+    /// Turn a planned trade into a signal. Buys are capped at the cash on hand.
+    fn trade_signal(
+        &self,
+        trade: &RebalanceTrade,
+        allocation: &AssetAllocation,
+        available_balance: Decimal,
+    ) -> StrategySignal {
+        let reason = format!(
+            "Rebalance: {} at {}% vs {}% target",
+            trade.symbol,
+            allocation.current_weight.round_dp(2),
+            allocation.target_weight.round_dp(2),
+        );
+
+        let signal = match trade.side {
+            TradeSide::Sell => StrategySignal::reduce_position(
+                trade.symbol.clone(),
+                QuantityType::Fixed(trade.quantity.unwrap_or(Decimal::ZERO)),
+                reason,
+                None,
+            ),
+            TradeSide::Buy => StrategySignal::add_to_position(
+                trade.symbol.clone(),
+                QuantityType::DollarAmount(trade.value.min(available_balance)),
+                reason,
+                None,
+            ),
+        };
+
+        signal
+            .with_indicators(vec![
+                IndicatorValue {
+                    name: "Current Weight".to_string(),
+                    value: allocation.current_weight,
+                    signal: "allocation".to_string(),
+                },
+                IndicatorValue {
+                    name: "Target Weight".to_string(),
+                    value: allocation.target_weight,
+                    signal: "allocation".to_string(),
+                },
+                IndicatorValue {
+                    name: "Drift".to_string(),
+                    value: allocation.drift,
+                    signal: if allocation.drift > Decimal::ZERO { "overweight" } else { "underweight" }.to_string(),
+                },
+            ])
+            .with_confidence(Decimal::ONE)
+    }
+
+    fn build_explanation(
+        &self,
+        context: &StrategyContext,
+        decision: &str,
+        total: Decimal,
+        allocations: &[AssetAllocation],
+        trades: &[RebalanceTrade],
+        rules: &[RuleEvaluation],
+    ) -> Value {
+        json!({
+            "strategy": self.metadata.id,
+            "timestamp": context.current_time,
+            "decision": decision,
+            "basket_value": total,
+            "cash": context.available_balance,
+            "allocations": allocations,
+            "planned_trades": trades,
+            "rebalance_in_progress": self.state.rebalance_in_progress,
+            "rules": rules,
+            "gated_by": rules.iter().find(|r| !r.passed).map(|r| r.rule.clone()),
+        })
+    }
+}
+
+#[async_trait]
+impl Strategy for RebalanceStrategy {
+    fn metadata(&self) -> StrategyMetadata {
+        self.metadata.clone()
+    }
+
+    async fn initialize(
+        &mut self,
+        parameters: &Value,
+        _mode: StrategyMode,
+        _context: &StrategyContext,
+    ) -> Result<(), AppError> {
+        let config: RebalanceConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid rebalance parameters: {}", e)))?;
+
+        config.validate()
+            .map_err(|e| AppError::BadRequest(e))?;
+        let lifecycle = self.lifecycle.transition(LifecycleEvent::Initialize)?;
+
+        self.config = Some(config);
+        self.state = RebalanceState::default();
+        self.lifecycle = lifecycle;
+        self.last_explanation = Value::Null;
+
+        info!("Rebalance strategy initialized successfully");
+        Ok(())
+    }
+
+    async fn analyze(
+        &mut self,
+        context: &StrategyContext,
+    ) -> Result<Option<StrategySignal>, AppError> {
+        let config = self.config.clone()
+            .ok_or_else(|| AppError::BadRequest("Strategy not initialized".to_string()))?;
+
+        if !self.lifecycle.accepts_signals() {
+            return Ok(None);
+        }
+
+        let (total, allocations) = Self::allocations(&config, context);
+        let max_drift = allocations.iter().map(|a| a.drift.abs()).max().unwrap_or(Decimal::ZERO);
+        let mut rules = Vec::new();
+
+        if !self.state.rebalance_in_progress {
+            let due = self.check_due(&config, context.current_time);
+            rules.push(RuleEvaluation::new(
+                "rebalance_check_due",
+                None,
+                config.rebalance_interval_hours.map(Decimal::from),
+                due,
+            ));
+            if !due {
+                self.last_explanation = self.build_explanation(context, "hold", total, &allocations, &[], &rules);
+                return Ok(None);
+            }
+            self.state.last_check_time = Some(context.current_time);
+
+            let drifted = total > Decimal::ZERO && max_drift >= config.drift_threshold_pct;
+            rules.push(RuleEvaluation::new("drift_threshold", Some(max_drift), Some(config.drift_threshold_pct), drifted));
+            if !drifted {
+                self.last_explanation = self.build_explanation(context, "hold", total, &allocations, &[], &rules);
+                return Ok(None);
+            }
+
+            info!("Basket drifted {} points from target, starting rebalance", max_drift.round_dp(2));
+            self.state.rebalance_in_progress = true;
+            self.state.last_rebalance_time = Some(context.current_time);
+            self.state.rebalance_count += 1;
+        }
+
+        let trades = Self::plan_trades(&config, total, &allocations);
+        // A buy the cash on hand cannot fund to the minimum ends the rebalance
+        let next = trades.first().filter(|trade| match trade.side {
+            TradeSide::Sell => true,
+            TradeSide::Buy => {
+                let affordable = trade.value.min(context.available_balance);
+                affordable > Decimal::ZERO && affordable >= config.min_trade_value
+            }
+        });
+        rules.push(RuleEvaluation::new(
+            "rebalance_trade_pending",
+            Some(Decimal::from(trades.len())),
+            Some(Decimal::ONE),
+            next.is_some(),
+        ));
+
+        let Some(trade) = next else {
+            self.state.rebalance_in_progress = false;
+            self.last_explanation = self.build_explanation(context, "rebalanced", total, &allocations, &trades, &rules);
+            return Ok(None);
+        };
+
+        let allocation = allocations.iter()
+            .find(|a| a.symbol == trade.symbol)
+            .expect("planned trades come from the allocations");
+        let mut signal = self.trade_signal(trade, allocation, context.available_balance);
+        signal.metadata.custom_data = json!({
+            "basket_value": total,
+            "allocations": allocations,
+            "planned_trades": trades,
+        });
+
+        self.state.trade_count += 1;
+        let decision = match trade.side {
+            TradeSide::Buy => "buy",
+            TradeSide::Sell => "sell",
+        };
+        self.last_explanation = self.build_explanation(context, decision, total, &allocations, &trades, &rules);
+
+        Ok(Some(signal))
+    }
+
+    fn validate_parameters(&self, parameters: &Value) -> Result<(), AppError> {
+        let config: RebalanceConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid parameters: {}", e)))?;
+
+        config.validate()
+            .map_err(|e| AppError::BadRequest(e))?;
+
+        Ok(())
+    }
+
+    fn parameter_schema(&self) -> Value {
+        RebalanceConfig::json_schema()
+    }
+
+    fn explain(&self) -> Value {
+        self.last_explanation.clone()
+    }
+
+    fn lifecycle(&self) -> StrategyLifecycle {
+        self.lifecycle
+    }
+
+    fn into_live(self: Box<Self>) -> Option<Box<dyn LiveExecutableStrategy>> {
+        Some(self)
+    }
+
+    fn supports_symbol(&self, symbol: &str) -> bool {
+        match &self.config {
+            Some(config) => config.target_weight(symbol).is_some(),
+            None => true,
+        }
+    }
+
+    fn get_state(&self) -> Result<Value, AppError> {
+        let mut state = serde_json::to_value(&self.state)
+            .map_err(|e| AppError::BadRequest(format!("Failed to serialize state: {}", e)))?;
+
+        if let Some(state_obj) = state.as_object_mut() {
+            state_obj.insert("lifecycle".to_string(), json!(self.lifecycle));
+            state_obj.insert("is_active".to_string(), Value::Bool(self.lifecycle.accepts_signals()));
+        }
+
+        Ok(state)
+    }
+
+    fn restore_state(&mut self, state: &Value) -> Result<(), AppError> {
+        self.state = serde_json::from_value(state.clone())
+            .map_err(|e| AppError::BadRequest(format!("Failed to deserialize state: {}", e)))?;
+        if let Some(lifecycle) = state.get("lifecycle") {
+            self.lifecycle = serde_json::from_value(lifecycle.clone())
+                .map_err(|e| AppError::BadRequest(format!("Failed to deserialize lifecycle: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LiveExecutableStrategy for RebalanceStrategy {
+    async fn start_live_execution(&mut self, _context: &StrategyContext) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Start)?;
+        info!("Rebalance strategy started for live execution");
+        Ok(())
+    }
+
+    async fn stop_live_execution(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Stop)?;
+        info!("Rebalance strategy stopped");
+        Ok(())
+    }
+
+    fn next_execution_time(&self) -> Option<DateTime<Utc>> {
+        let hours = self.config.as_ref()?.rebalance_interval_hours?;
+        if self.state.rebalance_in_progress {
+            return None;
+        }
+        self.state.last_check_time.map(|last_check| last_check + Duration::hours(hours as i64))
+    }
+}
+
+#[async_trait]
+impl ControllableStrategy for RebalanceStrategy {
+    async fn pause(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Pause)?;
+        info!("Rebalance strategy paused");
+        Ok(())
+    }
+
+    async fn resume(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Resume)?;
+        info!("Rebalance strategy resumed");
+        Ok(())
+    }
+}
+
+impl Default for RebalanceStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
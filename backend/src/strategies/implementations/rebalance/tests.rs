@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use crate::strategies::core::{
+        Position, QuantityType, Strategy, StrategyContext, StrategyContextBuilder, StrategyMode, StrategySignalType,
+    };
+    use crate::strategies::implementations::rebalance::{RebalanceConfig, RebalanceStrategy};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    fn position(symbol: &str, quantity: Decimal, price: Decimal) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            quantity,
+            average_price: price,
+            current_price: price,
+            pnl: Decimal::ZERO,
+            pnl_percentage: Decimal::ZERO,
+            created_at: start(),
+        }
+    }
+
+    /// A BTC/ETH basket valued at `btc_price` and `eth_price`
+    fn basket_context(
+        hours: i64,
+        cash: Decimal,
+        btc: (Decimal, Decimal),
+        eth: (Decimal, Decimal),
+    ) -> StrategyContext {
+        StrategyContextBuilder::new()
+            .strategy_id(Uuid::new_v4())
+            .user_id(Uuid::new_v4())
+            .symbol("BTCUSDT".to_string())
+            .interval("1h".to_string())
+            .mode(StrategyMode::Backtest)
+            .current_time(start() + Duration::hours(hours))
+            .current_price(btc.1)
+            .available_balance(cash)
+            .current_positions(vec![
+                position("BTCUSDT", btc.0, btc.1),
+                position("ETHUSDT", eth.0, eth.1),
+            ])
+            .build()
+            .expect("Failed to build test context")
+    }
+
+    async fn initialized_strategy(config: RebalanceConfig) -> RebalanceStrategy {
+        let mut strategy = RebalanceStrategy::new();
+        let params = serde_json::to_value(config).unwrap();
+        let context = basket_context(0, Decimal::ZERO, (Decimal::ONE, Decimal::from(10000)), (Decimal::from(5), Decimal::from(2000)));
+        strategy.initialize(&params, StrategyMode::Backtest, &context).await.unwrap();
+        strategy
+    }
+
+    fn fifty_fifty() -> RebalanceConfig {
+        RebalanceConfig::new(&[("BTCUSDT", Decimal::from(50)), ("ETHUSDT", Decimal::from(50))], Decimal::from(5))
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_trims_outperformer_back_to_target() {
+        let mut strategy = initialized_strategy(fifty_fifty()).await;
+        let eth = (Decimal::from(5), Decimal::from(2000));
+
+        // 1 BTC at 10,000 and 5 ETH at 2,000 start exactly on target
+        let balanced = basket_context(0, Decimal::ZERO, (Decimal::ONE, Decimal::from(10000)), eth);
+        assert!(strategy.analyze(&balanced).await.unwrap().is_none());
+
+        // BTC rallies 50%: 15,000 of a 25,000 basket is 60%, 10 points over target
+        let rallied = basket_context(1, Decimal::ZERO, (Decimal::ONE, Decimal::from(15000)), eth);
+        let trim = strategy.analyze(&rallied).await.unwrap().expect("drift should trigger a rebalance");
+        assert_eq!(trim.symbol, "BTCUSDT");
+        assert_eq!(trim.signal_type, StrategySignalType::ReducePosition);
+        let sold = match trim.action.quantity {
+            QuantityType::Fixed(quantity) => quantity,
+            other => panic!("expected a fixed sell quantity, got {:?}", other),
+        };
+        // Selling 2,500 worth leaves BTC at half the basket
+        let btc_left = Decimal::ONE - sold;
+        let btc_weight = btc_left * Decimal::from(15000) / Decimal::from(25000) * Decimal::from(100);
+        assert!((btc_weight - Decimal::from(50)).abs() < Decimal::new(1, 2), "BTC weight {}", btc_weight);
+        assert_eq!(trim.metadata.custom_data["planned_trades"].as_array().unwrap().len(), 2);
+
+        // The proceeds then top ETH back up
+        let cash = sold * Decimal::from(15000);
+        let after_sell = basket_context(2, cash, (btc_left, Decimal::from(15000)), eth);
+        let top_up = strategy.analyze(&after_sell).await.unwrap().expect("ETH is still underweight");
+        assert_eq!(top_up.symbol, "ETHUSDT");
+        assert_eq!(top_up.signal_type, StrategySignalType::AddToPosition);
+        let bought = match top_up.action.quantity {
+            QuantityType::DollarAmount(amount) => amount,
+            other => panic!("expected a dollar amount buy, got {:?}", other),
+        };
+        assert!((bought - Decimal::from(2500)).abs() < Decimal::ONE);
+
+        // Back on target, the rebalance completes
+        let eth_after = (eth.0 + bought / Decimal::from(2000), Decimal::from(2000));
+        let after_buy = basket_context(3, cash - bought, (btc_left, Decimal::from(15000)), eth_after);
+        assert!(strategy.analyze(&after_buy).await.unwrap().is_none());
+        assert_eq!(strategy.explain()["decision"], "rebalanced");
+
+        let state = strategy.get_state().unwrap();
+        assert_eq!(state["rebalance_in_progress"], false);
+        assert_eq!(state["rebalance_count"], 1);
+        assert_eq!(state["trade_count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_drift_inside_band_holds() {
+        let mut strategy = initialized_strategy(fifty_fifty()).await;
+
+        // 11,000 of 21,000 is about 52.4%, inside the 5 point band
+        let context = basket_context(0, Decimal::ZERO, (Decimal::ONE, Decimal::from(11000)), (Decimal::from(5), Decimal::from(2000)));
+        assert!(strategy.analyze(&context).await.unwrap().is_none());
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["decision"], "hold");
+        assert_eq!(explanation["gated_by"], "drift_threshold");
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_rebalance_waits_for_interval() {
+        let config = RebalanceConfig::scheduled(&[("BTCUSDT", Decimal::from(50)), ("ETHUSDT", Decimal::from(50))], 24);
+        let mut strategy = initialized_strategy(config).await;
+        let eth = (Decimal::from(5), Decimal::from(2000));
+
+        let balanced = basket_context(0, Decimal::ZERO, (Decimal::ONE, Decimal::from(10000)), eth);
+        assert!(strategy.analyze(&balanced).await.unwrap().is_none());
+
+        let rallied = |hours| basket_context(hours, Decimal::ZERO, (Decimal::ONE, Decimal::from(15000)), eth);
+        assert!(strategy.analyze(&rallied(1)).await.unwrap().is_none());
+        assert_eq!(strategy.explain()["gated_by"], "rebalance_check_due");
+
+        let signal = strategy.analyze(&rallied(24)).await.unwrap().expect("the daily check should rebalance");
+        assert_eq!(signal.symbol, "BTCUSDT");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let strategy = RebalanceStrategy::new();
+        assert!(strategy.validate_parameters(&serde_json::to_value(fifty_fifty()).unwrap()).is_ok());
+
+        let overweight = json!({ "target_weights": { "BTCUSDT": 70, "ETHUSDT": 50 }, "drift_threshold_pct": 5 });
+        assert!(strategy.validate_parameters(&overweight).is_err());
+
+        let single = json!({ "target_weights": { "BTCUSDT": 100 }, "drift_threshold_pct": 5 });
+        assert!(strategy.validate_parameters(&single).is_err());
+
+        let never = json!({ "target_weights": { "BTCUSDT": 50, "ETHUSDT": 50 }, "drift_threshold_pct": 0 });
+        assert!(strategy.validate_parameters(&never).is_err());
+    }
+}
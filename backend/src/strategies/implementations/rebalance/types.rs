@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Direction of a rebalancing trade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// Where one basket asset stands against its target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetAllocation {
+    pub symbol: String,
+    /// Held quantity
+    pub quantity: Decimal,
+    /// Latest price, if the asset is held or is the context symbol
+    pub price: Option<Decimal>,
+    /// Market value of the holding
+    pub value: Decimal,
+    /// Current weight in percent of basket value
+    pub current_weight: Decimal,
+    /// Target weight in percent of basket value
+    pub target_weight: Decimal,
+    /// Current minus target weight, in percentage points
+    pub drift: Decimal,
+}
+
+/// A trade that moves one asset back to its target weight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceTrade {
+    pub symbol: String,
+    pub side: TradeSide,
+    /// Quote-currency value to buy or sell
+    pub value: Decimal,
+    /// Base quantity to sell; buys are sized by value
+    pub quantity: Option<Decimal>,
+}
+
+/// Persistent rebalancing state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RebalanceState {
+    /// A rebalance has been triggered and still has trades left to place
+    pub rebalance_in_progress: bool,
+    /// When drift was last checked
+    pub last_check_time: Option<DateTime<Utc>>,
+    /// When the last rebalance was triggered
+    pub last_rebalance_time: Option<DateTime<Utc>>,
+    /// Number of rebalances triggered
+    pub rebalance_count: u32,
+    /// Number of rebalancing trades signalled
+    pub trade_count: u32,
+}
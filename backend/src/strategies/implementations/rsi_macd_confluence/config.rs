@@ -0,0 +1,164 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Configuration for the RSI + MACD confluence strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RsiMacdConfluenceConfig {
+    /// Bars the RSI is computed over
+    #[serde(default = "default_rsi_period")]
+    pub rsi_period: usize,
+    /// RSI level an entry must cross up out of
+    #[serde(default = "default_oversold")]
+    pub oversold: Decimal,
+    /// RSI level an exit must cross down out of
+    #[serde(default = "default_overbought")]
+    pub overbought: Decimal,
+    /// Fast EMA period of the MACD line
+    #[serde(default = "default_macd_fast")]
+    pub macd_fast: usize,
+    /// Slow EMA period of the MACD line
+    #[serde(default = "default_macd_slow")]
+    pub macd_slow: usize,
+    /// EMA period of the MACD signal line
+    #[serde(default = "default_macd_signal")]
+    pub macd_signal: usize,
+    /// Percentage of available balance to enter with
+    #[serde(default = "default_position_size_pct")]
+    pub position_size_pct: Decimal,
+}
+
+fn default_rsi_period() -> usize {
+    14
+}
+
+fn default_oversold() -> Decimal {
+    Decimal::from(30)
+}
+
+fn default_overbought() -> Decimal {
+    Decimal::from(70)
+}
+
+fn default_macd_fast() -> usize {
+    12
+}
+
+fn default_macd_slow() -> usize {
+    26
+}
+
+fn default_macd_signal() -> usize {
+    9
+}
+
+fn default_position_size_pct() -> Decimal {
+    Decimal::from(10)
+}
+
+impl Default for RsiMacdConfluenceConfig {
+    fn default() -> Self {
+        Self {
+            rsi_period: default_rsi_period(),
+            oversold: default_oversold(),
+            overbought: default_overbought(),
+            macd_fast: default_macd_fast(),
+            macd_slow: default_macd_slow(),
+            macd_signal: default_macd_signal(),
+            position_size_pct: default_position_size_pct(),
+        }
+    }
+}
+
+impl RsiMacdConfluenceConfig {
+    /// Bars needed before both indicators, and the previous bar's RSI, are defined
+    pub fn min_data_points(&self) -> usize {
+        (self.rsi_period + 2).max(self.macd_slow + self.macd_signal - 1)
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rsi_period < 2 || self.rsi_period > 100 {
+            return Err("RSI period must be between 2 and 100".to_string());
+        }
+        if self.oversold <= Decimal::ZERO || self.overbought >= Decimal::from(100) {
+            return Err("RSI levels must be between 0 and 100".to_string());
+        }
+        if self.oversold >= self.overbought {
+            return Err("Oversold level must be below the overbought level".to_string());
+        }
+
+        if self.macd_fast == 0 || self.macd_signal == 0 {
+            return Err("MACD periods must be at least 1".to_string());
+        }
+        if self.macd_fast >= self.macd_slow {
+            return Err("MACD fast period must be shorter than the slow period".to_string());
+        }
+        if self.macd_slow > 200 || self.macd_signal > 200 {
+            return Err("MACD periods cannot exceed 200".to_string());
+        }
+
+        if self.position_size_pct <= Decimal::ZERO || self.position_size_pct > Decimal::from(100) {
+            return Err("Position size must be between 0 and 100 percent".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Get JSON schema for this configuration
+    pub fn json_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "rsi_period": {
+                    "type": "integer",
+                    "minimum": 2,
+                    "maximum": 100,
+                    "default": 14,
+                    "description": "Bars the RSI is computed over"
+                },
+                "oversold": {
+                    "type": "number",
+                    "exclusiveMinimum": 0,
+                    "exclusiveMaximum": 100,
+                    "default": 30,
+                    "description": "RSI level an entry must cross up out of"
+                },
+                "overbought": {
+                    "type": "number",
+                    "exclusiveMinimum": 0,
+                    "exclusiveMaximum": 100,
+                    "default": 70,
+                    "description": "RSI level an exit must cross down out of"
+                },
+                "macd_fast": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "default": 12,
+                    "description": "Fast EMA period of the MACD line"
+                },
+                "macd_slow": {
+                    "type": "integer",
+                    "minimum": 2,
+                    "maximum": 200,
+                    "default": 26,
+                    "description": "Slow EMA period of the MACD line"
+                },
+                "macd_signal": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": 200,
+                    "default": 9,
+                    "description": "EMA period of the MACD signal line"
+                },
+                "position_size_pct": {
+                    "type": "number",
+                    "exclusiveMinimum": 0,
+                    "maximum": 100,
+                    "default": 10,
+                    "description": "Percentage of available balance to enter with"
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata, StrategyPreset};
+use super::{RsiMacdConfluenceStrategy, rsi_macd_confluence_presets};
+
+/// Factory for creating RSI + MACD confluence strategy instances
+pub struct RsiMacdConfluenceStrategyFactory {
+    metadata: StrategyMetadata,
+}
+
+impl RsiMacdConfluenceStrategyFactory {
+    /// Create a new RSI + MACD confluence strategy factory
+    pub fn new() -> Self {
+        Self {
+            metadata: RsiMacdConfluenceStrategy::create_metadata(),
+        }
+    }
+}
+
+impl StrategyFactory for RsiMacdConfluenceStrategyFactory {
+    fn create(&self) -> Box<dyn Strategy> {
+        Box::new(RsiMacdConfluenceStrategy::new())
+    }
+
+    fn metadata(&self) -> &StrategyMetadata {
+        &self.metadata
+    }
+
+    fn presets(&self) -> Vec<StrategyPreset> {
+        rsi_macd_confluence_presets()
+    }
+}
+
+impl Default for RsiMacdConfluenceStrategyFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,44 @@
+use rust_decimal::Decimal;
+
+use crate::strategies::core::{RiskLevel, StrategyPreset};
+use super::RsiMacdConfluenceConfig;
+
+/// Presets registered with the strategy registry
+pub fn rsi_macd_confluence_presets() -> Vec<StrategyPreset> {
+    vec![
+        StrategyPreset::new(
+            "conservative",
+            "Conservative",
+            "Only trades deep RSI extremes (25 / 75) with the standard MACD",
+            RiskLevel::Conservative,
+            &RsiMacdConfluenceConfig {
+                oversold: Decimal::from(25),
+                overbought: Decimal::from(75),
+                position_size_pct: Decimal::from(5),
+                ..RsiMacdConfluenceConfig::default()
+            },
+        ),
+        StrategyPreset::new(
+            "balanced",
+            "Balanced",
+            "RSI 14 at 30 / 70 with MACD 12 / 26 / 9",
+            RiskLevel::Moderate,
+            &RsiMacdConfluenceConfig::default(),
+        ),
+        StrategyPreset::new(
+            "aggressive",
+            "Aggressive",
+            "Fast RSI 7 at 35 / 65 with MACD 8 / 17 / 9; trades more often",
+            RiskLevel::Aggressive,
+            &RsiMacdConfluenceConfig {
+                rsi_period: 7,
+                oversold: Decimal::from(35),
+                overbought: Decimal::from(65),
+                macd_fast: 8,
+                macd_slow: 17,
+                position_size_pct: Decimal::from(15),
+                ..RsiMacdConfluenceConfig::default()
+            },
+        ),
+    ]
+}
@@ -0,0 +1,52 @@
+use crate::strategies::core::{register_strategy, FactorizableStrategy};
+use crate::utils::errors::AppError;
+use super::{RsiMacdConfluenceStrategy, RsiMacdConfluenceStrategyFactory};
+
+/// Register the RSI + MACD confluence strategy in the global registry
+pub fn register_rsi_macd_confluence_strategy() -> Result<(), AppError> {
+    let factory = RsiMacdConfluenceStrategyFactory::new();
+    register_strategy(factory)?;
+    tracing::info!("RSI + MACD Confluence strategy registered successfully");
+    Ok(())
+}
+
+impl FactorizableStrategy for RsiMacdConfluenceStrategy {
+    fn get_metadata() -> crate::strategies::core::StrategyMetadata {
+        RsiMacdConfluenceStrategy::create_metadata()
+    }
+}
+
+/// Initialize RSI + MACD confluence strategies during application startup
+pub fn init_rsi_macd_confluence_strategies() -> Result<(), AppError> {
+    tracing::info!("Initializing RSI + MACD Confluence strategies...");
+
+    match register_rsi_macd_confluence_strategy() {
+        Ok(_) => {
+            tracing::info!("All RSI + MACD Confluence strategies initialized successfully");
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("Failed to initialize RSI + MACD Confluence strategies: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::core::{get_global_registry, create_strategy};
+
+    #[test]
+    fn test_rsi_macd_confluence_strategy_registration() {
+        assert!(register_rsi_macd_confluence_strategy().is_ok());
+
+        let registry = get_global_registry();
+        let registry = registry.read().unwrap();
+        assert!(registry.contains("rsi_macd_confluence_v1"));
+
+        drop(registry);
+        let strategy = create_strategy("rsi_macd_confluence_v1").unwrap();
+        assert_eq!(strategy.metadata().name, "RSI + MACD Confluence");
+    }
+}
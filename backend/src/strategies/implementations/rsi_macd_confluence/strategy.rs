@@ -0,0 +1,358 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::exchange_connectors::Kline;
+use crate::strategies::core::{
+    Strategy, StrategyMetadata, StrategyMode, StrategyContext, StrategySignal,
+    StrategyCategory, RiskLevel, LiveExecutableStrategy, ControllableStrategy, IndicatorValue, QuantityType,
+    RuleEvaluation, StrategyLifecycle, LifecycleEvent,
+};
+use crate::strategies::indicators;
+use crate::utils::errors::AppError;
+
+use super::config::RsiMacdConfluenceConfig;
+use super::types::*;
+
+/// Momentum strategy that needs RSI and MACD to agree before it trades.
+///
+/// Enters long when the RSI crosses up out of oversold while the MACD histogram is
+/// positive, and exits when the RSI crosses down out of overbought while the histogram
+/// is negative. Either indicator alone is ignored, which filters out most of the false
+/// signals each produces on its own.
+pub struct RsiMacdConfluenceStrategy {
+    /// Strategy configuration
+    config: Option<RsiMacdConfluenceConfig>,
+    /// Current execution state
+    state: RsiMacdConfluenceState,
+    /// Lifecycle state; gates analysis and live execution
+    lifecycle: StrategyLifecycle,
+    /// Structured explanation of the last analyze call
+    last_explanation: Value,
+    /// Strategy metadata
+    metadata: StrategyMetadata,
+}
+
+impl RsiMacdConfluenceStrategy {
+    /// Create a new RSI + MACD confluence strategy instance
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            state: RsiMacdConfluenceState::default(),
+            lifecycle: StrategyLifecycle::Created,
+            last_explanation: Value::Null,
+            metadata: Self::create_metadata(),
+        }
+    }
+
+    /// Create strategy metadata
+    pub fn create_metadata() -> StrategyMetadata {
+        StrategyMetadata {
+            id: "rsi_macd_confluence_v1".to_string(),
+            name: "RSI + MACD Confluence".to_string(),
+            description: "Buys when RSI recovers from oversold with a positive MACD histogram and sells when it falls from overbought with a negative one, ignoring either indicator on its own".to_string(),
+            version: "1.0.0".to_string(),
+            author: "E-Squared Trading Bot".to_string(),
+            category: StrategyCategory::TechnicalAnalysis,
+            risk_level: RiskLevel::Moderate,
+            supported_modes: vec![
+                StrategyMode::Backtest,
+                StrategyMode::Paper,
+                StrategyMode::Live,
+            ],
+            min_balance: Some(Decimal::from(100)),
+            max_positions: Some(1),
+            supported_intervals: vec![
+                "15m".to_string(), "1h".to_string(), "4h".to_string(), "1d".to_string(),
+            ],
+            tags: vec![
+                "momentum".to_string(),
+                "rsi".to_string(),
+                "macd".to_string(),
+                "confluence".to_string(),
+                "technical".to_string(),
+            ],
+        }
+    }
+
+    /// RSI of this bar and the one before, and MACD of this bar; `None` until all are defined
+    fn read_indicators(config: &RsiMacdConfluenceConfig, data: &[Kline]) -> Option<ConfluenceReading> {
+        let rsi = indicators::rsi(data, config.rsi_period)?;
+        let (_, previous_bars) = data.split_last()?;
+        let previous_rsi = indicators::rsi(previous_bars, config.rsi_period)?;
+        let macd = indicators::macd_series(data, config.macd_fast, config.macd_slow, config.macd_signal)?.pop()?;
+
+        Some(ConfluenceReading {
+            previous_rsi,
+            rsi,
+            macd_line: macd.macd_line,
+            signal_line: macd.signal_line,
+            histogram: macd.histogram,
+        })
+    }
+
+    /// Both conditions an entry needs, RSI first
+    fn entry_rules(config: &RsiMacdConfluenceConfig, reading: &ConfluenceReading) -> [RuleEvaluation; 2] {
+        let crossed_up = reading.previous_rsi <= config.oversold && reading.rsi > config.oversold;
+        [
+            RuleEvaluation::new("rsi_crossed_up_from_oversold", Some(reading.rsi), Some(config.oversold), crossed_up),
+            RuleEvaluation::new("macd_histogram_positive", Some(reading.histogram), Some(Decimal::ZERO), reading.histogram > Decimal::ZERO),
+        ]
+    }
+
+    /// Both conditions an exit needs, RSI first
+    fn exit_rules(config: &RsiMacdConfluenceConfig, reading: &ConfluenceReading) -> [RuleEvaluation; 2] {
+        let crossed_down = reading.previous_rsi >= config.overbought && reading.rsi < config.overbought;
+        [
+            RuleEvaluation::new("rsi_crossed_down_from_overbought", Some(reading.rsi), Some(config.overbought), crossed_down),
+            RuleEvaluation::new("macd_histogram_negative", Some(reading.histogram), Some(Decimal::ZERO), reading.histogram < Decimal::ZERO),
+        ]
+    }
+
+    fn reading_indicators(reading: &ConfluenceReading) -> Vec<IndicatorValue> {
+        vec![
+            IndicatorValue {
+                name: "RSI".to_string(),
+                value: reading.rsi,
+                signal: "momentum".to_string(),
+            },
+            IndicatorValue {
+                name: "MACD Histogram".to_string(),
+                value: reading.histogram,
+                signal: if reading.histogram > Decimal::ZERO { "bullish" } else { "bearish" }.to_string(),
+            },
+        ]
+    }
+
+    fn build_explanation(
+        &self,
+        context: &StrategyContext,
+        decision: &str,
+        reading: Option<&ConfluenceReading>,
+        rules: &[RuleEvaluation],
+    ) -> Value {
+        json!({
+            "strategy": self.metadata.id,
+            "timestamp": context.current_time,
+            "decision": decision,
+            "price": context.current_price,
+            "indicators": reading,
+            "in_position": self.state.in_position,
+            "entry_price": self.state.entry_price,
+            "rules": rules,
+            "gated_by": rules.iter().find(|r| !r.passed).map(|r| r.rule.clone()),
+        })
+    }
+}
+
+#[async_trait]
+impl Strategy for RsiMacdConfluenceStrategy {
+    fn metadata(&self) -> StrategyMetadata {
+        self.metadata.clone()
+    }
+
+    async fn initialize(
+        &mut self,
+        parameters: &Value,
+        _mode: StrategyMode,
+        _context: &StrategyContext,
+    ) -> Result<(), AppError> {
+        let config: RsiMacdConfluenceConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid RSI + MACD confluence parameters: {}", e)))?;
+
+        config.validate()
+            .map_err(AppError::BadRequest)?;
+        let lifecycle = self.lifecycle.transition(LifecycleEvent::Initialize)?;
+
+        self.config = Some(config);
+        self.state = RsiMacdConfluenceState::default();
+        self.lifecycle = lifecycle;
+        self.last_explanation = Value::Null;
+
+        info!("RSI + MACD Confluence strategy initialized successfully");
+        Ok(())
+    }
+
+    async fn analyze(
+        &mut self,
+        context: &StrategyContext,
+    ) -> Result<Option<StrategySignal>, AppError> {
+        let config = self.config.clone()
+            .ok_or_else(|| AppError::BadRequest("Strategy not initialized".to_string()))?;
+
+        if !self.lifecycle.accepts_signals() {
+            return Ok(None);
+        }
+
+        let Some(reading) = Self::read_indicators(&config, &context.historical_data) else {
+            self.last_explanation = self.build_explanation(
+                context,
+                "hold",
+                None,
+                &[RuleEvaluation::new(
+                    "min_data_points",
+                    Some(Decimal::from(context.historical_data.len())),
+                    Some(Decimal::from(config.min_data_points())),
+                    false,
+                )],
+            );
+            return Ok(None);
+        };
+
+        self.state.last_rsi = Some(reading.rsi);
+        self.state.last_histogram = Some(reading.histogram);
+
+        if !self.state.in_position {
+            let rules = Self::entry_rules(&config, &reading);
+            if !rules.iter().all(|rule| rule.passed) {
+                self.last_explanation = self.build_explanation(context, "hold", Some(&reading), &rules);
+                return Ok(None);
+            }
+
+            self.state.in_position = true;
+            self.state.entry_price = Some(context.current_price);
+            self.state.entry_time = Some(context.current_time);
+            self.state.trade_count += 1;
+            self.last_explanation = self.build_explanation(context, "buy", Some(&reading), &rules);
+
+            let signal = StrategySignal::buy(
+                context.symbol.clone(),
+                QuantityType::BalancePercentage(config.position_size_pct),
+                format!(
+                    "RSI crossed up through {} with MACD histogram at {}",
+                    config.oversold,
+                    reading.histogram.round_dp(4)
+                ),
+                None,
+            );
+            return Ok(Some(
+                signal
+                    .with_indicators(Self::reading_indicators(&reading))
+                    .with_confidence(Decimal::ONE),
+            ));
+        }
+
+        let rules = Self::exit_rules(&config, &reading);
+        if !rules.iter().all(|rule| rule.passed) {
+            self.last_explanation = self.build_explanation(context, "hold", Some(&reading), &rules);
+            return Ok(None);
+        }
+
+        self.state.in_position = false;
+        self.state.entry_price = None;
+        self.state.entry_time = None;
+        self.state.trade_count += 1;
+        self.last_explanation = self.build_explanation(context, "sell", Some(&reading), &rules);
+
+        let signal = StrategySignal::sell(
+            context.symbol.clone(),
+            QuantityType::AllPosition,
+            format!(
+                "RSI crossed down through {} with MACD histogram at {}",
+                config.overbought,
+                reading.histogram.round_dp(4)
+            ),
+            None,
+        );
+        Ok(Some(
+            signal
+                .with_indicators(Self::reading_indicators(&reading))
+                .with_confidence(Decimal::ONE),
+        ))
+    }
+
+    fn validate_parameters(&self, parameters: &Value) -> Result<(), AppError> {
+        let config: RsiMacdConfluenceConfig = serde_json::from_value(parameters.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid parameters: {}", e)))?;
+
+        config.validate()
+            .map_err(AppError::BadRequest)?;
+
+        Ok(())
+    }
+
+    fn parameter_schema(&self) -> Value {
+        RsiMacdConfluenceConfig::json_schema()
+    }
+
+    fn min_data_points(&self) -> usize {
+        self.config.as_ref().map(|config| config.min_data_points()).unwrap_or(1)
+    }
+
+    fn explain(&self) -> Value {
+        self.last_explanation.clone()
+    }
+
+    fn lifecycle(&self) -> StrategyLifecycle {
+        self.lifecycle
+    }
+
+    fn into_live(self: Box<Self>) -> Option<Box<dyn LiveExecutableStrategy>> {
+        Some(self)
+    }
+
+    fn get_state(&self) -> Result<Value, AppError> {
+        let mut state = serde_json::to_value(&self.state)
+            .map_err(|e| AppError::BadRequest(format!("Failed to serialize state: {}", e)))?;
+
+        if let Some(state_obj) = state.as_object_mut() {
+            state_obj.insert("lifecycle".to_string(), json!(self.lifecycle));
+            state_obj.insert("is_active".to_string(), Value::Bool(self.lifecycle.accepts_signals()));
+        }
+
+        Ok(state)
+    }
+
+    fn restore_state(&mut self, state: &Value) -> Result<(), AppError> {
+        self.state = serde_json::from_value(state.clone())
+            .map_err(|e| AppError::BadRequest(format!("Failed to deserialize state: {}", e)))?;
+        if let Some(lifecycle) = state.get("lifecycle") {
+            self.lifecycle = serde_json::from_value(lifecycle.clone())
+                .map_err(|e| AppError::BadRequest(format!("Failed to deserialize lifecycle: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LiveExecutableStrategy for RsiMacdConfluenceStrategy {
+    async fn start_live_execution(&mut self, _context: &StrategyContext) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Start)?;
+        info!("RSI + MACD Confluence strategy started for live execution");
+        Ok(())
+    }
+
+    async fn stop_live_execution(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Stop)?;
+        info!("RSI + MACD Confluence strategy stopped");
+        Ok(())
+    }
+
+    fn next_execution_time(&self) -> Option<DateTime<Utc>> {
+        // Analyzed on every new candle
+        None
+    }
+}
+
+#[async_trait]
+impl ControllableStrategy for RsiMacdConfluenceStrategy {
+    async fn pause(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Pause)?;
+        info!("RSI + MACD Confluence strategy paused");
+        Ok(())
+    }
+
+    async fn resume(&mut self) -> Result<(), AppError> {
+        self.lifecycle.apply(LifecycleEvent::Resume)?;
+        info!("RSI + MACD Confluence strategy resumed");
+        Ok(())
+    }
+}
+
+impl Default for RsiMacdConfluenceStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod tests {
+    use crate::exchange_connectors::Kline;
+    use crate::strategies::core::{QuantityType, Strategy, StrategyContext, StrategyContextBuilder, StrategyMode, StrategySignalType};
+    use crate::strategies::implementations::rsi_macd_confluence::{RsiMacdConfluenceConfig, RsiMacdConfluenceStrategy};
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn create_klines(closes: &[i64]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let price = Decimal::from(*close);
+                Kline {
+                    open_time: base_time + Duration::hours(i as i64),
+                    close_time: base_time + Duration::hours(i as i64) + Duration::minutes(59),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::from(1000),
+                    quote_asset_volume: Decimal::from(1000) * price,
+                    number_of_trades: 100,
+                    taker_buy_base_asset_volume: Decimal::from(500),
+                    taker_buy_quote_asset_volume: Decimal::from(500) * price,
+                }
+            })
+            .collect()
+    }
+
+    fn context_at(klines: &[Kline], index: usize) -> StrategyContext {
+        StrategyContextBuilder::new()
+            .strategy_id(Uuid::new_v4())
+            .user_id(Uuid::new_v4())
+            .symbol("BTCUSDT".to_string())
+            .interval("1h".to_string())
+            .mode(StrategyMode::Backtest)
+            .current_time(klines[index].close_time)
+            .historical_data(klines[..=index].to_vec())
+            .current_price(klines[index].close)
+            .available_balance(Decimal::from(10000))
+            .build()
+            .expect("Failed to build test context")
+    }
+
+    /// RSI 3 at 30 / `overbought`, MACD 3 / 6 / 3, so short series reach every state
+    fn fast_config(overbought: i64) -> RsiMacdConfluenceConfig {
+        RsiMacdConfluenceConfig {
+            rsi_period: 3,
+            overbought: Decimal::from(overbought),
+            macd_fast: 3,
+            macd_slow: 6,
+            macd_signal: 3,
+            ..RsiMacdConfluenceConfig::default()
+        }
+    }
+
+    async fn initialized(config: &RsiMacdConfluenceConfig, klines: &[Kline]) -> RsiMacdConfluenceStrategy {
+        let mut strategy = RsiMacdConfluenceStrategy::new();
+        let parameters = serde_json::to_value(config).unwrap();
+        strategy.initialize(&parameters, StrategyMode::Backtest, &context_at(klines, 0)).await.unwrap();
+        strategy
+    }
+
+    #[tokio::test]
+    async fn test_trades_when_rsi_and_macd_agree() {
+        // A slide into oversold and a bounce, then a rally that rolls over
+        let klines = create_klines(&[100, 100, 100, 100, 100, 100, 98, 96, 94, 92, 90, 95, 100, 105, 110, 109, 103]);
+        let mut strategy = initialized(&fast_config(70), &klines).await;
+
+        let mut signals = Vec::new();
+        for index in 0..klines.len() {
+            if let Some(signal) = strategy.analyze(&context_at(&klines, index)).await.unwrap() {
+                signals.push((index, signal));
+            }
+        }
+
+        assert_eq!(signals.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![11, 16]);
+
+        let (_, entry) = &signals[0];
+        assert_eq!(entry.signal_type, StrategySignalType::Enter);
+        assert!(matches!(entry.action.quantity, QuantityType::BalancePercentage(pct) if pct == Decimal::from(10)));
+        assert!(entry.metadata.indicators[1].value > Decimal::ZERO);
+
+        let (_, exit) = &signals[1];
+        assert_eq!(exit.signal_type, StrategySignalType::Exit);
+        assert!(matches!(exit.action.quantity, QuantityType::AllPosition));
+        assert!(exit.metadata.indicators[1].value < Decimal::ZERO);
+
+        let state = strategy.get_state().unwrap();
+        assert_eq!(state["in_position"], false);
+        assert_eq!(state["trade_count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_rsi_recovery_alone_does_not_enter() {
+        // A sharp pullback from a rally: RSI bounces out of oversold while MACD is still falling
+        let klines = create_klines(&[100, 103, 106, 109, 112, 115, 118, 121, 124, 120, 116, 112, 116]);
+        let mut strategy = initialized(&fast_config(70), &klines).await;
+
+        for index in 0..klines.len() {
+            assert!(strategy.analyze(&context_at(&klines, index)).await.unwrap().is_none());
+        }
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["rules"][0]["passed"], true);
+        assert_eq!(explanation["gated_by"], "macd_histogram_positive");
+    }
+
+    #[tokio::test]
+    async fn test_positive_macd_alone_does_not_enter() {
+        // A steady climb: MACD histogram is positive but RSI never visits oversold
+        let klines = create_klines(&[100, 100, 100, 100, 100, 100, 102, 104, 106, 108, 110, 112]);
+        let mut strategy = initialized(&fast_config(70), &klines).await;
+
+        for index in 0..klines.len() {
+            assert!(strategy.analyze(&context_at(&klines, index)).await.unwrap().is_none());
+        }
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["rules"][1]["passed"], true);
+        assert_eq!(explanation["gated_by"], "rsi_crossed_up_from_oversold");
+    }
+
+    #[tokio::test]
+    async fn test_exit_needs_both_conditions() {
+        // With overbought at 80 the RSI drops through it while MACD is still positive,
+        // and MACD only turns negative once RSI is already below it
+        let klines = create_klines(&[100, 100, 100, 100, 100, 100, 98, 96, 94, 92, 90, 95, 100, 105, 110, 107, 101]);
+        let mut strategy = initialized(&fast_config(80), &klines).await;
+
+        for index in 0..=14 {
+            strategy.analyze(&context_at(&klines, index)).await.unwrap();
+        }
+        assert_eq!(strategy.get_state().unwrap()["in_position"], true);
+
+        assert!(strategy.analyze(&context_at(&klines, 15)).await.unwrap().is_none());
+        assert_eq!(strategy.explain()["gated_by"], "macd_histogram_negative");
+
+        assert!(strategy.analyze(&context_at(&klines, 16)).await.unwrap().is_none());
+        assert_eq!(strategy.explain()["gated_by"], "rsi_crossed_down_from_overbought");
+        assert_eq!(strategy.get_state().unwrap()["in_position"], true);
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_enough_bars() {
+        let klines = create_klines(&[100, 101, 102, 103, 104]);
+        let mut strategy = initialized(&fast_config(70), &klines).await;
+
+        assert!(strategy.analyze(&context_at(&klines, 4)).await.unwrap().is_none());
+        assert_eq!(strategy.explain()["gated_by"], "min_data_points");
+        assert_eq!(strategy.min_data_points(), 8);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let strategy = RsiMacdConfluenceStrategy::new();
+        assert!(strategy.validate_parameters(&json!({})).is_ok());
+        assert!(strategy.validate_parameters(&json!({ "rsi_period": 7, "macd_fast": 8, "macd_slow": 17 })).is_ok());
+
+        assert!(strategy.validate_parameters(&json!({ "rsi_period": 1 })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "oversold": 70, "overbought": 30 })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "overbought": 100 })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "macd_fast": 26, "macd_slow": 12 })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "macd_signal": 0 })).is_err());
+        assert!(strategy.validate_parameters(&json!({ "position_size_pct": 150 })).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Persistent confluence strategy state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RsiMacdConfluenceState {
+    /// Whether the strategy has entered and not yet exited
+    pub in_position: bool,
+    /// Price the open position was entered at
+    pub entry_price: Option<Decimal>,
+    /// When the open position was entered
+    pub entry_time: Option<DateTime<Utc>>,
+    /// RSI at the last analysis
+    pub last_rsi: Option<Decimal>,
+    /// MACD histogram at the last analysis
+    pub last_histogram: Option<Decimal>,
+    /// Number of entries and exits signalled
+    pub trade_count: u32,
+}
+
+/// Indicator readings one decision is made from
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfluenceReading {
+    /// RSI of the bar before the current one
+    pub previous_rsi: Decimal,
+    pub rsi: Decimal,
+    pub macd_line: Decimal,
+    pub signal_line: Decimal,
+    pub histogram: Decimal,
+}
@@ -2,6 +2,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use rust_decimal::Decimal;
 
+use crate::strategies::core::{
+    max_order_notional_schema, min_confidence_schema, validate_max_order_notional, validate_min_confidence,
+    ExecutionOrderType,
+};
 use super::types::{RiskSettings, SignalFilters};
 
 /// Complete SMA Crossover strategy configuration
@@ -21,8 +25,22 @@ pub struct SMACrossoverConfig {
     pub enable_long: bool,
     /// Enable short positions
     pub enable_short: bool,
-    /// Use market orders instead of limit orders
-    pub use_market_orders: bool,
+    /// Consecutive bars (including the crossing bar) the fast SMA must stay on the
+    /// new side of the slow SMA before a crossover is acted on; 0 acts immediately
+    #[serde(default)]
+    pub confirmation_bars: u32,
+    /// Market or offset limit orders for entries and exits
+    #[serde(default)]
+    pub execution_order_type: ExecutionOrderType,
+    /// Signals below this confidence (0-1) are not executed
+    #[serde(default)]
+    pub min_confidence: Option<Decimal>,
+    /// Largest quote value a single live order may have
+    #[serde(default)]
+    pub max_order_notional: Option<Decimal>,
+    /// Shrink orders above `max_order_notional` to the limit instead of rejecting them
+    #[serde(default)]
+    pub clamp_oversized_orders: bool,
     /// Additional confirmation indicators
     pub confirmation_indicators: ConfirmationSettings,
 }
@@ -76,7 +94,11 @@ impl Default for SMACrossoverConfig {
             filters: SignalFilters::default(),
             enable_long: true,
             enable_short: false,
-            use_market_orders: true,
+            confirmation_bars: 0,
+            execution_order_type: ExecutionOrderType::Market,
+            min_confidence: None,
+            max_order_notional: None,
+            clamp_oversized_orders: false,
             confirmation_indicators: ConfirmationSettings::default(),
         }
     }
@@ -140,7 +162,6 @@ impl SMACrossoverConfig {
                 macd_confirmation: false,
                 ..Default::default()
             },
-            use_market_orders: true,
             ..Default::default()
         }
     }
@@ -170,7 +191,6 @@ impl SMACrossoverConfig {
                 min_volume_multiplier: Decimal::from(2), // 2x volume
                 ..Default::default()
             },
-            use_market_orders: true,
             ..Default::default()
         }
     }
@@ -226,10 +246,18 @@ impl SMACrossoverConfig {
             }
         }
 
+        if self.confirmation_bars > 50 {
+            return Err("Confirmation bars cannot exceed 50".to_string());
+        }
+
         if !self.enable_long && !self.enable_short {
             return Err("At least one of long or short positions must be enabled".to_string());
         }
 
+        self.execution_order_type.validate()?;
+        validate_min_confidence(self.min_confidence)?;
+        validate_max_order_notional(self.max_order_notional)?;
+
         Ok(())
     }
 
@@ -301,9 +329,18 @@ impl SMACrossoverConfig {
                     "type": "boolean",
                     "description": "Enable short positions"
                 },
-                "use_market_orders": {
+                "confirmation_bars": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": 50,
+                    "description": "Bars the fast SMA must stay across the slow SMA before a crossover is acted on"
+                },
+                "execution_order_type": ExecutionOrderType::json_schema(),
+                "min_confidence": min_confidence_schema(),
+                "max_order_notional": max_order_notional_schema(),
+                "clamp_oversized_orders": {
                     "type": "boolean",
-                    "description": "Use market orders instead of limit orders"
+                    "description": "Shrink oversized orders to the limit instead of rejecting them"
                 },
                 "confirmation_indicators": {
                     "type": "object",
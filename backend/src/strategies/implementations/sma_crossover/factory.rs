@@ -1,5 +1,5 @@
-use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata};
-use super::SMACrossoverStrategy;
+use crate::strategies::core::{Strategy, StrategyFactory, StrategyMetadata, StrategyPreset};
+use super::{SMACrossoverStrategy, sma_crossover_presets};
 
 /// Factory for creating SMA Crossover strategy instances
 pub struct SMACrossoverStrategyFactory {
@@ -23,6 +23,10 @@ impl StrategyFactory for SMACrossoverStrategyFactory {
     fn metadata(&self) -> &StrategyMetadata {
         &self.metadata
     }
+
+    fn presets(&self) -> Vec<StrategyPreset> {
+        sma_crossover_presets()
+    }
 }
 
 impl Default for SMACrossoverStrategyFactory {
@@ -0,0 +1,41 @@
+use crate::strategies::core::{RiskLevel, StrategyPreset};
+use super::SMACrossoverConfig;
+
+/// Presets registered with the strategy registry
+pub fn sma_crossover_presets() -> Vec<StrategyPreset> {
+    let balanced = SMACrossoverConfig {
+        confirmation_bars: 2,
+        ..SMACrossoverConfig::simple(10, 30)
+    };
+
+    vec![
+        StrategyPreset::new(
+            "conservative",
+            "Conservative",
+            "Slow 20/50 crossover with tight stops and RSI, MACD and volume confirmation",
+            RiskLevel::Conservative,
+            &SMACrossoverConfig::conservative(20, 50),
+        ),
+        StrategyPreset::new(
+            "balanced",
+            "Balanced",
+            "10/30 crossover acted on once it holds for two bars",
+            RiskLevel::Moderate,
+            &balanced,
+        ),
+        StrategyPreset::new(
+            "aggressive",
+            "Aggressive",
+            "Fast 5/15 crossover with larger positions for trending markets",
+            RiskLevel::Aggressive,
+            &SMACrossoverConfig::aggressive(5, 15),
+        ),
+        StrategyPreset::new(
+            "scalping",
+            "Scalping",
+            "Very fast 3/8 crossover with small targets on high-volume markets",
+            RiskLevel::Expert,
+            &SMACrossoverConfig::scalping(3, 8),
+        ),
+    ]
+}
@@ -1,12 +1,13 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc, Duration};
 use rust_decimal::Decimal;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::{debug, info};
 
 use crate::strategies::core::{
     Strategy, StrategyMetadata, StrategyMode, StrategyContext, StrategySignal,
     StrategyCategory, RiskLevel, LiveExecutableStrategy, ControllableStrategy, IndicatorValue, QuantityType,
+    RuleEvaluation, StrategyLifecycle, LifecycleEvent,
 };
 use crate::strategies::indicators::{self};
 use crate::utils::errors::AppError;
@@ -22,12 +23,12 @@ pub struct SMACrossoverStrategy {
     state: SMACrossoverState,
     /// Execution history
     execution_history: Vec<SMACrossoverExecution>,
-    /// Is strategy currently paused
-    is_paused: bool,
-    /// Is strategy currently running (for live execution)
-    is_running: bool,
+    /// Lifecycle state; gates analysis and live execution
+    lifecycle: StrategyLifecycle,
     /// Last signal reason
     last_signal_reason: String,
+    /// Structured explanation of the last analyze call
+    last_explanation: Value,
     /// Strategy metadata
     metadata: StrategyMetadata,
 }
@@ -39,9 +40,9 @@ impl SMACrossoverStrategy {
             config: None,
             state: SMACrossoverState::default(),
             execution_history: Vec::new(),
-            is_paused: false,
-            is_running: false,
+            lifecycle: StrategyLifecycle::Created,
             last_signal_reason: String::new(),
+            last_explanation: Value::Null,
             metadata: Self::create_metadata(),
         }
     }
@@ -105,6 +106,40 @@ impl SMACrossoverStrategy {
         signal
     }
 
+    /// Hold a detected crossover until the fast SMA has stayed on its new side for
+    /// `confirmation_bars` consecutive bars; a crossover that reverts is dropped
+    fn confirm_crossover(&mut self, signal: CrossoverSignal, fast_sma: Decimal, slow_sma: Decimal, confirmation_bars: u32) -> CrossoverSignal {
+        if confirmation_bars == 0 {
+            return signal;
+        }
+
+        // The crossing check spans two bars, so the bar after a cross can report the same
+        // crossover again; that counts towards confirmation rather than restarting it
+        if signal != CrossoverSignal::None && self.state.pending_crossover.as_ref() != Some(&signal) {
+            self.state.pending_crossover = Some(signal);
+            self.state.pending_crossover_bars = 1;
+        } else {
+            let holds = match self.state.pending_crossover {
+                Some(CrossoverSignal::BullishCrossover) => fast_sma > slow_sma,
+                Some(CrossoverSignal::BearishCrossover) => fast_sma < slow_sma,
+                _ => false,
+            };
+            if holds {
+                self.state.pending_crossover_bars += 1;
+            } else {
+                self.state.pending_crossover = None;
+                self.state.pending_crossover_bars = 0;
+            }
+        }
+
+        if self.state.pending_crossover_bars >= confirmation_bars {
+            self.state.pending_crossover_bars = 0;
+            return self.state.pending_crossover.take().unwrap_or(CrossoverSignal::None);
+        }
+
+        CrossoverSignal::None
+    }
+
     /// Analyze market conditions and generate crossover analysis
     fn analyze_crossover(&self, context: &StrategyContext, fast_sma: Decimal, slow_sma: Decimal, signal: CrossoverSignal) -> Result<CrossoverAnalysis, AppError> {
         let config = self.config.as_ref().unwrap();
@@ -167,46 +202,50 @@ impl SMACrossoverStrategy {
         })
     }
 
-    /// Check if signal passes all filters
-    fn passes_filters(&self, analysis: &CrossoverAnalysis, context: &StrategyContext) -> bool {
+    /// Evaluate every configured filter against the analysis; the signal passes only if all do
+    fn evaluate_filters(&self, analysis: &CrossoverAnalysis, context: &StrategyContext) -> Vec<RuleEvaluation> {
         let config = self.config.as_ref().unwrap();
         let filters = &config.filters;
+        let mut rules = Vec::new();
 
         // Check minimum SMA spread
         if let Some(min_spread_pct) = filters.min_sma_spread_pct {
             let spread_pct = (analysis.sma_spread.abs() / analysis.slow_sma) * Decimal::from(100);
-            if spread_pct < min_spread_pct {
+            let passed = spread_pct >= min_spread_pct;
+            if !passed {
                 debug!("Signal filtered: SMA spread too small ({:.3}% < {:.3}%)", spread_pct, min_spread_pct);
-                return false;
             }
+            rules.push(RuleEvaluation::new("min_sma_spread_pct", Some(spread_pct), Some(min_spread_pct), passed));
         }
 
         // Check minimum volume
         if let Some(min_volume) = filters.min_volume {
-            if let Some(current_volume) = analysis.market_conditions.volume {
-                if current_volume < min_volume {
-                    debug!("Signal filtered: Volume too low");
-                    return false;
-                }
+            let current_volume = analysis.market_conditions.volume;
+            let passed = current_volume.map_or(true, |volume| volume >= min_volume);
+            if !passed {
+                debug!("Signal filtered: Volume too low");
             }
+            rules.push(RuleEvaluation::new("min_volume", current_volume, Some(min_volume), passed));
         }
 
         // Check RSI filters
         match analysis.signal {
             CrossoverSignal::BullishCrossover => {
                 if let (Some(rsi), Some(overbought)) = (analysis.market_conditions.rsi, filters.rsi_overbought) {
-                    if rsi > overbought {
+                    let passed = rsi <= overbought;
+                    if !passed {
                         debug!("Bullish signal filtered: RSI overbought ({:.1} > {:.1})", rsi, overbought);
-                        return false;
                     }
+                    rules.push(RuleEvaluation::new("rsi_overbought", Some(rsi), Some(overbought), passed));
                 }
             }
             CrossoverSignal::BearishCrossover => {
                 if let (Some(rsi), Some(oversold)) = (analysis.market_conditions.rsi, filters.rsi_oversold) {
-                    if rsi < oversold {
+                    let passed = rsi >= oversold;
+                    if !passed {
                         debug!("Bearish signal filtered: RSI oversold ({:.1} < {:.1})", rsi, oversold);
-                        return false;
                     }
+                    rules.push(RuleEvaluation::new("rsi_oversold", Some(rsi), Some(oversold), passed));
                 }
             }
             CrossoverSignal::None => {}
@@ -215,21 +254,15 @@ impl SMACrossoverStrategy {
         // Check MACD confirmation
         if filters.macd_confirmation {
             if let Some(macd_histogram) = analysis.market_conditions.macd_histogram {
-                match analysis.signal {
-                    CrossoverSignal::BullishCrossover => {
-                        if macd_histogram <= Decimal::ZERO {
-                            debug!("Bullish signal filtered: MACD not confirming");
-                            return false;
-                        }
-                    }
-                    CrossoverSignal::BearishCrossover => {
-                        if macd_histogram >= Decimal::ZERO {
-                            debug!("Bearish signal filtered: MACD not confirming");
-                            return false;
-                        }
-                    }
-                    CrossoverSignal::None => {}
+                let passed = match analysis.signal {
+                    CrossoverSignal::BullishCrossover => macd_histogram > Decimal::ZERO,
+                    CrossoverSignal::BearishCrossover => macd_histogram < Decimal::ZERO,
+                    CrossoverSignal::None => true,
+                };
+                if !passed {
+                    debug!("{:?} signal filtered: MACD not confirming", analysis.signal);
                 }
+                rules.push(RuleEvaluation::new("macd_confirmation", Some(macd_histogram), Some(Decimal::ZERO), passed));
             }
         }
 
@@ -237,15 +270,57 @@ impl SMACrossoverStrategy {
         if let Some(last_signal_time) = self.state.last_signal_time {
             let time_diff = context.current_time.signed_duration_since(last_signal_time);
             let min_interval = Duration::minutes(config.risk_settings.min_signal_interval as i64);
+            let passed = time_diff >= min_interval;
 
-            if time_diff < min_interval {
+            if !passed {
                 debug!("Signal filtered: Too soon since last signal ({} < {} minutes)",
                        time_diff.num_minutes(), min_interval.num_minutes());
-                return false;
             }
+            rules.push(RuleEvaluation::new(
+                "min_signal_interval_minutes",
+                Some(Decimal::from(time_diff.num_minutes())),
+                Some(Decimal::from(min_interval.num_minutes())),
+                passed,
+            ));
         }
 
-        true
+        rules
+    }
+
+    /// Build the structured explanation returned by `explain`
+    fn build_explanation(
+        &self,
+        context: &StrategyContext,
+        decision: &str,
+        analysis: Option<&CrossoverAnalysis>,
+        rules: &[RuleEvaluation],
+    ) -> Value {
+        let indicators = match analysis {
+            Some(analysis) => json!({
+                "fast_sma": analysis.fast_sma,
+                "slow_sma": analysis.slow_sma,
+                "sma_spread": analysis.sma_spread,
+                "rsi": analysis.market_conditions.rsi,
+                "macd_histogram": analysis.market_conditions.macd_histogram,
+                "strength": format!("{:?}", analysis.strength),
+                "confidence": analysis.confidence,
+            }),
+            None => json!({
+                "fast_sma": self.state.last_fast_sma,
+                "slow_sma": self.state.last_slow_sma,
+            }),
+        };
+
+        json!({
+            "strategy": self.metadata.id,
+            "timestamp": context.current_time,
+            "price": context.current_price,
+            "decision": decision,
+            "crossover": analysis.map(|a| format!("{:?}", a.signal)),
+            "indicators": indicators,
+            "rules": rules,
+            "gated_by": rules.iter().find(|rule| !rule.passed).map(|rule| rule.rule.clone()),
+        })
     }
 
     /// Calculate position size based on configuration and risk management
@@ -434,12 +509,14 @@ impl Strategy for SMACrossoverStrategy {
         // Validate configuration
         config.validate()
             .map_err(|e| AppError::BadRequest(e))?;
+        let lifecycle = self.lifecycle.transition(LifecycleEvent::Initialize)?;
 
         self.config = Some(config);
         self.state = SMACrossoverState::default();
         self.execution_history.clear();
-        self.is_paused = false;
+        self.lifecycle = lifecycle;
         self.last_signal_reason = "Strategy initialized".to_string();
+        self.last_explanation = Value::Null;
 
         info!("SMA Crossover strategy initialized successfully");
         Ok(())
@@ -452,13 +529,19 @@ impl Strategy for SMACrossoverStrategy {
         let config = self.config.as_ref()
             .ok_or_else(|| AppError::BadRequest("Strategy not initialized".to_string()))?;
 
-        if self.is_paused {
+        if !self.lifecycle.accepts_signals() {
             return Ok(None);
         }
 
         // Check risk management first
         if let Some(risk_side) = self.check_risk_management(context) {
             self.last_signal_reason = "Risk management trigger".to_string();
+            self.last_explanation = self.build_explanation(
+                context,
+                "risk_exit",
+                None,
+                &[RuleEvaluation::new("risk_management_exit", Some(context.current_price), self.state.entry_price, true)],
+            );
 
             let signal = match risk_side {
                 TradeSide::Buy => StrategySignal::buy(
@@ -499,9 +582,21 @@ impl Strategy for SMACrossoverStrategy {
         let enable_short = config.enable_short;
         let fast_period = config.fast_period;
         let slow_period = config.slow_period;
+        let confirmation_bars = config.confirmation_bars;
 
         // Check if we have enough data for SMA calculation
         if context.historical_data.len() < slow_period {
+            self.last_explanation = self.build_explanation(
+                context,
+                "hold",
+                None,
+                &[RuleEvaluation::new(
+                    "min_data_points",
+                    Some(Decimal::from(context.historical_data.len())),
+                    Some(Decimal::from(slow_period)),
+                    false,
+                )],
+            );
             return Ok(None);
         }
 
@@ -514,8 +609,30 @@ impl Strategy for SMACrossoverStrategy {
 
         // Detect crossover
         let crossover_signal = self.detect_crossover(fast_sma, slow_sma);
+        let crossover_signal = self.confirm_crossover(crossover_signal, fast_sma, slow_sma, confirmation_bars);
+
+        if crossover_signal == CrossoverSignal::None && self.state.pending_crossover.is_some() {
+            self.last_explanation = self.build_explanation(
+                context,
+                "hold",
+                None,
+                &[RuleEvaluation::new(
+                    "confirmation_bars",
+                    Some(Decimal::from(self.state.pending_crossover_bars)),
+                    Some(Decimal::from(confirmation_bars)),
+                    false,
+                )],
+            );
+            return Ok(None);
+        }
 
         if crossover_signal == CrossoverSignal::None {
+            self.last_explanation = self.build_explanation(
+                context,
+                "hold",
+                None,
+                &[RuleEvaluation::new("crossover_detected", Some(fast_sma - slow_sma), Some(Decimal::ZERO), false)],
+            );
             return Ok(None);
         }
 
@@ -523,7 +640,11 @@ impl Strategy for SMACrossoverStrategy {
         let analysis = self.analyze_crossover(context, fast_sma, slow_sma, crossover_signal)?;
 
         // Apply filters
-        if !self.passes_filters(&analysis, context) {
+        let mut rules = vec![RuleEvaluation::new("crossover_detected", Some(analysis.sma_spread), Some(Decimal::ZERO), true)];
+        rules.extend(self.evaluate_filters(&analysis, context));
+
+        if rules.iter().any(|rule| !rule.passed) {
+            self.last_explanation = self.build_explanation(context, "hold", Some(&analysis), &rules);
             return Ok(None);
         }
 
@@ -556,6 +677,19 @@ impl Strategy for SMACrossoverStrategy {
             CrossoverSignal::None => (None, None),
         };
 
+        rules.push(RuleEvaluation::new(
+            "position_allows_trade",
+            Some(Decimal::from(self.state.position)),
+            None,
+            side.is_some(),
+        ));
+        let decision = match side {
+            Some(TradeSide::Buy) => "buy",
+            Some(TradeSide::Sell) => "sell",
+            None => "hold",
+        };
+        self.last_explanation = self.build_explanation(context, decision, Some(&analysis), &rules);
+
         if let (Some(_signal_type), Some(trade_side)) = (signal_type, side) {
             let quantity = self.calculate_position_size(context, &analysis);
 
@@ -620,6 +754,18 @@ impl Strategy for SMACrossoverStrategy {
         SMACrossoverConfig::json_schema()
     }
 
+    fn explain(&self) -> Value {
+        self.last_explanation.clone()
+    }
+
+    fn lifecycle(&self) -> StrategyLifecycle {
+        self.lifecycle
+    }
+
+    fn into_live(self: Box<Self>) -> Option<Box<dyn LiveExecutableStrategy>> {
+        Some(self)
+    }
+
     fn get_state(&self) -> Result<Value, AppError> {
         let mut state_with_metadata = serde_json::to_value(&self.state)
             .map_err(|e| AppError::BadRequest(format!("Failed to serialize state: {}", e)))?;
@@ -658,21 +804,17 @@ impl Strategy for SMACrossoverStrategy {
 #[async_trait]
 impl LiveExecutableStrategy for SMACrossoverStrategy {
     async fn start_live_execution(&mut self, _context: &StrategyContext) -> Result<(), AppError> {
-        self.is_running = true;
+        self.lifecycle.apply(LifecycleEvent::Start)?;
         info!("SMA Crossover strategy started for live execution");
         Ok(())
     }
 
     async fn stop_live_execution(&mut self) -> Result<(), AppError> {
-        self.is_running = false;
+        self.lifecycle.apply(LifecycleEvent::Stop)?;
         info!("SMA Crossover strategy stopped");
         Ok(())
     }
 
-    fn is_running(&self) -> bool {
-        self.is_running
-    }
-
     fn next_execution_time(&self) -> Option<DateTime<Utc>> {
         // SMA crossover is event-driven, no scheduled executions
         None
@@ -682,20 +824,16 @@ impl LiveExecutableStrategy for SMACrossoverStrategy {
 #[async_trait]
 impl ControllableStrategy for SMACrossoverStrategy {
     async fn pause(&mut self) -> Result<(), AppError> {
-        self.is_paused = true;
+        self.lifecycle.apply(LifecycleEvent::Pause)?;
         info!("SMA Crossover strategy paused");
         Ok(())
     }
 
     async fn resume(&mut self) -> Result<(), AppError> {
-        self.is_paused = false;
+        self.lifecycle.apply(LifecycleEvent::Resume)?;
         info!("SMA Crossover strategy resumed");
         Ok(())
     }
-
-    fn is_paused(&self) -> bool {
-        self.is_paused
-    }
 }
 
 impl Default for SMACrossoverStrategy {
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::strategies::core::{StrategyContext, StrategyContextBuilder, StrategyMode, Strategy};
+    use crate::strategies::implementations::sma_crossover::{SMACrossoverStrategy, SMACrossoverConfig};
+    use crate::exchange_connectors::Kline;
+    use chrono::{Utc, Duration, TimeZone};
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    /// Helper function to create klines from a list of closing prices
+    fn create_klines(closes: &[i64]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let price = Decimal::from(*close);
+                Kline {
+                    open_time: base_time + Duration::hours(i as i64),
+                    close_time: base_time + Duration::hours(i as i64) + Duration::minutes(59),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::from(1000),
+                    quote_asset_volume: Decimal::from(1000) * price,
+                    number_of_trades: 100,
+                    taker_buy_base_asset_volume: Decimal::from(500),
+                    taker_buy_quote_asset_volume: Decimal::from(500) * price,
+                }
+            })
+            .collect()
+    }
+
+    /// Helper function to create a context for the bar at `index`
+    fn context_at(klines: &[Kline], index: usize) -> StrategyContext {
+        StrategyContextBuilder::new()
+            .strategy_id(Uuid::new_v4())
+            .user_id(Uuid::new_v4())
+            .symbol("BTCUSDT".to_string())
+            .interval("1h".to_string())
+            .mode(StrategyMode::Backtest)
+            .current_time(klines[index].close_time)
+            .historical_data(klines[..=index].to_vec())
+            .current_price(klines[index].close)
+            .available_balance(Decimal::from(10000))
+            .build()
+            .expect("Failed to build test context")
+    }
+
+    /// Runs the strategy bar by bar up to and including `last_index`
+    async fn run_until(strategy: &mut SMACrossoverStrategy, klines: &[Kline], last_index: usize) {
+        let config = serde_json::to_value(SMACrossoverConfig::aggressive(2, 3)).unwrap();
+        strategy
+            .initialize(&config, StrategyMode::Backtest, &context_at(klines, 0))
+            .await
+            .unwrap();
+
+        for index in 0..=last_index {
+            strategy.analyze(&context_at(klines, index)).await.unwrap();
+        }
+    }
+
+    /// Runs the strategy over every bar with `config` and returns the bars that emitted a signal
+    async fn signal_bars(config: SMACrossoverConfig, klines: &[Kline]) -> Vec<usize> {
+        let mut strategy = SMACrossoverStrategy::new();
+        strategy
+            .initialize(&serde_json::to_value(config).unwrap(), StrategyMode::Backtest, &context_at(klines, 0))
+            .await
+            .unwrap();
+
+        let mut bars = Vec::new();
+        for index in 0..klines.len() {
+            if strategy.analyze(&context_at(klines, index)).await.unwrap().is_some() {
+                bars.push(index);
+            }
+        }
+        bars
+    }
+
+    #[tokio::test]
+    async fn test_one_bar_crossover_blip_is_ignored_with_confirmation() {
+        // Fast SMA(2) pokes above slow SMA(3) at index 5 and drops back below at index 6
+        let klines = create_klines(&[100, 99, 98, 97, 96, 100, 90]);
+
+        let unconfirmed = SMACrossoverConfig::aggressive(2, 3);
+        assert_eq!(signal_bars(unconfirmed, &klines).await.first(), Some(&5));
+
+        let confirmed = SMACrossoverConfig { confirmation_bars: 2, ..SMACrossoverConfig::aggressive(2, 3) };
+        assert!(signal_bars(confirmed, &klines).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sustained_crossover_fires_after_confirmation_bars() {
+        let klines = create_klines(&[100, 99, 98, 97, 96, 100, 106, 110]);
+
+        let confirmed = SMACrossoverConfig { confirmation_bars: 2, ..SMACrossoverConfig::aggressive(2, 3) };
+        assert_eq!(signal_bars(confirmed, &klines).await, vec![6]);
+
+        let confirmed = SMACrossoverConfig { confirmation_bars: 3, ..SMACrossoverConfig::aggressive(2, 3) };
+        assert_eq!(signal_bars(confirmed, &klines).await, vec![7]);
+    }
+
+    #[tokio::test]
+    async fn test_explain_is_null_before_analysis() {
+        let strategy = SMACrossoverStrategy::new();
+        assert!(strategy.explain().is_null());
+    }
+
+    #[tokio::test]
+    async fn test_explain_lists_conditions_for_crossover_bar() {
+        // Downtrend followed by a bounce: fast SMA(2) crosses above slow SMA(3) at index 5
+        let klines = create_klines(&[100, 99, 98, 97, 96, 100, 106]);
+        let mut strategy = SMACrossoverStrategy::new();
+        run_until(&mut strategy, &klines, 5).await;
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["decision"], "buy");
+        assert_eq!(explanation["crossover"], "BullishCrossover");
+        assert!(explanation["gated_by"].is_null());
+
+        let rules: Vec<&str> = explanation["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["rule"].as_str().unwrap())
+            .collect();
+        assert_eq!(rules, vec!["crossover_detected", "min_sma_spread_pct", "position_allows_trade"]);
+        assert!(explanation["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|rule| rule["passed"] == true));
+
+        let fast_sma: Decimal = serde_json::from_value(explanation["indicators"]["fast_sma"].clone()).unwrap();
+        assert_eq!(fast_sma, Decimal::from(98));
+    }
+
+    #[tokio::test]
+    async fn test_explain_reports_gating_rule_without_crossover() {
+        let klines = create_klines(&[100, 99, 98, 97, 96, 100, 106]);
+        let mut strategy = SMACrossoverStrategy::new();
+        run_until(&mut strategy, &klines, 4).await;
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["decision"], "hold");
+        assert_eq!(explanation["gated_by"], "crossover_detected");
+    }
+
+    #[tokio::test]
+    async fn test_explain_reports_insufficient_data() {
+        let klines = create_klines(&[100, 99, 98]);
+        let mut strategy = SMACrossoverStrategy::new();
+        run_until(&mut strategy, &klines, 1).await;
+
+        let explanation = strategy.explain();
+        assert_eq!(explanation["gated_by"], "min_data_points");
+    }
+}
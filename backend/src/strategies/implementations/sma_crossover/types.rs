@@ -38,6 +38,12 @@ pub struct SMACrossoverState {
     pub last_signal: Option<CrossoverSignal>,
     /// Last signal timestamp
     pub last_signal_time: Option<DateTime<Utc>>,
+    /// Crossover waiting for confirmation bars
+    #[serde(default)]
+    pub pending_crossover: Option<CrossoverSignal>,
+    /// Consecutive bars the pending crossover has held
+    #[serde(default)]
+    pub pending_crossover_bars: u32,
 }
 
 impl Default for SMACrossoverState {
@@ -54,6 +60,8 @@ impl Default for SMACrossoverState {
             winning_trades: 0,
             last_signal: None,
             last_signal_time: None,
+            pending_crossover: None,
+            pending_crossover_bars: 0,
         }
     }
 }
@@ -2,15 +2,20 @@
 pub mod core;
 pub mod moving_averages;
 pub mod momentum;
+pub mod volume;
 pub mod service;
 pub mod integration;
 pub mod examples;
 
 // Re-export main components for easy access
+pub use crate::backtesting::heikin_ashi::to_heikin_ashi;
 
 // Legacy functions for backward compatibility
 use rust_decimal::{Decimal, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use crate::exchange_connectors::Kline;
+use crate::utils::precision::decimal_sqrt;
 
 /// Simple Moving Average
 pub fn sma(data: &[Kline], period: usize) -> Option<Decimal> {
@@ -28,33 +33,127 @@ pub fn sma(data: &[Kline], period: usize) -> Option<Decimal> {
     Some(sum / Decimal::from(period))
 }
 
-/// Exponential Moving Average
+/// Exponential Moving Average, seeded with the SMA of the first `period` closes.
+/// `None` until `period` bars exist, rather than averaging a shorter window.
 pub fn ema(data: &[Kline], period: usize) -> Option<Decimal> {
-    if data.is_empty() {
+    let closes: Vec<Decimal> = data.iter().map(|k| k.close).collect();
+    ema_of_values(&closes, period).pop()
+}
+
+/// EMA at every bar, aligned with `data`: `None` for the `period - 1` warm-up bars and
+/// `Some` from the `period`th bar onwards. The last element equals `ema(data, period)`.
+pub fn ema_series(data: &[Kline], period: usize) -> Vec<Option<Decimal>> {
+    let closes: Vec<Decimal> = data.iter().map(|k| k.close).collect();
+    let values = ema_of_values(&closes, period);
+
+    std::iter::repeat(None)
+        .take(data.len() - values.len())
+        .chain(values.into_iter().map(Some))
+        .collect()
+}
+
+fn ema_of_values(values: &[Decimal], period: usize) -> Vec<Decimal> {
+    if period == 0 || values.len() < period {
+        return Vec::new();
+    }
+
+    let multiplier = Decimal::from(2) / Decimal::from(period + 1);
+    let mut ema_value = values[..period].iter().sum::<Decimal>() / Decimal::from(period);
+
+    let mut series = Vec::with_capacity(values.len() - period + 1);
+    series.push(ema_value);
+    for value in &values[period..] {
+        ema_value = (*value * multiplier) + (ema_value * (Decimal::ONE - multiplier));
+        series.push(ema_value);
+    }
+
+    series
+}
+
+/// Moving average a multi-average indicator is built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaType {
+    #[default]
+    Sma,
+    Ema,
+}
+
+/// One moving average of a ribbon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RibbonAverage {
+    pub period: usize,
+    pub value: Decimal,
+}
+
+/// Stacked moving averages and how orderly the stack is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaRibbon {
+    /// Shortest period first
+    pub averages: Vec<RibbonAverage>,
+    /// Share of average pairs with the shorter average above the longer one, minus the
+    /// share with it below: 1 is a fully bullish stack, -1 fully bearish, near 0 tangled
+    pub alignment: Decimal,
+}
+
+/// Moving average ribbon over `periods` (at least two distinct, non-zero). Needs as
+/// many bars as the longest period.
+pub fn ma_ribbon(data: &[Kline], periods: Vec<usize>, ma_type: MaType) -> Option<MaRibbon> {
+    let mut periods = periods;
+    periods.sort_unstable();
+    periods.dedup();
+    if periods.len() < 2 || periods[0] == 0 || data.len() < *periods.last()? {
         return None;
     }
 
-    if data.len() < period {
-        // Use SMA for the first calculation
-        return sma(data, data.len());
+    let averages = periods
+        .into_iter()
+        .map(|period| {
+            let value = match ma_type {
+                MaType::Sma => sma(data, period),
+                MaType::Ema => ema(data, period),
+            }?;
+            Some(RibbonAverage { period, value })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut score = 0i64;
+    let mut pairs = 0i64;
+    for (i, shorter) in averages.iter().enumerate() {
+        for longer in &averages[i + 1..] {
+            pairs += 1;
+            match shorter.value.cmp(&longer.value) {
+                Ordering::Greater => score += 1,
+                Ordering::Less => score -= 1,
+                Ordering::Equal => {}
+            }
+        }
     }
 
-    let multiplier = Decimal::from(2) / Decimal::from(period + 1);
+    Some(MaRibbon {
+        averages,
+        alignment: Decimal::from(score) / Decimal::from(pairs),
+    })
+}
 
-    // Start with SMA of the first 'period' values
-    let initial_sma = data[..period]
-        .iter()
-        .map(|k| k.close)
-        .sum::<Decimal>() / Decimal::from(period);
+/// TRIX: one-bar percentage rate of change of a triple-smoothed EMA.
+/// Needs `3 * period - 1` bars.
+pub fn trix(data: &[Kline], period: usize) -> Option<Decimal> {
+    let single: Vec<Decimal> = ema_series(data, period).into_iter().flatten().collect();
+    let double = ema_of_values(&single, period);
+    let triple = ema_of_values(&double, period);
 
-    let mut ema_value = initial_sma;
+    if triple.len() < 2 {
+        return None;
+    }
 
-    // Calculate EMA for the rest of the values
-    for kline in data.iter().skip(period) {
-        ema_value = (kline.close * multiplier) + (ema_value * (Decimal::ONE - multiplier));
+    let current = triple[triple.len() - 1];
+    let previous = triple[triple.len() - 2];
+    if previous.is_zero() {
+        return None;
     }
 
-    Some(ema_value)
+    Some((current - previous) / previous * Decimal::from(100))
 }
 
 /// Relative Strength Index
@@ -149,6 +248,33 @@ pub fn bollinger_bands(data: &[Kline], period: usize, std_dev_multiplier: Decima
     })
 }
 
+/// Population standard deviation of the last `period` closes
+pub fn std_dev(data: &[Kline], period: usize) -> Option<Decimal> {
+    if period == 0 {
+        return None;
+    }
+    let mean = sma(data, period)?;
+
+    let variance = data
+        .iter()
+        .rev()
+        .take(period)
+        .map(|k| (k.close - mean) * (k.close - mean))
+        .sum::<Decimal>() / Decimal::from(period);
+
+    decimal_sqrt(variance)
+}
+
+/// How many standard deviations the last close sits from the `period`-bar SMA;
+/// `None` when the closes don't vary
+pub fn zscore(data: &[Kline], period: usize) -> Option<Decimal> {
+    let std_dev = std_dev(data, period).filter(|std_dev| !std_dev.is_zero())?;
+    let mean = sma(data, period)?;
+    let last_close = data.last()?.close;
+
+    Some((last_close - mean) / std_dev)
+}
+
 /// MACD (Moving Average Convergence Divergence)
 #[derive(Debug, Clone)]
 pub struct MACD {
@@ -157,23 +283,46 @@ pub struct MACD {
     pub histogram: Decimal,
 }
 
-pub fn macd(data: &[Kline], fast_period: usize, slow_period: usize, signal_period: usize) -> Option<MACD> {
-    let fast_ema = ema(data, fast_period)?;
-    let slow_ema = ema(data, slow_period)?;
+/// MACD of every bar from the one where the signal line is first defined
+/// (`slow_period + signal_period - 1` bars in). The MACD line is the fast EMA less the
+/// slow EMA, and the signal line is an EMA of `signal_period` over the MACD line. The
+/// last element equals `macd(data, fast_period, slow_period, signal_period)`.
+pub fn macd_series(data: &[Kline], fast_period: usize, slow_period: usize, signal_period: usize) -> Option<Vec<MACD>> {
+    if fast_period == 0 || fast_period > slow_period {
+        return None;
+    }
 
-    let macd_line = fast_ema - slow_ema;
+    let fast_ema = ema_series(data, fast_period);
+    let slow_ema = ema_series(data, slow_period);
 
-    // For signal line, we would need historical MACD values
-    // This is a simplified implementation
-    let signal_line = macd_line; // In practice, this should be EMA of MACD line
+    // Only bars where both EMAs have warmed up, i.e. from the slow one's first value
+    let macd_lines: Vec<Decimal> = fast_ema
+        .iter()
+        .zip(&slow_ema)
+        .filter_map(|(fast, slow)| Some((*fast)? - (*slow)?))
+        .collect();
 
-    let histogram = macd_line - signal_line;
+    // Likewise the signal line waits for `signal_period` MACD values
+    let signal_lines = ema_of_values(&macd_lines, signal_period);
+    if signal_lines.is_empty() {
+        return None;
+    }
 
-    Some(MACD {
-        macd_line,
-        signal_line,
-        histogram,
-    })
+    let series = macd_lines[signal_period - 1..]
+        .iter()
+        .zip(signal_lines)
+        .map(|(macd_line, signal_line)| MACD {
+            macd_line: *macd_line,
+            signal_line,
+            histogram: *macd_line - signal_line,
+        })
+        .collect();
+
+    Some(series)
+}
+
+pub fn macd(data: &[Kline], fast_period: usize, slow_period: usize, signal_period: usize) -> Option<MACD> {
+    macd_series(data, fast_period, slow_period, signal_period)?.pop()
 }
 
 /// Stochastic Oscillator
@@ -183,31 +332,43 @@ pub struct Stochastic {
     pub d_percent: Decimal,
 }
 
-pub fn stochastic(data: &[Kline], k_period: usize, d_period: usize) -> Option<Stochastic> {
-    if data.len() < k_period {
+/// Stochastic of every bar from the one where %D is first defined
+/// (`k_period + d_period - 1` bars in). %K places each close within the high-low range
+/// of its last `k_period` bars, and %D is the average of the last `d_period` %K values.
+/// The last element equals `stochastic(data, k_period, d_period)`.
+pub fn stochastic_series(data: &[Kline], k_period: usize, d_period: usize) -> Option<Vec<Stochastic>> {
+    if k_period == 0 || d_period == 0 || data.len() < k_period + d_period - 1 {
         return None;
     }
 
-    let recent_klines: Vec<&Kline> = data.iter().rev().take(k_period).collect();
-
-    let highest_high = recent_klines.iter().map(|k| k.high).max()?;
-    let lowest_low = recent_klines.iter().map(|k| k.low).min()?;
-    let current_close = data.last()?.close;
+    let k_percents: Vec<Decimal> = data
+        .windows(k_period)
+        .map(|window| {
+            let highest_high = window.iter().map(|k| k.high).max().unwrap_or_default();
+            let lowest_low = window.iter().map(|k| k.low).min().unwrap_or_default();
+            let current_close = window[window.len() - 1].close;
+
+            if highest_high != lowest_low {
+                ((current_close - lowest_low) / (highest_high - lowest_low)) * Decimal::from(100)
+            } else {
+                Decimal::from(50) // Default when high == low
+            }
+        })
+        .collect();
 
-    let k_percent = if highest_high != lowest_low {
-        ((current_close - lowest_low) / (highest_high - lowest_low)) * Decimal::from(100)
-    } else {
-        Decimal::from(50) // Default when high == low
-    };
+    let series = k_percents
+        .windows(d_period)
+        .map(|window| Stochastic {
+            k_percent: window[window.len() - 1],
+            d_percent: window.iter().sum::<Decimal>() / Decimal::from(d_period),
+        })
+        .collect();
 
-    // For D%, we would need historical %K values
-    // This is a simplified implementation
-    let d_percent = k_percent; // In practice, this should be SMA of %K
+    Some(series)
+}
 
-    Some(Stochastic {
-        k_percent,
-        d_percent,
-    })
+pub fn stochastic(data: &[Kline], k_period: usize, d_period: usize) -> Option<Stochastic> {
+    stochastic_series(data, k_period, d_period)?.pop()
 }
 
 /// Average True Range
@@ -244,26 +405,128 @@ pub fn atr(data: &[Kline], period: usize) -> Option<Decimal> {
     Some(atr)
 }
 
-/// Volume Weighted Average Price
-pub fn vwap(data: &[Kline]) -> Option<Decimal> {
-    if data.is_empty() {
+/// Directional movement index: trend direction from +DI and -DI, trend strength from ADX
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectionalMovement {
+    /// Share of the true range made up of upward moves, 0-100
+    pub plus_di: Decimal,
+    /// Share of the true range made up of downward moves, 0-100
+    pub minus_di: Decimal,
+    /// Average spread between the two, 0-100; above 25 is usually read as a trend
+    pub adx: Decimal,
+}
+
+/// Directional movement over `period` bars with Wilder's smoothing. Needs `2 * period`
+/// bars: `period` moves to seed the smoothed ranges, then `period` DX values to seed ADX.
+pub fn directional_movement(data: &[Kline], period: usize) -> Option<DirectionalMovement> {
+    if period == 0 || data.len() < 2 * period {
         return None;
     }
 
-    let mut total_volume = Decimal::ZERO;
-    let mut total_price_volume = Decimal::ZERO;
+    // (+DM, -DM, true range) of every move
+    let moves: Vec<(Decimal, Decimal, Decimal)> = data
+        .windows(2)
+        .map(|window| {
+            let (prev, current) = (&window[0], &window[1]);
+            let up = current.high - prev.high;
+            let down = prev.low - current.low;
+            let plus_dm = if up > down && up > Decimal::ZERO { up } else { Decimal::ZERO };
+            let minus_dm = if down > up && down > Decimal::ZERO { down } else { Decimal::ZERO };
+            let true_range = (current.high - current.low)
+                .max((current.high - prev.close).abs())
+                .max((current.low - prev.close).abs());
+            (plus_dm, minus_dm, true_range)
+        })
+        .collect();
+
+    let period_dec = Decimal::from(period);
+    let (mut plus_dm, mut minus_dm, mut true_range) = moves[..period]
+        .iter()
+        .fold((Decimal::ZERO, Decimal::ZERO, Decimal::ZERO), |(p, m, t), (plus, minus, tr)| {
+            (p + plus, m + minus, t + tr)
+        });
+
+    let directional_indexes = |plus_dm: Decimal, minus_dm: Decimal, true_range: Decimal| {
+        if true_range.is_zero() {
+            return (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+        }
+        let plus_di = plus_dm / true_range * Decimal::from(100);
+        let minus_di = minus_dm / true_range * Decimal::from(100);
+        let di_sum = plus_di + minus_di;
+        let dx = if di_sum.is_zero() {
+            Decimal::ZERO
+        } else {
+            (plus_di - minus_di).abs() / di_sum * Decimal::from(100)
+        };
+        (plus_di, minus_di, dx)
+    };
 
-    for kline in data {
-        let typical_price = (kline.high + kline.low + kline.close) / Decimal::from(3);
-        total_price_volume += typical_price * kline.volume;
-        total_volume += kline.volume;
+    let (mut plus_di, mut minus_di, dx) = directional_indexes(plus_dm, minus_dm, true_range);
+    let mut dx_values = vec![dx];
+    let mut adx = (period == 1).then_some(dx);
+
+    for (plus, minus, tr) in &moves[period..] {
+        plus_dm = plus_dm - plus_dm / period_dec + plus;
+        minus_dm = minus_dm - minus_dm / period_dec + minus;
+        true_range = true_range - true_range / period_dec + tr;
+
+        let (current_plus_di, current_minus_di, dx) = directional_indexes(plus_dm, minus_dm, true_range);
+        plus_di = current_plus_di;
+        minus_di = current_minus_di;
+        adx = match adx {
+            Some(adx) => Some((adx * (period_dec - Decimal::ONE) + dx) / period_dec),
+            None => {
+                dx_values.push(dx);
+                (dx_values.len() == period).then(|| dx_values.iter().sum::<Decimal>() / period_dec)
+            }
+        };
     }
 
-    if total_volume > Decimal::ZERO {
-        Some(total_price_volume / total_volume)
-    } else {
-        None
+    Some(DirectionalMovement {
+        plus_di,
+        minus_di,
+        adx: adx?,
+    })
+}
+
+/// Average Directional Index: how strongly the market trends, in either direction
+pub fn adx(data: &[Kline], period: usize) -> Option<Decimal> {
+    directional_movement(data, period).map(|dm| dm.adx)
+}
+
+/// Annualized realized volatility, in percent, of close-to-close returns over the
+/// last `period` bars. The bar spacing is taken from the data.
+pub fn realized_volatility(data: &[Kline], period: usize) -> Option<Decimal> {
+    if period < 2 || data.len() < period + 1 {
+        return None;
     }
+
+    let window = &data[data.len() - period - 1..];
+    let returns: Vec<f64> = window
+        .windows(2)
+        .filter(|w| !w[0].close.is_zero())
+        .filter_map(|w| ((w[1].close - w[0].close) / w[0].close).to_f64())
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+
+    let bar_seconds = (window[period].open_time - window[period - 1].open_time).num_seconds();
+    if bar_seconds <= 0 {
+        return None;
+    }
+    let periods_per_year = 365.25 * 86_400.0 / bar_seconds as f64;
+
+    Decimal::from_f64(variance.sqrt() * periods_per_year.sqrt() * 100.0)
+}
+
+/// Volume Weighted Average Price, skipping bars that didn't trade. See
+/// [`volume::vwap`] to choose how those bars are handled.
+pub fn vwap(data: &[Kline]) -> Option<Decimal> {
+    volume::vwap(data, volume::VolumePolicy::default()).ok().flatten()
 }
 
 /// Williams %R
@@ -284,4 +547,356 @@ pub fn williams_r(data: &[Kline], period: usize) -> Option<Decimal> {
     } else {
         Some(Decimal::from(-50)) // Default when high == low
     }
-}
\ No newline at end of file
+}
+
+/// Formula family used for pivot levels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PivotMethod {
+    Classic,
+    Fibonacci,
+    Camarilla,
+}
+
+/// Pivot point with three resistance and three support levels
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotPoints {
+    pub pivot: Decimal,
+    pub r1: Decimal,
+    pub r2: Decimal,
+    pub r3: Decimal,
+    pub s1: Decimal,
+    pub s2: Decimal,
+    pub s3: Decimal,
+}
+
+/// Support/resistance levels for the current period from the previous period's range
+pub fn pivot_points(prev_high: Decimal, prev_low: Decimal, prev_close: Decimal, method: PivotMethod) -> PivotPoints {
+    let pivot = (prev_high + prev_low + prev_close) / Decimal::from(3);
+    let range = prev_high - prev_low;
+
+    match method {
+        PivotMethod::Classic => PivotPoints {
+            pivot,
+            r1: Decimal::from(2) * pivot - prev_low,
+            r2: pivot + range,
+            r3: prev_high + Decimal::from(2) * (pivot - prev_low),
+            s1: Decimal::from(2) * pivot - prev_high,
+            s2: pivot - range,
+            s3: prev_low - Decimal::from(2) * (prev_high - pivot),
+        },
+        PivotMethod::Fibonacci => {
+            let (first, second) = (Decimal::new(382, 3), Decimal::new(618, 3));
+            PivotPoints {
+                pivot,
+                r1: pivot + first * range,
+                r2: pivot + second * range,
+                r3: pivot + range,
+                s1: pivot - first * range,
+                s2: pivot - second * range,
+                s3: pivot - range,
+            }
+        }
+        PivotMethod::Camarilla => {
+            let step = range * Decimal::new(11, 1);
+            PivotPoints {
+                pivot,
+                r1: prev_close + step / Decimal::from(12),
+                r2: prev_close + step / Decimal::from(6),
+                r3: prev_close + step / Decimal::from(4),
+                s1: prev_close - step / Decimal::from(12),
+                s2: prev_close - step / Decimal::from(6),
+                s3: prev_close - step / Decimal::from(4),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn klines(closes: &[i64]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let price = Decimal::from(*close);
+                Kline {
+                    open_time: base_time + Duration::hours(i as i64),
+                    close_time: base_time + Duration::hours(i as i64 + 1),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::ONE,
+                    quote_asset_volume: price,
+                    number_of_trades: 1,
+                    taker_buy_base_asset_volume: Decimal::ZERO,
+                    taker_buy_quote_asset_volume: Decimal::ZERO,
+                }
+            })
+            .collect()
+    }
+
+    const CLOSES: [i64; 12] = [44, 46, 45, 47, 49, 48, 50, 53, 52, 55, 54, 57];
+
+    #[test]
+    fn test_zscore_is_undefined_for_a_constant_series() {
+        let data = klines(&[50; 10]);
+
+        assert_eq!(std_dev(&data, 5), Some(Decimal::ZERO));
+        assert_eq!(zscore(&data, 5), None);
+        assert_eq!(zscore(&data[..4], 5), None);
+    }
+
+    #[test]
+    fn test_zscore_of_a_linear_series() {
+        // Last five closes 3..=7: mean 5, variance 2, so the last close is 2 / sqrt(2) out
+        let data = klines(&[1, 2, 3, 4, 5, 6, 7]);
+        let sqrt_two = Decimal::from_str("1.4142135623730950488016887242").unwrap();
+        let tolerance = Decimal::from_str("0.000000000000000001").unwrap();
+
+        assert!((std_dev(&data, 5).unwrap() - sqrt_two).abs() < tolerance);
+        assert!((zscore(&data, 5).unwrap() - sqrt_two).abs() < tolerance);
+        assert!(zscore(&data[..5], 5).unwrap() > Decimal::ZERO);
+        assert_eq!(std_dev(&data, 0), None);
+    }
+
+    #[test]
+    fn test_ema_series_ends_at_scalar_ema() {
+        let data = klines(&CLOSES);
+        for period in [1, 3, 5, 12] {
+            let series = ema_series(&data, period);
+            assert_eq!(series.len(), data.len());
+            assert_eq!(series.iter().filter(|value| value.is_none()).count(), period - 1);
+            assert_eq!(series.last().copied().flatten(), ema(&data, period));
+        }
+        assert!(ema_series(&data, 13).iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_ema_is_undefined_until_warmed_up() {
+        let data = klines(&CLOSES);
+
+        // No shorter-window fallback: five bars are not a 6-bar EMA
+        assert_eq!(ema(&data[..5], 6), None);
+        assert_eq!(ema(&data[..6], 6), sma(&data[..6], 6));
+        assert_eq!(ema(&data, 0), None);
+        assert_eq!(ema(&[], 3), None);
+
+        let series = ema_series(&data, 6);
+        assert_eq!(series[4], None);
+        assert_eq!(series[5], sma(&data[..6], 6));
+        assert_eq!(series[6], ema(&data[..7], 6));
+    }
+
+    /// Flat at 100 for bars 0-7, up 2 a bar to 120 at bar 17, then down 2 a bar
+    fn ramp() -> Vec<Kline> {
+        let closes: Vec<i64> = std::iter::repeat(100)
+            .take(8)
+            .chain((1..=10).map(|i| 100 + 2 * i))
+            .chain((1..=12).map(|i| 120 - 2 * i))
+            .collect();
+        klines(&closes)
+    }
+
+    #[test]
+    fn test_macd_histogram_changes_sign_at_the_turns() {
+        let data = ramp();
+        let series = macd_series(&data, 3, 6, 3).unwrap();
+        // The first value lands on bar 6 + 3 - 2 = 7
+        assert_eq!(series.len(), data.len() - 7);
+        let histogram_at = |bar: usize| series[bar - 7].histogram;
+
+        assert_eq!(histogram_at(7), Decimal::ZERO);
+        for bar in 8..=17 {
+            assert!(histogram_at(bar) > Decimal::ZERO, "bar {} should be bullish", bar);
+        }
+        for bar in 18..data.len() {
+            assert!(histogram_at(bar) < Decimal::ZERO, "bar {} should be bearish", bar);
+        }
+
+        // The histogram turns on the first falling bar, ahead of the MACD line itself
+        assert!(series[20 - 7].macd_line > Decimal::ZERO);
+        assert!(series[21 - 7].macd_line < Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_macd_is_the_last_bar_of_the_series() {
+        let data = ramp();
+        let latest = macd(&data, 3, 6, 3).unwrap();
+        let last = macd_series(&data, 3, 6, 3).unwrap().pop().unwrap();
+        assert_eq!(latest.macd_line, last.macd_line);
+        assert_eq!(latest.signal_line, last.signal_line);
+        assert_ne!(latest.histogram, Decimal::ZERO);
+        assert_eq!(latest.histogram, latest.macd_line - latest.signal_line);
+
+        // Too few bars for the signal line, or a fast period no shorter than the slow one
+        assert!(macd(&data[..7], 3, 6, 3).is_none());
+        assert!(macd(&data[..8], 3, 6, 3).is_some());
+        assert!(macd(&data, 6, 3, 3).is_none());
+        assert!(macd(&data, 3, 6, 0).is_none());
+    }
+
+    #[test]
+    fn test_stochastic_d_lags_a_rising_k() {
+        // Every bar spans 90-110 and the close climbs 2 a bar, so %K rises 10 points a bar
+        let data: Vec<Kline> = klines(&[90, 92, 94, 96, 98, 100, 102, 104])
+            .into_iter()
+            .map(|kline| Kline {
+                high: Decimal::from(110),
+                low: Decimal::from(90),
+                ..kline
+            })
+            .collect();
+
+        let series = stochastic_series(&data, 3, 3).unwrap();
+        assert_eq!(series.len(), data.len() - 4);
+        for (i, point) in series.iter().enumerate() {
+            assert_eq!(point.k_percent, Decimal::from(10 * (i as i64 + 4)));
+            // The average of the last three %K values trails the latest by one bar
+            assert_eq!(point.d_percent, point.k_percent - Decimal::from(10));
+        }
+
+        let latest = stochastic(&data, 3, 3).unwrap();
+        assert_eq!(latest.k_percent, Decimal::from(70));
+        assert_eq!(latest.d_percent, Decimal::from(60));
+
+        // %D needs `d_period` windows of `k_period` bars
+        assert!(stochastic(&data[..4], 3, 3).is_none());
+        assert!(stochastic(&data[..5], 3, 3).is_some());
+        assert_eq!(stochastic(&data, 3, 1).unwrap().d_percent, Decimal::from(70));
+    }
+
+    #[test]
+    fn test_trix_matches_reference_series() {
+        let data = klines(&CLOSES);
+        // TRIX(3) of the closes above, SMA-seeded EMAs
+        let reference = ["2.56647", "2.309631", "2.540777", "2.116615", "2.284681"];
+
+        assert_eq!(trix(&data[..7], 3), None);
+        for (offset, expected) in reference.iter().enumerate() {
+            let value = trix(&data[..8 + offset], 3).unwrap();
+            let expected: Decimal = expected.parse().unwrap();
+            assert!((value - expected).abs() < Decimal::new(1, 5), "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn test_ma_ribbon_is_fully_aligned_in_a_strong_uptrend() {
+        let uptrend: Vec<i64> = (0..20).map(|i| 100 + i * 2).collect();
+        let data = klines(&uptrend);
+
+        for ma_type in [MaType::Sma, MaType::Ema] {
+            let ribbon = ma_ribbon(&data, vec![13, 3, 8, 5], ma_type).unwrap();
+            assert_eq!(ribbon.alignment, Decimal::ONE);
+            assert_eq!(ribbon.averages.iter().map(|a| a.period).collect::<Vec<_>>(), vec![3, 5, 8, 13]);
+        }
+
+        let ribbon = ma_ribbon(&data, vec![3, 5, 8, 13], MaType::Sma).unwrap();
+        assert_eq!(ribbon.averages[0].value, Decimal::from(136));
+        assert_eq!(ribbon.averages[3].value, Decimal::from(126));
+
+        let downtrend: Vec<i64> = uptrend.into_iter().rev().collect();
+        let ribbon = ma_ribbon(&klines(&downtrend), vec![3, 5, 8, 13], MaType::Sma).unwrap();
+        assert_eq!(ribbon.alignment, -Decimal::ONE);
+    }
+
+    #[test]
+    fn test_ma_ribbon_is_tangled_in_a_choppy_market() {
+        let data = klines(&[100, 104, 99, 103, 101, 97, 102, 98, 104, 100, 96, 101, 99, 103, 98, 102, 100, 97, 103, 99]);
+
+        let ribbon = ma_ribbon(&data, vec![3, 5, 8, 13], MaType::Sma).unwrap();
+        assert!(ribbon.alignment.abs() <= Decimal::new(34, 2), "alignment {}", ribbon.alignment);
+
+        assert!(ma_ribbon(&data, vec![5], MaType::Sma).is_none());
+        assert!(ma_ribbon(&data, vec![5, 50], MaType::Sma).is_none());
+    }
+
+    /// Bars spanning one either side of the close
+    fn with_range(data: Vec<Kline>) -> Vec<Kline> {
+        data.into_iter()
+            .map(|kline| Kline {
+                high: kline.close + Decimal::ONE,
+                low: kline.close - Decimal::ONE,
+                ..kline
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_adx_is_strong_in_a_steady_uptrend() {
+        let closes: Vec<i64> = (0..40).map(|i| 100 + i * 2).collect();
+        let data = with_range(klines(&closes));
+
+        let dm = directional_movement(&data, 14).unwrap();
+        assert!(dm.adx > Decimal::from(25), "adx {}", dm.adx);
+        assert!(dm.plus_di > dm.minus_di);
+        assert_eq!(dm.minus_di, Decimal::ZERO);
+        assert_eq!(adx(&data, 14), Some(dm.adx));
+
+        // A downtrend is just as strong, with the roles of the indexes swapped
+        let falling: Vec<i64> = closes.into_iter().rev().collect();
+        let dm = directional_movement(&with_range(klines(&falling)), 14).unwrap();
+        assert!(dm.adx > Decimal::from(25));
+        assert!(dm.minus_di > dm.plus_di);
+    }
+
+    #[test]
+    fn test_adx_stays_low_in_a_ranging_market() {
+        let closes: Vec<i64> = (0..40).map(|i| if i % 2 == 0 { 100 } else { 102 }).collect();
+        let data = with_range(klines(&closes));
+        let ranging = adx(&data, 14).unwrap();
+        assert!(ranging < Decimal::from(20), "adx {}", ranging);
+
+        // A market that doesn't move has no trend at all
+        assert_eq!(adx(&klines(&[100; 30]), 14), Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_adx_needs_two_periods_of_bars() {
+        let closes: Vec<i64> = (0..28).map(|i| 100 + i).collect();
+        let data = with_range(klines(&closes));
+
+        assert!(adx(&data[..27], 14).is_none());
+        assert!(adx(&data, 14).is_some());
+        assert!(adx(&data, 0).is_none());
+    }
+
+    #[test]
+    fn test_pivot_points_match_reference_formulas() {
+        let (high, low, close) = (Decimal::from(110), Decimal::from(90), Decimal::from(104));
+        let pivot = Decimal::from(304) / Decimal::from(3);
+        let range = Decimal::from(20);
+
+        let classic = pivot_points(high, low, close, PivotMethod::Classic);
+        assert_eq!(classic.pivot, pivot);
+        assert_eq!(classic.r1, Decimal::from(2) * pivot - low);
+        assert_eq!(classic.s1, Decimal::from(2) * pivot - high);
+        assert_eq!(classic.r2, pivot + range);
+        assert_eq!(classic.s2, pivot - range);
+        assert_eq!(classic.r3, high + Decimal::from(2) * (pivot - low));
+        assert_eq!(classic.s3, low - Decimal::from(2) * (high - pivot));
+
+        let fibonacci = pivot_points(high, low, close, PivotMethod::Fibonacci);
+        assert_eq!(fibonacci.pivot, pivot);
+        assert_eq!(fibonacci.r1, pivot + Decimal::new(764, 2));
+        assert_eq!(fibonacci.s2, pivot - Decimal::new(1236, 2));
+        assert_eq!(fibonacci.r3, pivot + range);
+        assert_eq!(fibonacci.s3, pivot - range);
+
+        // Camarilla levels are centred on the close: 1.1 * range / 12, / 6 and / 4
+        let camarilla = pivot_points(high, low, close, PivotMethod::Camarilla);
+        assert_eq!(camarilla.r3, Decimal::new(1095, 1));
+        assert_eq!(camarilla.s3, Decimal::new(985, 1));
+        assert_eq!(camarilla.r2, close + Decimal::from(22) / Decimal::from(6));
+        assert_eq!(camarilla.s1, close - Decimal::from(22) / Decimal::from(12));
+
+        for levels in [classic, fibonacci, camarilla] {
+            assert!(levels.s3 < levels.s2 && levels.s2 < levels.s1);
+            assert!(levels.r1 < levels.r2 && levels.r2 < levels.r3);
+        }
+    }
+}
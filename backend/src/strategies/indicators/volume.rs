@@ -0,0 +1,326 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::exchange_connectors::Kline;
+use crate::utils::errors::AppError;
+
+/// What volume indicators do with a bar that (almost) didn't trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZeroVolumeAction {
+    /// Leave the bar out, so windows reach back to bars that traded
+    #[default]
+    Skip,
+    /// Keep the bar at the last traded bar's prices with zero volume, so windows
+    /// still span the same time. Bars before the first trade have no prices to
+    /// carry and are left out.
+    ForwardFill,
+    /// Refuse to compute over the bar
+    Error,
+}
+
+/// Zero-volume handling for one call of VWAP, OBV, CMF or MFI
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct VolumePolicy {
+    pub action: ZeroVolumeAction,
+    /// Bars with volume at or below this count as not traded
+    #[serde(default)]
+    pub min_volume: Decimal,
+}
+
+impl VolumePolicy {
+    pub fn new(action: ZeroVolumeAction) -> Self {
+        Self { action, min_volume: Decimal::ZERO }
+    }
+
+    /// Treat bars with volume up to `min_volume` as not traded too
+    pub fn with_min_volume(self, min_volume: Decimal) -> Self {
+        Self { min_volume, ..self }
+    }
+
+    fn is_illiquid(&self, kline: &Kline) -> bool {
+        kline.volume <= self.min_volume
+    }
+
+    /// The bars an indicator should compute over, given it reads the last `lookback` of
+    /// them (`None` for all). `Error` only rejects an illiquid bar the indicator reads.
+    pub fn apply(&self, data: &[Kline], lookback: Option<usize>) -> Result<Vec<Kline>, AppError> {
+        if self.action == ZeroVolumeAction::Error {
+            let read = match lookback {
+                Some(lookback) => &data[data.len().saturating_sub(lookback)..],
+                None => data,
+            };
+            if let Some(kline) = read.iter().find(|kline| self.is_illiquid(kline)) {
+                return Err(AppError::BadRequest(format!(
+                    "Bar at {} has volume {}, at or below the minimum of {}",
+                    kline.open_time, kline.volume, self.min_volume
+                )));
+            }
+            return Ok(data.to_vec());
+        }
+
+        let mut bars = Vec::with_capacity(data.len());
+        let mut last_traded: Option<&Kline> = None;
+
+        for kline in data {
+            if !self.is_illiquid(kline) {
+                last_traded = Some(kline);
+                bars.push(kline.clone());
+                continue;
+            }
+
+            if self.action == ZeroVolumeAction::ForwardFill {
+                if let Some(previous) = last_traded {
+                    bars.push(Kline {
+                        open_time: kline.open_time,
+                        close_time: kline.close_time,
+                        open: previous.close,
+                        high: previous.close,
+                        low: previous.close,
+                        close: previous.close,
+                        volume: Decimal::ZERO,
+                        quote_asset_volume: Decimal::ZERO,
+                        number_of_trades: 0,
+                        taker_buy_base_asset_volume: Decimal::ZERO,
+                        taker_buy_quote_asset_volume: Decimal::ZERO,
+                    });
+                }
+            }
+        }
+
+        Ok(bars)
+    }
+}
+
+fn typical_price(kline: &Kline) -> Decimal {
+    (kline.high + kline.low + kline.close) / Decimal::from(3)
+}
+
+/// Volume Weighted Average Price. `None` when no bar is left with volume.
+pub fn vwap(data: &[Kline], policy: VolumePolicy) -> Result<Option<Decimal>, AppError> {
+    let bars = policy.apply(data, None)?;
+
+    let mut total_volume = Decimal::ZERO;
+    let mut total_price_volume = Decimal::ZERO;
+    for kline in &bars {
+        total_price_volume += typical_price(kline) * kline.volume;
+        total_volume += kline.volume;
+    }
+
+    if total_volume > Decimal::ZERO {
+        Ok(Some(total_price_volume / total_volume))
+    } else {
+        Ok(None)
+    }
+}
+
+/// On-Balance Volume, starting from zero at the first bar
+pub fn obv(data: &[Kline], policy: VolumePolicy) -> Result<Option<Decimal>, AppError> {
+    let bars = policy.apply(data, None)?;
+    if bars.is_empty() {
+        return Ok(None);
+    }
+
+    let mut value = Decimal::ZERO;
+    for pair in bars.windows(2) {
+        if pair[1].close > pair[0].close {
+            value += pair[1].volume;
+        } else if pair[1].close < pair[0].close {
+            value -= pair[1].volume;
+        }
+    }
+
+    Ok(Some(value))
+}
+
+/// Chaikin Money Flow over the last `period` bars, between -1 and 1
+pub fn cmf(data: &[Kline], period: usize, policy: VolumePolicy) -> Result<Option<Decimal>, AppError> {
+    let bars = policy.apply(data, Some(period))?;
+    if period == 0 || bars.len() < period {
+        return Ok(None);
+    }
+
+    let mut money_flow_volume = Decimal::ZERO;
+    let mut total_volume = Decimal::ZERO;
+    for kline in &bars[bars.len() - period..] {
+        let range = kline.high - kline.low;
+        if range > Decimal::ZERO {
+            let multiplier = ((kline.close - kline.low) - (kline.high - kline.close)) / range;
+            money_flow_volume += multiplier * kline.volume;
+        }
+        total_volume += kline.volume;
+    }
+
+    if total_volume > Decimal::ZERO {
+        Ok(Some(money_flow_volume / total_volume))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Money Flow Index over the last `period` bars, between 0 and 100. `None` when no
+/// money flowed either way.
+pub fn mfi(data: &[Kline], period: usize, policy: VolumePolicy) -> Result<Option<Decimal>, AppError> {
+    let bars = policy.apply(data, Some(period + 1))?;
+    if period == 0 || bars.len() < period + 1 {
+        return Ok(None);
+    }
+
+    let mut positive_flow = Decimal::ZERO;
+    let mut negative_flow = Decimal::ZERO;
+    for pair in bars[bars.len() - period - 1..].windows(2) {
+        let previous = typical_price(&pair[0]);
+        let current = typical_price(&pair[1]);
+        let raw_flow = current * pair[1].volume;
+        if current > previous {
+            positive_flow += raw_flow;
+        } else if current < previous {
+            negative_flow += raw_flow;
+        }
+    }
+
+    let hundred = Decimal::from(100);
+    if negative_flow.is_zero() {
+        return Ok(if positive_flow.is_zero() { None } else { Some(hundred) });
+    }
+
+    let money_ratio = positive_flow / negative_flow;
+    Ok(Some(hundred - hundred / (Decimal::ONE + money_ratio)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Bars of (high, low, close, volume)
+    fn klines(bars: &[(i64, i64, i64, i64)]) -> Vec<Kline> {
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        bars.iter()
+            .enumerate()
+            .map(|(i, &(high, low, close, volume))| Kline {
+                open_time: base_time + Duration::hours(i as i64),
+                close_time: base_time + Duration::hours(i as i64 + 1),
+                open: Decimal::from(close),
+                high: Decimal::from(high),
+                low: Decimal::from(low),
+                close: Decimal::from(close),
+                volume: Decimal::from(volume),
+                quote_asset_volume: Decimal::from(close * volume),
+                number_of_trades: volume,
+                taker_buy_base_asset_volume: Decimal::ZERO,
+                taker_buy_quote_asset_volume: Decimal::ZERO,
+            })
+            .collect()
+    }
+
+    /// Traded bars around a three-bar run that didn't trade, quoted away from the market
+    fn with_dead_run() -> Vec<Kline> {
+        klines(&[
+            (102, 98, 101, 10),
+            (104, 99, 103, 12),
+            (103, 97, 98, 8),
+            (150, 150, 150, 0),
+            (40, 40, 40, 0),
+            (150, 150, 150, 0),
+            (106, 100, 105, 15),
+            (107, 101, 102, 9),
+        ])
+    }
+
+    fn traded_only() -> Vec<Kline> {
+        let data = with_dead_run();
+        [&data[..3], &data[6..]].concat()
+    }
+
+    fn policy(action: ZeroVolumeAction) -> VolumePolicy {
+        VolumePolicy::new(action)
+    }
+
+    #[test]
+    fn test_skip_computes_as_if_the_dead_bars_were_absent() {
+        let data = with_dead_run();
+        let skip = policy(ZeroVolumeAction::Skip);
+        let normal = policy(ZeroVolumeAction::Skip);
+        let reference = traded_only();
+
+        assert_eq!(vwap(&data, skip).unwrap(), vwap(&reference, normal).unwrap());
+        assert_eq!(obv(&data, skip).unwrap(), obv(&reference, normal).unwrap());
+        assert_eq!(cmf(&data, 4, skip).unwrap(), cmf(&reference, 4, normal).unwrap());
+        assert_eq!(mfi(&data, 4, skip).unwrap(), mfi(&reference, 4, normal).unwrap());
+        // 10 + 12 - 8 + 15 - 9: the dead bars' quotes at 150 and 40 move nothing
+        assert_eq!(obv(&data, skip).unwrap(), Some(Decimal::from(20)));
+    }
+
+    #[test]
+    fn test_forward_fill_carries_prices_forward_without_volume() {
+        let data = with_dead_run();
+        let fill = policy(ZeroVolumeAction::ForwardFill);
+        let reference = traded_only();
+        let normal = policy(ZeroVolumeAction::Skip);
+
+        let bars = fill.apply(&data, None).unwrap();
+        assert_eq!(bars.len(), data.len());
+        for bar in &bars[3..6] {
+            assert_eq!((bar.open, bar.high, bar.low, bar.close), (data[2].close, data[2].close, data[2].close, data[2].close));
+            assert_eq!(bar.volume, Decimal::ZERO);
+        }
+        assert_eq!(bars[4].open_time, data[4].open_time);
+
+        // The filled bars add no volume, but still take up their place in a window:
+        // the last four bars hold only two that traded
+        assert_eq!(vwap(&data, fill).unwrap(), vwap(&reference, normal).unwrap());
+        assert_eq!(obv(&data, fill).unwrap(), obv(&reference, normal).unwrap());
+        assert_eq!(cmf(&data, 4, fill).unwrap(), cmf(&reference, 2, normal).unwrap());
+        assert_eq!(mfi(&data, 4, fill).unwrap(), mfi(&reference, 2, normal).unwrap());
+        assert_ne!(cmf(&data, 4, fill).unwrap(), cmf(&data, 4, normal).unwrap());
+        // Repeated closes leave OBV where the last traded bar put it
+        assert_eq!(obv(&data[..6], fill).unwrap(), obv(&data[..3], normal).unwrap());
+    }
+
+    #[test]
+    fn test_error_rejects_dead_bars_in_every_indicator() {
+        let data = with_dead_run();
+        let error = policy(ZeroVolumeAction::Error);
+
+        assert!(vwap(&data, error).is_err());
+        assert!(obv(&data, error).is_err());
+        assert!(cmf(&data, 4, error).is_err());
+        assert!(mfi(&data, 4, error).is_err());
+        assert!(mfi(&traded_only(), 4, error).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_error_only_checks_the_bars_an_indicator_reads() {
+        let data = with_dead_run();
+        let error = policy(ZeroVolumeAction::Error);
+
+        // The last two bars traded; the dead run is further back
+        assert_eq!(cmf(&data, 2, error).unwrap(), cmf(&data[6..], 2, error).unwrap());
+        assert_eq!(mfi(&data, 1, error).unwrap(), mfi(&data[6..], 1, error).unwrap());
+        assert!(cmf(&data, 3, error).is_err());
+        assert!(mfi(&data, 2, error).is_err());
+    }
+
+    #[test]
+    fn test_window_of_dead_bars_gives_no_value() {
+        let dead = klines(&[(150, 150, 150, 0), (40, 40, 40, 0), (150, 150, 150, 0), (40, 40, 40, 0)]);
+        let skip = policy(ZeroVolumeAction::Skip);
+
+        assert_eq!(vwap(&dead, skip).unwrap(), None);
+        assert_eq!(obv(&dead, skip).unwrap(), None);
+        assert_eq!(cmf(&dead, 2, skip).unwrap(), None);
+        assert_eq!(mfi(&dead, 2, skip).unwrap(), None);
+        // Forward fill has no traded bar to repeat
+        assert_eq!(mfi(&dead, 2, policy(ZeroVolumeAction::ForwardFill)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_min_volume_treats_thin_bars_as_dead() {
+        let data = klines(&[(102, 98, 101, 10), (150, 150, 150, 1), (104, 99, 103, 12)]);
+        let thin = VolumePolicy::new(ZeroVolumeAction::Skip).with_min_volume(Decimal::from(2));
+
+        assert_eq!(obv(&data, thin).unwrap(), Some(Decimal::from(12)));
+        assert_eq!(obv(&data, VolumePolicy::default()).unwrap(), Some(Decimal::from(-11)));
+    }
+}
@@ -21,6 +21,15 @@ pub fn init_all_strategies() -> Result<(), AppError> {
     // Initialize Grid Trading strategies
     implementations::grid_trading::init_grid_trading_strategies()?;
 
+    // Initialize portfolio rebalancing strategies
+    implementations::rebalance::init_rebalance_strategies()?;
+
+    // Initialize moving average ribbon strategies
+    implementations::ma_ribbon::init_ma_ribbon_strategies()?;
+
+    // Initialize RSI + MACD confluence strategies
+    implementations::rsi_macd_confluence::init_rsi_macd_confluence_strategies()?;
+
     tracing::info!("All trading strategies initialized successfully");
     Ok(())
 }
\ No newline at end of file
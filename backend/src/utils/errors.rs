@@ -29,6 +29,7 @@ pub enum AppError {
     RateLimitError(String),
     Banned(String),
     ParseError(String),
+    Conflict(String),
 }
 
 impl fmt::Display for AppError {
@@ -58,6 +59,7 @@ impl fmt::Display for AppError {
             AppError::RateLimitError(msg) => write!(f, "Rate limit error: {}", msg),
             AppError::Banned(msg) => write!(f, "Banned: {}", msg),
             AppError::ParseError(msg) => write!(f, "Parse error: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
         }
     }
 }
@@ -215,6 +217,12 @@ impl ResponseError for AppError {
                     "message": msg
                 }))
             }
+            AppError::Conflict(msg) => {
+                HttpResponse::Conflict().json(serde_json::json!({
+                    "error": "Conflict",
+                    "message": msg
+                }))
+            }
         }
     }
 }
@@ -1,6 +1,9 @@
 use reqwest;
 use serde::{Deserialize, Serialize};
 use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::utils::http::{request_with_policy, RetryPolicy};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GeolocationResponse {
@@ -27,12 +30,19 @@ struct IpApiResponse {
 
 pub struct GeolocationService {
     client: reqwest::Client,
+    retry_policy: RetryPolicy,
 }
 
 impl GeolocationService {
     pub fn new() -> Self {
         Self {
             client: reqwest::Client::new(),
+            // Lookups run while a session is being tracked, so give up quickly
+            retry_policy: RetryPolicy {
+                max_attempts: 2,
+                deadline: Some(Duration::from_secs(5)),
+                ..RetryPolicy::default()
+            },
         }
     }
 
@@ -88,12 +98,7 @@ impl GeolocationService {
     async fn fetch_geolocation_data(&self, ip_address: &str) -> Result<IpApiResponse, Box<dyn std::error::Error + Send + Sync>> {
         let url = format!("http://ip-api.com/json/{}", ip_address);
 
-        let response = self
-            .client
-            .get(&url)
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await?;
+        let response = request_with_policy(&self.retry_policy, self.client.get(&url)).await?;
 
         let data: IpApiResponse = response.json().await?;
 
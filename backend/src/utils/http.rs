@@ -0,0 +1,488 @@
+//! Retry and timeout policy shared by the outbound HTTP clients
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use thiserror::Error;
+use tokio::time::{sleep, timeout, Instant};
+use tracing::debug;
+
+use crate::utils::errors::AppError;
+
+/// When and how often a failed request is retried
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles for each retry after it
+    pub base_delay: Duration,
+    /// Cap on any single delay
+    pub max_delay: Duration,
+    /// Fraction of each delay that is randomized either way (0 disables jitter)
+    pub jitter: f64,
+    /// Statuses worth retrying; other responses are returned straight away
+    pub retryable_statuses: Vec<u16>,
+    /// Time budget for all attempts and delays together
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.2,
+            retryable_statuses: vec![408, 429, 500, 502, 503, 504],
+            deadline: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The same policy with a single attempt, for requests that must not be replayed
+    pub fn without_retries(&self) -> Self {
+        Self {
+            max_attempts: 1,
+            ..self.clone()
+        }
+    }
+
+    /// Delay before retry number `retry` (1 for the first retry), without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u32 << retry.saturating_sub(1).min(20);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// `backoff` moved randomly by up to `jitter` of itself, still capped at `max_delay`
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return backoff;
+        }
+
+        let factor = rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter);
+        backoff.mul_f64(factor).min(self.max_delay)
+    }
+
+    pub fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retryable_statuses.contains(&status.as_u16())
+    }
+
+    /// Transport failures that may clear up on another attempt
+    pub fn is_retryable_error(error: &reqwest::Error) -> bool {
+        error.is_timeout() || error.is_connect()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("{0}")]
+    Transport(#[from] reqwest::Error),
+
+    #[error("Request did not complete within {deadline:?} ({attempts} attempts)")]
+    DeadlineExceeded { deadline: Duration, attempts: u32 },
+}
+
+impl From<HttpError> for AppError {
+    fn from(err: HttpError) -> Self {
+        AppError::ExternalServiceError(err.to_string())
+    }
+}
+
+/// Token bucket capping how many requests a client sends per minute. The bucket holds
+/// one second's worth of requests and refills continuously, so bursts stay short.
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Bucket allowing `requests_per_minute` requests a minute (at least one)
+    pub fn per_minute(requests_per_minute: u32) -> Self {
+        let refill_per_sec = f64::from(requests_per_minute.max(1)) / 60.0;
+        let capacity = refill_per_sec.max(1.0);
+        Self {
+            capacity,
+            refill_per_sec,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent and take its token
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().expect("token bucket lock poisoned");
+                let now = Instant::now();
+                let refill = now.duration_since(state.refilled_at).as_secs_f64() * self.refill_per_sec;
+                state.tokens = (state.tokens + refill).min(self.capacity);
+                state.refilled_at = now;
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.refill_per_sec)
+            };
+            debug!("Rate limited, waiting {:?} for a request token", wait);
+            sleep(wait).await;
+        }
+    }
+}
+
+/// Delay a response asks for in its `Retry-After` header, when given in seconds
+pub fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Send `request` under `policy`, retrying transport failures and retryable statuses.
+/// The final response is returned whatever its status so callers keep their own error
+/// mapping. Requests with a streaming body can't be replayed and get a single attempt.
+pub async fn request_with_policy(policy: &RetryPolicy, request: RequestBuilder) -> Result<Response, HttpError> {
+    request_with_gate(policy, || async {}, request).await
+}
+
+/// `request_with_policy`, taking a token from `limiter` before every attempt
+pub async fn request_with_rate_limit(
+    policy: &RetryPolicy,
+    limiter: &TokenBucket,
+    request: RequestBuilder,
+) -> Result<Response, HttpError> {
+    request_with_gate(policy, || limiter.acquire(), request).await
+}
+
+/// `request_with_policy`, awaiting `wait_turn` before every attempt, for a caller with its
+/// own rate limiting. Time spent waiting for a turn doesn't count against the policy's
+/// deadline; the deadline bounds the requests and the backoff between them.
+pub async fn request_with_gate<G, GFut>(
+    policy: &RetryPolicy,
+    wait_turn: G,
+    request: RequestBuilder,
+) -> Result<Response, HttpError>
+where
+    G: FnMut() -> GFut,
+    GFut: Future<Output = ()>,
+{
+    let should_retry = |outcome: &Result<Response, reqwest::Error>| match outcome {
+        Ok(response) => policy.is_retryable_status(response.status()),
+        Err(error) => RetryPolicy::is_retryable_error(error),
+    };
+    let requested_delay = |outcome: &Result<Response, reqwest::Error>| outcome.as_ref().ok().and_then(retry_after);
+
+    let outcome = if request.try_clone().is_some() {
+        retry_with_delays(
+            policy,
+            wait_turn,
+            || request.try_clone().expect("request was cloneable").send(),
+            should_retry,
+            requested_delay,
+        )
+        .await?
+    } else {
+        let mut request = Some(request);
+        retry_with_delays(
+            &policy.without_retries(),
+            wait_turn,
+            || request.take().expect("sent once").send(),
+            should_retry,
+            requested_delay,
+        )
+        .await?
+    };
+
+    outcome.map_err(HttpError::from)
+}
+
+/// Call `send` until `should_retry` accepts its outcome or attempts run out, and return the
+/// last outcome. Each attempt first awaits `wait_turn`, outside the deadline. An attempt
+/// still running at the deadline is abandoned, and no retry is scheduled that would start
+/// after it. A retry waits at least the delay `requested_delay` reads off a failed outcome;
+/// a requested delay longer than `max_delay` ends the retries instead.
+async fn retry_with_delays<T, E, F, Fut, G, GFut>(
+    policy: &RetryPolicy,
+    mut wait_turn: G,
+    mut send: F,
+    should_retry: impl Fn(&Result<T, E>) -> bool,
+    requested_delay: impl Fn(&Result<T, E>) -> Option<Duration>,
+) -> Result<Result<T, E>, HttpError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    G: FnMut() -> GFut,
+    GFut: Future<Output = ()>,
+{
+    let started = Instant::now();
+    let mut waited = Duration::ZERO;
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let waiting = Instant::now();
+        wait_turn().await;
+        waited += waiting.elapsed();
+
+        let outcome = match policy.deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_sub(started.elapsed().saturating_sub(waited));
+                timeout(remaining, send())
+                    .await
+                    .map_err(|_| HttpError::DeadlineExceeded { deadline, attempts: attempt })?
+            }
+            None => send().await,
+        };
+
+        if attempt >= max_attempts || !should_retry(&outcome) {
+            return Ok(outcome);
+        }
+
+        let delay = match requested_delay(&outcome) {
+            Some(requested) if requested > policy.max_delay => return Ok(outcome),
+            Some(requested) => requested.max(policy.delay(attempt)),
+            None => policy.delay(attempt),
+        };
+        if policy.deadline.is_some_and(|deadline| started.elapsed().saturating_sub(waited) + delay >= deadline) {
+            return Ok(outcome);
+        }
+
+        debug!("Request attempt {}/{} failed, retrying in {:?}", attempt, max_attempts, delay);
+        sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mock_http::{self, MockResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Answers request number `n` with `status(n)`, or never answers when it returns `None`
+    async fn spawn_server(
+        status: impl Fn(usize) -> Option<&'static str> + Send + Sync + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        mock_http::spawn(move |request| status(request.number).map(|status| MockResponse::json(status, "{}"))).await
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(5),
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_schedule() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        let schedule: Vec<u128> = (1..=6).map(|retry| policy.delay(retry).as_millis()).collect();
+        assert_eq!(schedule, vec![100, 200, 400, 800, 1000, 1000]);
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+
+        let jittered = RetryPolicy { jitter: 0.25, ..policy };
+        for _ in 0..100 {
+            let delay = jittered.delay(2);
+            assert!(delay >= Duration::from_millis(150) && delay <= Duration::from_millis(250), "{:?}", delay);
+            assert!(jittered.delay(5) <= Duration::from_secs(1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retryable_status_is_retried_until_success() {
+        let (url, requests) = spawn_server(|n| Some(if n < 2 { "503 Service Unavailable" } else { "200 OK" })).await;
+
+        let response = request_with_policy(&fast_policy(), reqwest::Client::new().get(&url)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_status_fails_fast() {
+        let (url, requests) = spawn_server(|_| Some("400 Bad Request")).await;
+
+        let response = request_with_policy(&fast_policy(), reqwest::Client::new().get(&url)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attempts_are_capped() {
+        let (url, requests) = spawn_server(|_| Some("500 Internal Server Error")).await;
+
+        let response = request_with_policy(&fast_policy(), reqwest::Client::new().get(&url)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_cuts_off_a_hanging_attempt() {
+        let (url, requests) = spawn_server(|_| None).await;
+        let policy = RetryPolicy {
+            deadline: Some(Duration::from_millis(200)),
+            ..fast_policy()
+        };
+
+        let started = Instant::now();
+        let result = request_with_policy(&policy, reqwest::Client::new().get(&url)).await;
+
+        assert!(matches!(result, Err(HttpError::DeadlineExceeded { attempts: 1, .. })));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_stops_retries_that_would_overrun_it() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(40),
+            max_delay: Duration::from_secs(1),
+            jitter: 0.0,
+            deadline: Some(Duration::from_millis(200)),
+            ..RetryPolicy::default()
+        };
+        let attempts = AtomicUsize::new(0);
+
+        // Retries wait 40, 80 then 160ms; the third would end past the deadline
+        let started = Instant::now();
+        let outcome = retry_with_delays(
+            &policy,
+            || async {},
+            || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>("unavailable") }
+            },
+            |outcome| outcome.is_err(),
+            |_| None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, Err("unavailable"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert!(started.elapsed() < Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_requested_delay_is_honored_or_ends_retries() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(500),
+            jitter: 0.0,
+            deadline: None,
+            ..RetryPolicy::default()
+        };
+
+        let attempts = AtomicUsize::new(0);
+        let started = Instant::now();
+        let outcome = retry_with_delays(
+            &policy,
+            || async {},
+            || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move { Err::<(), _>(attempt) }
+            },
+            |outcome| outcome.is_err(),
+            |_| Some(Duration::from_millis(100)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, Err(2));
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        // Asked to wait longer than the policy ever would, the caller gets the failure
+        let attempts = AtomicUsize::new(0);
+        let outcome = retry_with_delays(
+            &policy,
+            || async {},
+            || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move { Err::<(), _>(attempt) }
+            },
+            |outcome| outcome.is_err(),
+            |_| Some(Duration::from_secs(60)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, Err(0));
+    }
+
+    #[tokio::test]
+    async fn test_waiting_for_a_turn_does_not_use_up_the_deadline() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            jitter: 0.0,
+            deadline: Some(Duration::from_millis(100)),
+            ..RetryPolicy::default()
+        };
+        let attempts = AtomicUsize::new(0);
+
+        // Each turn takes longer than the whole deadline
+        let outcome = retry_with_delays(
+            &policy,
+            || sleep(Duration::from_millis(150)),
+            || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move { if attempt < 2 { Err("unavailable") } else { Ok(attempt) } }
+            },
+            |outcome| outcome.is_err(),
+            |_| None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_spreads_requests_past_a_burst() {
+        // 600 a minute refills 10 a second into a bucket holding 10
+        let bucket = TokenBucket::per_minute(600);
+
+        let started = Instant::now();
+        for _ in 0..10 {
+            bucket.acquire().await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(50));
+
+        for _ in 0..3 {
+            bucket.acquire().await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(250), "{:?}", started.elapsed());
+    }
+}
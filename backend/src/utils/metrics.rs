@@ -0,0 +1,177 @@
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+
+/// Global application metrics, exposed in Prometheus text format on `/metrics`
+static METRICS: Lazy<AppMetrics> = Lazy::new(AppMetrics::new);
+
+/// Prometheus collectors for request, backtest, order and exchange activity
+pub struct AppMetrics {
+    registry: Registry,
+    /// HTTP requests by method, matched route pattern and status code
+    pub http_requests_total: IntCounterVec,
+    /// HTTP request latency by method and matched route pattern
+    pub http_request_duration_seconds: HistogramVec,
+    /// Backtests run, by outcome ("success" / "error")
+    pub backtests_total: IntCounterVec,
+    /// Wall-clock duration of backtest simulations
+    pub backtest_duration_seconds: Histogram,
+    /// Live orders placed, by the component that placed them
+    pub live_orders_placed_total: IntCounterVec,
+    /// Failed exchange API calls, by exchange and error kind
+    pub exchange_api_errors_total: IntCounterVec,
+    /// Historical data cache lookups, by result ("hit" / "miss")
+    pub data_cache_lookups_total: IntCounterVec,
+    /// Entries evicted from the historical data cache to stay within its limits
+    pub data_cache_evictions_total: IntCounter,
+    /// Live orders over their max notional, by action taken ("rejected" / "clamped")
+    pub order_notional_limit_hits_total: IntCounterVec,
+    /// Strategy executions waiting for a free execution slot
+    pub execution_queue_depth: IntGauge,
+    /// Strategy executions currently running
+    pub executions_in_flight: IntGauge,
+}
+
+impl AppMetrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests_total = IntCounterVec::new(
+            Opts::new("http_requests_total", "Total HTTP requests"),
+            &["method", "route", "status"],
+        )
+        .expect("valid http_requests_total metric");
+
+        let http_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency in seconds"),
+            &["method", "route"],
+        )
+        .expect("valid http_request_duration_seconds metric");
+
+        let backtests_total = IntCounterVec::new(
+            Opts::new("backtests_total", "Total backtests run"),
+            &["status"],
+        )
+        .expect("valid backtests_total metric");
+
+        let backtest_duration_seconds = Histogram::with_opts(
+            HistogramOpts::new("backtest_duration_seconds", "Backtest simulation duration in seconds")
+                .buckets(vec![0.01, 0.05, 0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]),
+        )
+        .expect("valid backtest_duration_seconds metric");
+
+        let live_orders_placed_total = IntCounterVec::new(
+            Opts::new("live_orders_placed_total", "Total live orders placed"),
+            &["source"],
+        )
+        .expect("valid live_orders_placed_total metric");
+
+        let exchange_api_errors_total = IntCounterVec::new(
+            Opts::new("exchange_api_errors_total", "Total failed exchange API calls"),
+            &["exchange", "kind"],
+        )
+        .expect("valid exchange_api_errors_total metric");
+
+        let data_cache_lookups_total = IntCounterVec::new(
+            Opts::new("data_cache_lookups_total", "Total historical data cache lookups"),
+            &["result"],
+        )
+        .expect("valid data_cache_lookups_total metric");
+
+        let data_cache_evictions_total = IntCounter::new(
+            "data_cache_evictions_total",
+            "Total historical data cache evictions",
+        )
+        .expect("valid data_cache_evictions_total metric");
+
+        let order_notional_limit_hits_total = IntCounterVec::new(
+            Opts::new("order_notional_limit_hits_total", "Total live orders that exceeded their max notional"),
+            &["action"],
+        )
+        .expect("valid order_notional_limit_hits_total metric");
+
+        let execution_queue_depth = IntGauge::new(
+            "execution_queue_depth",
+            "Strategy executions waiting for a free execution slot",
+        )
+        .expect("valid execution_queue_depth metric");
+
+        let executions_in_flight = IntGauge::new(
+            "executions_in_flight",
+            "Strategy executions currently running",
+        )
+        .expect("valid executions_in_flight metric");
+
+        registry.register(Box::new(http_requests_total.clone())).expect("register http_requests_total");
+        registry.register(Box::new(http_request_duration_seconds.clone())).expect("register http_request_duration_seconds");
+        registry.register(Box::new(backtests_total.clone())).expect("register backtests_total");
+        registry.register(Box::new(backtest_duration_seconds.clone())).expect("register backtest_duration_seconds");
+        registry.register(Box::new(live_orders_placed_total.clone())).expect("register live_orders_placed_total");
+        registry.register(Box::new(exchange_api_errors_total.clone())).expect("register exchange_api_errors_total");
+        registry.register(Box::new(data_cache_lookups_total.clone())).expect("register data_cache_lookups_total");
+        registry.register(Box::new(data_cache_evictions_total.clone())).expect("register data_cache_evictions_total");
+        registry.register(Box::new(order_notional_limit_hits_total.clone())).expect("register order_notional_limit_hits_total");
+        registry.register(Box::new(execution_queue_depth.clone())).expect("register execution_queue_depth");
+        registry.register(Box::new(executions_in_flight.clone())).expect("register executions_in_flight");
+
+        Self {
+            registry,
+            http_requests_total,
+            http_request_duration_seconds,
+            backtests_total,
+            backtest_duration_seconds,
+            live_orders_placed_total,
+            exchange_api_errors_total,
+            data_cache_lookups_total,
+            data_cache_evictions_total,
+            order_notional_limit_hits_total,
+            execution_queue_depth,
+            executions_in_flight,
+        }
+    }
+
+    /// Encode all collectors in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        if let Err(e) = encoder.encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    /// Record a finished backtest simulation
+    pub fn record_backtest(&self, success: bool, duration_secs: f64) {
+        let status = if success { "success" } else { "error" };
+        self.backtests_total.with_label_values(&[status]).inc();
+        self.backtest_duration_seconds.observe(duration_secs);
+    }
+
+    /// Record a live order placed by `source`
+    pub fn record_live_order(&self, source: &str) {
+        self.live_orders_placed_total.with_label_values(&[source]).inc();
+    }
+
+    /// Record a historical data cache lookup
+    pub fn record_cache_lookup(&self, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.data_cache_lookups_total.with_label_values(&[result]).inc();
+    }
+
+    /// Record a live order that exceeded its max notional and was `action` ("rejected" / "clamped")
+    pub fn record_notional_limit_hit(&self, action: &str) {
+        self.order_notional_limit_hits_total.with_label_values(&[action]).inc();
+    }
+
+    /// Record a failed call to `exchange`
+    pub fn record_exchange_error(&self, exchange: &str, kind: &str) {
+        self.exchange_api_errors_total.with_label_values(&[exchange, kind]).inc();
+    }
+}
+
+/// Access the global metrics
+pub fn metrics() -> &'static AppMetrics {
+    &METRICS
+}
@@ -0,0 +1,123 @@
+//! Local HTTP server for tests of the outbound API clients.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A request the mock server received
+pub struct MockRequest {
+    /// Zero-based position among the requests the server has received
+    pub number: usize,
+    pub method: String,
+    pub path: String,
+    /// Query string as sent, without the leading `?`
+    pub query: String,
+}
+
+impl MockRequest {
+    /// Query parameters by name
+    pub fn params(&self) -> HashMap<String, String> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    pub fn param(&self, key: &str) -> Option<String> {
+        self.params().remove(key)
+    }
+}
+
+/// Response for the mock server to send back
+pub struct MockResponse {
+    pub status: &'static str,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    pub fn json(status: &'static str, body: impl ToString) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+}
+
+/// Read a request or response head up to and including the blank line, leaving
+/// whatever follows it unread
+pub async fn read_head<S: AsyncRead + Unpin>(stream: &mut S) -> String {
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        match stream.read_u8().await {
+            Ok(byte) => head.push(byte),
+            Err(_) => break,
+        }
+    }
+    String::from_utf8_lossy(&head).to_string()
+}
+
+/// Serve every request with `respond` on a free local port. A request it returns `None`
+/// for is never answered. Returns the base URL and the number of requests received so far.
+pub async fn spawn(
+    respond: impl Fn(&MockRequest) -> Option<MockResponse> + Send + Sync + 'static,
+) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let number = counter.fetch_add(1, Ordering::SeqCst);
+            let head = read_head(&mut socket).await;
+            let mut words = head.split_whitespace();
+            let method = words.next().unwrap_or_default().to_string();
+            let target = words.next().unwrap_or_default();
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let request = MockRequest {
+                number,
+                method,
+                path: path.to_string(),
+                query: query.to_string(),
+            };
+
+            match respond(&request) {
+                Some(response) => {
+                    let headers: String = response
+                        .headers
+                        .iter()
+                        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                        .collect();
+                    let response = format!(
+                        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        response.status,
+                        headers,
+                        response.body.len(),
+                        response.body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                }
+                None => {
+                    tokio::spawn(async move {
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                        drop(socket);
+                    });
+                }
+            }
+        }
+    });
+
+    (format!("http://{}", addr), requests)
+}
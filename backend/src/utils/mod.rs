@@ -1,4 +1,10 @@
 pub mod errors;
+pub mod http;
 pub mod session_tracker;
 pub mod geolocation;
-pub mod encryption;
\ No newline at end of file
+pub mod encryption;
+pub mod metrics;
+pub mod versioning;
+pub mod precision;
+#[cfg(test)]
+pub mod mock_http;
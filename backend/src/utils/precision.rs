@@ -0,0 +1,215 @@
+use once_cell::sync::Lazy;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Number of decimals numeric values are rounded to when serialized in API responses
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericPrecision {
+    /// USD amounts: balances, portfolio values, P&L
+    pub usd_decimals: u32,
+    /// Crypto quantities and asset prices
+    pub crypto_decimals: u32,
+}
+
+impl Default for NumericPrecision {
+    fn default() -> Self {
+        Self {
+            usd_decimals: 2,
+            crypto_decimals: 8,
+        }
+    }
+}
+
+impl NumericPrecision {
+    /// Read `USD_DECIMALS` / `CRYPTO_DECIMALS`, falling back to 2 and 8
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |key: &str, default: u32| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .map(|v| v.min(Decimal::MAX_SCALE))
+                .unwrap_or(default)
+        };
+
+        Self {
+            usd_decimals: read("USD_DECIMALS", defaults.usd_decimals),
+            crypto_decimals: read("CRYPTO_DECIMALS", defaults.crypto_decimals),
+        }
+    }
+}
+
+static PRECISION: Lazy<NumericPrecision> = Lazy::new(NumericPrecision::from_env);
+
+pub fn precision() -> &'static NumericPrecision {
+    &PRECISION
+}
+
+/// Round half away from zero and pad to exactly `decimals` places, so `0.1` renders as `0.10`
+pub fn round_to(value: Decimal, decimals: u32) -> Decimal {
+    let mut rounded = value.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero);
+    rounded.rescale(decimals);
+    rounded
+}
+
+pub fn round_usd(value: Decimal) -> Decimal {
+    round_to(value, precision().usd_decimals)
+}
+
+pub fn round_crypto(value: Decimal) -> Decimal {
+    round_to(value, precision().crypto_decimals)
+}
+
+/// Square root by Newton's method, to the full precision of `Decimal`; `None` for a
+/// negative value
+pub fn decimal_sqrt(value: Decimal) -> Option<Decimal> {
+    if value < Decimal::ZERO {
+        return None;
+    }
+    if value.is_zero() {
+        return Some(Decimal::ZERO);
+    }
+
+    let two = Decimal::from(2);
+    let mut root = if value > Decimal::ONE { value / two } else { Decimal::ONE };
+    for _ in 0..100 {
+        let next = (root + value / root) / two;
+        if next == root {
+            break;
+        }
+        root = next;
+    }
+    Some(root)
+}
+
+/// `#[serde(with = "crate::utils::precision::usd")]` for USD amounts
+pub mod usd {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&round_usd(*value).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        Decimal::deserialize(deserializer)
+    }
+}
+
+/// `#[serde(with = "crate::utils::precision::usd_option")]` for optional USD amounts
+pub mod usd_option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&round_usd(*value).to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Decimal>, D::Error> {
+        Option::<Decimal>::deserialize(deserializer)
+    }
+}
+
+/// `#[serde(with = "crate::utils::precision::crypto")]` for quantities and prices
+pub mod crypto {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&round_crypto(*value).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        Decimal::deserialize(deserializer)
+    }
+}
+
+/// `#[serde(with = "crate::utils::precision::crypto_option")]` for optional quantities and prices
+pub mod crypto_option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&round_crypto(*value).to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Decimal>, D::Error> {
+        Option::<Decimal>::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange_connectors::common_types::{AccountBalances, AssetBalance, WalletType};
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_round_to_pads_and_rounds_half_away_from_zero() {
+        assert_eq!(round_to(dec("1234.565"), 2).to_string(), "1234.57");
+        assert_eq!(round_to(dec("0.1"), 2).to_string(), "0.10");
+        assert_eq!(round_to(dec("0.000000000000001"), 8).to_string(), "0.00000000");
+        assert_eq!(round_to(dec("-2.005"), 2).to_string(), "-2.01");
+    }
+
+    #[test]
+    fn test_decimal_sqrt_matches_reference_roots() {
+        assert_eq!(decimal_sqrt(Decimal::from(144)), Some(Decimal::from(12)));
+        assert_eq!(decimal_sqrt(Decimal::ZERO), Some(Decimal::ZERO));
+        assert_eq!(decimal_sqrt(Decimal::NEGATIVE_ONE), None);
+
+        let close = |value: &str, expected: &str| {
+            let root = decimal_sqrt(dec(value)).unwrap();
+            assert!((root - dec(expected)).abs() < dec("0.000000000000000001"), "sqrt({}) = {}", value, root);
+        };
+        close("2", "1.414213562373095048801688724");
+        close("252", "15.874507866387543543009694522");
+        close("0.0004", "0.02");
+
+        // No overflow at the top of the range
+        let root = decimal_sqrt(Decimal::MAX).unwrap();
+        assert!((root - dec("281474976710656")).abs() < dec("0.000001"));
+    }
+
+    #[test]
+    fn test_usd_totals_render_to_2dp_and_quantities_to_8dp() {
+        let balances = AccountBalances {
+            spot: None,
+            margin: None,
+            futures_usdm: None,
+            futures_coinm: None,
+            total_usd_value: dec("98765.432109876"),
+            total_btc_value: dec("1.123456789123"),
+        };
+        let json = serde_json::to_value(&balances).unwrap();
+        assert_eq!(json["total_usd_value"], "98765.43");
+        assert_eq!(json["total_btc_value"], "1.12345679");
+
+        let balance = AssetBalance {
+            asset: "BTC".to_string(),
+            free: dec("0.123456789"),
+            locked: Decimal::ZERO,
+            total: dec("0.123456789"),
+            usd_value: Some(dec("12345.6789")),
+            btc_value: None,
+            wallet_type: WalletType::Spot,
+            priced: true,
+            price_source: None,
+        };
+        let json = serde_json::to_value(&balance).unwrap();
+        assert_eq!(json["total"], "0.12345679");
+        assert_eq!(json["locked"], "0.00000000");
+        assert_eq!(json["usd_value"], "12345.68");
+        assert!(json["btc_value"].is_null());
+
+        // Rounding is output-only; reading a response back keeps the rounded value
+        let parsed: AssetBalance = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.total, dec("0.12345679"));
+    }
+}
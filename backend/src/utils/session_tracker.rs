@@ -1,12 +1,45 @@
 use actix_web::HttpRequest;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use uuid::Uuid;
 use std::net::IpAddr;
 
-use crate::models::user_session::{ActiveModel as UserSessionActiveModel, Entity as UserSessionEntity};
+use crate::config::{Config, DEFAULT_SESSION_ABSOLUTE_TIMEOUT_SECS, DEFAULT_SESSION_IDLE_TIMEOUT_SECS};
+use crate::models::user_session::{self, ActiveModel as UserSessionActiveModel, Entity as UserSessionEntity};
 use crate::utils::errors::AppError;
 use crate::utils::geolocation::GeolocationService;
+use tracing::warn;
+
+/// Sliding expiry of login sessions: each request pushes the expiry `idle_timeout`
+/// ahead, but never past `absolute_timeout` after login
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionPolicy {
+    pub idle_timeout: Duration,
+    pub absolute_timeout: Duration,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::seconds(DEFAULT_SESSION_IDLE_TIMEOUT_SECS as i64),
+            absolute_timeout: Duration::seconds(DEFAULT_SESSION_ABSOLUTE_TIMEOUT_SECS as i64),
+        }
+    }
+}
+
+impl SessionPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            idle_timeout: Duration::seconds(config.session_idle_timeout_secs as i64),
+            absolute_timeout: Duration::seconds(config.session_absolute_timeout_secs as i64),
+        }
+    }
+
+    /// Expiry of a session created at `created_at` and last active at `now`
+    pub fn expires_at(&self, created_at: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+        (now + self.idle_timeout).min(created_at + self.absolute_timeout)
+    }
+}
 
 pub struct SessionTracker;
 
@@ -78,6 +111,83 @@ impl SessionTracker {
         Ok(())
     }
 
+    /// Record a new login session for `token`
+    pub async fn start_session(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        token: &str,
+        req: &HttpRequest,
+        policy: &SessionPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<user_session::Model, AppError> {
+        let ip_address = Self::extract_ip_address(req);
+        let user_agent = Self::extract_user_agent(req);
+
+        let session = UserSessionActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            session_token: Set(token.to_string()),
+            device_info: Set(Self::extract_device_info(&user_agent)),
+            ip_address: Set(ip_address.clone()),
+            location: Set(None),
+            user_agent: Set(user_agent),
+            is_current: Set(false),
+            last_activity: Set(now),
+            created_at: Set(now),
+            expires_at: Set(policy.expires_at(now, now)),
+        };
+        let session = session.insert(db).await.map_err(AppError::DatabaseError)?;
+
+        // The lookup calls an outside service; fill the location in once it answers
+        // instead of holding up the login
+        let db = db.clone();
+        let session_id = session.id;
+        tokio::spawn(async move {
+            let Some(location) = Self::resolve_location(&ip_address).await else {
+                return;
+            };
+            let update = UserSessionActiveModel {
+                id: Set(session_id),
+                location: Set(Some(location)),
+                ..Default::default()
+            };
+            if let Err(e) = update.update(&db).await {
+                warn!("Failed to record the location of session {}: {:?}", session_id, e);
+            }
+        });
+
+        Ok(session)
+    }
+
+    /// Check that the session behind `token` is still live and slide its expiry forward.
+    /// Revoked (deleted) and expired sessions are rejected.
+    pub async fn refresh_session(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        token: &str,
+        policy: &SessionPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<user_session::Model, AppError> {
+        let session = UserSessionEntity::find()
+            .filter(user_session::Column::SessionToken.eq(token))
+            .filter(user_session::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::Unauthorized("Session has been revoked".to_string()))?;
+
+        if session.expires_at <= now {
+            return Err(AppError::TokenExpired);
+        }
+
+        let expires_at = policy.expires_at(session.created_at, now);
+        let mut session_model: UserSessionActiveModel = session.into();
+        session_model.last_activity = Set(now);
+        session_model.expires_at = Set(expires_at);
+
+        session_model.update(db).await.map_err(AppError::DatabaseError)
+    }
+
     /// Extract IP address from request (static version for middleware)
     pub fn extract_ip_address_static(req: &HttpRequest) -> String {
         Self::extract_ip_address(req)
@@ -169,4 +279,89 @@ impl SessionTracker {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use chrono::TimeZone;
+    use crate::database::test_support::{seed_user, test_db};
+
+    fn policy() -> SessionPolicy {
+        SessionPolicy { idle_timeout: Duration::hours(1), absolute_timeout: Duration::hours(4) }
+    }
+
+    #[tokio::test]
+    async fn test_activity_slides_expiry_up_to_the_absolute_cap() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let login = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+        let req = TestRequest::default().to_http_request();
+
+        let session = SessionTracker::start_session(&db, user_id, "token", &req, &policy(), login).await.unwrap();
+        assert_eq!(session.expires_at, login + Duration::hours(1));
+
+        // Active 45 minutes in: idle expiry moves to an hour after that
+        let active = login + Duration::minutes(45);
+        let session = SessionTracker::refresh_session(&db, user_id, "token", &policy(), active).await.unwrap();
+        assert_eq!(session.expires_at, active + Duration::hours(1));
+        assert_eq!(session.last_activity, active);
+
+        // Keeping busy never extends past four hours after login
+        let mut now = active;
+        for _ in 0..6 {
+            now += Duration::minutes(50);
+            let session = SessionTracker::refresh_session(&db, user_id, "token", &policy(), now).await;
+            if now < login + Duration::hours(4) {
+                let expected = (now + Duration::hours(1)).min(login + Duration::hours(4));
+                assert_eq!(session.unwrap().expires_at, expected);
+            } else {
+                assert!(matches!(session, Err(AppError::TokenExpired)));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idle_and_revoked_sessions_are_rejected() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let login = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+        let req = TestRequest::default().to_http_request();
+
+        SessionTracker::start_session(&db, user_id, "idle", &req, &policy(), login).await.unwrap();
+        let idle = SessionTracker::refresh_session(&db, user_id, "idle", &policy(), login + Duration::minutes(61)).await;
+        assert!(matches!(idle, Err(AppError::TokenExpired)));
+
+        let session = SessionTracker::start_session(&db, user_id, "revoked", &req, &policy(), login).await.unwrap();
+        UserSessionEntity::delete_by_id(session.id).exec(&db).await.unwrap();
+        let revoked = SessionTracker::refresh_session(&db, user_id, "revoked", &policy(), login + Duration::minutes(5)).await;
+        assert!(matches!(revoked, Err(AppError::Unauthorized(_))));
+
+        // Another user's token does not unlock this user's session
+        SessionTracker::start_session(&db, user_id, "mine", &req, &policy(), login).await.unwrap();
+        let other_user = seed_user(&db).await;
+        let stolen = SessionTracker::refresh_session(&db, other_user, "mine", &policy(), login).await;
+        assert!(stolen.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_location_is_filled_in_after_login() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let req = TestRequest::default().insert_header(("X-Forwarded-For", "127.0.0.1")).to_http_request();
+
+        let session = SessionTracker::start_session(&db, user_id, "token", &req, &policy(), Utc::now()).await.unwrap();
+        assert_eq!(session.location, None);
+
+        let mut location = None;
+        for _ in 0..50 {
+            location = UserSessionEntity::find_by_id(session.id).one(&db).await.unwrap().unwrap().location;
+            if location.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(location.as_deref(), Some("Local Network"));
+    }
+}
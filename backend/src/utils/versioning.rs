@@ -0,0 +1,146 @@
+use actix_web::{http::header, HttpRequest};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+};
+use uuid::Uuid;
+
+use crate::utils::errors::AppError;
+
+/// Version the client expects to be updating, taken from the `If-Match` header
+/// (`"3"`, `W/"3"` or `3`) or from the request body. `If-Match: *` means any version.
+pub fn expected_version(req: &HttpRequest, body_version: Option<i32>) -> Result<Option<i32>, AppError> {
+    let header_version = match req.headers().get(header::IF_MATCH) {
+        Some(value) => {
+            let raw = value
+                .to_str()
+                .map_err(|_| AppError::BadRequest("Invalid If-Match header".to_string()))?
+                .trim();
+
+            if raw == "*" {
+                None
+            } else {
+                let tag = raw.trim_start_matches("W/").trim_matches('"');
+                Some(tag.parse::<i32>().map_err(|_| {
+                    AppError::BadRequest(format!("If-Match must be a strategy version, got {}", raw))
+                })?)
+            }
+        }
+        None => None,
+    };
+
+    match (header_version, body_version) {
+        (Some(h), Some(b)) if h != b => Err(AppError::BadRequest(
+            "If-Match header and expected_version disagree".to_string(),
+        )),
+        (Some(v), _) | (None, Some(v)) => Ok(Some(v)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Apply the `Set` fields of `changes` to the row `id` and bump its version, in one
+/// statement. With `expected` the write only happens if the stored version still
+/// matches; otherwise it fails with `409 Conflict` and nothing is written.
+pub async fn update_versioned<E, A, C>(
+    db: &C,
+    changes: A,
+    id: Uuid,
+    id_column: E::Column,
+    version_column: E::Column,
+    expected: Option<i32>,
+) -> Result<E::Model, AppError>
+where
+    E: EntityTrait,
+    A: ActiveModelTrait<Entity = E>,
+    C: ConnectionTrait,
+{
+    let mut update = E::update_many()
+        .set(changes)
+        .col_expr(version_column, Expr::col(version_column).add(1))
+        .filter(id_column.eq(id));
+    if let Some(version) = expected {
+        update = update.filter(version_column.eq(version));
+    }
+
+    let result = update.exec(db).await.map_err(AppError::DatabaseError)?;
+
+    let current = E::find()
+        .filter(id_column.eq(id))
+        .one(db)
+        .await
+        .map_err(AppError::DatabaseError)?
+        .ok_or_else(|| AppError::NotFound("Strategy not found".to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::Conflict(format!(
+            "Strategy was modified since version {}; reload and retry",
+            expected.unwrap_or_default()
+        )));
+    }
+
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use sea_orm::Set;
+    use crate::database::test_support::{seed_dca_strategy, seed_user, test_db};
+    use crate::models::dca_strategy;
+
+    fn rename(name: &str) -> dca_strategy::ActiveModel {
+        dca_strategy::ActiveModel {
+            name: Set(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_expected_version_from_if_match_or_body() {
+        let req = TestRequest::default().insert_header((header::IF_MATCH, "W/\"4\"")).to_http_request();
+        assert_eq!(expected_version(&req, None).unwrap(), Some(4));
+        assert!(matches!(expected_version(&req, Some(3)), Err(AppError::BadRequest(_))));
+
+        let req = TestRequest::default().insert_header((header::IF_MATCH, "*")).to_http_request();
+        assert_eq!(expected_version(&req, None).unwrap(), None);
+
+        let req = TestRequest::default().to_http_request();
+        assert_eq!(expected_version(&req, Some(2)).unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_versioned_update_succeeds_then_stale_update_conflicts() {
+        let db = test_db().await;
+        let user_id = seed_user(&db).await;
+        let strategy_id = seed_dca_strategy(&db, user_id, "BTC").await;
+
+        let updated = update_versioned(
+            &db,
+            rename("From tab A"),
+            strategy_id,
+            dca_strategy::Column::Id,
+            dca_strategy::Column::Version,
+            Some(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.name, "From tab A");
+        assert_eq!(updated.version, 2);
+
+        // A second tab still holding version 1 must not overwrite the first edit
+        let stale = update_versioned(
+            &db,
+            rename("From tab B"),
+            strategy_id,
+            dca_strategy::Column::Id,
+            dca_strategy::Column::Version,
+            Some(1),
+        )
+        .await;
+        assert!(matches!(stale, Err(AppError::Conflict(_))));
+
+        let stored = dca_strategy::Entity::find_by_id(strategy_id).one(&db).await.unwrap().unwrap();
+        assert_eq!(stored.name, "From tab A");
+        assert_eq!(stored.version, 2);
+    }
+}
@@ -70,7 +70,7 @@ export function SMAConfig({
       position_size_pct: initialData?.config?.position_size_pct || 10,
       enable_long: initialData?.config?.enable_long ?? true,
       enable_short: initialData?.config?.enable_short ?? false,
-      use_market_orders: initialData?.config?.use_market_orders ?? true,
+      execution_order_type: initialData?.config?.execution_order_type ?? { type: 'market' },
       risk_settings: {
         stop_loss_pct: initialData?.config?.risk_settings?.stop_loss_pct || 2.5,
         take_profit_pct: initialData?.config?.risk_settings?.take_profit_pct || 5.0,
@@ -446,17 +446,20 @@ export function SMAConfig({
                   <div className="space-y-3">
                     <div className="flex items-center space-x-3 p-3 bg-white/10 rounded-lg border border-white/20">
                       <Switch
-                        checked={formData.config.use_market_orders}
+                        checked={formData.config.execution_order_type?.type !== 'limit'}
                         onCheckedChange={(checked) => setFormData({ 
                           ...formData, 
-                          config: { ...formData.config, use_market_orders: checked }
+                          config: {
+                            ...formData.config,
+                            execution_order_type: checked ? { type: 'market' } : { type: 'limit', offset_bps: 10 }
+                          }
                         })}
                         className="data-[state=checked]:bg-blue-500 data-[state=unchecked]:bg-gray-600"
                       />
                       <div>
                         <Label className="text-white font-medium">Use Market Orders</Label>
                         <p className="text-xs text-white/60">
-                          Market orders execute immediately at current price. Limit orders may get better prices but might not fill.
+                          Market orders execute immediately at current price. Limit orders rest 0.1% away and fall back to market if unfilled after 5 minutes.
                         </p>
                       </div>
                     </div>
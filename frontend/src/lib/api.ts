@@ -111,6 +111,8 @@ export interface ExchangeConnection {
   last_sync?: string
   connection_status: 'pending' | 'connected' | 'error'
   last_error?: string
+  fee_tier: number
+  fee_token_discount: boolean
   created_at: string
   updated_at: string
 }
@@ -120,6 +122,7 @@ export interface CreateExchangeConnectionRequest {
   display_name: string
   api_key: string
   api_secret: string
+  passphrase?: string
   password: string
 }
 
@@ -127,6 +130,9 @@ export interface UpdateExchangeConnectionRequest {
   display_name?: string
   api_key?: string
   api_secret?: string
+  passphrase?: string
+  fee_tier?: number  // Exchange VIP tier, 0 is the base tier
+  fee_token_discount?: boolean  // Fees paid in the exchange token (e.g. BNB)
   password: string
 }
 
@@ -496,6 +502,11 @@ export interface GridTradingStrategiesResponse {
   active_strategies: number
 }
 
+// How a strategy's orders are sent; limit orders fall back to market once they time out
+export type ExecutionOrderType =
+  | { type: 'market' }
+  | { type: 'limit'; offset_bps: number; fallback_after_secs?: number }
+
 // SMA Crossover Strategy Types - Backend aligned
 export interface SMACrossoverConfig {
   fast_period: number
@@ -503,7 +514,7 @@ export interface SMACrossoverConfig {
   position_size_pct: number
   enable_long: boolean
   enable_short: boolean
-  use_market_orders: boolean
+  execution_order_type?: ExecutionOrderType
   risk_settings: {
     stop_loss_pct: number
     take_profit_pct: number
@@ -670,6 +681,13 @@ export interface BackendBacktestRequest {
   stop_loss_percentage?: number
   take_profit_percentage?: number
   asset_type?: 'crypto' | 'stock'  // New field for asset type
+  benchmark_symbol?: string  // Defaults to the traded symbol
+  benchmark_asset_type?: 'crypto' | 'stock'
+  dca_mode?: 'external_contribution' | 'fixed_capital'  // Defaults to external_contribution for DCA
+  fee_tier?: number  // Binance VIP tier; overrides fee_percentage
+  fee_token_discount?: boolean
+  min_data_coverage_pct?: number  // Defaults to 95
+  on_short_data?: 'warn' | 'reject'
 }
 
 // Database backtest result model (from history endpoint)
@@ -721,10 +739,21 @@ export interface BacktestEngineResult {
   trades: BacktestTrade[]
   metrics: BacktestEngineMetrics
   performance_chart: PerformancePoint[]
+  drawdown_curve?: DrawdownPoint[]
+  benchmark_curve?: { timestamp: string; portfolio_value: string }[]
+  actual_start_time?: string  // Range actually tested; config holds the requested one
+  actual_end_time?: string
+  data_coverage_pct?: string
   execution_time_ms: number
   open_positions?: OpenPosition[]
 }
 
+// Percent below the running equity peak; 0 at new highs, negative in drawdowns
+export interface DrawdownPoint {
+  timestamp: string
+  drawdown_pct: string
+}
+
 export interface BacktestEngineMetrics {
   total_return: string
   total_return_percentage: string
@@ -732,6 +761,10 @@ export interface BacktestEngineMetrics {
   sharpe_ratio?: string
   max_drawdown: string
   volatility: string
+  value_at_risk_95?: string
+  conditional_value_at_risk_95?: string
+  asset_volatility_annual_pct?: string
+  avg_atr_pct?: string
   total_trades: number
   winning_trades: number
   losing_trades: number
@@ -762,20 +795,18 @@ export interface BacktestResultDetail extends BacktestResult {
 
 export interface BacktestListResponse {
   results: BacktestResult[]
-  pagination: {
-    page: number
-    limit: number
-    total: number
-    total_pages: number
-  }
+  total_count: number
+  has_more: boolean
 }
 
 export interface BacktestListQuery {
   page?: number
   limit?: number
+  offset?: number
   strategy_name?: string
   symbol?: string
   status?: string
+  sort_by?: 'created_at' | 'total_return'
 }
 
 export interface DailyReturn {
@@ -1751,9 +1782,11 @@ class ApiClient {
     const searchParams = new URLSearchParams()
     if (query?.page) searchParams.append('page', query.page.toString())
     if (query?.limit) searchParams.append('limit', query.limit.toString())
+    if (query?.offset !== undefined) searchParams.append('offset', query.offset.toString())
     if (query?.strategy_name) searchParams.append('strategy_name', query.strategy_name)
     if (query?.symbol) searchParams.append('symbol', query.symbol)
     if (query?.status) searchParams.append('status', query.status)
+    if (query?.sort_by) searchParams.append('sort_by', query.sort_by)
 
     const url = `/backtesting/results${searchParams.toString() ? `?${searchParams.toString()}` : ''}`
     return this.request<BacktestListResponse>(url)
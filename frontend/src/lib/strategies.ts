@@ -203,7 +203,7 @@ export const DEFAULT_CONFIGS: Record<StrategyType, StrategyConfig> = {
     position_size_pct: 0, // User must configure
     enable_long: true,
     enable_short: false,
-    use_market_orders: true,
+    execution_order_type: { type: 'market' },
     risk_settings: {
       stop_loss_pct: 0,
       take_profit_pct: 0,